@@ -7,12 +7,15 @@
 //! - [`Cli`] for global flags plus the chosen subcommand.
 //! - [`Commands`] for the subcommands.
 //! - [`ValidationStrictness`] for install-time validation presets.
-//! - [`exit_code_for_clap_error`] for script-friendly clap exit codes.
+//! - [`exit_code_for_clap_error`] and [`exit_code_for_error`] for
+//!   script-friendly exit codes.
 
 use clap::error::ErrorKind;
-use clap::{Parser, Subcommand, ValueEnum, ValueHint};
+use clap::{Args, Parser, Subcommand, ValueEnum, ValueHint};
 use clap_complete::Shell;
+use fontlift_core::{ErrorCode, FontError};
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 
 /// How strictly `fontlift install` validates a font before touching the OS.
 ///
@@ -20,25 +23,180 @@ use std::path::PathBuf;
 /// `fontlift` itself. These presets trade speed for caution depending on where
 /// the font came from and how large it is.
 ///
-/// | Preset | File size cap | Parse timeout | Good for |
-/// |---|---|---|---|
-/// | `lenient` | 128 MB | 10 s | CJK superfamilies, large variable fonts |
-/// | `normal` | 64 MB | 5 s | Everyday use, the default |
-/// | `paranoid` | 32 MB | 2 s | Fonts from untrusted sources |
+/// | Preset | File size cap | Parse timeout | Table checks | Good for |
+/// |---|---|---|---|---|
+/// | `lenient` | 128 MB | 10 s | warn only | CJK superfamilies, large variable fonts |
+/// | `normal` | 64 MB | 5 s | cmap, metrics, loca | Everyday use, the default |
+/// | `paranoid` | 32 MB | 2 s | plus every outline | Fonts from untrusted sources |
 ///
 /// Use `lenient` for legitimately large CJK families or heavy variable fonts.
 /// Use `paranoid` for files you do not fully trust.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
 pub enum ValidationStrictness {
-    /// 128 MB, 10 s. Best for large CJK families and heavy variable fonts.
+    /// 128 MB, 10 s, table problems only warn. Best for large CJK families
+    /// and heavy variable fonts.
     Lenient,
-    /// 64 MB, 5 s. Default for most fonts.
+    /// 64 MB, 5 s, broken cmap/metrics/loca rejected. Default for most fonts.
     #[default]
     Normal,
-    /// 32 MB, 2 s. Best for untrusted files.
+    /// 32 MB, 2 s, every glyph outline parsed. Best for untrusted files.
     Paranoid,
 }
 
+/// Order for `fontlift list --sort-by`. Ties always fall back to family,
+/// then weight, then upright before italic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortBy {
+    /// Family, then weight, then italic, like a font menu.
+    Family,
+    /// Lightest first.
+    Weight,
+    /// Narrowest first (`OS/2.usWidthClass`).
+    Width,
+    /// Newest fontlift install first.
+    RecentlyInstalled,
+    /// Smallest file first.
+    Size,
+}
+
+/// What `fontlift install --on-conflict` does when a font clashes with an
+/// installed one by path, PostScript name, or family and style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum OnConflict {
+    /// Replace the installed font.
+    #[default]
+    Replace,
+    /// Keep the installed font and skip this one.
+    Skip,
+    /// Stop with an error.
+    Fail,
+    /// Install both; a clashing file name gets a ` (2)` suffix.
+    KeepBoth,
+}
+
+/// A cache `fontlift cleanup --cache` clears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CleanupCache {
+    /// The OS font cache (restarts the Font Cache Service on Windows).
+    System,
+    /// Adobe's `AdobeFnt*.lst` font lists and font caches.
+    Adobe,
+    /// Microsoft Office's font cache.
+    Office,
+    /// Java's font configuration cache.
+    Java,
+    /// Firefox and Chromium font caches.
+    Browser,
+}
+
+/// How `fontlift doctor --strategy` resolves an interrupted operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum DoctorStrategy {
+    /// Finish the remaining steps: copy, register, unregister, delete.
+    #[default]
+    Forward,
+    /// Undo the steps that ran: unregister, delete the copied file.
+    Back,
+}
+
+/// Font manager whose library `fontlift migrate --from` imports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MigrateFrom {
+    /// FontBase: the folder set as FontBase's fonts folder.
+    #[value(name = "fontbase")]
+    FontBase,
+    /// Suitcase Fusion: the `.fontvault` folder.
+    Suitcase,
+    /// RightFont: the `.rightfontlibrary` folder.
+    #[value(name = "rightfont")]
+    RightFont,
+}
+
+/// Format for `fontlift list --output`, for tools that already read
+/// fontconfig data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum ListOutput {
+    /// Paths and/or PostScript names, one per line.
+    #[default]
+    Text,
+    /// A `<fontconfig>` XML document with one `<pattern>` per face.
+    Fontconfig,
+    /// `fc-list` lines: `<file>: <family>:style=<style>`.
+    FcList,
+    /// Aligned columns with a header row; see `--columns`.
+    Table,
+}
+
+/// A column of `fontlift list --output table`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListColumn {
+    /// Family name.
+    Family,
+    /// Style (subfamily) name.
+    Style,
+    /// `OS/2.usWeightClass`.
+    Weight,
+    /// File extension: ttf, otf, ttc, ...
+    Format,
+    /// user or system.
+    Scope,
+    /// Font file path.
+    Path,
+}
+
+impl ListColumn {
+    /// Every column, in the default order.
+    pub const ALL: [ListColumn; 6] = [
+        ListColumn::Family,
+        ListColumn::Style,
+        ListColumn::Weight,
+        ListColumn::Format,
+        ListColumn::Scope,
+        ListColumn::Path,
+    ];
+}
+
+/// When `fontlift list` colors table output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum ColorChoice {
+    /// When stdout is a terminal and `NO_COLOR` is unset.
+    #[default]
+    Auto,
+    /// Always, even when piped.
+    Always,
+    /// Never.
+    Never,
+}
+
+/// Scope `fontlift list --scope` keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListScope {
+    /// Fonts installed for the current user.
+    User,
+    /// Fonts installed for all users.
+    System,
+}
+
+/// File format `fontlift list --format` keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListFormat {
+    /// `.ttf` files.
+    Ttf,
+    /// `.otf` files.
+    Otf,
+    /// `.ttc` and `.otc` collections.
+    Ttc,
+}
+
+/// Style `fontlift list --style` keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListStyle {
+    /// Italic and oblique faces.
+    Italic,
+    /// Faces of weight 600 or heavier.
+    Bold,
+}
+
 /// Cross-platform font installation and cleanup.
 ///
 /// `install` registers a font with the OS. `uninstall` removes the OS
@@ -55,6 +213,7 @@ pub enum ValidationStrictness {
 #[command(name = "fontlift")]
 #[command(about = "Install, uninstall, list, and remove fonts cross-platform", long_about = None)]
 #[command(version = env!("GIT_VERSION"))]
+#[command(after_help = EXIT_CODES_HELP)]
 pub struct Cli {
     /// Preview actions without changing files, registrations, or caches.
     #[arg(
@@ -88,6 +247,31 @@ pub struct Cli {
     #[arg(global = true, short = 'j', long, help = "Output results as JSON")]
     pub json: bool,
 
+    /// After installing or removing fonts, also show the applications to
+    /// restart as a desktop notification.
+    #[arg(
+        global = true,
+        long,
+        help = "Show a desktop notification listing apps to restart after font changes"
+    )]
+    pub notify: bool,
+
+    /// Read font names in this language (a BCP 47 tag such as `ja` or
+    /// `zh-Hant`) ahead of en-US and the locale. `FONTLIFT_LANG` sets it too.
+    #[arg(
+        global = true,
+        long,
+        value_name = "TAG",
+        help = "Prefer font names in this language, e.g. ja or de-DE"
+    )]
+    pub lang: Option<String>,
+
+    /// Register fonts in a file tree under DIR instead of with the OS, the
+    /// way `fontlift_core::testing::FakeFontManager` lays it out. For
+    /// end-to-end tests of the binary; hidden from `--help`.
+    #[arg(global = true, long, value_name = "DIR", hide = true)]
+    pub fake_registry: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -103,7 +287,10 @@ pub enum Commands {
     /// By default this prints one file path per line. Add `--name` to print
     /// PostScript names instead, or combine `--path --name` for
     /// `path::PostScriptName` pairs. `--sorted` produces stable, deduplicated
-    /// output for scripts and diffs.
+    /// output for scripts and diffs. `--sort-by` orders by font metadata
+    /// instead of by the printed text, so a family's styles stay together.
+    /// `--scope`, `--family`, `--format`, `--style` and `--modified-since`
+    /// narrow the listing; on Windows, files they rule out are not parsed.
     ///
     /// Examples:
     /// ```sh
@@ -111,6 +298,12 @@ pub enum Commands {
     /// fontlift list --name             # PostScript names only
     /// fontlift list --path --name      # path::name pairs
     /// fontlift list --sorted --json    # deduplicated JSON snapshot
+    /// fontlift list --name --sort-by family   # styles grouped by family
+    /// fontlift list --why Inter-Bold   # why is this font installed?
+    /// fontlift list --scope user --family 'Noto*' --style italic
+    /// fontlift list --format ttc --modified-since 2024-01-01
+    /// fontlift list --output table     # family, style, weight, ... columns
+    /// fontlift list --columns family,weight,path
     /// ```
     #[command(alias = "l")]
     List {
@@ -128,102 +321,173 @@ pub enum Commands {
         /// Sort output and remove duplicates for stable comparisons.
         #[arg(short, long, help = "Sort output and remove duplicates")]
         sorted: bool,
-    },
 
-    /// Install fonts into user or system scope.
-    ///
-    /// By default, `fontlift` copies each font into the OS font directory for
-    /// the chosen scope and then registers it. With `--inplace`, it registers
-    /// the file where it already lives. If that file later moves or disappears,
-    /// the registration goes stale.
-    ///
-    /// Directories are scanned one level deep for supported font files.
-    ///
-    /// Examples:
-    /// ```sh
-    /// fontlift install MyFont.otf
-    /// fontlift install ~/Downloads/fonts/          # install all fonts in dir
-    /// fontlift install --admin MyFont.otf          # system-wide (needs sudo)
-    /// fontlift install --inplace /opt/fonts/*.otf  # register without copying
-    /// fontlift install --validation-strictness lenient BigCJKFamily.otf
-    /// fontlift install --no-validate QuickTest.ttf # skip validation entirely
-    /// ```
-    #[command(alias = "i")]
-    Install {
-        /// One or more font files or directories to install.
+        /// Order by font metadata rather than the printed text.
         ///
-        /// Directories are scanned one level deep, not recursively.
+        /// `family` sorts by family, then weight, then upright before italic.
+        /// Other keys sort by that value first and use the family order for
+        /// ties. Fonts whose value is unknown are listed last.
         #[arg(
-            value_name = "FONT|DIR",
-            num_args = 1..,
-            value_hint = ValueHint::AnyPath,
-            help = "Font file(s) or directories to install"
+            long,
+            value_enum,
+            value_name = "KEY",
+            help = "Order by family, weight, width, recently-installed or size"
         )]
-        font_inputs: Vec<PathBuf>,
+        sort_by: Option<SortBy>,
 
-        /// Install in system scope for all users.
+        /// Print the inventory in a fontconfig format instead.
         ///
-        /// On macOS this targets `/Library/Fonts`. Without this flag, install
-        /// targets the current user only.
+        /// `fontconfig` writes a `<fontconfig>` XML document with one
+        /// `<pattern>` per face (file, index, family, style, weight, slant).
+        /// `fc-list` prints lines like `fc-list` does. `table` prints aligned
+        /// columns with a header. All three ignore `--path`, `--name` and
+        /// `--json`.
         #[arg(
-            short,
             long,
-            help = "Install system-wide for all users (requires admin privileges)"
+            value_enum,
+            value_name = "FORMAT",
+            default_value_t = ListOutput::Text,
+            help = "Output format: text, fontconfig (XML), fc-list or table"
         )]
-        admin: bool,
-
-        /// Skip the out-of-process validator before install.
-        #[arg(short = 'V', long, help = "Skip font validation before installing")]
-        no_validate: bool,
+        output: ListOutput,
 
-        /// Validation preset to use before install.
+        /// Columns for table output, in order. Implies `--output table`.
         ///
-        /// See [`ValidationStrictness`]. `lenient` suits very large fonts.
-        /// `paranoid` suits untrusted files.
+        /// Defaults to all of them: family, style, weight, format, scope,
+        /// path.
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            value_name = "COLUMNS",
+            help = "Table columns: family,style,weight,format,scope,path"
+        )]
+        columns: Vec<ListColumn>,
+
+        /// Color the table header and scope column.
         #[arg(
             long,
             value_enum,
-            default_value = "normal",
-            help = "Validation strictness: lenient | normal | paranoid"
+            value_name = "WHEN",
+            default_value_t = ColorChoice::Auto,
+            help = "Color table output: auto, always or never"
         )]
-        validation_strictness: ValidationStrictness,
+        color: ColorChoice,
+
+        /// Include each face's version, vendor ID, manufacturer, designer and
+        /// copyright in `--json` output.
+        ///
+        /// Left out by default to keep the listing small.
+        #[arg(long, help = "Add version, vendor and copyright to JSON output")]
+        details: bool,
 
-        /// Copy into the font directory before registering.
+        /// Explain why a font is installed instead of listing everything.
         ///
-        /// This is the default even when the flag is omitted. The flag mainly
-        /// exists so scripts can be explicit.
+        /// Accepts a PostScript name, full name, or file path. The answer
+        /// comes from the install database that `fontlift install` maintains.
         #[arg(
-            short = 'c',
             long,
-            help = "Copy font to the fonts directory then register (default behaviour)",
-            conflicts_with = "inplace"
+            value_name = "NAME|PATH",
+            help = "Explain why the given font is installed"
         )]
-        copy: bool,
+        why: Option<String>,
 
-        /// Register the font where it already lives, without copying it.
+        /// Ignore the metadata cache and re-read every font file.
+        #[arg(long, help = "Re-read font metadata instead of using the cache")]
+        no_cache: bool,
+
+        /// Only list fonts installed in this scope.
+        #[arg(long, value_enum, help = "Only list user or system fonts")]
+        scope: Option<ListScope>,
+
+        /// Only list families matching this glob, such as `'Noto*'`.
         ///
-        /// If the file later moves or is deleted, the registration becomes
-        /// stale. `fontlift cleanup` can prune those stale entries.
+        /// Matching ignores case; `*` matches any run of characters and `?`
+        /// one character.
         #[arg(
-            short = 'i',
             long,
-            help = "Register font at its current path without copying",
-            conflicts_with = "copy"
+            value_name = "GLOB",
+            help = "Only list families matching the glob (case-insensitive)"
         )]
-        inplace: bool,
+        family: Option<String>,
+
+        /// Only list files of this format.
+        #[arg(long, value_enum, help = "Only list ttf, otf or ttc files")]
+        format: Option<ListFormat>,
+
+        /// Only list italic faces, or faces of weight 600 and up.
+        #[arg(long, value_enum, help = "Only list italic or bold faces")]
+        style: Option<ListStyle>,
+
+        /// Only list files modified on or after this UTC date.
+        ///
+        /// Accepts `YYYY-MM-DD`, `YYYY-MM-DDTHH:MM:SS`, or an age such as
+        /// `7d` meaning that long ago.
+        #[arg(
+            long,
+            value_name = "DATE",
+            value_parser = parse_since,
+            help = "Only list files modified since DATE (YYYY-MM-DD or an age like 7d)"
+        )]
+        modified_since: Option<SystemTime>,
     },
 
+    /// Install fonts into user or system scope.
+    ///
+    /// By default, `fontlift` copies each font into the OS font directory for
+    /// the chosen scope and then registers it. With `--inplace`, it registers
+    /// the file where it already lives. If that file later moves or disappears,
+    /// the registration goes stale. With `--link`, the font directory gets a
+    /// symbolic link to the file instead of a copy.
+    ///
+    /// A symlinked input is installed as the file it points to: copies hold
+    /// its bytes, `--inplace` registers the real path and `--link` links to
+    /// it directly.
+    ///
+    /// Directories are scanned one level deep for supported font files.
+    /// `http://` and `https://` URLs are downloaded to a temp directory,
+    /// validated, and then installed like local files. `.zip`, `.tar.gz` and
+    /// `.tar` bundles are unpacked (nested folders included, `__MACOSX` noise
+    /// skipped) and every font inside is installed.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift install MyFont.otf
+    /// fontlift install ~/Downloads/fonts/          # install all fonts in dir
+    /// fontlift install --admin MyFont.otf          # system-wide (needs sudo)
+    /// fontlift install --for-user alice MyFont.otf # into alice's profile (Windows)
+    /// fontlift install --inplace /opt/fonts/*.otf  # register without copying
+    /// fontlift install --link ~/Fonts/Inter/*.ttf  # symlink into the font dir
+    /// fontlift install --validation-strictness lenient BigCJKFamily.otf
+    /// fontlift install --no-validate QuickTest.ttf # skip validation entirely
+    /// fontlift install --profile web Inter-*.ttf   # record as part of a profile
+    /// fontlift install https://example.com/MyFont.ttf --sha256 <HEX>
+    /// fontlift install FontPack.zip                # every font in the bundle
+    /// generate-font | fontlift install - --name MyFont.ttf
+    /// fontlift install --manifest fonts.json ~/Backup/Fonts
+    /// ```
+    #[command(alias = "i")]
+    Install(Box<InstallArgs>),
+
     /// Unregister a font while leaving the file on disk.
     ///
     /// Target by path, or by `--name`, which matches a PostScript name or a
     /// full name. `fontlift` tries the preferred scope first, then falls back
     /// to the other scope.
     ///
+    /// `--family` (a glob) and `--match` (a regular expression over the
+    /// PostScript, full and family names) select every matching font in the
+    /// scope, list them and ask before uninstalling them as one journaled
+    /// batch; `--yes` skips the question. A collection goes as a whole when
+    /// any of its faces matches.
+    ///
     /// Examples:
     /// ```sh
     /// fontlift uninstall ~/Library/Fonts/MyFont.otf
     /// fontlift uninstall --name HelveticaNeue-Bold
     /// fontlift uninstall --admin /Library/Fonts/MyFont.otf
+    /// fontlift uninstall --family "Test*"
+    /// fontlift uninstall --match '^Inter-.*Italic$' --yes
     /// ```
     #[command(alias = "u")]
     Uninstall {
@@ -240,26 +504,62 @@ pub enum Commands {
         )]
         font_inputs: Vec<PathBuf>,
 
+        /// Uninstall every font whose family name matches this glob.
+        #[arg(
+            long,
+            value_name = "GLOB",
+            conflicts_with_all = ["name", "font_inputs", "pattern"],
+            help = "Uninstall all fonts whose family matches this glob, e.g. \"Test*\""
+        )]
+        family: Option<String>,
+
+        /// Uninstall every font with a name matching this regular expression.
+        #[arg(
+            long = "match",
+            value_name = "REGEX",
+            conflicts_with_all = ["name", "font_inputs"],
+            help = "Uninstall all fonts whose PostScript, full or family name matches this regex"
+        )]
+        pattern: Option<String>,
+
+        /// Do not ask before uninstalling `--family`/`--match` results.
+        #[arg(short, long, help = "Uninstall pattern matches without asking")]
+        yes: bool,
+
         #[arg(
             short,
             long,
             help = "Uninstall from system scope (requires admin privileges)"
         )]
         admin: bool,
+
+        /// Act on another user's per-user fonts (Windows, elevated).
+        ///
+        /// Takes a SID or an account name such as `alice` or `CORP\alice`.
+        #[arg(
+            long,
+            value_name = "SID|USER",
+            conflicts_with = "admin",
+            help = "Uninstall from another user's fonts, given as SID or user name (Windows, requires admin privileges)"
+        )]
+        for_user: Option<String>,
     },
 
-    /// Unregister a font and delete its file.
+    /// Unregister a font and move its file into quarantine.
     ///
     /// This is the destructive counterpart to `uninstall`. If deregistration
-    /// fails, `fontlift` still tries to delete the file so the font is gone
-    /// from disk.
+    /// fails, `fontlift` still moves the file so the font is gone from the
+    /// fonts folder. `restore` brings a removed font back until it is purged,
+    /// 30 days later by default (`FONTLIFT_QUARANTINE_DAYS`); `--purge`
+    /// deletes the file at once.
     ///
-    /// Use `--dry-run` first to see exactly what will be deleted.
+    /// Use `--dry-run` first to see exactly what will be removed.
     ///
     /// Examples:
     /// ```sh
     /// fontlift remove ~/Library/Fonts/OldFont.otf
     /// fontlift remove --name OldFont-Regular
+    /// fontlift remove --purge ~/Library/Fonts/OldFont.otf
     /// fontlift --dry-run remove ~/Library/Fonts/OldFont.otf
     /// ```
     #[command(alias = "rm")]
@@ -283,6 +583,103 @@ pub enum Commands {
             help = "Remove from system scope (requires admin privileges)"
         )]
         admin: bool,
+
+        /// Act on another user's per-user fonts (Windows, elevated).
+        ///
+        /// Takes a SID or an account name such as `alice` or `CORP\alice`.
+        #[arg(
+            long,
+            value_name = "SID|USER",
+            conflicts_with = "admin",
+            help = "Remove from another user's fonts, given as SID or user name (Windows, requires admin privileges)"
+        )]
+        for_user: Option<String>,
+
+        #[arg(long, help = "Delete the files instead of moving them into quarantine")]
+        purge: bool,
+    },
+
+    /// Take a font out of service without deleting it.
+    ///
+    /// The font is unregistered and its file moved into fontlift's vault of
+    /// disabled fonts, from where `enable` puts it back. Meant for a broken
+    /// copy of a system font that makes applications crash. Faces the system
+    /// UI needs (San Francisco, Segoe UI and the like) cannot be disabled.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift disable --admin /Library/Fonts/Helvetica.ttc
+    /// fontlift disable --name Helvetica
+    /// fontlift disable --list
+    /// ```
+    Disable {
+        /// Use a PostScript name or full name instead of a file path.
+        #[arg(short, long, help = "PostScript or full name of the font to disable")]
+        name: Option<String>,
+
+        #[arg(
+            value_name = "FONT",
+            value_hint = ValueHint::FilePath,
+            conflicts_with = "name",
+            help = "Font file to disable"
+        )]
+        font: Option<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            help = "Disable a system-scope font (requires admin privileges)"
+        )]
+        admin: bool,
+
+        #[arg(
+            long,
+            conflicts_with_all = ["name", "font", "admin"],
+            help = "List disabled fonts instead"
+        )]
+        list: bool,
+    },
+
+    /// Put a disabled font back where it was and register it again.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift enable Helvetica
+    /// fontlift enable 3f2a91c0
+    /// ```
+    Enable {
+        #[arg(
+            value_name = "FONT",
+            help = "Id, PostScript name, file name or original path from `disable --list`"
+        )]
+        font: String,
+    },
+
+    /// Bring back a font `remove` moved into quarantine.
+    ///
+    /// The file goes back where it was and is registered again, with the
+    /// install record it had.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift restore OldFont-Regular
+    /// fontlift restore b81c04e2
+    /// fontlift restore --list
+    /// ```
+    Restore {
+        #[arg(
+            value_name = "FONT",
+            required_unless_present = "list",
+            help = "Id, PostScript name, file name or original path from `restore --list`"
+        )]
+        font: Option<String>,
+
+        #[arg(
+            long,
+            conflicts_with = "font",
+            help = "List removed fonts that can still be restored"
+        )]
+        list: bool,
     },
 
     /// Prune stale registrations, clear font caches, or both.
@@ -297,8 +694,37 @@ pub enum Commands {
     /// fontlift cleanup --prune-only   # remove stale registrations only
     /// fontlift cleanup --cache-only   # rebuild caches only
     /// fontlift cleanup --admin        # include system-wide cleanup
+    /// fontlift cleanup -C --cache adobe,office   # only the Adobe and Office caches
+    /// fontlift cleanup --admin --at-reboot   # delete locked caches at restart
     /// fontlift --dry-run cleanup      # preview without changing anything
+    /// fontlift --json cleanup -p     # list each pruned registration and why
+    /// fontlift cleanup -p --limit 500   # prune at most 500, continue next run
     /// ```
+    ///
+    /// Pruning works through stale registrations in chunks and reports
+    /// progress. A prune that is interrupted, or stopped by `--limit`, leaves
+    /// a checkpoint in the journal, and the next prune of that scope picks up
+    /// from it instead of rescanning every registration.
+    ///
+    /// Some cache files stay locked while sessions are running, so clearing
+    /// them in place only partly works. `--at-reboot` asks the OS to delete
+    /// them during the next restart instead (a delayed delete on Windows, a
+    /// one-shot launchd job on macOS). The next `fontlift` run after the
+    /// restart reports whether the scheduled clear completed.
+    ///
+    /// `--cache` picks which caches to clear instead of all of them, e.g. the
+    /// Adobe font lists without stopping the OS font cache service.
+    ///
+    /// Pruning also merges the numbered copies Windows makes when font file
+    /// names collide (`arial_0.ttf` next to `arial.ttf`): a copy with the
+    /// same SHA-256 as the original loses its registrations to the original
+    /// and is deleted. Copies whose contents differ are kept. `--json` lists
+    /// every merge under `duplicates`.
+    ///
+    /// On Windows the OS cache clear only deletes the current user's Font
+    /// Cache Service files, and the service is restarted even when deleting
+    /// fails; files that cannot be deleted are listed as warnings. `--deep`
+    /// deletes every user's cache files and `FNTCACHE.DAT` too.
     #[command(alias = "c")]
     Cleanup {
         /// Include system-wide registrations and caches.
@@ -326,8 +752,107 @@ pub enum Commands {
             conflicts_with = "prune_only"
         )]
         cache_only: bool,
+
+        /// Schedule the cache clear for the next restart instead of now.
+        #[arg(
+            long,
+            help = "Delete font caches at the next restart instead of now",
+            conflicts_with = "prune_only"
+        )]
+        at_reboot: bool,
+
+        /// Clear only these caches (comma-separated or repeated).
+        #[arg(
+            long,
+            value_enum,
+            value_name = "CACHE",
+            value_delimiter = ',',
+            help = "Clear only these caches: system, adobe, office, java, browser",
+            conflicts_with_all = ["prune_only", "at_reboot"]
+        )]
+        cache: Vec<CleanupCache>,
+
+        /// Clear every user's OS font cache files, not only the current
+        /// user's (Windows).
+        #[arg(
+            long,
+            help = "Delete all Font Cache Service files and FNTCACHE.DAT, not only yours (Windows)",
+            conflicts_with_all = ["prune_only", "at_reboot"]
+        )]
+        deep: bool,
+
+        /// Stop pruning after this many registrations.
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Prune at most N registrations; the next cleanup continues",
+            conflicts_with = "cache_only"
+        )]
+        limit: Option<usize>,
+    },
+
+    /// Routine upkeep in one run: prune, duplicates, quarantine, journal,
+    /// caches.
+    ///
+    /// Prunes stale registrations, reports PostScript names that more than
+    /// one installed file provides, purges expired quarantined fonts,
+    /// compacts the journal (see `doctor --compact`) and clears the font
+    /// caches. A step that fails is reported and the others still run;
+    /// `--json` prints a summary of every step.
+    ///
+    /// `--install-schedule` instead registers a launchd job (macOS) or Task
+    /// Scheduler task (Windows) running `fontlift maintain --quiet --json`
+    /// every Monday at 10:00, like `fontlift schedule cleanup`.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift maintain
+    /// fontlift --json maintain
+    /// sudo fontlift maintain --admin
+    /// fontlift maintain --install-schedule
+    /// fontlift schedule remove maintain
+    /// ```
+    Maintain {
+        /// Maintain system scope as well.
+        #[arg(
+            short,
+            long,
+            help = "Include system-wide maintenance (requires admin privileges)"
+        )]
+        admin: bool,
+
+        /// Register a weekly task running this command instead of running it.
+        #[arg(long, help = "Run maintenance every week with the OS task scheduler")]
+        install_schedule: bool,
+
+        /// Where each scheduled run's JSON summary is appended. Defaults to
+        /// `logs/maintain.log` next to the journal.
+        #[arg(
+            long,
+            value_name = "FILE",
+            value_hint = ValueHint::FilePath,
+            help = "Append each scheduled run's output to this file",
+            requires = "install_schedule"
+        )]
+        log_file: Option<PathBuf>,
     },
 
+    /// Show every place fontlift keeps, finds or caches fonts.
+    ///
+    /// Lists the user and system font folders, the fonts the OS ships,
+    /// directories from `FONTLIFT_ADDITIONAL_FONTS`, the download store, the
+    /// quarantine, the disable vaults, the metadata cache and the application
+    /// font caches `cleanup` clears. Each line shows which scope's commands
+    /// change it (`system` ones need `--admin`), how many font files and
+    /// bytes it holds, and whether this process can write there.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift sources
+    /// fontlift --json sources
+    /// ```
+    Sources,
+
     /// Print a shell completion script to stdout.
     ///
     /// Examples:
@@ -356,26 +881,1388 @@ pub enum Commands {
     /// Run with `--preview` (or `--dry-run`) first to see what recovery would
     /// do before committing to it.
     ///
+    /// By default recovery rolls forward: an install whose copy finished is
+    /// registered, a removal whose unregistration finished deletes the file.
+    /// `--strategy back` undoes the steps that ran instead, unregistering
+    /// the font and deleting the copied file.
+    ///
+    /// Steps whose file changed since the operation was recorded (a replaced
+    /// source font, a reinstalled font a removal would delete) are not
+    /// replayed: `doctor` warns and stops that operation until you confirm
+    /// with `--accept-changed`.
+    ///
     /// Examples:
     /// ```sh
-    /// fontlift doctor             # show and attempt recovery
-    /// fontlift doctor --preview   # show incomplete ops without recovering
+    /// fontlift doctor                   # show and attempt recovery
+    /// fontlift doctor --preview         # show incomplete ops without recovering
+    /// fontlift doctor --strategy back   # undo interrupted operations
+    /// fontlift doctor --accept-changed  # also replay steps on changed files
+    /// fontlift doctor --compact         # also drop old completed entries
     /// ```
     #[command(alias = "d")]
     Doctor {
         /// Show the recovery plan without changing anything.
         #[arg(short = 'P', long, help = "Show recovery plan without executing it")]
         preview: bool,
+
+        /// Replay steps even when their file changed since they were recorded.
+        #[arg(
+            long,
+            help = "Replay recovery steps on files that changed since the crash"
+        )]
+        accept_changed: bool,
+
+        /// Finish interrupted operations (`forward`) or undo them (`back`).
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = DoctorStrategy::Forward,
+            help = "Finish interrupted operations (forward) or undo them (back)"
+        )]
+        strategy: DoctorStrategy,
+
+        /// Drop completed journal entries past the retention policy now.
+        ///
+        /// Completed entries are kept for 30 days, 500 at most
+        /// (`FONTLIFT_JOURNAL_MAX_DAYS`, `FONTLIFT_JOURNAL_MAX_ENTRIES`).
+        /// Every journal write applies the policy too; this applies it
+        /// without waiting for the next operation. Interrupted operations
+        /// are never dropped.
+        #[arg(
+            long,
+            help = "Drop completed journal entries past the retention policy"
+        )]
+        compact: bool,
     },
-}
 
-/// Map clap outcomes to script-friendly exit codes.
-///
-/// `--help` and `--version` succeed with exit code 0. Other clap failures are
-/// user errors and exit 1.
-pub fn exit_code_for_clap_error(kind: ErrorKind) -> i32 {
-    match kind {
-        ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => 0,
-        _ => 1,
-    }
+    /// Show the log of completed installs, uninstalls, removals and cleanups.
+    ///
+    /// Every run that changed fonts (not `--dry-run`) is recorded with its
+    /// time, the user who ran it, the scope, the files it handled and how it
+    /// ended, so shared machines keep an audit trail. The log is
+    /// `history.jsonl` next to the journal; `FONTLIFT_HISTORY_PATH` moves it.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift history
+    /// fontlift history --since 7d
+    /// fontlift --json history --since 12h
+    /// ```
+    History {
+        #[arg(
+            long,
+            value_name = "AGE",
+            value_parser = parse_age,
+            help = "Only operations newer than AGE, e.g. 30m, 12h, 7d or 2w"
+        )]
+        since: Option<Duration>,
+    },
+
+    /// Browse installed fonts in an interactive terminal UI.
+    ///
+    /// Fonts are grouped by family; the right-hand pane shows the selected
+    /// face's metadata. Press `/` to search, `u` to uninstall, `r` to remove
+    /// (into quarantine), `d` to disable, `a` to activate a disabled font,
+    /// and `q` to quit. Every action asks for confirmation and runs through
+    /// the same code as its subcommand. Needs the `tui` feature.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift tui
+    /// ```
+    Tui,
+
+    /// Load fonts without installing them.
+    ///
+    /// Each font is registered for the fontlift process only (on Windows,
+    /// `AddFontResourceExW` with `FR_PRIVATE`): nothing is copied, nothing
+    /// is written to the registry, other apps never see it and `fontlift
+    /// list` never shows it. The faces the OS loaded are reported, then the
+    /// fonts are unloaded again.
+    ///
+    /// After `--`, runs a command with the fonts available and unloads them
+    /// when it exits. Private registrations do not reach child processes,
+    /// so for a command the fonts are registered for the login session
+    /// while it runs.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift preview Downloads/Inter.ttf
+    /// fontlift preview Specimens/ -- render-specimen --out specimen.pdf
+    /// ```
+    Preview {
+        /// Font files or directories to load.
+        #[arg(
+            value_name = "FONT|DIR",
+            required = true,
+            value_hint = ValueHint::AnyPath,
+            help = "Font file(s) or directories to load"
+        )]
+        fonts: Vec<PathBuf>,
+
+        /// Command to run while the fonts are loaded, after `--`.
+        #[arg(
+            last = true,
+            value_name = "COMMAND",
+            help = "Command to run with the fonts loaded (after --)"
+        )]
+        command: Vec<String>,
+    },
+
+    /// Write an inventory of installed fonts to a manifest.
+    ///
+    /// The manifest records each font file's path, SHA-256, size, scope,
+    /// version, names and install reason. Restore it on another machine (or
+    /// after a reinstall) with `fontlift install --manifest`. Fonts that ship
+    /// with the OS are left out.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift export fonts.json
+    /// fontlift export - | jq '.fonts | length'
+    /// ```
+    Export {
+        /// Manifest file to write, or `-` for standard output.
+        #[arg(
+            value_name = "FILE",
+            value_hint = ValueHint::FilePath,
+            help = "Manifest file to write (- for stdout)"
+        )]
+        output: PathBuf,
+    },
+
+    /// Install fonts on other machines over SSH.
+    ///
+    /// Copies the fonts (or a manifest from `fontlift export` and the fonts
+    /// it lists) into a staging directory on each host, then runs the
+    /// host's own fontlift to install them. Hosts are handled one after the
+    /// other; a host that fails does not stop the rest, and the command
+    /// fails at the end if any did. SSH runs in batch mode, so hosts need
+    /// key-based login; `~/.ssh/config` applies as usual.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift deploy --host admin@mac-01 --host admin@mac-02 Fonts/
+    /// fontlift deploy --host admin@mac-01 --manifest fonts.json ~/Backup/Fonts
+    /// fontlift --json deploy --host admin@mac-01 --sudo --admin Brand.otf
+    /// ```
+    Deploy {
+        /// Font files or directories to deploy; with `--manifest`,
+        /// directories to search for manifest fonts that moved.
+        #[arg(
+            value_name = "FONT|DIR",
+            required_unless_present = "manifest",
+            value_hint = ValueHint::AnyPath,
+            help = "Font file(s) or directories to deploy"
+        )]
+        fonts: Vec<PathBuf>,
+
+        /// SSH destination, `user@host` or a `~/.ssh/config` alias. Repeat
+        /// for several hosts.
+        #[arg(
+            long = "host",
+            value_name = "USER@HOST",
+            required = true,
+            help = "SSH destination to deploy to (repeatable)"
+        )]
+        hosts: Vec<String>,
+
+        /// Deploy the fonts listed in a manifest from `fontlift export`.
+        ///
+        /// Each host installs them with `fontlift install --manifest`, so
+        /// every font keeps the scope and install reason it was exported with.
+        #[arg(
+            long,
+            value_name = "FILE",
+            value_hint = ValueHint::FilePath,
+            help = "Deploy the fonts listed in this manifest"
+        )]
+        manifest: Option<PathBuf>,
+
+        /// Install system-wide on each host.
+        #[arg(
+            short,
+            long,
+            conflicts_with = "manifest",
+            help = "Install system-wide on each host"
+        )]
+        admin: bool,
+
+        /// Run the remote fontlift through `sudo -n`, for system-wide
+        /// installs. The remote account needs passwordless sudo for it.
+        #[arg(long, help = "Run the remote fontlift with sudo -n")]
+        sudo: bool,
+
+        /// Path of fontlift on the hosts, when it is not on their `PATH`.
+        #[arg(
+            long,
+            value_name = "PATH",
+            default_value = "fontlift",
+            help = "fontlift binary on the hosts"
+        )]
+        remote_fontlift: String,
+
+        /// Extra `ssh -o` option, e.g. `ConnectTimeout=10`. Repeatable.
+        #[arg(
+            long = "ssh-option",
+            value_name = "OPTION",
+            help = "Extra ssh -o option (repeatable)"
+        )]
+        ssh_options: Vec<String>,
+    },
+
+    /// Bring the fonts of another font manager under fontlift management.
+    ///
+    /// Reads a FontBase, Suitcase Fusion or RightFont library and installs
+    /// its fonts. Fonts the old manager had deactivated stay uninstalled when
+    /// its library records that; duplicates, Type 1 and unreadable files
+    /// are listed in the report instead of installed. Use `--dry-run` to
+    /// see the report first.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift --dry-run migrate --from fontbase --library ~/FontBase
+    /// fontlift migrate --from rightfont --library ~/Fonts.rightfontlibrary --store
+    /// fontlift --json migrate --from suitcase --library "Suitcase Fusion.fontvault"
+    /// ```
+    Migrate {
+        /// Which font manager the library belongs to.
+        #[arg(
+            long,
+            value_enum,
+            value_name = "MANAGER",
+            help = "Font manager to migrate from"
+        )]
+        from: MigrateFrom,
+
+        /// The other manager's library folder.
+        #[arg(
+            long,
+            value_name = "DIR",
+            value_hint = ValueHint::DirPath,
+            help = "Library folder of the other font manager"
+        )]
+        library: PathBuf,
+
+        /// Also keep a copy of every imported font, deactivated ones
+        /// included, in fontlift's download store, so they survive removing
+        /// the old library.
+        #[arg(long, help = "Keep a copy of each imported font in the download store")]
+        store: bool,
+
+        /// Install in system scope for all users.
+        #[arg(
+            short,
+            long,
+            help = "Install system-wide for all users (requires admin privileges)"
+        )]
+        admin: bool,
+
+        /// Register fonts inside the old library instead of copying them.
+        #[arg(
+            short = 'i',
+            long,
+            help = "Register fonts where they are without copying"
+        )]
+        inplace: bool,
+
+        /// Skip the out-of-process validator before install.
+        #[arg(short = 'V', long, help = "Skip font validation before installing")]
+        no_validate: bool,
+    },
+
+    /// Check installed font files for corruption and changes, or check
+    /// installed fonts against a foundry catalog.
+    ///
+    /// With `--all` or font paths, each file is re-read: it must still parse
+    /// as a font, and must match what was recorded about it — the SHA-256
+    /// kept in the install database or a `--manifest`, and the faces kept in
+    /// the manifest or the metadata cache. Files are
+    /// reported as verified, unrecorded (nothing to compare), modified,
+    /// corrupt, or missing.
+    ///
+    /// With `--catalog`, the catalog is a JSON file listing licensed faces
+    /// by PostScript name, optionally with the licensed version and the
+    /// SHA-256 of the shipped file. Every installed copy is checked and
+    /// reported as verified, outdated, version mismatch, tampered, or not
+    /// installed.
+    ///
+    /// Exits non-zero when anything is modified, corrupt, missing or out of
+    /// compliance, so it can gate CI or fleet audits.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift verify --all
+    /// fontlift verify --manifest fonts.json ~/Library/Fonts/Inter-*.ttf
+    /// fontlift verify --catalog acme-license.json
+    /// fontlift --json verify --catalog acme-license.json > report.json
+    /// ```
+    Verify {
+        /// Font files to check.
+        #[arg(value_name = "PATH", value_hint = ValueHint::FilePath)]
+        paths: Vec<PathBuf>,
+
+        /// Check every installed font file.
+        #[arg(
+            long,
+            help = "Check every installed font file",
+            conflicts_with = "paths"
+        )]
+        all: bool,
+
+        /// Manifest (`fontlift export`) whose recorded digests to compare
+        /// against, on top of the install database.
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Also compare against the digests in this manifest"
+        )]
+        manifest: Option<PathBuf>,
+
+        /// Catalog JSON file to compare against.
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Catalog JSON file to verify against",
+            required_unless_present_any = ["all", "paths"],
+            conflicts_with_all = ["all", "paths", "manifest"]
+        )]
+        catalog: Option<PathBuf>,
+    },
+
+    /// Run batch rules written in Rhai.
+    ///
+    /// Scripts can inspect installed fonts and font files on disk, then queue
+    /// installs and uninstalls. Nothing happens while the script runs: the
+    /// queued actions are printed as a plan and carried out afterwards, and
+    /// only if the script finished without errors. With `--dry-run` the plan
+    /// is printed and nothing is changed.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift script run weights.rhai
+    /// fontlift --dry-run script run weights.rhai   # show the plan only
+    /// ```
+    Script {
+        #[command(subcommand)]
+        command: ScriptCommand,
+    },
+
+    /// Search and install families from Google Fonts.
+    ///
+    /// Uses the Google Fonts Developer API. Set `GOOGLE_FONTS_API_KEY` (or
+    /// pass `--api-key`) to authenticate. Installs download each variant's
+    /// static TTF, validate it, and install it like a local file.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift google search mono
+    /// fontlift google install "Inter"
+    /// fontlift google install "Inter" --variant regular --variant 700
+    /// fontlift google install --admin "Noto Sans"
+    /// ```
+    Google {
+        #[command(subcommand)]
+        command: GoogleCommand,
+    },
+
+    /// Audit installed fonts against a set of rules.
+    ///
+    /// Each rule has a stable id and every finding a stable finding id, so
+    /// results can be diffed between runs. Save the current findings with
+    /// `--write-baseline` and pass `--baseline` later to report only new
+    /// ones. Exits non-zero when an error-severity finding remains.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift audit
+    /// fontlift audit --list-rules
+    /// fontlift audit --only missing-file,duplicate-postscript-name
+    /// fontlift audit --write-baseline audit-baseline.json
+    /// fontlift --json audit --baseline audit-baseline.json
+    /// ```
+    Audit {
+        /// Run only these rules (comma-separated or repeated).
+        #[arg(
+            long,
+            value_name = "RULE",
+            value_delimiter = ',',
+            help = "Run only these rule ids"
+        )]
+        only: Vec<String>,
+
+        /// Skip these rules (comma-separated or repeated).
+        #[arg(
+            long,
+            value_name = "RULE",
+            value_delimiter = ',',
+            help = "Skip these rule ids"
+        )]
+        skip: Vec<String>,
+
+        /// Suppress findings whose ids are listed in this baseline file.
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Suppress findings listed in a baseline file"
+        )]
+        baseline: Option<PathBuf>,
+
+        /// Write the current findings to this baseline file.
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Save current findings as a baseline"
+        )]
+        write_baseline: Option<PathBuf>,
+
+        /// Print the available rules and exit.
+        #[arg(long, help = "List available audit rules")]
+        list_rules: bool,
+    },
+
+    /// Suggest installed families that pair well with FAMILY.
+    ///
+    /// Candidates are ranked by classification contrast (serif with
+    /// sans-serif, a text face under a script or display face), x-height
+    /// compatibility and the range of weights and italics they offer. Each
+    /// suggestion lists the reasons behind its score.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift pair "Playfair Display"
+    /// fontlift --json pair Inter --limit 10
+    /// ```
+    Pair {
+        /// Installed family name, matched case-insensitively.
+        #[arg(value_name = "FAMILY")]
+        family: String,
+
+        /// Maximum number of suggestions.
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 5,
+            help = "Maximum number of suggestions"
+        )]
+        limit: usize,
+    },
+
+    /// Print a ready-to-paste name for an installed face and copy it to the
+    /// clipboard.
+    ///
+    /// NAME may be a PostScript name, a full name ("Atkinson Hyperlegible
+    /// Bold") or a family name, which picks the regular face. Without a
+    /// snippet flag the full name is produced. The clipboard is only used
+    /// when stdout is a terminal, so piping the output just prints it.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift copy-name "Atkinson Hyperlegible Bold" --css
+    /// fontlift copy-name Inter --postscript --no-copy
+    /// ```
+    #[command(name = "copy-name")]
+    CopyName {
+        /// Installed face, matched case-insensitively.
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// CSS `font-family` stack with weight and style.
+        #[arg(long, help = "Produce a CSS font-family declaration", conflicts_with_all = ["postscript", "family"])]
+        css: bool,
+
+        /// PostScript name, as scripts and `CTFontCreateWithName` expect.
+        #[arg(long, help = "Produce the PostScript name", conflicts_with = "family")]
+        postscript: bool,
+
+        /// Family name.
+        #[arg(long, help = "Produce the family name")]
+        family: bool,
+
+        /// Only print the snippet.
+        #[arg(long, help = "Print without copying to the clipboard")]
+        no_copy: bool,
+    },
+
+    /// Describe a font file: its names and, for variable fonts, the axes and
+    /// named instances it offers.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift info Roboto-VF.ttf
+    /// fontlift --json info Inter.ttc --face 1
+    /// ```
+    Info {
+        /// Font file to describe.
+        #[arg(value_name = "FONT", value_hint = ValueHint::FilePath)]
+        font: PathBuf,
+
+        /// Face to describe in a collection (`.ttc`/`.otc`).
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 0,
+            help = "Face index within a collection"
+        )]
+        face: u32,
+    },
+
+    /// Compare two fonts and report what changed between them.
+    ///
+    /// Compares the version, the table list with each table's length and
+    /// checksum, the name records, the variation axes and the glyph count.
+    /// Each of BEFORE and AFTER is a font file or the name of an installed
+    /// face (PostScript, full or family name), so a vendor update can be
+    /// checked against the installed copy before upgrading it.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift diff Inter-4.0.ttf Inter-4.1.ttf
+    /// fontlift diff Inter-Regular ~/Downloads/Inter-Regular.ttf
+    /// fontlift --json diff Old.ttc New.ttc --face 2
+    /// ```
+    Diff {
+        /// The older font: a file or an installed face's name.
+        #[arg(value_name = "BEFORE")]
+        before: String,
+
+        /// The newer font: a file or an installed face's name.
+        #[arg(value_name = "AFTER")]
+        after: String,
+
+        /// Face to compare in collections given as files.
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 0,
+            help = "Face index within collection files"
+        )]
+        face: u32,
+    },
+
+    /// Report which characters of TEXT a font file can display.
+    ///
+    /// Each face (every face of a collection) is checked against its `cmap`
+    /// table. Whitespace in TEXT is ignored. Exits with an error when a face
+    /// is missing characters, so scripts can test a font before using it.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift coverage Inter.ttf --text "Grüße 北京"
+    /// fontlift --json coverage Noto.ttc --text "€₹₽"
+    /// ```
+    Coverage {
+        /// Font file to check.
+        #[arg(value_name = "FONT", value_hint = ValueHint::FilePath)]
+        font: PathBuf,
+
+        /// Characters to look for.
+        #[arg(long, value_name = "TEXT", help = "Characters to look for")]
+        text: String,
+    },
+
+    /// Find installed fonts by name or by the characters they support.
+    ///
+    /// QUERY matches PostScript, full and family names, ignoring case.
+    /// `--supports` keeps the faces that can display every character of
+    /// TEXT. Matches print as `path::PostScriptName`.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift find Inter
+    /// fontlift find --supports "€"
+    /// fontlift --json find Noto --supports "北京"
+    /// ```
+    Find {
+        /// Name or family to look for.
+        #[arg(value_name = "QUERY", required_unless_present = "supports")]
+        query: Option<String>,
+
+        /// Characters every match must support.
+        #[arg(
+            long,
+            value_name = "TEXT",
+            help = "Only faces that support every character of TEXT"
+        )]
+        supports: Option<String>,
+    },
+
+    /// Report the fonts a folder of documents uses, and which are missing.
+    ///
+    /// Reads `font-family` from CSS and HTML, the font table of Word
+    /// (`.docx`) files, the families of InDesign markup (`.idml`) files and
+    /// the font names embedded in PDFs, then checks each against the
+    /// installed fonts. Useful before a cleanup, to see which fonts a
+    /// team's files still need.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift scan-usage ~/Projects/website
+    /// fontlift scan-usage "Client Jobs" --missing
+    /// fontlift --json scan-usage Brochure.idml
+    /// ```
+    #[command(name = "scan-usage")]
+    ScanUsage {
+        /// Folder to scan (recursively), or a single document.
+        #[arg(value_name = "DIR", value_hint = ValueHint::AnyPath)]
+        dir: PathBuf,
+
+        /// Only report fonts that are not installed.
+        #[arg(long, help = "Only report fonts that are not installed")]
+        missing: bool,
+    },
+
+    /// Write a type specimen PDF for a font file or an installed family.
+    ///
+    /// The sheet shows every style of the family, a sample paragraph at
+    /// several sizes in the regular face, and its character set with code
+    /// points. Fonts are embedded whole; text is not shaped, so kerning and
+    /// ligatures are not shown.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift specimen Inter --out inter.pdf
+    /// fontlift specimen ~/Downloads/Brand-Regular.otf
+    /// fontlift specimen "Source Serif 4" --sizes 10,14,28 --text "Hamburgefonstiv"
+    /// ```
+    Specimen {
+        /// Font file, installed family, or installed face name.
+        #[arg(value_name = "FONT|FAMILY")]
+        font: String,
+
+        /// Where to write the PDF.
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            default_value = "specimen.pdf",
+            value_hint = ValueHint::FilePath,
+            help = "PDF file to write"
+        )]
+        out: PathBuf,
+
+        /// Point sizes of the sample paragraph.
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_name = "PT",
+            help = "Sample paragraph sizes in points (default 9,12,18,24,36,48)"
+        )]
+        sizes: Vec<f32>,
+
+        /// Sample paragraph instead of the built-in pangrams.
+        #[arg(long, value_name = "TEXT", help = "Sample paragraph text")]
+        text: Option<String>,
+    },
+
+    /// Inspect fontlift's own state: journal, install database and caches.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift state check
+    /// fontlift state check --repair
+    /// ```
+    State {
+        #[command(subcommand)]
+        command: StateCommand,
+    },
+
+    /// Run fontlift maintenance on a schedule with the OS task scheduler.
+    ///
+    /// `schedule cleanup` installs a launchd job (macOS) or Task Scheduler
+    /// task (Windows) running `fontlift cleanup --quiet --json` at 10:00,
+    /// with each run's report appended to a log file.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift schedule cleanup --weekly
+    /// sudo fontlift schedule cleanup --daily --admin
+    /// fontlift schedule list
+    /// fontlift schedule remove cleanup
+    /// ```
+    Schedule {
+        #[command(subcommand)]
+        command: ScheduleCommand,
+    },
+
+    /// Answer install, uninstall, list and cleanup requests over a local
+    /// socket until asked to stop.
+    ///
+    /// For GUI front ends and editor plugins: one long-lived process and one
+    /// inventory snapshot instead of a new process and a full font scan per
+    /// call. Requests and responses are JSON, one object per line. Only the
+    /// user running the daemon can connect: on macOS the socket is private
+    /// to that user; on Windows (and with `--tcp`) the daemon listens on
+    /// loopback and clients authenticate with the token it writes to the
+    /// endpoint file.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift serve
+    /// fontlift serve --socket /tmp/fontlift-$USER/fontlift.sock
+    /// fontlift serve --tcp --endpoint ~/.fontlift-endpoint.json
+    /// ```
+    Serve {
+        /// Unix socket to listen on.
+        #[arg(
+            long,
+            value_name = "PATH",
+            value_hint = ValueHint::FilePath,
+            conflicts_with_all = ["tcp", "endpoint"],
+            help = "Listen on this Unix socket (default: next to the journal)"
+        )]
+        socket: Option<PathBuf>,
+
+        /// Listen on loopback TCP with token auth; always the case on Windows.
+        #[arg(long, help = "Listen on 127.0.0.1 and require the endpoint token")]
+        tcp: bool,
+
+        /// Where to write the TCP port and token.
+        #[arg(
+            long,
+            value_name = "PATH",
+            value_hint = ValueHint::FilePath,
+            help = "Write the TCP port and token here (default: next to the journal)"
+        )]
+        endpoint: Option<PathBuf>,
+    },
+
+    /// Print copy-pasteable command sequences for common workflows.
+    ///
+    /// Without TOPIC, lists the topics. Every example is checked against
+    /// this version's command line before it is printed.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift examples
+    /// fontlift examples migration
+    /// ```
+    Examples {
+        /// Workflow to show.
+        #[arg(
+            value_name = "TOPIC",
+            value_parser = clap::builder::PossibleValuesParser::new(crate::docs::topics())
+        )]
+        topic: Option<String>,
+    },
+
+    /// Print the fontlift man page, or write every page to a directory.
+    ///
+    /// Examples:
+    /// ```sh
+    /// fontlift man | man -l -
+    /// fontlift man install
+    /// fontlift man --out /usr/local/share/man/man1
+    /// ```
+    Man {
+        /// Subcommand whose page to print instead of the top-level one.
+        #[arg(value_name = "COMMAND", conflicts_with = "out")]
+        command: Option<String>,
+
+        /// Write `fontlift.1` and one page per subcommand here.
+        #[arg(
+            long,
+            value_name = "DIR",
+            value_hint = ValueHint::DirPath,
+            help = "Write all man pages into this directory"
+        )]
+        out: Option<PathBuf>,
+    },
+}
+
+/// Arguments of `fontlift install`, boxed in [`Commands::Install`] to keep
+/// the other variants small.
+#[derive(Args)]
+pub struct InstallArgs {
+    /// One or more font files, directories, or URLs to install.
+    ///
+    /// Directories are scanned one level deep unless `--recursive` is
+    /// given. `-`
+    /// reads one font from stdin, named by `--name`.
+    ///
+    /// With `--manifest`, these are directories to search for manifest
+    /// fonts that are no longer at their recorded paths.
+    /// With `--requirements`, they are directories searched for fonts
+    /// that satisfy the requirements.
+    #[arg(
+        value_name = "FONT|DIR|ARCHIVE|URL",
+        num_args = 1..,
+        required_unless_present_any = ["manifest", "requirements"],
+        value_hint = ValueHint::AnyPath,
+        help = "Font file(s), directories, archives, http(s) URLs, or - for stdin"
+    )]
+    pub font_inputs: Vec<PathBuf>,
+
+    /// File name for the font read from stdin when an input is `-`.
+    ///
+    /// The extension says what format the data is, and the installed
+    /// copy gets this name: `generate-font | fontlift install - --name
+    /// MyFont.ttf`.
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "File name for the font read from stdin (`-`), e.g. MyFont.ttf"
+    )]
+    pub name: Option<String>,
+
+    /// Scan directory inputs recursively.
+    ///
+    /// Symlinked folders are followed, but each folder is read once, so
+    /// a link back up the tree cannot loop.
+    #[arg(short = 'r', long, help = "Also install fonts from subdirectories")]
+    pub recursive: bool,
+
+    /// With `--recursive`, how many levels of subdirectories to enter.
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "recursive",
+        help = "Limit --recursive to N levels of subdirectories"
+    )]
+    pub max_depth: Option<usize>,
+
+    /// Only install files matching one of these globs.
+    ///
+    /// A glob (`*`, `?`, case-insensitive) matches a file's name or its
+    /// path below the directory given, e.g. `Sans/*.otf`. Repeatable.
+    #[arg(
+        long,
+        value_name = "GLOB",
+        help = "Only install directory files matching this glob (repeatable)"
+    )]
+    pub include: Vec<String>,
+
+    /// Leave out files and folders matching one of these globs.
+    ///
+    /// Matched like `--include`; an excluded folder is not entered.
+    /// Repeatable: `--exclude "*Trial*" --exclude "*Demo*"`.
+    #[arg(
+        long,
+        value_name = "GLOB",
+        help = "Skip directory files and folders matching this glob (repeatable)"
+    )]
+    pub exclude: Vec<String>,
+
+    /// Install in system scope for all users.
+    ///
+    /// On macOS this targets `/Library/Fonts`. Without this flag, install
+    /// targets the current user only.
+    #[arg(
+        short,
+        long,
+        help = "Install system-wide for all users (requires admin privileges)"
+    )]
+    pub admin: bool,
+
+    /// Act on another user's per-user fonts (Windows, elevated).
+    ///
+    /// Takes a SID or an account name such as `alice` or `CORP\alice`.
+    #[arg(
+        long,
+        value_name = "SID|USER",
+        conflicts_with = "admin",
+        help = "Install into another user's fonts, given as SID or user name (Windows, requires admin privileges)"
+    )]
+    pub for_user: Option<String>,
+
+    /// Skip the out-of-process validator before install.
+    #[arg(short = 'V', long, help = "Skip font validation before installing")]
+    pub no_validate: bool,
+
+    /// Validation preset to use before install.
+    ///
+    /// See [`ValidationStrictness`]. `lenient` suits very large fonts.
+    /// `paranoid` suits untrusted files.
+    #[arg(
+        long,
+        value_enum,
+        default_value = "normal",
+        help = "Validation strictness: lenient | normal | paranoid"
+    )]
+    pub validation_strictness: ValidationStrictness,
+
+    /// Validate every font again instead of trusting earlier results.
+    ///
+    /// fontlift remembers which file contents passed validation at
+    /// which strictness, and does not send them to the validator again.
+    #[arg(
+        long,
+        help = "Ignore cached validation results and validate every font again",
+        conflicts_with = "no_validate"
+    )]
+    pub revalidate: bool,
+
+    /// Name the installed copies after their fonts instead of their
+    /// original files.
+    ///
+    /// Placeholders: `{family}`, `{style}` and `{postscript}` from the
+    /// font's names, `{stem}` and `{ext}` from the original file name;
+    /// the pattern must end in `.{ext}`. Either way, characters that
+    /// break copies or registry entries (emoji, `<>:"/\|?*`, trailing
+    /// dots, device names such as `CON`) are replaced, and a name that
+    /// comes up twice in one install is numbered (`Name-2.ttf`). The
+    /// original name is kept in the install database.
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        help = "Name installed copies from the font, e.g. '{family}-{style}.{ext}'",
+        conflicts_with_all = ["inplace", "temp"]
+    )]
+    pub name_pattern: Option<String>,
+
+    /// Copy into the font directory before registering.
+    ///
+    /// This is the default even when the flag is omitted. The flag mainly
+    /// exists so scripts can be explicit.
+    #[arg(
+        short = 'c',
+        long,
+        help = "Copy font to the fonts directory then register (default behaviour)",
+        conflicts_with = "inplace"
+    )]
+    pub copy: bool,
+
+    /// Register the font where it already lives, without copying it.
+    ///
+    /// If the file later moves or is deleted, the registration becomes
+    /// stale. `fontlift cleanup` can prune those stale entries.
+    #[arg(
+        short = 'i',
+        long,
+        help = "Register font at its current path without copying",
+        conflicts_with = "copy"
+    )]
+    pub inplace: bool,
+
+    /// Put a symbolic link to the font in the font directory instead of
+    /// a copy.
+    ///
+    /// Package managers and synced folders keep one copy of each font
+    /// up to date; a link picks up their updates. Removing the font
+    /// later removes only the link. Creating links on Windows needs
+    /// Administrator rights or Developer Mode.
+    #[arg(
+        long,
+        help = "Symlink fonts into the fonts directory instead of copying",
+        conflicts_with_all = ["copy", "inplace", "temp", "no_smoothing", "rename_duplicates"]
+    )]
+    pub link: bool,
+
+    /// Register the fonts for the current login session only.
+    ///
+    /// Nothing is copied and nothing persistent is written, so the fonts
+    /// are gone after logout or restart. Handy for trying fonts before
+    /// installing them for real. The files must stay where they are.
+    /// `fontlift preview` loads fonts without showing them to other apps.
+    #[arg(
+        long,
+        help = "Install for this login session only, without copying",
+        conflicts_with_all = ["admin", "copy", "inplace", "profile", "dependency_of", "manifest"]
+    )]
+    pub temp: bool,
+
+    /// Install pixel fonts with smoothing turned off in their `gasp` table.
+    ///
+    /// fontlift warns when a font is bitmap-only or asks for unsmoothed
+    /// rendering. Windows has no per-font smoothing setting in the
+    /// registry; GDI honors the font's own `gasp` table instead, so this
+    /// installs a copy whose `gasp` table turns smoothing off at every
+    /// size. The original file is not changed. Ordinary outline fonts are
+    /// installed unchanged, and macOS ignores `gasp` entirely.
+    #[arg(
+        long,
+        help = "Turn off smoothing in installed copies of pixel fonts",
+        conflicts_with_all = ["inplace", "temp"]
+    )]
+    pub no_smoothing: bool,
+
+    /// What to do with installed fonts that clash with a new one.
+    ///
+    /// A clash is the same file path, PostScript name, or family and
+    /// style. `replace` removes the installed font first, as fontlift
+    /// always has; `skip` leaves it and moves on; `fail` stops with an
+    /// error; `keep-both` installs next to it.
+    #[arg(
+        long,
+        value_enum,
+        default_value = "replace",
+        value_name = "POLICY",
+        help = "On a clash with an installed font: replace | skip | fail | keep-both",
+        conflicts_with = "temp"
+    )]
+    pub on_conflict: OnConflict,
+
+    /// Replace a clashing installed font only with a newer version.
+    ///
+    /// Versions come from name ID 5, or `head.fontRevision` when the
+    /// version string has no number. A font that is not newer than every
+    /// font it clashes with, or whose version cannot be read, is skipped.
+    #[arg(
+        long,
+        help = "Replace clashing fonts only when the new one has a higher version",
+        conflicts_with_all = ["on_conflict", "temp"]
+    )]
+    pub upgrade_only: bool,
+
+    /// Install a renamed copy of a font whose PostScript name is taken by
+    /// a different installed font.
+    ///
+    /// Two unrelated fonts can share a PostScript name. Without this flag
+    /// fontlift refuses to replace one with the other; with it, the copy
+    /// it installs has four hex digits of its SHA-256 added to its family,
+    /// full and PostScript names (`Helvetica3FA2-Bold`), so both stay
+    /// installed side by side.
+    #[arg(
+        long,
+        help = "Install renamed copies of fonts whose PostScript name a different font uses",
+        conflicts_with_all = ["inplace", "temp"]
+    )]
+    pub rename_duplicates: bool,
+
+    /// Load every newly installed face through the platform font APIs
+    /// once the installs finish.
+    ///
+    /// Core Text and DirectWrite build their cache entries for a font the
+    /// first time it is used. Warming does that work up front, so the
+    /// first application to open a large family does not stall.
+    #[arg(
+        long,
+        help = "Pre-load the installed fonts into the platform font caches",
+        conflicts_with = "temp"
+    )]
+    pub warm_caches: bool,
+
+    /// Refuse fonts whose license bits restrict embedding.
+    ///
+    /// The OS/2 `fsType` field says whether documents may embed a font.
+    /// fontlift always warns about faces marked restricted-license or
+    /// bitmap-only; with this flag it installs none of the inputs instead.
+    #[arg(
+        long,
+        help = "Fail instead of warning when a font's fsType restricts embedding"
+    )]
+    pub strict_license: bool,
+
+    /// Convert PostScript Type 1 fonts to OpenType and install the
+    /// converted copies.
+    ///
+    /// macOS and Windows no longer load Type 1 (`.pfb`, `.pfa`). The
+    /// conversion runs FontForge, or the command line in
+    /// `FONTLIFT_TYPE1_CONVERTER` with `{input}` and `{output}` in it.
+    /// Without this flag Type 1 inputs are refused.
+    #[arg(
+        long,
+        help = "Convert Type 1 fonts (.pfb/.pfa) to OpenType before installing",
+        conflicts_with_all = ["inplace", "temp", "link"]
+    )]
+    pub convert: bool,
+
+    /// Record the install as part of a named profile instead of an
+    /// explicit request.
+    ///
+    /// Fonts installed for a profile may be removed by automated cleanup;
+    /// explicitly requested fonts never are.
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Record the fonts as installed for this profile",
+        conflicts_with = "dependency_of"
+    )]
+    pub profile: Option<String>,
+
+    /// Record the install as a dependency of a bundle or package.
+    #[arg(
+        long,
+        value_name = "BUNDLE",
+        help = "Record the fonts as a dependency of this bundle",
+        conflicts_with = "profile"
+    )]
+    pub dependency_of: Option<String>,
+
+    /// Expected SHA-256 digest (hex) of a downloaded font.
+    ///
+    /// Only valid when exactly one URL is given. The download is rejected
+    /// if the digest does not match.
+    #[arg(
+        long,
+        value_name = "HEX",
+        help = "Verify the downloaded font against this SHA-256 digest"
+    )]
+    pub sha256: Option<String>,
+
+    /// Check every font against a SHA-256 checksum manifest first.
+    ///
+    /// The manifest is `sha256sum` output (`<hex>  <path>`, paths
+    /// relative to the manifest's folder). A font that is not listed, or
+    /// whose digest differs, stops the install before anything changes.
+    /// Only files and folders on disk can be verified: extract archives and
+    /// download URLs first, then install the folder with `--verify`.
+    #[arg(
+        long,
+        value_name = "SUMS",
+        value_hint = ValueHint::FilePath,
+        help = "Refuse fonts not listed with a matching SHA-256 in this checksum file",
+        conflicts_with_all = ["manifest", "requirements"]
+    )]
+    pub verify: Option<PathBuf>,
+
+    /// Require a minisign signature of the `--verify` manifest.
+    ///
+    /// The signature is read from `<SUMS>.minisig`, as `minisign -Sm
+    /// SUMS` writes it, and must be made with this public key.
+    #[arg(
+        long,
+        value_name = "KEY",
+        value_hint = ValueHint::FilePath,
+        requires = "verify",
+        help = "Verify the checksum file's minisign signature with this public key"
+    )]
+    pub pubkey: Option<PathBuf>,
+
+    /// Reinstall every font listed in a manifest from `fontlift export`.
+    ///
+    /// Each font goes back into the scope it was exported from, with its
+    /// recorded install reason. Files are matched by SHA-256, so a font
+    /// that moved is still found under the given directories.
+    #[arg(
+        long,
+        value_name = "FILE",
+        value_hint = ValueHint::FilePath,
+        help = "Reinstall the fonts listed in this manifest",
+        conflicts_with_all = ["sha256", "profile", "dependency_of"]
+    )]
+    pub manifest: Option<PathBuf>,
+
+    /// Install the fonts a document or design file needs.
+    ///
+    /// Accepts an InDesign package or its `Document fonts` folder, a JSON
+    /// font list exported from Figma or Sketch, or a text file with one
+    /// font per line. Each requirement is met by an installed face, a
+    /// file under the given directories, or a Google Fonts download;
+    /// the command fails if any remain unmet, also under `--dry-run`.
+    #[arg(
+        long,
+        value_name = "FILE|DIR",
+        value_hint = ValueHint::AnyPath,
+        help = "Install the fonts listed in this requirements file or folder",
+        conflicts_with_all = ["sha256", "manifest", "temp"]
+    )]
+    pub requirements: Option<PathBuf>,
+}
+
+/// Subcommands of `fontlift state`.
+#[derive(Subcommand)]
+pub enum StateCommand {
+    /// Check the state stores against each other and the font files.
+    ///
+    /// Finds unreadable files, install records whose font is gone, installed
+    /// fonts without a record, stale cache and download entries, and
+    /// leftover temp files. Exits with an error while problems remain.
+    Check {
+        /// Fix what can be fixed safely: rebuild caches, drop dangling
+        /// entries and correct install records. Problems that need a person,
+        /// such as an unreadable journal, are only reported.
+        #[arg(long, help = "Repair the problems that can be fixed safely")]
+        repair: bool,
+    },
+}
+
+/// Subcommands of `fontlift schedule`.
+#[derive(Subcommand)]
+pub enum ScheduleCommand {
+    /// Run `fontlift cleanup` every day or week. Replaces an existing
+    /// cleanup task in the same scope.
+    Cleanup {
+        /// Run every day at 10:00.
+        #[arg(long, help = "Run every day", conflicts_with = "weekly")]
+        daily: bool,
+
+        /// Run every Monday at 10:00 (the default).
+        #[arg(long, help = "Run every Monday (default)")]
+        weekly: bool,
+
+        /// Clean up system scope, as root (macOS) or SYSTEM (Windows).
+        #[arg(
+            short,
+            long,
+            help = "Schedule system-wide cleanup (requires admin privileges)"
+        )]
+        admin: bool,
+
+        /// Where each run's JSON report is appended. Defaults to
+        /// `logs/cleanup.log` next to the journal.
+        #[arg(
+            long,
+            value_name = "FILE",
+            value_hint = ValueHint::FilePath,
+            help = "Append each run's output to this file"
+        )]
+        log_file: Option<PathBuf>,
+    },
+
+    /// List fontlift's scheduled tasks.
+    List,
+
+    /// Remove a scheduled task.
+    Remove {
+        /// Task name, as shown by `schedule list`.
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Remove the system-scope task.
+        #[arg(short, long, help = "Remove the system-wide task")]
+        admin: bool,
+    },
+}
+
+/// Subcommands of `fontlift google`.
+#[derive(Subcommand)]
+pub enum GoogleCommand {
+    /// List Google Fonts families whose name contains QUERY.
+    Search {
+        /// Case-insensitive part of a family name.
+        #[arg(value_name = "QUERY")]
+        query: String,
+
+        /// Developer API key. Defaults to `$GOOGLE_FONTS_API_KEY`.
+        #[arg(long, value_name = "KEY", help = "Google Fonts Developer API key")]
+        api_key: Option<String>,
+    },
+
+    /// Download and install a Google Fonts family.
+    Install {
+        /// Family name exactly as Google Fonts lists it, e.g. "Noto Sans".
+        #[arg(value_name = "FAMILY")]
+        family: String,
+
+        /// Only install these variants (`regular`, `italic`, `700`,
+        /// `700italic`, ...). Repeat the flag for several. Installs every
+        /// variant when omitted.
+        #[arg(
+            long = "variant",
+            value_name = "VARIANT",
+            help = "Variant to install (repeatable; default: all)"
+        )]
+        variants: Vec<String>,
+
+        /// Install in system scope for all users.
+        #[arg(
+            short,
+            long,
+            help = "Install system-wide for all users (requires admin privileges)"
+        )]
+        admin: bool,
+
+        /// Skip the out-of-process validator before install.
+        #[arg(short = 'V', long, help = "Skip font validation before installing")]
+        no_validate: bool,
+
+        /// Developer API key. Defaults to `$GOOGLE_FONTS_API_KEY`.
+        #[arg(long, value_name = "KEY", help = "Google Fonts Developer API key")]
+        api_key: Option<String>,
+
+        /// How many variants to download at once.
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = fontlift_core::fetch::DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+            value_parser = clap::value_parser!(usize),
+            help = "Maximum parallel downloads"
+        )]
+        max_downloads: usize,
+    },
+}
+
+/// Subcommands of `fontlift script`.
+#[derive(Subcommand)]
+pub enum ScriptCommand {
+    /// Run a script and apply the actions it queues.
+    ///
+    /// Available functions:
+    ///
+    /// - `installed()` — every installed face as a map with `path`,
+    ///   `postscript_name`, `full_name`, `family`, `style`, `weight`,
+    ///   `italic` and `scope`.
+    /// - `search(text)` — installed faces whose names contain `text`.
+    /// - `fonts_in(dir)` — the same maps for font files in `dir`.
+    /// - `install(path)` / `install(path, "system")` — queue an install.
+    /// - `uninstall(name)` — queue an uninstall by PostScript or full name.
+    /// - `is_dry_run()` — whether the plan will only be printed.
+    ///
+    /// Example (`weights.rhai`):
+    /// ```text
+    /// for font in fonts_in("/Volumes/Fonts/Inter") {
+    ///     if font.weight == 400 || font.weight == 700 {
+    ///         install(font.path);
+    ///     }
+    /// }
+    /// ```
+    Run {
+        /// Rhai script to run.
+        #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+
+        /// Refuse to apply plans with more actions than this.
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 500,
+            help = "Maximum number of actions a script may queue"
+        )]
+        max_actions: usize,
+    },
+}
+
+/// Map clap outcomes to script-friendly exit codes.
+///
+/// `--help` and `--version` succeed with exit code 0. Other clap failures are
+/// user errors and exit 1.
+pub fn exit_code_for_clap_error(kind: ErrorKind) -> i32 {
+    match kind {
+        ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => 0,
+        _ => 1,
+    }
+}
+
+/// The exit code table `fontlift --help` prints; keep it in step with
+/// [`exit_code_for_error`].
+pub const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  success
+  1  other failure, or arguments the command line cannot parse
+  2  font not found
+  3  permission denied (including protected system fonts and managed policy)
+  4  font failed validation (invalid format, legacy Type 1)
+  5  font already installed
+  6  the OS refused to register the font
+  7  installed fonts do not match the catalog or manifest
+  8  invalid argument (a bad value, or missing or conflicting options)";
+
+/// Map a failed command to the exit code scripts can branch on, by the
+/// error's [`ErrorCode`]. Codes are stable; new categories get new numbers.
+pub fn exit_code_for_error(error: &FontError) -> i32 {
+    match error.code() {
+        ErrorCode::FontNotFound => 2,
+        ErrorCode::PermissionDenied | ErrorCode::SystemFontProtection | ErrorCode::PolicyDenied => {
+            3
+        }
+        ErrorCode::InvalidFormat | ErrorCode::LegacyType1 => 4,
+        ErrorCode::AlreadyInstalled => 5,
+        ErrorCode::RegistrationFailed => 6,
+        ErrorCode::VerificationFailed => 7,
+        ErrorCode::InvalidArgument => 8,
+        ErrorCode::Io
+        | ErrorCode::UnsupportedOperation
+        | ErrorCode::ScriptError
+        | ErrorCode::StaleCursor => 1,
+    }
+}
+
+/// `--since` ages such as `7d`, parsed by [`fontlift_core::history::parse_age`].
+fn parse_age(text: &str) -> Result<Duration, String> {
+    fontlift_core::history::parse_age(text).map_err(|e| e.message())
+}
+
+fn parse_since(text: &str) -> Result<SystemTime, String> {
+    fontlift_core::listing::parse_since(text).map_err(|e| e.message())
 }
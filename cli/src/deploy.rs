@@ -0,0 +1,300 @@
+//! Pushing fonts to other machines over SSH (`fontlift deploy`).
+//!
+//! A deployment copies the font files (and, with `--manifest`, the manifest)
+//! into a fresh staging directory on each host, then has that host's own
+//! fontlift install them: `fontlift install` for plain files, `fontlift
+//! install --manifest` for a manifest, so each font keeps the scope and
+//! install reason it was exported with. The staging directory is removed
+//! afterwards, whether or not the install succeeded.
+//!
+//! Everything remote goes through a [`Transport`]. [`SshTransport`] shells
+//! out to the system `ssh`, so keys, agents, jump hosts and
+//! `~/.ssh/config` work the same as at the prompt; tests substitute a
+//! transport that records its calls.
+
+use fontlift_core::{FontError, FontResult};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Name the manifest is staged under on each host.
+const STAGED_MANIFEST: &str = "manifest.json";
+
+/// What a remote command printed, and whether it exited with status 0.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemoteOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// How a deployment reaches its hosts.
+pub trait Transport {
+    /// Copy the local file `local` to the path `remote` on `host`.
+    fn copy(&self, host: &str, local: &Path, remote: &str) -> FontResult<()>;
+
+    /// Run `command`, a POSIX shell command line, on `host`.
+    fn run(&self, host: &str, command: &str) -> FontResult<RemoteOutput>;
+}
+
+/// [`Transport`] over the system `ssh`.
+///
+/// It runs in batch mode: a host that would prompt for a password or a
+/// host key fails instead of hanging the deployment.
+#[derive(Debug, Clone, Default)]
+pub struct SshTransport {
+    /// Extra `-o` options, e.g. `ConnectTimeout=10`.
+    pub options: Vec<String>,
+}
+
+impl SshTransport {
+    fn command(&self, program: &str) -> Command {
+        let mut command = Command::new(program);
+        command.args(["-o", "BatchMode=yes"]);
+        for option in &self.options {
+            command.arg("-o").arg(option);
+        }
+        command
+    }
+
+    /// `ssh [options] -- HOST REMOTE_COMMAND`. The `--` comes before the
+    /// host so a host such as `-oProxyCommand=…` cannot pass for an option.
+    pub fn ssh_command(&self, host: &str, remote_command: &str) -> Command {
+        let mut command = self.command("ssh");
+        command.arg("--").arg(host).arg(remote_command);
+        command
+    }
+
+    fn output(program: &str, command: &mut Command) -> FontResult<Output> {
+        command
+            .output()
+            .map_err(|e| FontError::UnsupportedOperation(format!("Failed to run {program}: {e}")))
+    }
+}
+
+impl Transport for SshTransport {
+    fn copy(&self, host: &str, local: &Path, remote: &str) -> FontResult<()> {
+        // Streamed through `cat` rather than scp, whose quoting of remote
+        // paths differs between its legacy and SFTP modes.
+        let file = std::fs::File::open(local).map_err(FontError::IoError)?;
+        let output = Self::output(
+            "ssh",
+            self.ssh_command(host, &format!("cat > {}", shell_quote(remote)))
+                .stdin(file),
+        )?;
+        if output.status.success() {
+            return Ok(());
+        }
+        Err(FontError::RegistrationFailed(format!(
+            "Copying {} to {host} failed: {}",
+            local.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+
+    fn run(&self, host: &str, command: &str) -> FontResult<RemoteOutput> {
+        let output = Self::output("ssh", &mut self.ssh_command(host, command))?;
+        // 255 is ssh's own failure (unreachable host, refused key), as
+        // opposed to the remote command's exit status.
+        if output.status.code() == Some(255) {
+            return Err(FontError::RegistrationFailed(format!(
+                "Connecting to {host} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(RemoteOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+}
+
+/// What to put on every host, and how to install it there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployPlan {
+    /// Local font files to copy.
+    pub files: Vec<PathBuf>,
+    /// Local manifest to copy and install from, instead of plain files.
+    pub manifest: Option<PathBuf>,
+    /// Install plain files system-wide (`fontlift install --admin`).
+    pub admin: bool,
+    /// Run the remote fontlift through `sudo -n`.
+    pub sudo: bool,
+    /// The fontlift binary on the hosts.
+    pub remote_fontlift: String,
+}
+
+impl DeployPlan {
+    /// Check that the files can share one staging directory.
+    pub fn new(
+        files: Vec<PathBuf>,
+        manifest: Option<PathBuf>,
+        admin: bool,
+        sudo: bool,
+        remote_fontlift: String,
+    ) -> FontResult<Self> {
+        if files.is_empty() {
+            return Err(FontError::InvalidArgument(
+                "Nothing to deploy: no font files were found".to_string(),
+            ));
+        }
+        let mut names = BTreeSet::new();
+        for file in &files {
+            let name = staged_name(file)?;
+            if name == STAGED_MANIFEST || !names.insert(name.to_string()) {
+                return Err(FontError::InvalidArgument(format!(
+                    "More than one file to deploy is named '{name}'; rename one of them"
+                )));
+            }
+        }
+        Ok(Self {
+            files,
+            manifest,
+            admin,
+            sudo,
+            remote_fontlift,
+        })
+    }
+
+    /// The command line that installs the fonts staged in `staging`.
+    pub fn install_command(&self, staging: &str) -> String {
+        let mut words = Vec::new();
+        if self.sudo {
+            words.extend(["sudo".to_string(), "-n".to_string()]);
+        }
+        words.extend([shell_quote(&self.remote_fontlift), "install".to_string()]);
+        if self.manifest.is_some() {
+            words.push("--manifest".to_string());
+            words.push(shell_quote(&format!("{staging}/{STAGED_MANIFEST}")));
+            words.push(shell_quote(staging));
+        } else {
+            if self.admin {
+                words.push("--admin".to_string());
+            }
+            for file in &self.files {
+                let name = file.file_name().unwrap_or_default().to_string_lossy();
+                words.push(shell_quote(&format!("{staging}/{name}")));
+            }
+        }
+        words.join(" ")
+    }
+}
+
+/// How the deployment went on one host.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HostResult {
+    pub host: String,
+    pub success: bool,
+    /// Font files copied to the host.
+    pub copied: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// What the remote fontlift printed.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub output: String,
+}
+
+/// Deploy `plan` to `host`. Failures end up in the result, not an `Err`, so
+/// one unreachable host does not stop the rest of the fleet.
+pub fn deploy_to_host(transport: &dyn Transport, host: &str, plan: &DeployPlan) -> HostResult {
+    let mut result = HostResult {
+        host: host.to_string(),
+        success: false,
+        copied: 0,
+        error: None,
+        output: String::new(),
+    };
+
+    if host.is_empty() || host.starts_with('-') {
+        result.error = Some(format!(
+            "'{host}' is not a host name; host names cannot be empty or start with '-'"
+        ));
+        return result;
+    }
+
+    let staging = match make_staging_dir(transport, host) {
+        Ok(staging) => staging,
+        Err(e) => {
+            result.error = Some(e.message());
+            return result;
+        }
+    };
+
+    let outcome = stage_and_install(transport, host, plan, &staging, &mut result);
+    // Best effort: a leftover directory under the remote TMPDIR is harmless.
+    let _ = transport.run(host, &format!("rm -rf {}", shell_quote(&staging)));
+
+    match outcome {
+        Ok(()) => result.success = true,
+        Err(e) => result.error = Some(e.message()),
+    }
+    result
+}
+
+fn make_staging_dir(transport: &dyn Transport, host: &str) -> FontResult<String> {
+    let output = transport.run(host, "mktemp -d \"${TMPDIR:-/tmp}/fontlift-deploy.XXXXXX\"")?;
+    let staging = output.stdout.trim();
+    if !output.success || staging.is_empty() {
+        return Err(FontError::RegistrationFailed(format!(
+            "Could not create a staging directory on {host}: {}",
+            output.stderr.trim()
+        )));
+    }
+    Ok(staging.trim_end_matches('/').to_string())
+}
+
+fn stage_and_install(
+    transport: &dyn Transport,
+    host: &str,
+    plan: &DeployPlan,
+    staging: &str,
+    result: &mut HostResult,
+) -> FontResult<()> {
+    for file in &plan.files {
+        transport.copy(host, file, &format!("{staging}/{}", staged_name(file)?))?;
+        result.copied += 1;
+    }
+    if let Some(manifest) = &plan.manifest {
+        transport.copy(host, manifest, &format!("{staging}/{STAGED_MANIFEST}"))?;
+    }
+
+    let output = transport.run(host, &plan.install_command(staging))?;
+    result.output = [output.stdout.trim(), output.stderr.trim()]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !output.success {
+        return Err(FontError::RegistrationFailed(format!(
+            "fontlift install failed on {host}"
+        )));
+    }
+    Ok(())
+}
+
+fn staged_name(file: &Path) -> FontResult<&str> {
+    file.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| {
+            FontError::InvalidFormat(format!(
+                "{} has no usable file name to deploy under",
+                file.display()
+            ))
+        })
+}
+
+/// Quote `text` as a single word for a POSIX shell.
+pub fn shell_quote(text: &str) -> String {
+    let plain = !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@+,".contains(c));
+    if plain {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', r"'\''"))
+    }
+}
@@ -0,0 +1,221 @@
+//! Offline documentation: `fontlift examples` and `fontlift man`.
+//!
+//! Both are built from the `clap` definitions in `args`, so they describe the
+//! binary that prints them rather than whatever the website said last year:
+//!
+//! - Each workflow step is stored as an argument vector and parsed with
+//!   [`Cli::try_parse_from`] before it is printed. A renamed flag makes the
+//!   step fail to render (and the tests fail) instead of printing a command
+//!   that no longer works.
+//! - A step without a note of its own is captioned with its subcommand's
+//!   help text.
+//! - Man pages are rendered by `clap_mangen` from the same command tree.
+
+use crate::args::Cli;
+use clap::{Command, CommandFactory, Parser};
+use fontlift_core::{FontError, FontResult};
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+
+/// One command in a workflow.
+pub struct Step {
+    /// Caption; the subcommand's help text when `None`.
+    pub note: Option<&'static str>,
+    /// Arguments after `fontlift`.
+    pub args: &'static [&'static str],
+}
+
+/// A sequence of commands for a common task.
+pub struct Workflow {
+    pub topic: &'static str,
+    pub title: &'static str,
+    pub steps: &'static [Step],
+}
+
+const fn step(note: &'static str, args: &'static [&'static str]) -> Step {
+    Step {
+        note: Some(note),
+        args,
+    }
+}
+
+const fn plain(args: &'static [&'static str]) -> Step {
+    Step { note: None, args }
+}
+
+pub const WORKFLOWS: &[Workflow] = &[
+    Workflow {
+        topic: "bulk-install",
+        title: "Install a folder or archive of fonts",
+        steps: &[
+            step(
+                "See what would be copied and registered, without changing anything",
+                &["--dry-run", "install", "fonts/"],
+            ),
+            step(
+                "Install every font in the folder, recorded under a profile so they can be removed together",
+                &["install", "fonts/", "--profile", "design"],
+            ),
+            step(
+                "Archives and URLs work the same way",
+                &["install", "FontPack.zip"],
+            ),
+            plain(&["list", "--sorted", "--name"]),
+        ],
+    },
+    Workflow {
+        topic: "migration",
+        title: "Move your fonts to another machine",
+        steps: &[
+            step(
+                "On the old machine: write a manifest of the installed fonts",
+                &["export", "fonts.json"],
+            ),
+            step(
+                "On the new machine: reinstall everything the manifest lists",
+                &["install", "--manifest", "fonts.json"],
+            ),
+            step(
+                "Check that fontlift's records agree with what is on disk",
+                &["state", "check"],
+            ),
+        ],
+    },
+    Workflow {
+        topic: "cleanup",
+        title: "Tidy stale registrations, caches and fontlift's own state",
+        steps: &[
+            step(
+                "Preview the cleanup",
+                &["--dry-run", "cleanup"],
+            ),
+            plain(&["cleanup"]),
+            step(
+                "Show operations interrupted by a crash, and what recovery would do",
+                &["doctor", "--preview"],
+            ),
+            plain(&["state", "check", "--repair"]),
+            step(
+                "Run the cleanup every Monday from now on",
+                &["schedule", "cleanup", "--weekly"],
+            ),
+        ],
+    },
+    Workflow {
+        topic: "ci-validation",
+        title: "Validate fonts and installed state in CI",
+        steps: &[
+            step(
+                "Validate a build's fonts strictly without installing them",
+                &[
+                    "--dry-run",
+                    "install",
+                    "--validation-strictness",
+                    "paranoid",
+                    "build/fonts/",
+                ],
+            ),
+            step(
+                "Fail on audit findings that are not in the checked-in baseline",
+                &["audit", "--baseline", "audit-baseline.json"],
+            ),
+            plain(&["--json", "verify", "--catalog", "catalog.json"]),
+        ],
+    },
+];
+
+/// Topic names, for `fontlift examples` argument parsing.
+pub fn topics() -> impl Iterator<Item = &'static str> {
+    WORKFLOWS.iter().map(|workflow| workflow.topic)
+}
+
+pub fn find_workflow(topic: &str) -> Option<&'static Workflow> {
+    WORKFLOWS.iter().find(|workflow| workflow.topic == topic)
+}
+
+/// A step checked against the current command line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RenderedStep {
+    pub note: String,
+    pub command: String,
+}
+
+/// Shell-quote `arg` when it needs it.
+fn quote(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,~".contains(c))
+    {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// The help text of the (possibly nested) subcommand `args` invoke.
+fn subcommand_about(args: &[&str]) -> Option<String> {
+    let mut command = Cli::command();
+    let mut about = None;
+    for arg in args.iter().filter(|arg| !arg.starts_with('-')) {
+        let Some(sub) = command.find_subcommand(arg).cloned() else {
+            break;
+        };
+        about = sub.get_about().map(|text| text.to_string());
+        command = sub;
+    }
+    about
+}
+
+/// Parse `step` with the real CLI definition and caption it.
+pub fn render_step(step: &Step) -> FontResult<RenderedStep> {
+    let argv = std::iter::once("fontlift").chain(step.args.iter().copied());
+    let command = argv.clone().map(quote).collect::<Vec<_>>().join(" ");
+    Cli::try_parse_from(argv).map_err(|e| {
+        FontError::InvalidFormat(format!(
+            "Example `{}` no longer matches the command line: {}",
+            command,
+            e.kind()
+        ))
+    })?;
+
+    let note = step
+        .note
+        .map(str::to_string)
+        .or_else(|| subcommand_about(step.args))
+        .unwrap_or_default();
+    Ok(RenderedStep { note, command })
+}
+
+pub fn render_workflow(workflow: &Workflow) -> FontResult<Vec<RenderedStep>> {
+    workflow.steps.iter().map(render_step).collect()
+}
+
+/// The command a man page is rendered from. Building the tree gives each
+/// subcommand the display name man page file names use (`fontlift-install`).
+fn man_command(subcommand: Option<&str>) -> FontResult<Command> {
+    let mut command = Cli::command().disable_help_subcommand(true);
+    command.build();
+    let Some(name) = subcommand else {
+        return Ok(command);
+    };
+
+    command
+        .find_subcommand(name)
+        .cloned()
+        .ok_or_else(|| FontError::UnsupportedOperation(format!("No such command: {name}")))
+}
+
+/// Write the man page for `fontlift`, or for one of its subcommands.
+pub fn write_man_page<W: Write>(subcommand: Option<&str>, mut writer: W) -> FontResult<()> {
+    clap_mangen::Man::new(man_command(subcommand)?)
+        .render(&mut writer)
+        .map_err(FontError::IoError)
+}
+
+/// Write `fontlift.1` and one page per subcommand into `dir`.
+pub fn write_man_pages_to(dir: &Path) -> FontResult<()> {
+    std::fs::create_dir_all(dir).map_err(FontError::IoError)?;
+    clap_mangen::generate_to(Cli::command(), dir).map_err(FontError::IoError)
+}
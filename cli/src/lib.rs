@@ -1,11 +1,20 @@
 //! Top-level orchestrator for the `fontlift` CLI.
 //!
-//! This crate wires together two modules:
+//! This crate wires together seven modules:
 //!
 //! - **`args`** — argument definitions via `clap` derive macros. Every flag,
 //!   subcommand, and enum variant lives there.
 //! - **`ops`** — the actual command implementations: install, uninstall, list,
-//!   remove, cleanup, doctor, completions.
+//!   remove, disable, enable, cleanup, maintain, sources, doctor, history,
+//!   diff, coverage, find, scan-usage, specimen, preview, export, deploy, verify, script, google,
+//!   audit, pair, copy-name, info, state, schedule, tui, examples, man, completions.
+//! - **`docs`** — the workflow examples and man pages behind `fontlift
+//!   examples` and `fontlift man`, generated from the `args` definitions.
+//! - **`deploy`** — the SSH transport and per-host steps behind `fontlift
+//!   deploy`.
+//! - **`maintain`** — the steps of `fontlift maintain` and its summary.
+//! - **`script`** — the sandboxed Rhai engine behind `fontlift script run`.
+//! - **`tui`** — the interactive font browser behind `fontlift tui`.
 //!
 //! # Entry points
 //!
@@ -18,18 +27,50 @@
 //! full command dispatch without forking a process or catching `process::exit`.
 
 mod args;
+mod deploy;
+mod docs;
+mod maintain;
 mod ops;
+mod script;
+mod tui;
 
-pub use args::{exit_code_for_clap_error, Cli, Commands, ValidationStrictness};
+pub use args::{
+    exit_code_for_clap_error, exit_code_for_error, Cli, ColorChoice, Commands, DoctorStrategy,
+    GoogleCommand, InstallArgs, ListColumn, ListFormat, ListOutput, ListScope, ListStyle,
+    ScheduleCommand, ScriptCommand, SortBy, StateCommand, ValidationStrictness,
+};
 pub use ops::{
-    collect_font_inputs, create_font_manager, handle_cleanup_command, handle_doctor_command,
-    handle_install_command, handle_list_command, handle_remove_command, handle_uninstall_command,
-    render_list_output, write_completions, ListRender, ListRenderOptions, OperationOptions,
-    OutputOptions,
+    collect_font_inputs, create_font_manager, create_font_manager_for_user, handle_audit_command,
+    handle_cleanup_command, handle_copy_name_command, handle_coverage_command,
+    handle_deploy_command, handle_diff_command, handle_disable_command,
+    handle_disabled_list_command, handle_doctor_command, handle_enable_command,
+    handle_examples_command, handle_export_command, handle_find_command, handle_google_command,
+    handle_history_command, handle_info_command, handle_install_command, handle_list_command,
+    handle_maintain_command, handle_man_command, handle_manifest_install_command,
+    handle_migrate_command, handle_pair_command, handle_preview_command,
+    handle_quarantine_list_command, handle_remove_command, handle_requirements_install_command,
+    handle_restore_command, handle_scan_usage_command, handle_schedule_command,
+    handle_script_command, handle_serve_command, handle_sources_command, handle_specimen_command,
+    handle_state_command, handle_tui_command, handle_uninstall_command,
+    handle_uninstall_matching_command, handle_verify_command, handle_verify_integrity_command,
+    handle_why_command, render_list_output, report_restart_advisories,
+    report_scheduled_cache_clears, to_core_cache_targets, to_core_conflict_policy,
+    to_core_list_filter, to_core_migration_source, to_core_sort_key, use_color, write_completions,
+    AuditOptions, CleanupOptions, DeployOptions, InstallOptions, ListRender, ListRenderOptions,
+    OperationOptions, OutputOptions,
 };
 
 use clap::Parser;
-use fontlift_core::FontError;
+use fontlift_core::checksums::{ChecksumManifest, PublicKey};
+use fontlift_core::face_match::FaceMatcher;
+use fontlift_core::file_names::NamePattern;
+use fontlift_core::install_db::InstallReason;
+use fontlift_core::scan::ScanOptions;
+use fontlift_core::snippets::SnippetKind;
+use fontlift_core::specimen::SpecimenOptions;
+use fontlift_core::testing::FakeFontManager;
+use fontlift_core::{FontError, FontManager};
+use std::sync::Arc;
 
 /// Parse a fully constructed [`Cli`] and dispatch to the right command handler.
 ///
@@ -46,64 +87,458 @@ use fontlift_core::FontError;
 /// // run_cli(cli).await?;
 /// ```
 pub async fn run_cli(cli: Cli) -> Result<(), FontError> {
-    let manager = create_font_manager();
-    let op_opts = OperationOptions::new(cli.dry_run, cli.quiet, cli.verbose);
+    if let Some(lang) = &cli.lang {
+        fontlift_core::metadata::set_preferred_language(Some(lang));
+    }
+    let manager: Arc<dyn FontManager> = match (&cli.fake_registry, target_user(&cli.command)) {
+        (Some(root), _) => Arc::new(FakeFontManager::new(root)),
+        (None, Some(user)) => create_font_manager_for_user(user)?,
+        (None, None) => create_font_manager(),
+    };
+    let op_opts = OperationOptions::new(cli.dry_run, cli.quiet, cli.verbose).with_json(cli.json);
+    let changes_fonts = matches!(
+        cli.command,
+        Commands::Install(_)
+            | Commands::Uninstall { .. }
+            | Commands::Remove { .. }
+            | Commands::Disable { list: false, .. }
+            | Commands::Enable { .. }
+            | Commands::Restore { list: false, .. }
+            | Commands::Migrate { .. }
+            | Commands::Doctor { preview: false, .. }
+            | Commands::Maintain {
+                install_schedule: false,
+                ..
+            }
+            | Commands::Google {
+                command: GoogleCommand::Install { .. }
+            }
+    );
+
+    if !cli.dry_run {
+        report_scheduled_cache_clears(op_opts);
+    }
 
     match cli.command {
-        Commands::List { path, name, sorted } => {
-            handle_list_command(manager, path, name, sorted, cli.json).await?;
-        }
-        Commands::Install {
-            font_inputs,
-            admin,
-            no_validate,
-            validation_strictness,
-            copy: _,
-            inplace,
+        Commands::List {
+            path,
+            name,
+            sorted,
+            sort_by,
+            output,
+            columns,
+            color,
+            details,
+            why,
+            no_cache,
+            scope,
+            family,
+            format,
+            style,
+            modified_since,
         } => {
-            handle_install_command(
-                manager,
+            if no_cache {
+                fontlift_core::metadata_cache::set_enabled(false);
+            }
+            if let Some(query) = why {
+                handle_why_command(manager, query, cli.json).await?;
+            } else {
+                handle_list_command(
+                    manager,
+                    ListRenderOptions {
+                        show_path: path,
+                        show_name: name,
+                        sorted,
+                        sort_by: sort_by.map(to_core_sort_key),
+                        json: cli.json,
+                        output,
+                        columns,
+                        color: use_color(color),
+                        details,
+                    },
+                    &to_core_list_filter(scope, family, format, style, modified_since),
+                    op_opts.output,
+                )
+                .await?;
+            }
+        }
+        Commands::Install(args) => {
+            let InstallArgs {
                 font_inputs,
+                name,
+                recursive,
+                max_depth,
+                include,
+                exclude,
                 admin,
-                !no_validate,
+                for_user: _,
+                no_validate,
                 validation_strictness,
+                revalidate,
+                name_pattern,
+                copy: _,
                 inplace,
-                op_opts,
-            )
-            .await?;
+                link,
+                temp,
+                no_smoothing,
+                on_conflict,
+                upgrade_only,
+                rename_duplicates,
+                warm_caches,
+                strict_license,
+                convert,
+                profile,
+                dependency_of,
+                sha256,
+                verify,
+                pubkey,
+                manifest,
+                requirements,
+            } = *args;
+            let reason = match (profile, dependency_of, &requirements) {
+                (Some(name), _, _) => InstallReason::Profile { name },
+                (None, Some(of), _) => InstallReason::Dependency { of },
+                // Fonts pulled in for a document depend on that document.
+                (None, None, Some(path)) => InstallReason::Dependency {
+                    of: path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| path.display().to_string()),
+                },
+                (None, None, None) => InstallReason::Explicit,
+            };
+            let public_key = pubkey.as_deref().map(PublicKey::load).transpose()?;
+            let checksums = verify
+                .as_deref()
+                .map(|sums| ChecksumManifest::load(sums, public_key.as_ref()))
+                .transpose()?;
+            let install = InstallOptions::new(admin, !no_validate, validation_strictness, inplace)
+                .with_reason(reason)
+                .with_sha256(sha256)
+                .with_temporary(temp)
+                .with_link(link)
+                .with_no_smoothing(no_smoothing)
+                .with_conflict_policy(to_core_conflict_policy(on_conflict))
+                .with_upgrade_only(upgrade_only)
+                .with_rename_duplicates(rename_duplicates)
+                .with_warm_caches(warm_caches)
+                .with_strict_license(strict_license)
+                .with_convert(convert)
+                .with_revalidate(revalidate)
+                .with_stdin_name(name)
+                .with_checksums(checksums)
+                .with_scan(
+                    ScanOptions {
+                        recursive,
+                        max_depth,
+                        ..ScanOptions::default()
+                    }
+                    .with_include(include)
+                    .with_exclude(exclude),
+                )
+                .with_name_pattern(
+                    name_pattern
+                        .as_deref()
+                        .map(NamePattern::parse)
+                        .transpose()?,
+                );
+            match (manifest, requirements) {
+                (Some(manifest), _) => {
+                    handle_manifest_install_command(
+                        manager,
+                        manifest,
+                        font_inputs,
+                        install,
+                        op_opts,
+                    )
+                    .await?
+                }
+                (None, Some(requirements)) => {
+                    handle_requirements_install_command(
+                        manager,
+                        requirements,
+                        font_inputs,
+                        install,
+                        cli.json,
+                        op_opts,
+                    )
+                    .await?
+                }
+                (None, None) => {
+                    handle_install_command(manager, font_inputs, install, op_opts).await?
+                }
+            }
         }
         Commands::Uninstall {
             name,
             font_inputs,
+            family,
+            pattern,
+            yes,
             admin,
+            for_user: _,
         } => {
-            handle_uninstall_command(manager, name, font_inputs, admin, op_opts).await?;
+            let matcher = match (family, pattern) {
+                (Some(family), _) => Some(FaceMatcher::family(family)),
+                (None, Some(pattern)) => Some(FaceMatcher::regex(&pattern)?),
+                (None, None) => None,
+            };
+            match matcher {
+                Some(matcher) => {
+                    handle_uninstall_matching_command(manager, matcher, admin, yes, op_opts).await?
+                }
+                None => {
+                    handle_uninstall_command(manager, name, font_inputs, admin, op_opts).await?
+                }
+            }
         }
         Commands::Remove {
             name,
             font_inputs,
             admin,
+            for_user: _,
+            purge,
+        } => {
+            handle_remove_command(manager, name, font_inputs, admin, purge, op_opts).await?;
+        }
+        Commands::Disable {
+            name,
+            font,
+            admin,
+            list,
         } => {
-            handle_remove_command(manager, name, font_inputs, admin, op_opts).await?;
+            if list {
+                handle_disabled_list_command(cli.json)?;
+            } else {
+                handle_disable_command(manager, name, font, admin, op_opts).await?;
+            }
+        }
+        Commands::Enable { font } => {
+            handle_enable_command(manager, font, op_opts).await?;
         }
+        Commands::Restore { font, list } => match font {
+            Some(font) if !list => handle_restore_command(manager, font, op_opts).await?,
+            _ => handle_quarantine_list_command(cli.json)?,
+        },
         Commands::Cleanup {
             admin,
             prune_only,
             cache_only,
+            at_reboot,
+            limit,
+            cache,
+            deep,
+        } => {
+            let cleanup = CleanupOptions {
+                prune_only,
+                cache_only,
+                at_reboot,
+                limit,
+                caches: to_core_cache_targets(&cache),
+                deep,
+            };
+            handle_cleanup_command(manager, admin, cleanup, op_opts).await?;
+        }
+        Commands::Maintain {
+            admin,
+            install_schedule,
+            log_file,
         } => {
-            handle_cleanup_command(manager, admin, prune_only, cache_only, op_opts).await?;
+            handle_maintain_command(manager, admin, install_schedule, log_file, op_opts).await?;
+        }
+        Commands::Sources => {
+            handle_sources_command(manager, op_opts).await?;
         }
         Commands::Completions { shell } => {
             write_completions(shell, std::io::stdout())?;
         }
-        Commands::Doctor { preview } => {
-            handle_doctor_command(preview, op_opts).await?;
+        Commands::Doctor {
+            preview,
+            accept_changed,
+            strategy,
+            compact,
+        } => {
+            handle_doctor_command(manager, preview, accept_changed, strategy, compact, op_opts)
+                .await?;
+        }
+        Commands::Tui => handle_tui_command(manager).await?,
+        Commands::History { since } => {
+            handle_history_command(since, cli.json)?;
+        }
+        Commands::Preview { fonts, command } => {
+            handle_preview_command(manager, fonts, command, op_opts)?;
+        }
+        Commands::Export { output } => {
+            handle_export_command(manager, output, op_opts).await?;
         }
+        Commands::Deploy {
+            fonts,
+            hosts,
+            manifest,
+            admin,
+            sudo,
+            remote_fontlift,
+            ssh_options,
+        } => {
+            let options = DeployOptions {
+                admin,
+                sudo,
+                remote_fontlift,
+                ssh_options,
+            };
+            handle_deploy_command(hosts, fonts, manifest, options, op_opts)?;
+        }
+        Commands::Migrate {
+            from,
+            library,
+            store,
+            admin,
+            inplace,
+            no_validate,
+        } => {
+            let install =
+                InstallOptions::new(admin, !no_validate, ValidationStrictness::Normal, inplace);
+            handle_migrate_command(
+                manager,
+                to_core_migration_source(from),
+                library,
+                store,
+                install,
+                cli.json,
+                op_opts,
+            )
+            .await?;
+        }
+        Commands::Verify {
+            paths,
+            all,
+            manifest,
+            catalog,
+        } => match catalog {
+            Some(catalog) => handle_verify_command(manager, catalog, cli.json, op_opts).await?,
+            None => {
+                let targets = if all { None } else { Some(paths) };
+                handle_verify_integrity_command(manager, targets, manifest, cli.json, op_opts)
+                    .await?
+            }
+        },
+        Commands::Script { command } => {
+            handle_script_command(manager, command, op_opts).await?;
+        }
+        Commands::Google { command } => {
+            handle_google_command(manager, command, cli.json, op_opts).await?;
+        }
+        Commands::Audit {
+            only,
+            skip,
+            baseline,
+            write_baseline,
+            list_rules,
+        } => {
+            let audit = AuditOptions {
+                only,
+                skip,
+                baseline,
+                write_baseline,
+                list_rules,
+            };
+            handle_audit_command(manager, audit, cli.json, op_opts).await?;
+        }
+        Commands::Pair { family, limit } => {
+            handle_pair_command(manager, family, limit, cli.json).await?;
+        }
+        Commands::CopyName {
+            name,
+            css,
+            postscript,
+            family,
+            no_copy,
+        } => {
+            let kind = if css {
+                SnippetKind::Css
+            } else if postscript {
+                SnippetKind::PostScript
+            } else if family {
+                SnippetKind::Family
+            } else {
+                SnippetKind::FullName
+            };
+            handle_copy_name_command(manager, name, kind, !no_copy, cli.json, op_opts).await?;
+        }
+        Commands::Diff {
+            before,
+            after,
+            face,
+        } => {
+            handle_diff_command(manager, &before, &after, face, cli.json)?;
+        }
+        Commands::Coverage { font, text } => {
+            handle_coverage_command(&font, &text, cli.json)?;
+        }
+        Commands::Find { query, supports } => {
+            handle_find_command(manager, query, supports, cli.json).await?;
+        }
+        Commands::ScanUsage { dir, missing } => {
+            handle_scan_usage_command(manager, dir, missing, cli.json).await?;
+        }
+        Commands::Specimen {
+            font,
+            out,
+            sizes,
+            text,
+        } => {
+            let mut options = SpecimenOptions::default();
+            if !sizes.is_empty() {
+                options.sizes = sizes;
+            }
+            if let Some(text) = text {
+                options.sample = text;
+            }
+            handle_specimen_command(manager, font, out, options, op_opts).await?;
+        }
+        Commands::Info { font, face } => {
+            handle_info_command(&font, face, cli.json)?;
+        }
+        Commands::State { command } => {
+            handle_state_command(command, cli.json, op_opts).await?;
+        }
+        Commands::Schedule { command } => {
+            handle_schedule_command(manager, command, cli.json, op_opts).await?;
+        }
+        Commands::Serve {
+            socket,
+            tcp,
+            endpoint,
+        } => {
+            handle_serve_command(manager, socket, tcp, endpoint, op_opts).await?;
+        }
+        Commands::Examples { topic } => {
+            handle_examples_command(topic, cli.json)?;
+        }
+        Commands::Man { command, out } => {
+            handle_man_command(command, out, op_opts)?;
+        }
+    }
+
+    if changes_fonts && !cli.dry_run {
+        report_restart_advisories(cli.notify, op_opts);
     }
 
     Ok(())
 }
 
+/// The user named by `--for-user`, whose per-user fonts the command acts on
+/// instead of the caller's.
+fn target_user(command: &Commands) -> Option<&str> {
+    match command {
+        Commands::Install(args) => args.for_user.as_deref(),
+        Commands::Uninstall { for_user, .. } | Commands::Remove { for_user, .. } => {
+            for_user.as_deref()
+        }
+        _ => None,
+    }
+}
+
 /// Binary entry point: initialize logging, parse args, run, exit.
 ///
 /// `env_logger::init()` activates the `RUST_LOG` environment variable for
@@ -113,6 +548,8 @@ pub async fn run_cli(cli: Cli) -> Result<(), FontError> {
 /// Clap parse errors are handled here rather than in [`run_cli`] because they
 /// need special exit code treatment: `--help` and `--version` exit 0 (success),
 /// while genuine argument errors exit 1. See [`exit_code_for_clap_error`].
+/// Failed commands exit with their category's code from
+/// [`exit_code_for_error`] (2 not found, 3 permission denied, ...).
 pub async fn main() {
     env_logger::init();
 
@@ -125,11 +562,27 @@ pub async fn main() {
         }
     };
 
+    let json = cli.json;
     if let Err(e) = run_cli(cli).await {
-        eprintln!("❌ Error: {}", e);
-        std::process::exit(1);
+        if json {
+            eprintln!("{}", error_json(&e));
+        } else {
+            eprintln!("❌ Error: {}", e);
+        }
+        std::process::exit(exit_code_for_error(&e));
     }
 }
 
+/// How `--json` runs report a failure on stderr: the error's code, message
+/// and suggestion as separate fields.
+///
+/// ```json
+/// { "error": { "code": "font_not_found", "message": "...", "suggestion": "..." } }
+/// ```
+pub fn error_json(error: &FontError) -> String {
+    serde_json::to_string_pretty(&serde_json::json!({ "error": error }))
+        .unwrap_or_else(|_| format!("{{\"error\": {{\"code\": \"{}\"}}}}", error.code()))
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,245 @@
+//! `fontlift maintain`: the routine upkeep of a font library in one run.
+//!
+//! Each [`Step`] runs on its own; one that fails is reported and the rest
+//! still run, so a scheduled run always leaves a complete
+//! [`MaintenanceSummary`] in its log:
+//!
+//! 1. **prune** stale registrations, as `fontlift cleanup --prune-only`
+//! 2. **duplicates**: report PostScript names more than one file provides
+//!    (nothing is removed; `fontlift audit` has the details)
+//! 3. **quarantine**: delete removed fonts past their retention
+//! 4. **journal**: compact the journal to its
+//!    [`RetentionPolicy`](journal::RetentionPolicy)
+//! 5. **caches**: clear the font caches last, after everything else changed
+
+use fontlift_core::{
+    audit::{AuditEngine, AuditFinding, AuditSnapshot, DuplicatePostScriptNameRule},
+    cache::{CacheClearStrategy, CacheTargets},
+    journal, progress, quarantine, FontError, FontManager, FontScope,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::SystemTime;
+
+/// One part of a maintenance run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    Prune,
+    Duplicates,
+    Quarantine,
+    Journal,
+    Caches,
+}
+
+impl Step {
+    /// Every step, in the order a run takes them.
+    pub const ALL: [Step; 5] = [
+        Step::Prune,
+        Step::Duplicates,
+        Step::Quarantine,
+        Step::Journal,
+        Step::Caches,
+    ];
+
+    /// The step's name in the summary: `prune`, `duplicates`, ...
+    pub fn name(self) -> &'static str {
+        match self {
+            Step::Prune => "prune",
+            Step::Duplicates => "duplicates",
+            Step::Quarantine => "quarantine",
+            Step::Journal => "journal",
+            Step::Caches => "caches",
+        }
+    }
+
+    /// What the step does, for `--dry-run`.
+    pub fn description(self) -> &'static str {
+        match self {
+            Step::Prune => "prune stale registrations",
+            Step::Duplicates => "report duplicate PostScript names",
+            Step::Quarantine => "purge expired quarantined fonts",
+            Step::Journal => "drop old completed journal entries",
+            Step::Caches => "clear font caches",
+        }
+    }
+}
+
+/// How a step ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Ok,
+    /// It ran and found something worth a look.
+    Warning,
+    /// It could not run in this scope, e.g. a cache clear needing admin.
+    Skipped,
+    Failed,
+}
+
+/// The outcome of one step.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepReport {
+    pub step: Step,
+    pub status: StepStatus,
+    /// One line for people: `pruned 3 stale registration(s)`.
+    pub summary: String,
+    /// The step's own report, for machines.
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub details: Value,
+}
+
+impl StepReport {
+    fn new(step: Step, status: StepStatus, summary: impl Into<String>) -> Self {
+        Self {
+            step,
+            status,
+            summary: summary.into(),
+            details: Value::Null,
+        }
+    }
+
+    fn with_details(mut self, details: impl Serialize) -> Self {
+        self.details = serde_json::to_value(details).unwrap_or(Value::Null);
+        self
+    }
+
+    fn failed(step: Step, error: &FontError) -> Self {
+        Self::new(step, StepStatus::Failed, error.to_string())
+    }
+}
+
+/// Everything a maintenance run did; `fontlift maintain --json` prints it.
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceSummary {
+    pub scope: FontScope,
+    pub steps: Vec<StepReport>,
+}
+
+impl MaintenanceSummary {
+    pub fn failed(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|step| step.status == StepStatus::Failed)
+            .count()
+    }
+}
+
+/// Run every step for `scope`.
+pub fn run(
+    manager: &dyn FontManager,
+    scope: FontScope,
+    observer: &dyn progress::OperationObserver,
+) -> MaintenanceSummary {
+    let steps = Step::ALL
+        .into_iter()
+        .map(|step| match step {
+            Step::Prune => prune(manager, scope, observer),
+            Step::Duplicates => duplicates(manager, scope),
+            Step::Quarantine => purge_quarantine(),
+            Step::Journal => compact_journal(),
+            Step::Caches => clear_caches(manager, scope, observer),
+        })
+        .collect();
+    MaintenanceSummary { scope, steps }
+}
+
+fn prune(
+    manager: &dyn FontManager,
+    scope: FontScope,
+    observer: &dyn progress::OperationObserver,
+) -> StepReport {
+    let options = fontlift_core::prune::PruneOptions::default();
+    match progress::prune_observed(manager, scope, &options, observer) {
+        Ok(report) => StepReport::new(
+            Step::Prune,
+            StepStatus::Ok,
+            format!("pruned {} stale registration(s)", report.len()),
+        )
+        .with_details(report),
+        Err(e) => StepReport::failed(Step::Prune, &e),
+    }
+}
+
+fn duplicates(manager: &dyn FontManager, scope: FontScope) -> StepReport {
+    let fonts = match manager.list_installed_fonts() {
+        Ok(fonts) => fonts,
+        Err(e) => return StepReport::failed(Step::Duplicates, &e),
+    };
+    let fonts = fonts
+        .into_iter()
+        .filter(|font| font.source.scope.map_or(true, |s| s == scope))
+        .collect();
+    let findings: Vec<AuditFinding> = AuditEngine::empty()
+        .with_rule(DuplicatePostScriptNameRule)
+        .run(&AuditSnapshot::new(fonts))
+        .findings;
+    let status = if findings.is_empty() {
+        StepStatus::Ok
+    } else {
+        StepStatus::Warning
+    };
+    StepReport::new(
+        Step::Duplicates,
+        status,
+        format!(
+            "{} PostScript name(s) provided by several files",
+            findings.len()
+        ),
+    )
+    .with_details(findings)
+}
+
+fn purge_quarantine() -> StepReport {
+    match quarantine::purge_expired(SystemTime::now(), quarantine::retention()) {
+        Ok(purged) => {
+            let paths: Vec<_> = purged.iter().map(|font| &font.original_path).collect();
+            StepReport::new(
+                Step::Quarantine,
+                StepStatus::Ok,
+                format!("purged {} expired quarantined font(s)", purged.len()),
+            )
+            .with_details(json!({ "purged": paths }))
+        }
+        Err(e) => StepReport::failed(Step::Quarantine, &e),
+    }
+}
+
+fn compact_journal() -> StepReport {
+    let policy = journal::RetentionPolicy::from_env();
+    match journal::compact(&policy) {
+        Ok(removed) => StepReport::new(
+            Step::Journal,
+            StepStatus::Ok,
+            format!(
+                "dropped {removed} completed journal entries (keeping {} days, at most {})",
+                policy.max_age.as_secs() / 86_400,
+                policy.max_entries
+            ),
+        )
+        .with_details(json!({ "removed": removed })),
+        Err(e) => StepReport::failed(Step::Journal, &e),
+    }
+}
+
+fn clear_caches(
+    manager: &dyn FontManager,
+    scope: FontScope,
+    observer: &dyn progress::OperationObserver,
+) -> StepReport {
+    let strategy = CacheClearStrategy::for_scope(scope);
+    match progress::clear_caches_observed(manager, strategy, &CacheTargets::all(), observer) {
+        Ok(result) => StepReport::new(
+            Step::Caches,
+            StepStatus::Ok,
+            format!("cleared {} font cache entries", result.entries_cleared),
+        )
+        .with_details(json!(result)),
+        Err(FontError::PermissionDenied(message)) if scope == FontScope::User => StepReport::new(
+            Step::Caches,
+            StepStatus::Skipped,
+            format!("needs admin: {message}"),
+        ),
+        Err(e) => StepReport::failed(Step::Caches, &e),
+    }
+}
@@ -1,26 +1,91 @@
 use clap::CommandFactory;
 use clap_complete::{generate, Shell};
 use fontlift_core::{
-    journal::{self, JournalAction, RecoveryPolicy},
-    protection, validation,
-    validation_ext::{self, ValidatorConfig},
-    FontError, FontManager, FontScope, FontliftFontFaceInfo, FontliftFontSource,
+    archive::{self, ExtractedArchive},
+    audit::{AuditBaseline, AuditEngine, AuditSnapshot, Severity},
+    cache::{CacheClearStrategy, CacheTarget, CacheTargets},
+    catalog::{self, ComplianceStatus},
+    checksums::ChecksumManifest,
+    conflicts::{self, ConflictPolicy, Upgrade},
+    content_store::ContentStore,
+    dedupe, diff,
+    face_match::{self, FaceMatcher},
+    fetch::{self, FetchRequest, FetchedFont},
+    file_names::{self, NamePattern},
+    font_bytes::{self, StagedFont},
+    fontconfig, google_fonts,
+    history::{self, HistoryEntry, HistoryOperation},
+    install_db::{self, InstallReason},
+    integrity::{self, Baseline, IntegrityReport, IntegrityStatus},
+    journal::{
+        self, JournalAction, JournalEntry, RecoveryExecutor, RecoveryPolicy, ScheduledClearStatus,
+        Transaction,
+    },
+    links,
+    listing::{FormatFilter, ListFilter, StyleFilter},
+    manifest::{self, Manifest},
+    metadata,
+    metadata_cache::MetadataCache,
+    migrate::{self, MigrationReport, MigrationSource},
+    notify::{self, FontChange, NotifyConfig},
+    operations::{self, scope_order, Validation},
+    pairing, pixel_fonts,
+    policy::check_managed_scope,
+    progress::{self, ItemProgress, Operation, OperationObserver, OperationSummary, Tracker},
+    protection::{self, ProtectionPolicy},
+    prune::PruneOptions,
+    quarantine, rename,
+    requirements::{self, RequirementsReport},
+    restart_advisory,
+    scan::{self, ScanOptions},
+    schedule::{self, Frequency, ScheduledTask},
+    serve,
+    session::TemporaryFont,
+    snippets::{self, SnippetKind},
+    sorting::{self, SortKey},
+    sources::{self, SourceUsage},
+    specimen::{self, SpecimenOptions},
+    state_check::{self, Resolution, StatePaths},
+    symlinks::{self, SymlinkPolicy},
+    type1, usage, validation, validation_ext, variations, vault, FontError, FontManager, FontScope,
+    FontliftFontFaceInfo, FontliftFontSource,
 };
+use indicatif::{ProgressBar, ProgressStyle};
 use serde_json::to_string_pretty;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
 
-use crate::args::{Cli, ValidationStrictness};
+use crate::args::{
+    CleanupCache, Cli, ColorChoice, DoctorStrategy, GoogleCommand, ListColumn, ListFormat,
+    ListOutput, ListScope, ListStyle, MigrateFrom, OnConflict, ScheduleCommand, ScriptCommand,
+    SortBy, StateCommand, ValidationStrictness,
+};
+use crate::deploy;
+use crate::docs;
+use crate::maintain::{self, StepStatus};
+use crate::script::{self, ScriptAction};
+use crate::tui;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct ListRenderOptions {
     pub show_path: bool,
     pub show_name: bool,
     pub sorted: bool,
+    /// Order by font metadata instead of sorting the printed lines.
+    pub sort_by: Option<SortKey>,
     pub json: bool,
+    /// `--output`; anything but `Text` takes precedence over `json`.
+    pub output: ListOutput,
+    /// `--columns`; non-empty selects table output whatever `output` says.
+    pub columns: Vec<ListColumn>,
+    /// Color the table header and scope column.
+    pub color: bool,
+    /// `--details`: keep version, vendor and copyright in JSON output.
+    pub details: bool,
 }
 
 #[derive(Debug, PartialEq)]
@@ -33,6 +98,8 @@ pub enum ListRender {
 pub struct OutputOptions {
     pub quiet: bool,
     pub verbose: bool,
+    /// `--json`, for handlers whose signature doesn't take it directly.
+    pub json: bool,
 }
 
 impl OutputOptions {
@@ -55,27 +122,298 @@ impl OperationOptions {
     pub fn new(dry_run: bool, quiet: bool, verbose: bool) -> Self {
         Self {
             dry_run,
-            output: OutputOptions { quiet, verbose },
+            output: OutputOptions {
+                quiet,
+                verbose,
+                json: false,
+            },
+        }
+    }
+
+    pub fn with_json(mut self, json: bool) -> Self {
+        self.output.json = json;
+        self
+    }
+}
+
+/// Per-invocation settings for `fontlift install`.
+#[derive(Debug, Clone)]
+pub struct InstallOptions {
+    pub admin: bool,
+    pub validate: bool,
+    pub strictness: ValidationStrictness,
+    pub inplace: bool,
+    /// Why the fonts are being installed; stored in the install database.
+    pub reason: InstallReason,
+    /// Expected SHA-256 digest of a single URL input.
+    pub sha256: Option<String>,
+    /// Recorded as the install origin for inputs that have none of their own,
+    /// e.g. `google-fonts:Inter` for files a provider downloaded.
+    pub origin: Option<String>,
+    /// Register for the current login session only: no copy, no install
+    /// database record.
+    pub temporary: bool,
+    /// Symlink fonts into the font directory instead of copying them.
+    pub link: bool,
+    /// Install pixel fonts as copies with smoothing turned off in `gasp`.
+    pub no_smoothing: bool,
+    /// What happens to installed fonts the new ones clash with.
+    pub on_conflict: ConflictPolicy,
+    /// Replace clashing fonts only with newer versions; skip the rest.
+    pub upgrade_only: bool,
+    /// Install renamed copies of fonts that share a PostScript name with a
+    /// different installed font, instead of refusing them.
+    pub rename_duplicates: bool,
+    /// Load the installed fonts through the platform font APIs afterwards.
+    pub warm_caches: bool,
+    /// Refuse fonts whose OS/2 `fsType` restricts embedding, instead of
+    /// warning about them.
+    pub strict_license: bool,
+    /// Convert Type 1 inputs to OpenType instead of refusing them.
+    pub convert: bool,
+    /// Validate again files the validation cache has seen pass.
+    pub revalidate: bool,
+    /// Name installed copies after their fonts, e.g. `{family}-{style}.{ext}`.
+    pub name_pattern: Option<NamePattern>,
+    /// `--name`: file name for the font read from stdin (a `-` input).
+    pub stdin_name: Option<String>,
+    /// How directory inputs are walked: `-r`, `--include`, `--exclude`.
+    pub scan: ScanOptions,
+    /// `--verify`: digests every font installed must match, loaded (and
+    /// signature-checked) before the install starts.
+    pub checksums: Option<ChecksumManifest>,
+}
+
+impl InstallOptions {
+    pub fn new(
+        admin: bool,
+        validate: bool,
+        strictness: ValidationStrictness,
+        inplace: bool,
+    ) -> Self {
+        Self {
+            admin,
+            validate,
+            strictness,
+            inplace,
+            reason: InstallReason::Explicit,
+            sha256: None,
+            origin: None,
+            temporary: false,
+            link: false,
+            no_smoothing: false,
+            on_conflict: ConflictPolicy::Replace,
+            upgrade_only: false,
+            rename_duplicates: false,
+            warm_caches: false,
+            strict_license: false,
+            convert: false,
+            revalidate: false,
+            name_pattern: None,
+            stdin_name: None,
+            scan: ScanOptions::default(),
+            checksums: None,
         }
     }
+
+    pub fn with_reason(mut self, reason: InstallReason) -> Self {
+        self.reason = reason;
+        self
+    }
+
+    pub fn with_sha256(mut self, sha256: Option<String>) -> Self {
+        self.sha256 = sha256;
+        self
+    }
+
+    pub fn with_origin(mut self, origin: Option<String>) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn with_temporary(mut self, temporary: bool) -> Self {
+        self.temporary = temporary;
+        self
+    }
+
+    pub fn with_link(mut self, link: bool) -> Self {
+        self.link = link;
+        self
+    }
+
+    pub fn with_no_smoothing(mut self, no_smoothing: bool) -> Self {
+        self.no_smoothing = no_smoothing;
+        self
+    }
+
+    pub fn with_conflict_policy(mut self, on_conflict: ConflictPolicy) -> Self {
+        self.on_conflict = on_conflict;
+        self
+    }
+
+    pub fn with_upgrade_only(mut self, upgrade_only: bool) -> Self {
+        self.upgrade_only = upgrade_only;
+        self
+    }
+
+    pub fn with_rename_duplicates(mut self, rename_duplicates: bool) -> Self {
+        self.rename_duplicates = rename_duplicates;
+        self
+    }
+
+    pub fn with_warm_caches(mut self, warm_caches: bool) -> Self {
+        self.warm_caches = warm_caches;
+        self
+    }
+
+    pub fn with_strict_license(mut self, strict_license: bool) -> Self {
+        self.strict_license = strict_license;
+        self
+    }
+
+    pub fn with_convert(mut self, convert: bool) -> Self {
+        self.convert = convert;
+        self
+    }
+
+    pub fn with_revalidate(mut self, revalidate: bool) -> Self {
+        self.revalidate = revalidate;
+        self
+    }
+
+    pub fn with_name_pattern(mut self, name_pattern: Option<NamePattern>) -> Self {
+        self.name_pattern = name_pattern;
+        self
+    }
+
+    pub fn with_stdin_name(mut self, stdin_name: Option<String>) -> Self {
+        self.stdin_name = stdin_name;
+        self
+    }
+
+    pub fn with_scan(mut self, scan: ScanOptions) -> Self {
+        self.scan = scan;
+        self
+    }
+
+    pub fn with_checksums(mut self, checksums: Option<ChecksumManifest>) -> Self {
+        self.checksums = checksums;
+        self
+    }
 }
 
 pub(crate) fn log_status(opts: &OperationOptions, message: &str) {
     if opts.output.should_print() {
-        println!("{}", message);
+        above_progress_bar(|| println!("{}", message));
     }
 }
 
 pub(crate) fn log_verbose(opts: &OperationOptions, message: &str) {
     if opts.output.should_print_verbose() {
-        eprintln!("{}", message);
+        above_progress_bar(|| eprintln!("{}", message));
+    }
+}
+
+/// The progress bar on screen, if any.
+fn active_progress_bar() -> &'static Mutex<Option<ProgressBar>> {
+    static BAR: OnceLock<Mutex<Option<ProgressBar>>> = OnceLock::new();
+    BAR.get_or_init(|| Mutex::new(None))
+}
+
+/// Print without tearing the progress bar: hide it, print, redraw it.
+fn above_progress_bar(print: impl FnOnce()) {
+    let bar = active_progress_bar()
+        .lock()
+        .ok()
+        .and_then(|bar| bar.clone());
+    match bar {
+        Some(bar) => bar.suspend(print),
+        None => print(),
+    }
+}
+
+/// Shows install, uninstall and cleanup progress on stderr.
+///
+/// Draws a bar (a spinner for single steps) when stderr is a terminal and
+/// output is neither quiet nor JSON. Otherwise only long prunes report, one
+/// status line per chunk, as before.
+struct CliProgress {
+    opts: OperationOptions,
+    draw: bool,
+}
+
+impl CliProgress {
+    fn new(opts: &OperationOptions) -> Self {
+        Self {
+            opts: *opts,
+            draw: opts.output.should_print()
+                && !opts.output.json
+                && !opts.dry_run
+                && std::io::stderr().is_terminal(),
+        }
     }
 }
 
-fn scope_order(preferred: FontScope) -> [FontScope; 2] {
-    match preferred {
-        FontScope::User => [FontScope::User, FontScope::System],
-        FontScope::System => [FontScope::System, FontScope::User],
+impl OperationObserver for CliProgress {
+    fn on_start(&self, operation: Operation, total: usize) {
+        if !self.draw || total == 0 {
+            return;
+        }
+        let bar = if total == 1 {
+            let spinner = ProgressBar::new_spinner();
+            spinner.enable_steady_tick(Duration::from_millis(100));
+            spinner
+        } else {
+            let bar = ProgressBar::new(total as u64);
+            if let Ok(style) =
+                ProgressStyle::with_template("{msg:30!} [{bar:30}] {pos}/{len} ({eta})")
+            {
+                bar.set_style(style.progress_chars("=> "));
+            }
+            bar
+        };
+        bar.set_message(operation.verb());
+        if let Ok(mut slot) = active_progress_bar().lock() {
+            *slot = Some(bar);
+        }
+    }
+
+    fn on_item(&self, item: &ItemProgress<'_>) {
+        if !self.draw {
+            if item.operation == Operation::Prune && item.total > PruneOptions::default().chunk_size
+            {
+                log_status(
+                    &self.opts,
+                    &format!("Pruning stale registrations: {}/{}", item.done, item.total),
+                );
+            }
+            return;
+        }
+        let Some(bar) = active_progress_bar()
+            .lock()
+            .ok()
+            .and_then(|bar| bar.clone())
+        else {
+            return;
+        };
+        bar.set_position(item.done as u64);
+        if let Some(name) = item.path.and_then(Path::file_name) {
+            bar.set_message(format!(
+                "{} {}",
+                item.operation.verb(),
+                name.to_string_lossy()
+            ));
+        }
+    }
+
+    fn on_done(&self, _summary: &OperationSummary) {
+        let bar = active_progress_bar()
+            .lock()
+            .ok()
+            .and_then(|mut slot| slot.take());
+        if let Some(bar) = bar {
+            bar.finish_and_clear();
+        }
     }
 }
 
@@ -87,43 +425,76 @@ fn describe_scope_chain(preferred: FontScope) -> String {
         .join(" then ")
 }
 
-fn uninstall_across_scopes(
-    manager: &Arc<dyn FontManager>,
+/// Apply the site [`ProtectionPolicy`] before unregistering or deleting `path`.
+///
+/// `postscript_names` are the faces being touched; for path targets they are
+/// read from the file.
+fn check_protection_policy(
+    policy: &ProtectionPolicy,
     path: &Path,
-    preferred_scope: FontScope,
-) -> Result<FontScope, FontError> {
-    let mut last_error: Option<FontError> = None;
-
-    for scope in scope_order(preferred_scope) {
-        let source = FontliftFontSource::new(path.to_path_buf()).with_scope(Some(scope));
-        match manager.uninstall_font(&source) {
-            Ok(()) => return Ok(scope),
-            Err(err) => last_error = Some(err),
-        }
-    }
-
-    if let Some(err) = last_error {
-        Err(err)
-    } else {
-        Err(FontError::RegistrationFailed(format!(
-            "Failed to uninstall font {} in any scope",
-            path.display()
-        )))
+    postscript_names: &[&str],
+    opts: &OperationOptions,
+) -> Result<(), FontError> {
+    if let Some(warning) = operations::check_protection(policy, path, postscript_names)? {
+        log_status(opts, &format!("⚠️  Protection policy: {}", warning));
     }
+    Ok(())
 }
 
 pub fn render_list_output(
     mut fonts: Vec<FontliftFontFaceInfo>,
-    opts: ListRenderOptions,
+    mut opts: ListRenderOptions,
 ) -> Result<ListRender, FontError> {
-    // JSON and explicitly sorted output should dedupe the underlying font records first
-    let must_dedupe_fonts = opts.sorted || opts.json;
+    if !opts.columns.is_empty() {
+        opts.output = ListOutput::Table;
+    }
+
+    // JSON, fontconfig and explicitly sorted output should dedupe the underlying font records first
+    let must_dedupe_fonts = opts.sorted || opts.json || opts.output != ListOutput::Text;
 
     if must_dedupe_fonts {
         fonts = protection::dedupe_fonts(fonts);
     }
 
+    if let Some(key) = opts.sort_by {
+        sorting::sort_faces(&mut fonts, key);
+    }
+
+    match opts.output {
+        ListOutput::Text => {}
+        ListOutput::Fontconfig => {
+            if opts.sort_by.is_none() {
+                fonts.sort_by(sorting::compare_faces);
+            }
+            let xml = fontconfig::to_fontconfig_xml(&fonts);
+            return Ok(ListRender::Lines(xml.lines().map(str::to_string).collect()));
+        }
+        ListOutput::FcList => {
+            let mut lines: Vec<String> = fonts.iter().map(fontconfig::fc_list_line).collect();
+            if opts.sort_by.is_none() {
+                lines.sort();
+            }
+            return Ok(ListRender::Lines(lines));
+        }
+        ListOutput::Table => {
+            if opts.sort_by.is_none() {
+                fonts.sort_by(sorting::compare_faces);
+            }
+            let columns = if opts.columns.is_empty() {
+                &ListColumn::ALL[..]
+            } else {
+                &opts.columns[..]
+            };
+            return Ok(ListRender::Lines(render_table(&fonts, columns, opts.color)));
+        }
+    }
+
     if opts.json {
+        if !opts.details {
+            for font in &mut fonts {
+                font.details = None;
+            }
+        }
         let json = to_string_pretty(&fonts).map_err(|e| {
             FontError::InvalidFormat(format!("Failed to serialize font list to JSON: {}", e))
         })?;
@@ -149,19 +520,132 @@ pub fn render_list_output(
         }
     }
 
-    // Always present the list in deterministic order; dedupe path-only output by default
-    lines.sort();
-
-    if (opts.show_path && !opts.show_name) || opts.sorted {
-        lines.dedup();
+    let dedupe = (opts.show_path && !opts.show_name) || opts.sorted;
+    if opts.sort_by.is_some() {
+        // Keep the metadata order; a collection's faces share one path, so
+        // drop repeats wherever they are rather than only adjacent ones.
+        if dedupe {
+            let mut seen = HashSet::new();
+            lines.retain(|line| seen.insert(line.clone()));
+        }
+    } else {
+        // Always present the list in deterministic order; dedupe path-only output by default
+        lines.sort();
+        if dedupe {
+            lines.dedup();
+        }
     }
 
     Ok(ListRender::Lines(lines))
 }
 
-pub fn collect_font_inputs(inputs: &[PathBuf]) -> Result<Vec<PathBuf>, FontError> {
+/// `--color` resolved against the terminal and `NO_COLOR`.
+pub fn use_color(choice: ColorChoice) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+        }
+    }
+}
+
+fn table_cell(font: &FontliftFontFaceInfo, column: ListColumn) -> String {
+    match column {
+        ListColumn::Family => font.family_name.clone(),
+        ListColumn::Style => font.style.clone(),
+        ListColumn::Weight => font
+            .weight
+            .map_or_else(|| "-".to_string(), |weight| weight.to_string()),
+        ListColumn::Format => font
+            .source
+            .path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase)
+            .or_else(|| font.source.format.clone())
+            .unwrap_or_else(|| "-".to_string()),
+        ListColumn::Scope => match font.source.scope {
+            Some(FontScope::User) => "user".to_string(),
+            Some(FontScope::System) => "system".to_string(),
+            None => "-".to_string(),
+        },
+        ListColumn::Path => font.source.path.display().to_string(),
+    }
+}
+
+/// Header plus one row per face, padded to the widest cell of each column.
+/// Weights are right-aligned; the last column is not padded. Colors are
+/// applied after padding so they do not disturb alignment.
+fn render_table(
+    fonts: &[FontliftFontFaceInfo],
+    columns: &[ListColumn],
+    color: bool,
+) -> Vec<String> {
+    const BOLD: &str = "\x1b[1m";
+    const GREEN: &str = "\x1b[32m";
+    const YELLOW: &str = "\x1b[33m";
+    const RESET: &str = "\x1b[0m";
+
+    let header: Vec<String> = columns
+        .iter()
+        .map(|column| format!("{column:?}").to_uppercase())
+        .collect();
+    let rows: Vec<Vec<String>> = fonts
+        .iter()
+        .map(|font| columns.iter().map(|&c| table_cell(font, c)).collect())
+        .collect();
+    let widths: Vec<usize> = (0..columns.len())
+        .map(|i| {
+            std::iter::once(&header)
+                .chain(&rows)
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let render = |row: &[String], is_header: bool| -> String {
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                let last = i + 1 == row.len();
+                let padded = if columns[i] == ListColumn::Weight && !is_header {
+                    format!("{cell:>width$}", width = widths[i])
+                } else if last {
+                    cell.clone()
+                } else {
+                    format!("{cell:<width$}", width = widths[i])
+                };
+                let paint = match (is_header, columns[i], cell.as_str()) {
+                    _ if !color => None,
+                    (true, _, _) => Some(BOLD),
+                    (false, ListColumn::Scope, "user") => Some(GREEN),
+                    (false, ListColumn::Scope, "system") => Some(YELLOW),
+                    _ => None,
+                };
+                match paint {
+                    Some(code) => format!("{code}{padded}{RESET}"),
+                    None => padded,
+                }
+            })
+            .collect();
+        cells.join("  ")
+    };
+
+    std::iter::once(render(&header, true))
+        .chain(rows.iter().map(|row| render(row, false)))
+        .collect()
+}
+
+/// The font files named by `inputs`, with directories walked as `scan` says.
+pub fn collect_font_inputs(
+    inputs: &[PathBuf],
+    scan: &ScanOptions,
+) -> Result<Vec<PathBuf>, FontError> {
     if inputs.is_empty() {
-        return Err(FontError::InvalidFormat(
+        return Err(FontError::InvalidArgument(
             "At least one font path or directory is required".to_string(),
         ));
     }
@@ -170,16 +654,12 @@ pub fn collect_font_inputs(inputs: &[PathBuf]) -> Result<Vec<PathBuf>, FontError
 
     for input in inputs {
         if input.is_dir() {
-            for entry in fs::read_dir(input).map_err(FontError::IoError)? {
-                let entry = entry.map_err(FontError::IoError)?;
-                let path = entry.path();
-                if path.is_file() && validation::is_valid_font_extension(&path) {
-                    found.insert(path);
-                }
-            }
+            found.extend(scan::font_files_in(input, scan)?);
         } else if input.is_file() {
             if validation::is_valid_font_extension(input) {
                 found.insert(input.clone());
+            } else if type1::detect(input).is_some() {
+                return Err(FontError::LegacyType1(input.clone()));
             } else {
                 return Err(FontError::InvalidFormat(format!(
                     "Invalid font extension: {}",
@@ -213,7 +693,25 @@ pub fn create_font_manager() -> Arc<dyn FontManager> {
 
     #[cfg(not(any(target_os = "macos", target_os = "windows")))]
     {
-        compile_error!("Linux support not yet implemented");
+        Arc::new(fontlift_core::DummyFontManager) // LOCAL-ONLY
+    }
+}
+
+/// The platform manager acting on the per-user fonts of `user` (a SID or
+/// an account name) rather than the caller's, for `--for-user`.
+pub fn create_font_manager_for_user(user: &str) -> Result<Arc<dyn FontManager>, FontError> {
+    #[cfg(target_os = "windows")]
+    {
+        Ok(Arc::new(
+            fontlift_platform_win::WinFontManager::new().for_user(user)?,
+        ))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err(FontError::UnsupportedOperation(format!(
+            "--for-user {user}: installing for another user is only supported on Windows"
+        )))
     }
 }
 
@@ -226,120 +724,721 @@ pub fn write_completions<W: Write>(shell: Shell, mut writer: W) -> Result<(), Fo
     Ok(())
 }
 
-pub async fn handle_list_command(
-    manager: Arc<dyn FontManager>,
-    path: bool,
-    name: bool,
-    sorted: bool,
-    json: bool,
-) -> Result<(), FontError> {
-    let fonts = manager.list_installed_fonts()?;
-    let opts = ListRenderOptions {
-        show_path: path,
-        show_name: name,
-        sorted,
-        json,
-    };
-
-    match render_list_output(fonts, opts)? {
-        ListRender::Lines(lines) => {
-            for line in lines {
-                println!("{}", line);
-            }
-        }
-        ListRender::Json(json) => {
+/// `fontlift examples [TOPIC]`: the topic list, or one workflow's commands.
+pub fn handle_examples_command(topic: Option<String>, json: bool) -> Result<(), FontError> {
+    let Some(topic) = topic else {
+        if json {
+            let topics: Vec<_> = docs::WORKFLOWS
+                .iter()
+                .map(|w| serde_json::json!({ "topic": w.topic, "title": w.title }))
+                .collect();
+            let json = to_string_pretty(&topics).map_err(|e| {
+                FontError::InvalidFormat(format!("Failed to serialize topics: {}", e))
+            })?;
             println!("{}", json);
+            return Ok(());
+        }
+
+        println!("Topics (fontlift examples <TOPIC>):");
+        let width = docs::topics().map(str::len).max().unwrap_or(0);
+        for workflow in docs::WORKFLOWS {
+            println!("  {:width$}  {}", workflow.topic, workflow.title);
         }
+        return Ok(());
+    };
+
+    let workflow = docs::find_workflow(&topic)
+        .ok_or_else(|| FontError::UnsupportedOperation(format!("No examples for '{}'", topic)))?;
+    let steps = docs::render_workflow(workflow)?;
+
+    if json {
+        let json = to_string_pretty(&serde_json::json!({
+            "topic": workflow.topic,
+            "title": workflow.title,
+            "steps": steps,
+        }))
+        .map_err(|e| FontError::InvalidFormat(format!("Failed to serialize examples: {}", e)))?;
+        println!("{}", json);
+        return Ok(());
     }
 
+    println!("# {}", workflow.title);
+    for step in steps {
+        println!("\n# {}\n{}", step.note, step.command);
+    }
     Ok(())
 }
 
-fn to_core_strictness(s: ValidationStrictness) -> validation_ext::ValidationStrictness {
-    match s {
-        ValidationStrictness::Lenient => validation_ext::ValidationStrictness::Lenient,
-        ValidationStrictness::Normal => validation_ext::ValidationStrictness::Normal,
-        ValidationStrictness::Paranoid => validation_ext::ValidationStrictness::Paranoid,
+/// `fontlift info`: names of one face and, for variable fonts, its design
+/// space. JSON output also lists every localized name record.
+pub fn handle_info_command(font: &Path, face: u32, json: bool) -> Result<(), FontError> {
+    let info = metadata::read_face_info_at(font, face)?;
+    let variations = variations::read_variations(font, face)?;
+
+    if json {
+        let json = to_string_pretty(&serde_json::json!({
+            "face": info,
+            "names": metadata::read_localized_names(font, face)?,
+            "variations": variations,
+        }))
+        .map_err(|e| FontError::InvalidFormat(format!("Failed to serialize font info: {}", e)))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    println!("{}", info.postscript_name);
+    println!("  file:   {}", font.display());
+    println!("  family: {}", info.family_name);
+    println!("  style:  {}", info.style);
+    if let Some(weight) = info.weight {
+        println!("  weight: {}", weight);
+    }
+    if let Some(license) = &info.license {
+        println!("  license: {}", license.lines().next().unwrap_or_default());
+    }
+    if let Some(url) = &info.license_url {
+        println!("  license url: {}", url);
+    }
+    if let Some(embedding) = &info.embedding {
+        println!("  embedding: {}", embedding.description());
+    }
+
+    let Some(variations) = variations else {
+        println!("  static font (no variation axes)");
+        return Ok(());
+    };
+    println!("  axes:");
+    for axis in &variations.axes {
+        println!(
+            "    {}  {}  {} … {} (default {}){}",
+            axis.tag,
+            axis.name.as_deref().unwrap_or("-"),
+            axis.min,
+            axis.max,
+            axis.default,
+            if axis.hidden { " [hidden]" } else { "" }
+        );
     }
+    if variations.instances.is_empty() {
+        println!("  no named instances");
+        return Ok(());
+    }
+    println!("  named instances:");
+    let width = variations
+        .instances
+        .iter()
+        .map(|i| i.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for instance in &variations.instances {
+        println!(
+            "    {:width$}  {}",
+            instance.name,
+            instance.coordinates_label()
+        );
+    }
+    Ok(())
 }
 
-pub async fn handle_install_command(
+/// `fontlift diff`: what changed from `before` to `after`, each a font file
+/// or the name of an installed face.
+pub fn handle_diff_command(
     manager: Arc<dyn FontManager>,
-    font_inputs: Vec<PathBuf>,
-    admin: bool,
-    validate: bool,
-    strictness: ValidationStrictness,
-    inplace: bool,
-    opts: OperationOptions,
+    before: &str,
+    after: &str,
+    face: u32,
+    json: bool,
 ) -> Result<(), FontError> {
-    let scope = if admin {
-        FontScope::System
-    } else {
-        FontScope::User
+    let mut installed = None;
+    let mut locate = |arg: &str| -> Result<(PathBuf, u32), FontError> {
+        let path = PathBuf::from(arg);
+        if path.exists() {
+            return Ok((path, face));
+        }
+        if installed.is_none() {
+            installed = Some(protection::dedupe_fonts(manager.list_installed_fonts()?));
+        }
+        let font = snippets::resolve_face(installed.as_deref().unwrap_or_default(), arg)?;
+        Ok((
+            font.source.path.clone(),
+            font.source.face_index.unwrap_or(0),
+        ))
     };
+    let (before_path, before_face) = locate(before)?;
+    let (after_path, after_face) = locate(after)?;
+    let changes = diff::diff(
+        &diff::summarize(&before_path, before_face)?,
+        &diff::summarize(&after_path, after_face)?,
+    );
 
-    let targets = collect_font_inputs(&font_inputs)?;
-
-    // Optional pre-flight validation using out-of-process validator
-    if validate {
-        log_verbose(&opts, "Running out-of-process font validation...");
-        let config = ValidatorConfig::from_strictness(to_core_strictness(strictness));
+    if json {
+        let json = to_string_pretty(&serde_json::json!({
+            "before": before_path,
+            "after": after_path,
+            "changes": changes,
+        }))
+        .map_err(|e| FontError::InvalidFormat(format!("Failed to serialize font diff: {}", e)))?;
+        println!("{}", json);
+        return Ok(());
+    }
 
-        match validation_ext::validate_and_introspect(&targets, &config) {
-            Ok(results) => {
-                for (i, result) in results.iter().enumerate() {
-                    if let Err(e) = result {
-                        log_status(
-                            &opts,
-                            &format!("⚠️  Validation failed for {}: {}", targets[i].display(), e),
-                        );
-                        if !opts.dry_run {
-                            return Err(FontError::InvalidFormat(format!(
-                                "Font validation failed: {}",
-                                targets[i].display()
-                            )));
-                        }
-                    } else {
-                        log_verbose(&opts, &format!("✓ Validated: {}", targets[i].display()));
-                    }
-                }
-            }
-            Err(e) => {
-                // Validator not available - warn but continue
-                log_verbose(
-                    &opts,
-                    &format!("⚠️  Validation skipped (validator unavailable): {}", e),
-                );
-            }
+    println!("--- {}", before_path.display());
+    println!("+++ {}", after_path.display());
+    if changes.is_empty() {
+        println!("no differences in version, tables, names, axes or glyph count");
+        return Ok(());
+    }
+    for change in &changes {
+        let label = match change.item.as_str() {
+            "" => change.section.as_str().to_string(),
+            item => format!("{} {}", change.section.as_str(), item),
+        };
+        match (&change.before, &change.after) {
+            (Some(old), Some(new)) => println!("~ {label}: {old} → {new}"),
+            (None, Some(new)) => println!("+ {label}: {new}"),
+            (Some(old), None) => println!("- {label}: {old}"),
+            (None, None) => println!("~ {label}"),
         }
     }
+    println!("{} change(s)", changes.len());
+    Ok(())
+}
 
-    for path in targets {
-        log_verbose(&opts, &format!("Scope: {}", scope.description()));
-        if opts.dry_run {
-            log_status(
-                &opts,
-                &format!(
-                    "DRY-RUN: would install font {} ({})",
-                    path.display(),
-                    scope.description()
-                ),
+/// Report which characters of `text` each face of `font` supports; fail
+/// when one misses any.
+pub fn handle_coverage_command(font: &Path, text: &str, json: bool) -> Result<(), FontError> {
+    let faces = metadata::read_all_face_info(font)?;
+    let mut reports = Vec::with_capacity(faces.len());
+    for face in faces {
+        let index = face.source.face_index.unwrap_or(0);
+        let coverage = metadata::read_coverage(font, index, text)?;
+        reports.push((face, index, coverage));
+    }
+    let incomplete = reports
+        .iter()
+        .filter(|(_, _, coverage)| !coverage.is_complete())
+        .count();
+
+    if json {
+        let reports: Vec<_> = reports
+            .iter()
+            .map(|(face, index, coverage)| {
+                serde_json::json!({
+                    "path": font,
+                    "face_index": index,
+                    "postscript_name": face.postscript_name,
+                    "supported": coverage.supported,
+                    "missing": coverage.missing,
+                })
+            })
+            .collect();
+        let json = to_string_pretty(&reports).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize coverage: {}", e))
+        })?;
+        println!("{}", json);
+    } else {
+        for (face, _, coverage) in &reports {
+            let total = coverage.supported.len() + coverage.missing.len();
+            println!(
+                "{}: {} of {} supported",
+                face.postscript_name,
+                coverage.supported.len(),
+                total
             );
-            continue;
+            if !coverage.is_complete() {
+                let codepoints: Vec<String> = coverage
+                    .missing
+                    .iter()
+                    .map(|c| format!("{} U+{:04X}", c, u32::from(*c)))
+                    .collect();
+                println!("  missing: {}", codepoints.join(", "));
+            }
         }
+    }
 
-        // Determine actual install path: copy mode (default) vs inplace mode
-        let install_path = if inplace {
-            path.clone()
-        } else {
-            // Copy mode (default): copy font to system fonts directory
-            let fonts_dir = if admin {
-                PathBuf::from("/Library/Fonts")
-            } else {
-                dirs::home_dir()
-                    .ok_or_else(|| {
-                        FontError::UnsupportedOperation(
-                            "Cannot determine home directory".to_string(),
+    match incomplete {
+        0 => Ok(()),
+        n => Err(FontError::VerificationFailed(format!(
+            "{} face(s) of {} miss characters of the text",
+            n,
+            font.display()
+        ))),
+    }
+}
+
+/// Installed faces matching `query` by name and supporting every character
+/// of `supports`, printed like `list -p -n`.
+pub async fn handle_find_command(
+    manager: Arc<dyn FontManager>,
+    query: Option<String>,
+    supports: Option<String>,
+    json: bool,
+) -> Result<(), FontError> {
+    let needle = query.as_deref().map(|q| q.trim().to_lowercase());
+    let mut found: Vec<FontliftFontFaceInfo> =
+        protection::dedupe_fonts(manager.list_installed_fonts()?)
+            .into_iter()
+            .filter(|font| {
+                needle.as_deref().map_or(true, |needle| {
+                    [&font.postscript_name, &font.full_name, &font.family_name]
+                        .iter()
+                        .any(|name| name.to_lowercase().contains(needle))
+                })
+            })
+            .collect();
+    if let Some(text) = &supports {
+        // Unreadable files cannot be shown to support anything.
+        found.retain(|font| {
+            metadata::read_coverage(&font.source.path, font.source.face_index.unwrap_or(0), text)
+                .is_ok_and(|coverage| coverage.is_complete())
+        });
+    }
+    found.sort_by(sorting::compare_faces);
+
+    if json {
+        let json = to_string_pretty(&found).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize matching fonts: {}", e))
+        })?;
+        println!("{}", json);
+        return Ok(());
+    }
+    for font in &found {
+        println!("{}::{}", font.source.path.display(), font.postscript_name);
+    }
+    Ok(())
+}
+
+/// `fontlift serve`: run the request daemon until a client sends `shutdown`.
+///
+/// Unix sockets are the default where the standard library has them;
+/// elsewhere, and with `--tcp`, the daemon uses loopback TCP with a token.
+pub async fn handle_serve_command(
+    manager: Arc<dyn FontManager>,
+    socket: Option<PathBuf>,
+    tcp: bool,
+    endpoint: Option<PathBuf>,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    if opts.dry_run {
+        log_status(&opts, "Dry run: not starting the daemon");
+        return Ok(());
+    }
+
+    let use_tcp = tcp || !cfg!(unix);
+    tokio::task::spawn_blocking(move || {
+        if use_tcp {
+            let endpoint = endpoint.unwrap_or_else(serve::default_endpoint_path);
+            log_status(
+                &opts,
+                &format!(
+                    "Listening on 127.0.0.1; port and token in {}",
+                    endpoint.display()
+                ),
+            );
+            return serve::serve_tcp(manager, &endpoint);
+        }
+        #[cfg(unix)]
+        {
+            let socket = socket.unwrap_or_else(serve::default_socket_path);
+            log_status(&opts, &format!("Listening on {}", socket.display()));
+            serve::serve_unix(Arc::new(serve::Daemon::new(manager)), &socket)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = socket;
+            unreachable!("non-Unix platforms always use TCP")
+        }
+    })
+    .await
+    .map_err(|e| FontError::IoError(std::io::Error::other(e)))??;
+
+    log_status(&opts, "✅ Daemon stopped");
+    Ok(())
+}
+
+/// `fontlift man`: one page to stdout, or every page into `out`.
+pub fn handle_man_command(
+    command: Option<String>,
+    out: Option<PathBuf>,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let Some(dir) = out else {
+        return docs::write_man_page(command.as_deref(), std::io::stdout().lock());
+    };
+
+    if opts.dry_run {
+        log_status(
+            &opts,
+            &format!("DRY-RUN: would write man pages to {}", dir.display()),
+        );
+        return Ok(());
+    }
+    docs::write_man_pages_to(&dir)?;
+    log_status(&opts, &format!("✅ Wrote man pages to {}", dir.display()));
+    Ok(())
+}
+
+pub async fn handle_list_command(
+    manager: Arc<dyn FontManager>,
+    opts: ListRenderOptions,
+    filter: &ListFilter,
+    output: OutputOptions,
+) -> Result<(), FontError> {
+    let listing = manager.list_installed_fonts_report(filter)?;
+    // On stderr, so the listing itself stays parseable.
+    if output.should_print() {
+        for warning in &listing.warnings {
+            eprintln!("⚠️  {}", warning);
+        }
+        if listing.is_partial() {
+            eprintln!("⚠️  The listing is incomplete: fonts in the locations above are missing");
+        }
+    }
+    match render_list_output(listing.fonts, opts)? {
+        ListRender::Lines(lines) => {
+            for line in lines {
+                println!("{}", line);
+            }
+        }
+        ListRender::Json(json) => {
+            println!("{}", json);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn to_core_sort_key(s: SortBy) -> SortKey {
+    match s {
+        SortBy::Family => SortKey::Family,
+        SortBy::Weight => SortKey::Weight,
+        SortBy::Width => SortKey::Width,
+        SortBy::RecentlyInstalled => SortKey::RecentlyInstalled,
+        SortBy::Size => SortKey::Size,
+    }
+}
+
+/// `fontlift list` filter flags as a core filter.
+pub fn to_core_list_filter(
+    scope: Option<ListScope>,
+    family: Option<String>,
+    format: Option<ListFormat>,
+    style: Option<ListStyle>,
+    modified_since: Option<SystemTime>,
+) -> ListFilter {
+    ListFilter {
+        scope: scope.map(|scope| match scope {
+            ListScope::User => FontScope::User,
+            ListScope::System => FontScope::System,
+        }),
+        family,
+        format: format.map(|format| match format {
+            ListFormat::Ttf => FormatFilter::Ttf,
+            ListFormat::Otf => FormatFilter::Otf,
+            ListFormat::Ttc => FormatFilter::Ttc,
+        }),
+        style: style.map(|style| match style {
+            ListStyle::Italic => StyleFilter::Italic,
+            ListStyle::Bold => StyleFilter::Bold,
+        }),
+        modified_since,
+    }
+}
+
+/// `--cache` selections as core targets; `None` (every cache) when empty.
+pub fn to_core_cache_targets(caches: &[CleanupCache]) -> Option<CacheTargets> {
+    if caches.is_empty() {
+        return None;
+    }
+    Some(CacheTargets::only(caches.iter().map(|cache| match cache {
+        CleanupCache::System => CacheTarget::System,
+        CleanupCache::Adobe => CacheTarget::Adobe,
+        CleanupCache::Office => CacheTarget::Office,
+        CleanupCache::Java => CacheTarget::Java,
+        CleanupCache::Browser => CacheTarget::Browser,
+    })))
+}
+
+pub fn to_core_conflict_policy(policy: OnConflict) -> ConflictPolicy {
+    match policy {
+        OnConflict::Replace => ConflictPolicy::Replace,
+        OnConflict::Skip => ConflictPolicy::Skip,
+        OnConflict::Fail => ConflictPolicy::Fail,
+        OnConflict::KeepBoth => ConflictPolicy::KeepBoth,
+    }
+}
+
+pub fn to_core_migration_source(from: MigrateFrom) -> MigrationSource {
+    match from {
+        MigrateFrom::FontBase => MigrationSource::FontBase,
+        MigrateFrom::Suitcase => MigrationSource::Suitcase,
+        MigrateFrom::RightFont => MigrationSource::RightFont,
+    }
+}
+
+fn to_core_strictness(s: ValidationStrictness) -> validation_ext::ValidationStrictness {
+    match s {
+        ValidationStrictness::Lenient => validation_ext::ValidationStrictness::Lenient,
+        ValidationStrictness::Normal => validation_ext::ValidationStrictness::Normal,
+        ValidationStrictness::Paranoid => validation_ext::ValidationStrictness::Paranoid,
+    }
+}
+
+pub async fn handle_install_command(
+    manager: Arc<dyn FontManager>,
+    font_inputs: Vec<PathBuf>,
+    install: InstallOptions,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let mut history = HistoryEntry::start(HistoryOperation::Install, scope_for(install.admin));
+    let result = install_fonts(manager.clone(), font_inputs, install, opts, &mut history).await;
+    announce_change(manager.as_ref(), &history, &opts);
+    record_history(history, &result, &opts);
+    result
+}
+
+async fn install_fonts(
+    manager: Arc<dyn FontManager>,
+    font_inputs: Vec<PathBuf>,
+    install: InstallOptions,
+    opts: OperationOptions,
+    history: &mut HistoryEntry,
+) -> Result<(), FontError> {
+    let InstallOptions {
+        admin,
+        validate,
+        strictness,
+        inplace,
+        reason,
+        sha256,
+        origin,
+        temporary,
+        link,
+        no_smoothing,
+        on_conflict,
+        upgrade_only,
+        rename_duplicates,
+        warm_caches,
+        strict_license,
+        convert,
+        revalidate,
+        name_pattern,
+        stdin_name,
+        scan,
+        checksums,
+    } = install;
+
+    let scope = if admin {
+        FontScope::System
+    } else {
+        FontScope::User
+    };
+    let managed = check_managed_scope(scope, "install")?;
+    let strictness = managed.enforce_validation(validate, to_core_strictness(strictness))?;
+    // Sources are checked as given: downloads and archive contents are
+    // staged in temporary directories.
+    match &origin {
+        Some(origin) => managed.check_source(origin)?,
+        None => {
+            for input in &font_inputs {
+                managed.check_source(&input.to_string_lossy())?;
+            }
+        }
+    }
+
+    // `staged` owns the temp files behind URL and archive inputs; it must
+    // outlive the install loop below.
+    let in_place_flag = if temporary {
+        Some("--temp")
+    } else if inplace {
+        Some("--inplace")
+    } else if link {
+        Some("--link")
+    } else {
+        None
+    };
+    let staged = stage_install_inputs(
+        font_inputs,
+        sha256.as_deref(),
+        stdin_name.as_deref(),
+        in_place_flag,
+        convert,
+        checksums.is_some(),
+        &opts,
+    )?;
+    if staged.paths.is_empty() {
+        return Ok(());
+    }
+    let from_directory = staged.paths.iter().any(|input| input.is_dir());
+
+    // `replace` leaves ordinary conflicts to the platform, but a different
+    // font under an installed font's PostScript name must be caught first.
+    let installed = if temporary {
+        Vec::new()
+    } else {
+        manager.list_installed_fonts()?
+    };
+
+    let collected = collect_font_inputs(&staged.paths, &scan)?;
+    if let Some(checksums) = &checksums {
+        checksums.verify_files(&collected)?;
+        log_status(
+            &opts,
+            &format!("🔏 {} font(s) match the checksum manifest", collected.len()),
+        );
+    }
+    let deduped = dedupe::dedupe(&collected, &installed)?;
+    for skipped in &deduped.skipped {
+        log_status(
+            &opts,
+            &format!("⏭️  {}: skipped: {skipped}", skipped.path.display()),
+        );
+    }
+    let targets = deduped.unique;
+    if targets.is_empty() {
+        return Ok(());
+    }
+    for path in &targets {
+        managed.check_install(path, scope)?;
+    }
+
+    // Optional pre-flight validation using out-of-process validator
+    if validate {
+        log_verbose(&opts, "Running out-of-process font validation...");
+        match operations::validate(&targets, strictness, &managed, revalidate)? {
+            Validation::Checked { results, cached } => {
+                if cached > 0 {
+                    log_verbose(
+                        &opts,
+                        &format!("{cached} font(s) validated before; not checked again"),
+                    );
+                }
+                for (i, result) in results.iter().enumerate() {
+                    if let Err(e) = result {
+                        log_status(
+                            &opts,
+                            &format!("⚠️  Validation failed for {}: {}", targets[i].display(), e),
+                        );
+                        if !opts.dry_run {
+                            return Err(FontError::InvalidFormat(format!(
+                                "Font validation failed: {}",
+                                targets[i].display()
+                            )));
+                        }
+                    } else {
+                        log_verbose(&opts, &format!("✓ Validated: {}", targets[i].display()));
+                    }
+                }
+            }
+            Validation::Unavailable(e) => {
+                // Validator not available - warn but continue
+                log_verbose(
+                    &opts,
+                    &format!("⚠️  Validation skipped (validator unavailable): {}", e),
+                );
+            }
+        }
+    }
+
+    let pixel_fonts = warn_about_pixel_fonts(&targets, no_smoothing, &opts);
+    check_embedding_licenses(&targets, strict_license, &opts)?;
+
+    if temporary {
+        return install_for_session(&manager, targets, &opts);
+    }
+
+    if opts.dry_run {
+        return print_install_plans(&manager, &targets, scope, on_conflict, upgrade_only, &opts);
+    }
+
+    let mut newly_installed = Vec::new();
+    // Names given out in this batch, so two inputs never share a target.
+    let mut batch_names = HashSet::new();
+    // A directory is installed as a whole or not at all.
+    let mut batch = if from_directory {
+        InstallBatch::begin(manager.as_ref(), targets.len(), &opts)?
+    } else {
+        InstallBatch::none(manager.as_ref(), &opts)
+    };
+    let observer = CliProgress::new(&opts);
+    let mut tracker = Tracker::start(&observer, Operation::Install, targets.len());
+    for path in targets {
+        log_verbose(&opts, &format!("Scope: {}", scope.description()));
+        // In place, a symlinked input registers the real file: the path the
+        // OS reports it under.
+        let path = if inplace {
+            SymlinkPolicy::Resolve.apply(&path)
+        } else {
+            path
+        };
+
+        // `--no-smoothing` installs a patched copy of pixel fonts; it must
+        // outlive the copy below.
+        let unsmoothed = if no_smoothing && pixel_fonts.contains(&path) {
+            let copy = pixel_fonts::write_unsmoothed_copy(&path)?;
+            log_verbose(
+                &opts,
+                &format!(
+                    "Turned off smoothing in the installed copy of {}",
+                    path.display()
+                ),
+            );
+            Some(copy)
+        } else {
+            None
+        };
+        let unsmoothed_file = unsmoothed.as_ref().map_or(&path, |copy| &copy.path);
+
+        // A different font under an installed font's PostScript name would
+        // silently take its place: refuse, or install a renamed copy.
+        let collisions = if inplace {
+            Vec::new()
+        } else {
+            conflicts::name_collisions(&installed, unsmoothed_file)?
+        };
+        let renamed = match collisions.first() {
+            Some(_) if rename_duplicates => {
+                let copy = rename::write_renamed_copy(unsmoothed_file)?;
+                for collision in &collisions {
+                    log_status(&opts, &format!("ℹ️  {collision}"));
+                }
+                log_status(
+                    &opts,
+                    &format!(
+                        "ℹ️  Installing {} under new names tagged {}",
+                        path.display(),
+                        copy.tag
+                    ),
+                );
+                Some(copy)
+            }
+            Some(collision) if on_conflict == ConflictPolicy::Replace && !upgrade_only => {
+                log_status(&opts, &format!("⛔ Not replacing: {collision}"));
+                log_status(
+                    &opts,
+                    "   Pass --rename-duplicates to install it next to the installed font under new names.",
+                );
+                return Err(FontError::AlreadyInstalled(collision.installed.clone()));
+            }
+            _ => None,
+        };
+        let source_file = renamed.as_ref().map_or(unsmoothed_file, |copy| &copy.path);
+
+        // Determine actual install path: copy mode (default) vs inplace mode
+        let mut original_file_name = None;
+        let mut install_path = if inplace {
+            path.clone()
+        } else {
+            // Copy mode (default): copy font to system fonts directory
+            let fonts_dir = if let Some(dir) = manager.install_directory(scope) {
+                dir
+            } else if admin {
+                PathBuf::from("/Library/Fonts")
+            } else {
+                dirs::home_dir()
+                    .ok_or_else(|| {
+                        FontError::UnsupportedOperation(
+                            "Cannot determine home directory".to_string(),
                         )
                     })?
                     .join("Library/Fonts")
@@ -348,145 +1447,3244 @@ pub async fn handle_install_command(
             if !fonts_dir.exists() {
                 fs::create_dir_all(&fonts_dir).map_err(FontError::IoError)?;
             }
-            let target = fonts_dir.join(path.file_name().unwrap_or_default());
-            if target != path {
-                log_verbose(
+            let source_name = source_file
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            let file_name = installed_file_name(
+                source_file,
+                &source_name,
+                name_pattern.as_ref(),
+                &mut batch_names,
+            );
+            let target = file_names::fit_target_path(&fonts_dir, &file_name)?;
+            if renamed.is_some() {
+                original_file_name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned());
+            }
+            if file_name != source_name {
+                log_verbose(&opts, &format!("Installing {source_name} as {file_name}"));
+                original_file_name.get_or_insert(source_name);
+            }
+            if target
+                .file_name()
+                .is_some_and(|name| name != file_name.as_str())
+            {
+                log_status(
                     &opts,
-                    &format!("Copying {} to {}", path.display(), target.display()),
+                    &format!(
+                        "ℹ️  {} is too long for {}; installing it as {}",
+                        file_name,
+                        fonts_dir.display(),
+                        target.file_name().unwrap_or_default().to_string_lossy()
+                    ),
                 );
-                fs::copy(&path, &target).map_err(FontError::IoError)?;
+                original_file_name.get_or_insert(file_name);
             }
             target
         };
 
+        if upgrade_only {
+            let clashes = conflicts::conflicts_for_file(&installed, source_file, &install_path)?;
+            if !clashes.is_empty() {
+                match conflicts::check_upgrade(&path, &clashes)? {
+                    skip @ Upgrade::Skip { .. } => {
+                        log_status(
+                            &opts,
+                            &format!("⏭️  Skipping {}: skipped ({skip})", path.display()),
+                        );
+                        tracker.item(Some(&path), true);
+                        continue;
+                    }
+                    upgrade => {
+                        log_verbose(&opts, &format!("{}: {upgrade}", path.display()));
+                    }
+                }
+            }
+        } else if on_conflict != ConflictPolicy::Replace {
+            let clashes = conflicts::conflicts_for_file(&installed, source_file, &install_path)?;
+            if let Some(first) = clashes.first() {
+                match on_conflict {
+                    ConflictPolicy::Skip => {
+                        log_status(
+                            &opts,
+                            &format!(
+                                "⏭️  Skipping {}: clashes with installed {}",
+                                path.display(),
+                                first.display()
+                            ),
+                        );
+                        tracker.item(Some(&path), true);
+                        continue;
+                    }
+                    ConflictPolicy::Fail => {
+                        return Err(FontError::AlreadyInstalled(first.clone()));
+                    }
+                    ConflictPolicy::KeepBoth if install_path != path && install_path.exists() => {
+                        install_path = conflicts::keep_both_path(&install_path);
+                        log_status(
+                            &opts,
+                            &format!(
+                                "ℹ️  Keeping both: installing {} as {}",
+                                path.display(),
+                                install_path.display()
+                            ),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut actions = Vec::new();
+        let backup = batch.backup_for(&path, &install_path);
+        if let Some(backup) = &backup {
+            actions.push(JournalAction::BackupFile {
+                path: install_path.clone(),
+                backup: backup.clone(),
+            });
+        }
+        if install_path != path {
+            actions.push(JournalAction::CopyFile {
+                from: source_file.clone(),
+                to: install_path.clone(),
+            });
+        }
+        actions.push(JournalAction::RegisterFont {
+            path: install_path.clone(),
+            scope,
+        });
+        batch.add(&path, actions)?;
+        if let Some(backup) = backup {
+            batch.move_aside(&install_path, backup)?;
+        }
+
+        if install_path != path {
+            let verb = if link { "Linking" } else { "Copying" };
+            log_verbose(
+                &opts,
+                &format!("{verb} {} to {}", path.display(), install_path.display()),
+            );
+            if link {
+                symlinks::link_file(source_file, &install_path).map_err(FontError::IoError)?;
+            } else {
+                links::copy_file(source_file, &install_path).map_err(FontError::IoError)?;
+            }
+            batch.step_done()?;
+        }
+
         log_status(
             &opts,
             &format!("Installing font from: {}", install_path.display()),
         );
-        let source = FontliftFontSource::new(install_path).with_scope(Some(scope));
-        manager.install_font(&source)?;
-        log_status(&opts, "✅ Successfully installed font");
+        let source = FontliftFontSource::new(install_path.clone()).with_scope(Some(scope));
+        let outcome = manager.install_font_with_policy(&source, on_conflict)?;
+        batch.step_done()?;
+        let Some(install_path) = outcome.installed else {
+            log_status(
+                &opts,
+                &format!(
+                    "⏭️  Skipped {}: it clashes with an installed font",
+                    path.display()
+                ),
+            );
+            tracker.item(Some(&path), true);
+            continue;
+        };
+        for clash in &outcome.conflicts {
+            log_verbose(
+                &opts,
+                &format!("On conflict ({}): {}", outcome.policy, clash.display()),
+            );
+        }
+        log_status(&opts, "✅ Successfully installed font");
+        tracker.item(Some(&path), true);
+        batch.installed(&install_path);
+        newly_installed.push(install_path.clone());
+        history.handled(&install_path);
+
+        // The font is installed at this point; a bookkeeping failure should
+        // not turn a successful install into an error.
+        let reason = reason.clone();
+        let origin = staged
+            .origins
+            .get(&path)
+            .cloned()
+            .or_else(|| origin.clone());
+        if let Err(e) =
+            operations::record_install(&install_path, scope, reason, origin, original_file_name)
+        {
+            log_status(
+                &opts,
+                &format!("⚠️  Could not record install reason: {}", e),
+            );
+        }
+    }
+    drop(tracker);
+    batch.commit()?;
+
+    if warm_caches {
+        warm_font_caches(manager.as_ref(), &newly_installed, &opts);
+    }
+
+    Ok(())
+}
+
+/// A directory install journaled as one [`Transaction`], so `fontlift
+/// doctor` sees an interrupted one as a single operation.
+///
+/// Dropped before [`InstallBatch::commit`] — an install in the batch failed
+/// — it uninstalls the fonts the batch already installed and deletes their
+/// copies. Fonts the batch overwrote are moved aside first and put back by
+/// the roll back; a commit deletes them. Without a transaction every method
+/// does nothing.
+pub(crate) struct InstallBatch<'a> {
+    transaction: Option<Transaction>,
+    installed: Vec<PathBuf>,
+    backups: Vec<PathBuf>,
+    manager: &'a dyn FontManager,
+    opts: &'a OperationOptions,
+}
+
+impl<'a> InstallBatch<'a> {
+    pub(crate) fn begin(
+        manager: &'a dyn FontManager,
+        count: usize,
+        opts: &'a OperationOptions,
+    ) -> Result<Self, FontError> {
+        let mut batch = Self::none(manager, opts);
+        batch.transaction = Some(Transaction::begin(format!("Install {count} font(s)"))?);
+        Ok(batch)
+    }
+
+    fn none(manager: &'a dyn FontManager, opts: &'a OperationOptions) -> Self {
+        Self {
+            transaction: None,
+            installed: Vec::new(),
+            backups: Vec::new(),
+            manager,
+            opts,
+        }
+    }
+
+    pub(crate) fn add(
+        &mut self,
+        font: &Path,
+        actions: Vec<JournalAction>,
+    ) -> Result<(), FontError> {
+        match &mut self.transaction {
+            Some(transaction) => transaction.add(font.display().to_string(), actions),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn step_done(&mut self) -> Result<(), FontError> {
+        match &mut self.transaction {
+            Some(transaction) => transaction.step_done(),
+            None => Ok(()),
+        }
+    }
+
+    /// Where to move the font at `target` before installing `font` over
+    /// it, when there is one and the batch can undo the install.
+    pub(crate) fn backup_for(&self, font: &Path, target: &Path) -> Option<PathBuf> {
+        (self.transaction.is_some() && target != font && target.exists())
+            .then(|| journal::backup_path(target))
+    }
+
+    /// Carry out the [`JournalAction::BackupFile`] step for `target`.
+    pub(crate) fn move_aside(&mut self, target: &Path, backup: PathBuf) -> Result<(), FontError> {
+        log_verbose(
+            self.opts,
+            &format!("Moving {} aside to {}", target.display(), backup.display()),
+        );
+        fs::rename(target, &backup).map_err(FontError::IoError)?;
+        self.backups.push(backup);
+        self.step_done()
+    }
+
+    fn installed(&mut self, path: &Path) {
+        if self.transaction.is_some() {
+            self.installed.push(path.to_path_buf());
+        }
+    }
+
+    pub(crate) fn commit(mut self) -> Result<(), FontError> {
+        let Some(transaction) = self.transaction.take() else {
+            return Ok(());
+        };
+        transaction.commit()?;
+        for backup in std::mem::take(&mut self.backups) {
+            if let Err(e) = fs::remove_file(&backup) {
+                log_verbose(
+                    self.opts,
+                    &format!("⚠️  Could not delete {}: {e}", backup.display()),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for InstallBatch<'_> {
+    fn drop(&mut self) {
+        let Some(transaction) = self.transaction.take() else {
+            return;
+        };
+        log_status(
+            self.opts,
+            "↩️  The install failed; removing the fonts this batch already installed \
+             and restoring the ones it replaced",
+        );
+        let executor = DoctorExecutor {
+            manager: self.manager,
+            accept_changed: false,
+            opts: self.opts,
+        };
+        match transaction.roll_back(&executor) {
+            Ok(results) if results.iter().all(|r| r.success) => {}
+            Ok(_) => log_status(
+                self.opts,
+                "⚠️  Some steps could not be undone; run `fontlift doctor --strategy back` to retry",
+            ),
+            Err(e) => log_status(self.opts, &format!("⚠️  Could not roll back the batch: {e}")),
+        }
+        let installed = std::mem::take(&mut self.installed);
+        if installed.is_empty() {
+            return;
+        }
+        if let Err(e) = install_db::update_install_db(|db| {
+            for path in &installed {
+                db.forget(path);
+            }
+        }) {
+            log_status(
+                self.opts,
+                &format!("⚠️  Could not update the install records: {e}"),
+            );
+        }
+    }
+}
+
+/// `install --warm-caches`: load every face of the fonts just installed so
+/// the platform caches them now. The fonts are installed either way, so
+/// failures are only warnings.
+fn warm_font_caches(manager: &dyn FontManager, paths: &[PathBuf], opts: &OperationOptions) {
+    let started = Instant::now();
+    let mut faces = 0;
+    for path in paths {
+        let source = FontliftFontSource::new(path.clone());
+        match manager.warm_font_cache(&source) {
+            Ok(count) => {
+                log_verbose(
+                    opts,
+                    &format!("Warmed {count} face(s) in {}", path.display()),
+                );
+                faces += count;
+            }
+            Err(FontError::UnsupportedOperation(message)) => {
+                log_verbose(opts, &format!("⚠️  {message}"));
+                return;
+            }
+            Err(e) => log_status(
+                opts,
+                &format!("⚠️  Could not warm the cache for {}: {e}", path.display()),
+            ),
+        }
+    }
+    log_status(
+        opts,
+        &format!(
+            "🔥 Warmed font caches for {faces} face(s) in {:.1}s",
+            started.elapsed().as_secs_f64()
+        ),
+    );
+}
+
+/// Warn about bitmap-only and pixel fonts among `targets`, and return the
+/// paths `--no-smoothing` would change.
+fn warn_about_pixel_fonts(
+    targets: &[PathBuf],
+    no_smoothing: bool,
+    opts: &OperationOptions,
+) -> HashSet<PathBuf> {
+    let mut found = HashSet::new();
+    for path in targets {
+        let Ok(Some(report)) = pixel_fonts::analyze(path) else {
+            continue;
+        };
+        log_status(
+            opts,
+            &format!("⚠️  {} {}", path.display(), report.warning()),
+        );
+        if !report.benefits_from_no_smoothing() {
+            continue;
+        }
+        if !no_smoothing && cfg!(windows) {
+            log_status(
+                opts,
+                "   Pass --no-smoothing to install a copy that turns smoothing off at every size.",
+            );
+        }
+        found.insert(path.clone());
+    }
+    found
+}
+
+/// Warn about faces among `targets` whose `fsType` restricts embedding; with
+/// `--strict-license`, refuse to install them (except in a dry run).
+fn check_embedding_licenses(
+    targets: &[PathBuf],
+    strict_license: bool,
+    opts: &OperationOptions,
+) -> Result<(), FontError> {
+    let mut restricted = Vec::new();
+    for path in targets {
+        let Ok(faces) = metadata::read_all_face_info(path) else {
+            continue;
+        };
+        for face in faces {
+            let Some(embedding) = face.embedding.filter(|e| e.is_restricted()) else {
+                continue;
+            };
+            log_status(
+                opts,
+                &format!(
+                    "⚠️  {} ({}): {}",
+                    path.display(),
+                    face.postscript_name,
+                    embedding.description()
+                ),
+            );
+            restricted.push(face.postscript_name);
+        }
+    }
+    if restricted.is_empty() || !strict_license || opts.dry_run {
+        return Ok(());
+    }
+    Err(FontError::VerificationFailed(format!(
+        "--strict-license: {} face(s) restrict embedding: {}",
+        restricted.len(),
+        restricted.join(", ")
+    )))
+}
+
+/// Dry-run output for `fontlift install`: each font's planned platform
+/// actions, as numbered steps or (with `--json`) one array of plans.
+///
+/// A font the manager would refuse gets its error in place of the steps;
+/// the rest are still planned.
+fn print_install_plans(
+    manager: &Arc<dyn FontManager>,
+    targets: &[PathBuf],
+    scope: FontScope,
+    on_conflict: ConflictPolicy,
+    upgrade_only: bool,
+    opts: &OperationOptions,
+) -> Result<(), FontError> {
+    let installed = if upgrade_only {
+        manager.list_installed_fonts()?
+    } else {
+        Vec::new()
+    };
+    let mut plans = Vec::new();
+    for path in targets {
+        let source = FontliftFontSource::new(path.clone()).with_scope(Some(scope));
+        let plan = manager.plan_install(&source);
+        let upgrade = if upgrade_only {
+            let clashes = conflicts::conflicts_for_file(&installed, path, path)?;
+            if clashes.is_empty() {
+                None
+            } else {
+                Some(conflicts::check_upgrade(path, &clashes)?)
+            }
+        } else {
+            None
+        };
+
+        if opts.output.json {
+            let (actions, error) = match plan {
+                Ok(actions) => (actions, None),
+                Err(e) => (Vec::new(), Some(e.to_string())),
+            };
+            plans.push(serde_json::json!({
+                "font": path,
+                "scope": scope,
+                "on_conflict": on_conflict,
+                "upgrade": upgrade,
+                "actions": actions,
+                "error": error,
+            }));
+            continue;
+        }
+
+        if let Some(skip @ Upgrade::Skip { .. }) = &upgrade {
+            log_status(
+                opts,
+                &format!("DRY-RUN: would skip font {} ({skip})", path.display()),
+            );
+            continue;
+        }
+        log_status(
+            opts,
+            &format!(
+                "DRY-RUN: would install font {} ({}{})",
+                path.display(),
+                scope.description(),
+                match on_conflict {
+                    ConflictPolicy::Replace => String::new(),
+                    policy => format!(", on conflict: {policy}"),
+                }
+            ),
+        );
+        if let Some(upgrade) = &upgrade {
+            log_status(opts, &format!("  {upgrade}"));
+        }
+        match plan {
+            Ok(actions) => {
+                for (step, action) in actions.iter().enumerate() {
+                    log_status(opts, &format!("  {}. {}", step + 1, action.description()));
+                }
+            }
+            Err(e) => log_status(opts, &format!("  ⚠️  would fail: {}", e)),
+        }
+    }
+
+    if opts.output.json {
+        let json = to_string_pretty(&plans).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize install plan: {}", e))
+        })?;
+        println!("{}", json);
+    }
+    Ok(())
+}
+
+/// Register each font for the current login session, where it already lives.
+fn install_for_session(
+    manager: &Arc<dyn FontManager>,
+    targets: Vec<PathBuf>,
+    opts: &OperationOptions,
+) -> Result<(), FontError> {
+    for path in targets {
+        if opts.dry_run {
+            log_status(
+                opts,
+                &format!(
+                    "DRY-RUN: would install font {} for this session only",
+                    path.display()
+                ),
+            );
+            continue;
+        }
+
+        manager.install_font_session(&FontliftFontSource::new(path.clone()))?;
+        log_status(
+            opts,
+            &format!(
+                "✅ Installed {} for this session only; it is gone after logout. Keep the file where it is while you use it.",
+                path.display()
+            ),
+        );
+    }
+    Ok(())
+}
+
+/// `fontlift preview`: load fonts for this process only and report what the
+/// OS made of them, or keep them loaded while `command` runs.
+pub fn handle_preview_command(
+    manager: Arc<dyn FontManager>,
+    fonts: Vec<PathBuf>,
+    command: Vec<String>,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let targets = collect_font_inputs(&fonts, &ScanOptions::default())?;
+    if opts.dry_run {
+        log_status(
+            &opts,
+            &format!(
+                "DRY-RUN: would load {} font file(s) without installing them",
+                targets.len()
+            ),
+        );
+        return Ok(());
+    }
+
+    let Some((program, args)) = command.split_first() else {
+        for path in targets {
+            let font = TemporaryFont::install_private(manager.clone(), &path)?;
+            let names = match font.postscript_names() {
+                [] => "no face names could be read".to_string(),
+                names => names.join(", "),
+            };
+            log_status(&opts, &format!("✅ {}: {}", path.display(), names));
+            font.uninstall()?;
+        }
+        return Ok(());
+    };
+
+    // Private registrations are not inherited, so the command gets session
+    // registrations; the guards undo them when this function returns.
+    let loaded = targets
+        .iter()
+        .map(|path| TemporaryFont::install(manager.clone(), path))
+        .collect::<Result<Vec<_>, _>>()?;
+    log_verbose(
+        &opts,
+        &format!("Loaded {} font file(s); running {}", loaded.len(), program),
+    );
+    let status = std::process::Command::new(program)
+        .args(args)
+        .status()
+        .map_err(FontError::IoError)?;
+    for font in loaded {
+        font.uninstall()?;
+    }
+    if !status.success() {
+        return Err(FontError::ScriptError(format!(
+            "`{}` exited with {}",
+            program, status
+        )));
+    }
+    Ok(())
+}
+
+/// Local files to install, plus the temp downloads and extractions behind them.
+///
+/// Dropping this deletes those temp directories, so keep it alive until every
+/// font has been copied or registered.
+struct StagedInputs {
+    paths: Vec<PathBuf>,
+    /// Where each staged file came from (a URL or an archive path).
+    origins: HashMap<PathBuf, String>,
+    _downloads: Vec<FetchedFont>,
+    _archives: Vec<ExtractedArchive>,
+    _converted: Vec<type1::ConvertedFont>,
+    _stdin: Option<StagedFont>,
+}
+
+/// The install input that reads a font from stdin.
+const STDIN_INPUT: &str = "-";
+
+/// Download URL inputs, unpack archive inputs and read a `-` input from
+/// stdin into a file named `stdin_name`, returning local font paths.
+/// With `convert`, Type 1 inputs, and the Type 1 fonts in input directories,
+/// are converted to OpenType and the converted copies staged instead.
+///
+/// In dry-run mode nothing is downloaded, extracted, read or converted;
+/// those inputs are reported and dropped. `in_place_flag` names the option
+/// (`--inplace`, `--temp`) that registers files where they are, which rules
+/// out URL, archive and stdin inputs.
+fn stage_install_inputs(
+    font_inputs: Vec<PathBuf>,
+    sha256: Option<&str>,
+    stdin_name: Option<&str>,
+    in_place_flag: Option<&str>,
+    convert: bool,
+    verify: bool,
+    opts: &OperationOptions,
+) -> Result<StagedInputs, FontError> {
+    let reads_stdin = font_inputs
+        .iter()
+        .any(|input| input.as_os_str() == STDIN_INPUT);
+    let stdin_name = match (reads_stdin, stdin_name) {
+        (true, Some(name)) => Some(font_bytes::staged_file_name(name)?),
+        (true, None) => {
+            return Err(FontError::InvalidArgument(
+                "`-` reads a font from stdin and needs --name, e.g. --name MyFont.ttf".to_string(),
+            ))
+        }
+        (false, Some(_)) => {
+            return Err(FontError::InvalidArgument(
+                "--name names the font read from stdin; add `-` to the inputs".to_string(),
+            ))
+        }
+        (false, None) => None,
+    };
+
+    let (urls, local): (Vec<PathBuf>, Vec<PathBuf>) = font_inputs
+        .into_iter()
+        .filter(|input| input.as_os_str() != STDIN_INPUT)
+        .partition(|input| input.to_str().is_some_and(fetch::is_url));
+    let (archives, paths): (Vec<PathBuf>, Vec<PathBuf>) = local
+        .into_iter()
+        .partition(|input| input.is_file() && archive::is_archive(input));
+
+    if sha256.is_some() && urls.len() != 1 {
+        return Err(FontError::InvalidArgument(
+            "--sha256 requires exactly one URL input".to_string(),
+        ));
+    }
+
+    if let Some(flag) =
+        in_place_flag.filter(|_| !(urls.is_empty() && archives.is_empty() && !reads_stdin))
+    {
+        return Err(FontError::InvalidArgument(format!(
+            "{flag} cannot be used with URL, archive or stdin inputs (their files are temporary)"
+        )));
+    }
+
+    // Manifest entries are relative to the manifest's folder, which
+    // downloaded and extracted temp files are never under.
+    if verify && !(urls.is_empty() && archives.is_empty() && !reads_stdin) {
+        return Err(FontError::InvalidArgument(
+            "--verify checks files and folders on disk; extract the archive or download the fonts first, then install that folder with --verify".to_string(),
+        ));
+    }
+
+    let mut staged = StagedInputs {
+        paths,
+        origins: HashMap::new(),
+        _downloads: Vec::new(),
+        _archives: Vec::new(),
+        _converted: Vec::new(),
+        _stdin: None,
+    };
+
+    if let Some(name) = stdin_name {
+        if opts.dry_run {
+            log_status(opts, &format!("DRY-RUN: would read {name} from stdin"));
+        } else {
+            let font = StagedFont::read_from(std::io::stdin().lock(), &name)?;
+            log_verbose(opts, &format!("Read {name} from stdin"));
+            staged.paths.push(font.path.clone());
+            staged
+                .origins
+                .insert(font.path.clone(), "stdin".to_string());
+            staged._stdin = Some(font);
+        }
+    }
+
+    if convert {
+        stage_type1_conversions(&mut staged, opts)?;
+    }
+
+    if opts.dry_run {
+        for url in &urls {
+            log_status(
+                opts,
+                &format!("DRY-RUN: would download {}", url.to_string_lossy()),
+            );
+        }
+    } else if !urls.is_empty() {
+        let requests: Vec<FetchRequest> = urls
+            .iter()
+            .map(|url| {
+                log_status(opts, &format!("Downloading {}", url.to_string_lossy()));
+                FetchRequest::new(url.to_string_lossy()).with_sha256(sha256.map(str::to_string))
+            })
+            .collect();
+
+        for fetched in fetch::fetch_fonts(&requests, fetch::DEFAULT_MAX_CONCURRENT_DOWNLOADS)? {
+            log_verbose(
+                opts,
+                &format!(
+                    "Downloaded {} (sha256 {})",
+                    fetched.path.display(),
+                    fetched.sha256
+                ),
+            );
+            staged.paths.push(fetched.path.clone());
+            staged
+                .origins
+                .insert(fetched.path.clone(), fetched.url.clone());
+            staged._downloads.push(fetched);
+        }
+    }
+
+    for bundle in archives {
+        if opts.dry_run {
+            log_status(
+                opts,
+                &format!("DRY-RUN: would extract fonts from {}", bundle.display()),
+            );
+            continue;
+        }
+
+        let extracted = archive::extract_fonts(&bundle)?;
+        log_status(
+            opts,
+            &format!(
+                "Extracted {} font file(s) from {}",
+                extracted.fonts.len(),
+                bundle.display()
+            ),
+        );
+        for font in &extracted.fonts {
+            staged.paths.push(font.clone());
+            staged
+                .origins
+                .insert(font.clone(), bundle.display().to_string());
+        }
+        staged._archives.push(extracted);
+    }
+
+    Ok(staged)
+}
+
+/// The file name to install `source_file` (named `source_name`) under:
+/// built from `name_pattern` when one is given and the font's names can be
+/// read, sanitized, and numbered if an earlier font in the batch took it.
+pub(crate) fn installed_file_name(
+    source_file: &Path,
+    source_name: &str,
+    name_pattern: Option<&NamePattern>,
+    batch_names: &mut HashSet<String>,
+) -> String {
+    let name = name_pattern
+        .and_then(|pattern| {
+            metadata::read_face_info(source_file)
+                .ok()
+                .map(|face| pattern.render(&face, source_name))
+        })
+        .unwrap_or_else(|| file_names::sanitize_file_name(source_name));
+    file_names::unique_file_name(&name, batch_names)
+}
+
+/// Replace the Type 1 fonts among `staged.paths`, given directly or inside
+/// an input directory, with OpenType conversions of them.
+fn stage_type1_conversions(
+    staged: &mut StagedInputs,
+    opts: &OperationOptions,
+) -> Result<(), FontError> {
+    let mut sources = Vec::new();
+    for path in &staged.paths {
+        if path.is_dir() {
+            for entry in fs::read_dir(path).map_err(FontError::IoError)? {
+                let entry = entry.map_err(FontError::IoError)?.path();
+                // Metrics files beside the outlines are read by the converter.
+                if entry.is_file() && type1::detect(&entry).is_some_and(|kind| kind.has_outlines())
+                {
+                    sources.push(entry);
+                }
+            }
+        } else if path.is_file() && type1::detect(path).is_some() {
+            sources.push(path.clone());
+        }
+    }
+    if sources.is_empty() {
+        return Ok(());
+    }
+    staged.paths.retain(|path| !sources.contains(path));
+
+    if opts.dry_run {
+        for source in &sources {
+            log_status(
+                opts,
+                &format!("DRY-RUN: would convert {} to OpenType", source.display()),
+            );
+        }
+        return Ok(());
+    }
+
+    let converter = type1::Converter::find()?;
+    for source in sources {
+        let converted = type1::convert_for_install(&converter, &source)?;
+        log_status(opts, &format!("Converted {} to OpenType", source.display()));
+        staged.paths.push(converted.path.clone());
+        staged
+            .origins
+            .insert(converted.path.clone(), source.display().to_string());
+        staged._converted.push(converted);
+    }
+    Ok(())
+}
+
+/// Delete a font file `remove` has unregistered and forget its install
+/// record. Says in verbose output when only a hard link was removed.
+fn remove_font_file(path: &Path, opts: &OperationOptions) -> Result<(), FontError> {
+    let removed = links::remove_file(path).map_err(FontError::IoError)?;
+    log_verbose(opts, &format!("{}: {}", path.display(), removed));
+    forget_install_record(path, opts);
+    Ok(())
+}
+
+/// Dispose of a font file `remove` has unregistered: into quarantine, with
+/// its install record, or deleted outright with `--purge`.
+fn discard_font_file(
+    path: &Path,
+    scope: FontScope,
+    purge: bool,
+    opts: &OperationOptions,
+) -> Result<(), FontError> {
+    if !path.exists() {
+        log_status(
+            opts,
+            &format!("⚠️  Font file not found: {}", path.display()),
+        );
+        return Ok(());
+    }
+    if purge {
+        remove_font_file(path, opts)?;
+        log_status(
+            opts,
+            &format!("✅ Successfully removed font file: {}", path.display()),
+        );
+        return Ok(());
+    }
+
+    let record = install_db::load_install_db()
+        .ok()
+        .and_then(|db| db.find(path).cloned());
+    let font = quarantine::quarantine(path, scope, record)?;
+    forget_install_record(path, opts);
+    log_status(
+        opts,
+        &format!(
+            "✅ Removed {} (undo with `fontlift restore {}`)",
+            path.display(),
+            font.id
+        ),
+    );
+    Ok(())
+}
+
+/// Delete quarantined fonts older than the retention period. A failure
+/// only costs disk space, so it is reported and otherwise ignored.
+fn purge_expired_quarantine(opts: &OperationOptions) {
+    match quarantine::purge_expired(SystemTime::now(), quarantine::retention()) {
+        Ok(purged) => {
+            for font in purged {
+                log_verbose(
+                    opts,
+                    &format!("Purged {} from quarantine", font.original_path.display()),
+                );
+            }
+        }
+        Err(e) => log_verbose(opts, &format!("⚠️  Could not purge quarantine: {}", e)),
+    }
+}
+
+fn scope_for(admin: bool) -> FontScope {
+    if admin {
+        FontScope::System
+    } else {
+        FontScope::User
+    }
+}
+
+/// Append a finished command to the history log. Dry runs change nothing
+/// and are not recorded; like the install database, a failed write only
+/// warns.
+fn record_history(history: HistoryEntry, result: &Result<(), FontError>, opts: &OperationOptions) {
+    if opts.dry_run {
+        return;
+    }
+    if let Err(e) = history::append(&history.finish(result.as_ref().map(|_| ()))) {
+        log_verbose(opts, &format!("⚠️  Could not record history: {}", e));
+    }
+}
+
+/// Run the `[notify]` hooks of `fontlift.toml` for the files `history`
+/// handled. The fonts already changed, so failures are only warnings.
+pub(crate) fn announce_change(
+    manager: &dyn FontManager,
+    history: &HistoryEntry,
+    opts: &OperationOptions,
+) {
+    if opts.dry_run {
+        return;
+    }
+    let Some(change) = FontChange::from_history(history) else {
+        return;
+    };
+    let config = match NotifyConfig::load() {
+        Ok(config) if config.is_empty() => return,
+        Ok(config) => config,
+        Err(e) => {
+            log_status(opts, &format!("⚠️  Not notifying applications: {e}"));
+            return;
+        }
+    };
+    log_verbose(opts, "Notifying applications that fonts changed");
+    for failure in notify::notify(manager, &config, &change) {
+        log_status(opts, &format!("⚠️  {failure}"));
+    }
+}
+
+fn forget_install_record(path: &Path, opts: &OperationOptions) {
+    if let Err(e) = operations::forget_install(path) {
+        log_verbose(
+            opts,
+            &format!("⚠️  Could not update install database: {}", e),
+        );
+    }
+}
+
+/// Answer `fontlift list --why <NAME|PATH>` from the install database.
+pub async fn handle_why_command(
+    manager: Arc<dyn FontManager>,
+    query: String,
+    json: bool,
+) -> Result<(), FontError> {
+    let path = PathBuf::from(&query);
+    let path = if path.exists() {
+        path
+    } else {
+        manager
+            .list_installed_fonts()?
+            .into_iter()
+            .find(|f| f.postscript_name == query || f.full_name == query)
+            .map(|f| f.source.path)
+            .ok_or_else(|| FontError::FontNotFound(PathBuf::from(&query)))?
+    };
+
+    let db = install_db::load_install_db()?;
+    let record = db.find(&path);
+
+    if json {
+        let json = to_string_pretty(&record).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize install record: {}", e))
+        })?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    match record {
+        Some(record) => println!("{}", record.explain()),
+        None => println!(
+            "{} was not installed by fontlift (no install record)",
+            path.display()
+        ),
+    }
+
+    Ok(())
+}
+
+/// Suggest installed families that pair well with `family`.
+///
+/// Fails with [`FontError::FontNotFound`] when no installed face belongs to
+/// `family`.
+/// `fontlift scan-usage`: the fonts documents under `dir` reference,
+/// checked against the installed fonts.
+pub async fn handle_scan_usage_command(
+    manager: Arc<dyn FontManager>,
+    dir: PathBuf,
+    missing_only: bool,
+    json: bool,
+) -> Result<(), FontError> {
+    let mut report = usage::scan(&dir)?;
+    report.cross_reference(&manager.list_installed_fonts()?);
+    if missing_only {
+        report.references.retain(|reference| !reference.installed);
+    }
+
+    if json {
+        let json = to_string_pretty(&report).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize font usage: {}", e))
+        })?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    for reference in &report.references {
+        let mark = if reference.installed { "✅" } else { "❌" };
+        let documents = reference
+            .documents
+            .iter()
+            .map(|path| {
+                path.strip_prefix(&dir)
+                    .ok()
+                    .filter(|relative| !relative.as_os_str().is_empty())
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        println!(
+            "{mark} {} ({})",
+            reference.requirement.label(),
+            documents.join(", ")
+        );
+    }
+    for skipped in &report.skipped {
+        println!("⚠️  Skipped {}: {}", skipped.path.display(), skipped.reason);
+    }
+    println!(
+        "{} font(s) referenced by {} document(s), {} not installed",
+        report.references.len(),
+        report.documents,
+        report.missing().len()
+    );
+    Ok(())
+}
+
+/// Write a specimen PDF of `font` — a file, an installed family or an
+/// installed face — to `out`.
+pub async fn handle_specimen_command(
+    manager: Arc<dyn FontManager>,
+    font: String,
+    out: PathBuf,
+    options: SpecimenOptions,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let installed = if Path::new(&font).is_file() {
+        Vec::new()
+    } else {
+        manager.list_installed_fonts()?
+    };
+    let faces = specimen::specimen_faces(&installed, &font)?;
+
+    if opts.dry_run {
+        log_status(
+            &opts,
+            &format!(
+                "DRY-RUN: would write a specimen of {} face(s) to {}",
+                faces.len(),
+                out.display()
+            ),
+        );
+        return Ok(());
+    }
+
+    let report = specimen::write_specimen(&faces, &options, &out)?;
+    if opts.output.json {
+        let json = to_string_pretty(&report).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize specimen report: {}", e))
+        })?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    for face in &report.faces {
+        log_verbose(&opts, &format!("   {face}"));
+    }
+    if report.characters_omitted > 0 {
+        log_status(
+            &opts,
+            &format!(
+                "⚠️  Character set cut off after {} characters ({} more)",
+                report.characters, report.characters_omitted
+            ),
+        );
+    }
+    log_status(
+        &opts,
+        &format!(
+            "📄 Wrote {} ({} page(s), {} face(s))",
+            report.output.display(),
+            report.pages,
+            report.faces.len()
+        ),
+    );
+    Ok(())
+}
+
+pub async fn handle_pair_command(
+    manager: Arc<dyn FontManager>,
+    family: String,
+    limit: usize,
+    json: bool,
+) -> Result<(), FontError> {
+    let profiles = pairing::profile_families(&manager.list_installed_fonts()?);
+    let target = pairing::find_family(&profiles, &family)
+        .ok_or_else(|| FontError::FontNotFound(PathBuf::from(&family)))?;
+    let suggestions = pairing::suggest_pairings(target, &profiles, limit);
+
+    if json {
+        let json = to_string_pretty(&serde_json::json!({
+            "family": target,
+            "suggestions": suggestions,
+        }))
+        .map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize pairing suggestions: {}", e))
+        })?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    let x_height = target
+        .x_height
+        .map(|x| format!(", x-height {x:.2} em"))
+        .unwrap_or_default();
+    println!("{} ({}{})", target.family, target.classification, x_height);
+
+    if suggestions.is_empty() {
+        println!("No installed family complements {}", target.family);
+        return Ok(());
+    }
+    for (rank, suggestion) in suggestions.iter().enumerate() {
+        println!(
+            "{:>3}. {} ({}, score {})",
+            rank + 1,
+            suggestion.family,
+            suggestion.classification,
+            suggestion.score
+        );
+        for reason in &suggestion.rationale {
+            println!("       - {}", reason);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print a name snippet for the installed face `name` and, when stdout is a
+/// terminal and `copy` is set, put it on the clipboard.
+///
+/// With `--json` every snippet is printed and nothing is copied.
+pub async fn handle_copy_name_command(
+    manager: Arc<dyn FontManager>,
+    name: String,
+    kind: SnippetKind,
+    copy: bool,
+    json: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let installed = manager.list_installed_fonts()?;
+    let face = snippets::resolve_face(&installed, &name)?;
+    let names = snippets::name_snippets(face);
+
+    if json {
+        let json = to_string_pretty(&serde_json::json!({
+            "path": face.source.path,
+            "snippets": names,
+        }))
+        .map_err(|e| FontError::InvalidFormat(format!("Failed to serialize snippets: {}", e)))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    let snippet = names.get(kind);
+    println!("{}", snippet);
+
+    if copy && std::io::stdout().is_terminal() {
+        match snippets::copy_to_clipboard(snippet) {
+            Ok(()) => log_status(&opts, "📋 Copied to clipboard"),
+            Err(e) => log_status(
+                &opts,
+                &format!("⚠️  Could not copy to the clipboard: {}", e),
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// Compare installed fonts with a foundry catalog and print the findings.
+///
+/// Returns [`FontError::VerificationFailed`] when any catalog entry is not
+/// verified, after the full report has been printed.
+pub async fn handle_verify_command(
+    manager: Arc<dyn FontManager>,
+    catalog_path: PathBuf,
+    json: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let catalog = catalog::load_catalog(&catalog_path)?;
+    log_verbose(
+        &opts,
+        &format!(
+            "Verifying {} catalog entries from {}",
+            catalog.fonts.len(),
+            catalog_path.display()
+        ),
+    );
+
+    let installed = manager.list_installed_fonts()?;
+    let report = catalog::verify_against_catalog(&catalog, &installed);
+
+    if json {
+        let json = to_string_pretty(&report).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize compliance report: {}", e))
+        })?;
+        println!("{}", json);
+    } else {
+        for finding in &report.findings {
+            let location = finding
+                .path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let detail = match finding.status {
+                ComplianceStatus::Verified => "verified".to_string(),
+                ComplianceStatus::Outdated | ComplianceStatus::VersionMismatch => format!(
+                    "{} (installed {}, licensed {})",
+                    if finding.status == ComplianceStatus::Outdated {
+                        "outdated"
+                    } else {
+                        "version mismatch"
+                    },
+                    finding.installed_version.as_deref().unwrap_or("?"),
+                    finding.expected_version.as_deref().unwrap_or("?")
+                ),
+                ComplianceStatus::Tampered => "tampered (SHA-256 differs)".to_string(),
+                ComplianceStatus::NotInstalled => "not installed".to_string(),
+            };
+            let marker = if finding.status.is_compliant() {
+                "✅"
+            } else {
+                "⚠️ "
+            };
+            log_status(
+                &opts,
+                &format!(
+                    "{} {}  {}  {}",
+                    marker, finding.postscript_name, detail, location
+                ),
+            );
+        }
+    }
+
+    let violations = report.violations().len();
+    if violations > 0 {
+        return Err(FontError::VerificationFailed(format!(
+            "{} of {} catalog finding(s) are out of compliance",
+            violations,
+            report.findings.len()
+        )));
+    }
+
+    if !json {
+        log_status(&opts, "✅ All catalog fonts verified");
+    }
+    Ok(())
+}
+
+/// Check font files for corruption and for changes since they were
+/// recorded (`fontlift verify --all` / `fontlift verify PATH...`).
+///
+/// `paths` of `None` checks every installed font file. Each file is compared
+/// with the digest in `manifest` (when given) or the install database, and
+/// with the faces in the manifest and the metadata cache.
+pub async fn handle_verify_integrity_command(
+    manager: Arc<dyn FontManager>,
+    paths: Option<Vec<PathBuf>>,
+    manifest_path: Option<PathBuf>,
+    json: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let explicit = paths.is_some();
+    let files = match paths {
+        Some(paths) => paths,
+        None => manager
+            .list_installed_fonts()?
+            .into_iter()
+            .map(|font| font.source.path)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+    };
+    let manifest = manifest_path
+        .as_deref()
+        .map(manifest::load_manifest)
+        .transpose()?;
+    let db = install_db::load_install_db()?;
+    let cache = MetadataCache::open();
+    log_verbose(&opts, &format!("Verifying {} font file(s)", files.len()));
+
+    let mut report = IntegrityReport::default();
+    for path in files {
+        let mut baseline = Baseline::default();
+        let recorded = manifest
+            .iter()
+            .flat_map(|manifest| &manifest.fonts)
+            .find(|font| font.path == path);
+        if let Some(font) = recorded {
+            if let Some(sha256) = &font.sha256 {
+                baseline = baseline.with_digest(sha256.clone(), font.size, "manifest");
+            }
+            baseline = baseline.with_postscript_names(
+                font.faces.iter().map(|face| face.postscript_name.clone()),
+                "manifest",
+            );
+        }
+        if let Some(sha256) = db.find(&path).and_then(|record| record.sha256.clone()) {
+            baseline = baseline.with_digest(sha256, None, "install database");
+        }
+        if let Some(faces) = cache.recorded_faces(&path) {
+            baseline = baseline.with_postscript_names(
+                faces.iter().map(|face| face.postscript_name.clone()),
+                "metadata cache",
+            );
+        }
+
+        let check = if explicit {
+            integrity::verify_path(&path, &baseline)?
+        } else {
+            integrity::verify_file(&path, &baseline)
+        };
+        report.checks.push(check);
+    }
+
+    if json {
+        let json = to_string_pretty(&report).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize integrity report: {}", e))
+        })?;
+        println!("{}", json);
+    } else {
+        for check in &report.checks {
+            let marker = if check.status.is_problem() {
+                "⚠️ "
+            } else {
+                "✅"
+            };
+            log_status(
+                &opts,
+                &format!(
+                    "{} {}  {}",
+                    marker,
+                    check.status.as_str(),
+                    check.path.display()
+                ),
+            );
+            for problem in &check.problems {
+                log_status(&opts, &format!("   {problem}"));
+            }
+        }
+    }
+
+    let problems = report.problems().len();
+    if problems > 0 {
+        return Err(FontError::VerificationFailed(format!(
+            "{} of {} font file(s) are modified, corrupt or missing",
+            problems,
+            report.checks.len()
+        )));
+    }
+
+    if !json {
+        let unrecorded = report.count(IntegrityStatus::Unrecorded);
+        let mut summary = format!(
+            "✅ {} font file(s) verified",
+            report.count(IntegrityStatus::Verified)
+        );
+        if unrecorded > 0 {
+            summary.push_str(&format!(
+                "; {unrecorded} well-formed but with nothing recorded to compare"
+            ));
+        }
+        log_status(&opts, &summary);
+    }
+    Ok(())
+}
+
+/// Tell the user which running applications to restart after a font change.
+///
+/// Advisories are a convenience: failing to list processes or to show the
+/// notification is only logged.
+pub fn report_restart_advisories(notify: bool, opts: OperationOptions) {
+    let advisories = match restart_advisory::restart_advisories() {
+        Ok(advisories) => advisories,
+        Err(e) => {
+            log_verbose(
+                &opts,
+                &format!("Could not check for apps to restart: {}", e),
+            );
+            return;
+        }
+    };
+    if advisories.is_empty() {
+        return;
+    }
+
+    let apps: Vec<&str> = advisories.iter().map(|a| a.app.as_str()).collect();
+    log_status(
+        &opts,
+        &format!(
+            "ℹ️  Restart these apps to see the changes: {}",
+            apps.join(", ")
+        ),
+    );
+    if notify {
+        if let Err(e) = restart_advisory::notify_desktop(
+            "Fonts changed",
+            &format!("Restart to see the changes: {}", apps.join(", ")),
+        ) {
+            log_verbose(&opts, &format!("Could not show notification: {}", e));
+        }
+    }
+}
+
+/// Report cache clears scheduled with `cleanup --at-reboot` that the last
+/// restart carried out, or failed to.
+///
+/// Like restart advisories this never fails the command it runs before;
+/// problems reading the journal are only logged.
+pub fn report_scheduled_cache_clears(opts: OperationOptions) {
+    let finished = match journal::take_finished_cache_clears() {
+        Ok(finished) => finished,
+        Err(e) => {
+            log_verbose(
+                &opts,
+                &format!("Could not check scheduled cache clears: {}", e),
+            );
+            return;
+        }
+    };
+
+    for (clear, status) in finished {
+        match status {
+            ScheduledClearStatus::Completed => log_status(
+                &opts,
+                &format!(
+                    "✅ Font caches scheduled for deletion at restart were cleared ({})",
+                    clear.scope.description()
+                ),
+            ),
+            ScheduledClearStatus::Incomplete { remaining } => {
+                log_status(
+                    &opts,
+                    &format!(
+                        "⚠️  Scheduled cache clear did not finish: {} of {} file(s) remain ({})",
+                        remaining.len(),
+                        clear.files.len(),
+                        clear.scope.description()
+                    ),
+                );
+                for path in remaining {
+                    log_verbose(&opts, &format!("Still present: {}", path.display()));
+                }
+            }
+            ScheduledClearStatus::Pending => {}
+        }
+    }
+}
+
+/// Write the installed-font inventory to `output` (`-` for stdout).
+pub async fn handle_export_command(
+    manager: Arc<dyn FontManager>,
+    output: PathBuf,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let installed = manager.list_installed_fonts()?;
+    let db = install_db::load_install_db().unwrap_or_else(|e| {
+        log_verbose(
+            &opts,
+            &format!(
+                "⚠️  Install database unavailable, exporting without reasons: {}",
+                e
+            ),
+        );
+        install_db::InstallDb::new()
+    });
+    let manifest = Manifest::from_installed(&installed, &db);
+
+    if output.as_os_str() == "-" {
+        println!("{}", manifest.to_json()?);
+        return Ok(());
+    }
+
+    if opts.dry_run {
+        log_status(
+            &opts,
+            &format!(
+                "DRY-RUN: would export {} font file(s) to {}",
+                manifest.fonts.len(),
+                output.display()
+            ),
+        );
+        return Ok(());
+    }
+
+    manifest.save(&output)?;
+    log_status(
+        &opts,
+        &format!(
+            "✅ Exported {} font file(s) to {}",
+            manifest.fonts.len(),
+            output.display()
+        ),
+    );
+    Ok(())
+}
+
+/// Reinstall the fonts listed in a manifest.
+///
+/// Every entry is installed into its recorded scope with its recorded reason;
+/// `search_roots` are searched for entries that left their recorded path.
+/// Entries that cannot be found or installed are reported together at the
+/// end, after everything else has been installed.
+pub async fn handle_manifest_install_command(
+    manager: Arc<dyn FontManager>,
+    manifest_path: PathBuf,
+    search_roots: Vec<PathBuf>,
+    install: InstallOptions,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let manifest = manifest::load_manifest(&manifest_path)?;
+    let resolution = manifest.resolve(&search_roots);
+    log_verbose(
+        &opts,
+        &format!(
+            "Manifest {} lists {} font file(s); {} found locally",
+            manifest_path.display(),
+            manifest.fonts.len(),
+            resolution.found.len()
+        ),
+    );
+
+    let mut failures = Vec::new();
+    for font in &resolution.missing {
+        log_status(
+            &opts,
+            &format!(
+                "⚠️  {} not found (expected {})",
+                font.label(),
+                font.path.display()
+            ),
+        );
+        failures.push(font.label());
+    }
+
+    for (font, source) in resolution.found {
+        let entry_install = InstallOptions {
+            admin: font.scope == FontScope::System,
+            reason: font.reason.clone().unwrap_or(InstallReason::Explicit),
+            origin: font.origin.clone(),
+            ..install.clone()
+        };
+        if let Err(e) =
+            handle_install_command(manager.clone(), vec![source], entry_install, opts).await
+        {
+            log_status(&opts, &format!("⚠️  {}: {}", font.label(), e));
+            failures.push(font.label());
+        }
+    }
+
+    if !failures.is_empty() {
+        return Err(FontError::VerificationFailed(format!(
+            "{} of {} manifest font(s) were not installed: {}",
+            failures.len(),
+            manifest.fonts.len(),
+            failures.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Remote settings for `fontlift deploy`.
+#[derive(Debug, Clone, Default)]
+pub struct DeployOptions {
+    pub admin: bool,
+    pub sudo: bool,
+    pub remote_fontlift: String,
+    pub ssh_options: Vec<String>,
+}
+
+/// Copy fonts, or the fonts a manifest lists, to each host over SSH and
+/// install them with the host's fontlift.
+///
+/// Every host is attempted; returns [`FontError::VerificationFailed`] at the
+/// end when any of them failed.
+pub fn handle_deploy_command(
+    hosts: Vec<String>,
+    fonts: Vec<PathBuf>,
+    manifest_path: Option<PathBuf>,
+    options: DeployOptions,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let files: Vec<PathBuf> = match &manifest_path {
+        Some(path) => {
+            let resolution = manifest::load_manifest(path)?.resolve(&fonts);
+            if !resolution.missing.is_empty() {
+                let labels: Vec<String> = resolution.missing.iter().map(|f| f.label()).collect();
+                return Err(FontError::VerificationFailed(format!(
+                    "{} manifest font(s) not found locally: {}",
+                    labels.len(),
+                    labels.join(", ")
+                )));
+            }
+            let unique: BTreeSet<PathBuf> =
+                resolution.found.into_iter().map(|(_, path)| path).collect();
+            unique.into_iter().collect()
+        }
+        None => collect_font_inputs(&fonts, &ScanOptions::default())?,
+    };
+    let plan = deploy::DeployPlan::new(
+        files,
+        manifest_path,
+        options.admin,
+        options.sudo,
+        options.remote_fontlift,
+    )?;
+
+    if opts.dry_run {
+        for host in &hosts {
+            log_status(
+                &opts,
+                &format!(
+                    "DRY-RUN: would copy {} font file(s) to {} and run: {}",
+                    plan.files.len(),
+                    host,
+                    plan.install_command("<staging>")
+                ),
+            );
+        }
+        return Ok(());
+    }
+
+    let transport = deploy::SshTransport {
+        options: options.ssh_options,
+    };
+    let mut results = Vec::new();
+    for host in &hosts {
+        log_verbose(
+            &opts,
+            &format!("Deploying {} font file(s) to {}", plan.files.len(), host),
+        );
+        let result = deploy::deploy_to_host(&transport, host, &plan);
+        if !opts.output.json {
+            match &result.error {
+                None => log_status(
+                    &opts,
+                    &format!("✅ {}: deployed {} font file(s)", host, result.copied),
+                ),
+                Some(error) => log_status(&opts, &format!("❌ {}: {}", host, error)),
+            }
+            if !result.output.is_empty() {
+                log_verbose(&opts, &result.output);
+            }
+        }
+        results.push(result);
+    }
+
+    if opts.output.json {
+        println!(
+            "{}",
+            to_string_pretty(&results).map_err(|e| {
+                FontError::InvalidFormat(format!("Failed to serialize deploy results: {}", e))
+            })?
+        );
+    }
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|r| !r.success)
+        .map(|r| r.host.as_str())
+        .collect();
+    if !failed.is_empty() {
+        return Err(FontError::VerificationFailed(format!(
+            "Deployment failed on {} of {} host(s): {}",
+            failed.len(),
+            results.len(),
+            failed.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Install what a document's font requirements need.
+///
+/// Requirements already met by installed faces are left alone. The rest are
+/// installed from bundled or local files under `search_roots`, then from
+/// Google Fonts when a family is still missing. The report is printed before
+/// anything is installed; unmet requirements make the command fail, also
+/// under `--dry-run`, so it doubles as a check.
+pub async fn handle_requirements_install_command(
+    manager: Arc<dyn FontManager>,
+    requirements_path: PathBuf,
+    search_roots: Vec<PathBuf>,
+    install: InstallOptions,
+    json: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let requirements = requirements::load_requirements(&requirements_path)?;
+    let installed = manager.list_installed_fonts()?;
+    let local = requirements::local_faces(&search_roots);
+    let mut google = None;
+    let mut report = requirements::resolve(&requirements, &installed, &local, None);
+    if report.wants_provider() {
+        let api_key = std::env::var("GOOGLE_FONTS_API_KEY").ok();
+        match google_fonts::fetch_families(api_key.as_deref(), None) {
+            Ok(families) => {
+                report = requirements::resolve(&requirements, &installed, &local, Some(&families));
+                google = Some(families);
+            }
+            Err(e) => log_verbose(&opts, &format!("Skipping Google Fonts: {}", e)),
+        }
+    }
+
+    if json {
+        let json = to_string_pretty(&report).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize requirements report: {}", e))
+        })?;
+        println!("{}", json);
+    } else {
+        print_requirements_report(&report, &opts);
+    }
+
+    if !opts.dry_run {
+        let local_files = report.local_files();
+        if !local_files.is_empty() {
+            handle_install_command(manager.clone(), local_files, install.clone(), opts).await?;
+        }
+        for (family, variants) in report.provider_downloads() {
+            let Some(found) = google
+                .as_deref()
+                .and_then(|families| google_fonts::find_family(families, &family))
+            else {
+                continue;
+            };
+            // Keep the downloads alive until the install below has copied them.
+            let downloads = google_fonts::download_family(
+                found,
+                &variants,
+                fetch::DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+            )?;
+            let paths = downloads.iter().map(|d| d.path.clone()).collect();
+            let provider_install = install
+                .clone()
+                .with_origin(Some(format!("google-fonts:{}", found.family)));
+            handle_install_command(manager.clone(), paths, provider_install, opts).await?;
+        }
+    }
+
+    let unmet = report.unmet();
+    if !unmet.is_empty() {
+        let labels: Vec<String> = unmet.iter().map(|r| r.requirement.label()).collect();
+        return Err(FontError::VerificationFailed(format!(
+            "{} of {} font requirement(s) of {} are unmet: {}",
+            unmet.len(),
+            report.resolved.len(),
+            requirements_path.display(),
+            labels.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+fn print_requirements_report(report: &RequirementsReport, opts: &OperationOptions) {
+    log_status(
+        opts,
+        &format!(
+            "{} font requirement(s) in {}",
+            report.resolved.len(),
+            report.source.display()
+        ),
+    );
+    for resolved in &report.resolved {
+        let label = resolved.requirement.label();
+        let line = match &resolved.resolution {
+            requirements::Resolution::Installed { path } => {
+                format!("  ✅ {label}: installed ({})", path.display())
+            }
+            requirements::Resolution::Local { path } => {
+                format!("  📦 {label}: install from {}", path.display())
+            }
+            requirements::Resolution::Provider {
+                provider,
+                family,
+                variant,
+            } => format!("  ⬇️  {label}: download {family} {variant} from {provider}"),
+            requirements::Resolution::Unmet { reason } => format!("  ❌ {label}: {reason}"),
+        };
+        log_status(opts, &line);
+    }
+}
+
+/// Import another font manager's library.
+///
+/// Active fonts (and those whose state the library does not record) are
+/// installed one by one with origin `migrate:<manager>`; deactivated ones
+/// are left alone. Install failures join the unmapped entries in the report
+/// and make the command fail once everything else is done.
+pub async fn handle_migrate_command(
+    manager: Arc<dyn FontManager>,
+    source: MigrationSource,
+    library: PathBuf,
+    store: bool,
+    install: InstallOptions,
+    json: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let plan = migrate::plan_migration(source, &library)?;
+    log_verbose(
+        &opts,
+        &format!(
+            "{} library {} holds {} font file(s)",
+            source.display_name(),
+            library.display(),
+            plan.fonts.len()
+        ),
+    );
+
+    let mut report = MigrationReport::new(&plan);
+    let content_store = store.then(ContentStore::open_default);
+    let mut failures = 0;
+    for font in &plan.fonts {
+        if let Some(content_store) = &content_store {
+            if !opts.dry_run {
+                let bytes = fs::read(&font.path).map_err(FontError::IoError)?;
+                content_store.put(&bytes)?;
+            }
+            report.stored.push(font.path.clone());
+        }
+        if !font.should_activate() {
+            report.inactive.push(font.path.clone());
+            continue;
+        }
+        if opts.dry_run {
+            report.installed.push(font.path.clone());
+            continue;
+        }
+        let font_install = install.clone().with_origin(Some(source.origin()));
+        // The report is the output; per-font install chatter is verbose-only.
+        let font_opts = OperationOptions {
+            output: OutputOptions {
+                quiet: opts.output.quiet || json || !opts.output.verbose,
+                ..opts.output
+            },
+            ..opts
+        };
+        match handle_install_command(
+            manager.clone(),
+            vec![font.path.clone()],
+            font_install,
+            font_opts,
+        )
+        .await
+        {
+            Ok(()) => report.installed.push(font.path.clone()),
+            Err(e) => {
+                failures += 1;
+                report.unmapped.push(migrate::Unmapped {
+                    path: font.path.clone(),
+                    reason: format!("install failed: {}", e.message()),
+                });
+            }
+        }
+    }
+
+    if json {
+        let json = to_string_pretty(&report).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize migration report: {}", e))
+        })?;
+        println!("{}", json);
+    } else {
+        print_migration_report(&report, &opts);
+    }
+
+    if failures > 0 {
+        return Err(FontError::VerificationFailed(format!(
+            "{} of {} font(s) from {} could not be installed",
+            failures,
+            plan.fonts.len(),
+            library.display()
+        )));
+    }
+    Ok(())
+}
+
+fn print_migration_report(report: &MigrationReport, opts: &OperationOptions) {
+    let verb = if opts.dry_run {
+        "Would install"
+    } else {
+        "Installed"
+    };
+    log_status(
+        opts,
+        &format!(
+            "{} {} font(s) from {} library {}",
+            verb,
+            report.installed.len(),
+            report.source.display_name(),
+            report.library.display()
+        ),
+    );
+    for path in &report.installed {
+        log_verbose(opts, &format!("  ✅ {}", path.display()));
+    }
+    if !report.inactive.is_empty() {
+        log_status(
+            opts,
+            &format!(
+                "Left {} deactivated font(s) uninstalled:",
+                report.inactive.len()
+            ),
+        );
+        for path in &report.inactive {
+            log_status(opts, &format!("  ⏸️  {}", path.display()));
+        }
+    }
+    if !report.stored.is_empty() {
+        log_status(
+            opts,
+            &format!("Kept {} font(s) in the download store", report.stored.len()),
+        );
+    }
+    if !report.unmapped.is_empty() {
+        log_status(
+            opts,
+            &format!("Could not migrate {} item(s):", report.unmapped.len()),
+        );
+        for item in &report.unmapped {
+            log_status(
+                opts,
+                &format!("  ⚠️  {}: {}", item.path.display(), item.reason),
+            );
+        }
+    }
+}
+
+/// Run a batch script, print the plan it queued, then carry the plan out.
+///
+/// The plan is applied in script order and stops at the first failing action.
+/// Under `--dry-run` each action goes through the regular dry-run paths, so
+/// the output matches what `install`/`uninstall` would print.
+pub async fn handle_script_command(
+    manager: Arc<dyn FontManager>,
+    command: ScriptCommand,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let ScriptCommand::Run { file, max_actions } = command;
+
+    let source = fs::read_to_string(&file).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(file.clone()),
+        _ => FontError::IoError(e),
+    })?;
+
+    let installed = manager.list_installed_fonts()?;
+    let actions = script::plan_script(&source, installed, opts)?;
+
+    if actions.is_empty() {
+        log_status(&opts, "Script queued no actions");
+        return Ok(());
+    }
+    if actions.len() > max_actions {
+        return Err(FontError::ScriptError(format!(
+            "{} queued {} actions, more than --max-actions {}",
+            file.display(),
+            actions.len(),
+            max_actions
+        )));
+    }
+
+    log_status(&opts, &format!("Plan ({} actions):", actions.len()));
+    for action in &actions {
+        log_status(&opts, &format!("  {}", action.describe()));
+    }
+
+    for action in actions {
+        match action {
+            ScriptAction::Install { path, scope } => {
+                let install = InstallOptions::new(
+                    scope == FontScope::System,
+                    true,
+                    ValidationStrictness::Normal,
+                    false,
+                );
+                handle_install_command(manager.clone(), vec![path], install, opts).await?;
+            }
+            ScriptAction::Uninstall { name } => {
+                handle_uninstall_command(manager.clone(), Some(name), Vec::new(), false, opts)
+                    .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `fontlift google search` / `fontlift google install`.
+pub async fn handle_google_command(
+    manager: Arc<dyn FontManager>,
+    command: GoogleCommand,
+    json: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let env_key = || std::env::var("GOOGLE_FONTS_API_KEY").ok();
+
+    match command {
+        GoogleCommand::Search { query, api_key } => {
+            let api_key = api_key.or_else(env_key);
+            let families = google_fonts::fetch_families(api_key.as_deref(), None)?;
+            let matches = google_fonts::search_families(&families, &query);
+
+            if json {
+                let json = to_string_pretty(&matches).map_err(|e| {
+                    FontError::InvalidFormat(format!("Failed to serialize search results: {}", e))
+                })?;
+                println!("{}", json);
+                return Ok(());
+            }
+
+            if matches.is_empty() {
+                log_status(
+                    &opts,
+                    &format!("No Google Fonts families match '{}'", query),
+                );
+            }
+            for family in matches {
+                println!(
+                    "{} ({}; {})",
+                    family.family,
+                    family.category.as_deref().unwrap_or("uncategorized"),
+                    family.variants.join(", ")
+                );
+            }
+            Ok(())
+        }
+        GoogleCommand::Install {
+            family,
+            variants,
+            admin,
+            no_validate,
+            api_key,
+            max_downloads,
+        } => {
+            let api_key = api_key.or_else(env_key);
+            let families = google_fonts::fetch_families(api_key.as_deref(), Some(&family))?;
+            let found = google_fonts::find_family(&families, &family).ok_or_else(|| {
+                FontError::InvalidArgument(format!(
+                    "Google Fonts has no family named '{}' (try `fontlift google search`)",
+                    family
+                ))
+            })?;
+            let files = found.variant_files(&variants)?;
+
+            if opts.dry_run {
+                for (variant, url) in &files {
+                    log_status(
+                        &opts,
+                        &format!(
+                            "DRY-RUN: would download {} {} from {}",
+                            found.family, variant, url
+                        ),
+                    );
+                }
+                return Ok(());
+            }
+
+            log_status(
+                &opts,
+                &format!("Downloading {} variant(s) of {}", files.len(), found.family),
+            );
+            // Keep the downloads alive until the install below has copied them.
+            let downloads = google_fonts::download_family(found, &variants, max_downloads)?;
+            let paths = downloads.iter().map(|d| d.path.clone()).collect();
+
+            let install =
+                InstallOptions::new(admin, !no_validate, ValidationStrictness::Normal, false)
+                    .with_origin(Some(format!("google-fonts:{}", found.family)));
+            handle_install_command(manager, paths, install, opts).await
+        }
+    }
+}
+
+/// Selection and baseline settings for `fontlift audit`.
+#[derive(Debug, Clone, Default)]
+pub struct AuditOptions {
+    pub only: Vec<String>,
+    pub skip: Vec<String>,
+    pub baseline: Option<PathBuf>,
+    pub write_baseline: Option<PathBuf>,
+    pub list_rules: bool,
+}
+
+/// Run the audit rule engine over installed fonts and print the findings.
+///
+/// Returns [`FontError::VerificationFailed`] when an error-severity finding
+/// is not covered by the baseline.
+pub async fn handle_audit_command(
+    manager: Arc<dyn FontManager>,
+    audit: AuditOptions,
+    json: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let engine = AuditEngine::with_default_rules().select(&audit.only, &audit.skip)?;
+
+    if audit.list_rules {
+        for rule in engine.rules() {
+            println!(
+                "{:<28} {:<8} {}",
+                rule.id(),
+                rule.severity().label(),
+                rule.description()
+            );
+        }
+        return Ok(());
+    }
+
+    let snapshot = AuditSnapshot::new(manager.list_installed_fonts()?);
+    let mut report = engine.run(&snapshot);
+
+    if let Some(path) = &audit.write_baseline {
+        if opts.dry_run {
+            log_status(
+                &opts,
+                &format!("DRY-RUN: would write audit baseline to {}", path.display()),
+            );
+        } else {
+            AuditBaseline::from_report(&report).save(path)?;
+            log_verbose(
+                &opts,
+                &format!("Wrote audit baseline to {}", path.display()),
+            );
+        }
+    }
+    if let Some(path) = &audit.baseline {
+        report.apply_baseline(&AuditBaseline::load(path)?);
+    }
+
+    if json {
+        let json = to_string_pretty(&report).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize audit report: {}", e))
+        })?;
+        println!("{}", json);
+    } else {
+        for finding in &report.findings {
+            log_status(
+                &opts,
+                &format!(
+                    "{:<7} {}  {}",
+                    finding.severity.label(),
+                    finding.id,
+                    finding.message
+                ),
+            );
+        }
+        log_status(
+            &opts,
+            &format!(
+                "{} finding(s) from {} rule(s), {} suppressed by baseline",
+                report.findings.len(),
+                report.rules.len(),
+                report.suppressed
+            ),
+        );
+    }
+
+    if report.has_severity(Severity::Error) {
+        return Err(FontError::VerificationFailed(
+            "audit found error-severity problems".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Replace inputs that name a font by its original file name with the
+/// shortened copy fontlift installed (see [`file_names`]).
+///
+/// Inputs without such a record, or when the install database cannot be
+/// read, are returned unchanged.
+fn resolve_shortened_inputs(inputs: Vec<PathBuf>, opts: &OperationOptions) -> Vec<PathBuf> {
+    let Ok(db) = install_db::load_install_db() else {
+        return inputs;
+    };
+    inputs
+        .into_iter()
+        .map(|input| {
+            let record = input
+                .file_name()
+                .and_then(|name| db.find_by_original_name(&name.to_string_lossy()));
+            match record {
+                Some(record) if record.path != input && record.path.exists() => {
+                    log_verbose(
+                        opts,
+                        &format!(
+                            "{} was installed as {}",
+                            input.display(),
+                            record.path.display()
+                        ),
+                    );
+                    record.path.clone()
+                }
+                _ => input,
+            }
+        })
+        .collect()
+}
+
+pub async fn handle_uninstall_command(
+    manager: Arc<dyn FontManager>,
+    name: Option<String>,
+    font_inputs: Vec<PathBuf>,
+    admin: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let mut history = HistoryEntry::start(HistoryOperation::Uninstall, scope_for(admin));
+    let result = uninstall_fonts(
+        manager.clone(),
+        name,
+        font_inputs,
+        admin,
+        opts,
+        &mut history,
+    )
+    .await;
+    announce_change(manager.as_ref(), &history, &opts);
+    record_history(history, &result, &opts);
+    result
+}
+
+async fn uninstall_fonts(
+    manager: Arc<dyn FontManager>,
+    name: Option<String>,
+    font_inputs: Vec<PathBuf>,
+    admin: bool,
+    opts: OperationOptions,
+    history: &mut HistoryEntry,
+) -> Result<(), FontError> {
+    let default_scope = if admin {
+        FontScope::System
+    } else {
+        FontScope::User
+    };
+    let managed = check_managed_scope(default_scope, "uninstall")?;
+    let policy = ProtectionPolicy::load_default()?;
+
+    if let Some(font_name) = name {
+        log_status(&opts, &format!("Uninstalling font by name: {}", font_name));
+
+        // Find font by name in installed fonts
+        let installed_fonts = manager.list_installed_fonts()?;
+        if let Some(font) = installed_fonts
+            .iter()
+            .find(|f| f.postscript_name == font_name || f.full_name == font_name)
+        {
+            let starting_scope = font.source.scope.unwrap_or(default_scope);
+            check_protection_policy(
+                &policy,
+                &font.source.path,
+                &[font.postscript_name.as_str()],
+                &opts,
+            )?;
+
+            if opts.dry_run {
+                log_status(
+                    &opts,
+                    &format!(
+                        "DRY-RUN: would uninstall '{}' at {} (checking {})",
+                        font_name,
+                        font.source.path.display(),
+                        describe_scope_chain(starting_scope)
+                    ),
+                );
+            } else {
+                match operations::unregister(
+                    manager.as_ref(),
+                    &managed,
+                    &font.source.path,
+                    &scope_order(starting_scope),
+                ) {
+                    Ok(used_scope) => {
+                        forget_install_record(&font.source.path, &opts);
+                        history.handled(&font.source.path);
+                        log_status(
+                            &opts,
+                            &format!(
+                                "✅ Successfully uninstalled font '{}' ({})",
+                                font_name,
+                                used_scope.description()
+                            ),
+                        );
+                    }
+                    Err(e) => {
+                        log_status(
+                            &opts,
+                            &format!("⚠️  Could not unregister font '{}': {}", font_name, e),
+                        );
+                        history.failed(&font.source.path, e.message());
+                    }
+                }
+            }
+        } else {
+            log_status(
+                &opts,
+                &format!(
+                    "⚠️  Font '{}' is not installed, nothing to uninstall",
+                    font_name
+                ),
+            );
+            return Ok(());
+        }
+    } else {
+        let font_inputs = resolve_shortened_inputs(font_inputs, &opts);
+        let targets = collect_font_inputs(&font_inputs, &ScanOptions::default())?;
+        let observer = CliProgress::new(&opts);
+        let mut tracker = Tracker::start(&observer, Operation::Uninstall, targets.len());
+        for path in targets {
+            check_protection_policy(&policy, &path, &[], &opts)?;
+            if opts.dry_run {
+                log_status(
+                    &opts,
+                    &format!(
+                        "DRY-RUN: would uninstall font at {} (checking {})",
+                        path.display(),
+                        describe_scope_chain(default_scope)
+                    ),
+                );
+                continue;
+            }
+
+            log_status(
+                &opts,
+                &format!("Uninstalling font from path: {}", path.display()),
+            );
+
+            let result = operations::unregister(
+                manager.as_ref(),
+                &managed,
+                &path,
+                &scope_order(default_scope),
+            );
+            match &result {
+                Ok(used_scope) => {
+                    forget_install_record(&path, &opts);
+                    history.handled(&path);
+                    log_status(
+                        &opts,
+                        &format!(
+                            "✅ Successfully uninstalled font ({})",
+                            used_scope.description()
+                        ),
+                    );
+                }
+                Err(e) => {
+                    log_status(
+                        &opts,
+                        &format!(
+                            "⚠️  Font at {} may not be registered: {}",
+                            path.display(),
+                            e
+                        ),
+                    );
+                    history.failed(&path, e.message());
+                }
+            }
+            tracker.item(Some(&path), result.is_ok());
+        }
+    }
+
+    Ok(())
+}
+
+/// `fontlift uninstall --family/--match`: list the installed fonts in the
+/// scope that match, ask unless `yes`, then uninstall them as one journaled
+/// batch.
+pub async fn handle_uninstall_matching_command(
+    manager: Arc<dyn FontManager>,
+    matcher: FaceMatcher,
+    admin: bool,
+    yes: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let mut history = HistoryEntry::start(HistoryOperation::Uninstall, scope_for(admin));
+    let result =
+        uninstall_matching_fonts(manager.as_ref(), &matcher, admin, yes, opts, &mut history);
+    announce_change(manager.as_ref(), &history, &opts);
+    record_history(history, &result, &opts);
+    result
+}
+
+fn uninstall_matching_fonts(
+    manager: &dyn FontManager,
+    matcher: &FaceMatcher,
+    admin: bool,
+    yes: bool,
+    opts: OperationOptions,
+    history: &mut HistoryEntry,
+) -> Result<(), FontError> {
+    let scope = scope_for(admin);
+    check_managed_scope(scope, "uninstall")?;
+    let policy = ProtectionPolicy::load_default()?;
+
+    let installed = manager.list_installed_fonts()?;
+    let matched = face_match::matching_fonts(&installed, matcher, scope);
+    if matched.is_empty() {
+        log_status(
+            &opts,
+            &format!(
+                "⚠️  No {} fonts match {}, nothing to uninstall",
+                scope.description(),
+                matcher.describe()
+            ),
+        );
+        return Ok(());
+    }
+    for font in &matched {
+        let faces: Vec<&str> = font.faces.iter().map(String::as_str).collect();
+        check_protection_policy(&policy, &font.path, &faces, &opts)?;
+    }
+
+    let lead = if opts.dry_run {
+        "DRY-RUN: would uninstall"
+    } else {
+        "Fonts to uninstall:"
+    };
+    log_status(
+        &opts,
+        &format!(
+            "{lead} {} file(s) matching {}",
+            matched.len(),
+            matcher.describe()
+        ),
+    );
+    for font in &matched {
+        log_status(
+            &opts,
+            &format!("  {} ({})", font.path.display(), font.faces.join(", ")),
+        );
+    }
+    if opts.dry_run {
+        return Ok(());
+    }
+    if !yes && !confirm(&format!("Uninstall {} font file(s)?", matched.len()))? {
+        log_status(&opts, "Nothing uninstalled");
+        return Ok(());
+    }
+
+    let observer = CliProgress::new(&opts);
+    let mut tracker = Tracker::start(&observer, Operation::Uninstall, matched.len());
+    let uninstalled = face_match::uninstall_matching(manager, &matched, scope, |font, result| {
+        match result {
+            Ok(()) => {
+                forget_install_record(&font.path, &opts);
+                history.handled(&font.path);
+                log_status(&opts, &format!("✅ Uninstalled {}", font.path.display()));
+            }
+            Err(e) => {
+                log_status(
+                    &opts,
+                    &format!("⚠️  Could not unregister {}: {}", font.path.display(), e),
+                );
+                history.failed(&font.path, e.message());
+            }
+        }
+        tracker.item(Some(&font.path), result.is_ok());
+    })?;
+    log_status(
+        &opts,
+        &format!(
+            "Uninstalled {uninstalled} of {} font file(s)",
+            matched.len()
+        ),
+    );
+    Ok(())
+}
+
+/// Ask `question` on stderr and read a yes/no answer from stdin. Without a
+/// terminal to ask on this fails, pointing at `--yes`.
+fn confirm(question: &str) -> Result<bool, FontError> {
+    if !std::io::stdin().is_terminal() {
+        return Err(FontError::UnsupportedOperation(format!(
+            "{question} Cannot ask without a terminal; pass --yes to go ahead"
+        )));
+    }
+    eprint!("{question} [y/N] ");
+    std::io::stderr().flush().map_err(FontError::IoError)?;
+    let mut answer = String::new();
+    std::io::stdin()
+        .read_line(&mut answer)
+        .map_err(FontError::IoError)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+pub async fn handle_remove_command(
+    manager: Arc<dyn FontManager>,
+    name: Option<String>,
+    font_inputs: Vec<PathBuf>,
+    admin: bool,
+    purge: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let mut history = HistoryEntry::start(HistoryOperation::Remove, scope_for(admin));
+    let result = remove_fonts(
+        manager.clone(),
+        name,
+        font_inputs,
+        admin,
+        purge,
+        opts,
+        &mut history,
+    )
+    .await;
+    announce_change(manager.as_ref(), &history, &opts);
+    record_history(history, &result, &opts);
+    result
+}
+
+async fn remove_fonts(
+    manager: Arc<dyn FontManager>,
+    name: Option<String>,
+    font_inputs: Vec<PathBuf>,
+    admin: bool,
+    purge: bool,
+    opts: OperationOptions,
+    history: &mut HistoryEntry,
+) -> Result<(), FontError> {
+    let scope = if admin {
+        FontScope::System
+    } else {
+        FontScope::User
+    };
+    let managed = check_managed_scope(scope, "removal")?;
+    let policy = ProtectionPolicy::load_default()?;
+    if !opts.dry_run {
+        purge_expired_quarantine(&opts);
+    }
+    let disposal = if purge { "delete" } else { "quarantine" };
+    let planned = if purge {
+        "deleting the file"
+    } else {
+        "into quarantine"
+    };
+
+    if let Some(font_name) = name {
+        log_status(&opts, &format!("Removing font by name: {}", font_name));
+
+        // Find font by name in installed fonts
+        let installed_fonts = manager.list_installed_fonts()?;
+        if let Some(font) = installed_fonts
+            .iter()
+            .find(|f| f.postscript_name == font_name || f.full_name == font_name)
+        {
+            check_protection_policy(
+                &policy,
+                &font.source.path,
+                &[font.postscript_name.as_str()],
+                &opts,
+            )?;
+            if opts.dry_run {
+                log_status(
+                    &opts,
+                    &format!(
+                        "DRY-RUN: would remove '{}' at {} ({})",
+                        font_name,
+                        font.source.path.display(),
+                        planned
+                    ),
+                );
+            } else {
+                let path = font.source.path.clone();
+                let starting_scope = font.source.scope.unwrap_or(scope);
+
+                // Try to unregister, but don't fail if not registered
+                match operations::unregister(
+                    manager.as_ref(),
+                    &managed,
+                    &path,
+                    &scope_order(starting_scope),
+                ) {
+                    Ok(used_scope) => {
+                        log_verbose(
+                            &opts,
+                            &format!("Unregistered font ({})", used_scope.description()),
+                        );
+                    }
+                    Err(e) => {
+                        log_status(
+                            &opts,
+                            &format!(
+                                "⚠️  Could not unregister font '{}': {} (will still {} file)",
+                                font_name, e, disposal
+                            ),
+                        );
+                    }
+                }
+
+                // Always try to get rid of the file
+                discard_font_file(&path, starting_scope, purge, &opts)?;
+                history.handled(&path);
+            }
+        } else {
+            log_status(
+                &opts,
+                &format!(
+                    "⚠️  Font '{}' is not installed, nothing to remove",
+                    font_name
+                ),
+            );
+            return Ok(());
+        }
+    } else {
+        let font_inputs = resolve_shortened_inputs(font_inputs, &opts);
+        let targets = collect_font_inputs(&font_inputs, &ScanOptions::default())?;
+        for path in targets {
+            check_protection_policy(&policy, &path, &[], &opts)?;
+            if opts.dry_run {
+                log_status(
+                    &opts,
+                    &format!(
+                        "DRY-RUN: would remove font at {} ({}, {})",
+                        path.display(),
+                        scope.description(),
+                        planned
+                    ),
+                );
+                continue;
+            }
+
+            log_status(
+                &opts,
+                &format!("Removing font from path: {}", path.display()),
+            );
+
+            // Try to unregister, but don't fail if not registered
+            match operations::unregister(manager.as_ref(), &managed, &path, &scope_order(scope)) {
+                Ok(used_scope) => {
+                    log_verbose(
+                        &opts,
+                        &format!("Unregistered font ({})", used_scope.description()),
+                    );
+                }
+                Err(e) => {
+                    log_status(
+                        &opts,
+                        &format!(
+                            "⚠️  Could not unregister font: {} (will still {} file)",
+                            e, disposal
+                        ),
+                    );
+                }
+            }
+
+            // Always try to get rid of the file
+            discard_font_file(&path, scope, purge, &opts)?;
+            history.handled(&path);
+        }
+    }
+
+    Ok(())
+}
+
+/// `fontlift disable`: unregister a font and move it into the vault.
+pub async fn handle_disable_command(
+    manager: Arc<dyn FontManager>,
+    name: Option<String>,
+    font: Option<PathBuf>,
+    admin: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let default_scope = if admin {
+        FontScope::System
+    } else {
+        FontScope::User
+    };
+
+    let (path, scope) = match (name, font) {
+        (Some(font_name), _) => {
+            let installed_fonts = manager.list_installed_fonts()?;
+            let font = installed_fonts
+                .iter()
+                .find(|f| f.postscript_name == font_name || f.full_name == font_name)
+                .ok_or_else(|| FontError::FontNotFound(PathBuf::from(&font_name)))?;
+            (
+                font.source.path.clone(),
+                font.source.scope.unwrap_or(default_scope),
+            )
+        }
+        (None, Some(path)) => (path, default_scope),
+        (None, None) => {
+            return Err(FontError::InvalidArgument(
+                "Nothing to disable: give a font file or --name".to_string(),
+            ))
+        }
+    };
+    check_managed_scope(scope, "disable")?;
+    let policy = ProtectionPolicy::load_default()?;
+
+    if opts.dry_run {
+        let names = vault::read_postscript_names(&path)?;
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        if let Some(warning) = vault::check_can_disable(&path, &names, &policy)? {
+            log_status(&opts, &format!("⚠️  Protection policy: {}", warning));
+        }
+        log_status(
+            &opts,
+            &format!(
+                "DRY-RUN: would unregister {} ({}) and move it to {}",
+                path.display(),
+                scope.description(),
+                vault::vault_dir(scope).display()
+            ),
+        );
+        return Ok(());
+    }
+
+    let outcome = vault::disable(manager.as_ref(), &path, scope, &policy)?;
+    if let Some(warning) = &outcome.warning {
+        log_status(&opts, &format!("⚠️  Protection policy: {}", warning));
+    }
+    forget_install_record(&path, &opts);
+    log_status(
+        &opts,
+        &format!(
+            "✅ Disabled {} ({}); `fontlift enable {}` puts it back",
+            path.display(),
+            scope.description(),
+            outcome.font.id
+        ),
+    );
+    Ok(())
+}
+
+/// `fontlift enable`: move a disabled font back and register it.
+pub async fn handle_enable_command(
+    manager: Arc<dyn FontManager>,
+    query: String,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let font = vault::find_disabled(&query)?.ok_or_else(|| {
+        FontError::InvalidArgument(format!(
+            "No disabled font matches '{}'; see `fontlift disable --list`",
+            query
+        ))
+    })?;
+    check_managed_scope(font.scope, "install")?;
+
+    if opts.dry_run {
+        log_status(
+            &opts,
+            &format!(
+                "DRY-RUN: would restore {} ({}) and register it",
+                font.original_path.display(),
+                font.scope.description()
+            ),
+        );
+        return Ok(());
+    }
+
+    vault::enable(manager.as_ref(), &font)?;
+    log_status(
+        &opts,
+        &format!(
+            "✅ Enabled {} ({})",
+            font.original_path.display(),
+            font.scope.description()
+        ),
+    );
+    Ok(())
+}
+
+/// `fontlift tui`.
+pub async fn handle_tui_command(manager: Arc<dyn FontManager>) -> Result<(), FontError> {
+    tui::run(manager).await
+}
+
+/// `fontlift disable --list`.
+pub fn handle_disabled_list_command(json: bool) -> Result<(), FontError> {
+    let fonts = vault::list_disabled()?;
+
+    if json {
+        let json = to_string_pretty(&fonts).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize disabled fonts: {}", e))
+        })?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    if fonts.is_empty() {
+        println!("No disabled fonts");
+        return Ok(());
+    }
+    for font in &fonts {
+        println!(
+            "{}  {}  {}  ({})",
+            font.id,
+            font.postscript_names.join(", "),
+            font.original_path.display(),
+            font.scope.description()
+        );
+    }
+    Ok(())
+}
+
+/// `fontlift restore`: bring a removed font back out of quarantine.
+pub async fn handle_restore_command(
+    manager: Arc<dyn FontManager>,
+    query: String,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let font = quarantine::find_quarantined(&query)?.ok_or_else(|| {
+        FontError::InvalidArgument(format!(
+            "No removed font matches '{}'; see `fontlift restore --list`",
+            query
+        ))
+    })?;
+    check_managed_scope(font.scope, "install")?;
+
+    if opts.dry_run {
+        log_status(
+            &opts,
+            &format!(
+                "DRY-RUN: would restore {} ({}) and register it",
+                font.original_path.display(),
+                font.scope.description()
+            ),
+        );
+        return Ok(());
+    }
+
+    quarantine::restore(manager.as_ref(), &font)?;
+    if let Some(record) = font.record.clone() {
+        if let Err(e) = install_db::update_install_db(|db| {
+            db.forget(&record.path);
+            db.records.push(record);
+        }) {
+            log_verbose(
+                &opts,
+                &format!("⚠️  Could not update install database: {}", e),
+            );
+        }
+    }
+    log_status(
+        &opts,
+        &format!(
+            "✅ Restored {} ({})",
+            font.original_path.display(),
+            font.scope.description()
+        ),
+    );
+    Ok(())
+}
+
+/// `fontlift restore --list`.
+pub fn handle_quarantine_list_command(json: bool) -> Result<(), FontError> {
+    let fonts = quarantine::list_quarantined()?;
+
+    if json {
+        let json = to_string_pretty(&fonts).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize removed fonts: {}", e))
+        })?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    if fonts.is_empty() {
+        println!("No removed fonts to restore");
+        return Ok(());
+    }
+    let retention = quarantine::retention();
+    for font in &fonts {
+        let days_left = font
+            .expires_at(retention)
+            .duration_since(SystemTime::now())
+            .map_or(0, |left| left.as_secs() / (24 * 60 * 60));
+        println!(
+            "{}  {}  {}  ({}, purged in {} days)",
+            font.id,
+            font.postscript_names.join(", "),
+            font.original_path.display(),
+            font.scope.description(),
+            days_left
+        );
+    }
+    Ok(())
+}
+
+/// `fontlift history`: completed operations, oldest first, optionally only
+/// those newer than `since`.
+pub fn handle_history_command(since: Option<Duration>, json: bool) -> Result<(), FontError> {
+    let mut entries = history::load_history()?;
+    if let Some(age) = since {
+        let cutoff = SystemTime::now()
+            .checked_sub(age)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        entries = history::since(entries, cutoff);
+    }
+
+    if json {
+        let json = to_string_pretty(&entries)
+            .map_err(|e| FontError::InvalidFormat(format!("Failed to serialize history: {}", e)))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("No operations recorded");
+        return Ok(());
+    }
+    for entry in &entries {
+        let user = match &entry.sudo_user {
+            Some(sudo_user) => format!("{} (sudo by {})", entry.user, sudo_user),
+            None => entry.user.clone(),
+        };
+        println!(
+            "{} UTC  {}  {}  {}  {}",
+            history::format_utc(entry.at),
+            entry.operation.as_str(),
+            entry.scope.description(),
+            user,
+            entry.outcome.as_str()
+        );
+        for file in &entry.files {
+            println!("    {}", file.display());
+        }
+        for failed in &entry.failed {
+            println!("    ✗ {}: {}", failed.path.display(), failed.error);
+        }
+        if let Some(error) = &entry.error {
+            println!("    error: {}", error);
+        }
     }
-
     Ok(())
 }
 
-pub async fn handle_uninstall_command(
+/// Which steps `fontlift cleanup` runs, and how far.
+#[derive(Debug, Clone, Default)]
+pub struct CleanupOptions {
+    pub prune_only: bool,
+    pub cache_only: bool,
+    /// Schedule the cache clear for the next restart instead.
+    pub at_reboot: bool,
+    /// Prune at most this many registrations.
+    pub limit: Option<usize>,
+    /// Clear only these caches; `None` clears all of them.
+    pub caches: Option<CacheTargets>,
+    /// Clear every user's OS cache files, not only the current user's.
+    pub deep: bool,
+}
+
+pub async fn handle_cleanup_command(
     manager: Arc<dyn FontManager>,
-    name: Option<String>,
-    font_inputs: Vec<PathBuf>,
     admin: bool,
+    cleanup: CleanupOptions,
     opts: OperationOptions,
 ) -> Result<(), FontError> {
-    let default_scope = if admin {
+    let mut history = HistoryEntry::start(HistoryOperation::Cleanup, scope_for(admin));
+    let result = clean_up(manager.clone(), admin, cleanup, opts, &mut history).await;
+    announce_change(manager.as_ref(), &history, &opts);
+    record_history(history, &result, &opts);
+    result
+}
+
+async fn clean_up(
+    manager: Arc<dyn FontManager>,
+    admin: bool,
+    cleanup: CleanupOptions,
+    opts: OperationOptions,
+    history: &mut HistoryEntry,
+) -> Result<(), FontError> {
+    let CleanupOptions {
+        prune_only,
+        cache_only,
+        at_reboot,
+        limit,
+        caches,
+        deep,
+    } = cleanup;
+    let scope = if admin {
         FontScope::System
     } else {
         FontScope::User
     };
+    check_managed_scope(scope, "cleanup")?;
 
-    if let Some(font_name) = name {
-        log_status(&opts, &format!("Uninstalling font by name: {}", font_name));
+    let run_prune = !cache_only;
+    let run_cache_clear = !prune_only;
+    // With --json, stdout carries the cleanup report alone.
+    let json = opts.output.json;
+    let mut opts = opts;
+    opts.output.quiet |= json;
 
-        // Find font by name in installed fonts
-        let installed_fonts = manager.list_installed_fonts()?;
-        if let Some(font) = installed_fonts
-            .iter()
-            .find(|f| f.postscript_name == font_name || f.full_name == font_name)
-        {
-            let starting_scope = font.source.scope.unwrap_or(default_scope);
+    log_status(
+        &opts,
+        &format!(
+            "Starting {} cleanup...",
+            if admin { "system" } else { "user" }
+        ),
+    );
 
-            if opts.dry_run {
+    if opts.dry_run {
+        let mut planned = Vec::new();
+        let prune_step = match limit {
+            Some(n) => format!("prune up to {n} stale registrations"),
+            None => "prune stale registrations".to_string(),
+        };
+        let cache_step = match &caches {
+            Some(targets) => format!("clear the {} caches", targets.describe()),
+            None => "clear font caches".to_string(),
+        };
+        let cache_step = if deep {
+            format!("{cache_step}, every user's included")
+        } else {
+            cache_step
+        };
+        if run_prune {
+            planned.push(prune_step.as_str());
+        }
+        if run_cache_clear && at_reboot {
+            planned.push("schedule font cache deletion at the next restart");
+        } else if run_cache_clear {
+            planned.push(cache_step.as_str());
+        }
+        log_status(
+            &opts,
+            &format!(
+                "DRY-RUN: would {} ({})",
+                planned.join(" and "),
+                scope.description()
+            ),
+        );
+        if run_prune {
+            let report = manager.repair_renamed_copies(scope, true)?;
+            for merge in &report.merged {
                 log_status(
                     &opts,
                     &format!(
-                        "DRY-RUN: would uninstall '{}' at {} (checking {})",
-                        font_name,
-                        font.source.path.display(),
-                        describe_scope_chain(starting_scope)
+                        "DRY-RUN: would merge duplicate {} into {}",
+                        merge.copy.display(),
+                        merge.canonical.display()
                     ),
                 );
-            } else {
-                match uninstall_across_scopes(&manager, &font.source.path, starting_scope) {
-                    Ok(used_scope) => {
-                        log_status(
-                            &opts,
-                            &format!(
-                                "✅ Successfully uninstalled font '{}' ({})",
-                                font_name,
-                                used_scope.description()
-                            ),
-                        );
-                    }
-                    Err(e) => {
-                        log_status(
-                            &opts,
-                            &format!("⚠️  Could not unregister font '{}': {}", font_name, e),
-                        );
-                    }
-                }
             }
-        } else {
+        }
+        return Ok(());
+    }
+
+    let targets = caches.unwrap_or_else(CacheTargets::all).with_deep(deep);
+    let observer = CliProgress::new(&opts);
+    let mut pruned = None;
+    let mut repaired = None;
+    let mut cleared = None;
+    if run_prune {
+        let options = PruneOptions::default().with_limit(limit);
+        let report = progress::prune_observed(manager.as_ref(), scope, &options, &observer)?;
+        if report.resumed {
+            log_status(&opts, "Continued an unfinished prune from the journal");
+        }
+        log_verbose(
+            &opts,
+            &format!("Pruned {} stale font registration(s)", report.len()),
+        );
+        if report.remaining > 0 {
             log_status(
                 &opts,
                 &format!(
-                    "⚠️  Font '{}' is not installed, nothing to uninstall",
-                    font_name
+                    "Stopped at the limit; {} stale registration(s) left for the next cleanup",
+                    report.remaining
                 ),
             );
-            return Ok(());
         }
-    } else {
-        let targets = collect_font_inputs(&font_inputs)?;
-        for path in targets {
-            if opts.dry_run {
-                log_status(
-                    &opts,
-                    &format!(
-                        "DRY-RUN: would uninstall font at {} (checking {})",
-                        path.display(),
-                        describe_scope_chain(default_scope)
-                    ),
-                );
-                continue;
+        for entry in &report.removed {
+            if let Some(path) = &entry.path {
+                history.handled(path);
             }
+            let path = entry
+                .path
+                .as_ref()
+                .map(|path| format!(" → {}", path.display()))
+                .unwrap_or_default();
+            log_verbose(
+                &opts,
+                &format!("  {}{} ({})", entry.name, path, entry.reason.description()),
+            );
+        }
+        pruned = Some(report);
 
+        // Numbered copies Windows made of colliding file names (arial_0.ttf).
+        let report = manager.repair_renamed_copies(scope, false)?;
+        if !report.merged.is_empty() {
             log_status(
                 &opts,
-                &format!("Uninstalling font from path: {}", path.display()),
+                &format!(
+                    "Merged {} duplicate font cop{} into the original file",
+                    report.merged.len(),
+                    if report.merged.len() == 1 { "y" } else { "ies" }
+                ),
+            );
+        }
+        for merge in &report.merged {
+            if merge.deleted {
+                history.handled(&merge.copy);
+            }
+            log_verbose(
+                &opts,
+                &format!(
+                    "  {} → {} ({} registration(s) {})",
+                    merge.copy.display(),
+                    merge.canonical.display(),
+                    merge.registrations.len(),
+                    if merge.canonical_registered {
+                        "removed"
+                    } else {
+                        "moved"
+                    }
+                ),
+            );
+        }
+        for kept in &report.kept {
+            log_verbose(
+                &opts,
+                &format!(
+                    "  kept {}: {} from {}",
+                    kept.copy.display(),
+                    kept.reason,
+                    kept.canonical.display()
+                ),
             );
+        }
+        for warning in &report.warnings {
+            log_status(&opts, &format!("⚠️  {}", warning));
+        }
+        if !report.is_empty() {
+            repaired = Some(report);
+        }
+    }
 
-            match uninstall_across_scopes(&manager, &path, default_scope) {
-                Ok(used_scope) => {
-                    log_status(
-                        &opts,
-                        &format!(
-                            "✅ Successfully uninstalled font ({})",
-                            used_scope.description()
-                        ),
-                    );
+    if run_cache_clear && at_reboot {
+        let files = manager.schedule_cache_clear_at_reboot(scope)?;
+        journal::record_scheduled_cache_clear(scope, &files)?;
+        for file in &files {
+            log_verbose(&opts, &format!("Scheduled {}", file.display()));
+        }
+        log_status(
+            &opts,
+            &format!(
+                "✅ Scheduled {} cache file(s) for deletion at the next restart",
+                files.len()
+            ),
+        );
+    } else if run_cache_clear {
+        let strategy = CacheClearStrategy::for_scope(scope);
+        match progress::clear_caches_observed(manager.as_ref(), strategy, &targets, &observer) {
+            Ok(result) => {
+                log_status(
+                    &opts,
+                    &format!(
+                        "✅ Cleared {} font cache entr{}",
+                        result.entries_cleared,
+                        if result.entries_cleared == 1 {
+                            "y"
+                        } else {
+                            "ies"
+                        }
+                    ),
+                );
+                for warning in &result.warnings {
+                    log_status(&opts, &format!("⚠️  {}", warning));
                 }
-                Err(e) => {
-                    log_status(
-                        &opts,
-                        &format!(
-                            "⚠️  Font at {} may not be registered: {}",
-                            path.display(),
-                            e
-                        ),
-                    );
+                if result.restart_required {
+                    log_status(&opts, "Restart the computer to finish clearing font caches");
                 }
+                cleared = Some(result);
+            }
+            Err(FontError::PermissionDenied(msg)) if scope == FontScope::User => {
+                log_status(
+                    &opts,
+                    &format!("⚠️  Skipping cache clear (requires admin): {}", msg),
+                );
             }
+            Err(err) => return Err(err),
         }
     }
 
+    if json && (pruned.is_some() || repaired.is_some() || cleared.is_some()) {
+        let json = to_string_pretty(&serde_json::json!({
+            "prune": pruned,
+            "duplicates": repaired,
+            "cache": cleared
+        }))
+        .map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize cleanup report: {}", e))
+        })?;
+        println!("{}", json);
+    }
+
     Ok(())
 }
 
-pub async fn handle_remove_command(
+/// Run every maintenance step, or register a weekly task that does.
+pub async fn handle_maintain_command(
     manager: Arc<dyn FontManager>,
-    name: Option<String>,
-    font_inputs: Vec<PathBuf>,
     admin: bool,
+    install_schedule: bool,
+    log_file: Option<PathBuf>,
     opts: OperationOptions,
 ) -> Result<(), FontError> {
     let scope = if admin {
@@ -495,197 +4693,348 @@ pub async fn handle_remove_command(
         FontScope::User
     };
 
-    if let Some(font_name) = name {
-        log_status(&opts, &format!("Removing font by name: {}", font_name));
+    if install_schedule {
+        let program = std::env::current_exe().map_err(FontError::IoError)?;
+        let log_file = log_file.unwrap_or_else(|| schedule::default_log_file("maintain"));
+        let task = ScheduledTask::maintain(program, scope, Frequency::Weekly, log_file);
+        if opts.dry_run {
+            log_status(
+                &opts,
+                &format!(
+                    "DRY-RUN: would run `{}` {} ({}), logging to {}",
+                    task.command_line(),
+                    Frequency::Weekly.description(),
+                    scope.description(),
+                    task.log_file.display()
+                ),
+            );
+            return Ok(());
+        }
+        let location = manager.schedule_task(&task)?;
+        log_verbose(&opts, &format!("Registered {}", location));
+        log_status(
+            &opts,
+            &format!(
+                "✅ Scheduled {} maintenance ({}); output goes to {}",
+                Frequency::Weekly.description(),
+                scope.description(),
+                task.log_file.display()
+            ),
+        );
+        return Ok(());
+    }
 
-        // Find font by name in installed fonts
-        let installed_fonts = manager.list_installed_fonts()?;
-        if let Some(font) = installed_fonts
+    // With --json, stdout carries the summary alone.
+    let json = opts.output.json;
+    let mut opts = opts;
+    opts.output.quiet |= json;
+
+    if opts.dry_run {
+        let planned: Vec<_> = maintain::Step::ALL
             .iter()
-            .find(|f| f.postscript_name == font_name || f.full_name == font_name)
-        {
-            if opts.dry_run {
-                log_status(
-                    &opts,
-                    &format!(
-                        "DRY-RUN: would remove '{}' at {}",
-                        font_name,
-                        font.source.path.display()
-                    ),
-                );
-            } else {
-                let path = font.source.path.clone();
-                let starting_scope = font.source.scope.unwrap_or(scope);
+            .map(|step| step.description())
+            .collect();
+        log_status(
+            &opts,
+            &format!(
+                "DRY-RUN: would {} ({})",
+                planned.join(", "),
+                scope.description()
+            ),
+        );
+        return Ok(());
+    }
 
-                // Try to unregister, but don't fail if not registered
-                match uninstall_across_scopes(&manager, &path, starting_scope) {
-                    Ok(used_scope) => {
-                        log_verbose(
-                            &opts,
-                            &format!("Unregistered font ({})", used_scope.description()),
-                        );
-                    }
-                    Err(e) => {
-                        log_status(
-                            &opts,
-                            &format!(
-                                "⚠️  Could not unregister font '{}': {} (will still delete file)",
-                                font_name, e
-                            ),
-                        );
-                    }
-                }
+    let summary = maintain::run(manager.as_ref(), scope, &CliProgress::new(&opts));
+    if json {
+        let json = to_string_pretty(&summary).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize maintenance summary: {}", e))
+        })?;
+        println!("{}", json);
+    }
+    for step in &summary.steps {
+        let mark = match step.status {
+            StepStatus::Ok => "✅",
+            StepStatus::Warning | StepStatus::Skipped => "⚠️ ",
+            StepStatus::Failed => "❌",
+        };
+        log_status(
+            &opts,
+            &format!("{} {}: {}", mark, step.step.name(), step.summary),
+        );
+    }
 
-                // Always try to delete the file
-                if path.exists() {
-                    fs::remove_file(&path).map_err(FontError::IoError)?;
-                    log_status(
-                        &opts,
-                        &format!("✅ Successfully removed font file: {}", path.display()),
-                    );
-                } else {
-                    log_status(
-                        &opts,
-                        &format!("⚠️  Font file not found: {}", path.display()),
-                    );
-                }
-            }
+    match summary.failed() {
+        0 => Ok(()),
+        failed => Err(FontError::VerificationFailed(format!(
+            "{} maintenance step(s) failed",
+            failed
+        ))),
+    }
+}
+
+/// List every font folder, store and cache with what it holds.
+pub async fn handle_sources_command(
+    manager: Arc<dyn FontManager>,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    let mut roots = match manager.source_roots() {
+        Ok(roots) => roots,
+        Err(FontError::UnsupportedOperation(msg)) => {
+            log_verbose(&opts, &format!("⚠️  {}", msg));
+            Vec::new()
+        }
+        Err(err) => return Err(err),
+    };
+    roots.extend(sources::fontlift_roots());
+    let usage: Vec<SourceUsage> = roots.into_iter().map(sources::measure).collect();
+
+    if opts.output.json {
+        let json = to_string_pretty(&usage).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize font sources: {}", e))
+        })?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    for source in &usage {
+        let scope = match source.root.scope {
+            Some(FontScope::User) => "user",
+            Some(FontScope::System) => "system (--admin)",
+            None => "shared",
+        };
+        let access = if source.writable {
+            "writable"
         } else {
-            log_status(
-                &opts,
-                &format!(
-                    "⚠️  Font '{}' is not installed, nothing to remove",
-                    font_name
-                ),
-            );
-            return Ok(());
+            "read-only"
+        };
+        let mut path = source.root.path.display().to_string();
+        if let Some(owner) = &source.root.owner {
+            path.push_str(&format!(" ({})", owner));
         }
-    } else {
-        let targets = collect_font_inputs(&font_inputs)?;
-        for path in targets {
+        if !source.exists {
+            path.push_str(" [not created yet]");
+        }
+        println!(
+            "{:<15} {:<17} {:>6} fonts {:>9}  {:<9} {}",
+            source.root.kind.as_str(),
+            scope,
+            source.font_files,
+            format_size(source.bytes),
+            access,
+            path
+        );
+    }
+    Ok(())
+}
+
+/// `512 B`, `3.4 KB`, `12.0 MB`, ...
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Install, list or remove fontlift's scheduled tasks.
+pub async fn handle_schedule_command(
+    manager: Arc<dyn FontManager>,
+    command: ScheduleCommand,
+    json: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    match command {
+        ScheduleCommand::Cleanup {
+            daily,
+            weekly: _,
+            admin,
+            log_file,
+        } => {
+            let scope = if admin {
+                FontScope::System
+            } else {
+                FontScope::User
+            };
+            let frequency = if daily {
+                Frequency::Daily
+            } else {
+                Frequency::Weekly
+            };
+            let program = std::env::current_exe().map_err(FontError::IoError)?;
+            let log_file = log_file.unwrap_or_else(|| schedule::default_log_file("cleanup"));
+            let task = ScheduledTask::cleanup(program, scope, frequency, log_file);
+
             if opts.dry_run {
                 log_status(
                     &opts,
                     &format!(
-                        "DRY-RUN: would remove font at {} ({})",
-                        path.display(),
-                        scope.description()
+                        "DRY-RUN: would run `{}` {} ({}), logging to {}",
+                        task.command_line(),
+                        frequency.description(),
+                        scope.description(),
+                        task.log_file.display()
                     ),
                 );
-                continue;
+                return Ok(());
             }
 
+            let location = manager.schedule_task(&task)?;
+            log_verbose(&opts, &format!("Registered {}", location));
             log_status(
                 &opts,
-                &format!("Removing font from path: {}", path.display()),
+                &format!(
+                    "✅ Scheduled {} cleanup ({}); output goes to {}",
+                    frequency.description(),
+                    scope.description(),
+                    task.log_file.display()
+                ),
             );
-
-            // Try to unregister, but don't fail if not registered
-            match uninstall_across_scopes(&manager, &path, scope) {
-                Ok(used_scope) => {
-                    log_verbose(
-                        &opts,
-                        &format!("Unregistered font ({})", used_scope.description()),
-                    );
-                }
-                Err(e) => {
-                    log_status(
-                        &opts,
-                        &format!(
-                            "⚠️  Could not unregister font: {} (will still delete file)",
-                            e
-                        ),
+        }
+        ScheduleCommand::List => {
+            let tasks = manager.list_scheduled_tasks()?;
+            if json {
+                let json = to_string_pretty(&tasks).map_err(|e| {
+                    FontError::InvalidFormat(format!("Failed to serialize scheduled tasks: {}", e))
+                })?;
+                println!("{}", json);
+            } else if tasks.is_empty() {
+                log_status(&opts, "No scheduled tasks");
+            } else {
+                for task in tasks {
+                    println!(
+                        "{}\t{}\t{}\t{}",
+                        task.name,
+                        task.scope.description(),
+                        task.frequency.map(Frequency::description).unwrap_or("-"),
+                        task.location
                     );
                 }
             }
-
-            // Always try to delete the file
-            if path.exists() {
-                fs::remove_file(&path).map_err(FontError::IoError)?;
-                log_status(
-                    &opts,
-                    &format!("✅ Successfully removed font file: {}", path.display()),
-                );
+        }
+        ScheduleCommand::Remove { name, admin } => {
+            let scope = if admin {
+                FontScope::System
             } else {
+                FontScope::User
+            };
+            if opts.dry_run {
                 log_status(
                     &opts,
-                    &format!("⚠️  Font file not found: {}", path.display()),
+                    &format!(
+                        "DRY-RUN: would remove scheduled task '{}' ({})",
+                        name,
+                        scope.description()
+                    ),
                 );
+                return Ok(());
             }
+            manager.remove_scheduled_task(&name, scope)?;
+            log_status(&opts, &format!("✅ Removed scheduled task '{}'", name));
         }
     }
 
     Ok(())
 }
 
-pub async fn handle_cleanup_command(
-    manager: Arc<dyn FontManager>,
-    admin: bool,
-    prune_only: bool,
-    cache_only: bool,
+/// Check fontlift's state stores and, with `--repair`, fix what is safe.
+///
+/// Returns [`FontError::VerificationFailed`] while any problem remains, after
+/// the full report has been printed.
+pub async fn handle_state_command(
+    command: StateCommand,
+    json: bool,
     opts: OperationOptions,
 ) -> Result<(), FontError> {
-    let scope = if admin {
-        FontScope::System
+    let StateCommand::Check { repair } = command;
+    let repair = repair && !opts.dry_run;
+    let paths = StatePaths::current();
+    let report = if repair {
+        // No install may change the stores while they are repaired.
+        journal::with_journal_lock(|| Ok(state_check::check_state(&paths, true)))?
     } else {
-        FontScope::User
+        state_check::check_state(&paths, false)
     };
 
-    let run_prune = !cache_only;
-    let run_cache_clear = !prune_only;
-
-    log_status(
-        &opts,
-        &format!(
-            "Starting {} cleanup...",
-            if admin { "system" } else { "user" }
-        ),
-    );
-
-    if opts.dry_run {
-        let mut planned = Vec::new();
-        if run_prune {
-            planned.push("prune stale registrations");
-        }
-        if run_cache_clear {
-            planned.push("clear font caches");
+    if json {
+        let json = to_string_pretty(&report).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize state report: {}", e))
+        })?;
+        println!("{}", json);
+    } else if report.findings.is_empty() {
+        log_status(&opts, "✅ fontlift state is consistent");
+    } else {
+        for finding in &report.findings {
+            let (mark, note) = match &finding.resolution {
+                Resolution::Repaired => ("✅", "repaired".to_string()),
+                Resolution::Repairable if opts.dry_run => {
+                    ("⚠️ ", "DRY-RUN: would repair".to_string())
+                }
+                Resolution::Repairable => ("⚠️ ", "fix with --repair".to_string()),
+                Resolution::Manual(action) => ("❌", action.clone()),
+            };
+            log_status(
+                &opts,
+                &format!(
+                    "{} {}: {} ({}) — {}",
+                    mark,
+                    finding.store.description(),
+                    finding.problem,
+                    finding.path.display(),
+                    note
+                ),
+            );
         }
-        log_status(
-            &opts,
-            &format!(
-                "DRY-RUN: would {} ({})",
-                planned.join(" and "),
-                scope.description()
-            ),
-        );
-        return Ok(());
     }
 
-    if run_prune {
-        let pruned = manager.prune_missing_fonts(scope)?;
-        log_verbose(
-            &opts,
-            &format!("Pruned {} stale font registration(s)", pruned),
-        );
+    let unresolved = report.unresolved().len();
+    if unresolved > 0 {
+        return Err(FontError::VerificationFailed(format!(
+            "{} of {} state problem(s) remain",
+            unresolved,
+            report.findings.len()
+        )));
     }
+    Ok(())
+}
 
-    if run_cache_clear {
-        match manager.clear_font_caches(scope) {
-            Ok(()) => log_status(&opts, "✅ Successfully cleared font caches"),
-            Err(FontError::PermissionDenied(msg)) if scope == FontScope::User => {
-                log_status(
-                    &opts,
-                    &format!("⚠️  Skipping cache clear (requires admin): {}", msg),
-                );
-            }
-            Err(err) => return Err(err),
+/// Show interrupted operations and finish or undo them, as `strategy` says.
+///
+/// Steps on files that changed since they were recorded are only replayed
+/// when `accept_changed` is set; otherwise they are reported and stop their
+/// operation.
+pub async fn handle_doctor_command(
+    manager: Arc<dyn FontManager>,
+    preview: bool,
+    accept_changed: bool,
+    strategy: DoctorStrategy,
+    compact: bool,
+    opts: OperationOptions,
+) -> Result<(), FontError> {
+    if compact {
+        let policy = journal::RetentionPolicy::from_env();
+        if preview || opts.dry_run {
+            let mut journal = journal::load_journal()?;
+            let dropped = journal.compact(&policy);
+            log_status(
+                &opts,
+                &format!("DRY-RUN: would drop {dropped} completed journal entries"),
+            );
+        } else {
+            let dropped = journal::compact(&policy)?;
+            log_status(
+                &opts,
+                &format!("Compacted the journal: dropped {dropped} completed entries"),
+            );
         }
     }
 
-    Ok(())
-}
-
-pub async fn handle_doctor_command(preview: bool, opts: OperationOptions) -> Result<(), FontError> {
     log_status(&opts, "Checking for interrupted operations...");
 
     let journal = journal::load_journal()?;
@@ -709,6 +5058,10 @@ pub async fn handle_doctor_command(preview: bool, opts: OperationOptions) -> Res
         if let Some(desc) = &entry.description {
             log_status(&opts, &format!("  Description: {}", desc));
         }
+        if let Some(env) = &entry.environment {
+            log_status(&opts, &format!("  Recorded by: {}", env.summary()));
+            log_verbose(&opts, &format!("  Environment: {}", env.details()));
+        }
         log_status(
             &opts,
             &format!(
@@ -717,34 +5070,180 @@ pub async fn handle_doctor_command(preview: bool, opts: OperationOptions) -> Res
                 entry.actions.len()
             ),
         );
+        if !entry.items.is_empty() {
+            log_status(
+                &opts,
+                &format!(
+                    "  Batch: {} of {} font(s) finished",
+                    entry.finished_items(),
+                    entry.items.len()
+                ),
+            );
+        }
 
         for (i, action) in entry.remaining_actions().iter().enumerate() {
             let step_num = entry.current_step + i + 1;
-            log_status(&opts, &format!("  [{}] {}", step_num, action.description()));
+            let changed = match action {
+                JournalAction::CopyFile { from: path, .. } | JournalAction::DeleteFile { path } => {
+                    entry.file_changed(path)
+                }
+                _ => false,
+            };
+            let note = if changed {
+                "  ⚠️  file changed since recorded"
+            } else {
+                ""
+            };
+            log_status(
+                &opts,
+                &format!("  [{}] {}{}", step_num, action.description(), note),
+            );
         }
     }
 
     if preview || opts.dry_run {
+        let action = match strategy {
+            DoctorStrategy::Forward => "finish",
+            DoctorStrategy::Back => "undo",
+        };
         log_status(
             &opts,
-            "\nDRY-RUN: would attempt recovery of above operations",
+            &format!("\nDRY-RUN: would {action} the above operations"),
         );
         return Ok(());
     }
 
-    log_status(&opts, "\nAttempting recovery...");
+    let executor = DoctorExecutor {
+        manager: manager.as_ref(),
+        accept_changed,
+        opts: &opts,
+    };
+    let results = match strategy {
+        DoctorStrategy::Forward => {
+            log_status(&opts, "\nAttempting recovery...");
+            journal::recover_with(&executor)?
+        }
+        DoctorStrategy::Back => {
+            log_status(&opts, "\nRolling back...");
+            journal::roll_back_with(&executor)?
+        }
+    };
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    let failed = results.len() - succeeded;
+
+    if failed > 0 {
+        log_status(
+            &opts,
+            &format!(
+                "⚠️  Recovery completed with {} success, {} failure(s)",
+                succeeded, failed
+            ),
+        );
+    } else if succeeded > 0 {
+        log_status(
+            &opts,
+            &format!("✅ Successfully recovered {} action(s)", succeeded),
+        );
+    } else {
+        log_status(&opts, "✅ No recovery actions needed");
+    }
+
+    Ok(())
+}
+
+/// Carries out `fontlift doctor` recovery: file steps directly, registration
+/// steps through the platform font manager.
+struct DoctorExecutor<'a> {
+    manager: &'a dyn FontManager,
+    accept_changed: bool,
+    opts: &'a OperationOptions,
+}
+
+impl DoctorExecutor<'_> {
+    fn register(&self, path: &Path, scope: FontScope) -> Result<bool, FontError> {
+        if !path.exists() {
+            log_status(
+                self.opts,
+                &format!(
+                    "⚠️  Cannot register {}: the file is missing",
+                    path.display()
+                ),
+            );
+            return Ok(false);
+        }
+        let source = FontliftFontSource::new(path.to_path_buf()).with_scope(Some(scope));
+        match self.manager.install_font(&source) {
+            Ok(()) | Err(FontError::AlreadyInstalled(_)) => Ok(true),
+            Err(e) => {
+                log_status(
+                    self.opts,
+                    &format!("⚠️  Could not register {}: {e}", path.display()),
+                );
+                Ok(false)
+            }
+        }
+    }
+
+    fn unregister(&self, path: &Path, scope: FontScope) -> Result<bool, FontError> {
+        let source = FontliftFontSource::new(path.to_path_buf()).with_scope(Some(scope));
+        match self.manager.uninstall_font(&source) {
+            Ok(()) | Err(FontError::FontNotFound(_)) => Ok(true),
+            Err(e) => {
+                log_status(
+                    self.opts,
+                    &format!("⚠️  Could not unregister {}: {e}", path.display()),
+                );
+                Ok(false)
+            }
+        }
+    }
+}
+
+impl RecoveryExecutor for DoctorExecutor<'_> {
+    fn execute(
+        &self,
+        entry: &JournalEntry,
+        action: &JournalAction,
+        policy: RecoveryPolicy,
+    ) -> Result<bool, FontError> {
+        log_verbose(
+            self.opts,
+            &format!("  {:?}: {}", policy, action.description()),
+        );
 
-    let results = journal::recover_incomplete_operations(|action, policy| {
-        log_verbose(&opts, &format!("  {:?}: {}", policy, action.description()));
+        let policy = match policy {
+            RecoveryPolicy::NeedsConfirmation if self.accept_changed => RecoveryPolicy::RollForward,
+            RecoveryPolicy::NeedsConfirmation => {
+                log_status(
+                    self.opts,
+                    &format!(
+                        "⚠️  Not replaying '{}': the file changed since the operation was recorded. \
+                         Rerun with --accept-changed to replay it anyway.",
+                        action.description()
+                    ),
+                );
+                return Ok(false);
+            }
+            other => other,
+        };
 
-        // Execute recovery based on policy
         match (action, policy) {
             (_, RecoveryPolicy::Skip) => Ok(true),
+            (JournalAction::BackupFile { path, backup }, RecoveryPolicy::RollForward) => {
+                if path.exists() && !backup.exists() {
+                    fs::rename(path, backup).map_err(FontError::IoError)?;
+                }
+                Ok(true)
+            }
+            // Copies are renamed into place, so `to` is either complete or
+            // absent; an interrupted copy only leaves its partial file.
             (JournalAction::CopyFile { from, to }, RecoveryPolicy::RollForward) => {
+                links::remove_partial(to).map_err(FontError::IoError)?;
                 if to.exists() {
                     Ok(true)
                 } else if from.exists() {
-                    std::fs::copy(from, to)
+                    links::copy_file(from, to)
                         .map(|_| true)
                         .map_err(FontError::IoError)
                 } else {
@@ -753,53 +5252,76 @@ pub async fn handle_doctor_command(preview: bool, opts: OperationOptions) -> Res
             }
             (JournalAction::DeleteFile { path }, RecoveryPolicy::RollForward) => {
                 if path.exists() {
-                    std::fs::remove_file(path)
+                    links::remove_file(path)
                         .map(|_| true)
                         .map_err(FontError::IoError)
                 } else {
                     Ok(true)
                 }
             }
-            (JournalAction::RegisterFont { .. }, RecoveryPolicy::RollForward) => {
-                // Font registration recovery needs the manager - skip for now
-                log_verbose(
-                    &opts,
-                    "  (font registration recovery requires manual intervention)",
-                );
-                Ok(false)
+            (JournalAction::RegisterFont { path, scope }, RecoveryPolicy::RollForward) => {
+                self.register(path, *scope)
             }
-            (JournalAction::UnregisterFont { .. }, RecoveryPolicy::RollForward) => {
-                // Font unregistration recovery needs the manager - skip for now
-                log_verbose(
-                    &opts,
-                    "  (font unregistration recovery requires manual intervention)",
-                );
-                Ok(false)
+            (JournalAction::UnregisterFont { path, scope }, RecoveryPolicy::RollForward) => {
+                self.unregister(path, *scope)
+            }
+            // Put the replaced font back, registered again if the entry
+            // registered the font that replaced it.
+            (JournalAction::BackupFile { path, backup }, RecoveryPolicy::RollBack) => {
+                if !backup.exists() {
+                    return Ok(true);
+                }
+                fs::rename(backup, path).map_err(FontError::IoError)?;
+                let scope = entry.actions.iter().find_map(|action| match action {
+                    JournalAction::RegisterFont {
+                        path: registered,
+                        scope,
+                    } if registered == path => Some(*scope),
+                    _ => None,
+                });
+                match scope {
+                    Some(scope) => self.register(path, scope),
+                    None => Ok(true),
+                }
+            }
+            // Undo a copy: the file at `to` is the orphan the install left.
+            (JournalAction::CopyFile { to, .. }, RecoveryPolicy::RollBack) => {
+                links::remove_partial(to).map_err(FontError::IoError)?;
+                if to.exists() {
+                    links::remove_file(to).map_err(FontError::IoError)?;
+                }
+                Ok(true)
+            }
+            // The interrupted step may never have registered the font, so a
+            // failed unregistration does not stop the roll back; a leftover
+            // registration is what `fontlift cleanup --prune-only` removes.
+            (JournalAction::RegisterFont { path, scope }, RecoveryPolicy::RollBack) => {
+                if !self.unregister(path, *scope)? {
+                    log_status(
+                        self.opts,
+                        "   If it stays registered, `fontlift cleanup --prune-only` removes it.",
+                    );
+                }
+                Ok(true)
+            }
+            (JournalAction::UnregisterFont { path, scope }, RecoveryPolicy::RollBack) => {
+                self.register(path, *scope)
+            }
+            // A delete that has not happened needs no undo; one that has
+            // cannot be undone.
+            (JournalAction::DeleteFile { path }, RecoveryPolicy::RollBack) => {
+                if path.exists() {
+                    Ok(true)
+                } else {
+                    log_status(
+                        self.opts,
+                        &format!("⚠️  Cannot undo the deletion of {}", path.display()),
+                    );
+                    Ok(false)
+                }
             }
             (JournalAction::ClearCache { .. }, _) => Ok(true),
             _ => Ok(false),
         }
-    })?;
-
-    let succeeded = results.iter().filter(|r| r.success).count();
-    let failed = results.len() - succeeded;
-
-    if failed > 0 {
-        log_status(
-            &opts,
-            &format!(
-                "⚠️  Recovery completed with {} success, {} failure(s)",
-                succeeded, failed
-            ),
-        );
-    } else if succeeded > 0 {
-        log_status(
-            &opts,
-            &format!("✅ Successfully recovered {} action(s)", succeeded),
-        );
-    } else {
-        log_status(&opts, "✅ No recovery actions needed");
     }
-
-    Ok(())
 }
@@ -0,0 +1,215 @@
+//! Rhai engine behind `fontlift script run`.
+//!
+//! Scripts never touch fonts directly. The functions exposed to them either
+//! read (a snapshot of installed fonts, metadata of files on disk) or queue a
+//! [`ScriptAction`]. `ops::handle_script_command` prints the queued plan and
+//! carries it out afterwards, through the same install and uninstall paths the
+//! regular subcommands use. That keeps `--dry-run` trivially honest and means
+//! a script that errors while it runs changes nothing. Applying the plan is
+//! not atomic, though: actions run in order and stop at the first one that
+//! fails, and the actions before it stay applied.
+//!
+//! The engine is sandboxed: no `eval`, no module imports, and hard limits on
+//! operations and call depth so a runaway loop ends with an error instead of
+//! hanging the terminal.
+
+use fontlift_core::FontScope;
+use std::path::PathBuf;
+
+/// One change queued by a script.
+///
+/// Only the Rhai engine queues actions, so without the `script` feature
+/// nothing constructs them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(not(feature = "script"), allow(dead_code))]
+pub enum ScriptAction {
+    Install { path: PathBuf, scope: FontScope },
+    Uninstall { name: String },
+}
+
+impl ScriptAction {
+    pub fn describe(&self) -> String {
+        match self {
+            ScriptAction::Install { path, scope } => {
+                format!("install {} ({})", path.display(), scope.description())
+            }
+            ScriptAction::Uninstall { name } => format!("uninstall '{}'", name),
+        }
+    }
+}
+
+#[cfg(feature = "script")]
+mod engine {
+    use super::ScriptAction;
+    use crate::ops::{log_status, OperationOptions};
+    use fontlift_core::{metadata, validation, FontError, FontScope, FontliftFontFaceInfo};
+    use rhai::{Array, Dynamic, Engine, EvalAltResult, Map};
+    use std::cell::RefCell;
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
+
+    const MAX_OPERATIONS: u64 = 10_000_000;
+    const MAX_CALL_LEVELS: usize = 32;
+
+    fn scope_name(scope: FontScope) -> &'static str {
+        match scope {
+            FontScope::User => "user",
+            FontScope::System => "system",
+        }
+    }
+
+    fn face_to_map(face: &FontliftFontFaceInfo) -> Dynamic {
+        let mut map = Map::new();
+        map.insert("path".into(), face.source.path.display().to_string().into());
+        map.insert(
+            "postscript_name".into(),
+            face.postscript_name.clone().into(),
+        );
+        map.insert("full_name".into(), face.full_name.clone().into());
+        map.insert("family".into(), face.family_name.clone().into());
+        map.insert("style".into(), face.style.clone().into());
+        map.insert(
+            "weight".into(),
+            face.weight
+                .map(|w| Dynamic::from_int(w.into()))
+                .unwrap_or(Dynamic::UNIT),
+        );
+        map.insert(
+            "italic".into(),
+            face.italic.map(Dynamic::from_bool).unwrap_or(Dynamic::UNIT),
+        );
+        map.insert(
+            "scope".into(),
+            face.source
+                .scope
+                .map(|s| scope_name(s).into())
+                .unwrap_or(Dynamic::UNIT),
+        );
+        map.into()
+    }
+
+    fn fonts_in(dir: &str) -> Result<Array, Box<EvalAltResult>> {
+        let entries = std::fs::read_dir(dir).map_err(|e| format!("fonts_in({dir}): {e}"))?;
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && validation::is_valid_font_extension(path))
+            .collect();
+        paths.sort();
+
+        Ok(paths
+            .iter()
+            .map(|path| {
+                let face = metadata::read_face_info(path)
+                    .unwrap_or_else(|_| validation::extract_basic_info_from_path(path));
+                face_to_map(&face)
+            })
+            .collect())
+    }
+
+    fn parse_scope(scope: &str) -> Result<FontScope, Box<EvalAltResult>> {
+        match scope.to_ascii_lowercase().as_str() {
+            "user" => Ok(FontScope::User),
+            "system" => Ok(FontScope::System),
+            other => {
+                Err(format!("unknown scope '{other}' (expected \"user\" or \"system\")").into())
+            }
+        }
+    }
+
+    /// Run `source` and return the actions it queued, in order.
+    ///
+    /// `installed` is the snapshot `installed()` and `search()` read from.
+    /// `print` output from the script goes through the normal status output,
+    /// so `--quiet` silences it.
+    pub fn plan_script(
+        source: &str,
+        installed: Vec<FontliftFontFaceInfo>,
+        opts: OperationOptions,
+    ) -> Result<Vec<ScriptAction>, FontError> {
+        let actions: Rc<RefCell<Vec<ScriptAction>>> = Rc::default();
+        let installed: Rc<Vec<FontliftFontFaceInfo>> = Rc::new(installed);
+
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(MAX_CALL_LEVELS);
+        engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+        engine.disable_symbol("eval");
+        engine.on_print(move |text| log_status(&opts, text));
+
+        let all = installed.clone();
+        engine.register_fn("installed", move || -> Array {
+            all.iter().map(face_to_map).collect()
+        });
+
+        let all = installed.clone();
+        engine.register_fn("search", move |text: &str| -> Array {
+            let needle = text.to_lowercase();
+            all.iter()
+                .filter(|f| {
+                    [&f.postscript_name, &f.full_name, &f.family_name]
+                        .iter()
+                        .any(|name| name.to_lowercase().contains(&needle))
+                })
+                .map(face_to_map)
+                .collect()
+        });
+
+        engine.register_fn("fonts_in", fonts_in);
+
+        let queue = actions.clone();
+        engine.register_fn("install", move |path: &str| {
+            queue.borrow_mut().push(ScriptAction::Install {
+                path: Path::new(path).to_path_buf(),
+                scope: FontScope::User,
+            });
+        });
+
+        let queue = actions.clone();
+        engine.register_fn(
+            "install",
+            move |path: &str, scope: &str| -> Result<(), Box<EvalAltResult>> {
+                queue.borrow_mut().push(ScriptAction::Install {
+                    path: Path::new(path).to_path_buf(),
+                    scope: parse_scope(scope)?,
+                });
+                Ok(())
+            },
+        );
+
+        let queue = actions.clone();
+        engine.register_fn("uninstall", move |name: &str| {
+            queue.borrow_mut().push(ScriptAction::Uninstall {
+                name: name.to_string(),
+            });
+        });
+
+        let dry_run = opts.dry_run;
+        engine.register_fn("is_dry_run", move || dry_run);
+
+        engine
+            .run(source)
+            .map_err(|e| FontError::ScriptError(e.to_string()))?;
+
+        drop(engine);
+        let actions = Rc::try_unwrap(actions)
+            .map(RefCell::into_inner)
+            .unwrap_or_else(|shared| shared.borrow().clone());
+        Ok(actions)
+    }
+}
+
+#[cfg(feature = "script")]
+pub use engine::plan_script;
+
+/// Stand-in used when fontlift was built without the `script` feature.
+#[cfg(not(feature = "script"))]
+pub fn plan_script(
+    _source: &str,
+    _installed: Vec<fontlift_core::FontliftFontFaceInfo>,
+    _opts: crate::ops::OperationOptions,
+) -> Result<Vec<ScriptAction>, fontlift_core::FontError> {
+    Err(fontlift_core::FontError::UnsupportedOperation(
+        "Running scripts requires the `script` feature".to_string(),
+    ))
+}
@@ -1,7 +1,11 @@
 use super::*;
 use clap_complete::Shell;
+use fontlift_core::cache::{CacheClearResult, CacheClearStrategy};
+use fontlift_core::plan::PlannedAction;
+use fontlift_core::prune::{PruneOptions, PruneProgress, PruneReason, PruneReport};
 use fontlift_core::{FontError, FontManager, FontScope, FontliftFontFaceInfo, FontliftFontSource};
 use serde_json::Value;
+use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
@@ -13,15 +17,112 @@ fn test_cli_parsing() {
 
     let cli = Cli::try_parse_from(["fontlift", "list", "-p"]).unwrap();
     match cli.command {
-        Commands::List { path, name, sorted } => {
+        Commands::List {
+            path,
+            name,
+            sorted,
+            sort_by,
+            output,
+            columns,
+            color,
+            details,
+            why,
+            no_cache,
+            scope,
+            family,
+            format,
+            style,
+            modified_since,
+        } => {
             assert!(path);
             assert!(!name);
             assert!(!sorted);
+            assert!(sort_by.is_none());
+            assert_eq!(output, ListOutput::Text);
+            assert!(columns.is_empty());
+            assert_eq!(color, ColorChoice::Auto);
+            assert!(!details);
+            assert!(why.is_none());
+            assert!(!no_cache);
+            assert!(to_core_list_filter(scope, family, format, style, modified_since).is_empty());
         }
         _ => panic!("Expected list command"),
     }
 }
 
+#[test]
+fn list_filters_reach_the_manager() {
+    use clap::Parser;
+    use fontlift_core::listing::{FormatFilter, StyleFilter};
+
+    let cli = Cli::try_parse_from([
+        "fontlift",
+        "list",
+        "--scope",
+        "system",
+        "--family",
+        "Noto*",
+        "--format",
+        "ttc",
+        "--style",
+        "bold",
+        "--modified-since",
+        "2024-01-01",
+    ])
+    .unwrap();
+    let Commands::List {
+        scope,
+        family,
+        format,
+        style,
+        modified_since,
+        ..
+    } = cli.command
+    else {
+        panic!("Expected list command");
+    };
+    let filter = to_core_list_filter(scope, family, format, style, modified_since);
+    assert_eq!(filter.scope, Some(FontScope::System));
+    assert_eq!(filter.family.as_deref(), Some("Noto*"));
+    assert_eq!(filter.format, Some(FormatFilter::Ttc));
+    assert_eq!(filter.style, Some(StyleFilter::Bold));
+    assert_eq!(
+        filter.modified_since,
+        Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_704_067_200))
+    );
+    assert!(Cli::try_parse_from(["fontlift", "list", "--modified-since", "soon"]).is_err());
+}
+
+#[test]
+fn for_user_targets_another_users_fonts_and_excludes_admin() {
+    let cli = Cli::try_parse_from([
+        "fontlift",
+        "remove",
+        "--for-user",
+        "S-1-5-21-1-2-3-1001",
+        "a.ttf",
+    ])
+    .unwrap();
+    assert_eq!(target_user(&cli.command), Some("S-1-5-21-1-2-3-1001"));
+    let cli = Cli::try_parse_from(["fontlift", "install", "a.ttf"]).unwrap();
+    assert_eq!(target_user(&cli.command), None);
+    assert!(Cli::try_parse_from([
+        "fontlift",
+        "uninstall",
+        "--admin",
+        "--for-user",
+        "alice",
+        "a.ttf"
+    ])
+    .is_err());
+
+    #[cfg(not(target_os = "windows"))]
+    assert!(matches!(
+        create_font_manager_for_user("alice"),
+        Err(FontError::UnsupportedOperation(_))
+    ));
+}
+
 fn sample_font(path: &str, postscript: &str) -> FontliftFontFaceInfo {
     FontliftFontFaceInfo::new(
         FontliftFontSource::new(PathBuf::from(path)),
@@ -45,7 +146,12 @@ fn list_renders_json_sorted_and_deduped() {
         show_path: true,
         show_name: true,
         sorted: true,
+        sort_by: None,
         json: true,
+        output: ListOutput::Text,
+        columns: Vec::new(),
+        color: false,
+        details: false,
     };
 
     let output = render_list_output(fonts, opts).expect("render");
@@ -68,6 +174,43 @@ fn list_renders_json_sorted_and_deduped() {
     );
 }
 
+#[test]
+fn list_json_includes_details_only_when_asked() {
+    let mut font = sample_font("/fonts/Alpha.ttf", "Alpha-Regular");
+    font.details = Some(fontlift_core::metadata::FaceDetails {
+        version: Some("Version 2.003".to_string()),
+        vendor_id: Some("ADBO".to_string()),
+        ..Default::default()
+    });
+    let render = |details| {
+        let opts = ListRenderOptions {
+            show_path: true,
+            show_name: true,
+            sorted: false,
+            sort_by: None,
+            json: true,
+            output: ListOutput::Text,
+            columns: Vec::new(),
+            color: false,
+            details,
+        };
+        let ListRender::Json(json) = render_list_output(vec![font.clone()], opts).expect("render")
+        else {
+            panic!("expected json output");
+        };
+        serde_json::from_str::<Vec<Value>>(&json).expect("valid json")
+    };
+
+    assert!(render(false)[0].get("details").is_none());
+    let detailed = render(true);
+    assert_eq!(detailed[0]["details"]["version"], "Version 2.003");
+    assert_eq!(detailed[0]["details"]["vendor_id"], "ADBO");
+    assert!(detailed[0]["details"].get("copyright").is_none());
+
+    let cli = Cli::try_parse_from(["fontlift", "list", "--details"]).expect("parse");
+    assert!(matches!(cli.command, Commands::List { details: true, .. }));
+}
+
 #[test]
 fn list_renders_lines_sorted_and_deduped_by_default() {
     let fonts = vec![
@@ -80,7 +223,12 @@ fn list_renders_lines_sorted_and_deduped_by_default() {
         show_path: true,
         show_name: false,
         sorted: false,
+        sort_by: None,
         json: false,
+        output: ListOutput::Text,
+        columns: Vec::new(),
+        color: false,
+        details: false,
     };
 
     let output = render_list_output(fonts, opts).expect("render");
@@ -99,6 +247,179 @@ fn list_renders_lines_sorted_and_deduped_by_default() {
     );
 }
 
+#[test]
+fn list_sort_by_family_groups_styles_by_weight() {
+    use clap::Parser;
+
+    let cli = Cli::try_parse_from(["fontlift", "list", "--name", "--sort-by", "family"]).unwrap();
+    let Commands::List { sort_by, .. } = cli.command else {
+        panic!("Expected list command");
+    };
+    assert_eq!(sort_by, Some(SortBy::Family));
+
+    let face = |postscript: &str, family: &str, weight: u16, italic: bool| {
+        let mut face = sample_font(&format!("/fonts/{postscript}.ttf"), postscript);
+        face.family_name = family.to_string();
+        face.weight = Some(weight);
+        face.italic = Some(italic);
+        face
+    };
+    let fonts = vec![
+        face("Inter-Black", "Inter", 900, false),
+        face("Inter-BoldItalic", "Inter", 700, true),
+        face("Inter-Bold", "Inter", 700, false),
+        face("Abel-Regular", "Abel", 400, false),
+        face("Inter-Light", "Inter", 300, false),
+    ];
+
+    let opts = ListRenderOptions {
+        show_path: false,
+        show_name: true,
+        sorted: true,
+        sort_by: sort_by.map(to_core_sort_key),
+        json: false,
+        output: ListOutput::Text,
+        columns: Vec::new(),
+        color: false,
+        details: false,
+    };
+    let ListRender::Lines(lines) = render_list_output(fonts, opts).expect("render") else {
+        panic!("expected line output");
+    };
+    assert_eq!(
+        lines,
+        vec![
+            "Abel-Regular",
+            "Inter-Light",
+            "Inter-Bold",
+            "Inter-BoldItalic",
+            "Inter-Black"
+        ]
+    );
+}
+
+#[test]
+fn list_output_fontconfig_formats_ignore_json() {
+    use clap::Parser;
+
+    let cli = Cli::try_parse_from(["fontlift", "list", "--output", "fc-list"]).unwrap();
+    let Commands::List { output, .. } = cli.command else {
+        panic!("Expected list command");
+    };
+    assert_eq!(output, ListOutput::FcList);
+
+    let mut bold = sample_font("/fonts/Beta.ttf", "Beta-Bold");
+    bold.family_name = "Beta".to_string();
+    bold.style = "Bold".to_string();
+    bold.weight = Some(700);
+    let fonts = vec![bold, sample_font("/fonts/Alpha.ttf", "Alpha-Regular")];
+    let opts = |output| ListRenderOptions {
+        show_path: false,
+        show_name: true,
+        sorted: false,
+        sort_by: None,
+        json: true,
+        output,
+        columns: Vec::new(),
+        color: false,
+        details: false,
+    };
+
+    let ListRender::Lines(lines) =
+        render_list_output(fonts.clone(), opts(ListOutput::FcList)).expect("render")
+    else {
+        panic!("expected line output");
+    };
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("/fonts/Alpha.ttf: "));
+    assert_eq!(lines[1], "/fonts/Beta.ttf: Beta:style=Bold");
+
+    let ListRender::Lines(xml) =
+        render_list_output(fonts, opts(ListOutput::Fontconfig)).expect("render")
+    else {
+        panic!("expected line output");
+    };
+    assert_eq!(xml[0], "<?xml version=\"1.0\"?>");
+    assert_eq!(xml.last().map(String::as_str), Some("</fontconfig>"));
+    assert_eq!(xml.iter().filter(|l| l.contains("<pattern>")).count(), 2);
+    assert!(xml
+        .iter()
+        .any(|l| l.contains("<patelt name=\"weight\"><int>200</int></patelt>")));
+}
+
+#[test]
+fn list_table_aligns_selected_columns() {
+    use clap::Parser;
+
+    let cli =
+        Cli::try_parse_from(["fontlift", "list", "--columns", "family,weight,scope"]).unwrap();
+    let Commands::List { columns, color, .. } = cli.command else {
+        panic!("Expected list command");
+    };
+    assert_eq!(
+        columns,
+        vec![ListColumn::Family, ListColumn::Weight, ListColumn::Scope]
+    );
+    assert!(!use_color(ColorChoice::Never));
+    assert!(use_color(ColorChoice::Always));
+
+    let mut bold = sample_font("/fonts/Beta.ttc", "Beta-Bold");
+    bold.family_name = "Beta Display".to_string();
+    bold.weight = Some(700);
+    bold.source.scope = Some(FontScope::System);
+    let mut regular = sample_font("/fonts/Alpha.ttf", "Alpha-Regular");
+    regular.family_name = "Alpha".to_string();
+    regular.weight = Some(50);
+    regular.source.scope = Some(FontScope::User);
+    let fonts = vec![bold, regular];
+    let opts = |columns: Vec<ListColumn>, color| ListRenderOptions {
+        show_path: false,
+        show_name: false,
+        sorted: false,
+        sort_by: None,
+        json: true,
+        output: if columns.is_empty() {
+            ListOutput::Table
+        } else {
+            ListOutput::Text
+        },
+        columns,
+        color,
+        details: false,
+    };
+
+    let ListRender::Lines(lines) =
+        render_list_output(fonts.clone(), opts(columns, color == ColorChoice::Always))
+            .expect("render")
+    else {
+        panic!("expected table lines");
+    };
+    assert_eq!(
+        lines,
+        vec![
+            "FAMILY        WEIGHT  SCOPE",
+            "Alpha             50  user",
+            "Beta Display     700  system",
+        ]
+    );
+
+    let ListRender::Lines(all) =
+        render_list_output(fonts.clone(), opts(Vec::new(), false)).expect("render")
+    else {
+        panic!("expected table lines");
+    };
+    assert!(all[0].starts_with("FAMILY") && all[0].ends_with("PATH"));
+    assert!(all[2].contains("  ttc  ") && all[2].ends_with("/fonts/Beta.ttc"));
+
+    let ListRender::Lines(colored) =
+        render_list_output(fonts, opts(vec![ListColumn::Scope], true)).expect("render")
+    else {
+        panic!("expected table lines");
+    };
+    assert_eq!(colored[0], "\x1b[1mSCOPE\x1b[0m");
+    assert_eq!(colored[1], "\x1b[32muser\x1b[0m");
+}
+
 #[test]
 fn list_renders_name_only_sorted_by_default() {
     let fonts = vec![
@@ -111,7 +432,12 @@ fn list_renders_name_only_sorted_by_default() {
         show_path: false,
         show_name: true,
         sorted: false,
+        sort_by: None,
         json: false,
+        output: ListOutput::Text,
+        columns: Vec::new(),
+        color: false,
+        details: false,
     };
 
     let output = render_list_output(fonts, opts).expect("render");
@@ -141,16 +467,64 @@ fn collect_font_inputs_scans_directories_and_dedupes() {
 
     // Provide both a directory and a direct file reference to ensure deduplication
     let inputs = vec![tmp.path().to_path_buf(), beta.clone()];
-    let collected = collect_font_inputs(&inputs).expect("collect");
+    let collected = collect_font_inputs(&inputs, &ScanOptions::default()).expect("collect");
 
     assert_eq!(collected, vec![alpha.clone(), beta.clone()]);
 }
 
+#[test]
+fn collect_font_inputs_walks_subdirectories_when_recursive() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let nested = tmp.path().join("Acme/Sans");
+    fs::create_dir_all(&nested).expect("create nested");
+    let regular = nested.join("Sans-Regular.otf");
+    let trial = nested.join("Sans-Trial.otf");
+    fs::write(&regular, b"test").expect("write regular");
+    fs::write(&trial, b"test").expect("write trial");
+
+    let inputs = vec![tmp.path().to_path_buf()];
+    assert!(collect_font_inputs(&inputs, &ScanOptions::default()).is_err());
+
+    let cli = Cli::try_parse_from([
+        "fontlift",
+        "install",
+        "lib",
+        "-r",
+        "--max-depth",
+        "3",
+        "--exclude",
+        "*Trial*",
+    ])
+    .expect("parse install -r");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs {
+        recursive,
+        max_depth,
+        exclude,
+        ..
+    } = *args;
+    assert!(recursive);
+    let scan = ScanOptions::recursive(max_depth).with_exclude(exclude);
+    assert_eq!(
+        collect_font_inputs(&inputs, &scan).expect("collect"),
+        vec![regular]
+    );
+    assert!(Cli::try_parse_from(["fontlift", "install", "lib", "--max-depth", "2"]).is_err());
+}
+
 #[derive(Default)]
 struct RecordingManager {
     installs: Mutex<Vec<(PathBuf, FontScope)>>,
+    sessions: Mutex<Vec<PathBuf>>,
+    plans: Mutex<Vec<PathBuf>>,
     prunes: Mutex<Vec<FontScope>>,
-    cache_clears: Mutex<Vec<FontScope>>,
+    /// `dry_run` of each duplicate repair.
+    repairs: Mutex<Vec<bool>>,
+    cache_clears: Mutex<Vec<CacheClearStrategy>>,
+    listed: Vec<FontliftFontFaceInfo>,
+    uninstalls: Mutex<Vec<(PathBuf, FontScope)>>,
 }
 
 impl FontManager for RecordingManager {
@@ -163,7 +537,28 @@ impl FontManager for RecordingManager {
         Ok(())
     }
 
-    fn uninstall_font(&self, _source: &FontliftFontSource) -> fontlift_core::FontResult<()> {
+    fn install_font_session(&self, source: &FontliftFontSource) -> fontlift_core::FontResult<()> {
+        self.sessions
+            .lock()
+            .expect("lock")
+            .push(source.path.clone());
+        Ok(())
+    }
+
+    fn plan_install(
+        &self,
+        source: &FontliftFontSource,
+    ) -> fontlift_core::FontResult<Vec<PlannedAction>> {
+        self.plans.lock().expect("lock").push(source.path.clone());
+        Ok(Vec::new())
+    }
+
+    fn uninstall_font(&self, source: &FontliftFontSource) -> fontlift_core::FontResult<()> {
+        let scope = source.scope.unwrap_or(FontScope::User);
+        self.uninstalls
+            .lock()
+            .expect("lock")
+            .push((source.path.clone(), scope));
         Ok(())
     }
 
@@ -176,17 +571,34 @@ impl FontManager for RecordingManager {
     }
 
     fn list_installed_fonts(&self) -> fontlift_core::FontResult<Vec<FontliftFontFaceInfo>> {
-        Ok(Vec::new())
+        Ok(self.listed.clone())
     }
 
-    fn clear_font_caches(&self, _scope: FontScope) -> fontlift_core::FontResult<()> {
-        self.cache_clears.lock().expect("lock").push(_scope);
-        Ok(())
+    fn clear_font_caches(
+        &self,
+        strategy: CacheClearStrategy,
+    ) -> fontlift_core::FontResult<CacheClearResult> {
+        self.cache_clears.lock().expect("lock").push(strategy);
+        Ok(CacheClearResult::success(2, false))
     }
 
-    fn prune_missing_fonts(&self, scope: FontScope) -> fontlift_core::FontResult<usize> {
+    fn prune_stale_registrations(
+        &self,
+        scope: FontScope,
+        _options: &PruneOptions,
+        _progress: &mut dyn FnMut(&PruneProgress),
+    ) -> fontlift_core::FontResult<PruneReport> {
         self.prunes.lock().expect("lock").push(scope);
-        Ok(0)
+        Ok(PruneReport::new(scope))
+    }
+
+    fn repair_renamed_copies(
+        &self,
+        scope: FontScope,
+        dry_run: bool,
+    ) -> fontlift_core::FontResult<fontlift_core::renamed_copies::RepairReport> {
+        self.repairs.lock().expect("lock").push(dry_run);
+        Ok(fontlift_core::renamed_copies::RepairReport::new(scope))
     }
 }
 
@@ -237,12 +649,15 @@ impl FontManager for ScopedUninstallManager {
         )])
     }
 
-    fn clear_font_caches(&self, _scope: FontScope) -> fontlift_core::FontResult<()> {
-        Ok(())
+    fn clear_font_caches(
+        &self,
+        _strategy: CacheClearStrategy,
+    ) -> fontlift_core::FontResult<CacheClearResult> {
+        Ok(CacheClearResult::default())
     }
 
-    fn prune_missing_fonts(&self, _scope: FontScope) -> fontlift_core::FontResult<usize> {
-        Ok(0)
+    fn prune_missing_fonts(&self, scope: FontScope) -> fontlift_core::FontResult<PruneReport> {
+        Ok(PruneReport::new(scope))
     }
 }
 
@@ -279,16 +694,26 @@ impl FontManager for DenyCacheManager {
         Ok(vec![])
     }
 
-    fn clear_font_caches(&self, _scope: FontScope) -> fontlift_core::FontResult<()> {
+    fn clear_font_caches(
+        &self,
+        _strategy: CacheClearStrategy,
+    ) -> fontlift_core::FontResult<CacheClearResult> {
         *self.cache_attempts.lock().expect("lock") += 1;
         Err(FontError::PermissionDenied(
             "cache clearing requires admin".to_string(),
         ))
     }
 
-    fn prune_missing_fonts(&self, _scope: FontScope) -> fontlift_core::FontResult<usize> {
+    fn prune_stale_registrations(
+        &self,
+        scope: FontScope,
+        _options: &PruneOptions,
+        _progress: &mut dyn FnMut(&PruneProgress),
+    ) -> fontlift_core::FontResult<PruneReport> {
         *self.prunes.lock().expect("lock") += 1;
-        Ok(1)
+        let mut report = PruneReport::new(scope);
+        report.record("Gone (TrueType)", None, PruneReason::MissingFile);
+        Ok(report)
     }
 }
 
@@ -306,10 +731,12 @@ fn dry_run_install_skips_invoking_manager() {
         .block_on(handle_install_command(
             manager.clone(),
             vec![font.clone()],
-            false,
-            false, // no validation
-            ValidationStrictness::Normal,
-            false, // inplace (false = copy mode, default)
+            InstallOptions::new(
+                false,
+                false, // no validation
+                ValidationStrictness::Normal,
+                false, // inplace (false = copy mode, default)
+            ),
             opts,
         ))
         .expect("dry run install");
@@ -318,6 +745,36 @@ fn dry_run_install_skips_invoking_manager() {
         manager.installs.lock().expect("lock").is_empty(),
         "dry-run should not call install_font"
     );
+    assert_eq!(
+        *manager.plans.lock().expect("lock"),
+        vec![font],
+        "dry-run should ask the manager for its plan"
+    );
+}
+
+#[test]
+fn install_skips_byte_identical_inputs() {
+    let runtime = Runtime::new().expect("runtime");
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let font = tmp.path().join("Same.ttf");
+    fs::write(&font, b"same bytes").expect("write font");
+    fs::write(tmp.path().join("Same copy.ttf"), b"same bytes").expect("write copy");
+
+    let manager = Arc::new(RecordingManager::default());
+    runtime
+        .block_on(handle_install_command(
+            manager.clone(),
+            vec![tmp.path().to_path_buf()],
+            InstallOptions::new(false, false, ValidationStrictness::Normal, false),
+            OperationOptions::new(true, true, false),
+        ))
+        .expect("dry run install");
+
+    assert_eq!(
+        manager.plans.lock().expect("lock").len(),
+        1,
+        "the identical copy should be skipped"
+    );
 }
 
 #[test]
@@ -331,22 +788,37 @@ fn cleanup_respects_prune_and_cache_flags() {
         .block_on(handle_cleanup_command(
             manager.clone(),
             false,
-            false,
-            false,
+            CleanupOptions::default(),
             base_opts,
         ))
         .expect("cleanup both");
     assert_eq!(manager.prunes.lock().expect("lock").len(), 1);
+    assert_eq!(*manager.repairs.lock().expect("lock"), [false]);
     assert_eq!(manager.cache_clears.lock().expect("lock").len(), 1);
 
-    // prune-only
+    // a dry run only plans the duplicate merges
     let manager = Arc::new(RecordingManager::default());
     runtime
         .block_on(handle_cleanup_command(
             manager.clone(),
             false,
-            true,
+            CleanupOptions::default(),
+            OperationOptions::new(true, true, false),
+        ))
+        .expect("dry-run cleanup");
+    assert_eq!(*manager.repairs.lock().expect("lock"), [true]);
+    assert!(manager.prunes.lock().expect("lock").is_empty());
+
+    // prune-only
+    let manager = Arc::new(RecordingManager::default());
+    runtime
+        .block_on(handle_cleanup_command(
+            manager.clone(),
             false,
+            CleanupOptions {
+                prune_only: true,
+                ..CleanupOptions::default()
+            },
             base_opts,
         ))
         .expect("prune-only");
@@ -362,8 +834,10 @@ fn cleanup_respects_prune_and_cache_flags() {
         .block_on(handle_cleanup_command(
             manager.clone(),
             false,
-            false,
-            true,
+            CleanupOptions {
+                cache_only: true,
+                ..CleanupOptions::default()
+            },
             base_opts,
         ))
         .expect("cache-only");
@@ -371,7 +845,29 @@ fn cleanup_respects_prune_and_cache_flags() {
         manager.prunes.lock().expect("lock").is_empty(),
         "prune should be skipped"
     );
-    assert_eq!(manager.cache_clears.lock().expect("lock").len(), 1);
+    assert!(manager.repairs.lock().expect("lock").is_empty());
+    assert_eq!(
+        *manager.cache_clears.lock().expect("lock"),
+        [CacheClearStrategy::UserOnly]
+    );
+
+    // --admin clears the system caches, JSON report included
+    let manager = Arc::new(RecordingManager::default());
+    runtime
+        .block_on(handle_cleanup_command(
+            manager.clone(),
+            true,
+            CleanupOptions {
+                cache_only: true,
+                ..CleanupOptions::default()
+            },
+            base_opts.with_json(true),
+        ))
+        .expect("cache-only admin");
+    assert_eq!(
+        *manager.cache_clears.lock().expect("lock"),
+        [CacheClearStrategy::SystemOnly]
+    );
 }
 
 #[test]
@@ -382,9 +878,8 @@ fn cleanup_skips_cache_clear_permission_denied_on_windows_user_scope() {
 
     let result = runtime.block_on(handle_cleanup_command(
         manager.clone(),
-        false, // admin
-        false, // prune_only
-        false, // cache_only
+        false,
+        CleanupOptions::default(),
         base_opts,
     ));
 
@@ -400,6 +895,45 @@ fn cleanup_skips_cache_clear_permission_denied_on_windows_user_scope() {
     );
 }
 
+#[test]
+fn cleanup_cache_flag_selects_cache_targets() {
+    use clap::Parser;
+    use fontlift_core::cache::{CacheTarget, CacheTargets};
+
+    let cli = Cli::try_parse_from(["fontlift", "cleanup", "-C", "--cache", "adobe,office"])
+        .expect("parse --cache");
+    let Commands::Cleanup { cache, .. } = cli.command else {
+        panic!("Expected cleanup command");
+    };
+    let targets = to_core_cache_targets(&cache).expect("targets");
+    assert_eq!(
+        targets,
+        CacheTargets::only([CacheTarget::Adobe, CacheTarget::Office])
+    );
+    assert!(to_core_cache_targets(&[]).is_none());
+    assert!(Cli::try_parse_from(["fontlift", "cleanup", "-p", "--cache", "adobe"]).is_err());
+    let cli = Cli::try_parse_from(["fontlift", "cleanup", "-C", "--deep"]).expect("parse --deep");
+    assert!(matches!(cli.command, Commands::Cleanup { deep: true, .. }));
+    assert!(Cli::try_parse_from(["fontlift", "cleanup", "--deep", "--at-reboot"]).is_err());
+
+    // A manager that can only clear everything refuses a narrower selection.
+    let runtime = Runtime::new().expect("runtime");
+    let manager = Arc::new(RecordingManager::default());
+    let cleanup = CleanupOptions {
+        cache_only: true,
+        caches: Some(targets),
+        ..CleanupOptions::default()
+    };
+    let result = runtime.block_on(handle_cleanup_command(
+        manager.clone(),
+        false,
+        cleanup,
+        OperationOptions::new(false, true, false),
+    ));
+    assert!(matches!(result, Err(FontError::UnsupportedOperation(_))));
+    assert!(manager.cache_clears.lock().expect("lock").is_empty());
+}
+
 #[test]
 fn uninstall_by_name_checks_both_scopes() {
     let runtime = Runtime::new().expect("runtime");
@@ -423,6 +957,45 @@ fn uninstall_by_name_checks_both_scopes() {
     );
 }
 
+#[test]
+fn uninstall_by_name_falls_back_to_the_other_scope_when_one_fails() {
+    use fontlift_core::testing::{FakeFontManager, FakeOperation};
+
+    let runtime = Runtime::new().expect("runtime");
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let font = tmp.path().join("Fallback-Regular.ttf");
+    fs::write(&font, b"test").expect("write font");
+
+    let manager = Arc::new(FakeFontManager::new(tmp.path().join("root")));
+    for scope in [FontScope::User, FontScope::System] {
+        manager
+            .install_font(&FontliftFontSource::new(font.clone()).with_scope(Some(scope)))
+            .expect("install into fake registry");
+    }
+    manager.fail_next(
+        FakeOperation::Uninstall,
+        FontError::PermissionDenied("user font in use".to_string()),
+    );
+
+    runtime
+        .block_on(handle_uninstall_command(
+            manager.clone(),
+            Some("Fallback-Regular".to_string()),
+            Vec::new(),
+            false,
+            OperationOptions::new(false, true, false),
+        ))
+        .expect("uninstall should fall back to system scope");
+
+    let installed = |scope| {
+        manager
+            .is_font_installed(&FontliftFontSource::new(font.clone()).with_scope(Some(scope)))
+            .expect("installed check")
+    };
+    assert!(installed(FontScope::User), "the failed user copy stays");
+    assert!(!installed(FontScope::System));
+}
+
 #[test]
 fn completions_include_core_commands() {
     let mut buffer = Vec::new();
@@ -448,7 +1021,7 @@ fn subcommand_aliases_match_legacy() {
 
     // install alias
     let cli = Cli::try_parse_from(["fontlift", "i", "font.ttf"]).expect("alias i");
-    assert!(matches!(cli.command, Commands::Install { .. }));
+    assert!(matches!(cli.command, Commands::Install(_)));
 
     // uninstall alias
     let cli = Cli::try_parse_from(["fontlift", "u", "-n", "FontName"]).expect("alias u");
@@ -480,17 +1053,52 @@ fn clap_error_exit_codes_match_legacy() {
     );
 }
 
+#[test]
+fn error_categories_have_distinct_exit_codes() {
+    let path = PathBuf::from("/fonts/Missing.ttf");
+    let cases = [
+        (FontError::FontNotFound(path.clone()), 2),
+        (FontError::PermissionDenied("admin".into()), 3),
+        (FontError::SystemFontProtection(path.clone()), 3),
+        (FontError::PolicyDenied("locked".into()), 3),
+        (FontError::InvalidFormat("bad cmap".into()), 4),
+        (FontError::LegacyType1(path.clone()), 4),
+        (FontError::AlreadyInstalled(path.clone()), 5),
+        (FontError::RegistrationFailed("GDI".into()), 6),
+        (FontError::VerificationFailed("drift".into()), 7),
+        (FontError::InvalidArgument("no inputs".into()), 8),
+        (FontError::UnsupportedOperation("linux".into()), 1),
+        (FontError::IoError(std::io::Error::other("disk")), 1),
+    ];
+    for (error, code) in cases {
+        assert_eq!(exit_code_for_error(&error), code, "{:?}", error.code());
+    }
+
+    // A missing file reaches the exit code as "not found".
+    let error = handle_info_command(&path, 0, false).expect_err("missing font");
+    assert_eq!(exit_code_for_error(&error), 2);
+}
+
+#[test]
+fn help_documents_exit_codes() {
+    use clap::CommandFactory;
+
+    let help = Cli::command().render_help().to_string();
+    assert!(help.contains("Exit codes:"), "{help}");
+    assert!(help.contains("2  font not found"), "{help}");
+}
+
 #[test]
 fn validation_strictness_presets_parse() {
     // Default is Normal
     let cli = Cli::try_parse_from(["fontlift", "install", "font.ttf"]).expect("default strictness");
-    let Commands::Install {
-        validation_strictness,
-        ..
-    } = cli.command
-    else {
+    let Commands::Install(args) = cli.command else {
         panic!("expected Install");
     };
+    let InstallArgs {
+        validation_strictness,
+        ..
+    } = *args;
     assert!(matches!(
         validation_strictness,
         ValidationStrictness::Normal
@@ -505,13 +1113,13 @@ fn validation_strictness_presets_parse() {
         "lenient",
     ])
     .expect("lenient");
-    let Commands::Install {
-        validation_strictness,
-        ..
-    } = cli.command
-    else {
+    let Commands::Install(args) = cli.command else {
         panic!("expected Install");
     };
+    let InstallArgs {
+        validation_strictness,
+        ..
+    } = *args;
     assert!(matches!(
         validation_strictness,
         ValidationStrictness::Lenient
@@ -526,13 +1134,13 @@ fn validation_strictness_presets_parse() {
         "paranoid",
     ])
     .expect("paranoid");
-    let Commands::Install {
-        validation_strictness,
-        ..
-    } = cli.command
-    else {
+    let Commands::Install(args) = cli.command else {
         panic!("expected Install");
     };
+    let InstallArgs {
+        validation_strictness,
+        ..
+    } = *args;
     assert!(matches!(
         validation_strictness,
         ValidationStrictness::Paranoid
@@ -543,25 +1151,194 @@ fn validation_strictness_presets_parse() {
 fn no_validate_flag_parses() {
     let cli =
         Cli::try_parse_from(["fontlift", "install", "font.ttf", "--no-validate"]).expect("parse");
-    let Commands::Install { no_validate, .. } = cli.command else {
+    let Commands::Install(args) = cli.command else {
         panic!("expected Install");
     };
+    let InstallArgs { no_validate, .. } = *args;
     assert!(no_validate, "--no-validate should set flag to true");
 }
 
 #[test]
-fn help_text_includes_all_commands() {
-    use clap::CommandFactory;
+fn install_reason_flags_parse() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "font.ttf", "--profile", "web"])
+        .expect("parse profile");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs {
+        profile,
+        dependency_of,
+        ..
+    } = *args;
+    assert_eq!(profile.as_deref(), Some("web"));
+    assert!(dependency_of.is_none());
 
-    let mut cmd = Cli::command();
-    let help = cmd.render_help().to_string();
+    let err = Cli::try_parse_from([
+        "fontlift",
+        "install",
+        "font.ttf",
+        "--profile",
+        "web",
+        "--dependency-of",
+        "kit",
+    ]);
+    assert!(err.is_err(), "--profile and --dependency-of conflict");
+
+    let cli = Cli::try_parse_from(["fontlift", "list", "--why", "Inter-Bold"]).expect("parse why");
+    let Commands::List { why, .. } = cli.command else {
+        panic!("expected List");
+    };
+    assert_eq!(why.as_deref(), Some("Inter-Bold"));
+}
 
-    // Verify all main commands are listed in help
-    assert!(help.contains("list"), "help should mention list command");
-    assert!(
-        help.contains("install"),
-        "help should mention install command"
-    );
+#[test]
+fn verify_requires_catalog() {
+    let cli = Cli::try_parse_from(["fontlift", "verify", "--catalog", "acme.json"])
+        .expect("parse verify");
+    let Commands::Verify { catalog, .. } = cli.command else {
+        panic!("expected Verify");
+    };
+    assert_eq!(catalog, Some(PathBuf::from("acme.json")));
+
+    assert!(Cli::try_parse_from(["fontlift", "verify"]).is_err());
+    for other in [
+        &["--all"][..],
+        &["Inter.ttf"],
+        &["--manifest", "fonts.json", "--all"],
+    ] {
+        let mut args = vec!["fontlift", "verify", "--catalog", "acme.json"];
+        args.extend_from_slice(other);
+        assert!(
+            Cli::try_parse_from(args).is_err(),
+            "--catalog with {other:?}"
+        );
+    }
+}
+
+#[test]
+fn verify_all_or_paths_checks_file_integrity() {
+    let cli = Cli::try_parse_from(["fontlift", "verify", "--all"]).expect("parse verify --all");
+    let Commands::Verify { all, paths, .. } = cli.command else {
+        panic!("expected Verify");
+    };
+    assert!(all && paths.is_empty());
+    assert!(Cli::try_parse_from(["fontlift", "verify", "--all", "Inter.ttf"]).is_err());
+
+    let runtime = Runtime::new().expect("runtime");
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let font = fontlift_testfonts::TestFont::new("Verify Sans")
+        .write_to(tmp.path())
+        .expect("write font");
+    let verify = |path: &PathBuf| {
+        runtime.block_on(handle_verify_integrity_command(
+            Arc::new(RecordingManager::default()),
+            Some(vec![path.clone()]),
+            None,
+            false,
+            OperationOptions::new(false, true, false),
+        ))
+    };
+    verify(&font).expect("well-formed font verifies");
+
+    let truncated = tmp.path().join("Truncated.ttf");
+    fs::write(&truncated, &fs::read(&font).expect("read font")[..40]).expect("write");
+    assert!(matches!(
+        verify(&truncated),
+        Err(FontError::VerificationFailed(_))
+    ));
+    assert!(matches!(
+        verify(&tmp.path().join("Missing.ttf")),
+        Err(FontError::FontNotFound(_))
+    ));
+}
+
+#[test]
+fn history_records_finished_commands_and_filters_by_age() {
+    let cli = Cli::try_parse_from(["fontlift", "history", "--since", "7d"]).expect("parse");
+    let Commands::History { since } = cli.command else {
+        panic!("expected History");
+    };
+    assert_eq!(
+        since,
+        Some(std::time::Duration::from_secs(7 * 24 * 60 * 60))
+    );
+    assert!(Cli::try_parse_from(["fontlift", "history", "--since", "week"]).is_err());
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let log = tmp.path().join("history.jsonl");
+    std::env::set_var("FONTLIFT_HISTORY_PATH", &log);
+
+    let runtime = Runtime::new().expect("runtime");
+    let manager = Arc::new(RecordingManager::default());
+    let cleanup = |dry_run| {
+        runtime.block_on(handle_cleanup_command(
+            manager.clone(),
+            true,
+            CleanupOptions {
+                cache_only: true,
+                ..CleanupOptions::default()
+            },
+            OperationOptions::new(dry_run, true, false),
+        ))
+    };
+    cleanup(true).expect("dry run");
+    cleanup(false).expect("cleanup");
+    handle_history_command(Some(std::time::Duration::from_secs(60)), true).expect("query");
+    std::env::remove_var("FONTLIFT_HISTORY_PATH");
+
+    // Other tests may run commands while the variable is set, so look for
+    // this one among whatever was recorded.
+    let entries = fontlift_core::history::load_history_from(&log).expect("history");
+    assert!(
+        entries.iter().any(|entry| {
+            entry.operation == fontlift_core::history::HistoryOperation::Cleanup
+                && entry.scope == FontScope::System
+                && entry.outcome == fontlift_core::history::HistoryOutcome::Succeeded
+        }),
+        "{entries:?}"
+    );
+}
+
+#[test]
+fn sha256_requires_exactly_one_url_input() {
+    let runtime = Runtime::new().expect("runtime");
+    let manager = Arc::new(RecordingManager::default());
+    let opts = OperationOptions::new(true, true, false);
+
+    let result = runtime.block_on(handle_install_command(
+        manager.clone(),
+        vec![PathBuf::from("/fonts/Local.ttf")],
+        InstallOptions::new(false, false, ValidationStrictness::Normal, false)
+            .with_sha256(Some("00".repeat(32))),
+        opts,
+    ));
+    assert!(result.is_err(), "--sha256 without a URL must be rejected");
+
+    // Dry-run never downloads, so a URL-only install is a no-op.
+    runtime
+        .block_on(handle_install_command(
+            manager.clone(),
+            vec![PathBuf::from("https://example.com/Remote.ttf")],
+            InstallOptions::new(false, true, ValidationStrictness::Normal, false),
+            opts,
+        ))
+        .expect("dry-run URL install");
+    assert!(manager.installs.lock().expect("lock").is_empty());
+}
+
+#[test]
+fn help_text_includes_all_commands() {
+    use clap::CommandFactory;
+
+    let mut cmd = Cli::command();
+    let help = cmd.render_help().to_string();
+
+    // Verify all main commands are listed in help
+    assert!(help.contains("list"), "help should mention list command");
+    assert!(
+        help.contains("install"),
+        "help should mention install command"
+    );
     assert!(
         help.contains("uninstall"),
         "help should mention uninstall command"
@@ -612,3 +1389,2313 @@ fn shell_completions_generate_for_all_shells() {
         );
     }
 }
+
+#[cfg(feature = "script")]
+mod script_tests {
+    use super::*;
+    use crate::script::{plan_script, ScriptAction};
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/fixtures/fonts")
+            .join(name)
+    }
+
+    #[test]
+    fn script_filters_files_by_weight_and_queues_installs() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let regular = tmp.path().join("AtkinsonHyperlegible-Regular.ttf");
+        fs::copy(fixture("AtkinsonHyperlegible-Regular.ttf"), &regular).expect("copy");
+        fs::write(tmp.path().join("Unknown-Black.ttf"), b"not a font").expect("write");
+
+        let source = format!(
+            r#"
+            for font in fonts_in({:?}) {{
+                if font.weight == 400 {{
+                    install(font.path, "system");
+                }}
+            }}
+            "#,
+            tmp.path().display().to_string()
+        );
+
+        let opts = OperationOptions::new(true, true, false);
+        let actions = plan_script(&source, Vec::new(), opts).expect("plan");
+        assert_eq!(
+            actions,
+            vec![ScriptAction::Install {
+                path: regular,
+                scope: FontScope::System,
+            }]
+        );
+    }
+
+    #[test]
+    fn script_searches_installed_snapshot_for_uninstalls() {
+        let installed = vec![
+            sample_font("/fonts/Alpha.ttf", "Alpha-Regular"),
+            sample_font("/fonts/Beta.ttf", "Beta-Bold"),
+        ];
+        let source = r#"
+            for font in search("beta") { uninstall(font.postscript_name); }
+            if installed().len() != 2 { throw "snapshot missing fonts"; }
+        "#;
+
+        let opts = OperationOptions::new(true, true, false);
+        let actions = plan_script(source, installed, opts).expect("plan");
+        assert_eq!(
+            actions,
+            vec![ScriptAction::Uninstall {
+                name: "Beta-Bold".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn script_sandbox_rejects_eval_bad_scopes_and_runaway_loops() {
+        let opts = OperationOptions::new(true, true, false);
+        for source in [
+            r#"eval("install(\"/tmp/x.ttf\")")"#,
+            r#"install("/tmp/x.ttf", "global")"#,
+            "loop {}",
+        ] {
+            let err = plan_script(source, Vec::new(), opts).expect_err(source);
+            assert!(matches!(err, FontError::ScriptError(_)), "{source}: {err}");
+        }
+    }
+
+    #[test]
+    fn script_command_enforces_max_actions_and_dry_run() {
+        let runtime = Runtime::new().expect("runtime");
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let font = tmp.path().join("Scripted.ttf");
+        fs::write(&font, b"test").expect("write font");
+        let script = tmp.path().join("rules.rhai");
+        fs::write(
+            &script,
+            format!(
+                "install({:?}); install({:?});",
+                font.display().to_string(),
+                font.display().to_string()
+            ),
+        )
+        .expect("write script");
+
+        let manager = Arc::new(RecordingManager::default());
+        let opts = OperationOptions::new(true, true, false);
+
+        let err = runtime
+            .block_on(handle_script_command(
+                manager.clone(),
+                ScriptCommand::Run {
+                    file: script.clone(),
+                    max_actions: 1,
+                },
+                opts,
+            ))
+            .expect_err("too many actions");
+        assert!(matches!(err, FontError::ScriptError(_)));
+
+        runtime
+            .block_on(handle_script_command(
+                manager.clone(),
+                ScriptCommand::Run {
+                    file: script,
+                    max_actions: 10,
+                },
+                opts,
+            ))
+            .expect("dry-run script");
+        assert!(
+            manager.installs.lock().expect("lock").is_empty(),
+            "dry-run script should not call install_font"
+        );
+    }
+}
+
+#[test]
+fn google_install_collects_repeated_variants() {
+    let cli = Cli::try_parse_from([
+        "fontlift",
+        "google",
+        "install",
+        "Noto Sans",
+        "--variant",
+        "regular",
+        "--variant",
+        "700",
+        "--admin",
+    ])
+    .expect("parse google install");
+    let Commands::Google {
+        command:
+            GoogleCommand::Install {
+                family,
+                variants,
+                admin,
+                max_downloads,
+                ..
+            },
+    } = cli.command
+    else {
+        panic!("expected google install");
+    };
+    assert_eq!(family, "Noto Sans");
+    assert_eq!(variants, vec!["regular", "700"]);
+    assert!(admin);
+    assert_eq!(max_downloads, 4);
+
+    assert!(Cli::try_parse_from(["fontlift", "google", "search"]).is_err());
+}
+
+#[test]
+fn audit_parses_rule_selection_lists() {
+    let cli = Cli::try_parse_from([
+        "fontlift",
+        "audit",
+        "--only",
+        "missing-file,duplicate-postscript-name",
+        "--skip",
+        "unsupported-extension",
+    ])
+    .expect("parse audit");
+    let Commands::Audit { only, skip, .. } = cli.command else {
+        panic!("expected Audit");
+    };
+    assert_eq!(only, vec!["missing-file", "duplicate-postscript-name"]);
+    assert_eq!(skip, vec!["unsupported-extension"]);
+}
+
+#[test]
+fn audit_rejects_unknown_rules_before_listing_fonts() {
+    let runtime = Runtime::new().expect("runtime");
+    let manager = Arc::new(RecordingManager::default());
+    let audit = AuditOptions {
+        only: vec!["no-such-rule".to_string()],
+        ..AuditOptions::default()
+    };
+
+    let result = runtime.block_on(handle_audit_command(
+        manager,
+        audit,
+        false,
+        OperationOptions::new(false, true, false),
+    ));
+    assert!(result.is_err());
+}
+
+#[test]
+fn export_and_manifest_install_parse() {
+    let cli = Cli::try_parse_from(["fontlift", "export", "fonts.json"]).expect("parse export");
+    let Commands::Export { output } = cli.command else {
+        panic!("expected Export");
+    };
+    assert_eq!(output, PathBuf::from("fonts.json"));
+
+    let cli = Cli::try_parse_from(["fontlift", "install", "--manifest", "fonts.json"])
+        .expect("manifest install needs no font inputs");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs {
+        manifest,
+        font_inputs,
+        ..
+    } = *args;
+    assert_eq!(manifest, Some(PathBuf::from("fonts.json")));
+    assert!(font_inputs.is_empty());
+
+    assert!(Cli::try_parse_from(["fontlift", "install"]).is_err());
+    assert!(Cli::try_parse_from([
+        "fontlift",
+        "install",
+        "--manifest",
+        "fonts.json",
+        "--sha256",
+        "00",
+    ])
+    .is_err());
+}
+
+#[test]
+fn manifest_install_reports_fonts_it_cannot_find() {
+    use fontlift_core::install_db::InstallDb;
+    use fontlift_core::manifest::Manifest;
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let present = tmp.path().join("Present-Regular.ttf");
+    fs::write(&present, b"present").expect("write font");
+    let absent = tmp.path().join("Absent-Regular.ttf");
+    fs::write(&absent, b"absent").expect("write font");
+
+    let manifest = Manifest::from_installed(
+        &[
+            sample_font(&present.to_string_lossy(), "Present-Regular"),
+            sample_font(&absent.to_string_lossy(), "Absent-Regular"),
+        ],
+        &InstallDb::new(),
+    );
+    let manifest_path = tmp.path().join("fonts.json");
+    manifest.save(&manifest_path).expect("save manifest");
+    fs::remove_file(&absent).expect("remove font");
+
+    let runtime = Runtime::new().expect("runtime");
+    let err = runtime
+        .block_on(handle_manifest_install_command(
+            Arc::new(RecordingManager::default()),
+            manifest_path,
+            Vec::new(),
+            InstallOptions::new(false, false, ValidationStrictness::Normal, false),
+            OperationOptions::new(true, true, false),
+        ))
+        .expect_err("missing font must be reported");
+    let message = err.to_string();
+    assert!(message.contains("1 of 2"), "{message}");
+    assert!(message.contains("Absent-Regular"), "{message}");
+}
+
+#[test]
+fn on_conflict_defaults_to_replace() {
+    use crate::args::OnConflict;
+    use fontlift_core::conflicts::ConflictPolicy;
+
+    let parse = |args: &[&str]| {
+        let cli = Cli::try_parse_from(args).expect("parse install");
+        let Commands::Install(args) = cli.command else {
+            panic!("expected Install");
+        };
+        let InstallArgs { on_conflict, .. } = *args;
+        to_core_conflict_policy(on_conflict)
+    };
+    assert_eq!(
+        parse(&["fontlift", "install", "a.ttf"]),
+        ConflictPolicy::Replace
+    );
+    assert_eq!(
+        parse(&["fontlift", "install", "a.ttf", "--on-conflict", "keep-both"]),
+        ConflictPolicy::KeepBoth
+    );
+    assert_eq!(
+        to_core_conflict_policy(OnConflict::Skip),
+        ConflictPolicy::Skip
+    );
+    assert!(Cli::try_parse_from([
+        "fontlift",
+        "install",
+        "a.ttf",
+        "--temp",
+        "--on-conflict",
+        "skip",
+    ])
+    .is_err());
+}
+
+#[test]
+fn rename_duplicates_needs_a_copy_to_rename() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "a.ttf", "--rename-duplicates"])
+        .expect("parse install --rename-duplicates");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs {
+        rename_duplicates, ..
+    } = *args;
+    assert!(rename_duplicates);
+    for flag in ["--inplace", "--temp"] {
+        assert!(
+            Cli::try_parse_from(["fontlift", "install", "a.ttf", "--rename-duplicates", flag])
+                .is_err()
+        );
+    }
+}
+
+#[test]
+fn name_pattern_renames_installed_copies() {
+    let cli = Cli::try_parse_from([
+        "fontlift",
+        "install",
+        "a.ttf",
+        "--name-pattern",
+        "{family}-{style}.{ext}",
+    ])
+    .expect("parse install --name-pattern");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs { name_pattern, .. } = *args;
+    assert_eq!(name_pattern.as_deref(), Some("{family}-{style}.{ext}"));
+    assert!(Cli::try_parse_from([
+        "fontlift",
+        "install",
+        "a.ttf",
+        "--name-pattern",
+        "{family}.{ext}",
+        "--inplace",
+    ])
+    .is_err());
+
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/fonts/AtkinsonHyperlegible-Regular.ttf");
+    let pattern = NamePattern::parse("{family}-{style}.{ext}").expect("pattern");
+    let mut taken = HashSet::new();
+    let name = |pattern: Option<&NamePattern>, source: &str, taken: &mut HashSet<String>| {
+        crate::ops::installed_file_name(&fixture, source, pattern, taken)
+    };
+    assert_eq!(
+        name(Some(&pattern), "download (1).TTF", &mut taken),
+        "Atkinson Hyperlegible-Regular.ttf"
+    );
+    assert_eq!(
+        name(Some(&pattern), "copy.ttf", &mut taken),
+        "Atkinson Hyperlegible-Regular-2.ttf"
+    );
+    assert_eq!(name(None, "CON.ttf", &mut taken), "CON_.ttf");
+}
+
+#[test]
+fn revalidate_needs_validation() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "a.ttf", "--revalidate"])
+        .expect("parse install --revalidate");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs { revalidate, .. } = *args;
+    assert!(revalidate);
+    assert!(Cli::try_parse_from([
+        "fontlift",
+        "install",
+        "a.ttf",
+        "--revalidate",
+        "--no-validate"
+    ])
+    .is_err());
+}
+
+#[test]
+fn warm_caches_applies_to_persistent_installs_only() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "a.ttf", "--warm-caches"])
+        .expect("parse install --warm-caches");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs { warm_caches, .. } = *args;
+    assert!(warm_caches);
+    assert!(
+        Cli::try_parse_from(["fontlift", "install", "a.ttf", "--warm-caches", "--temp"]).is_err()
+    );
+}
+
+#[test]
+fn upgrade_only_excludes_other_conflict_policies() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "a.ttf", "--upgrade-only"])
+        .expect("parse install --upgrade-only");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs { upgrade_only, .. } = *args;
+    assert!(upgrade_only);
+    assert!(Cli::try_parse_from([
+        "fontlift",
+        "install",
+        "a.ttf",
+        "--upgrade-only",
+        "--on-conflict",
+        "skip",
+    ])
+    .is_err());
+}
+
+#[test]
+fn requirements_install_parses_and_reports_unmet_fonts() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "--requirements", "Brochure Folder"])
+        .expect("requirements install needs no font inputs");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs { requirements, .. } = *args;
+    assert_eq!(requirements, Some(PathBuf::from("Brochure Folder")));
+    assert!(Cli::try_parse_from([
+        "fontlift",
+        "install",
+        "--requirements",
+        "fonts.txt",
+        "--manifest",
+        "fonts.json",
+    ])
+    .is_err());
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let list = tmp.path().join("fonts.txt");
+    fs::write(&list, "Missing-Regular\n").expect("write requirements");
+    let runtime = Runtime::new().expect("runtime");
+    let err = runtime
+        .block_on(handle_requirements_install_command(
+            Arc::new(RecordingManager::default()),
+            list,
+            Vec::new(),
+            InstallOptions::new(false, false, ValidationStrictness::Normal, false),
+            false,
+            OperationOptions::new(true, true, false),
+        ))
+        .expect_err("unmet requirement must fail");
+    let message = err.to_string();
+    assert!(message.contains("1 of 1"), "{message}");
+    assert!(message.contains("Missing-Regular"), "{message}");
+}
+
+#[test]
+fn migrate_parses_and_dry_run_installs_nothing() {
+    let cli = Cli::try_parse_from([
+        "fontlift",
+        "migrate",
+        "--from",
+        "rightfont",
+        "--library",
+        "Fonts.rightfontlibrary",
+        "--store",
+    ])
+    .expect("parse migrate");
+    let Commands::Migrate {
+        from,
+        library,
+        store,
+        ..
+    } = cli.command
+    else {
+        panic!("expected Migrate");
+    };
+    assert_eq!(
+        to_core_migration_source(from),
+        fontlift_core::migrate::MigrationSource::RightFont
+    );
+    assert_eq!(library, PathBuf::from("Fonts.rightfontlibrary"));
+    assert!(store);
+    assert!(Cli::try_parse_from(["fontlift", "migrate", "--from", "fontbase"]).is_err());
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let manager = Arc::new(RecordingManager::default());
+    let runtime = Runtime::new().expect("runtime");
+    runtime
+        .block_on(handle_migrate_command(
+            manager.clone(),
+            fontlift_core::migrate::MigrationSource::FontBase,
+            tmp.path().to_path_buf(),
+            true,
+            InstallOptions::new(false, false, ValidationStrictness::Normal, true),
+            false,
+            OperationOptions::new(true, true, false),
+        ))
+        .expect("empty library migrates");
+    assert!(manager.installs.lock().expect("lock").is_empty());
+}
+
+#[test]
+fn notify_is_a_global_flag() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "font.ttf", "--notify"])
+        .expect("--notify after the subcommand");
+    assert!(cli.notify);
+    let cli = Cli::try_parse_from(["fontlift", "remove", "-n", "Font"]).expect("parse remove");
+    assert!(!cli.notify);
+}
+
+#[test]
+fn lang_is_a_global_option() {
+    let cli = Cli::try_parse_from(["fontlift", "info", "font.ttf", "--lang", "ja"])
+        .expect("--lang after the subcommand");
+    assert_eq!(cli.lang.as_deref(), Some("ja"));
+    let cli = Cli::try_parse_from(["fontlift", "list"]).expect("parse list");
+    assert_eq!(cli.lang, None);
+}
+
+#[test]
+fn protection_policy_blocks_removal_of_protected_fonts() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let protected_dir = tmp.path().join("corp-fonts");
+    fs::create_dir_all(&protected_dir).expect("create dir");
+    let font = protected_dir.join("CorpSans-Regular.ttf");
+    fs::write(&font, b"font").expect("write font");
+    let policy_path = tmp.path().join("protection.json");
+    fs::write(
+        &policy_path,
+        serde_json::json!({ "protected_dirs": [protected_dir] }).to_string(),
+    )
+    .expect("write policy");
+
+    std::env::set_var("FONTLIFT_PROTECTION_POLICY_PATH", &policy_path);
+    let runtime = Runtime::new().expect("runtime");
+    let result = runtime.block_on(handle_remove_command(
+        Arc::new(RecordingManager::default()),
+        None,
+        vec![font.clone()],
+        false,
+        false,
+        OperationOptions::new(false, true, false),
+    ));
+    std::env::remove_var("FONTLIFT_PROTECTION_POLICY_PATH");
+
+    assert!(matches!(result, Err(FontError::SystemFontProtection(_))));
+    assert!(font.exists(), "protected font must not be deleted");
+}
+
+#[test]
+fn managed_policy_denies_installs_it_rules_out() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let blocked = fontlift_testfonts::TestFont::new("Comic Sans")
+        .write_to(tmp.path())
+        .expect("write font");
+    let policy_path = tmp.path().join("policy.json");
+    fs::write(
+        &policy_path,
+        serde_json::json!({
+            "allow_system_scope": false,
+            "validation_strictness": "normal",
+            "blocked_families": ["comic*"]
+        })
+        .to_string(),
+    )
+    .expect("write policy");
+
+    std::env::set_var("FONTLIFT_POLICY_PATH", &policy_path);
+    let runtime = Runtime::new().expect("runtime");
+    let manager = Arc::new(RecordingManager::default());
+    let install = |admin: bool, validate: bool| {
+        runtime.block_on(handle_install_command(
+            manager.clone(),
+            vec![blocked.clone()],
+            InstallOptions::new(admin, validate, ValidationStrictness::Lenient, false),
+            OperationOptions::new(false, true, false),
+        ))
+    };
+    let system = install(true, true);
+    let unvalidated = install(false, false);
+    let family = install(false, true);
+    let uninstall = runtime.block_on(handle_uninstall_command(
+        manager.clone(),
+        None,
+        vec![blocked.clone()],
+        true,
+        OperationOptions::new(false, true, false),
+    ));
+    std::env::remove_var("FONTLIFT_POLICY_PATH");
+
+    for (what, result) in [
+        ("system scope", system),
+        ("skipped validation", unvalidated),
+        ("blocked family", family),
+        ("system uninstall", uninstall),
+    ] {
+        assert!(
+            matches!(result, Err(FontError::PolicyDenied(_))),
+            "{what}: {result:?}"
+        );
+    }
+    assert!(manager.installs.lock().unwrap().is_empty());
+}
+
+#[test]
+fn pair_parses_family_and_reports_unknown_families() {
+    let cli = Cli::try_parse_from(["fontlift", "pair", "Playfair Display", "--limit", "3"])
+        .expect("parse pair");
+    match cli.command {
+        Commands::Pair { family, limit } => {
+            assert_eq!(family, "Playfair Display");
+            assert_eq!(limit, 3);
+        }
+        _ => panic!("expected pair"),
+    }
+
+    let runtime = Runtime::new().expect("runtime");
+    let result = runtime.block_on(handle_pair_command(
+        Arc::new(RecordingManager::default()),
+        "Playfair Display".to_string(),
+        5,
+        false,
+    ));
+    assert!(matches!(result, Err(FontError::FontNotFound(_))));
+}
+
+#[derive(Default)]
+struct RebootScheduleManager {
+    files: Vec<PathBuf>,
+    cache_clears: Mutex<usize>,
+}
+
+impl FontManager for RebootScheduleManager {
+    fn install_font(&self, _source: &FontliftFontSource) -> fontlift_core::FontResult<()> {
+        Ok(())
+    }
+
+    fn uninstall_font(&self, _source: &FontliftFontSource) -> fontlift_core::FontResult<()> {
+        Ok(())
+    }
+
+    fn remove_font(&self, _source: &FontliftFontSource) -> fontlift_core::FontResult<()> {
+        Ok(())
+    }
+
+    fn is_font_installed(&self, _source: &FontliftFontSource) -> fontlift_core::FontResult<bool> {
+        Ok(false)
+    }
+
+    fn list_installed_fonts(&self) -> fontlift_core::FontResult<Vec<FontliftFontFaceInfo>> {
+        Ok(Vec::new())
+    }
+
+    fn clear_font_caches(
+        &self,
+        _strategy: CacheClearStrategy,
+    ) -> fontlift_core::FontResult<CacheClearResult> {
+        *self.cache_clears.lock().expect("lock") += 1;
+        Ok(CacheClearResult::default())
+    }
+
+    fn schedule_cache_clear_at_reboot(
+        &self,
+        _scope: FontScope,
+    ) -> fontlift_core::FontResult<Vec<PathBuf>> {
+        Ok(self.files.clone())
+    }
+}
+
+#[test]
+fn cleanup_at_reboot_records_the_schedule_instead_of_clearing() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let cache = tmp.path().join("FNTCACHE.DAT");
+    fs::write(&cache, b"cache").expect("write cache");
+    std::env::set_var("FONTLIFT_JOURNAL_PATH", tmp.path().join("journal.json"));
+
+    let cli = Cli::try_parse_from(["fontlift", "cleanup", "--admin", "--at-reboot"])
+        .expect("parse --at-reboot");
+    assert!(matches!(
+        cli.command,
+        Commands::Cleanup {
+            at_reboot: true,
+            ..
+        }
+    ));
+    assert!(Cli::try_parse_from(["fontlift", "cleanup", "--prune-only", "--at-reboot"]).is_err());
+    assert!(matches!(
+        Cli::try_parse_from(["fontlift", "cleanup", "--limit", "500"])
+            .expect("parse --limit")
+            .command,
+        Commands::Cleanup {
+            limit: Some(500),
+            ..
+        }
+    ));
+    assert!(Cli::try_parse_from(["fontlift", "cleanup", "--cache-only", "--limit", "5"]).is_err());
+
+    let manager = Arc::new(RebootScheduleManager {
+        files: vec![cache.clone()],
+        ..Default::default()
+    });
+    let runtime = Runtime::new().expect("runtime");
+    runtime
+        .block_on(handle_cleanup_command(
+            manager.clone(),
+            true,
+            CleanupOptions {
+                cache_only: true,
+                at_reboot: true,
+                ..CleanupOptions::default()
+            },
+            OperationOptions::new(false, true, false),
+        ))
+        .expect("schedule cleanup");
+
+    assert_eq!(*manager.cache_clears.lock().expect("lock"), 0);
+    let journal = fontlift_core::journal::load_journal().expect("journal");
+    let scheduled: Vec<_> = journal
+        .scheduled_cache_clears
+        .iter()
+        .filter(|clear| clear.files.iter().any(|file| file.path == cache))
+        .collect();
+    assert_eq!(scheduled.len(), 1);
+    assert_eq!(scheduled[0].scope, FontScope::System);
+}
+
+#[cfg(unix)]
+#[test]
+fn notify_hooks_run_after_changes_only() {
+    use fontlift_core::history::{HistoryEntry, HistoryOperation};
+    use std::path::Path;
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let log = tmp.path().join("hook.log");
+    let config = tmp.path().join("fontlift.toml");
+    fs::write(
+        &config,
+        format!(
+            "[notify]\ncommand = \"echo $FONTLIFT_EVENT >> '{}'\"\n",
+            log.display()
+        ),
+    )
+    .expect("write config");
+    std::env::set_var("FONTLIFT_CONFIG_PATH", &config);
+
+    let manager = RecordingManager::default();
+    let mut history = HistoryEntry::start(HistoryOperation::Uninstall, FontScope::User);
+    let opts = OperationOptions::new(false, true, false);
+    crate::ops::announce_change(&manager, &history, &opts);
+    assert!(!log.exists(), "nothing changed, so no hook runs");
+
+    history.handled(Path::new("/fonts/Gone.ttf"));
+    crate::ops::announce_change(
+        &manager,
+        &history,
+        &OperationOptions::new(true, true, false),
+    );
+    assert!(!log.exists(), "dry runs change nothing");
+    crate::ops::announce_change(&manager, &history, &opts);
+    assert_eq!(fs::read_to_string(&log).expect("hook ran"), "uninstall\n");
+}
+
+#[test]
+fn failed_directory_installs_roll_back_the_whole_batch() {
+    use fontlift_core::journal::{self, JournalAction};
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    std::env::set_var("FONTLIFT_JOURNAL_PATH", tmp.path().join("journal.json"));
+    let source = tmp.path().join("First-Regular.ttf");
+    let copied = tmp.path().join("Fonts/First-Regular.ttf");
+    fs::write(&source, b"font").expect("write source");
+    fs::create_dir_all(copied.parent().expect("parent")).expect("fonts dir");
+    fs::copy(&source, &copied).expect("copy");
+
+    let manager = RecordingManager::default();
+    let opts = OperationOptions::new(false, true, false);
+    let mut batch = crate::ops::InstallBatch::begin(&manager, 2, &opts).expect("begin");
+    batch
+        .add(
+            &source,
+            vec![
+                JournalAction::CopyFile {
+                    from: source.clone(),
+                    to: copied.clone(),
+                },
+                JournalAction::RegisterFont {
+                    path: copied.clone(),
+                    scope: FontScope::User,
+                },
+            ],
+        )
+        .expect("add");
+    batch.step_done().expect("copied");
+    batch.step_done().expect("registered");
+
+    let journal = journal::load_journal().expect("journal");
+    let entry = journal
+        .incomplete_entries()
+        .into_iter()
+        .find(|entry| {
+            entry
+                .actions
+                .iter()
+                .any(|a| a.description().contains("First"))
+        })
+        .cloned()
+        .expect("batch entry");
+    assert_eq!(entry.items.len(), 1);
+    assert_eq!(entry.finished_items(), 1);
+
+    // The second font failed: dropping the batch undoes the first.
+    drop(batch);
+    assert!(!copied.exists());
+    assert!(source.exists());
+    let journal = journal::load_journal().expect("journal");
+    assert!(journal.find_entry(entry.id).expect("entry").completed);
+}
+
+#[test]
+fn failed_batches_restore_the_fonts_they_replaced() {
+    use fontlift_core::journal::JournalAction;
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    std::env::set_var("FONTLIFT_JOURNAL_PATH", tmp.path().join("journal.json"));
+    let source = tmp.path().join("Replaced-Regular.ttf");
+    let installed = tmp.path().join("Fonts/Replaced-Regular.ttf");
+    fs::write(&source, b"new font").expect("write source");
+    fs::create_dir_all(installed.parent().expect("parent")).expect("fonts dir");
+    fs::write(&installed, b"original font").expect("write installed");
+
+    let manager = RecordingManager::default();
+    let opts = OperationOptions::new(false, true, false);
+    let install_over = || {
+        let mut batch = crate::ops::InstallBatch::begin(&manager, 2, &opts).expect("begin");
+        let backup = batch
+            .backup_for(&source, &installed)
+            .expect("the installed font is moved aside");
+        batch
+            .add(
+                &source,
+                vec![
+                    JournalAction::BackupFile {
+                        path: installed.clone(),
+                        backup: backup.clone(),
+                    },
+                    JournalAction::CopyFile {
+                        from: source.clone(),
+                        to: installed.clone(),
+                    },
+                    JournalAction::RegisterFont {
+                        path: installed.clone(),
+                        scope: FontScope::User,
+                    },
+                ],
+            )
+            .expect("add");
+        batch
+            .move_aside(&installed, backup.clone())
+            .expect("move aside");
+        fs::copy(&source, &installed).expect("copy");
+        batch.step_done().expect("copied");
+        batch.step_done().expect("registered");
+        (batch, backup)
+    };
+
+    // The second font failed: the first is put back as it was.
+    let (batch, backup) = install_over();
+    assert_eq!(fs::read(&backup).expect("backup"), b"original font");
+    drop(batch);
+    assert_eq!(fs::read(&installed).expect("restored"), b"original font");
+    assert!(!backup.exists());
+    assert_eq!(
+        *manager.installs.lock().expect("lock"),
+        [(installed.clone(), FontScope::User)],
+        "the restored font is registered again"
+    );
+
+    // A committed batch keeps the new font and drops the backup.
+    let (batch, backup) = install_over();
+    batch.commit().expect("commit");
+    assert_eq!(fs::read(&installed).expect("installed"), b"new font");
+    assert!(!backup.exists());
+}
+
+#[test]
+fn doctor_registers_interrupted_installs_or_rolls_them_back() {
+    use fontlift_core::journal::{self, JournalAction};
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    std::env::set_var("FONTLIFT_JOURNAL_PATH", tmp.path().join("journal.json"));
+    let copied = tmp.path().join("Copied-Regular.ttf");
+    fs::write(&copied, b"font").expect("write copy");
+    let interrupted_install = |to: &PathBuf| {
+        journal::update_journal(|j| {
+            let id = j.record_operation(
+                vec![
+                    JournalAction::CopyFile {
+                        from: tmp.path().join("Downloads/Copied-Regular.ttf"),
+                        to: to.clone(),
+                    },
+                    JournalAction::RegisterFont {
+                        path: to.clone(),
+                        scope: FontScope::User,
+                    },
+                ],
+                None,
+            );
+            j.mark_step(id, 1)
+        })
+        .expect("record install");
+    };
+    let doctor = |strategy| {
+        let cli = Cli::try_parse_from(["fontlift", "doctor", "--strategy", strategy])
+            .expect("parse --strategy");
+        let Commands::Doctor { strategy, .. } = cli.command else {
+            panic!("expected Doctor");
+        };
+        let manager = Arc::new(RecordingManager::default());
+        Runtime::new()
+            .expect("runtime")
+            .block_on(handle_doctor_command(
+                manager.clone(),
+                false,
+                false,
+                strategy,
+                false,
+                OperationOptions::new(false, true, false),
+            ))
+            .expect("doctor");
+        let installs = manager.installs.lock().expect("lock").clone();
+        installs
+    };
+
+    interrupted_install(&copied);
+    assert_eq!(doctor("forward"), [(copied.clone(), FontScope::User)]);
+    assert!(copied.exists());
+    assert!(journal::load_journal()
+        .expect("journal")
+        .incomplete_entries()
+        .is_empty());
+
+    interrupted_install(&copied);
+    assert!(doctor("back").is_empty());
+    assert!(!copied.exists(), "the orphan copy is deleted");
+    assert!(journal::load_journal()
+        .expect("journal")
+        .incomplete_entries()
+        .is_empty());
+
+    // Both recovered entries are recent, so compaction keeps them.
+    let cli = Cli::try_parse_from(["fontlift", "doctor", "--compact"]).expect("parse --compact");
+    assert!(matches!(
+        cli.command,
+        Commands::Doctor { compact: true, .. }
+    ));
+    Runtime::new()
+        .expect("runtime")
+        .block_on(handle_doctor_command(
+            Arc::new(RecordingManager::default()),
+            false,
+            false,
+            DoctorStrategy::Forward,
+            true,
+            OperationOptions::new(false, true, false),
+        ))
+        .expect("doctor --compact");
+    assert_eq!(journal::load_journal().expect("journal").entries.len(), 2);
+}
+
+#[test]
+fn uninstall_by_original_name_finds_shortened_copy() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let fonts_dir = tmp.path().join("Fonts");
+    fs::create_dir_all(&fonts_dir).expect("fonts dir");
+    let original = "AcmeGroteskDisplayCondensedExtraBoldItalic-VariableFont_opsz,wght.ttf";
+    let short_name = fontlift_core::file_names::shorten_file_name(original, 40).expect("shorten");
+    let installed = fonts_dir.join(&short_name);
+    fs::write(&installed, b"font").expect("write font");
+
+    std::env::set_var(
+        "FONTLIFT_INSTALL_DB_PATH",
+        tmp.path().join("installed.json"),
+    );
+    fontlift_core::install_db::update_install_db(|db| {
+        db.record(
+            installed.clone(),
+            FontScope::User,
+            fontlift_core::install_db::InstallReason::Explicit,
+        )
+        .original_file_name = Some(original.to_string());
+    })
+    .expect("record install");
+
+    let runtime = Runtime::new().expect("runtime");
+    runtime
+        .block_on(handle_uninstall_command(
+            Arc::new(RecordingManager::default()),
+            None,
+            vec![fonts_dir.join(original)],
+            false,
+            OperationOptions::new(false, true, false),
+        ))
+        .expect("uninstall by original name");
+
+    let db = fontlift_core::install_db::load_install_db().expect("db");
+    assert!(db.find(&installed).is_none(), "record should be forgotten");
+}
+
+#[test]
+fn install_no_smoothing_needs_a_copy() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "--no-smoothing", "Pixel.ttf"])
+        .expect("parse install --no-smoothing");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs { no_smoothing, .. } = *args;
+    assert!(no_smoothing);
+    for other in ["--inplace", "--temp"] {
+        assert!(
+            Cli::try_parse_from(["fontlift", "install", "--no-smoothing", other, "Pixel.ttf"])
+                .is_err(),
+            "--no-smoothing with {other}"
+        );
+    }
+}
+
+#[test]
+fn install_link_replaces_the_copy() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "--link", "Inter.ttf"])
+        .expect("parse install --link");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs { link, .. } = *args;
+    assert!(link);
+    for other in ["--copy", "--inplace", "--temp", "--no-smoothing"] {
+        assert!(
+            Cli::try_parse_from(["fontlift", "install", "--link", other, "Inter.ttf"]).is_err(),
+            "--link with {other}"
+        );
+    }
+}
+
+#[test]
+fn install_temp_registers_for_the_session_without_copying() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "--temp", "Trial.ttf"])
+        .expect("parse install --temp");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs { temp, .. } = *args;
+    assert!(temp);
+    assert!(
+        Cli::try_parse_from(["fontlift", "install", "--temp", "--admin", "Trial.ttf"]).is_err()
+    );
+    assert!(
+        Cli::try_parse_from(["fontlift", "install", "--temp", "--inplace", "Trial.ttf"]).is_err()
+    );
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let font = tmp.path().join("Trial.ttf");
+    fs::write(&font, b"font").expect("write font");
+
+    let runtime = Runtime::new().expect("runtime");
+    let manager = Arc::new(RecordingManager::default());
+    let install =
+        InstallOptions::new(false, false, ValidationStrictness::Normal, false).with_temporary(true);
+    runtime
+        .block_on(handle_install_command(
+            manager.clone(),
+            vec![font.clone()],
+            install.clone(),
+            OperationOptions::new(false, true, false),
+        ))
+        .expect("session install");
+
+    assert_eq!(*manager.sessions.lock().expect("lock"), vec![font]);
+    assert!(manager.installs.lock().expect("lock").is_empty());
+
+    let result = runtime.block_on(handle_install_command(
+        manager,
+        vec![PathBuf::from("https://example.com/Remote.ttf")],
+        install,
+        OperationOptions::new(false, true, false),
+    ));
+    assert!(
+        matches!(result, Err(FontError::InvalidArgument(message)) if message.starts_with("--temp"))
+    );
+}
+
+#[test]
+fn install_from_stdin_needs_a_font_file_name() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "-", "--name", "Piped.ttf"])
+        .expect("parse install -");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs {
+        font_inputs, name, ..
+    } = *args;
+    assert_eq!(font_inputs, vec![PathBuf::from("-")]);
+    assert_eq!(name.as_deref(), Some("Piped.ttf"));
+
+    let runtime = Runtime::new().expect("runtime");
+    let manager = Arc::new(RecordingManager::default());
+    let install = |name: Option<&str>| {
+        InstallOptions::new(false, false, ValidationStrictness::Normal, false)
+            .with_stdin_name(name.map(str::to_string))
+    };
+    let run = |inputs: &[&str], install: InstallOptions, dry_run| {
+        runtime.block_on(handle_install_command(
+            manager.clone(),
+            inputs.iter().map(PathBuf::from).collect(),
+            install,
+            OperationOptions::new(dry_run, true, false),
+        ))
+    };
+
+    for (inputs, name, needle) in [
+        (&["-"][..], None, "needs --name"),
+        (&["Other.ttf"][..], Some("Piped.ttf"), "add `-`"),
+        (&["-"][..], Some("../Piped.ttf"), "not a file name"),
+        (&["-"][..], Some("Piped"), "no font extension"),
+    ] {
+        let result = run(inputs, install(name), false);
+        assert!(
+            matches!(&result, Err(FontError::InvalidArgument(message)) if message.contains(needle)),
+            "{inputs:?} {name:?}: {result:?}"
+        );
+    }
+    let result = run(
+        &["-"],
+        install(Some("Piped.ttf")).with_temporary(true),
+        false,
+    );
+    assert!(
+        matches!(result, Err(FontError::InvalidArgument(message)) if message.contains("stdin"))
+    );
+
+    run(&["-"], install(Some("Piped.ttf")), true).expect("dry run");
+    assert!(manager.installs.lock().expect("lock").is_empty());
+}
+
+#[test]
+fn install_verify_refuses_fonts_missing_from_the_checksum_manifest() {
+    assert!(Cli::try_parse_from(["fontlift", "install", "pack", "--pubkey", "key.pub"]).is_err());
+    let cli = Cli::try_parse_from(["fontlift", "install", "pack", "--verify", "pack.sums"])
+        .expect("parse install --verify");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs { verify, pubkey, .. } = *args;
+    assert_eq!(verify, Some(PathBuf::from("pack.sums")));
+    assert_eq!(pubkey, None);
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let listed = tmp.path().join("Listed.ttf");
+    fs::write(&listed, b"listed").expect("write listed");
+    let sums = tmp.path().join("pack.sums");
+    fs::write(
+        &sums,
+        format!(
+            "{}  Listed.ttf\n",
+            fontlift_core::digest::sha256_hex(b"listed")
+        ),
+    )
+    .expect("write sums");
+    let checksums = ChecksumManifest::load(&sums, None).expect("load sums");
+
+    let runtime = Runtime::new().expect("runtime");
+    let manager = Arc::new(RecordingManager::default());
+    // Verification runs before the dry-run plan, so a dry run fails too.
+    let run = |inputs: Vec<PathBuf>| {
+        runtime.block_on(handle_install_command(
+            manager.clone(),
+            inputs,
+            InstallOptions::new(false, false, ValidationStrictness::Normal, false)
+                .with_checksums(Some(checksums.clone())),
+            OperationOptions::new(true, true, false),
+        ))
+    };
+    run(vec![listed.clone()]).expect("listed font passes");
+
+    fs::write(tmp.path().join("Extra.ttf"), b"extra").expect("write extra");
+    let result = run(vec![tmp.path().to_path_buf()]);
+    assert!(
+        matches!(&result, Err(FontError::VerificationFailed(detail)) if detail.contains("Extra.ttf is not listed")),
+        "{result:?}"
+    );
+
+    fs::write(&listed, b"tampered").expect("tamper listed");
+    assert!(matches!(
+        run(vec![listed]),
+        Err(FontError::VerificationFailed(_))
+    ));
+    assert!(manager.installs.lock().expect("lock").is_empty());
+}
+
+#[test]
+fn install_verify_rejects_url_and_archive_inputs() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let pack = tmp.path().join("pack.zip");
+    fs::write(&pack, b"zip").expect("write archive");
+    let sums = tmp.path().join("pack.sums");
+    fs::write(&sums, format!("{}  Font.ttf\n", "0".repeat(64))).expect("write sums");
+    let checksums = ChecksumManifest::load(&sums, None).expect("load sums");
+
+    let runtime = Runtime::new().expect("runtime");
+    let manager = Arc::new(RecordingManager::default());
+    for input in [pack, PathBuf::from("https://example.com/Font.ttf")] {
+        let result = runtime.block_on(handle_install_command(
+            manager.clone(),
+            vec![input],
+            InstallOptions::new(false, false, ValidationStrictness::Normal, false)
+                .with_checksums(Some(checksums.clone())),
+            OperationOptions::new(true, true, false),
+        ));
+        assert!(
+            matches!(&result, Err(FontError::InvalidArgument(detail)) if detail.contains("--verify")),
+            "{result:?}"
+        );
+    }
+    assert!(manager.plans.lock().expect("lock").is_empty());
+}
+
+/// Records private loads and session registrations.
+#[derive(Default)]
+struct PreviewManager {
+    calls: Arc<Mutex<Vec<String>>>,
+}
+
+struct RecordedPrivateFont(Arc<Mutex<Vec<String>>>);
+
+impl fontlift_core::memory::MemoryRegistration for RecordedPrivateFont {
+    fn unregister(&mut self) -> fontlift_core::FontResult<()> {
+        self.0.lock().unwrap().push("unload private".to_string());
+        Ok(())
+    }
+}
+
+impl FontManager for PreviewManager {
+    fn install_font(&self, _source: &FontliftFontSource) -> fontlift_core::FontResult<()> {
+        unreachable!("preview never installs")
+    }
+    fn uninstall_font(&self, _source: &FontliftFontSource) -> fontlift_core::FontResult<()> {
+        unreachable!("preview never uninstalls")
+    }
+    fn remove_font(&self, _source: &FontliftFontSource) -> fontlift_core::FontResult<()> {
+        unreachable!("preview never removes")
+    }
+    fn is_font_installed(&self, _source: &FontliftFontSource) -> fontlift_core::FontResult<bool> {
+        Ok(false)
+    }
+    fn list_installed_fonts(&self) -> fontlift_core::FontResult<Vec<FontliftFontFaceInfo>> {
+        Ok(Vec::new())
+    }
+    fn clear_font_caches(
+        &self,
+        _strategy: CacheClearStrategy,
+    ) -> fontlift_core::FontResult<CacheClearResult> {
+        Ok(CacheClearResult::default())
+    }
+    fn install_font_session(&self, _source: &FontliftFontSource) -> fontlift_core::FontResult<()> {
+        self.calls.lock().unwrap().push("load session".to_string());
+        Ok(())
+    }
+    fn uninstall_font_session(
+        &self,
+        _source: &FontliftFontSource,
+    ) -> fontlift_core::FontResult<()> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push("unload session".to_string());
+        Ok(())
+    }
+    fn register_font_private(
+        &self,
+        source: &FontliftFontSource,
+    ) -> fontlift_core::FontResult<fontlift_core::memory::FontHandle> {
+        self.calls.lock().unwrap().push("load private".to_string());
+        Ok(fontlift_core::memory::FontHandle::new(
+            Box::new(RecordedPrivateFont(self.calls.clone())),
+            vec!["Preview-Regular".to_string()],
+            source.path.display().to_string(),
+        ))
+    }
+}
+
+#[test]
+fn preview_loads_fonts_privately_or_for_the_command() {
+    let cli = Cli::try_parse_from(["fontlift", "preview", "A.ttf", "--", "app", "--flag"])
+        .expect("parse preview");
+    let Commands::Preview { fonts, command } = cli.command else {
+        panic!("expected Preview");
+    };
+    assert_eq!(fonts, vec![PathBuf::from("A.ttf")]);
+    assert_eq!(command, vec!["app", "--flag"]);
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let font = tmp.path().join("Preview.ttf");
+    fs::write(&font, b"font").expect("write font");
+    let manager = Arc::new(PreviewManager::default());
+    let opts = OperationOptions::new(false, true, false);
+
+    handle_preview_command(manager.clone(), vec![font.clone()], Vec::new(), opts)
+        .expect("private preview");
+    assert_eq!(
+        *manager.calls.lock().unwrap(),
+        vec!["load private", "unload private"]
+    );
+
+    #[cfg(unix)]
+    {
+        manager.calls.lock().unwrap().clear();
+        handle_preview_command(
+            manager.clone(),
+            vec![font.clone()],
+            vec!["true".into()],
+            opts,
+        )
+        .expect("command preview");
+        let failed =
+            handle_preview_command(manager.clone(), vec![font], vec!["false".into()], opts);
+        assert!(matches!(failed, Err(FontError::ScriptError(_))));
+        assert_eq!(
+            *manager.calls.lock().unwrap(),
+            vec![
+                "load session",
+                "unload session",
+                "load session",
+                "unload session"
+            ]
+        );
+    }
+}
+
+#[test]
+fn copy_name_parses_one_snippet_kind_and_reports_unknown_faces() {
+    let cli = Cli::try_parse_from([
+        "fontlift",
+        "copy-name",
+        "Atkinson Hyperlegible Bold",
+        "--css",
+        "--no-copy",
+    ])
+    .expect("parse copy-name");
+    let Commands::CopyName {
+        name,
+        css,
+        postscript,
+        no_copy,
+        ..
+    } = cli.command
+    else {
+        panic!("expected CopyName");
+    };
+    assert_eq!(name, "Atkinson Hyperlegible Bold");
+    assert!(css && no_copy && !postscript);
+    assert!(Cli::try_parse_from(["fontlift", "copy-name", "Inter", "--css", "--family"]).is_err());
+
+    let runtime = Runtime::new().expect("runtime");
+    let result = runtime.block_on(handle_copy_name_command(
+        Arc::new(RecordingManager::default()),
+        "Inter".to_string(),
+        fontlift_core::snippets::SnippetKind::Css,
+        false,
+        false,
+        OperationOptions::new(false, true, false),
+    ));
+    assert!(matches!(result, Err(FontError::FontNotFound(_))));
+}
+
+#[test]
+fn info_parses_face_index_and_reports_missing_files() {
+    let cli = Cli::try_parse_from(["fontlift", "info", "Inter.ttc", "--face", "2"]).expect("parse");
+    let Commands::Info { font, face } = cli.command else {
+        panic!("expected info");
+    };
+    assert_eq!(font, PathBuf::from("Inter.ttc"));
+    assert_eq!(face, 2);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let missing = tmp.path().join("Missing-VF.ttf");
+    assert!(matches!(
+        handle_info_command(&missing, 0, true),
+        Err(FontError::FontNotFound(path)) if path == missing
+    ));
+}
+
+#[test]
+fn coverage_fails_when_characters_are_missing_and_find_needs_a_filter() {
+    let cli = Cli::try_parse_from(["fontlift", "coverage", "Inter.ttf", "--text", "€"])
+        .expect("parse coverage");
+    assert!(matches!(cli.command, Commands::Coverage { ref text, .. } if text == "€"));
+    assert!(Cli::try_parse_from(["fontlift", "coverage", "Inter.ttf"]).is_err());
+    let cli = Cli::try_parse_from(["fontlift", "find", "--supports", "€"]).expect("parse find");
+    assert!(matches!(
+        cli.command,
+        Commands::Find {
+            query: None,
+            supports: Some(_)
+        }
+    ));
+    assert!(Cli::try_parse_from(["fontlift", "find"]).is_err());
+
+    let font = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/fonts/AtkinsonHyperlegible-Regular.ttf");
+    handle_coverage_command(&font, "Hello, world", true).expect("latin is covered");
+    assert!(matches!(
+        handle_coverage_command(&font, "Hello 北京", false),
+        Err(FontError::VerificationFailed(_))
+    ));
+}
+
+#[test]
+fn disable_and_enable_parse_and_reject_unknown_fonts() {
+    let cli = Cli::try_parse_from([
+        "fontlift",
+        "disable",
+        "--admin",
+        "/Library/Fonts/Helvetica.ttc",
+    ])
+    .expect("parse disable");
+    assert!(matches!(
+        cli.command,
+        Commands::Disable {
+            admin: true,
+            list: false,
+            ..
+        }
+    ));
+    assert!(Cli::try_parse_from(["fontlift", "disable", "--list", "--name", "Helvetica"]).is_err());
+    assert!(Cli::try_parse_from(["fontlift", "enable"]).is_err());
+
+    let tmp = tempfile::tempdir().unwrap();
+    std::env::set_var("FONTLIFT_VAULT_DIR", tmp.path().join("vault"));
+    let runtime = Runtime::new().expect("runtime");
+    let opts = OperationOptions::new(false, true, false);
+    let missing = tmp.path().join("Gone.ttf");
+    assert!(matches!(
+        runtime.block_on(handle_disable_command(
+            Arc::new(RecordingManager::default()),
+            None,
+            Some(missing.clone()),
+            false,
+            opts,
+        )),
+        Err(FontError::FontNotFound(path)) if path == missing
+    ));
+    assert!(runtime
+        .block_on(handle_enable_command(
+            Arc::new(RecordingManager::default()),
+            "Helvetica".to_string(),
+            opts,
+        ))
+        .is_err());
+}
+
+#[test]
+fn uninstall_by_family_or_pattern_takes_every_match() {
+    assert!(
+        Cli::try_parse_from(["fontlift", "uninstall", "--family", "A*", "--match", "B"]).is_err()
+    );
+    assert!(Cli::try_parse_from(["fontlift", "uninstall", "--family", "A*", "-n", "A"]).is_err());
+    assert!(Cli::try_parse_from(["fontlift", "uninstall", "--match", "B", "B.ttf"]).is_err());
+    let cli = Cli::try_parse_from(["fontlift", "u", "--match", "Bold$", "-y"]).expect("parse");
+    assert!(matches!(
+        cli.command,
+        Commands::Uninstall {
+            pattern: Some(_),
+            yes: true,
+            ..
+        }
+    ));
+
+    let tmp = tempfile::tempdir().unwrap();
+    std::env::set_var("FONTLIFT_JOURNAL_PATH", tmp.path().join("journal.json"));
+    let face = |path: &str, family: &str, postscript: &str| {
+        let source =
+            FontliftFontSource::new(tmp.path().join(path)).with_scope(Some(FontScope::User));
+        FontliftFontFaceInfo::new(
+            source,
+            postscript.to_string(),
+            postscript.to_string(),
+            family.to_string(),
+            "Regular".to_string(),
+        )
+    };
+    let manager = Arc::new(RecordingManager {
+        listed: vec![
+            face("TestSans.ttf", "Test Sans", "TestSans-Regular"),
+            face("TestMono.ttf", "Test Mono", "TestMono-Regular"),
+            face("Keep.ttf", "Keep", "Keep-Regular"),
+        ],
+        ..RecordingManager::default()
+    });
+    let runtime = Runtime::new().expect("runtime");
+
+    let dry = OperationOptions::new(true, true, false);
+    runtime
+        .block_on(handle_uninstall_matching_command(
+            manager.clone(),
+            FaceMatcher::family("test*"),
+            false,
+            false,
+            dry,
+        ))
+        .expect("dry run needs no confirmation");
+    assert!(manager.uninstalls.lock().expect("lock").is_empty());
+
+    let opts = OperationOptions::new(false, true, false);
+    runtime
+        .block_on(handle_uninstall_matching_command(
+            manager.clone(),
+            FaceMatcher::family("test*"),
+            false,
+            true,
+            opts,
+        ))
+        .expect("uninstall matches");
+    assert_eq!(
+        *manager.uninstalls.lock().expect("lock"),
+        [
+            (tmp.path().join("TestSans.ttf"), FontScope::User),
+            (tmp.path().join("TestMono.ttf"), FontScope::User),
+        ]
+    );
+
+    // System scope has no matches, so nothing happens.
+    runtime
+        .block_on(handle_uninstall_matching_command(
+            manager.clone(),
+            FaceMatcher::regex("^Keep").unwrap(),
+            true,
+            true,
+            opts,
+        ))
+        .expect("no matches");
+    assert_eq!(manager.uninstalls.lock().expect("lock").len(), 2);
+}
+
+#[test]
+fn remove_quarantines_and_restore_brings_fonts_back() {
+    let cli = Cli::try_parse_from(["fontlift", "rm", "--purge", "Old.ttf"]).expect("parse");
+    assert!(matches!(cli.command, Commands::Remove { purge: true, .. }));
+    assert!(Cli::try_parse_from(["fontlift", "restore"]).is_err());
+    assert!(Cli::try_parse_from(["fontlift", "restore", "--list", "Old"]).is_err());
+
+    let tmp = tempfile::tempdir().unwrap();
+    std::env::set_var("FONTLIFT_JOURNAL_PATH", tmp.path().join("journal.json"));
+    std::env::set_var("FONTLIFT_QUARANTINE_DIR", tmp.path().join("quarantine"));
+    let fonts = tmp.path().join("Fonts");
+    fs::create_dir_all(&fonts).unwrap();
+    let font = fonts.join("Removed-Regular.ttf");
+    fs::write(&font, b"font").unwrap();
+
+    let runtime = Runtime::new().expect("runtime");
+    let opts = OperationOptions::new(false, true, false);
+    let manager = Arc::new(RecordingManager::default());
+    runtime
+        .block_on(handle_remove_command(
+            manager.clone(),
+            None,
+            vec![font.clone()],
+            false,
+            false,
+            opts,
+        ))
+        .expect("remove");
+    assert!(!font.exists());
+
+    runtime
+        .block_on(handle_restore_command(
+            manager.clone(),
+            "Removed-Regular.ttf".to_string(),
+            opts,
+        ))
+        .expect("restore");
+    assert_eq!(fs::read(&font).unwrap(), b"font");
+    assert_eq!(
+        *manager.installs.lock().expect("lock"),
+        [(font.clone(), FontScope::User)]
+    );
+}
+
+#[test]
+fn maintain_runs_every_step_and_compacts_the_journal() {
+    let cli = Cli::try_parse_from(["fontlift", "maintain", "--admin"]).expect("parse");
+    assert!(matches!(
+        cli.command,
+        Commands::Maintain {
+            admin: true,
+            install_schedule: false,
+            ..
+        }
+    ));
+    assert!(Cli::try_parse_from(["fontlift", "maintain", "--log-file", "m.log"]).is_err());
+
+    let tmp = tempfile::tempdir().unwrap();
+    std::env::set_var("FONTLIFT_JOURNAL_PATH", tmp.path().join("journal.json"));
+    std::env::set_var("FONTLIFT_QUARANTINE_DIR", tmp.path().join("quarantine"));
+    // Written directly: saving through fontlift would already compact it.
+    let mut journal = fontlift_core::journal::Journal::new();
+    for age_days in [90, 1] {
+        let id = journal.record_operation(Vec::new(), None);
+        let entry = journal.entries.iter_mut().find(|e| e.id == id).unwrap();
+        entry.completed = true;
+        entry.started_at -= std::time::Duration::from_secs(age_days * 24 * 60 * 60);
+    }
+    fs::write(
+        fontlift_core::journal::journal_path(),
+        serde_json::to_string(&journal).unwrap(),
+    )
+    .unwrap();
+
+    let manager = RecordingManager::default();
+    let summary = crate::maintain::run(
+        &manager,
+        FontScope::User,
+        &fontlift_core::progress::NoopObserver,
+    );
+    let steps: Vec<_> = summary
+        .steps
+        .iter()
+        .map(|step| (step.step.name(), step.status))
+        .collect();
+    assert_eq!(
+        steps,
+        [
+            ("prune", crate::maintain::StepStatus::Ok),
+            ("duplicates", crate::maintain::StepStatus::Ok),
+            ("quarantine", crate::maintain::StepStatus::Ok),
+            ("journal", crate::maintain::StepStatus::Ok),
+            ("caches", crate::maintain::StepStatus::Ok),
+        ]
+    );
+    assert_eq!(summary.steps[3].details["removed"], 1);
+    assert_eq!(summary.steps[4].details["entries_cleared"], 2);
+    assert_eq!(*manager.prunes.lock().expect("lock"), [FontScope::User]);
+    assert_eq!(
+        *manager.cache_clears.lock().expect("lock"),
+        [CacheClearStrategy::UserOnly]
+    );
+    let json = serde_json::to_value(&summary).unwrap();
+    assert_eq!(json["steps"][0]["step"], "prune");
+
+    // Scheduling dry-runs never reach the platform scheduler.
+    let runtime = Runtime::new().expect("runtime");
+    runtime
+        .block_on(handle_maintain_command(
+            Arc::new(RecordingManager::default()),
+            false,
+            true,
+            None,
+            OperationOptions::new(true, true, false),
+        ))
+        .expect("dry-run schedule");
+}
+
+#[test]
+fn sources_lists_fontlift_roots_without_platform_support() {
+    let cli = Cli::try_parse_from(["fontlift", "sources"]).expect("parse");
+    assert!(matches!(cli.command, Commands::Sources));
+    assert_eq!(crate::ops::format_size(512), "512 B");
+    assert_eq!(
+        crate::ops::format_size(3 * 1024 * 1024 + 512 * 1024),
+        "3.5 MB"
+    );
+
+    // The recording manager reports no platform roots; fontlift's own are
+    // still listed.
+    let runtime = Runtime::new().expect("runtime");
+    runtime
+        .block_on(handle_sources_command(
+            Arc::new(RecordingManager::default()),
+            OperationOptions::new(false, true, false).with_json(true),
+        ))
+        .expect("sources");
+}
+
+#[test]
+fn state_check_parses_repair_flag() {
+    let cli = Cli::try_parse_from(["fontlift", "state", "check", "--repair"]).expect("parse");
+    let Commands::State {
+        command: StateCommand::Check { repair },
+    } = cli.command
+    else {
+        panic!("expected state check");
+    };
+    assert!(repair);
+    assert!(Cli::try_parse_from(["fontlift", "state"]).is_err());
+}
+
+#[test]
+fn serve_parses_socket_or_tcp_but_not_both() {
+    let cli = Cli::try_parse_from(["fontlift", "serve", "--socket", "/tmp/f.sock"]).expect("parse");
+    let Commands::Serve { socket, tcp, .. } = cli.command else {
+        panic!("expected serve");
+    };
+    assert_eq!(socket, Some(PathBuf::from("/tmp/f.sock")));
+    assert!(!tcp);
+    assert!(Cli::try_parse_from(["fontlift", "serve", "--tcp", "--endpoint", "e.json"]).is_ok());
+    assert!(Cli::try_parse_from(["fontlift", "serve", "--socket", "s", "--tcp"]).is_err());
+}
+
+#[test]
+fn schedule_cleanup_parses_frequency_and_scope() {
+    let cli = Cli::try_parse_from([
+        "fontlift",
+        "schedule",
+        "cleanup",
+        "--daily",
+        "--admin",
+        "--log-file",
+        "cleanup.log",
+    ])
+    .expect("parse");
+    let Commands::Schedule {
+        command:
+            ScheduleCommand::Cleanup {
+                daily,
+                admin,
+                log_file,
+                ..
+            },
+    } = cli.command
+    else {
+        panic!("expected schedule cleanup");
+    };
+    assert!(daily && admin);
+    assert_eq!(log_file, Some(PathBuf::from("cleanup.log")));
+    assert!(
+        Cli::try_parse_from(["fontlift", "schedule", "cleanup", "--daily", "--weekly"]).is_err()
+    );
+
+    // Dry runs never reach the platform scheduler, which the recording
+    // manager does not implement.
+    let runtime = Runtime::new().expect("runtime");
+    runtime
+        .block_on(handle_schedule_command(
+            Arc::new(RecordingManager::default()),
+            ScheduleCommand::Remove {
+                name: "cleanup".to_string(),
+                admin: false,
+            },
+            false,
+            OperationOptions::new(true, true, false),
+        ))
+        .expect("dry run");
+    let result = runtime.block_on(handle_schedule_command(
+        Arc::new(RecordingManager::default()),
+        ScheduleCommand::List,
+        false,
+        OperationOptions::new(false, true, false),
+    ));
+    assert!(matches!(result, Err(FontError::UnsupportedOperation(_))));
+}
+
+#[test]
+fn every_example_parses_and_has_a_caption() {
+    for workflow in docs::WORKFLOWS {
+        let steps =
+            docs::render_workflow(workflow).unwrap_or_else(|e| panic!("{}: {}", workflow.topic, e));
+        for step in steps {
+            assert!(!step.note.is_empty(), "{}", step.command);
+        }
+    }
+
+    let uncaptioned = docs::Step {
+        note: None,
+        args: &["list", "--sorted", "--name"],
+    };
+    let step = docs::render_step(&uncaptioned).unwrap();
+    assert_eq!(step.note, "List installed fonts");
+    assert_eq!(step.command, "fontlift list --sorted --name");
+
+    let stale = docs::Step {
+        note: None,
+        args: &["install", "--no-such-flag", "a.ttf"],
+    };
+    assert!(matches!(
+        docs::render_step(&stale),
+        Err(FontError::InvalidFormat(_))
+    ));
+    assert!(Cli::try_parse_from(["fontlift", "examples", "no-such-topic"]).is_err());
+}
+
+#[test]
+fn man_pages_cover_subcommands() {
+    let mut page = Vec::new();
+    docs::write_man_page(Some("install"), &mut page).unwrap();
+    let page = String::from_utf8(page).unwrap();
+    assert!(page.contains("fontlift\\-install"));
+    assert!(page.contains("validation\\-strictness"));
+
+    let tmp = tempfile::tempdir().unwrap();
+    docs::write_man_pages_to(tmp.path()).unwrap();
+    assert!(tmp.path().join("fontlift.1").exists());
+    assert!(tmp.path().join("fontlift-state-check.1").exists());
+}
+
+#[test]
+fn json_errors_carry_code_message_and_suggestion() {
+    let error = FontError::PermissionDenied("HKLM is read-only".to_string());
+    let value: Value = serde_json::from_str(&error_json(&error)).expect("valid json");
+    assert_eq!(value["error"]["code"], "permission_denied");
+    assert_eq!(
+        value["error"]["message"],
+        "Permission denied: HKLM is read-only"
+    );
+    assert_eq!(value["error"]["suggestion"], error.suggestion());
+}
+
+#[test]
+fn strict_license_refuses_fonts_that_restrict_embedding() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "a.ttf", "--strict-license"])
+        .expect("parse install --strict-license");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs { strict_license, .. } = *args;
+    assert!(strict_license);
+
+    // A copy of the fixture with OS/2 fsType set to "restricted license".
+    let mut data = fs::read(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/fixtures/fonts/AtkinsonHyperlegible-Regular.ttf"),
+    )
+    .expect("read fixture");
+    let tables = u16::from_be_bytes([data[4], data[5]]) as usize;
+    let os2 = (0..tables)
+        .map(|i| 12 + 16 * i)
+        .find(|&record| &data[record..record + 4] == b"OS/2")
+        .map(|record| u32::from_be_bytes(data[record + 8..record + 12].try_into().unwrap()))
+        .expect("OS/2 table") as usize;
+    data[os2 + 8..os2 + 10].copy_from_slice(&0x0002u16.to_be_bytes());
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let font = tmp.path().join("Restricted-Regular.ttf");
+    fs::write(&font, data).expect("write font");
+
+    let manager = Arc::new(RecordingManager::default());
+    let runtime = Runtime::new().expect("runtime");
+    let err = runtime
+        .block_on(handle_install_command(
+            manager.clone(),
+            vec![font],
+            InstallOptions::new(false, false, ValidationStrictness::Normal, false)
+                .with_strict_license(true),
+            OperationOptions::new(false, true, false),
+        ))
+        .expect_err("restricted font must be refused");
+    assert!(matches!(err, FontError::VerificationFailed(_)), "{err}");
+    assert!(manager.installs.lock().expect("lock").is_empty());
+}
+
+#[test]
+fn type1_inputs_need_convert() {
+    let cli = Cli::try_parse_from(["fontlift", "install", "Old.pfb", "--convert"])
+        .expect("parse install --convert");
+    let Commands::Install(args) = cli.command else {
+        panic!("expected Install");
+    };
+    let InstallArgs { convert, .. } = *args;
+    assert!(convert);
+    assert!(
+        Cli::try_parse_from(["fontlift", "install", "Old.pfb", "--convert", "--temp"]).is_err()
+    );
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let font = tmp.path().join("Old.pfb");
+    fs::write(&font, [0x80, 0x01, 0x00, 0x00]).expect("write font");
+    let manager = Arc::new(RecordingManager::default());
+    let runtime = Runtime::new().expect("runtime");
+    let install = |convert: bool| {
+        runtime.block_on(handle_install_command(
+            manager.clone(),
+            vec![font.clone()],
+            InstallOptions::new(false, false, ValidationStrictness::Normal, false)
+                .with_convert(convert),
+            OperationOptions::new(true, true, false),
+        ))
+    };
+
+    let err = install(false).expect_err("Type 1 must be refused");
+    assert!(matches!(err, FontError::LegacyType1(_)), "{err}");
+    assert!(err.suggestion().contains("--convert"));
+
+    // A dry run reports the conversion without needing a converter.
+    install(true).expect("dry run convert");
+    assert!(manager.plans.lock().expect("lock").is_empty());
+}
+
+#[test]
+fn diff_compares_files_and_installed_faces() {
+    let cli = Cli::try_parse_from([
+        "fontlift",
+        "diff",
+        "Old.ttf",
+        "Inter-Regular",
+        "--face",
+        "1",
+    ])
+    .expect("parse diff");
+    assert!(matches!(
+        cli.command,
+        Commands::Diff { ref before, ref after, face: 1 }
+            if before == "Old.ttf" && after == "Inter-Regular"
+    ));
+    assert!(Cli::try_parse_from(["fontlift", "diff", "Old.ttf"]).is_err());
+
+    let font = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/fonts/AtkinsonHyperlegible-Regular.ttf");
+    let font = font.to_string_lossy();
+    handle_diff_command(Arc::new(RecordingManager::default()), &font, &font, 0, true)
+        .expect("a font matches itself");
+    assert!(matches!(
+        handle_diff_command(
+            Arc::new(RecordingManager::default()),
+            &font,
+            "NotInstalled-Regular",
+            0,
+            false,
+        ),
+        Err(FontError::FontNotFound(_))
+    ));
+}
+
+mod tui_tests {
+    use super::*;
+    use crate::tui::{Action, Browser, Entry, Key, Mode, Outcome, Row};
+
+    fn entry(family: &str, style: &str, weight: u16, disabled: bool) -> Entry {
+        let postscript = format!("{}-{}", family.replace(' ', ""), style.replace(' ', ""));
+        let mut face = sample_font(&format!("/fonts/{postscript}.ttf"), &postscript);
+        face.family_name = family.to_string();
+        face.style = style.to_string();
+        face.weight = Some(weight);
+        Entry { face, disabled }
+    }
+
+    fn browser() -> Browser {
+        Browser::new(vec![
+            entry("Roboto", "Regular", 400, false),
+            entry("Inter", "Bold", 700, false),
+            entry("Inter", "Regular", 400, false),
+            entry("Lato", "Light", 300, true),
+        ])
+    }
+
+    fn selected(browser: &Browser) -> String {
+        browser
+            .selected_entry()
+            .map(|e| e.face.postscript_name.clone())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn groups_faces_under_family_headings() {
+        let browser = browser();
+        let rows = browser.rows();
+        let headings: Vec<_> = rows
+            .iter()
+            .filter_map(|row| match row {
+                Row::Family(first, count) => {
+                    Some((browser.entries()[*first].face.family_name.clone(), *count))
+                }
+                Row::Face(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            headings,
+            vec![
+                ("Inter".to_string(), 2),
+                ("Lato".to_string(), 1),
+                ("Roboto".to_string(), 1)
+            ]
+        );
+        assert_eq!(rows.len(), 7);
+        assert_eq!(selected(&browser), "Inter-Regular");
+        assert_eq!(browser.selected_row(), Some(1));
+    }
+
+    #[test]
+    fn search_narrows_and_navigation_stays_in_bounds() {
+        let mut browser = browser();
+        browser.handle_key(Key::Char('/'));
+        for c in "ROB".chars() {
+            browser.handle_key(Key::Char(c));
+        }
+        browser.handle_key(Key::Enter);
+        assert_eq!(browser.mode, Mode::Browse);
+        assert_eq!(browser.matches().len(), 1);
+        assert_eq!(selected(&browser), "Roboto-Regular");
+        browser.handle_key(Key::Down);
+        assert_eq!(selected(&browser), "Roboto-Regular");
+
+        browser.handle_key(Key::Char('/'));
+        browser.handle_key(Key::Esc);
+        assert!(browser.query.is_empty());
+        browser.handle_key(Key::PageDown);
+        assert_eq!(selected(&browser), "Roboto-Regular");
+        browser.handle_key(Key::Char('g'));
+        browser.handle_key(Key::Char('j'));
+        assert_eq!(selected(&browser), "Inter-Bold");
+        assert!(matches!(browser.handle_key(Key::Char('q')), Outcome::Quit));
+    }
+
+    #[test]
+    fn actions_need_confirmation_and_fit_the_font() {
+        let mut browser = browser();
+        browser.handle_key(Key::Char('u'));
+        assert_eq!(browser.mode, Mode::Confirm(Action::Uninstall));
+        assert!(matches!(
+            browser.handle_key(Key::Char('n')),
+            Outcome::Continue
+        ));
+        assert_eq!(browser.status.as_deref(), Some("Cancelled"));
+
+        browser.handle_key(Key::Char('r'));
+        match browser.handle_key(Key::Char('y')) {
+            Outcome::Run(action, entry) => {
+                assert_eq!(action, Action::Remove);
+                assert_eq!(entry.face.postscript_name, "Inter-Regular");
+            }
+            other => panic!("expected an action, got {other:?}"),
+        }
+
+        browser.handle_key(Key::Char('a'));
+        assert_eq!(browser.mode, Mode::Browse);
+        assert!(browser.status.is_some());
+
+        browser.handle_key(Key::Down);
+        browser.handle_key(Key::Down);
+        assert_eq!(selected(&browser), "Lato-Light");
+        browser.handle_key(Key::Char('d'));
+        assert_eq!(browser.mode, Mode::Browse);
+        browser.handle_key(Key::Char('a'));
+        assert_eq!(browser.mode, Mode::Confirm(Action::Activate));
+
+        // Reloading keeps the selection on the same face.
+        browser.set_entries(vec![
+            entry("Lato", "Light", 300, false),
+            entry("Inter", "Regular", 400, false),
+        ]);
+        assert_eq!(selected(&browser), "Lato-Light");
+        assert!(browser.details().iter().any(|(label, _)| *label == "Path"));
+    }
+}
+
+mod deploy_tests {
+    use super::*;
+    use crate::deploy::{
+        deploy_to_host, shell_quote, DeployPlan, RemoteOutput, SshTransport, Transport,
+    };
+    use fontlift_core::FontResult;
+    use std::path::Path;
+
+    /// Records calls; fails `install` on hosts named `broken`.
+    #[derive(Default)]
+    struct FakeTransport {
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl Transport for FakeTransport {
+        fn copy(&self, host: &str, local: &Path, remote: &str) -> FontResult<()> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("copy {host} {} {remote}", local.display()));
+            Ok(())
+        }
+
+        fn run(&self, host: &str, command: &str) -> FontResult<RemoteOutput> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("run {host} {command}"));
+            if command.starts_with("mktemp") {
+                return Ok(RemoteOutput {
+                    success: true,
+                    stdout: "/tmp/fontlift-deploy.abc\n".into(),
+                    stderr: String::new(),
+                });
+            }
+            let failed = host == "broken" && command.contains(" install ");
+            Ok(RemoteOutput {
+                success: !failed,
+                stdout: String::new(),
+                stderr: if failed { "boom".into() } else { String::new() },
+            })
+        }
+    }
+
+    #[test]
+    fn deploy_stages_installs_and_cleans_up_on_each_host() {
+        let plan = DeployPlan::new(
+            vec![
+                PathBuf::from("/fonts/Inter.ttf"),
+                PathBuf::from("/fonts/My Font.otf"),
+            ],
+            None,
+            true,
+            true,
+            "fontlift".into(),
+        )
+        .unwrap();
+        let transport = FakeTransport::default();
+
+        let ok = deploy_to_host(&transport, "admin@mac-01", &plan);
+        assert!(ok.success);
+        assert_eq!(ok.copied, 2);
+        assert_eq!(
+            transport.calls.lock().unwrap().clone(),
+            vec![
+                "run admin@mac-01 mktemp -d \"${TMPDIR:-/tmp}/fontlift-deploy.XXXXXX\"",
+                "copy admin@mac-01 /fonts/Inter.ttf /tmp/fontlift-deploy.abc/Inter.ttf",
+                "copy admin@mac-01 /fonts/My Font.otf /tmp/fontlift-deploy.abc/My Font.otf",
+                "run admin@mac-01 sudo -n fontlift install --admin \
+                 /tmp/fontlift-deploy.abc/Inter.ttf '/tmp/fontlift-deploy.abc/My Font.otf'",
+                "run admin@mac-01 rm -rf /tmp/fontlift-deploy.abc",
+            ]
+        );
+
+        transport.calls.lock().unwrap().clear();
+        let failed = deploy_to_host(&transport, "broken", &plan);
+        assert!(!failed.success);
+        assert_eq!(failed.output, "boom");
+        assert!(failed.error.unwrap().contains("broken"));
+        assert!(transport
+            .calls
+            .lock()
+            .unwrap()
+            .last()
+            .unwrap()
+            .starts_with("run broken rm -rf"));
+    }
+
+    #[test]
+    fn hosts_cannot_be_passed_as_ssh_options() {
+        let plan = DeployPlan::new(
+            vec![PathBuf::from("/fonts/Inter.ttf")],
+            None,
+            false,
+            false,
+            "fontlift".into(),
+        )
+        .unwrap();
+        let transport = FakeTransport::default();
+        let rejected = deploy_to_host(&transport, "-oProxyCommand=touch /tmp/pwned", &plan);
+        assert!(!rejected.success);
+        assert!(rejected.error.unwrap().contains("not a host name"));
+        assert!(transport.calls.lock().unwrap().is_empty());
+
+        let ssh = SshTransport {
+            options: vec!["ConnectTimeout=10".into()],
+        };
+        let command = ssh.ssh_command("-oProxyCommand=x", "true");
+        let args: Vec<_> = command.get_args().map(|a| a.to_string_lossy()).collect();
+        assert_eq!(
+            args,
+            [
+                "-o",
+                "BatchMode=yes",
+                "-o",
+                "ConnectTimeout=10",
+                "--",
+                "-oProxyCommand=x",
+                "true"
+            ]
+        );
+    }
+
+    #[test]
+    fn manifest_deploys_install_from_the_staged_manifest() {
+        let plan = DeployPlan::new(
+            vec![PathBuf::from("/fonts/Inter.ttf")],
+            Some(PathBuf::from("/tmp/fonts.json")),
+            false,
+            false,
+            "/opt/bin/fontlift".into(),
+        )
+        .unwrap();
+        assert_eq!(
+            plan.install_command("/tmp/stage"),
+            "/opt/bin/fontlift install --manifest /tmp/stage/manifest.json /tmp/stage"
+        );
+
+        let duplicate = DeployPlan::new(
+            vec![PathBuf::from("/a/Inter.ttf"), PathBuf::from("/b/Inter.ttf")],
+            None,
+            false,
+            false,
+            "fontlift".into(),
+        );
+        assert!(duplicate.is_err());
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn deploy_requires_a_host_and_parses_repeated_ones() {
+        assert!(Cli::try_parse_from(["fontlift", "deploy", "Inter.ttf"]).is_err());
+        assert!(Cli::try_parse_from([
+            "fontlift",
+            "deploy",
+            "--host",
+            "a",
+            "--manifest",
+            "m.json",
+            "--admin"
+        ])
+        .is_err());
+
+        let cli = Cli::try_parse_from([
+            "fontlift",
+            "deploy",
+            "--host",
+            "admin@mac-01",
+            "--host",
+            "mac-02",
+            "--ssh-option",
+            "ConnectTimeout=5",
+            "Fonts",
+        ])
+        .expect("parse deploy");
+        let Commands::Deploy {
+            hosts,
+            fonts,
+            ssh_options,
+            remote_fontlift,
+            ..
+        } = cli.command
+        else {
+            panic!("expected Deploy");
+        };
+        assert_eq!(hosts, vec!["admin@mac-01", "mac-02"]);
+        assert_eq!(fonts, vec![PathBuf::from("Fonts")]);
+        assert_eq!(ssh_options, vec!["ConnectTimeout=5"]);
+        assert_eq!(remote_fontlift, "fontlift");
+    }
+}
+
+#[test]
+fn scan_usage_reports_fonts_documents_reference() {
+    let cli = Cli::try_parse_from(["fontlift", "scan-usage", "site", "--missing"])
+        .expect("parse scan-usage");
+    let Commands::ScanUsage { dir, missing } = cli.command else {
+        panic!("expected ScanUsage");
+    };
+    assert_eq!(dir, PathBuf::from("site"));
+    assert!(missing);
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    fs::write(
+        tmp.path().join("style.css"),
+        "body { font-family: Inter, sans-serif }",
+    )
+    .expect("write css");
+    let runtime = Runtime::new().expect("runtime");
+    runtime
+        .block_on(handle_scan_usage_command(
+            Arc::new(RecordingManager::default()),
+            tmp.path().to_path_buf(),
+            true,
+            true,
+        ))
+        .expect("scan usage");
+    assert!(runtime
+        .block_on(handle_scan_usage_command(
+            Arc::new(RecordingManager::default()),
+            tmp.path().join("missing"),
+            false,
+            false,
+        ))
+        .is_err());
+}
+
+#[test]
+fn specimen_writes_a_pdf_for_a_font_file() {
+    let cli = Cli::try_parse_from(["fontlift", "specimen", "Inter", "--sizes", "10,14"])
+        .expect("parse specimen");
+    let Commands::Specimen {
+        font, out, sizes, ..
+    } = cli.command
+    else {
+        panic!("expected Specimen");
+    };
+    assert_eq!(font, "Inter");
+    assert_eq!(out, PathBuf::from("specimen.pdf"));
+    assert_eq!(sizes, [10.0, 14.0]);
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let font = fontlift_testfonts::TestFont::new("Proof Sans")
+        .write_to(tmp.path())
+        .expect("write font");
+    let out = tmp.path().join("proof.pdf");
+    let runtime = Runtime::new().expect("runtime");
+    let run = |dry_run| {
+        runtime.block_on(handle_specimen_command(
+            Arc::new(RecordingManager::default()),
+            font.display().to_string(),
+            out.clone(),
+            fontlift_core::specimen::SpecimenOptions::default(),
+            OperationOptions::new(dry_run, true, false),
+        ))
+    };
+    run(true).expect("dry-run specimen");
+    assert!(!out.exists());
+    run(false).expect("specimen");
+    assert!(fs::read(&out).expect("read pdf").starts_with(b"%PDF-"));
+}
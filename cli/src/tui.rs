@@ -0,0 +1,573 @@
+//! Terminal font browser behind `fontlift tui`.
+//!
+//! [`Browser`] holds everything the screen shows — the installed and
+//! disabled fonts grouped by family, the search query, the selection and
+//! the pending confirmation — and turns key presses into [`Outcome`]s. It
+//! knows nothing about terminals, so its behaviour is tested directly.
+//!
+//! The `terminal` module (the `tui` feature) draws a [`Browser`] with
+//! `ratatui` and carries out the actions it asks for through the regular
+//! `uninstall`, `remove`, `disable` and `enable` handlers, so protection
+//! policies, the journal and the history log apply exactly as they do on
+//! the command line.
+
+// Without the `tui` feature only the stand-in `run` is reachable.
+#![cfg_attr(not(feature = "tui"), allow(dead_code))]
+
+use fontlift_core::{sorting, vault, FontManager, FontResult, FontScope, FontliftFontFaceInfo};
+
+/// How far Page Up and Page Down move the selection.
+const PAGE: usize = 10;
+
+/// One face in the browser.
+#[derive(Debug, Clone)]
+pub(crate) struct Entry {
+    pub face: FontliftFontFaceInfo,
+    /// Moved aside with `fontlift disable`; `activate` brings it back.
+    pub disabled: bool,
+}
+
+/// What the selected font can have done to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Action {
+    Uninstall,
+    Remove,
+    Disable,
+    Activate,
+}
+
+impl Action {
+    pub fn verb(self) -> &'static str {
+        match self {
+            Action::Uninstall => "Uninstall",
+            Action::Remove => "Remove",
+            Action::Disable => "Disable",
+            Action::Activate => "Activate",
+        }
+    }
+
+    pub fn done(self) -> &'static str {
+        match self {
+            Action::Uninstall => "Uninstalled",
+            Action::Remove => "Removed",
+            Action::Disable => "Disabled",
+            Action::Activate => "Activated",
+        }
+    }
+
+    /// Installed fonts can be uninstalled, removed or disabled; disabled
+    /// ones can only be activated.
+    fn applies_to(self, entry: &Entry) -> bool {
+        (self == Action::Activate) == entry.disabled
+    }
+}
+
+/// Keys the browser understands, independent of the terminal library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Key {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Enter,
+    Esc,
+    Backspace,
+    Char(char),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Mode {
+    Browse,
+    /// Typing into the search query.
+    Search,
+    /// Waiting for `y` before running the action on the selection.
+    Confirm(Action),
+}
+
+/// What the caller should do after a key press.
+#[derive(Debug, Clone)]
+pub(crate) enum Outcome {
+    Continue,
+    Quit,
+    Run(Action, Box<Entry>),
+}
+
+/// A line of the font list: a family heading or one of its faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Row {
+    /// Index of the family's first face in `entries`, and how many faces
+    /// match.
+    Family(usize, usize),
+    Face(usize),
+}
+
+pub(crate) struct Browser {
+    entries: Vec<Entry>,
+    pub query: String,
+    pub mode: Mode,
+    /// Position within [`Browser::matches`].
+    selected: usize,
+    /// Result of the last action, shown until the next key press.
+    pub status: Option<String>,
+}
+
+impl Browser {
+    pub fn new(entries: Vec<Entry>) -> Self {
+        let mut browser = Self {
+            entries: Vec::new(),
+            query: String::new(),
+            mode: Mode::Browse,
+            selected: 0,
+            status: None,
+        };
+        browser.set_entries(entries);
+        browser
+    }
+
+    /// Replace the fonts after an action, keeping the selection on the same
+    /// face when it is still there.
+    pub fn set_entries(&mut self, mut entries: Vec<Entry>) {
+        entries.sort_by(|a, b| sorting::compare_faces(&a.face, &b.face));
+        let previous = self
+            .selected_entry()
+            .map(|entry| entry.face.postscript_name.clone());
+        self.entries = entries;
+        let matches = self.matches();
+        self.selected = previous
+            .and_then(|name| {
+                matches
+                    .iter()
+                    .position(|&i| self.entries[i].face.postscript_name == name)
+            })
+            .unwrap_or(self.selected)
+            .min(matches.len().saturating_sub(1));
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Indices of the entries whose family, full name or PostScript name
+    /// contains the query, ignoring case.
+    pub fn matches(&self) -> Vec<usize> {
+        let query = self.query.to_lowercase();
+        (0..self.entries.len())
+            .filter(|&i| {
+                let face = &self.entries[i].face;
+                query.is_empty()
+                    || [&face.family_name, &face.full_name, &face.postscript_name]
+                        .iter()
+                        .any(|name| name.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    /// The matching faces with a heading before each family.
+    pub fn rows(&self) -> Vec<Row> {
+        let matches = self.matches();
+        let mut rows = Vec::with_capacity(matches.len() * 2);
+        for (n, &i) in matches.iter().enumerate() {
+            let family = &self.entries[i].face.family_name;
+            let starts_family = n == 0 || self.entries[matches[n - 1]].face.family_name != *family;
+            if starts_family {
+                let count = matches[n..]
+                    .iter()
+                    .take_while(|&&j| self.entries[j].face.family_name == *family)
+                    .count();
+                rows.push(Row::Family(i, count));
+            }
+            rows.push(Row::Face(i));
+        }
+        rows
+    }
+
+    /// Position of the selected face in [`Browser::rows`].
+    pub fn selected_row(&self) -> Option<usize> {
+        let selected = *self.matches().get(self.selected)?;
+        self.rows()
+            .iter()
+            .position(|row| *row == Row::Face(selected))
+    }
+
+    pub fn selected_entry(&self) -> Option<&Entry> {
+        self.matches().get(self.selected).map(|&i| &self.entries[i])
+    }
+
+    /// Label and value pairs describing the selected face.
+    pub fn details(&self) -> Vec<(&'static str, String)> {
+        let Some(entry) = self.selected_entry() else {
+            return Vec::new();
+        };
+        let face = &entry.face;
+        let known = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        let mut details = vec![
+            ("Family", face.family_name.clone()),
+            ("Style", face.style.clone()),
+            ("Full name", face.full_name.clone()),
+            ("PostScript", face.postscript_name.clone()),
+            ("Weight", known(face.weight.map(|w| w.to_string()))),
+            (
+                "Italic",
+                known(
+                    face.italic
+                        .map(|i| if i { "yes" } else { "no" }.to_string()),
+                ),
+            ),
+            ("Format", known(face.source.format.clone())),
+            (
+                "Scope",
+                known(face.source.scope.map(|s| s.description().to_string())),
+            ),
+            ("Path", face.source.path.display().to_string()),
+        ];
+        if let Some(index) = face
+            .source
+            .face_index
+            .filter(|_| face.source.is_collection == Some(true))
+        {
+            details.push(("Face", index.to_string()));
+        }
+        if let Some(license) = &face.license {
+            details.push(("License", license.clone()));
+        }
+        if entry.disabled {
+            details.push(("Status", "disabled".to_string()));
+        }
+        details
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> Outcome {
+        self.status = None;
+        match self.mode {
+            Mode::Search => {
+                match key {
+                    Key::Char(c) => self.query.push(c),
+                    Key::Backspace => {
+                        self.query.pop();
+                    }
+                    Key::Enter => self.mode = Mode::Browse,
+                    Key::Esc => {
+                        self.query.clear();
+                        self.mode = Mode::Browse;
+                    }
+                    _ => return self.navigate(key),
+                }
+                self.selected = 0;
+                Outcome::Continue
+            }
+            Mode::Confirm(action) => {
+                self.mode = Mode::Browse;
+                match (key, self.selected_entry()) {
+                    (Key::Char('y') | Key::Char('Y'), Some(entry)) => {
+                        Outcome::Run(action, Box::new(entry.clone()))
+                    }
+                    _ => {
+                        self.status = Some("Cancelled".to_string());
+                        Outcome::Continue
+                    }
+                }
+            }
+            Mode::Browse => match key {
+                Key::Char('q') | Key::Esc => Outcome::Quit,
+                Key::Char('/') => {
+                    self.mode = Mode::Search;
+                    Outcome::Continue
+                }
+                Key::Char('u') => self.confirm(Action::Uninstall),
+                Key::Char('r') => self.confirm(Action::Remove),
+                Key::Char('d') => self.confirm(Action::Disable),
+                Key::Char('a') => self.confirm(Action::Activate),
+                Key::Char('k') => self.navigate(Key::Up),
+                Key::Char('j') => self.navigate(Key::Down),
+                Key::Char('g') => self.navigate(Key::Home),
+                Key::Char('G') => self.navigate(Key::End),
+                _ => self.navigate(key),
+            },
+        }
+    }
+
+    fn confirm(&mut self, action: Action) -> Outcome {
+        match self.selected_entry() {
+            Some(entry) if action.applies_to(entry) => self.mode = Mode::Confirm(action),
+            Some(entry) => {
+                self.status = Some(if entry.disabled {
+                    "This font is disabled; press a to activate it".to_string()
+                } else {
+                    "This font is already active".to_string()
+                });
+            }
+            None => {}
+        }
+        Outcome::Continue
+    }
+
+    fn navigate(&mut self, key: Key) -> Outcome {
+        let last = self.matches().len().saturating_sub(1);
+        self.selected = match key {
+            Key::Up => self.selected.saturating_sub(1),
+            Key::Down => (self.selected + 1).min(last),
+            Key::PageUp => self.selected.saturating_sub(PAGE),
+            Key::PageDown => (self.selected + PAGE).min(last),
+            Key::Home => 0,
+            Key::End => last,
+            _ => self.selected,
+        };
+        Outcome::Continue
+    }
+}
+
+/// Installed fonts plus the disabled ones from the vault.
+pub(crate) fn load_entries(manager: &dyn FontManager) -> FontResult<Vec<Entry>> {
+    let mut entries: Vec<Entry> =
+        fontlift_core::protection::dedupe_fonts(manager.list_installed_fonts()?)
+            .into_iter()
+            .map(|face| Entry {
+                face,
+                disabled: false,
+            })
+            .collect();
+
+    for font in vault::list_disabled()? {
+        let faces =
+            fontlift_core::metadata::read_all_face_info(&font.vault_path).unwrap_or_default();
+        let faces = if faces.is_empty() {
+            font.postscript_names
+                .iter()
+                .map(|name| {
+                    FontliftFontFaceInfo::new(
+                        fontlift_core::FontliftFontSource::new(font.original_path.clone()),
+                        name.clone(),
+                        name.clone(),
+                        name.clone(),
+                        "Regular".to_string(),
+                    )
+                })
+                .collect()
+        } else {
+            faces
+        };
+        entries.extend(faces.into_iter().map(|mut face| {
+            face.source.path = font.original_path.clone();
+            face.source.scope = Some(font.scope);
+            Entry {
+                face,
+                disabled: true,
+            }
+        }));
+    }
+    Ok(entries)
+}
+
+/// Whether acting on `entry` needs `--admin`.
+fn needs_admin(entry: &Entry) -> bool {
+    entry.face.source.scope == Some(FontScope::System)
+}
+
+#[cfg(feature = "tui")]
+mod terminal {
+    use super::{load_entries, needs_admin, Action, Browser, Entry, Key, Mode, Outcome, Row};
+    use crate::ops::{
+        handle_disable_command, handle_enable_command, handle_remove_command,
+        handle_uninstall_command, OperationOptions,
+    };
+    use fontlift_core::{FontError, FontManager};
+    use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use ratatui::layout::{Constraint, Layout};
+    use ratatui::style::{Color, Modifier, Style};
+    use ratatui::text::{Line, Span};
+    use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+    use ratatui::{DefaultTerminal, Frame};
+    use std::sync::Arc;
+
+    const HELP: &str = "↑↓ move  / search  u uninstall  r remove  d disable  a activate  q quit";
+
+    pub async fn run(manager: Arc<dyn FontManager>) -> Result<(), FontError> {
+        let mut browser = Browser::new(load_entries(manager.as_ref())?);
+        let mut terminal = ratatui::try_init().map_err(FontError::IoError)?;
+        let result = event_loop(&mut terminal, &mut browser, &manager).await;
+        ratatui::restore();
+        result
+    }
+
+    async fn event_loop(
+        terminal: &mut DefaultTerminal,
+        browser: &mut Browser,
+        manager: &Arc<dyn FontManager>,
+    ) -> Result<(), FontError> {
+        loop {
+            terminal
+                .draw(|frame| draw(frame, browser))
+                .map_err(FontError::IoError)?;
+            let Event::Key(key) = event::read().map_err(FontError::IoError)? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                return Ok(());
+            }
+            let Some(key) = translate(key.code) else {
+                continue;
+            };
+            match browser.handle_key(key) {
+                Outcome::Continue => {}
+                Outcome::Quit => return Ok(()),
+                Outcome::Run(action, entry) => {
+                    let name = entry.face.postscript_name.clone();
+                    browser.status = Some(match perform(manager, action, &entry).await {
+                        Ok(()) => format!("{} {}", action.done(), name),
+                        Err(e) => format!("✗ {} {}: {}", action.verb(), name, e),
+                    });
+                    browser.set_entries(load_entries(manager.as_ref())?);
+                }
+            }
+        }
+    }
+
+    fn translate(code: KeyCode) -> Option<Key> {
+        Some(match code {
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::PageDown => Key::PageDown,
+            KeyCode::Home => Key::Home,
+            KeyCode::End => Key::End,
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Esc => Key::Esc,
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Char(c) => Key::Char(c),
+            _ => return None,
+        })
+    }
+
+    /// Run `action` through the same handler its subcommand uses, quietly so
+    /// nothing is printed over the screen.
+    async fn perform(
+        manager: &Arc<dyn FontManager>,
+        action: Action,
+        entry: &Entry,
+    ) -> Result<(), FontError> {
+        let opts = OperationOptions::new(false, true, false);
+        let admin = needs_admin(entry);
+        let name = Some(entry.face.postscript_name.clone());
+        let manager = Arc::clone(manager);
+        match action {
+            Action::Uninstall => {
+                handle_uninstall_command(manager, name, Vec::new(), admin, opts).await
+            }
+            Action::Remove => {
+                handle_remove_command(manager, name, Vec::new(), admin, false, opts).await
+            }
+            Action::Disable => handle_disable_command(manager, name, None, admin, opts).await,
+            Action::Activate => {
+                handle_enable_command(manager, entry.face.postscript_name.clone(), opts).await
+            }
+        }
+    }
+
+    fn draw(frame: &mut Frame, browser: &Browser) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, details_area] =
+            Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .areas(main);
+
+        let entries = browser.entries();
+        let rows = browser.rows();
+        let items: Vec<ListItem> = rows
+            .iter()
+            .map(|row| match *row {
+                Row::Family(first, count) => ListItem::new(Line::from(vec![
+                    Span::styled(
+                        entries[first].face.family_name.clone(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(format!(" ({count})"), Style::default().fg(Color::DarkGray)),
+                ])),
+                Row::Face(i) => {
+                    let entry = &entries[i];
+                    let weight = entry.face.weight.map(|w| w.to_string()).unwrap_or_default();
+                    let mut spans = vec![Span::raw(format!(
+                        "  {:<24} {:>4}",
+                        entry.face.style, weight
+                    ))];
+                    if let Some(scope) = entry.face.source.scope {
+                        spans.push(Span::styled(
+                            format!("  {}", scope.description()),
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    }
+                    if entry.disabled {
+                        spans.push(Span::styled(
+                            "  disabled",
+                            Style::default().fg(Color::Yellow),
+                        ));
+                    }
+                    ListItem::new(Line::from(spans))
+                }
+            })
+            .collect();
+
+        let title = if browser.query.is_empty() {
+            format!(" Fonts ({}) ", browser.matches().len())
+        } else {
+            format!(
+                " Fonts ({}) matching '{}' ",
+                browser.matches().len(),
+                browser.query
+            )
+        };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(browser.selected_row());
+        frame.render_stateful_widget(list, list_area, &mut state);
+
+        let details: Vec<Line> = browser
+            .details()
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(format!("{label:<11}"), Style::default().fg(Color::Cyan)),
+                    Span::raw(value),
+                ])
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(details)
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title(" Details ")),
+            details_area,
+        );
+
+        let footer_text = match browser.mode {
+            Mode::Search => format!("/{}█", browser.query),
+            Mode::Confirm(action) => format!(
+                "{} {}? (y/n)",
+                action.verb(),
+                browser
+                    .selected_entry()
+                    .map(|e| e.face.postscript_name.as_str())
+                    .unwrap_or_default()
+            ),
+            Mode::Browse => browser.status.clone().unwrap_or_else(|| HELP.to_string()),
+        };
+        frame.render_widget(Paragraph::new(footer_text), footer);
+    }
+}
+
+#[cfg(feature = "tui")]
+pub use terminal::run;
+
+/// Stand-in used when fontlift was built without the `tui` feature.
+#[cfg(not(feature = "tui"))]
+pub async fn run(
+    _manager: std::sync::Arc<dyn FontManager>,
+) -> Result<(), fontlift_core::FontError> {
+    Err(fontlift_core::FontError::UnsupportedOperation(
+        "The font browser requires the `tui` feature".to_string(),
+    ))
+}
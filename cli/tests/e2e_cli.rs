@@ -0,0 +1,228 @@
+// End-to-end runs of the real `fontlift` binary against the fake registry.
+//
+// `fake_registry_tests.rs` calls the command handlers directly; these
+// tests go through argument parsing, stdout/stderr and the exit code as a
+// user or script would. Each run passes `--fake-registry`, so fonts land in
+// a `FakeFontManager` file tree rather than the OS, on every platform. Each
+// test also gets its own state directory (journal, install database,
+// history, caches, content store, vault) and policy paths, so tests run in
+// parallel without env locks and never touch real fonts.
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("workspace root")
+        .join("tests/fixtures/fonts")
+        .join(name)
+}
+
+/// A sandboxed `fontlift` installation.
+struct Sandbox {
+    root: TempDir,
+}
+
+/// What one run of the binary produced.
+struct Run {
+    code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+impl Run {
+    fn success(self) -> Self {
+        assert_eq!(
+            self.code, 0,
+            "expected success\nstdout:\n{}\nstderr:\n{}",
+            self.stdout, self.stderr
+        );
+        self
+    }
+
+    fn json(&self) -> Value {
+        serde_json::from_str(&self.stdout)
+            .unwrap_or_else(|e| panic!("stdout is not JSON ({e}):\n{}", self.stdout))
+    }
+}
+
+impl Sandbox {
+    fn new() -> Self {
+        Self {
+            root: TempDir::new().expect("sandbox root"),
+        }
+    }
+
+    fn registry(&self) -> PathBuf {
+        self.root.path().join("registry")
+    }
+
+    fn state(&self) -> PathBuf {
+        self.root.path().join("state")
+    }
+
+    fn run(&self, args: &[&str]) -> Run {
+        let Output {
+            status,
+            stdout,
+            stderr,
+        } = Command::new(env!("CARGO_BIN_EXE_fontlift"))
+            .arg("--fake-registry")
+            .arg(self.registry())
+            .args(args)
+            .env("FONTLIFT_JOURNAL_PATH", self.state().join("journal.json"))
+            .env("FONTLIFT_VAULT_DIR", self.state().join("disabled"))
+            .env("FONTLIFT_CONTENT_STORE", self.state().join("store"))
+            .env(
+                "FONTLIFT_METADATA_CACHE_PATH",
+                self.state().join("metadata-cache.json"),
+            )
+            .env(
+                "FONTLIFT_VALIDATION_CACHE_PATH",
+                self.state().join("validation-cache.json"),
+            )
+            .env("FONTLIFT_POLICY_PATH", self.state().join("policy.json"))
+            .env(
+                "FONTLIFT_PROTECTION_POLICY_PATH",
+                self.state().join("protection.json"),
+            )
+            .env("HOME", self.root.path().join("home"))
+            .env_remove("FONTLIFT_INSTALL_DB_PATH")
+            .env_remove("FONTLIFT_HISTORY_PATH")
+            .env_remove("FONTLIFT_QUARANTINE_DIR")
+            .env_remove("RUST_LOG")
+            .output()
+            .expect("run fontlift");
+        Run {
+            code: status.code().unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        }
+    }
+
+    fn state_file(&self, name: &str) -> Option<Value> {
+        let text = std::fs::read_to_string(self.state().join(name)).ok()?;
+        Some(serde_json::from_str(&text).expect("state file is JSON"))
+    }
+
+    fn listed(&self) -> Vec<Value> {
+        match self.run(&["--json", "list", "--sorted"]).success().json() {
+            Value::Array(faces) => faces,
+            other => panic!("list --json is not an array: {other}"),
+        }
+    }
+}
+
+fn path_arg(path: &Path) -> &str {
+    path.to_str().expect("UTF-8 fixture path")
+}
+
+#[test]
+fn install_list_uninstall_doctor_round_trip() {
+    let sandbox = Sandbox::new();
+    let font = fixture("AtkinsonHyperlegible-Regular.ttf");
+
+    sandbox
+        .run(&["--quiet", "install", path_arg(&font)])
+        .success();
+
+    let faces = sandbox.listed();
+    assert_eq!(faces.len(), 1, "one face after install: {faces:?}");
+    let installed = faces[0]["source"]["path"].as_str().unwrap().to_string();
+    assert!(installed.starts_with(path_arg(&sandbox.registry())));
+    assert_eq!(faces[0]["source"]["scope"], "User");
+
+    let db = sandbox.state_file("installed.json").expect("install db");
+    assert_eq!(db["records"][0]["reason"]["kind"], "explicit");
+
+    let name = faces[0]["postscript_name"].as_str().unwrap().to_string();
+    sandbox
+        .run(&["--quiet", "uninstall", "--name", &name])
+        .success();
+    assert!(sandbox.listed().is_empty());
+
+    let history = sandbox
+        .run(&["--json", "history", "--since", "1h"])
+        .success()
+        .json();
+    let operations: Vec<_> = history
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| (entry["operation"].clone(), entry["outcome"].clone()))
+        .collect();
+    assert_eq!(
+        operations,
+        vec![
+            ("install".into(), "succeeded".into()),
+            ("uninstall".into(), "succeeded".into())
+        ]
+    );
+    assert_eq!(history[0]["files"][0], installed.as_str());
+
+    let doctor = sandbox.run(&["doctor", "--preview"]).success();
+    assert!(
+        doctor.stdout.contains("No interrupted operations"),
+        "{}",
+        doctor.stdout
+    );
+}
+
+#[test]
+fn dry_run_leaves_no_state() {
+    let sandbox = Sandbox::new();
+    let font = fixture("AtkinsonHyperlegible-Regular.otf");
+
+    sandbox
+        .run(&["--dry-run", "install", path_arg(&font)])
+        .success();
+
+    assert!(sandbox.listed().is_empty());
+    assert!(sandbox.state_file("installed.json").is_none());
+    assert!(!sandbox.state().join("history.jsonl").exists());
+}
+
+#[test]
+fn failures_exit_nonzero_with_json_errors() {
+    let sandbox = Sandbox::new();
+
+    let error_code = |run: &Run| -> Value {
+        let error: Value = serde_json::from_str(&run.stderr).expect("JSON error on stderr");
+        error["error"]["code"].clone()
+    };
+
+    let missing = sandbox.run(&["--json", "install", "/no/such/font.ttf"]);
+    assert_eq!(missing.code, 2);
+    assert_eq!(error_code(&missing), "font_not_found");
+
+    let notes = sandbox.root.path().join("notes.txt");
+    std::fs::write(&notes, "not a font").expect("write notes");
+    let not_a_font = sandbox.run(&["--json", "install", path_arg(&notes)]);
+    assert_eq!(not_a_font.code, 4);
+    assert_eq!(error_code(&not_a_font), "invalid_format");
+
+    let font = sandbox.root.path().join("Atkinson.otf");
+    std::fs::copy(fixture("AtkinsonHyperlegible-Regular.otf"), &font).expect("copy fixture");
+    let sums = sandbox.root.path().join("fonts.sums");
+    std::fs::write(&sums, format!("{}  Atkinson.otf\n", "0".repeat(64)))
+        .expect("write checksum manifest");
+    let mismatch = sandbox.run(&[
+        "--json",
+        "install",
+        "--verify",
+        path_arg(&sums),
+        path_arg(&font),
+    ]);
+    assert_eq!(mismatch.code, 7);
+    assert_eq!(error_code(&mismatch), "verification_failed");
+    assert!(sandbox.listed().is_empty());
+
+    let bad_args = sandbox.run(&["install", "--no-such-flag"]);
+    assert_eq!(bad_args.code, 1);
+    assert!(bad_args.stderr.contains("--no-such-flag"));
+
+    assert_eq!(sandbox.run(&["--version"]).success().code, 0);
+}
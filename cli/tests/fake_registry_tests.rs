@@ -1,27 +1,28 @@
-#![cfg(target_os = "macos")]
-// These tests serialize on a process-global `Mutex` to guard the shared
-// `FONTLIFT_FAKE_REGISTRY_ROOT` env var while the awaited CLI handlers run.
-// `#[tokio::test]` uses a single-threaded runtime, so holding the guard across
-// `.await` is safe here; the lock must span the awaits to keep env mutation
-// from racing between tests.
+// The command handlers run against `FakeFontManager`, the file-tree registry
+// from `fontlift_core::testing`, so these tests run on every platform and
+// never touch the OS font registry. The journal, install database, history
+// and caches are still found through env vars, so tests serialize on a
+// process-global `Mutex` while the awaited handlers run. `#[tokio::test]`
+// uses a single-threaded runtime, so holding the guard across `.await` is
+// safe here; the lock must span the awaits to keep env mutation from racing
+// between tests.
 #![allow(clippy::await_holding_lock)]
 
 use std::env;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
-/// Mutex to serialize tests that modify FONTLIFT_FAKE_REGISTRY_ROOT
+/// Mutex to serialize tests that point fontlift's state files at a sandbox
 static ENV_LOCK: Mutex<()> = Mutex::new(());
 
 use fontlift_cli::{
-    handle_cleanup_command, handle_doctor_command, handle_install_command,
-    handle_uninstall_command, ListRender, ListRenderOptions, OperationOptions,
+    handle_doctor_command, handle_install_command, handle_uninstall_command, DoctorStrategy,
+    InstallOptions, ListOutput, ListRender, ListRenderOptions, OperationOptions,
     ValidationStrictness,
 };
 use fontlift_core::{
-    journal, validation_ext::ValidatorConfig, FontManager, FontScope, FontliftFontSource,
+    journal, testing::FakeFontManager, FontError, FontManager, FontScope, FontliftFontSource,
 };
-use fontlift_platform_mac::MacFontManager;
 use serde_json::Value;
 use tempfile::TempDir;
 
@@ -53,6 +54,20 @@ fn quiet_opts() -> OperationOptions {
     OperationOptions::new(false, true, false)
 }
 
+fn list_json_opts() -> ListRenderOptions {
+    ListRenderOptions {
+        show_path: true,
+        show_name: true,
+        sorted: true,
+        sort_by: None,
+        json: true,
+        output: ListOutput::Text,
+        columns: Vec::new(),
+        color: false,
+        details: false,
+    }
+}
+
 struct EnvGuard {
     key: &'static str,
     previous: Option<std::ffi::OsString>,
@@ -76,27 +91,74 @@ impl Drop for EnvGuard {
     }
 }
 
+/// A fake registry in a temporary directory, with fontlift's state files
+/// kept in its `state` folder for as long as it lives.
+struct Sandbox {
+    _guards: Vec<EnvGuard>,
+    root: TempDir,
+}
+
+impl Sandbox {
+    fn new() -> Self {
+        let root = TempDir::new().expect("temp dir for fake registry");
+        let state = root.path().join("state");
+        let _guards = [
+            ("FONTLIFT_JOURNAL_PATH", "journal.json"),
+            ("FONTLIFT_INSTALL_DB_PATH", "installed.json"),
+            ("FONTLIFT_HISTORY_PATH", "history.jsonl"),
+            ("FONTLIFT_METADATA_CACHE_PATH", "metadata-cache.json"),
+            ("FONTLIFT_VALIDATION_CACHE_PATH", "validation-cache.json"),
+            ("FONTLIFT_CONTENT_STORE", "store"),
+            ("FONTLIFT_POLICY_PATH", "policy.json"),
+            ("FONTLIFT_PROTECTION_POLICY_PATH", "protection.json"),
+        ]
+        .into_iter()
+        .map(|(key, name)| EnvGuard::set_path(key, &state.join(name)))
+        .collect();
+        Self { _guards, root }
+    }
+
+    /// The registry root: `Library/Fonts` holds user fonts and
+    /// `System/Library/Fonts` system fonts.
+    fn path(&self) -> &Path {
+        self.root.path()
+    }
+
+    fn manager(&self) -> Arc<dyn FontManager> {
+        Arc::new(FakeFontManager::new(self.path()))
+    }
+
+    /// Require validation, as a managed machine can.
+    fn require_validation(&self) {
+        let policy = self.path().join("state/policy.json");
+        std::fs::create_dir_all(policy.parent().unwrap()).expect("state dir");
+        std::fs::write(&policy, r#"{"validation_strictness": "normal"}"#).expect("write policy");
+    }
+}
+
+fn list_json(manager: &Arc<dyn FontManager>) -> String {
+    let fonts = manager.list_installed_fonts().expect("list");
+    match fontlift_cli::render_list_output(fonts, list_json_opts()).expect("render") {
+        ListRender::Json(json) => json,
+        ListRender::Lines(_) => panic!("expected JSON output"),
+    }
+}
+
 #[tokio::test]
-async fn mac_fake_registry_user_scope_round_trip() {
+async fn user_scope_round_trip() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
-    let mac_manager = MacFontManager::new();
-    assert!(mac_manager.is_fake_registry_enabled());
-    let manager: Arc<dyn FontManager> = Arc::new(mac_manager);
+    let registry = Sandbox::new();
+    let manager = registry.manager();
 
     let source_path = fixture_font();
-    let target_path = temp_root
+    let target_path = registry
         .path()
         .join("Library/Fonts/AtkinsonHyperlegible-Regular.ttf");
 
     handle_install_command(
         manager.clone(),
         vec![source_path.clone()],
-        false,
-        false, // validate
-        ValidationStrictness::Normal,
-        false, // inplace
+        InstallOptions::new(false, false, ValidationStrictness::Normal, false),
         quiet_opts(),
     )
     .await
@@ -122,32 +184,17 @@ async fn mac_fake_registry_user_scope_round_trip() {
         "listed fonts include the installed user font"
     );
 
-    let rendered = fontlift_cli::render_list_output(
-        fonts.clone(),
-        ListRenderOptions {
-            show_path: true,
-            show_name: true,
-            sorted: true,
-            json: true,
-        },
-    )
-    .expect("render list to JSON");
-
-    if let ListRender::Json(json) = rendered {
-        let parsed: Value = serde_json::from_str(&json).expect("valid JSON output");
-        let array = parsed.as_array().expect("list renders to array");
-        assert!(
-            array.iter().any(|entry| {
-                entry["source"]["path"]
-                    .as_str()
-                    .map(|p| p.ends_with("AtkinsonHyperlegible-Regular.ttf"))
-                    .unwrap_or(false)
-            }),
-            "JSON output includes installed font"
-        );
-    } else {
-        panic!("expected JSON render");
-    }
+    let parsed: Value = serde_json::from_str(&list_json(&manager)).expect("valid JSON output");
+    let array = parsed.as_array().expect("list renders to array");
+    assert!(
+        array.iter().any(|entry| {
+            entry["source"]["path"]
+                .as_str()
+                .map(|p| p.ends_with("AtkinsonHyperlegible-Regular.ttf"))
+                .unwrap_or(false)
+        }),
+        "JSON output includes installed font"
+    );
 
     handle_uninstall_command(
         manager.clone(),
@@ -174,24 +221,20 @@ async fn mac_fake_registry_user_scope_round_trip() {
 }
 
 #[tokio::test]
-async fn mac_fake_registry_system_scope_without_admin() {
+async fn system_scope_without_admin() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
-    let manager: Arc<dyn FontManager> = Arc::new(MacFontManager::new());
+    let registry = Sandbox::new();
+    let manager = registry.manager();
 
     let source_path = fixture_font();
-    let system_target = temp_root
+    let system_target = registry
         .path()
         .join("System/Library/Fonts/AtkinsonHyperlegible-Regular.ttf");
 
     handle_install_command(
         manager.clone(),
         vec![source_path.clone()],
-        true,
-        false, // validate
-        ValidationStrictness::Normal,
-        false, // inplace
+        InstallOptions::new(true, false, ValidationStrictness::Normal, false),
         quiet_opts(),
     )
     .await
@@ -218,11 +261,14 @@ async fn mac_fake_registry_system_scope_without_admin() {
 
 /// Test that malformed fonts are rejected when CLI validation is enabled
 #[tokio::test]
-async fn mac_fake_registry_rejects_malformed_font_with_validation() {
+async fn rejects_malformed_font_with_validation() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
-    let manager: Arc<dyn FontManager> = Arc::new(MacFontManager::new());
+    let registry = Sandbox::new();
+    let manager = registry.manager();
+
+    // Required validation fails the install whether the validator rejects
+    // the font or was not built next to the tests.
+    registry.require_validation();
 
     let malformed_path = malformed_fixture();
     assert!(malformed_path.exists(), "malformed fixture must exist");
@@ -231,10 +277,7 @@ async fn mac_fake_registry_rejects_malformed_font_with_validation() {
     let result = handle_install_command(
         manager.clone(),
         vec![malformed_path.clone()],
-        false,
-        true, // validate=true
-        ValidationStrictness::Normal,
-        false, // inplace
+        InstallOptions::new(false, true, ValidationStrictness::Normal, false),
         quiet_opts(),
     )
     .await;
@@ -245,124 +288,69 @@ async fn mac_fake_registry_rejects_malformed_font_with_validation() {
     );
 
     let err_msg = result.unwrap_err().to_string();
-    // The validator should reject the font
     assert!(
-        err_msg.contains("Invalid") || err_msg.contains("validation") || err_msg.contains("parse"),
+        err_msg.to_lowercase().contains("validat"),
         "error should indicate validation failure: {err_msg}"
     );
 
     // Font should NOT be installed
-    let target_path = temp_root.path().join("Library/Fonts/malformed.ttf");
+    let target_path = registry.path().join("Library/Fonts/malformed.ttf");
     assert!(
         !target_path.exists(),
         "malformed font should not be copied to fake registry"
     );
 }
 
-/// Test that malformed fonts CAN be installed when validation is disabled
+/// Test that skipping validation does not let an unreadable font in: the
+/// install still reads its names to look for conflicts, and that read fails
 #[tokio::test]
-async fn mac_fake_registry_accepts_malformed_font_without_validation() {
+async fn rejects_malformed_font_without_validation() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
-    let manager: Arc<dyn FontManager> = Arc::new(MacFontManager::new());
+    let registry = Sandbox::new();
+    let manager = registry.manager();
 
     let malformed_path = malformed_fixture();
     assert!(malformed_path.exists(), "malformed fixture must exist");
 
-    // Install with validation disabled should succeed (at CLI level, Core Text may still reject)
     let result = handle_install_command(
         manager.clone(),
         vec![malformed_path.clone()],
-        false,
-        false, // validate=false
-        ValidationStrictness::Normal,
-        false, // inplace
+        InstallOptions::new(false, false, ValidationStrictness::Normal, false),
         quiet_opts(),
     )
     .await;
 
-    // The fake registry will accept the file even if it's malformed
-    // (Real Core Text would reject it, but fake registry just copies files)
-    assert!(
-        result.is_ok(),
-        "installing malformed font without validation should succeed in fake registry: {:?}",
-        result.err()
-    );
-
-    let target_path = temp_root.path().join("Library/Fonts/malformed.ttf");
-    assert!(
-        target_path.exists(),
-        "malformed font should be copied to fake registry when validation disabled"
-    );
-}
-
-/// Test that the manager-level validation rejects malformed fonts
-#[tokio::test]
-async fn mac_manager_with_validation_rejects_malformed_font() {
-    let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
-
-    // Create manager WITH validation enabled
-    let manager = MacFontManager::with_validation(ValidatorConfig::default());
-
-    let malformed_path = malformed_fixture();
-    let source = FontliftFontSource::new(malformed_path.clone()).with_scope(Some(FontScope::User));
-
-    let result = manager.install_font(&source);
-
     assert!(
-        result.is_err(),
-        "manager with validation should reject malformed font"
+        matches!(result, Err(FontError::InvalidFormat(_))),
+        "an unreadable font is refused without validation too: {result:?}"
     );
-
-    let target_path = temp_root.path().join("Library/Fonts/malformed.ttf");
+    let target_path = registry.path().join("Library/Fonts/malformed.ttf");
     assert!(
         !target_path.exists(),
-        "malformed font should not be installed"
+        "malformed font should not be copied to fake registry"
     );
 }
 
 /// Golden output test: validates exact JSON schema for list --json output
 #[tokio::test]
-async fn mac_fake_registry_list_json_golden_output() {
+async fn list_json_golden_output() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
-    let mac_manager = MacFontManager::new();
-    let manager: Arc<dyn FontManager> = Arc::new(mac_manager);
+    let registry = Sandbox::new();
+    let manager = registry.manager();
 
     // Install the fixture font
     let source_path = fixture_font();
     handle_install_command(
         manager.clone(),
         vec![source_path.clone()],
-        false,
-        false,
-        ValidationStrictness::Normal,
-        false, // inplace
+        InstallOptions::new(false, false, ValidationStrictness::Normal, false),
         quiet_opts(),
     )
     .await
     .expect("install should succeed");
 
     // List and render as JSON
-    let fonts = manager.list_installed_fonts().expect("list");
-    let rendered = fontlift_cli::render_list_output(
-        fonts,
-        ListRenderOptions {
-            show_path: true,
-            show_name: true,
-            sorted: true,
-            json: true,
-        },
-    )
-    .expect("render");
-
-    let ListRender::Json(json) = rendered else {
-        panic!("expected JSON output");
-    };
+    let json = list_json(&manager);
 
     // Parse and validate schema
     let parsed: Vec<Value> = serde_json::from_str(&json).expect("valid JSON array");
@@ -430,12 +418,10 @@ async fn mac_fake_registry_list_json_golden_output() {
 
 /// Test OTF format installation and JSON output
 #[tokio::test]
-async fn mac_fake_registry_otf_format_golden_output() {
+async fn otf_format_golden_output() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
-    let mac_manager = MacFontManager::new();
-    let manager: Arc<dyn FontManager> = Arc::new(mac_manager);
+    let registry = Sandbox::new();
+    let manager = registry.manager();
 
     let source_path = fixture_font_otf();
     assert!(source_path.exists(), "OTF fixture must exist");
@@ -443,30 +429,13 @@ async fn mac_fake_registry_otf_format_golden_output() {
     handle_install_command(
         manager.clone(),
         vec![source_path.clone()],
-        false,
-        false,
-        ValidationStrictness::Normal,
-        false, // inplace
+        InstallOptions::new(false, false, ValidationStrictness::Normal, false),
         quiet_opts(),
     )
     .await
     .expect("OTF install should succeed");
 
-    let fonts = manager.list_installed_fonts().expect("list");
-    let rendered = fontlift_cli::render_list_output(
-        fonts,
-        ListRenderOptions {
-            show_path: true,
-            show_name: true,
-            sorted: true,
-            json: true,
-        },
-    )
-    .expect("render");
-
-    let ListRender::Json(json) = rendered else {
-        panic!("expected JSON output");
-    };
+    let json = list_json(&manager);
 
     let parsed: Vec<Value> = serde_json::from_str(&json).expect("valid JSON array");
     assert_eq!(
@@ -492,12 +461,10 @@ async fn mac_fake_registry_otf_format_golden_output() {
 
 /// Test TTC (font collection) format installation and JSON output
 #[tokio::test]
-async fn mac_fake_registry_ttc_format_golden_output() {
+async fn ttc_format_golden_output() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
-    let mac_manager = MacFontManager::new();
-    let manager: Arc<dyn FontManager> = Arc::new(mac_manager);
+    let registry = Sandbox::new();
+    let manager = registry.manager();
 
     let source_path = fixture_font_ttc();
     assert!(source_path.exists(), "TTC fixture must exist");
@@ -505,30 +472,13 @@ async fn mac_fake_registry_ttc_format_golden_output() {
     handle_install_command(
         manager.clone(),
         vec![source_path.clone()],
-        false,
-        false,
-        ValidationStrictness::Normal,
-        false, // inplace
+        InstallOptions::new(false, false, ValidationStrictness::Normal, false),
         quiet_opts(),
     )
     .await
     .expect("TTC install should succeed");
 
-    let fonts = manager.list_installed_fonts().expect("list");
-    let rendered = fontlift_cli::render_list_output(
-        fonts,
-        ListRenderOptions {
-            show_path: true,
-            show_name: true,
-            sorted: true,
-            json: true,
-        },
-    )
-    .expect("render");
-
-    let ListRender::Json(json) = rendered else {
-        panic!("expected JSON output");
-    };
+    let json = list_json(&manager);
 
     let parsed: Vec<Value> = serde_json::from_str(&json).expect("valid JSON array");
     // TTC may contain multiple fonts, should have at least one
@@ -551,20 +501,19 @@ async fn mac_fake_registry_ttc_format_golden_output() {
 
 /// Test doctor command finds incomplete journal entries
 #[tokio::test]
-async fn mac_fake_registry_doctor_finds_incomplete_operations() {
+async fn doctor_finds_incomplete_operations() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
+    let registry = Sandbox::new();
 
     // Create an incomplete journal entry
     let mut test_journal = journal::Journal::new();
     let actions = vec![
         journal::JournalAction::CopyFile {
             from: fixture_font(),
-            to: temp_root.path().join("Library/Fonts/test-font.ttf"),
+            to: registry.path().join("Library/Fonts/test-font.ttf"),
         },
         journal::JournalAction::RegisterFont {
-            path: temp_root.path().join("Library/Fonts/test-font.ttf"),
+            path: registry.path().join("Library/Fonts/test-font.ttf"),
             scope: FontScope::User,
         },
     ];
@@ -574,7 +523,15 @@ async fn mac_fake_registry_doctor_finds_incomplete_operations() {
     journal::save_journal(&test_journal).expect("save journal");
 
     // Verify doctor command succeeds in preview mode (dry-run)
-    let result = handle_doctor_command(true, quiet_opts()).await;
+    let result = handle_doctor_command(
+        registry.manager(),
+        true,
+        false,
+        DoctorStrategy::Forward,
+        false, // compact
+        quiet_opts(),
+    )
+    .await;
     assert!(
         result.is_ok(),
         "doctor command preview should succeed: {:?}",
@@ -592,13 +549,20 @@ async fn mac_fake_registry_doctor_finds_incomplete_operations() {
 
 /// Test doctor command reports no issues when journal is clean
 #[tokio::test]
-async fn mac_fake_registry_doctor_clean_journal() {
+async fn doctor_clean_journal() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
+    let registry = Sandbox::new();
 
     // Create an empty journal (or just don't create one at all)
-    let result = handle_doctor_command(false, quiet_opts()).await;
+    let result = handle_doctor_command(
+        registry.manager(),
+        false,
+        false,
+        DoctorStrategy::Forward,
+        false, // compact
+        quiet_opts(),
+    )
+    .await;
     assert!(
         result.is_ok(),
         "doctor command on clean system should succeed: {:?}",
@@ -615,13 +579,12 @@ async fn mac_fake_registry_doctor_clean_journal() {
 
 /// Test doctor command recovers incomplete file copy operation (simulated crash)
 #[tokio::test]
-async fn mac_fake_registry_doctor_recovers_incomplete_copy() {
+async fn doctor_recovers_incomplete_copy() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
+    let registry = Sandbox::new();
 
     // Setup: create target directory
-    let target_dir = temp_root.path().join("Library/Fonts");
+    let target_dir = registry.path().join("Library/Fonts");
     std::fs::create_dir_all(&target_dir).expect("create fonts dir");
 
     let source_font = fixture_font();
@@ -643,7 +606,15 @@ async fn mac_fake_registry_doctor_recovers_incomplete_copy() {
     );
 
     // Run doctor (non-preview mode) to trigger recovery
-    let result = handle_doctor_command(false, quiet_opts()).await;
+    let result = handle_doctor_command(
+        registry.manager(),
+        false,
+        false,
+        DoctorStrategy::Forward,
+        false, // compact
+        quiet_opts(),
+    )
+    .await;
     assert!(
         result.is_ok(),
         "doctor command should succeed: {:?}",
@@ -666,46 +637,27 @@ async fn mac_fake_registry_doctor_recovers_incomplete_copy() {
 
 /// Test that validates JSON output can be captured and compared for regression testing
 #[tokio::test]
-async fn mac_fake_registry_golden_output_capture() {
+async fn golden_output_capture() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
-    let mac_manager = MacFontManager::new();
-    let manager: Arc<dyn FontManager> = Arc::new(mac_manager);
+    let registry = Sandbox::new();
+    let manager = registry.manager();
 
     // Install a known fixture font
     let source_path = fixture_font();
     handle_install_command(
         manager.clone(),
         vec![source_path.clone()],
-        false,
-        false,
-        ValidationStrictness::Normal,
-        false, // inplace
+        InstallOptions::new(false, false, ValidationStrictness::Normal, false),
         quiet_opts(),
     )
     .await
     .expect("install should succeed");
 
     // Capture JSON output
-    let fonts = manager.list_installed_fonts().expect("list");
-    let rendered = fontlift_cli::render_list_output(
-        fonts,
-        ListRenderOptions {
-            show_path: true,
-            show_name: true,
-            sorted: true,
-            json: true,
-        },
-    )
-    .expect("render");
-
-    let ListRender::Json(json) = rendered else {
-        panic!("expected JSON output");
-    };
+    let json = list_json(&manager);
 
     // Write golden output to temp file for inspection
-    let golden_path = temp_root.path().join("golden_output.json");
+    let golden_path = registry.path().join("golden_output.json");
     std::fs::write(&golden_path, &json).expect("write golden output");
 
     // Parse to verify structure
@@ -713,21 +665,7 @@ async fn mac_fake_registry_golden_output_capture() {
     assert_eq!(parsed.len(), 1, "should have exactly one font");
 
     // Verify deterministic output (same input produces same output)
-    let fonts2 = manager.list_installed_fonts().expect("list again");
-    let rendered2 = fontlift_cli::render_list_output(
-        fonts2,
-        ListRenderOptions {
-            show_path: true,
-            show_name: true,
-            sorted: true,
-            json: true,
-        },
-    )
-    .expect("render again");
-
-    let ListRender::Json(json2) = rendered2 else {
-        panic!("expected JSON output");
-    };
+    let json2 = list_json(&manager);
 
     assert_eq!(
         json, json2,
@@ -737,13 +675,12 @@ async fn mac_fake_registry_golden_output_capture() {
 
 /// Test doctor command handles incomplete delete operation (rollforward)
 #[tokio::test]
-async fn mac_fake_registry_doctor_recovers_incomplete_delete() {
+async fn doctor_recovers_incomplete_delete() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
+    let registry = Sandbox::new();
 
     // Setup: create a file that should have been deleted
-    let target_dir = temp_root.path().join("Library/Fonts");
+    let target_dir = registry.path().join("Library/Fonts");
     std::fs::create_dir_all(&target_dir).expect("create fonts dir");
     let orphan_file = target_dir.join("orphan-font.ttf");
     std::fs::copy(fixture_font(), &orphan_file).expect("create orphan file");
@@ -763,7 +700,15 @@ async fn mac_fake_registry_doctor_recovers_incomplete_delete() {
     );
 
     // Run doctor to trigger recovery
-    let result = handle_doctor_command(false, quiet_opts()).await;
+    let result = handle_doctor_command(
+        registry.manager(),
+        false,
+        false,
+        DoctorStrategy::Forward,
+        false, // compact
+        quiet_opts(),
+    )
+    .await;
     assert!(
         result.is_ok(),
         "doctor command should succeed: {:?}",
@@ -790,13 +735,12 @@ async fn mac_fake_registry_doctor_recovers_incomplete_delete() {
 /// recognizing the completed copy and preserving the partially installed file
 /// rather than rolling it back.
 #[tokio::test]
-async fn mac_fake_registry_doctor_recovers_interrupted_install() {
+async fn doctor_recovers_interrupted_install() {
     let _env_lock = ENV_LOCK.lock().expect("env lock");
-    let temp_root = TempDir::new().expect("temp dir for fake registry");
-    let _guard = EnvGuard::set_path("FONTLIFT_FAKE_REGISTRY_ROOT", temp_root.path());
+    let registry = Sandbox::new();
 
     // Simulate a completed copy step: the file already sits in Library/Fonts.
-    let target_dir = temp_root.path().join("Library/Fonts");
+    let target_dir = registry.path().join("Library/Fonts");
     std::fs::create_dir_all(&target_dir).expect("create fonts dir");
     let installed_font = target_dir.join("interrupted-font.ttf");
     std::fs::copy(fixture_font(), &installed_font).expect("simulate completed copy");
@@ -817,7 +761,15 @@ async fn mac_fake_registry_doctor_recovers_interrupted_install() {
     journal::save_journal(&test_journal).expect("save journal");
 
     // Run doctor (non-preview) to resume the interrupted install.
-    let result = handle_doctor_command(false, quiet_opts()).await;
+    let result = handle_doctor_command(
+        registry.manager(),
+        false,
+        false,
+        DoctorStrategy::Forward,
+        false, // compact
+        quiet_opts(),
+    )
+    .await;
     assert!(
         result.is_ok(),
         "doctor should handle the interrupted install: {:?}",
@@ -832,8 +784,7 @@ async fn mac_fake_registry_doctor_recovers_interrupted_install() {
     );
 
     // The completed copy step must be recognized, advancing the journal cursor
-    // past it (registration still requires a live manager, so the entry is not
-    // forced to complete).
+    // past it; registration then goes through the manager.
     let reloaded = journal::load_journal().expect("reload journal");
     let entry = reloaded
         .entries
@@ -849,32 +800,33 @@ async fn mac_fake_registry_doctor_recovers_interrupted_install() {
 
 /// Test that `cleanup --cache-only` removes Adobe `AdobeFnt*.lst` manifests.
 ///
-/// This exercises the real cache-clear path (not the fake registry), so
-/// `FONTLIFT_FAKE_REGISTRY_ROOT` must be unset — `clear_font_caches`
-/// short-circuits to a no-op in fake-registry mode. `FONTLIFT_TEST_CACHE_ROOT`
-/// sandboxes all deletions to a temp dir so no real cache is touched.
+/// This exercises the real macOS cache-clear path, not the fake registry,
+/// which has no caches. `FONTLIFT_TEST_CACHE_ROOT` sandboxes all deletions
+/// to a temp dir so no real cache is touched.
+#[cfg(target_os = "macos")]
 #[tokio::test]
 async fn cleanup_cache_only_clears_adobe_font_lists() {
-    let _env_lock = ENV_LOCK.lock().expect("env lock");
+    use fontlift_cli::{handle_cleanup_command, CleanupOptions};
 
-    let fake_prev = env::var_os("FONTLIFT_FAKE_REGISTRY_ROOT");
-    env::remove_var("FONTLIFT_FAKE_REGISTRY_ROOT");
-
-    let cache_root = TempDir::new().expect("temp dir for cache root");
-    let _cache_guard = EnvGuard::set_path("FONTLIFT_TEST_CACHE_ROOT", cache_root.path());
+    let _env_lock = ENV_LOCK.lock().expect("env lock");
+    let sandbox = Sandbox::new();
+    let _cache_guard = EnvGuard::set_path("FONTLIFT_TEST_CACHE_ROOT", sandbox.path());
 
     // Adobe apps keep an AdobeFnt*.lst manifest under TypeSupport.
-    let type_support = cache_root
+    let type_support = sandbox
         .path()
         .join("Library/Application Support/Adobe/TypeSupport");
     std::fs::create_dir_all(&type_support).expect("create Adobe TypeSupport");
     let adobe_list = type_support.join("AdobeFnt20.lst");
     std::fs::write(&adobe_list, b"cache").expect("write AdobeFnt list");
 
-    let manager: Arc<dyn FontManager> = Arc::new(MacFontManager::new());
+    let manager: Arc<dyn FontManager> = Arc::new(fontlift_platform_mac::MacFontManager::new());
 
-    // admin=false, prune_only=false, cache_only=true
-    let result = handle_cleanup_command(manager, false, false, true, quiet_opts()).await;
+    let cleanup = CleanupOptions {
+        cache_only: true,
+        ..CleanupOptions::default()
+    };
+    let result = handle_cleanup_command(manager, false, cleanup, quiet_opts()).await;
     assert!(
         result.is_ok(),
         "cache-only cleanup should succeed: {:?}",
@@ -885,9 +837,4 @@ async fn cleanup_cache_only_clears_adobe_font_lists() {
         !adobe_list.exists(),
         "cleanup --cache-only should remove Adobe AdobeFnt*.lst manifests"
     );
-
-    // Restore the prior fake-registry env state for subsequent tests.
-    if let Some(prev) = fake_prev {
-        env::set_var("FONTLIFT_FAKE_REGISTRY_ROOT", prev);
-    }
 }
@@ -0,0 +1,119 @@
+//! A custom recovery executor for fontlift's journal.
+//!
+//! Run with: `cargo run -p fontlift-core --example custom_recovery`
+//!
+//! `fontlift doctor` is one way to finish operations a crash interrupted.
+//! Tools that manage fonts their own way (a deployment agent, a GUI that
+//! wants to ask before each step) can run recovery themselves:
+//!
+//! 1. **Record** an operation, as an installer would, and stop halfway
+//! 2. **Recover** it with an executor that decides what to do with each
+//!    remaining step and [`RecoveryPolicy`]
+//! 3. **Report** the results, which serialize to JSON
+//!
+//! The example works in a temporary directory and points
+//! `FONTLIFT_JOURNAL_PATH` there, so it never touches the real journal or
+//! your fonts.
+
+use fontlift_core::journal::{self, JournalAction, JournalEntry, RecoveryExecutor, RecoveryPolicy};
+use fontlift_core::{links, FontError, FontResult, FontScope};
+use std::cell::RefCell;
+use std::fs;
+
+/// Replays file steps, leaves registration to the user, and keeps a log.
+#[derive(Default)]
+struct FileOnlyExecutor {
+    log: RefCell<Vec<String>>,
+}
+
+impl RecoveryExecutor for FileOnlyExecutor {
+    fn execute(
+        &self,
+        entry: &JournalEntry,
+        action: &JournalAction,
+        policy: RecoveryPolicy,
+    ) -> FontResult<bool> {
+        let operation = entry.description.as_deref().unwrap_or("operation");
+        self.log.borrow_mut().push(format!(
+            "{operation}: {} ({policy:?})",
+            action.description()
+        ));
+
+        match (action, policy) {
+            (_, RecoveryPolicy::Skip) => Ok(true),
+            (JournalAction::CopyFile { from, to }, RecoveryPolicy::RollForward) => {
+                if let Some(dir) = to.parent() {
+                    fs::create_dir_all(dir).map_err(FontError::IoError)?;
+                }
+                // Renamed into place, so an interrupted copy never leaves a
+                // truncated `to` that later looks finished.
+                links::remove_partial(to).map_err(FontError::IoError)?;
+                links::copy_file(from, to).map_err(FontError::IoError)?;
+                Ok(true)
+            }
+            (JournalAction::DeleteFile { path }, RecoveryPolicy::RollForward) => {
+                fs::remove_file(path).map_err(FontError::IoError)?;
+                Ok(true)
+            }
+            // Registration needs a FontManager, and a changed file needs a
+            // person to look at it: stop here and leave the entry for
+            // `fontlift doctor`.
+            _ => Ok(false),
+        }
+    }
+}
+
+fn main() -> FontResult<()> {
+    let workspace = tempfile::tempdir().map_err(FontError::IoError)?;
+    std::env::set_var(
+        "FONTLIFT_JOURNAL_PATH",
+        workspace.path().join("journal.json"),
+    );
+
+    // Step 1: an install that was interrupted before the copy.
+    let source = workspace.path().join("Downloads/Demo-Regular.ttf");
+    let target = workspace.path().join("Fonts/Demo-Regular.ttf");
+    fs::create_dir_all(source.parent().unwrap()).map_err(FontError::IoError)?;
+    fs::write(&source, b"not really a font").map_err(FontError::IoError)?;
+    journal::update_journal(|journal| {
+        Ok(journal.record_operation(
+            vec![
+                JournalAction::CopyFile {
+                    from: source.clone(),
+                    to: target.clone(),
+                },
+                JournalAction::RegisterFont {
+                    path: target.clone(),
+                    scope: FontScope::User,
+                },
+            ],
+            Some("Install Demo-Regular.ttf".to_string()),
+        ))
+    })?;
+
+    // Step 2: recover with our own executor.
+    let executor = FileOnlyExecutor::default();
+    let results = journal::recover_with(&executor)?;
+
+    // Step 3: report.
+    for line in executor.log.borrow().iter() {
+        println!("— {line}");
+    }
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&results)
+            .map_err(|e| FontError::InvalidFormat(e.to_string()))?
+    );
+    println!("Copied: {}", target.exists());
+
+    let journal = journal::load_journal()?;
+    for entry in journal.incomplete_entries() {
+        println!(
+            "Still incomplete: {} at step {} of {}",
+            entry.description.as_deref().unwrap_or("operation"),
+            entry.current_step + 1,
+            entry.actions.len()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,286 @@
+//! Extract font files from the `.zip` / `.tar.gz` bundles foundries ship.
+//!
+//! `fontlift install FontPack.zip` unpacks the archive into a private temp
+//! directory and installs the font files it finds there. Only entries with a
+//! recognised font extension are written out; everything else (licence PDFs,
+//! specimens, `__MACOSX` resource-fork noise, `._*` AppleDouble files) is
+//! skipped without touching disk.
+//!
+//! Extraction is guarded against the usual archive hazards:
+//!
+//! - entries that would escape the temp directory (`../`, absolute paths) are
+//!   ignored;
+//! - individual entries larger than the validator's size cap are rejected, so
+//!   a decompression bomb cannot fill the disk.
+//!
+//! The extraction code is only compiled with the `archive` feature.
+
+use crate::{validation, FontError, FontResult};
+use std::path::{Component, Path, PathBuf};
+
+/// Is this a bundle format fontlift can unpack?
+pub fn is_archive(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    name.ends_with(".zip")
+        || name.ends_with(".tar.gz")
+        || name.ends_with(".tgz")
+        || name.ends_with(".tar")
+}
+
+/// Should an archive entry at `entry` be extracted?
+///
+/// Accepts font files only and drops macOS archive noise.
+pub fn is_font_entry(entry: &Path) -> bool {
+    let noise = entry.components().any(|c| match c {
+        Component::Normal(part) => {
+            let part = part.to_string_lossy();
+            part == "__MACOSX" || part.starts_with("._")
+        }
+        _ => false,
+    });
+
+    !noise && validation::is_valid_font_extension(entry)
+}
+
+/// Turn an archive entry name into a safe relative path, or `None` if it
+/// would escape the extraction directory.
+pub fn sanitize_entry_path(entry: &Path) -> Option<PathBuf> {
+    let mut clean = PathBuf::new();
+    for component in entry.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!clean.as_os_str().is_empty()).then_some(clean)
+}
+
+/// Fonts extracted from one archive into a private temp directory.
+///
+/// The directory is deleted when this value is dropped, so keep it alive until
+/// every font has been copied or registered.
+#[derive(Debug)]
+pub struct ExtractedArchive {
+    pub archive: PathBuf,
+    /// Extracted font files, sorted.
+    pub fonts: Vec<PathBuf>,
+    dir: PathBuf,
+}
+
+impl Drop for ExtractedArchive {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[cfg(feature = "archive")]
+mod extract {
+    use super::*;
+    use crate::validation_ext::DEFAULT_MAX_SIZE;
+    use std::fs::{self, File};
+    use std::io::{self, Read};
+
+    fn write_entry(
+        dir: &Path,
+        name: &Path,
+        reader: &mut dyn Read,
+        fonts: &mut Vec<PathBuf>,
+    ) -> FontResult<()> {
+        if !is_font_entry(name) {
+            return Ok(());
+        }
+        let Some(relative) = sanitize_entry_path(name) else {
+            log::warn!("Skipping unsafe archive entry: {}", name.display());
+            return Ok(());
+        };
+
+        let target = dir.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(FontError::IoError)?;
+        }
+
+        let mut out = File::create(&target).map_err(FontError::IoError)?;
+        let written = io::copy(&mut reader.take(DEFAULT_MAX_SIZE + 1), &mut out)
+            .map_err(FontError::IoError)?;
+        if written > DEFAULT_MAX_SIZE {
+            return Err(FontError::InvalidFormat(format!(
+                "Archive entry exceeds the {} MB size limit: {}",
+                DEFAULT_MAX_SIZE / (1024 * 1024),
+                name.display()
+            )));
+        }
+
+        fonts.push(target);
+        Ok(())
+    }
+
+    fn extract_zip(archive: &Path, dir: &Path, fonts: &mut Vec<PathBuf>) -> FontResult<()> {
+        let file = File::open(archive).map_err(FontError::IoError)?;
+        let mut zip = zip::ZipArchive::new(file)
+            .map_err(|e| FontError::InvalidFormat(format!("Invalid zip archive: {e}")))?;
+
+        for i in 0..zip.len() {
+            let mut entry = zip
+                .by_index(i)
+                .map_err(|e| FontError::InvalidFormat(format!("Invalid zip entry: {e}")))?;
+            if entry.is_dir() {
+                continue;
+            }
+            let name = PathBuf::from(entry.name());
+            write_entry(dir, &name, &mut entry, fonts)?;
+        }
+        Ok(())
+    }
+
+    fn extract_tar<R: Read>(reader: R, dir: &Path, fonts: &mut Vec<PathBuf>) -> FontResult<()> {
+        let mut tar = tar::Archive::new(reader);
+        let entries = tar
+            .entries()
+            .map_err(|e| FontError::InvalidFormat(format!("Invalid tar archive: {e}")))?;
+
+        for entry in entries {
+            let mut entry =
+                entry.map_err(|e| FontError::InvalidFormat(format!("Invalid tar entry: {e}")))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = entry.path().map_err(FontError::IoError)?.into_owned();
+            write_entry(dir, &name, &mut entry, fonts)?;
+        }
+        Ok(())
+    }
+
+    /// Extract every font file in `archive` into a fresh temp directory.
+    ///
+    /// Returns [`FontError::InvalidFormat`] when the archive cannot be read or
+    /// contains no font files.
+    pub fn extract_fonts(archive: &Path) -> FontResult<ExtractedArchive> {
+        if !archive.is_file() {
+            return Err(FontError::FontNotFound(archive.to_path_buf()));
+        }
+
+        let dir = std::env::temp_dir().join(format!("fontlift-archive-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).map_err(FontError::IoError)?;
+
+        // Build the guard first so the temp directory is cleaned up on error.
+        let mut extracted = ExtractedArchive {
+            archive: archive.to_path_buf(),
+            fonts: Vec::new(),
+            dir,
+        };
+
+        let name = archive.to_string_lossy().to_lowercase();
+        let mut fonts = Vec::new();
+        if name.ends_with(".zip") {
+            extract_zip(archive, &extracted.dir, &mut fonts)?;
+        } else {
+            let file = File::open(archive).map_err(FontError::IoError)?;
+            if name.ends_with(".tar") {
+                extract_tar(file, &extracted.dir, &mut fonts)?;
+            } else {
+                extract_tar(
+                    flate2::read::GzDecoder::new(file),
+                    &extracted.dir,
+                    &mut fonts,
+                )?;
+            }
+        }
+
+        if fonts.is_empty() {
+            return Err(FontError::InvalidFormat(format!(
+                "No font files found in archive: {}",
+                archive.display()
+            )));
+        }
+
+        fonts.sort();
+        extracted.fonts = fonts;
+        Ok(extracted)
+    }
+}
+
+#[cfg(feature = "archive")]
+pub use extract::extract_fonts;
+
+/// Stand-in used when fontlift was built without the `archive` feature.
+#[cfg(not(feature = "archive"))]
+pub fn extract_fonts(archive: &Path) -> FontResult<ExtractedArchive> {
+    Err(FontError::UnsupportedOperation(format!(
+        "Installing from archives requires the `archive` feature: {}",
+        archive.display()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognises_archive_extensions() {
+        assert!(is_archive(Path::new("/tmp/FontPack.zip")));
+        assert!(is_archive(Path::new("/tmp/FontPack.TAR.GZ")));
+        assert!(is_archive(Path::new("/tmp/FontPack.tgz")));
+        assert!(!is_archive(Path::new("/tmp/Font.ttf")));
+    }
+
+    #[test]
+    fn filters_macos_noise_and_non_fonts() {
+        assert!(is_font_entry(Path::new("Pack/OTF/Inter-Bold.otf")));
+        assert!(!is_font_entry(Path::new(
+            "__MACOSX/Pack/OTF/._Inter-Bold.otf"
+        )));
+        assert!(!is_font_entry(Path::new("Pack/._Inter-Bold.otf")));
+        assert!(!is_font_entry(Path::new("Pack/LICENSE.pdf")));
+    }
+
+    #[test]
+    fn rejects_entries_escaping_the_extraction_dir() {
+        assert_eq!(
+            sanitize_entry_path(Path::new("./Pack/Font.ttf")),
+            Some(PathBuf::from("Pack/Font.ttf"))
+        );
+        assert_eq!(sanitize_entry_path(Path::new("../evil.ttf")), None);
+        assert_eq!(sanitize_entry_path(Path::new("/etc/evil.ttf")), None);
+    }
+
+    #[cfg(feature = "archive")]
+    #[test]
+    fn extracts_nested_fonts_from_zip() {
+        use std::io::Write;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let archive_path = tmp.path().join("Pack.zip");
+        {
+            let file = std::fs::File::create(&archive_path).unwrap();
+            let mut zip = zip::ZipWriter::new(file);
+            let options = zip::write::SimpleFileOptions::default();
+            for name in [
+                "Pack/TTF/Alpha-Regular.ttf",
+                "Pack/OTF/Alpha-Bold.otf",
+                "__MACOSX/Pack/TTF/._Alpha-Regular.ttf",
+                "Pack/README.txt",
+            ] {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(b"font").unwrap();
+            }
+            zip.finish().unwrap();
+        }
+
+        let extracted = extract_fonts(&archive_path).unwrap();
+        let names: Vec<_> = extracted
+            .fonts
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["Alpha-Bold.otf", "Alpha-Regular.ttf"]);
+
+        let dir = extracted.dir.clone();
+        drop(extracted);
+        assert!(!dir.exists(), "temp dir is removed on drop");
+    }
+}
@@ -0,0 +1,523 @@
+//! Rule-based audit of installed fonts (`fontlift audit`).
+//!
+//! Every check is an [`AuditRule`] trait object with a stable id, a default
+//! [`Severity`] and the scopes it applies to. [`AuditEngine`] takes one
+//! [`AuditSnapshot`] of installed-font metadata and runs the selected rules
+//! over it in parallel, so adding a rule never means re-listing fonts.
+//!
+//! Each [`AuditFinding`] carries an id derived from the rule id and the
+//! finding's subject (path or name). The id survives reordering and unrelated
+//! changes, which lets CI keep an [`AuditBaseline`] of accepted findings and
+//! fail only on new ones.
+
+use crate::{
+    digest, protection, sorting, validation, FontError, FontResult, FontScope, FontliftFontFaceInfo,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// How bad a finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// One problem reported by a rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditFinding {
+    /// Stable id: same rule + same subject → same id across runs.
+    pub id: String,
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+impl AuditFinding {
+    /// Build a finding whose id is derived from `rule` and `subject`.
+    ///
+    /// `subject` should identify what the finding is about (a path, a
+    /// PostScript name), not describe it, so rewording a message does not
+    /// change the id.
+    pub fn new(
+        rule: &str,
+        severity: Severity,
+        subject: &str,
+        message: String,
+        path: Option<PathBuf>,
+    ) -> Self {
+        let hash = digest::sha256_hex(format!("{rule}\0{}", subject.to_lowercase()).as_bytes());
+        Self {
+            id: format!("{rule}:{}", &hash[..12]),
+            rule: rule.to_string(),
+            severity,
+            message,
+            path,
+        }
+    }
+}
+
+/// Installed-font metadata shared by every rule in one run.
+#[derive(Debug, Clone, Default)]
+pub struct AuditSnapshot {
+    pub fonts: Vec<FontliftFontFaceInfo>,
+}
+
+impl AuditSnapshot {
+    pub fn new(fonts: Vec<FontliftFontFaceInfo>) -> Self {
+        Self { fonts }
+    }
+
+    /// Faces a rule limited to `scopes` should look at. Faces whose scope the
+    /// platform did not report are always included.
+    fn in_scopes(&self, scopes: &[FontScope]) -> Vec<&FontliftFontFaceInfo> {
+        self.fonts
+            .iter()
+            .filter(|f| f.source.scope.map_or(true, |s| scopes.contains(&s)))
+            .collect()
+    }
+}
+
+/// A single audit check.
+pub trait AuditRule: Send + Sync {
+    /// Stable kebab-case id used by `--only`, `--skip` and finding ids.
+    fn id(&self) -> &'static str;
+
+    /// One-line summary for `fontlift audit --list-rules`.
+    fn description(&self) -> &'static str;
+
+    fn severity(&self) -> Severity;
+
+    /// Scopes this rule inspects. Defaults to both.
+    fn scopes(&self) -> &'static [FontScope] {
+        &[FontScope::User, FontScope::System]
+    }
+
+    fn check(&self, fonts: &[&FontliftFontFaceInfo]) -> Vec<AuditFinding>;
+}
+
+/// Registered fonts whose file is gone.
+pub struct MissingFileRule;
+
+impl AuditRule for MissingFileRule {
+    fn id(&self) -> &'static str {
+        "missing-file"
+    }
+
+    fn description(&self) -> &'static str {
+        "Registered font file no longer exists on disk"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, fonts: &[&FontliftFontFaceInfo]) -> Vec<AuditFinding> {
+        let missing: BTreeSet<&Path> = fonts
+            .iter()
+            .map(|f| f.source.path.as_path())
+            .filter(|path| !path.exists())
+            .collect();
+
+        missing
+            .into_iter()
+            .map(|path| {
+                AuditFinding::new(
+                    self.id(),
+                    self.severity(),
+                    &path.to_string_lossy(),
+                    format!("{} is registered but missing", path.display()),
+                    Some(path.to_path_buf()),
+                )
+            })
+            .collect()
+    }
+}
+
+/// The same PostScript name served by more than one file.
+pub struct DuplicatePostScriptNameRule;
+
+impl AuditRule for DuplicatePostScriptNameRule {
+    fn id(&self) -> &'static str {
+        "duplicate-postscript-name"
+    }
+
+    fn description(&self) -> &'static str {
+        "Several files register the same PostScript name"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, fonts: &[&FontliftFontFaceInfo]) -> Vec<AuditFinding> {
+        let mut by_name: BTreeMap<String, BTreeSet<&Path>> = BTreeMap::new();
+        for font in fonts {
+            by_name
+                .entry(font.postscript_name.to_lowercase())
+                .or_default()
+                .insert(font.source.path.as_path());
+        }
+
+        by_name
+            .into_iter()
+            .filter(|(_, paths)| paths.len() > 1)
+            .map(|(name, paths)| {
+                let listed: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+                AuditFinding::new(
+                    self.id(),
+                    self.severity(),
+                    &name,
+                    format!(
+                        "PostScript name '{}' is provided by {} files: {}",
+                        name,
+                        paths.len(),
+                        listed.join(", ")
+                    ),
+                    None,
+                )
+            })
+            .collect()
+    }
+}
+
+/// User-scope files sitting in an OS-owned font directory.
+pub struct UserFontInSystemDirRule;
+
+impl AuditRule for UserFontInSystemDirRule {
+    fn id(&self) -> &'static str {
+        "user-font-in-system-dir"
+    }
+
+    fn description(&self) -> &'static str {
+        "User-scope registration points into a protected system font directory"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn scopes(&self) -> &'static [FontScope] {
+        &[FontScope::User]
+    }
+
+    fn check(&self, fonts: &[&FontliftFontFaceInfo]) -> Vec<AuditFinding> {
+        fonts
+            .iter()
+            .filter(|f| f.source.scope == Some(FontScope::User))
+            .map(|f| f.source.path.as_path())
+            .filter(|path| protection::is_protected_system_font_path(path))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|path| {
+                AuditFinding::new(
+                    self.id(),
+                    self.severity(),
+                    &path.to_string_lossy(),
+                    format!(
+                        "{} is registered per-user but lives in a system font directory",
+                        path.display()
+                    ),
+                    Some(path.to_path_buf()),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Registrations pointing at files without a font extension.
+pub struct UnsupportedExtensionRule;
+
+impl AuditRule for UnsupportedExtensionRule {
+    fn id(&self) -> &'static str {
+        "unsupported-extension"
+    }
+
+    fn description(&self) -> &'static str {
+        "Registered file does not have a recognised font extension"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, fonts: &[&FontliftFontFaceInfo]) -> Vec<AuditFinding> {
+        fonts
+            .iter()
+            .map(|f| f.source.path.as_path())
+            .filter(|path| !validation::is_valid_font_extension(path))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|path| {
+                AuditFinding::new(
+                    self.id(),
+                    self.severity(),
+                    &path.to_string_lossy(),
+                    format!("{} has an unrecognised extension", path.display()),
+                    Some(path.to_path_buf()),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Everything one audit run produced.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditReport {
+    /// Ids of the rules that ran, in registration order.
+    pub rules: Vec<String>,
+    /// Findings not covered by the baseline, sorted by rule then id.
+    pub findings: Vec<AuditFinding>,
+    /// How many findings the baseline suppressed.
+    pub suppressed: usize,
+}
+
+impl AuditReport {
+    /// Drop findings the baseline already accepts.
+    pub fn apply_baseline(&mut self, baseline: &AuditBaseline) {
+        let before = self.findings.len();
+        self.findings.retain(|f| !baseline.ids.contains(&f.id));
+        self.suppressed += before - self.findings.len();
+    }
+
+    pub fn has_severity(&self, at_least: Severity) -> bool {
+        self.findings.iter().any(|f| f.severity >= at_least)
+    }
+}
+
+/// Finding ids accepted as known, stored as JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditBaseline {
+    pub ids: BTreeSet<String>,
+}
+
+impl AuditBaseline {
+    pub fn from_report(report: &AuditReport) -> Self {
+        Self {
+            ids: report.findings.iter().map(|f| f.id.clone()).collect(),
+        }
+    }
+
+    pub fn load(path: &Path) -> FontResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+            _ => FontError::IoError(e),
+        })?;
+        serde_json::from_str(&content).map_err(|e| {
+            FontError::InvalidFormat(format!(
+                "Failed to parse audit baseline {}: {e}",
+                path.display()
+            ))
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> FontResult<()> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize audit baseline: {e}"))
+        })?;
+        std::fs::write(path, content).map_err(FontError::IoError)
+    }
+}
+
+/// A set of rules to run against a snapshot.
+pub struct AuditEngine {
+    rules: Vec<Box<dyn AuditRule>>,
+}
+
+impl Default for AuditEngine {
+    fn default() -> Self {
+        Self::with_default_rules()
+    }
+}
+
+impl AuditEngine {
+    /// An engine with no rules; add them with [`AuditEngine::with_rule`].
+    pub fn empty() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// The built-in rule set.
+    pub fn with_default_rules() -> Self {
+        Self::empty()
+            .with_rule(MissingFileRule)
+            .with_rule(DuplicatePostScriptNameRule)
+            .with_rule(UserFontInSystemDirRule)
+            .with_rule(UnsupportedExtensionRule)
+    }
+
+    pub fn with_rule(mut self, rule: impl AuditRule + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &dyn AuditRule> {
+        self.rules.iter().map(|r| r.as_ref())
+    }
+
+    /// Keep only the rules in `only` (all when empty), minus those in `skip`.
+    ///
+    /// Unknown ids are an error so a typo in CI does not silently disable a
+    /// check.
+    pub fn select(mut self, only: &[String], skip: &[String]) -> FontResult<Self> {
+        let known: BTreeSet<&str> = self.rules.iter().map(|r| r.id()).collect();
+        if let Some(unknown) = only
+            .iter()
+            .chain(skip)
+            .find(|id| !known.contains(id.as_str()))
+        {
+            return Err(FontError::InvalidArgument(format!(
+                "Unknown audit rule '{}' (known: {})",
+                unknown,
+                known.into_iter().collect::<Vec<_>>().join(", ")
+            )));
+        }
+
+        self.rules.retain(|rule| {
+            let id = rule.id().to_string();
+            (only.is_empty() || only.contains(&id)) && !skip.contains(&id)
+        });
+        Ok(self)
+    }
+
+    /// Run every rule over `snapshot`, one thread per rule.
+    pub fn run(&self, snapshot: &AuditSnapshot) -> AuditReport {
+        let mut findings: Vec<AuditFinding> = std::thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .rules
+                .iter()
+                .map(|rule| {
+                    scope.spawn(move || {
+                        let fonts = snapshot.in_scopes(rule.scopes());
+                        rule.check(&fonts)
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_default())
+                .collect()
+        });
+
+        // Within a rule, report faces in family/weight/italic order so a
+        // family's findings read together; findings without a path go last.
+        let mut ordered: Vec<&FontliftFontFaceInfo> = snapshot.fonts.iter().collect();
+        ordered.sort_by(|a, b| sorting::compare_faces(a, b));
+        let mut rank: HashMap<&Path, usize> = HashMap::new();
+        for (i, face) in ordered.iter().enumerate() {
+            rank.entry(face.source.path.as_path()).or_insert(i);
+        }
+        let rank_of = |finding: &AuditFinding| {
+            finding
+                .path
+                .as_deref()
+                .and_then(|path| rank.get(path).copied())
+                .unwrap_or(usize::MAX)
+        };
+        findings.sort_by(|a, b| {
+            a.rule
+                .cmp(&b.rule)
+                .then_with(|| rank_of(a).cmp(&rank_of(b)))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        let mut seen = HashSet::new();
+        findings.retain(|finding| seen.insert(finding.id.clone()));
+
+        AuditReport {
+            rules: self.rules.iter().map(|r| r.id().to_string()).collect(),
+            findings,
+            suppressed: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FontliftFontSource;
+
+    fn face(path: &Path, name: &str, scope: FontScope) -> FontliftFontFaceInfo {
+        FontliftFontFaceInfo::new(
+            FontliftFontSource::new(path.to_path_buf()).with_scope(Some(scope)),
+            name.into(),
+            name.into(),
+            "Family".into(),
+            "Regular".into(),
+        )
+    }
+
+    fn snapshot(dir: &Path) -> AuditSnapshot {
+        let present = dir.join("Alpha.ttf");
+        std::fs::write(&present, b"font").unwrap();
+        AuditSnapshot::new(vec![
+            face(&present, "Alpha", FontScope::User),
+            face(&dir.join("Gone.ttf"), "Alpha", FontScope::User),
+            face(
+                Path::new("/Library/Fonts/Beta.ttf"),
+                "Beta",
+                FontScope::User,
+            ),
+        ])
+    }
+
+    #[test]
+    fn default_rules_report_sorted_findings_with_stable_ids() {
+        let tmp = tempfile::tempdir().unwrap();
+        let snapshot = snapshot(tmp.path());
+
+        let report = AuditEngine::with_default_rules().run(&snapshot);
+        let rules: Vec<&str> = report.findings.iter().map(|f| f.rule.as_str()).collect();
+        assert_eq!(
+            rules,
+            vec![
+                "duplicate-postscript-name",
+                "missing-file",
+                "missing-file",
+                "user-font-in-system-dir"
+            ]
+        );
+        assert!(report.has_severity(Severity::Error));
+
+        let again = AuditEngine::with_default_rules().run(&snapshot);
+        assert_eq!(report.findings, again.findings, "ids are deterministic");
+    }
+
+    #[test]
+    fn selection_and_baseline_filter_findings() {
+        let tmp = tempfile::tempdir().unwrap();
+        let snapshot = snapshot(tmp.path());
+
+        let engine = AuditEngine::with_default_rules()
+            .select(&["missing-file".into()], &[])
+            .unwrap();
+        let mut report = engine.run(&snapshot);
+        assert_eq!(report.rules, vec!["missing-file"]);
+        assert_eq!(report.findings.len(), 2);
+
+        let baseline = AuditBaseline::from_report(&report);
+        let path = tmp.path().join("baseline.json");
+        baseline.save(&path).unwrap();
+        report.apply_baseline(&AuditBaseline::load(&path).unwrap());
+        assert!(report.findings.is_empty());
+        assert_eq!(report.suppressed, 2);
+
+        assert!(AuditEngine::with_default_rules()
+            .select(&[], &["no-such-rule".into()])
+            .is_err());
+    }
+}
@@ -0,0 +1,268 @@
+//! Verify installed fonts against a foundry-published catalog.
+//!
+//! Licensing audits ask "are the fonts on this machine exactly the ones we
+//! paid for?". Foundries (or an internal font team) can answer that with a
+//! catalog: a JSON file listing each licensed face by PostScript name, with
+//! the licensed version and the SHA-256 of the shipped file.
+//!
+//! ```json
+//! {
+//!   "name": "Acme Sans license #1234",
+//!   "fonts": [
+//!     { "postscript_name": "AcmeSans-Regular", "version": "2.003", "sha256": "9f86d0…" }
+//!   ]
+//! }
+//! ```
+//!
+//! [`verify_against_catalog`] compares that list with the installed fonts and
+//! produces a [`ComplianceReport`] with one [`ComplianceFinding`] per
+//! installed copy (or per missing font).
+
+use crate::{digest, metadata, FontError, FontResult, FontliftFontFaceInfo};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// One licensed face in a catalog.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    pub postscript_name: String,
+    /// Licensed version, compared against `head.fontRevision`.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// SHA-256 of the file as shipped by the foundry.
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+/// A foundry or internal catalog of licensed fonts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Catalog {
+    /// Free-form label such as an order or license number.
+    #[serde(default)]
+    pub name: Option<String>,
+    pub fonts: Vec<CatalogEntry>,
+}
+
+/// Outcome for one catalog entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceStatus {
+    /// Version and hash match the catalog.
+    Verified,
+    /// The installed version is older than the licensed one.
+    Outdated,
+    /// The installed version differs from the licensed one in some other way.
+    VersionMismatch,
+    /// Same version, different bytes: the file was modified.
+    Tampered,
+    /// No installed face carries this PostScript name.
+    NotInstalled,
+}
+
+impl ComplianceStatus {
+    pub fn is_compliant(self) -> bool {
+        matches!(self, ComplianceStatus::Verified)
+    }
+}
+
+/// Result for one catalog entry and (when installed) one installed file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceFinding {
+    pub postscript_name: String,
+    pub status: ComplianceStatus,
+    pub path: Option<PathBuf>,
+    pub expected_version: Option<String>,
+    pub installed_version: Option<String>,
+    pub expected_sha256: Option<String>,
+    pub actual_sha256: Option<String>,
+}
+
+/// Everything [`verify_against_catalog`] found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceReport {
+    pub catalog: Option<String>,
+    pub findings: Vec<ComplianceFinding>,
+}
+
+impl ComplianceReport {
+    pub fn is_compliant(&self) -> bool {
+        self.findings.iter().all(|f| f.status.is_compliant())
+    }
+
+    /// Findings that need attention.
+    pub fn violations(&self) -> Vec<&ComplianceFinding> {
+        self.findings
+            .iter()
+            .filter(|f| !f.status.is_compliant())
+            .collect()
+    }
+}
+
+/// Read a catalog JSON file.
+pub fn load_catalog(path: &Path) -> FontResult<Catalog> {
+    let content = std::fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    serde_json::from_str(&content).map_err(|e| {
+        FontError::InvalidFormat(format!("Failed to parse catalog {}: {e}", path.display()))
+    })
+}
+
+fn classify(
+    entry: &CatalogEntry,
+    installed_version: Option<&str>,
+    actual_sha256: Option<&str>,
+) -> ComplianceStatus {
+    if let (Some(expected), Some(installed)) = (entry.version.as_deref(), installed_version) {
+        match (
+            metadata::parse_version(expected),
+            metadata::parse_version(installed),
+        ) {
+            (Some(e), Some(i)) if i < e => return ComplianceStatus::Outdated,
+            (Some(e), Some(i)) if i > e => return ComplianceStatus::VersionMismatch,
+            _ => {}
+        }
+    }
+
+    match (entry.sha256.as_deref(), actual_sha256) {
+        (Some(expected), Some(actual)) if !digest::digests_match(expected, actual) => {
+            ComplianceStatus::Tampered
+        }
+        // A hash we were asked to check but could not compute is not proof
+        // of compliance.
+        (Some(_), None) => ComplianceStatus::Tampered,
+        _ => ComplianceStatus::Verified,
+    }
+}
+
+/// Compare installed fonts with `catalog`.
+///
+/// PostScript names match case-insensitively. Every installed copy of a
+/// catalog face is checked, so a stale duplicate in another scope is flagged
+/// even when the primary copy is fine.
+pub fn verify_against_catalog(
+    catalog: &Catalog,
+    installed: &[FontliftFontFaceInfo],
+) -> ComplianceReport {
+    let mut findings = Vec::new();
+
+    for entry in &catalog.fonts {
+        let mut paths: Vec<&Path> = installed
+            .iter()
+            .filter(|f| {
+                f.postscript_name
+                    .eq_ignore_ascii_case(&entry.postscript_name)
+            })
+            .map(|f| f.source.path.as_path())
+            .collect();
+        paths.sort();
+        paths.dedup();
+
+        if paths.is_empty() {
+            findings.push(ComplianceFinding {
+                postscript_name: entry.postscript_name.clone(),
+                status: ComplianceStatus::NotInstalled,
+                path: None,
+                expected_version: entry.version.clone(),
+                installed_version: None,
+                expected_sha256: entry.sha256.clone(),
+                actual_sha256: None,
+            });
+            continue;
+        }
+
+        for path in paths {
+            let installed_version = metadata::read_font_version(path).ok().flatten();
+            let actual_sha256 = digest::sha256_file(path).ok();
+
+            findings.push(ComplianceFinding {
+                postscript_name: entry.postscript_name.clone(),
+                status: classify(
+                    entry,
+                    installed_version.as_deref(),
+                    actual_sha256.as_deref(),
+                ),
+                path: Some(path.to_path_buf()),
+                expected_version: entry.version.clone(),
+                installed_version,
+                expected_sha256: entry.sha256.clone(),
+                actual_sha256,
+            });
+        }
+    }
+
+    ComplianceReport {
+        catalog: catalog.name.clone(),
+        findings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FontliftFontSource;
+
+    fn entry(version: Option<&str>, sha256: Option<&str>) -> CatalogEntry {
+        CatalogEntry {
+            postscript_name: "Acme-Regular".into(),
+            version: version.map(Into::into),
+            sha256: sha256.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn classifies_version_and_hash_differences() {
+        assert_eq!(
+            classify(&entry(Some("2.000"), Some("AB")), Some("2.000"), Some("ab")),
+            ComplianceStatus::Verified
+        );
+        assert_eq!(
+            classify(&entry(Some("2.000"), None), Some("1.500"), None),
+            ComplianceStatus::Outdated
+        );
+        assert_eq!(
+            classify(&entry(Some("2.000"), None), Some("3.000"), None),
+            ComplianceStatus::VersionMismatch
+        );
+        assert_eq!(
+            classify(&entry(Some("2.000"), Some("ab")), Some("2.000"), Some("cd")),
+            ComplianceStatus::Tampered
+        );
+    }
+
+    #[test]
+    fn reports_missing_and_tampered_fonts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("Acme-Regular.ttf");
+        std::fs::write(&path, b"modified bytes").unwrap();
+
+        let installed = vec![FontliftFontFaceInfo::new(
+            FontliftFontSource::new(path.clone()),
+            "Acme-Regular".into(),
+            "Acme Regular".into(),
+            "Acme".into(),
+            "Regular".into(),
+        )];
+        let catalog = Catalog {
+            name: Some("order 42".into()),
+            fonts: vec![
+                entry(None, Some(&digest::sha256_hex(b"original bytes"))),
+                CatalogEntry {
+                    postscript_name: "Acme-Bold".into(),
+                    version: None,
+                    sha256: None,
+                },
+            ],
+        };
+
+        let report = verify_against_catalog(&catalog, &installed);
+        assert!(!report.is_compliant());
+        let statuses: Vec<_> = report.findings.iter().map(|f| f.status).collect();
+        assert_eq!(
+            statuses,
+            vec![ComplianceStatus::Tampered, ComplianceStatus::NotInstalled]
+        );
+        assert_eq!(report.violations().len(), 2);
+    }
+}
@@ -0,0 +1,432 @@
+//! Checksum manifests and minisign signatures for vendored font packs.
+//!
+//! Organizations hand out approved font packs with a list of SHA-256
+//! digests next to them, in the format `sha256sum` writes:
+//!
+//! ```text
+//! 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08  Sans/Sans-Regular.otf
+//! ```
+//!
+//! (`shasum -a 256 --tag` lines, `SHA256 (Sans/Sans-Regular.otf) = 9f86…`,
+//! work too.) Paths are relative to the manifest's folder.
+//!
+//! With a public key, the manifest must also carry a minisign signature in
+//! `<manifest>.minisig`, as `minisign -S -m pack.sums` writes it:
+//!
+//! ```text
+//! fontlift install pack/ --verify pack.sums --pubkey key.pub
+//! ```
+//!
+//! Both checks fail closed. A bad or missing signature rejects the whole
+//! manifest, and a font that is not listed, or whose digest differs, stops
+//! the install before anything is copied.
+
+use crate::{digest, FontError, FontResult};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use blake2::{Blake2b512, Digest};
+use ed25519_dalek::{Signature, VerifyingKey};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Largest number of bad files listed in one error.
+const MAX_REPORTED: usize = 10;
+
+/// Expected SHA-256 digests of the files in a font pack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumManifest {
+    /// Folder the listed paths are relative to.
+    base: PathBuf,
+    /// Normalized relative path (`/`-separated) → lowercase hex digest.
+    entries: BTreeMap<String, String>,
+}
+
+impl ChecksumManifest {
+    /// Read the manifest at `path`. With `public_key`, its minisign
+    /// signature (`<path>.minisig`) must verify first.
+    pub fn load(path: &Path, public_key: Option<&PublicKey>) -> FontResult<Self> {
+        let bytes = read(path)?;
+        if let Some(key) = public_key {
+            let signature_path = signature_path(path);
+            let signature = String::from_utf8_lossy(&read(&signature_path)?).into_owned();
+            key.verify(&bytes, &signature).map_err(|e| match e {
+                FontError::VerificationFailed(detail) => {
+                    FontError::VerificationFailed(format!("{}: {detail}", signature_path.display()))
+                }
+                other => other,
+            })?;
+        }
+        let text = String::from_utf8(bytes).map_err(|_| {
+            FontError::InvalidFormat(format!("{} is not UTF-8 text", path.display()))
+        })?;
+        let base = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        Self::parse(&text, base)
+    }
+
+    /// Parse manifest `text` whose paths are relative to `base`.
+    pub fn parse(text: &str, base: &Path) -> FontResult<Self> {
+        let mut entries = BTreeMap::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (digest, file) = parse_line(line).ok_or_else(|| {
+                FontError::InvalidFormat(format!(
+                    "Checksum manifest line {}: expected `<sha256>  <path>`",
+                    index + 1
+                ))
+            })?;
+            let file = normalize(file);
+            if let Some(previous) = entries.insert(file.clone(), digest.to_ascii_lowercase()) {
+                if !digest::digests_match(&previous, digest) {
+                    return Err(FontError::InvalidFormat(format!(
+                        "Checksum manifest lists {file} twice with different digests"
+                    )));
+                }
+            }
+        }
+        if entries.is_empty() {
+            return Err(FontError::InvalidFormat(
+                "Checksum manifest lists no files".to_string(),
+            ));
+        }
+        Ok(Self {
+            base: base.to_path_buf(),
+            entries,
+        })
+    }
+
+    /// Number of files listed.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The digest listed for `file`, if it lies under the manifest's folder
+    /// and is listed.
+    pub fn expected(&self, file: &Path) -> Option<&str> {
+        let base = fs::canonicalize(&self.base).ok()?;
+        let file = fs::canonicalize(file).ok()?;
+        let relative = file.strip_prefix(base).ok()?;
+        self.entries
+            .get(&normalize(&relative.to_string_lossy()))
+            .map(String::as_str)
+    }
+
+    /// Check that every one of `files` is listed with its current digest.
+    ///
+    /// Returns [`FontError::VerificationFailed`] naming the unlisted and
+    /// changed files otherwise.
+    pub fn verify_files(&self, files: &[PathBuf]) -> FontResult<()> {
+        let mut problems = Vec::new();
+        for file in files {
+            match self.expected(file) {
+                None => problems.push(format!("{} is not listed", file.display())),
+                Some(expected) => {
+                    let actual = digest::sha256_file(file)?;
+                    if !digest::digests_match(expected, &actual) {
+                        problems.push(format!(
+                            "{} has SHA-256 {actual}, expected {expected}",
+                            file.display()
+                        ));
+                    }
+                }
+            }
+        }
+        if problems.is_empty() {
+            return Ok(());
+        }
+        let count = problems.len();
+        problems.truncate(MAX_REPORTED);
+        let more = count.saturating_sub(MAX_REPORTED);
+        Err(FontError::VerificationFailed(format!(
+            "{count} file(s) do not match the checksum manifest: {}{}",
+            problems.join("; "),
+            if more > 0 {
+                format!("; and {more} more")
+            } else {
+                String::new()
+            }
+        )))
+    }
+}
+
+/// Where minisign puts the signature of `path`: `<path>.minisig`.
+pub fn signature_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".minisig");
+    PathBuf::from(name)
+}
+
+/// A minisign Ed25519 public key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    key_id: [u8; 8],
+    key: VerifyingKey,
+}
+
+impl PublicKey {
+    /// Read a minisign public key file (`minisign.pub`).
+    pub fn load(path: &Path) -> FontResult<Self> {
+        let text = String::from_utf8_lossy(&read(path)?).into_owned();
+        Self::parse(&text).map_err(|e| match e {
+            FontError::InvalidFormat(detail) => {
+                FontError::InvalidFormat(format!("{}: {detail}", path.display()))
+            }
+            other => other,
+        })
+    }
+
+    /// Parse a public key file, or the bare base64 line `minisign -p` prints.
+    pub fn parse(text: &str) -> FontResult<Self> {
+        let line = payload_lines(text)
+            .next()
+            .ok_or_else(|| invalid("no public key"))?;
+        let bytes = decode(line, "public key")?;
+        if bytes.len() != 42 || &bytes[..2] != b"Ed" {
+            return Err(invalid("not a minisign Ed25519 public key"));
+        }
+        let key = VerifyingKey::from_bytes(bytes[10..].try_into().expect("32 bytes"))
+            .map_err(|_| invalid("public key is not a valid Ed25519 point"))?;
+        Ok(Self {
+            key_id: bytes[2..10].try_into().expect("8 bytes"),
+            key,
+        })
+    }
+
+    /// Key id as minisign prints it (uppercase hex, little-endian).
+    pub fn key_id(&self) -> String {
+        self.key_id
+            .iter()
+            .rev()
+            .map(|b| format!("{b:02X}"))
+            .collect()
+    }
+
+    /// Check a minisign `signature` file's contents over `data`.
+    ///
+    /// Both the legacy (`Ed`) and the pre-hashed (`ED`, BLAKE2b-512)
+    /// signatures are accepted, and the trusted comment must verify too.
+    pub fn verify(&self, data: &[u8], signature: &str) -> FontResult<()> {
+        let mut lines = payload_lines(signature);
+        let signature_line = lines
+            .next()
+            .ok_or_else(|| invalid("empty signature file"))?;
+        let bytes = decode(signature_line, "signature")?;
+        if bytes.len() != 74 {
+            return Err(invalid("not a minisign signature"));
+        }
+        let prehashed = match &bytes[..2] {
+            b"Ed" => false,
+            b"ED" => true,
+            _ => return Err(invalid("unsupported signature algorithm")),
+        };
+        if bytes[2..10] != self.key_id {
+            return Err(FontError::VerificationFailed(format!(
+                "signed with another key, not {}",
+                self.key_id()
+            )));
+        }
+        let detached = Signature::from_bytes(bytes[10..].try_into().expect("64 bytes"));
+        let verified = if prehashed {
+            self.key.verify_strict(&Blake2b512::digest(data), &detached)
+        } else {
+            self.key.verify_strict(data, &detached)
+        };
+        verified.map_err(|_| {
+            FontError::VerificationFailed("signature does not match the manifest".to_string())
+        })?;
+
+        let comment = signature
+            .lines()
+            .find_map(|line| line.strip_prefix("trusted comment: "))
+            .ok_or_else(|| invalid("signature has no trusted comment"))?;
+        let global = decode(
+            lines
+                .next()
+                .ok_or_else(|| invalid("signature has no trusted comment signature"))?,
+            "trusted comment signature",
+        )?;
+        let global = Signature::from_slice(&global)
+            .map_err(|_| invalid("malformed trusted comment signature"))?;
+        let mut signed = bytes[10..].to_vec();
+        signed.extend_from_slice(comment.trim_end_matches('\r').as_bytes());
+        self.key.verify_strict(&signed, &global).map_err(|_| {
+            FontError::VerificationFailed("trusted comment signature does not match".to_string())
+        })
+    }
+}
+
+/// `<hex>  <path>`, `<hex> *<path>` or `SHA256 (<path>) = <hex>`.
+fn parse_line(line: &str) -> Option<(&str, &str)> {
+    if let Some(rest) = line.strip_prefix("SHA256 (") {
+        let (file, digest) = rest.rsplit_once(") = ")?;
+        return is_sha256(digest.trim()).then_some((digest.trim(), file));
+    }
+    let (digest, file) = line.split_once(' ')?;
+    let file = file
+        .strip_prefix(' ')
+        .or_else(|| file.strip_prefix('*'))
+        .unwrap_or(file);
+    (is_sha256(digest) && !file.is_empty()).then_some((digest, file))
+}
+
+fn is_sha256(text: &str) -> bool {
+    text.len() == 64 && text.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// `./Sans\Regular.otf` → `Sans/Regular.otf`.
+fn normalize(file: &str) -> String {
+    let file = file.replace('\\', "/");
+    file.trim_start_matches("./").to_string()
+}
+
+/// Lines of a minisign file that are not comments.
+fn payload_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.contains("comment:"))
+}
+
+fn decode(line: &str, what: &str) -> FontResult<Vec<u8>> {
+    STANDARD
+        .decode(line)
+        .map_err(|_| invalid(&format!("{what} is not base64")))
+}
+
+fn invalid(detail: &str) -> FontError {
+    FontError::InvalidFormat(format!("minisign: {detail}"))
+}
+
+fn read(path: &Path) -> FontResult<Vec<u8>> {
+    fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const KEY_ID: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn public_key_file(key: &SigningKey) -> String {
+        let mut bytes = b"Ed".to_vec();
+        bytes.extend_from_slice(&KEY_ID);
+        bytes.extend_from_slice(key.verifying_key().as_bytes());
+        format!(
+            "untrusted comment: minisign public key\n{}\n",
+            STANDARD.encode(bytes)
+        )
+    }
+
+    /// What `minisign -S` writes for `data` (pre-hashed).
+    fn minisig(key: &SigningKey, data: &[u8]) -> String {
+        let signature = key.sign(&Blake2b512::digest(data)).to_bytes();
+        let comment = "timestamp:1700000000\tfile:pack.sums";
+        let mut global = signature.to_vec();
+        global.extend_from_slice(comment.as_bytes());
+        let mut line = b"ED".to_vec();
+        line.extend_from_slice(&KEY_ID);
+        line.extend_from_slice(&signature);
+        format!(
+            "untrusted comment: signature from minisign secret key\n{}\ntrusted comment: {comment}\n{}\n",
+            STANDARD.encode(line),
+            STANDARD.encode(key.sign(&global).to_bytes())
+        )
+    }
+
+    fn pack() -> (tempfile::TempDir, PathBuf) {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("Sans")).unwrap();
+        fs::write(tmp.path().join("Sans/Sans-Regular.otf"), b"regular").unwrap();
+        fs::write(tmp.path().join("Extra.ttf"), b"extra").unwrap();
+        let sums = tmp.path().join("pack.sums");
+        fs::write(
+            &sums,
+            format!(
+                "{}  ./Sans/Sans-Regular.otf\nSHA256 (Extra.ttf) = {}\n",
+                digest::sha256_hex(b"regular"),
+                digest::sha256_hex(b"extra").to_uppercase()
+            ),
+        )
+        .unwrap();
+        (tmp, sums)
+    }
+
+    #[test]
+    fn listed_files_with_matching_digests_pass() {
+        let (tmp, sums) = pack();
+        let manifest = ChecksumManifest::load(&sums, None).unwrap();
+        assert_eq!(manifest.len(), 2);
+        let files = vec![
+            tmp.path().join("Sans/Sans-Regular.otf"),
+            tmp.path().join("Extra.ttf"),
+        ];
+        manifest.verify_files(&files).unwrap();
+
+        fs::write(tmp.path().join("Extra.ttf"), b"tampered").unwrap();
+        fs::write(tmp.path().join("Unlisted.ttf"), b"new").unwrap();
+        let files = vec![
+            tmp.path().join("Extra.ttf"),
+            tmp.path().join("Unlisted.ttf"),
+        ];
+        let Err(FontError::VerificationFailed(detail)) = manifest.verify_files(&files) else {
+            panic!("expected a verification failure");
+        };
+        assert!(detail.starts_with("2 file(s)"), "{detail}");
+        assert!(detail.contains("Unlisted.ttf is not listed"), "{detail}");
+
+        assert!(ChecksumManifest::parse("not a checksum\n", tmp.path()).is_err());
+    }
+
+    #[test]
+    fn signed_manifests_must_verify_against_the_key() {
+        let (_tmp, sums) = pack();
+        let key = signing_key();
+        let public = PublicKey::parse(&public_key_file(&key)).unwrap();
+        assert_eq!(public.key_id(), "0807060504030201");
+
+        assert!(matches!(
+            ChecksumManifest::load(&sums, Some(&public)),
+            Err(FontError::FontNotFound(_))
+        ));
+
+        fs::write(
+            signature_path(&sums),
+            minisig(&key, &fs::read(&sums).unwrap()),
+        )
+        .unwrap();
+        assert_eq!(
+            ChecksumManifest::load(&sums, Some(&public)).unwrap().len(),
+            2
+        );
+
+        let mut edited = fs::read(&sums).unwrap();
+        edited.extend_from_slice(b"# appended\n");
+        fs::write(&sums, edited).unwrap();
+        assert!(matches!(
+            ChecksumManifest::load(&sums, Some(&public)),
+            Err(FontError::VerificationFailed(_))
+        ));
+
+        let other = PublicKey::parse(&public_key_file(&SigningKey::from_bytes(&[9; 32]))).unwrap();
+        let data = b"data";
+        assert!(other.verify(data, &minisig(&key, data)).is_err());
+        assert!(public.verify(data, &minisig(&key, data)).is_ok());
+    }
+}
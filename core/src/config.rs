@@ -35,6 +35,10 @@
 //! | `FONTLIFT_PARALLEL` | Process multiple fonts concurrently | `true` |
 //! | `FONTLIFT_MAX_THREADS` | Thread pool ceiling (unset = all cores) | (all cores) |
 //! | `FONTLIFT_JOURNAL_PATH` | Override journal file location | Platform default |
+//! | `FONTLIFT_CONFIG_PATH` | `fontlift.toml` with the `[notify]` hooks (see [`crate::notify`]) | Per-user config dir |
+//! | `FONTLIFT_VAULT_DIR` | Where `disable` keeps fonts (`user/`, `system/`) | Platform default |
+//! | `FONTLIFT_QUARANTINE_DIR` | Where `remove` keeps removed fonts | Next to the journal |
+//! | `FONTLIFT_QUARANTINE_DAYS` | Days before removed fonts are purged | `30` |
 
 use anyhow::{Context, Result};
 use std::env;
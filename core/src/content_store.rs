@@ -0,0 +1,258 @@
+//! Hash-addressed store for downloaded font files.
+//!
+//! Provider installs (`fontlift install <url>`, `fontlift google install`)
+//! keep every download here, named by its SHA-256:
+//!
+//! ```text
+//! <store>/sha256/ab/abcdef…        verified font bytes
+//! <store>/urls/<sha256(url)>.json  what that URL last served (digest, ETag, length)
+//! <store>/partial/<sha256(url)>    interrupted download, resumed on the next run
+//! ```
+//!
+//! A repeated run asks the server whether the URL changed (`If-None-Match` /
+//! `If-Modified-Since`) and reuses the stored bytes when it did not. When the
+//! caller already knows the digest, a stored copy is used without any request.
+//!
+//! The store lives in the per-user cache directory; `FONTLIFT_CONTENT_STORE`
+//! overrides it. Every file is written to a temporary name and renamed into
+//! place, so concurrent downloads of the same content are harmless.
+//!
+//! A blob may also be hard-linked into a fonts folder. Its link count is then
+//! its reference count ([`ContentStore::installed_links`]); removing either
+//! name leaves the other intact (see [`crate::links`]).
+
+use crate::digest::{digests_match, sha256_file, sha256_hex};
+use crate::{links, FontError, FontResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What a URL served the last time it was downloaded in full.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrlRecord {
+    pub url: String,
+    pub sha256: String,
+    pub length: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+/// Validator of an interrupted download, sent as `If-Range` when resuming.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialRecord {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+/// A content store rooted at one directory.
+#[derive(Debug, Clone)]
+pub struct ContentStore {
+    root: PathBuf,
+}
+
+/// Default store location, honouring `FONTLIFT_CONTENT_STORE`.
+pub fn default_store_path() -> PathBuf {
+    if let Ok(path) = std::env::var("FONTLIFT_CONTENT_STORE") {
+        return PathBuf::from(path);
+    }
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("fontlift")
+        .join("store")
+}
+
+impl ContentStore {
+    pub fn at(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// The store at [`default_store_path`].
+    pub fn open_default() -> Self {
+        Self::at(default_store_path())
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Where the blob for `sha256` lives (whether or not it exists).
+    pub fn blob_path(&self, sha256: &str) -> PathBuf {
+        let digest = sha256.trim().to_ascii_lowercase();
+        let prefix = digest.get(..2).unwrap_or("00");
+        self.root.join("sha256").join(prefix).join(&digest)
+    }
+
+    /// The stored blob for `sha256`, re-verified on the way out.
+    ///
+    /// A blob whose bytes no longer match its name is deleted and reported
+    /// as missing, so the caller downloads a fresh copy.
+    pub fn get(&self, sha256: &str) -> FontResult<Option<PathBuf>> {
+        let path = self.blob_path(sha256);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        if digests_match(&sha256_file(&path)?, sha256) {
+            return Ok(Some(path));
+        }
+        log::warn!("Discarding corrupt stored download {}", path.display());
+        let shared = self.installed_links(sha256);
+        if shared > 0 {
+            log::warn!("{shared} installed font file(s) are hard links to the same corrupt bytes");
+        }
+        let _ = fs::remove_file(&path);
+        Ok(None)
+    }
+
+    /// How many names outside the store share the blob for `sha256`: fonts
+    /// installed as hard links to it. 0 when there is no blob.
+    pub fn installed_links(&self, sha256: &str) -> u64 {
+        links::link_count(&self.blob_path(sha256)).map_or(0, |links| links.saturating_sub(1))
+    }
+
+    /// Store `bytes` and return their digest.
+    pub fn put(&self, bytes: &[u8]) -> FontResult<String> {
+        let sha256 = sha256_hex(bytes);
+        let path = self.blob_path(&sha256);
+        if !path.is_file() {
+            write_atomic(&path, bytes)?;
+        }
+        Ok(sha256)
+    }
+
+    /// Move an already-verified file into the store under `sha256`.
+    pub fn adopt(&self, file: &Path, sha256: &str) -> FontResult<PathBuf> {
+        let path = self.blob_path(sha256);
+        create_parent(&path)?;
+        if path.is_file() {
+            let _ = fs::remove_file(file);
+        } else {
+            fs::rename(file, &path).map_err(FontError::IoError)?;
+        }
+        Ok(path)
+    }
+
+    /// The last full download of `url`, if one was recorded.
+    pub fn url_record(&self, url: &str) -> Option<UrlRecord> {
+        read_json(&self.url_record_path(url))
+    }
+
+    pub fn record_url(&self, record: &UrlRecord) -> FontResult<()> {
+        write_json(&self.url_record_path(&record.url), record)
+    }
+
+    /// Where an interrupted download of `url` is kept.
+    pub fn partial_path(&self, url: &str) -> PathBuf {
+        self.root.join("partial").join(sha256_hex(url.as_bytes()))
+    }
+
+    pub fn partial_record(&self, url: &str) -> Option<PartialRecord> {
+        read_json(&partial_meta_path(&self.partial_path(url)))
+    }
+
+    pub fn record_partial(&self, url: &str, record: &PartialRecord) -> FontResult<()> {
+        write_json(&partial_meta_path(&self.partial_path(url)), record)
+    }
+
+    /// Forget an interrupted download of `url`.
+    pub fn discard_partial(&self, url: &str) {
+        let path = self.partial_path(url);
+        let _ = fs::remove_file(partial_meta_path(&path));
+        let _ = fs::remove_file(path);
+    }
+
+    fn url_record_path(&self, url: &str) -> PathBuf {
+        self.root
+            .join("urls")
+            .join(format!("{}.json", sha256_hex(url.as_bytes())))
+    }
+}
+
+fn partial_meta_path(partial: &Path) -> PathBuf {
+    partial.with_extension("json")
+}
+
+fn create_parent(path: &Path) -> FontResult<()> {
+    match path.parent() {
+        Some(parent) => fs::create_dir_all(parent).map_err(FontError::IoError),
+        None => Ok(()),
+    }
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> FontResult<()> {
+    create_parent(path)?;
+    let tmp = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    fs::write(&tmp, bytes).map_err(FontError::IoError)?;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        FontError::IoError(e)
+    })
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> FontResult<()> {
+    let json = serde_json::to_vec_pretty(value)
+        .map_err(|e| FontError::InvalidFormat(format!("Failed to encode store record: {e}")))?;
+    write_atomic(path, &json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stores_blobs_by_digest_and_drops_corrupt_ones() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = ContentStore::at(tmp.path());
+
+        let digest = store.put(b"font bytes").unwrap();
+        let path = store.get(&digest).unwrap().expect("stored blob");
+        assert_eq!(fs::read(&path).unwrap(), b"font bytes");
+        assert!(path.starts_with(tmp.path().join("sha256").join(&digest[..2])));
+        assert_eq!(store.installed_links(&digest), 0);
+        let installed = tmp.path().join("Stored-Regular.ttf");
+        fs::hard_link(&path, &installed).unwrap();
+        assert_eq!(store.installed_links(&digest), 1);
+        fs::remove_file(&installed).unwrap();
+
+        fs::write(&path, b"tampered").unwrap();
+        assert!(store.get(&digest).unwrap().is_none());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn records_urls_and_partials() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = ContentStore::at(tmp.path());
+        let url = "https://example.com/Font.ttf";
+        assert!(store.url_record(url).is_none());
+
+        let record = UrlRecord {
+            url: url.to_string(),
+            sha256: sha256_hex(b"x"),
+            length: 1,
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+        store.record_url(&record).unwrap();
+        assert_eq!(store.url_record(url), Some(record));
+
+        let partial = PartialRecord {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+        store.record_partial(url, &partial).unwrap();
+        fs::write(store.partial_path(url), b"half").unwrap();
+        assert_eq!(store.partial_record(url), Some(partial));
+        store.discard_partial(url);
+        assert!(!store.partial_path(url).exists());
+        assert!(store.partial_record(url).is_none());
+    }
+}
@@ -0,0 +1,176 @@
+//! Byte-identical install inputs (`fontlift install`).
+//!
+//! A folder of fonts often holds the same file twice under different names
+//! (`Inter-Regular.ttf` and `Inter-Regular (1).ttf`), and a font may already
+//! be installed with exactly the bytes being installed again. Installing such
+//! inputs only adds a second registration of the same font. [`dedupe`] keeps
+//! the first of each set of identical inputs and drops the rest, along with
+//! any input identical to an installed file, reporting why.
+//!
+//! Installed files are only hashed when their size matches an input, so a
+//! large font library costs a `stat` per file rather than a read.
+
+use crate::digest::sha256_file;
+use crate::{FontResult, FontliftFontFaceInfo};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// An input [`dedupe`] dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedDuplicate {
+    pub path: PathBuf,
+    /// The earlier input, or the installed file, with the same bytes.
+    pub identical_to: PathBuf,
+    /// Whether `identical_to` is an installed font rather than an input.
+    pub installed: bool,
+}
+
+impl fmt::Display for SkippedDuplicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.installed { "installed " } else { "" };
+        write!(f, "identical to {kind}{}", self.identical_to.display())
+    }
+}
+
+/// The inputs to install, in their original order, and the ones skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Deduped {
+    pub unique: Vec<PathBuf>,
+    pub skipped: Vec<SkippedDuplicate>,
+}
+
+/// Split `inputs` into the ones worth installing and the byte-identical
+/// duplicates, of each other or of an `installed` font file.
+///
+/// An input that is itself one of the installed files is kept, so
+/// re-registering an installed font in place still works.
+pub fn dedupe(inputs: &[PathBuf], installed: &[FontliftFontFaceInfo]) -> FontResult<Deduped> {
+    let mut hashes = Vec::with_capacity(inputs.len());
+    for path in inputs {
+        hashes.push((file_len(path), sha256_file(path)?));
+    }
+
+    let sizes: HashSet<u64> = hashes.iter().filter_map(|(len, _)| *len).collect();
+    let mut installed_by_hash: HashMap<String, PathBuf> = HashMap::new();
+    let mut seen = HashSet::new();
+    for face in installed {
+        let path = &face.source.path;
+        if !seen.insert(path.clone()) || inputs.iter().any(|input| same_file(input, path)) {
+            continue;
+        }
+        if !file_len(path).is_some_and(|len| sizes.contains(&len)) {
+            continue;
+        }
+        // An installed file that cannot be read is no one's duplicate.
+        if let Ok(hash) = sha256_file(path) {
+            installed_by_hash
+                .entry(hash)
+                .or_insert_with(|| path.clone());
+        }
+    }
+
+    let mut result = Deduped::default();
+    let mut first_input: HashMap<&str, &PathBuf> = HashMap::new();
+    for (path, (_, hash)) in inputs.iter().zip(&hashes) {
+        let skipped = if let Some(installed) = installed_by_hash.get(hash) {
+            Some((installed.clone(), true))
+        } else if let Some(first) = first_input.get(hash.as_str()) {
+            Some(((*first).clone(), false))
+        } else {
+            first_input.insert(hash, path);
+            None
+        };
+        match skipped {
+            Some((identical_to, installed)) => result.skipped.push(SkippedDuplicate {
+                path: path.clone(),
+                identical_to,
+                installed,
+            }),
+            None => result.unique.push(path.clone()),
+        }
+    }
+    Ok(result)
+}
+
+fn file_len(path: &Path) -> Option<u64> {
+    std::fs::metadata(path).ok().map(|meta| meta.len())
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b
+        || matches!(
+            (a.canonicalize(), b.canonicalize()),
+            (Ok(a), Ok(b)) if a == b
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FontliftFontSource;
+    use fontlift_testfonts::TestFont;
+
+    fn installed_face(path: &Path) -> FontliftFontFaceInfo {
+        FontliftFontFaceInfo::new(
+            FontliftFontSource::new(path.to_path_buf()),
+            "Dedupe-Regular".to_string(),
+            "Dedupe Regular".to_string(),
+            "Dedupe".to_string(),
+            "Regular".to_string(),
+        )
+    }
+
+    #[test]
+    fn skips_inputs_identical_to_each_other_or_to_installed_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let inputs_dir = tmp.path().join("inputs");
+        let installed_dir = tmp.path().join("installed");
+        std::fs::create_dir_all(&inputs_dir).unwrap();
+        std::fs::create_dir_all(&installed_dir).unwrap();
+
+        let first = TestFont::new("Dedupe Sans").write_to(&inputs_dir).unwrap();
+        let copy = inputs_dir.join("Dedupe Sans copy.ttf");
+        std::fs::copy(&first, &copy).unwrap();
+        let other = TestFont::new("Other Sans").write_to(&inputs_dir).unwrap();
+        let installed = TestFont::new("Other Sans")
+            .write_to(&installed_dir)
+            .unwrap();
+
+        let result = dedupe(
+            &[first.clone(), copy.clone(), other.clone()],
+            &[installed_face(&installed)],
+        )
+        .unwrap();
+
+        assert_eq!(result.unique, vec![first.clone()]);
+        assert_eq!(
+            result.skipped,
+            vec![
+                SkippedDuplicate {
+                    path: copy,
+                    identical_to: first.clone(),
+                    installed: false,
+                },
+                SkippedDuplicate {
+                    path: other,
+                    identical_to: installed.clone(),
+                    installed: true,
+                },
+            ]
+        );
+        assert_eq!(
+            result.skipped[1].to_string(),
+            format!("identical to installed {}", installed.display())
+        );
+
+        // Re-installing an installed file in place is not a duplicate.
+        let result = dedupe(
+            std::slice::from_ref(&installed),
+            &[installed_face(&installed)],
+        )
+        .unwrap();
+        assert_eq!(result.unique, vec![installed]);
+        assert!(result.skipped.is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+//! What changed between two font files (`fontlift diff`).
+//!
+//! A vendor update is not always what its release notes say. Before
+//! upgrading installed copies, `fontlift diff Old.ttf New.ttf` shows whether
+//! anything actually changed, and where:
+//!
+//! | Section | Compared |
+//! |---|---|
+//! | `version` | name ID 5 and `head.fontRevision` |
+//! | `table` | which tables exist, and each one's length and checksum |
+//! | `name` | every decodable name record, by ID, platform, encoding and language |
+//! | `axis` | `fvar` axes, by tag |
+//! | `glyphs` | `maxp.numGlyphs` |
+//!
+//! Table checksums are computed from the table data rather than read from
+//! the table directory, where they are sometimes stale, and `head` is
+//! checksummed without its `checkSumAdjustment`, which changes whenever any
+//! other table does.
+
+use crate::{metadata, variations, FontError, FontResult};
+use read_fonts::{tables::name::NameId, FileRef, FontRef, TableProvider};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// The parts of one face that [`diff`] compares.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FaceSummary {
+    /// [`FontVersion::label`](metadata::FontVersion::label), with the
+    /// version string when there is one.
+    pub version: String,
+    /// Table tag to `(length, checksum)`.
+    pub tables: BTreeMap<String, (u32, u32)>,
+    /// `ID 5 (3/1/0x409)` to the decoded string.
+    pub names: BTreeMap<String, String>,
+    /// Axis tag to `min … max (default d)`.
+    pub axes: BTreeMap<String, String>,
+    pub glyph_count: Option<u16>,
+}
+
+/// Which part of a face a [`Change`] is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffSection {
+    Version,
+    Table,
+    Name,
+    Axis,
+    Glyphs,
+}
+
+impl DiffSection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DiffSection::Version => "version",
+            DiffSection::Table => "table",
+            DiffSection::Name => "name",
+            DiffSection::Axis => "axis",
+            DiffSection::Glyphs => "glyphs",
+        }
+    }
+}
+
+/// One difference. `before` is `None` for something added, `after` for
+/// something removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Change {
+    pub section: DiffSection,
+    /// The table tag, name record or axis tag; empty for `version` and
+    /// `glyphs`.
+    pub item: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Read the [`FaceSummary`] of the face at `face_index` (0 for single-face
+/// files).
+pub fn summarize(path: &Path, face_index: u32) -> FontResult<FaceSummary> {
+    let data = std::fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let file = FileRef::new(&data)
+        .map_err(|e| FontError::InvalidFormat(format!("{}: {e}", path.display())))?;
+    let font = file
+        .fonts()
+        .nth(face_index as usize)
+        .and_then(Result::ok)
+        .ok_or_else(|| {
+            FontError::InvalidFormat(format!("{}: no face {face_index}", path.display()))
+        })?;
+
+    let version = metadata::FontVersion {
+        revision: font.head().ok().map(|head| head.font_revision().to_f64()),
+        version_string: metadata::name_string(&font, NameId::VERSION_STRING),
+    };
+    let axes = variations::read_variations(path, face_index)?
+        .map(|info| info.axes)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|axis| {
+            (
+                axis.tag,
+                format!("{} … {} (default {})", axis.min, axis.max, axis.default),
+            )
+        })
+        .collect();
+
+    Ok(FaceSummary {
+        version: match &version.version_string {
+            Some(string) => format!("{} ({string})", version.label()),
+            None => version.label(),
+        },
+        tables: table_stamps(&font),
+        names: name_records(&font),
+        axes,
+        glyph_count: font.maxp().ok().map(|maxp| maxp.num_glyphs()),
+    })
+}
+
+fn table_stamps(font: &FontRef<'_>) -> BTreeMap<String, (u32, u32)> {
+    font.table_directory
+        .table_records()
+        .iter()
+        .filter_map(|record| {
+            let tag = record.tag();
+            let mut bytes = font.table_data(tag)?.as_bytes().to_vec();
+            if tag == read_fonts::types::Tag::new(b"head") && bytes.len() >= 12 {
+                bytes[8..12].fill(0);
+            }
+            Some((tag.to_string(), (bytes.len() as u32, checksum(&bytes))))
+        })
+        .collect()
+}
+
+/// The OpenType table checksum: the sum of the big-endian `u32`s of the
+/// data, zero-padded to a multiple of four.
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn name_records(font: &FontRef<'_>) -> BTreeMap<String, String> {
+    let Ok(name) = font.name() else {
+        return BTreeMap::new();
+    };
+    let strings = name.string_data();
+    name.name_record()
+        .iter()
+        .filter_map(|record| {
+            let value = record.string(strings).ok()?.to_string();
+            let key = format!(
+                "ID {} ({}/{}/{:#x})",
+                record.name_id().to_u16(),
+                record.platform_id(),
+                record.encoding_id(),
+                record.language_id()
+            );
+            Some((key, value))
+        })
+        .collect()
+}
+
+/// Everything that differs from `before` to `after`, by section.
+pub fn diff(before: &FaceSummary, after: &FaceSummary) -> Vec<Change> {
+    let mut changes = Vec::new();
+    if before.version != after.version {
+        changes.push(Change {
+            section: DiffSection::Version,
+            item: String::new(),
+            before: Some(before.version.clone()),
+            after: Some(after.version.clone()),
+        });
+    }
+    let stamp =
+        |(length, checksum): &(u32, u32)| format!("{length} bytes, checksum {checksum:08x}");
+    changes.extend(diff_maps(
+        DiffSection::Table,
+        &before.tables,
+        &after.tables,
+        stamp,
+    ));
+    changes.extend(diff_maps(
+        DiffSection::Name,
+        &before.names,
+        &after.names,
+        String::clone,
+    ));
+    changes.extend(diff_maps(
+        DiffSection::Axis,
+        &before.axes,
+        &after.axes,
+        String::clone,
+    ));
+    if before.glyph_count != after.glyph_count {
+        let count = |count: Option<u16>| count.map(|n| n.to_string());
+        changes.push(Change {
+            section: DiffSection::Glyphs,
+            item: String::new(),
+            before: count(before.glyph_count),
+            after: count(after.glyph_count),
+        });
+    }
+    changes
+}
+
+fn diff_maps<V: PartialEq>(
+    section: DiffSection,
+    before: &BTreeMap<String, V>,
+    after: &BTreeMap<String, V>,
+    show: impl Fn(&V) -> String,
+) -> Vec<Change> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let (old, new) = (before.get(key), after.get(key));
+            if old == new {
+                return None;
+            }
+            Some(Change {
+                section,
+                item: key.clone(),
+                before: old.map(&show),
+                after: new.map(&show),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontlift_testfonts::TestFont;
+
+    #[test]
+    fn reports_changed_versions_names_and_tables() {
+        let tmp = tempfile::tempdir().unwrap();
+        let old_dir = tmp.path().join("old");
+        let new_dir = tmp.path().join("new");
+        std::fs::create_dir_all(&old_dir).unwrap();
+        std::fs::create_dir_all(&new_dir).unwrap();
+        let old = TestFont::new("Diff Sans")
+            .with_revision(1.0)
+            .write_to(&old_dir)
+            .unwrap();
+        let new = TestFont::new("Diff Sans")
+            .with_revision(2.0)
+            .with_weight_axis(100, 900)
+            .write_to(&new_dir)
+            .unwrap();
+
+        let before = summarize(&old, 0).unwrap();
+        assert!(diff(&before, &before).is_empty());
+        assert!(before.tables.contains_key("head"));
+
+        let changes = diff(&before, &summarize(&new, 0).unwrap());
+        let sections: BTreeSet<DiffSection> = changes.iter().map(|c| c.section).collect();
+        assert!(sections.contains(&DiffSection::Version), "{changes:?}");
+        assert!(sections.contains(&DiffSection::Table), "{changes:?}");
+        let fvar = changes
+            .iter()
+            .find(|c| c.section == DiffSection::Table && c.item == "fvar")
+            .expect("fvar added");
+        assert!(fvar.before.is_none() && fvar.after.is_some());
+        let wght = changes
+            .iter()
+            .find(|c| c.section == DiffSection::Axis)
+            .expect("axis added");
+        assert_eq!(wght.item, "wght");
+
+        assert!(matches!(
+            summarize(&old, 3),
+            Err(FontError::InvalidFormat(_))
+        ));
+    }
+}
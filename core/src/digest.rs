@@ -0,0 +1,59 @@
+//! Content hashes for font files.
+//!
+//! Several features need to know whether two files hold the same bytes:
+//! download verification, catalog audits, manifests. They all use the
+//! lowercase hex SHA-256 produced here so digests can be compared as plain
+//! strings.
+
+use crate::{FontError, FontResult};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// Lowercase hex SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
+
+/// Lowercase hex SHA-256 of the file at `path`, streamed from disk.
+pub fn sha256_file(path: &Path) -> FontResult<String> {
+    let mut file = File::open(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher).map_err(FontError::IoError)?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Compare two hex digests, ignoring case and surrounding whitespace.
+pub fn digests_match(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_and_byte_digests_agree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("Font.ttf");
+        std::fs::write(&path, b"font bytes").unwrap();
+
+        let from_file = sha256_file(&path).unwrap();
+        assert_eq!(from_file, sha256_hex(b"font bytes"));
+        assert_eq!(from_file.len(), 64);
+        assert!(digests_match(&from_file.to_uppercase(), &from_file));
+
+        assert!(matches!(
+            sha256_file(&tmp.path().join("missing.ttf")),
+            Err(FontError::FontNotFound(_))
+        ));
+    }
+}
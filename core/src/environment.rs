@@ -0,0 +1,298 @@
+//! The environment an operation ran in, kept with its journal entry.
+//!
+//! "Who installed this, and how?" is hard to answer months later from a
+//! list of copied files alone. Every [`JournalEntry`](crate::journal::JournalEntry)
+//! therefore records an [`OperationEnvironment`]:
+//!
+//! ```text
+//! fontlift 5.0.15 on macos 14.5 (aarch64), user scope, not elevated
+//! config 3f9a1c0e77d2b4a1, providers: CoreText=macos 14.5, fontlift-platform-mac=5.0.15
+//! ```
+//!
+//! The platform managers announce themselves with [`register_provider`]
+//! when they are created, including whether the process is elevated; callers
+//! without a manager can ask [`is_elevated`] directly. The config hash covers every `FONTLIFT_*` variable,
+//! so two operations with the same hash ran under the same configuration
+//! without the journal storing paths or values.
+
+use crate::{digest, journal::JournalAction, FontScope};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+/// The platform back end doing the work, as registered by its manager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderInfo {
+    /// Provider name to version, such as `fontlift-platform-mac` → `5.0.15`
+    /// and `CoreText` → the OS release it ships with.
+    pub versions: BTreeMap<String, String>,
+    /// Whether the process has administrator/root rights.
+    pub elevated: Option<bool>,
+}
+
+/// Snapshot of the environment when an operation was recorded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationEnvironment {
+    pub fontlift_version: String,
+    /// `macos`, `windows`, `linux`, ...
+    pub os: String,
+    /// Release such as `14.5` or `10.0.22631`, when it could be read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os_version: Option<String>,
+    pub arch: String,
+    /// Scopes the operation's actions touch.
+    #[serde(default)]
+    pub scopes: Vec<FontScope>,
+    /// `None` when no platform manager reported it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevated: Option<bool>,
+    /// First 16 hex digits of the SHA-256 of the `FONTLIFT_*` variables.
+    pub config_hash: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub providers: BTreeMap<String, String>,
+}
+
+impl OperationEnvironment {
+    /// Capture the current environment for an operation touching `scopes`.
+    pub fn capture(scopes: &[FontScope]) -> Self {
+        let provider = registered_provider();
+        let mut unique: Vec<FontScope> = Vec::new();
+        for scope in scopes {
+            if !unique.contains(scope) {
+                unique.push(*scope);
+            }
+        }
+        Self {
+            fontlift_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            os_version: os_version(),
+            arch: std::env::consts::ARCH.to_string(),
+            scopes: unique,
+            elevated: provider.as_ref().and_then(|p| p.elevated),
+            config_hash: config_hash(std::env::vars()),
+            providers: provider.map(|p| p.versions).unwrap_or_default(),
+        }
+    }
+
+    /// Capture the environment for a set of journal actions.
+    pub fn for_actions(actions: &[JournalAction]) -> Self {
+        let scopes: Vec<FontScope> = actions
+            .iter()
+            .filter_map(|action| match action {
+                JournalAction::RegisterFont { scope, .. }
+                | JournalAction::UnregisterFont { scope, .. }
+                | JournalAction::ClearCache { scope } => Some(*scope),
+                _ => None,
+            })
+            .collect();
+        Self::capture(&scopes)
+    }
+
+    /// One-line summary: `fontlift 5.0.15 on macos 14.5 (aarch64), user
+    /// scope, not elevated`.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!(
+            "fontlift {} on {}{} ({})",
+            self.fontlift_version,
+            self.os,
+            self.os_version
+                .as_deref()
+                .map(|v| format!(" {v}"))
+                .unwrap_or_default(),
+            self.arch
+        )];
+        if !self.scopes.is_empty() {
+            let scopes: Vec<&str> = self
+                .scopes
+                .iter()
+                .map(|scope| match scope {
+                    FontScope::User => "user",
+                    FontScope::System => "system",
+                })
+                .collect();
+            parts.push(format!("{} scope", scopes.join("+")));
+        }
+        match self.elevated {
+            Some(true) => parts.push("elevated".to_string()),
+            Some(false) => parts.push("not elevated".to_string()),
+            None => {}
+        }
+        parts.join(", ")
+    }
+
+    /// `config <hash>, providers: a=1, b=2`.
+    pub fn details(&self) -> String {
+        let mut details = format!("config {}", self.config_hash);
+        if !self.providers.is_empty() {
+            let providers: Vec<String> = self
+                .providers
+                .iter()
+                .map(|(name, version)| format!("{name}={version}"))
+                .collect();
+            details.push_str(&format!(", providers: {}", providers.join(", ")));
+        }
+        details
+    }
+}
+
+fn provider_slot() -> &'static Mutex<Option<ProviderInfo>> {
+    static PROVIDER: OnceLock<Mutex<Option<ProviderInfo>>> = OnceLock::new();
+    PROVIDER.get_or_init(|| Mutex::new(None))
+}
+
+/// Record the platform back end for later [`OperationEnvironment::capture`]
+/// calls. The most recent registration wins.
+pub fn register_provider(info: ProviderInfo) {
+    if let Ok(mut slot) = provider_slot().lock() {
+        *slot = Some(info);
+    }
+}
+
+/// The provider last passed to [`register_provider`].
+pub fn registered_provider() -> Option<ProviderInfo> {
+    provider_slot().lock().ok().and_then(|slot| slot.clone())
+}
+
+/// Hash of the `FONTLIFT_*` variables among `vars`, order-independent.
+pub fn config_hash(vars: impl IntoIterator<Item = (String, String)>) -> String {
+    let config: BTreeMap<String, String> = vars
+        .into_iter()
+        .filter(|(name, _)| name.starts_with("FONTLIFT_"))
+        .collect();
+    let text: String = config
+        .iter()
+        .map(|(name, value)| format!("{name}={value}\n"))
+        .collect();
+    digest::sha256_hex(text.as_bytes())[..16].to_string()
+}
+
+/// Does the process run as root (Unix) or with an elevated token (Windows)?
+///
+/// System-scope installs need this, so callers that pick a scope on their
+/// own, such as the Python `scope="auto"`, use it to choose one that can
+/// succeed.
+#[cfg(unix)]
+pub fn is_elevated() -> bool {
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    unsafe { libc::geteuid() == 0 }
+}
+
+#[cfg(windows)]
+pub fn is_elevated() -> bool {
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::Security::{
+        GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+    };
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    // SAFETY: the token handle is opened, queried into a correctly sized
+    // TOKEN_ELEVATION, and closed before returning.
+    unsafe {
+        let mut token = HANDLE::default();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
+            return false;
+        }
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut length = 0u32;
+        let queried = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut _ as *mut _),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut length,
+        );
+        let _ = CloseHandle(token);
+        queried.is_ok() && elevation.TokenIsElevated != 0
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn is_elevated() -> bool {
+    false
+}
+
+/// The OS release, read once per process.
+pub fn os_version() -> Option<String> {
+    static VERSION: OnceLock<Option<String>> = OnceLock::new();
+    VERSION.get_or_init(read_os_version).clone()
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(target_os = "macos")]
+fn read_os_version() -> Option<String> {
+    command_output("sw_vers", &["-productVersion"])
+}
+
+#[cfg(windows)]
+fn read_os_version() -> Option<String> {
+    // "Microsoft Windows [Version 10.0.22631.3880]"
+    let text = command_output("cmd", &["/C", "ver"])?;
+    let start = text.find("Version ")? + "Version ".len();
+    Some(text[start..].trim_end_matches(']').to_string())
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn read_os_version() -> Option<String> {
+    command_output("uname", &["-r"])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn config_hash_covers_only_fontlift_variables() {
+        let vars = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let base = config_hash(vars(&[("FONTLIFT_DRY_RUN", "1"), ("HOME", "/a")]));
+        assert_eq!(base.len(), 16);
+        assert_eq!(
+            base,
+            config_hash(vars(&[("HOME", "/b"), ("FONTLIFT_DRY_RUN", "1")]))
+        );
+        assert_ne!(base, config_hash(vars(&[("FONTLIFT_DRY_RUN", "0")])));
+    }
+
+    #[test]
+    fn captures_scopes_and_registered_provider() {
+        register_provider(ProviderInfo {
+            versions: BTreeMap::from([("test-provider".to_string(), "1.2".to_string())]),
+            elevated: Some(false),
+        });
+        let path = PathBuf::from("/fonts/A.ttf");
+        let env = OperationEnvironment::for_actions(&[
+            JournalAction::UnregisterFont {
+                path: path.clone(),
+                scope: FontScope::System,
+            },
+            JournalAction::DeleteFile { path: path.clone() },
+            JournalAction::RegisterFont {
+                path,
+                scope: FontScope::System,
+            },
+        ]);
+
+        assert_eq!(env.scopes, vec![FontScope::System]);
+        assert_eq!(env.fontlift_version, env!("CARGO_PKG_VERSION"));
+        assert!(env.summary().contains("system scope, not elevated"));
+        assert!(env.details().contains("test-provider=1.2"));
+
+        let json = serde_json::to_string(&env).unwrap();
+        let back: OperationEnvironment = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, env);
+    }
+}
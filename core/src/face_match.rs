@@ -0,0 +1,237 @@
+//! Choosing installed fonts by pattern: `fontlift uninstall --family
+//! "Test*"`, `fontlift uninstall --match <REGEX>` and
+//! `fontlift.uninstall_matching()` in Python.
+//!
+//! A [`FaceMatcher`] picks faces from a listing; [`matching_fonts`] groups
+//! them by file, since a file is what gets unregistered — every face of a
+//! collection goes with it. [`uninstall_matching`] then unregisters the
+//! files under one journal [`Transaction`], so an interrupted batch is one
+//! operation for `fontlift doctor` to finish.
+
+use crate::journal::{JournalAction, Transaction};
+use crate::listing::glob_match;
+use crate::operations;
+use crate::policy::ManagedPolicy;
+use crate::{FontError, FontManager, FontResult, FontScope, FontliftFontFaceInfo};
+use regex::Regex;
+use std::path::PathBuf;
+
+/// Which faces a pattern selects.
+#[derive(Debug, Clone)]
+pub enum FaceMatcher {
+    /// Case-insensitive glob (`*`, `?`) over the family name.
+    Family(String),
+    /// Regular expression searched in the PostScript, full and family names.
+    Names(Regex),
+}
+
+impl FaceMatcher {
+    /// Match family names against `pattern`, such as `Test*`.
+    pub fn family(pattern: impl Into<String>) -> Self {
+        FaceMatcher::Family(pattern.into())
+    }
+
+    /// Match names against the regular expression `pattern`.
+    pub fn regex(pattern: &str) -> FontResult<Self> {
+        Regex::new(pattern)
+            .map(FaceMatcher::Names)
+            .map_err(|e| FontError::InvalidArgument(format!("Invalid pattern '{pattern}': {e}")))
+    }
+
+    /// Does `face` match?
+    pub fn matches(&self, face: &FontliftFontFaceInfo) -> bool {
+        match self {
+            FaceMatcher::Family(pattern) => glob_match(pattern, &face.family_name),
+            FaceMatcher::Names(regex) => {
+                [&face.postscript_name, &face.full_name, &face.family_name]
+                    .iter()
+                    .any(|name| regex.is_match(name))
+            }
+        }
+    }
+
+    /// `family "Test*"` or `names /Bold$/`, for messages.
+    pub fn describe(&self) -> String {
+        match self {
+            FaceMatcher::Family(pattern) => format!("family \"{pattern}\""),
+            FaceMatcher::Names(regex) => format!("names /{}/", regex.as_str()),
+        }
+    }
+}
+
+/// An installed font file with at least one matching face.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedFont {
+    pub path: PathBuf,
+    /// The scope the listing found it in, when known.
+    pub scope: Option<FontScope>,
+    /// PostScript names of the file's matching faces.
+    pub faces: Vec<String>,
+}
+
+/// The files of `fonts` with a face `matcher` selects, in listing order.
+/// Faces registered in a scope other than `scope` are left out.
+pub fn matching_fonts(
+    fonts: &[FontliftFontFaceInfo],
+    matcher: &FaceMatcher,
+    scope: FontScope,
+) -> Vec<MatchedFont> {
+    let mut matched: Vec<MatchedFont> = Vec::new();
+    for face in fonts {
+        if face.source.scope.is_some_and(|found| found != scope) || !matcher.matches(face) {
+            continue;
+        }
+        match matched
+            .iter_mut()
+            .find(|font| font.path == face.source.path)
+        {
+            Some(font) => font.faces.push(face.postscript_name.clone()),
+            None => matched.push(MatchedFont {
+                path: face.source.path.clone(),
+                scope: face.source.scope,
+                faces: vec![face.postscript_name.clone()],
+            }),
+        }
+    }
+    matched
+}
+
+/// Unregister every font in `fonts`, recorded as one journal transaction,
+/// and return how many were uninstalled. Files are left on disk.
+///
+/// A font that fails to unregister, or whose scope the managed policy rules
+/// out, does not stop the others; `each` hears how every font went, in
+/// order.
+pub fn uninstall_matching(
+    manager: &dyn FontManager,
+    fonts: &[MatchedFont],
+    default_scope: FontScope,
+    mut each: impl FnMut(&MatchedFont, &FontResult<()>),
+) -> FontResult<usize> {
+    let managed = ManagedPolicy::load_default()?;
+    let mut transaction = Transaction::begin(format!("Uninstall {} font(s)", fonts.len()))?;
+    let mut uninstalled = 0;
+    for font in fonts {
+        let scope = font.scope.unwrap_or(default_scope);
+        transaction.add(
+            font.path.display().to_string(),
+            vec![JournalAction::UnregisterFont {
+                path: font.path.clone(),
+                scope,
+            }],
+        )?;
+        let result = operations::unregister(manager, &managed, &font.path, &[scope]).map(drop);
+        // A failed unregister has nothing left to recover either.
+        transaction.step_done()?;
+        if result.is_ok() {
+            uninstalled += 1;
+        }
+        each(font, &result);
+    }
+    transaction.commit()?;
+    Ok(uninstalled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journal::load_journal_from;
+    use crate::FontliftFontSource;
+
+    fn face(path: &str, family: &str, postscript: &str, scope: FontScope) -> FontliftFontFaceInfo {
+        let source = FontliftFontSource::new(PathBuf::from(path)).with_scope(Some(scope));
+        FontliftFontFaceInfo::new(
+            source,
+            postscript.to_string(),
+            postscript.replace('-', " "),
+            family.to_string(),
+            "Regular".to_string(),
+        )
+    }
+
+    fn listing() -> Vec<FontliftFontFaceInfo> {
+        vec![
+            face(
+                "/f/TestSans.ttc",
+                "Test Sans",
+                "TestSans-Regular",
+                FontScope::User,
+            ),
+            face(
+                "/f/TestSans.ttc",
+                "Test Sans",
+                "TestSans-Bold",
+                FontScope::User,
+            ),
+            face("/f/Other.ttf", "Other", "Other-Bold", FontScope::User),
+            face(
+                "/s/TestSerif.ttf",
+                "Test Serif",
+                "TestSerif-Regular",
+                FontScope::System,
+            ),
+        ]
+    }
+
+    #[test]
+    fn groups_matching_faces_by_file_within_the_scope() {
+        let fonts = listing();
+
+        let matched = matching_fonts(&fonts, &FaceMatcher::family("test*"), FontScope::User);
+        assert_eq!(
+            matched,
+            [MatchedFont {
+                path: PathBuf::from("/f/TestSans.ttc"),
+                scope: Some(FontScope::User),
+                faces: vec!["TestSans-Regular".into(), "TestSans-Bold".into()],
+            }]
+        );
+
+        let bold = FaceMatcher::regex("-Bold$").unwrap();
+        let paths: Vec<_> = matching_fonts(&fonts, &bold, FontScope::User)
+            .into_iter()
+            .map(|font| font.path)
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("/f/TestSans.ttc"),
+                PathBuf::from("/f/Other.ttf")
+            ]
+        );
+
+        let system = matching_fonts(&fonts, &FaceMatcher::family("Test*"), FontScope::System);
+        assert_eq!(system[0].path, PathBuf::from("/s/TestSerif.ttf"));
+        assert!(FaceMatcher::regex("(").is_err());
+    }
+
+    #[test]
+    fn uninstalls_matches_under_one_journal_entry() {
+        let tmp = tempfile::tempdir().unwrap();
+        let journal = tmp.path().join("journal.json");
+        std::env::set_var("FONTLIFT_JOURNAL_PATH", &journal);
+
+        let matched = matching_fonts(&listing(), &FaceMatcher::family("*"), FontScope::User);
+        let mut seen = Vec::new();
+        let count = uninstall_matching(
+            &crate::DummyFontManager,
+            &matched,
+            FontScope::User,
+            |font, result| {
+                seen.push((font.path.clone(), result.is_ok()));
+            },
+        )
+        .unwrap();
+
+        assert_eq!(count, 0, "the dummy manager refuses every uninstall");
+        assert_eq!(seen.len(), 2);
+        let journal = load_journal_from(&journal).unwrap();
+        let entry = journal
+            .entries
+            .iter()
+            .find(|e| e.description.as_deref() == Some("Uninstall 2 font(s)"))
+            .expect("one entry for the batch");
+        assert!(entry.completed);
+        assert_eq!(entry.actions.len(), 2);
+    }
+}
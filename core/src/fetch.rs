@@ -0,0 +1,552 @@
+//! Download fonts from `http(s)` URLs so they can be installed like local files.
+//!
+//! `fontlift install https://example.com/MyFont.ttf` runs in three stages:
+//!
+//! 1. **Fetch** — [`fetch_font`] downloads the file into the
+//!    [content store](crate::content_store), refusing bodies larger than the
+//!    validator's size cap. An interrupted download is kept and resumed with
+//!    a `Range` request on the next run; a URL fetched before is revalidated
+//!    (`If-None-Match`) instead of downloaded again.
+//! 2. **Verify** — the byte count must match what the server announced, and
+//!    if the caller supplied an expected SHA-256 digest, the bytes must match
+//!    it exactly. A known digest that is already stored needs no request.
+//! 3. **Hand off** — the verified file is copied into a private temp
+//!    directory under its font filename. The caller validates it with the
+//!    out-of-process validator and passes it to the platform manager.
+//!
+//! [`fetch_fonts`] runs several downloads at once, capped at a caller-chosen
+//! number of connections.
+//!
+//! The network client is only compiled with the `fetch` feature. Without it,
+//! [`is_url`] still recognises URLs so callers can print a clear error instead
+//! of "file not found".
+
+use crate::{validation, FontError, FontResult};
+use std::path::{Path, PathBuf};
+
+/// Does this input look like a remote URL rather than a local path?
+pub fn is_url(input: &str) -> bool {
+    let lower = input.to_ascii_lowercase();
+    lower.starts_with("https://") || lower.starts_with("http://")
+}
+
+/// Pick a local filename for a URL: the last path segment, without query or
+/// fragment.
+///
+/// Returns [`FontError::InvalidFormat`] when the URL does not end in a
+/// recognised font extension, because the platform managers rely on the
+/// extension to pick a format.
+pub fn filename_from_url(url: &str) -> FontResult<String> {
+    let without_query = url.split(['?', '#']).next().unwrap_or(url);
+    let name = without_query
+        .rsplit('/')
+        .next()
+        .filter(|segment| !segment.is_empty())
+        .ok_or_else(|| FontError::InvalidFormat(format!("URL does not name a font file: {url}")))?;
+
+    if !validation::is_valid_font_extension(Path::new(name)) {
+        return Err(FontError::InvalidFormat(format!(
+            "URL does not point at a font file (unsupported extension): {url}"
+        )));
+    }
+
+    Ok(name.to_string())
+}
+
+/// A downloaded font in a private temp directory.
+///
+/// The directory is deleted when this value is dropped, so keep it alive until
+/// the platform manager has copied or registered the file.
+#[derive(Debug)]
+pub struct FetchedFont {
+    pub url: String,
+    pub path: PathBuf,
+    /// Lowercase hex SHA-256 of the downloaded bytes.
+    pub sha256: String,
+    dir: PathBuf,
+}
+
+impl Drop for FetchedFont {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Downloads [`fetch_fonts`] runs at once unless told otherwise.
+pub const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 4;
+
+/// One download for [`fetch_fonts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchRequest {
+    pub url: String,
+    /// Local filename; defaults to [`filename_from_url`].
+    pub filename: Option<String>,
+    pub sha256: Option<String>,
+}
+
+impl FetchRequest {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            filename: None,
+            sha256: None,
+        }
+    }
+
+    pub fn with_filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    pub fn with_sha256(mut self, sha256: Option<String>) -> Self {
+        self.sha256 = sha256;
+        self
+    }
+}
+
+#[cfg(feature = "fetch")]
+mod download {
+    use super::*;
+    use crate::content_store::{ContentStore, PartialRecord, UrlRecord};
+    use crate::digest::{digests_match, sha256_file};
+    use crate::validation_ext::DEFAULT_MAX_SIZE;
+    use std::fs::OpenOptions;
+    use std::io::Read;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    pub(super) fn verify_digest(actual: &str, expected: &str) -> FontResult<()> {
+        if !digests_match(actual, expected) {
+            return Err(FontError::InvalidFormat(format!(
+                "SHA-256 mismatch: expected {}, got {actual}",
+                expected.trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Download `url` into a fresh temp directory.
+    ///
+    /// When `expected_sha256` is set, the download is rejected (and deleted)
+    /// unless its digest matches.
+    pub fn fetch_font(url: &str, expected_sha256: Option<&str>) -> FontResult<FetchedFont> {
+        let filename = filename_from_url(url)?;
+        fetch_font_as(url, &filename, expected_sha256)
+    }
+
+    /// Like [`fetch_font`], but save the download as `filename`.
+    ///
+    /// For providers whose URLs end in opaque hashes rather than a readable
+    /// font filename. `filename` must carry a font extension.
+    pub fn fetch_font_as(
+        url: &str,
+        filename: &str,
+        expected_sha256: Option<&str>,
+    ) -> FontResult<FetchedFont> {
+        fetch_into_store(
+            &ContentStore::open_default(),
+            url,
+            filename,
+            expected_sha256,
+        )
+    }
+
+    /// Download every request, at most `max_concurrent` at a time.
+    ///
+    /// Results come back in request order. The first failure is returned
+    /// once all downloads have finished; completed ones stay in the content
+    /// store, so a rerun only fetches what failed.
+    pub fn fetch_fonts(
+        requests: &[FetchRequest],
+        max_concurrent: usize,
+    ) -> FontResult<Vec<FetchedFont>> {
+        let store = ContentStore::open_default();
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<FontResult<FetchedFont>>>> =
+            Mutex::new(requests.iter().map(|_| None).collect());
+
+        std::thread::scope(|scope| {
+            for _ in 0..max_concurrent.clamp(1, requests.len().max(1)) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(request) = requests.get(index) else {
+                        break;
+                    };
+                    let result = match &request.filename {
+                        Some(filename) => Ok(filename.clone()),
+                        None => filename_from_url(&request.url),
+                    }
+                    .and_then(|filename| {
+                        fetch_into_store(&store, &request.url, &filename, request.sha256.as_deref())
+                    });
+                    results.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(result);
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+            .into_iter()
+            .map(|result| result.expect("every request was attempted"))
+            .collect()
+    }
+
+    pub(super) fn fetch_into_store(
+        store: &ContentStore,
+        url: &str,
+        filename: &str,
+        expected_sha256: Option<&str>,
+    ) -> FontResult<FetchedFont> {
+        if !is_url(url) {
+            return Err(FontError::InvalidArgument(format!(
+                "Only http:// and https:// URLs are supported: {url}"
+            )));
+        }
+        if filename.contains(['/', '\\'])
+            || !validation::is_valid_font_extension(Path::new(filename))
+        {
+            return Err(FontError::InvalidFormat(format!(
+                "Download target is not a font filename: {filename}"
+            )));
+        }
+
+        let (blob, sha256) = download_to_store(store, url, expected_sha256, true)?;
+
+        let dir = std::env::temp_dir().join(format!("fontlift-fetch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).map_err(FontError::IoError)?;
+        let fetched = FetchedFont {
+            url: url.to_string(),
+            path: dir.join(filename),
+            sha256,
+            dir,
+        };
+        std::fs::copy(&blob, &fetched.path).map_err(FontError::IoError)?;
+
+        Ok(fetched)
+    }
+
+    fn request_error(url: &str, e: impl std::fmt::Display) -> FontError {
+        FontError::IoError(std::io::Error::other(format!(
+            "Failed to download {url}: {e}"
+        )))
+    }
+
+    fn too_large(url: &str) -> FontError {
+        FontError::InvalidFormat(format!(
+            "Download exceeds the {} MB size limit: {url}",
+            DEFAULT_MAX_SIZE / (1024 * 1024)
+        ))
+    }
+
+    /// Total body size announced by the server, if any.
+    fn announced_length(response: &ureq::Response) -> Option<u64> {
+        if response.status() == 206 {
+            // Content-Range: bytes 100-199/200
+            response
+                .header("Content-Range")
+                .and_then(|range| range.rsplit('/').next())
+                .and_then(|total| total.trim().parse().ok())
+        } else {
+            response
+                .header("Content-Length")
+                .and_then(|length| length.trim().parse().ok())
+        }
+    }
+
+    /// Make sure the bytes behind `url` are in `store`, returning the blob
+    /// path and its digest.
+    fn download_to_store(
+        store: &ContentStore,
+        url: &str,
+        expected_sha256: Option<&str>,
+        may_retry: bool,
+    ) -> FontResult<(PathBuf, String)> {
+        if let Some(expected) = expected_sha256 {
+            if let Some(path) = store.get(expected)? {
+                return Ok((path, expected.trim().to_ascii_lowercase()));
+            }
+        }
+
+        let previous = match store.url_record(url) {
+            Some(record) => store.get(&record.sha256)?.map(|path| (record, path)),
+            None => None,
+        };
+        let partial = store.partial_path(url);
+        let resume_from = match &previous {
+            Some(_) => 0,
+            None => std::fs::metadata(&partial).map(|m| m.len()).unwrap_or(0),
+        };
+
+        let mut request = ureq::get(url);
+        if let Some((record, _)) = &previous {
+            if let Some(etag) = &record.etag {
+                request = request.set("If-None-Match", etag);
+            }
+            if let Some(modified) = &record.last_modified {
+                request = request.set("If-Modified-Since", modified);
+            }
+        } else if resume_from > 0 {
+            request = request.set("Range", &format!("bytes={resume_from}-"));
+            if let Some(validator) = store
+                .partial_record(url)
+                .and_then(|p| p.etag.or(p.last_modified))
+            {
+                request = request.set("If-Range", &validator);
+            }
+        }
+
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(416, _)) if resume_from > 0 && may_retry => {
+                // The partial file no longer fits what the server has.
+                store.discard_partial(url);
+                return download_to_store(store, url, expected_sha256, false);
+            }
+            Err(e) => return Err(request_error(url, e)),
+        };
+
+        if response.status() == 304 {
+            if let Some((record, path)) = previous {
+                if let Some(expected) = expected_sha256 {
+                    verify_digest(&record.sha256, expected)?;
+                }
+                return Ok((path, record.sha256));
+            }
+        }
+
+        let offset = if response.status() == 206 {
+            resume_from
+        } else {
+            0
+        };
+        let total = announced_length(&response);
+        if total.is_some_and(|total| total > DEFAULT_MAX_SIZE) {
+            store.discard_partial(url);
+            return Err(too_large(url));
+        }
+
+        let etag = response.header("ETag").map(str::to_string);
+        let last_modified = response.header("Last-Modified").map(str::to_string);
+        store.record_partial(
+            url,
+            &PartialRecord {
+                etag: etag.clone(),
+                last_modified: last_modified.clone(),
+            },
+        )?;
+
+        if let Some(parent) = partial.parent() {
+            std::fs::create_dir_all(parent).map_err(FontError::IoError)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(offset > 0)
+            .truncate(offset == 0)
+            .open(&partial)
+            .map_err(FontError::IoError)?;
+
+        let copied = std::io::copy(
+            &mut response.into_reader().take(DEFAULT_MAX_SIZE + 1 - offset),
+            &mut file,
+        );
+        drop(file);
+        let length = std::fs::metadata(&partial)
+            .map(|m| m.len())
+            .map_err(FontError::IoError)?;
+
+        if let Err(e) = copied {
+            return Err(FontError::IoError(std::io::Error::new(
+                e.kind(),
+                format!(
+                    "Download of {url} was interrupted after {length} bytes ({e}); \
+                     run the command again to resume"
+                ),
+            )));
+        }
+        if length > DEFAULT_MAX_SIZE {
+            store.discard_partial(url);
+            return Err(too_large(url));
+        }
+        if let Some(total) = total {
+            if length != total {
+                if length > total {
+                    store.discard_partial(url);
+                }
+                return Err(FontError::InvalidFormat(format!(
+                    "Download of {url} is incomplete: received {length} of {total} bytes"
+                )));
+            }
+        }
+
+        let sha256 = sha256_file(&partial)?;
+        if let Some(expected) = expected_sha256 {
+            if let Err(e) = verify_digest(&sha256, expected) {
+                store.discard_partial(url);
+                return Err(e);
+            }
+        }
+
+        let path = store.adopt(&partial, &sha256)?;
+        store.discard_partial(url);
+        store.record_url(&UrlRecord {
+            url: url.to_string(),
+            sha256: sha256.clone(),
+            length,
+            etag,
+            last_modified,
+        })?;
+
+        Ok((path, sha256))
+    }
+}
+
+#[cfg(feature = "fetch")]
+pub use download::{fetch_font, fetch_font_as, fetch_fonts};
+
+/// Stand-in used when fontlift was built without the `fetch` feature.
+#[cfg(not(feature = "fetch"))]
+pub fn fetch_font(url: &str, expected_sha256: Option<&str>) -> FontResult<FetchedFont> {
+    fetch_font_as(url, "", expected_sha256)
+}
+
+/// Stand-in used when fontlift was built without the `fetch` feature.
+#[cfg(not(feature = "fetch"))]
+pub fn fetch_fonts(
+    requests: &[FetchRequest],
+    _max_concurrent: usize,
+) -> FontResult<Vec<FetchedFont>> {
+    match requests.first() {
+        Some(request) => fetch_font_as(&request.url, "", None).map(|fetched| vec![fetched]),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Stand-in used when fontlift was built without the `fetch` feature.
+#[cfg(not(feature = "fetch"))]
+pub fn fetch_font_as(
+    url: &str,
+    _filename: &str,
+    _expected_sha256: Option<&str>,
+) -> FontResult<FetchedFont> {
+    Err(FontError::UnsupportedOperation(format!(
+        "Installing from URLs requires the `fetch` feature: {url}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognises_http_urls_only() {
+        assert!(is_url("https://example.com/MyFont.ttf"));
+        assert!(is_url("HTTP://example.com/MyFont.ttf"));
+        assert!(!is_url("ftp://example.com/MyFont.ttf"));
+        assert!(!is_url("/Users/me/https.ttf"));
+    }
+
+    #[test]
+    fn filename_strips_query_and_requires_font_extension() {
+        assert_eq!(
+            filename_from_url("https://cdn.example.com/fonts/Inter-Bold.otf?v=3#x").unwrap(),
+            "Inter-Bold.otf"
+        );
+        assert!(filename_from_url("https://example.com/").is_err());
+        assert!(filename_from_url("https://example.com/download.php?id=4").is_err());
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn sha256_verification_is_case_insensitive_and_rejects_mismatch() {
+        let digest = crate::digest::sha256_hex(b"font");
+        assert_eq!(digest.len(), 64);
+        assert!(download::verify_digest(&digest, &digest.to_uppercase()).is_ok());
+        let other = crate::digest::sha256_hex(b"other");
+        assert!(download::verify_digest(&other, &digest).is_err());
+    }
+
+    /// Serve one canned response per connection and hand back the requests.
+    #[cfg(feature = "fetch")]
+    fn serve(responses: Vec<Vec<u8>>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut byte = [0u8; 1];
+                while !request.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap() == 1 {
+                    request.push(byte[0]);
+                }
+                requests.push(String::from_utf8_lossy(&request).to_lowercase());
+                let _ = stream.write_all(&response);
+            }
+            requests
+        });
+        (base, handle)
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn resumes_interrupted_downloads_and_revalidates_stored_ones() {
+        use crate::content_store::ContentStore;
+
+        let body = fontlift_testfonts::TestFont::new("Fetch Sans").build();
+        let half = body.len() / 2;
+        let digest = crate::digest::sha256_hex(&body);
+
+        let head = |status: &str, extra: String| {
+            format!("HTTP/1.1 {status}\r\nETag: \"v1\"\r\nConnection: close\r\n{extra}\r\n")
+                .into_bytes()
+        };
+        let mut cut = head("200 OK", format!("Content-Length: {}\r\n", body.len()));
+        cut.extend_from_slice(&body[..half]);
+        let mut rest = head(
+            "206 Partial Content",
+            format!(
+                "Content-Range: bytes {}-{}/{}\r\nContent-Length: {}\r\n",
+                half,
+                body.len() - 1,
+                body.len(),
+                body.len() - half
+            ),
+        );
+        rest.extend_from_slice(&body[half..]);
+        let unchanged = head("304 Not Modified", String::new());
+
+        let (base, server) = serve(vec![cut, rest, unchanged]);
+        let url = format!("{base}/FetchSans-Regular.ttf");
+        let tmp = tempfile::tempdir().unwrap();
+        let store = ContentStore::at(tmp.path());
+        let fetch = |sha: Option<&str>| {
+            download::fetch_into_store(&store, &url, "FetchSans-Regular.ttf", sha)
+        };
+
+        assert!(fetch(None).is_err());
+        assert_eq!(
+            std::fs::metadata(store.partial_path(&url)).unwrap().len(),
+            half as u64
+        );
+
+        let resumed = fetch(None).unwrap();
+        assert_eq!(resumed.sha256, digest);
+        assert_eq!(std::fs::read(&resumed.path).unwrap(), body);
+        assert!(!store.partial_path(&url).exists());
+
+        let revalidated = fetch(None).unwrap();
+        assert_eq!(revalidated.sha256, digest);
+
+        // All three responses are used up, so this only works without a request.
+        assert!(fetch(Some(&digest)).is_ok());
+
+        let requests = server.join().unwrap();
+        assert!(requests[1].contains(&format!("range: bytes={half}-")));
+        assert!(requests[1].contains("if-range: \"v1\""));
+        assert!(requests[2].contains("if-none-match: \"v1\""));
+    }
+}
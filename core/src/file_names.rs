@@ -0,0 +1,325 @@
+//! File names for installed copies of fonts.
+//!
+//! Font files from foundries and build pipelines sometimes carry very long
+//! names (`AcmeGroteskDisplayCondensedExtraBoldItalic-VariableFont_opsz,wght.ttf`).
+//! Joined with a deep profile directory they can exceed the Windows
+//! `MAX_PATH` limit of 260 characters, and the copy then fails with an
+//! unhelpful I/O error.
+//!
+//! [`fit_file_name`] checks the target path before anything is copied. When
+//! it is too long, the file name is shortened deterministically: the stem is
+//! truncated and an 8-digit hash of the original name is appended, keeping
+//! the extension.
+//!
+//! ```text
+//! AcmeGroteskDisplayCondensedExtraBoldItalic-VariableFont_opsz,wght.ttf
+//! AcmeGroteskDisplayCondensedExtra-1f3a9c02.ttf
+//! ```
+//!
+//! The same original name in the same directory always maps to the same short
+//! name, so it can be recomputed when uninstalling. Only the file name
+//! changes: the names applications show come from the font's `name` table.
+//!
+//! Before that, [`sanitize_file_name`] makes the name safe to copy and to
+//! register: emoji and other symbols, characters Windows forbids, and
+//! trailing dots and spaces go, and reserved device names (`CON.ttf`) get an
+//! underscore. A [`NamePattern`] (`fontlift install --name-pattern
+//! '{family}-{style}.{ext}'`) builds the name from the font's own names
+//! instead, and [`unique_file_name`] numbers names that come up twice in one
+//! batch.
+
+use crate::{digest, FontError, FontResult, FontliftFontFaceInfo};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Windows `MAX_PATH`, including the terminating NUL.
+pub const WINDOWS_MAX_PATH: usize = 260;
+
+/// `PATH_MAX` on macOS and Linux.
+pub const UNIX_MAX_PATH: usize = 1024;
+
+/// Longest file name NTFS, APFS and HFS+ accept, in UTF-16 code units.
+pub const MAX_FILE_NAME: usize = 255;
+
+/// Path length limit on this platform.
+pub fn platform_max_path() -> usize {
+    if cfg!(windows) {
+        WINDOWS_MAX_PATH
+    } else {
+        UNIX_MAX_PATH
+    }
+}
+
+/// Length in UTF-16 code units, the unit both Windows and Apple file systems
+/// count in.
+fn units(value: &str) -> usize {
+    value.encode_utf16().count()
+}
+
+/// The name to give `file_name` inside `dir` so the full path stays below
+/// `max_path` (which counts the terminating NUL, like `MAX_PATH`).
+///
+/// Returns `file_name` unchanged when it fits. Fails when `dir` itself is so
+/// deep that not even a shortened name fits.
+pub fn fit_file_name(dir: &Path, file_name: &str, max_path: usize) -> FontResult<String> {
+    // Directory, separator, file name, NUL.
+    let available = max_path
+        .saturating_sub(units(&dir.to_string_lossy()) + 2)
+        .min(MAX_FILE_NAME);
+    if units(file_name) <= available {
+        return Ok(file_name.to_string());
+    }
+
+    shorten_file_name(file_name, available).ok_or_else(|| {
+        FontError::UnsupportedOperation(format!(
+            "Font directory {} is too deep: no file name fits within the {max_path}-character path limit",
+            dir.display()
+        ))
+    })
+}
+
+/// [`fit_file_name`] for this platform's limit, joined onto `dir`.
+pub fn fit_target_path(dir: &Path, file_name: &str) -> FontResult<PathBuf> {
+    Ok(dir.join(fit_file_name(dir, file_name, platform_max_path())?))
+}
+
+/// Shorten `file_name` to at most `max_len` UTF-16 units as
+/// `<truncated stem>-<hash>.<ext>`. `None` when not even one character of
+/// the stem fits.
+pub fn shorten_file_name(file_name: &str, max_len: usize) -> Option<String> {
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{ext}")),
+        _ => (file_name, String::new()),
+    };
+    let hash = &digest::sha256_hex(file_name.as_bytes())[..8];
+    let suffix = format!("-{hash}{extension}");
+
+    let budget = max_len.checked_sub(units(&suffix))?;
+    let mut kept = String::new();
+    let mut used = 0;
+    for c in stem.chars() {
+        if used + c.len_utf16() > budget {
+            break;
+        }
+        used += c.len_utf16();
+        kept.push(c);
+    }
+    // Don't leave a separator dangling before the hash.
+    let kept = kept.trim_end_matches(['-', '_', ' ', '.']);
+    if kept.is_empty() {
+        return None;
+    }
+
+    Some(format!("{kept}{suffix}"))
+}
+
+/// Device names Windows reserves in every directory, with any extension.
+const RESERVED_WINDOWS_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Punctuation kept in file names besides letters and digits.
+const SAFE_PUNCTUATION: &str = "-_.,+&()[]' ";
+
+/// `file_name` made safe for font directories and registry values.
+///
+/// Letters and digits of any script are kept, as is common punctuation;
+/// everything else (emoji, control characters, `<>:"/\|?*`) becomes `_`,
+/// with runs collapsed. Leading and trailing spaces and dots are trimmed
+/// from the stem, and a stem that is a reserved Windows device name gets a
+/// trailing `_`. Names that are already safe come back unchanged.
+pub fn sanitize_file_name(file_name: &str) -> String {
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, Some(ext)),
+        _ => (file_name, None),
+    };
+
+    let mut clean = String::new();
+    for c in stem.chars() {
+        let c = if c.is_alphanumeric() || SAFE_PUNCTUATION.contains(c) {
+            c
+        } else {
+            '_'
+        };
+        if !(c == '_' && clean.ends_with('_')) {
+            clean.push(c);
+        }
+    }
+    let mut clean = clean.trim_matches([' ', '.']).to_string();
+    if clean.is_empty() || clean == "_" {
+        clean = "font".to_string();
+    }
+    if RESERVED_WINDOWS_NAMES
+        .iter()
+        .any(|reserved| clean.eq_ignore_ascii_case(reserved))
+    {
+        clean.push('_');
+    }
+
+    let extension: String = extension
+        .unwrap_or_default()
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect();
+    if extension.is_empty() {
+        clean
+    } else {
+        format!("{clean}.{extension}")
+    }
+}
+
+/// Placeholders a [`NamePattern`] may use.
+const PLACEHOLDERS: &[&str] = &["{family}", "{style}", "{postscript}", "{stem}", "{ext}"];
+
+/// A file name built from a font's names, such as `{family}-{style}.{ext}`.
+///
+/// `{family}`, `{style}` and `{postscript}` come from the font's `name`
+/// table, `{stem}` and `{ext}` from its original file name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamePattern(String);
+
+impl NamePattern {
+    /// Check that `pattern` uses only known placeholders and keeps the
+    /// extension.
+    pub fn parse(pattern: &str) -> FontResult<Self> {
+        let mut rest = pattern.to_string();
+        for placeholder in PLACEHOLDERS {
+            rest = rest.replace(placeholder, "");
+        }
+        if rest.contains(['{', '}']) {
+            return Err(FontError::InvalidArgument(format!(
+                "Unknown placeholder in name pattern '{pattern}'; use {}",
+                PLACEHOLDERS.join(", ")
+            )));
+        }
+        if !pattern.ends_with(".{ext}") {
+            return Err(FontError::InvalidArgument(format!(
+                "Name pattern '{pattern}' must end in .{{ext}}"
+            )));
+        }
+        Ok(Self(pattern.to_string()))
+    }
+
+    /// The sanitized file name for `face`, originally named `original`.
+    pub fn render(&self, face: &FontliftFontFaceInfo, original: &str) -> String {
+        let (stem, ext) = original.rsplit_once('.').unwrap_or((original, ""));
+        let name = self
+            .0
+            .replace("{family}", &face.family_name)
+            .replace("{style}", &face.style)
+            .replace("{postscript}", &face.postscript_name)
+            .replace("{stem}", stem)
+            .replace("{ext}", &ext.to_lowercase());
+        sanitize_file_name(&name)
+    }
+}
+
+/// `file_name`, or the first of `Name-2.ext`, `Name-3.ext`, ... that is not
+/// in `taken`, compared case-insensitively as macOS and Windows do. The
+/// chosen name is added to `taken`.
+pub fn unique_file_name(file_name: &str, taken: &mut HashSet<String>) -> String {
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{ext}")),
+        _ => (file_name, String::new()),
+    };
+    let mut candidate = file_name.to_string();
+    let mut n = 2;
+    while !taken.insert(candidate.to_lowercase()) {
+        candidate = format!("{stem}-{n}{extension}");
+        n += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LONG_NAME: &str = "AcmeGroteskDisplayCondensedExtraBoldItalic-VariableFont_opsz,wght.ttf";
+
+    #[test]
+    fn keeps_names_that_fit_and_shortens_deterministically() {
+        let shallow = Path::new(r"C:\Users\ada\AppData\Local\Microsoft\Windows\Fonts");
+        assert_eq!(
+            fit_file_name(shallow, LONG_NAME, WINDOWS_MAX_PATH).unwrap(),
+            LONG_NAME
+        );
+
+        let deep = PathBuf::from(format!(
+            r"C:\Users\{}\AppData\Local\Microsoft\Windows\Fonts",
+            "a".repeat(150)
+        ));
+        let short = fit_file_name(&deep, LONG_NAME, WINDOWS_MAX_PATH).unwrap();
+        assert_ne!(short, LONG_NAME);
+        assert!(short.starts_with("AcmeGrotesk"));
+        assert!(short.ends_with(".ttf"));
+        assert!(units(&deep.join(&short).to_string_lossy()) < WINDOWS_MAX_PATH);
+        assert_eq!(
+            fit_file_name(&deep, LONG_NAME, WINDOWS_MAX_PATH).unwrap(),
+            short
+        );
+
+        // Different originals that share a prefix stay distinct.
+        let other = LONG_NAME.replace("Italic", "Oblique");
+        assert_ne!(
+            fit_file_name(&deep, &other, WINDOWS_MAX_PATH).unwrap(),
+            short
+        );
+    }
+
+    #[test]
+    fn sanitizes_exotic_names() {
+        assert_eq!(sanitize_file_name("Inter-Bold.ttf"), "Inter-Bold.ttf");
+        assert_eq!(
+            sanitize_file_name("Noto Sans 日本語.otf"),
+            "Noto Sans 日本語.otf"
+        );
+        assert_eq!(sanitize_file_name("Party🎉🎉Font .ttf"), "Party_Font.ttf");
+        assert_eq!(sanitize_file_name("a<b>:c?.otf"), "a_b_c_.otf");
+        assert_eq!(sanitize_file_name("CON.ttf"), "CON_.ttf");
+        assert_eq!(sanitize_file_name("lpt1.OTF"), "lpt1_.OTF");
+        assert_eq!(sanitize_file_name("🎉.ttf"), "font.ttf");
+    }
+
+    #[test]
+    fn patterns_build_names_and_collisions_are_numbered() {
+        let mut face = FontliftFontFaceInfo::new(
+            crate::FontliftFontSource::new(PathBuf::from("x.TTF")),
+            "Acme-BoldItalic".into(),
+            "Acme Bold Italic".into(),
+            "Acme".into(),
+            "Bold Italic".into(),
+        );
+        let pattern = NamePattern::parse("{family}-{style}.{ext}").unwrap();
+        assert_eq!(pattern.render(&face, "x.TTF"), "Acme-Bold Italic.ttf");
+        face.family_name = "Acme/Sans".into();
+        assert_eq!(
+            NamePattern::parse("{postscript}_{stem}.{ext}")
+                .unwrap()
+                .render(&face, "x.TTF"),
+            "Acme-BoldItalic_x.ttf"
+        );
+        assert!(NamePattern::parse("{family}-{weight}.{ext}").is_err());
+        assert!(NamePattern::parse("{family}.ttf").is_err());
+
+        let mut taken = HashSet::new();
+        assert_eq!(unique_file_name("Acme.ttf", &mut taken), "Acme.ttf");
+        assert_eq!(unique_file_name("ACME.ttf", &mut taken), "ACME-2.ttf");
+        assert_eq!(unique_file_name("Acme.ttf", &mut taken), "Acme-3.ttf");
+    }
+
+    #[test]
+    fn rejects_directories_too_deep_for_any_name() {
+        let too_deep = PathBuf::from(format!(r"C:\{}", "x".repeat(250)));
+        assert!(matches!(
+            fit_file_name(&too_deep, LONG_NAME, WINDOWS_MAX_PATH),
+            Err(FontError::UnsupportedOperation(_))
+        ));
+        assert_eq!(shorten_file_name("Font.ttf", 13), None);
+        assert_eq!(
+            shorten_file_name("Font-.ttf", 18).as_deref(),
+            Some(format!("Font-{}.ttf", &digest::sha256_hex(b"Font-.ttf")[..8]).as_str())
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Installing fonts that exist only as bytes: `fontlift install - --name
+//! MyFont.ttf` reading stdin, `fontlift.install_bytes()` in Python and
+//! [`FontManager::install_font_bytes`].
+//!
+//! Platforms install from files, so the bytes are written to a private temp
+//! directory under the suggested file name, which then names the installed
+//! copy too. The directory is removed when the [`StagedFont`] is dropped,
+//! after the platform has copied the file into its font folder.
+//!
+//! [`FontManager::install_font_bytes`]: crate::FontManager::install_font_bytes
+
+use crate::validation_ext::DEFAULT_MAX_SIZE;
+use crate::{file_names, validation, FontError, FontResult};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Font bytes written to a temp file, deleted with its directory when
+/// dropped.
+#[derive(Debug)]
+pub struct StagedFont {
+    /// The suggested file name, sanitized like installed copies.
+    pub path: PathBuf,
+    dir: PathBuf,
+}
+
+impl Drop for StagedFont {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+impl StagedFont {
+    /// Write `data` to a temp file named `file_name`.
+    pub fn write(data: &[u8], file_name: &str) -> FontResult<Self> {
+        Self::read_from(data, file_name)
+    }
+
+    /// Copy `reader` (stdin) to a temp file named `file_name`. Empty input
+    /// and input past the validator's size limit are refused.
+    pub fn read_from(reader: impl Read, file_name: &str) -> FontResult<Self> {
+        let name = staged_file_name(file_name)?;
+        let dir = std::env::temp_dir().join(format!("fontlift-bytes-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).map_err(FontError::IoError)?;
+        // Build the guard first so the temp directory is cleaned up on error.
+        let staged = StagedFont {
+            path: dir.join(name),
+            dir,
+        };
+
+        let mut out = File::create(&staged.path).map_err(FontError::IoError)?;
+        let written = io::copy(&mut reader.take(DEFAULT_MAX_SIZE + 1), &mut out)
+            .map_err(FontError::IoError)?;
+        if written == 0 {
+            return Err(FontError::InvalidFormat(format!(
+                "No font data for {file_name}"
+            )));
+        }
+        if written > DEFAULT_MAX_SIZE {
+            return Err(FontError::InvalidFormat(format!(
+                "Font data for {file_name} exceeds the {} MB size limit",
+                DEFAULT_MAX_SIZE / (1024 * 1024)
+            )));
+        }
+        Ok(staged)
+    }
+}
+
+/// `file_name` checked as the name of a staged font: a bare file name with
+/// a font extension, sanitized with [`file_names::sanitize_file_name`].
+pub fn staged_file_name(file_name: &str) -> FontResult<String> {
+    if Path::new(file_name)
+        .file_name()
+        .and_then(|name| name.to_str())
+        != Some(file_name)
+    {
+        return Err(FontError::InvalidArgument(format!(
+            "'{file_name}' is not a file name; use a name such as MyFont.ttf"
+        )));
+    }
+    if !validation::is_valid_font_extension(Path::new(file_name)) {
+        return Err(FontError::InvalidArgument(format!(
+            "'{file_name}' has no font extension (.ttf, .otf, .ttc, .otc, .woff, .woff2)"
+        )));
+    }
+    Ok(file_names::sanitize_file_name(file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontlift_testfonts::TestFont;
+
+    #[test]
+    fn stages_bytes_under_the_suggested_name() {
+        let tmp = tempfile::tempdir().unwrap();
+        let font = TestFont::new("Piped Sans").write_to(tmp.path()).unwrap();
+        let data = std::fs::read(&font).unwrap();
+
+        let staged = StagedFont::read_from(&data[..], "Piped.ttf").unwrap();
+        assert_eq!(staged.path.file_name().unwrap(), "Piped.ttf");
+        let info = crate::metadata::read_face_info(&staged.path).unwrap();
+        assert_eq!(info.postscript_name, "PipedSans-Regular");
+
+        let dir = staged.path.parent().unwrap().to_path_buf();
+        drop(staged);
+        assert!(!dir.exists(), "temp dir is removed on drop");
+    }
+
+    #[test]
+    fn refuses_paths_missing_extensions_and_empty_input() {
+        assert!(staged_file_name("../evil.ttf").is_err());
+        assert!(staged_file_name("dir/MyFont.ttf").is_err());
+        assert!(staged_file_name("MyFont").is_err());
+        assert!(staged_file_name("").is_err());
+        assert_eq!(staged_file_name("My Font.otf").unwrap(), "My Font.otf");
+        assert!(StagedFont::write(b"", "Empty.ttf").is_err());
+    }
+}
@@ -0,0 +1,203 @@
+//! Installed-font inventories in fontconfig's formats (`list --output`).
+//!
+//! Toolchains that already read fontconfig data can take a macOS or Windows
+//! inventory without a fontlift-specific parser:
+//!
+//! - [`fc_list_line`] matches `fc-list`'s default output,
+//!   `/Library/Fonts/Inter.ttc: Inter:style=Bold`.
+//! - [`to_fontconfig_xml`] writes one `<pattern>` per face inside a
+//!   `<selectfont><acceptfont>` block, so the file is also a valid
+//!   `fonts.conf` fragment:
+//!
+//! ```xml
+//! <pattern>
+//!   <patelt name="file"><string>/Library/Fonts/Inter.ttc</string></patelt>
+//!   <patelt name="index"><int>1</int></patelt>
+//!   <patelt name="family"><string>Inter</string></patelt>
+//!   <patelt name="style"><string>Bold</string></patelt>
+//!   <patelt name="weight"><int>200</int></patelt>
+//!   <patelt name="slant"><int>0</int></patelt>
+//! </pattern>
+//! ```
+//!
+//! Weights are converted to fontconfig's scale (Regular 80, Bold 200) the
+//! way fontconfig converts `OS/2.usWeightClass`.
+
+use crate::FontliftFontFaceInfo;
+use std::fmt::Write;
+
+/// `FC_SLANT_ROMAN` and `FC_SLANT_ITALIC`.
+const FC_SLANT_ROMAN: i32 = 0;
+const FC_SLANT_ITALIC: i32 = 100;
+
+/// fontconfig's `(usWeightClass, FC_WEIGHT)` anchor points.
+const WEIGHT_MAP: &[(f64, f64)] = &[
+    (100.0, 0.0),
+    (200.0, 40.0),
+    (300.0, 50.0),
+    (350.0, 55.0),
+    (380.0, 75.0),
+    (400.0, 80.0),
+    (500.0, 100.0),
+    (600.0, 180.0),
+    (700.0, 200.0),
+    (800.0, 205.0),
+    (900.0, 210.0),
+    (1000.0, 215.0),
+];
+
+/// `usWeightClass` on fontconfig's weight scale, interpolating between the
+/// named weights like `FcWeightFromOpenTypeDouble`.
+pub fn fc_weight(weight: u16) -> i32 {
+    let weight = f64::from(weight.clamp(100, 1000));
+    let upper = WEIGHT_MAP
+        .iter()
+        .position(|&(ot, _)| weight <= ot)
+        .unwrap_or(WEIGHT_MAP.len() - 1);
+    if upper == 0 {
+        return WEIGHT_MAP[0].1 as i32;
+    }
+    let (ot0, fc0) = WEIGHT_MAP[upper - 1];
+    let (ot1, fc1) = WEIGHT_MAP[upper];
+    (fc0 + (fc1 - fc0) * (weight - ot0) / (ot1 - ot0)).round() as i32
+}
+
+fn is_italic(face: &FontliftFontFaceInfo) -> bool {
+    face.italic
+        .unwrap_or_else(|| face.style.to_lowercase().contains("italic"))
+}
+
+/// One face as `fc-list` prints it: `<file>: <family>:style=<style>`.
+pub fn fc_list_line(face: &FontliftFontFaceInfo) -> String {
+    // fc-list escapes the pattern separators inside values.
+    let escape = |s: &str| {
+        let mut out = String::with_capacity(s.len());
+        for c in s.chars() {
+            if matches!(c, '\\' | ':' | ',' | '=') {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+        out
+    };
+    format!(
+        "{}: {}:style={}",
+        face.source.path.display(),
+        escape(&face.family_name),
+        escape(&face.style)
+    )
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A fontconfig XML document with one `<pattern>` per face.
+pub fn to_fontconfig_xml(faces: &[FontliftFontFaceInfo]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\"?>\n\
+         <!DOCTYPE fontconfig SYSTEM \"urn:fontconfig:fonts.dtd\">\n\
+         <fontconfig>\n",
+    );
+    let _ = writeln!(
+        xml,
+        "  <!-- {} installed face(s), exported by fontlift {} -->",
+        faces.len(),
+        env!("CARGO_PKG_VERSION")
+    );
+    xml.push_str("  <selectfont>\n    <acceptfont>\n");
+
+    for face in faces {
+        let string = |name: &str, value: &str| {
+            format!(
+                "        <patelt name=\"{name}\"><string>{}</string></patelt>\n",
+                xml_escape(value)
+            )
+        };
+        let int = |name: &str, value: i64| {
+            format!("        <patelt name=\"{name}\"><int>{value}</int></patelt>\n")
+        };
+
+        xml.push_str("      <pattern>\n");
+        xml.push_str(&string("file", &face.source.path.to_string_lossy()));
+        xml.push_str(&int("index", face.source.face_index.unwrap_or(0).into()));
+        xml.push_str(&string("family", &face.family_name));
+        xml.push_str(&string("style", &face.style));
+        xml.push_str(&string("fullname", &face.full_name));
+        xml.push_str(&string("postscriptname", &face.postscript_name));
+        if let Some(weight) = face.weight {
+            xml.push_str(&int("weight", fc_weight(weight).into()));
+        }
+        let slant = if is_italic(face) {
+            FC_SLANT_ITALIC
+        } else {
+            FC_SLANT_ROMAN
+        };
+        xml.push_str(&int("slant", slant.into()));
+        if let Some(format) = &face.source.format {
+            xml.push_str(&string("fontformat", &fontformat(format)));
+        }
+        xml.push_str("      </pattern>\n");
+    }
+
+    xml.push_str("    </acceptfont>\n  </selectfont>\n</fontconfig>\n");
+    xml
+}
+
+/// fontconfig's `fontformat` names (FreeType driver names).
+fn fontformat(format: &str) -> String {
+    match format.to_ascii_uppercase().as_str() {
+        "OTF" | "OTC" => "CFF".to_string(),
+        "TTF" | "TTC" => "TrueType".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FontliftFontSource;
+    use std::path::PathBuf;
+
+    #[test]
+    fn renders_fc_list_lines_and_patterns() {
+        let mut face = FontliftFontFaceInfo::new(
+            FontliftFontSource::new(PathBuf::from("/Library/Fonts/Inter.ttc"))
+                .with_format(Some("TTC".to_string())),
+            "Inter-BoldItalic".to_string(),
+            "Inter Bold Italic".to_string(),
+            "Inter".to_string(),
+            "Bold Italic".to_string(),
+        );
+        face.source.face_index = Some(3);
+        face.weight = Some(700);
+        face.italic = Some(true);
+
+        assert_eq!(
+            fc_list_line(&face),
+            "/Library/Fonts/Inter.ttc: Inter:style=Bold Italic"
+        );
+        face.family_name = "R&D: Sans".to_string();
+        assert!(fc_list_line(&face).contains(" R&D\\: Sans:style="));
+
+        let xml = to_fontconfig_xml(std::slice::from_ref(&face));
+        assert!(xml.contains("<patelt name=\"family\"><string>R&amp;D: Sans</string></patelt>"));
+        assert!(xml.contains("<patelt name=\"index\"><int>3</int></patelt>"));
+        assert!(xml.contains("<patelt name=\"weight\"><int>200</int></patelt>"));
+        assert!(xml.contains("<patelt name=\"slant\"><int>100</int></patelt>"));
+        assert!(xml.contains("<string>TrueType</string>"));
+        assert!(xml.trim_end().ends_with("</fontconfig>"));
+    }
+
+    #[test]
+    fn weights_follow_fontconfig_scale() {
+        assert_eq!(fc_weight(400), 80);
+        assert_eq!(fc_weight(700), 200);
+        assert_eq!(fc_weight(100), 0);
+        assert_eq!(fc_weight(450), 90);
+        assert_eq!(fc_weight(1), 0);
+    }
+}
@@ -0,0 +1,284 @@
+//! Google Fonts provider for `fontlift google search` / `fontlift google install`.
+//!
+//! Family metadata comes from the Google Fonts Developer API
+//! (`https://www.googleapis.com/webfonts/v1/webfonts`), which lists every
+//! family with a static TTF URL per variant. Set `GOOGLE_FONTS_API_KEY` to
+//! authenticate. `FONTLIFT_GOOGLE_FONTS_API` points fontlift at a mirror that
+//! serves the same JSON shape instead.
+//!
+//! Variants use the API's naming: `"regular"`, `"italic"`, `"700"`,
+//! `"700italic"`. Downloaded files are renamed after the family and style
+//! (`Inter-BoldItalic.ttf`) because the API's own URLs end in opaque hashes.
+//!
+//! Parsing and name handling are always available. Network access needs the
+//! `google-fonts` feature, which builds on `fetch`.
+
+use crate::{sorting, FontError, FontResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Default Developer API endpoint.
+pub const DEFAULT_API_URL: &str = "https://www.googleapis.com/webfonts/v1/webfonts";
+
+/// One family as listed by the Developer API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoogleFontFamily {
+    pub family: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub variants: Vec<String>,
+    /// Variant name → static TTF URL.
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+}
+
+impl GoogleFontFamily {
+    /// Download URLs for `variants` (all variants when empty), in API order.
+    ///
+    /// Returns [`FontError::InvalidFormat`] naming the first variant the
+    /// family does not have.
+    pub fn variant_files(&self, variants: &[String]) -> FontResult<Vec<(String, String)>> {
+        let wanted: Vec<&String> = if variants.is_empty() {
+            self.variants.iter().collect()
+        } else {
+            variants.iter().collect()
+        };
+
+        wanted
+            .into_iter()
+            .map(|variant| {
+                self.files
+                    .get(variant)
+                    .map(|url| (variant.clone(), url.clone()))
+                    .ok_or_else(|| {
+                        FontError::InvalidFormat(format!(
+                            "{} has no '{}' variant (available: {})",
+                            self.family,
+                            variant,
+                            self.variants.join(", ")
+                        ))
+                    })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FamilyList {
+    #[serde(default)]
+    items: Vec<GoogleFontFamily>,
+}
+
+/// Parse a Developer API response body.
+pub fn parse_family_list(json: &str) -> FontResult<Vec<GoogleFontFamily>> {
+    serde_json::from_str::<FamilyList>(json)
+        .map(|list| list.items)
+        .map_err(|e| FontError::InvalidFormat(format!("Unexpected Google Fonts response: {e}")))
+}
+
+/// Families whose name contains `query`, case-insensitively, in name order.
+pub fn search_families<'a>(
+    families: &'a [GoogleFontFamily],
+    query: &str,
+) -> Vec<&'a GoogleFontFamily> {
+    let needle = query.to_lowercase();
+    let mut matches: Vec<_> = families
+        .iter()
+        .filter(|f| f.family.to_lowercase().contains(&needle))
+        .collect();
+    matches.sort_by(|a, b| sorting::compare_names(&a.family, &b.family));
+    matches
+}
+
+/// The family named exactly `name`, case-insensitively.
+pub fn find_family<'a>(
+    families: &'a [GoogleFontFamily],
+    name: &str,
+) -> Option<&'a GoogleFontFamily> {
+    families
+        .iter()
+        .find(|f| f.family.eq_ignore_ascii_case(name))
+}
+
+/// Style name for an API variant: `"700italic"` → `"BoldItalic"`.
+pub fn variant_style_name(variant: &str) -> String {
+    let (weight, italic) = match variant {
+        "regular" => ("400", false),
+        "italic" => ("400", true),
+        other => match other.strip_suffix("italic") {
+            Some(weight) => (weight, true),
+            None => (other, false),
+        },
+    };
+
+    let weight_name = match weight {
+        "100" => "Thin",
+        "200" => "ExtraLight",
+        "300" => "Light",
+        "400" => "Regular",
+        "500" => "Medium",
+        "600" => "SemiBold",
+        "700" => "Bold",
+        "800" => "ExtraBold",
+        "900" => "Black",
+        other => other,
+    };
+
+    match (weight_name, italic) {
+        ("Regular", true) => "Italic".to_string(),
+        (name, true) => format!("{name}Italic"),
+        (name, false) => name.to_string(),
+    }
+}
+
+/// Local filename for one variant: `"Noto Sans"`, `"700"` → `NotoSans-Bold.ttf`.
+pub fn variant_file_name(family: &str, variant: &str) -> String {
+    let stem: String = family
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    format!("{}-{}.ttf", stem, variant_style_name(variant))
+}
+
+/// API endpoint, honouring `FONTLIFT_GOOGLE_FONTS_API`.
+pub fn api_url() -> String {
+    std::env::var("FONTLIFT_GOOGLE_FONTS_API").unwrap_or_else(|_| DEFAULT_API_URL.to_string())
+}
+
+#[cfg(feature = "google-fonts")]
+mod remote {
+    use super::*;
+    use crate::fetch::{self, FetchRequest, FetchedFont};
+
+    /// Fetch the family list, optionally narrowed to one `family`.
+    pub fn fetch_families(
+        api_key: Option<&str>,
+        family: Option<&str>,
+    ) -> FontResult<Vec<GoogleFontFamily>> {
+        let mut request = ureq::get(&api_url()).query("sort", "alpha");
+        if let Some(key) = api_key {
+            request = request.query("key", key);
+        }
+        if let Some(family) = family {
+            request = request.query("family", family);
+        }
+
+        let body = request
+            .call()
+            .map_err(|e| {
+                let hint = if api_key.is_none() {
+                    " (set GOOGLE_FONTS_API_KEY or pass --api-key)"
+                } else {
+                    ""
+                };
+                FontError::IoError(std::io::Error::other(format!(
+                    "Google Fonts request failed: {e}{hint}"
+                )))
+            })?
+            .into_string()
+            .map_err(FontError::IoError)?;
+
+        parse_family_list(&body)
+    }
+
+    /// Download the static TTFs for `variants` of `family` (all when empty),
+    /// at most `max_concurrent` at a time.
+    ///
+    /// Downloads go through the content store, so variants that did not
+    /// change since the last run are not fetched again.
+    pub fn download_family(
+        family: &GoogleFontFamily,
+        variants: &[String],
+        max_concurrent: usize,
+    ) -> FontResult<Vec<FetchedFont>> {
+        let requests: Vec<FetchRequest> = family
+            .variant_files(variants)?
+            .into_iter()
+            .map(|(variant, url)| {
+                FetchRequest::new(url.replacen("http://", "https://", 1))
+                    .with_filename(variant_file_name(&family.family, &variant))
+            })
+            .collect();
+        fetch::fetch_fonts(&requests, max_concurrent)
+    }
+}
+
+#[cfg(feature = "google-fonts")]
+pub use remote::{download_family, fetch_families};
+
+/// Stand-in used when fontlift was built without the `google-fonts` feature.
+#[cfg(not(feature = "google-fonts"))]
+pub fn fetch_families(
+    _api_key: Option<&str>,
+    _family: Option<&str>,
+) -> FontResult<Vec<GoogleFontFamily>> {
+    Err(FontError::UnsupportedOperation(
+        "Google Fonts support requires the `google-fonts` feature".to_string(),
+    ))
+}
+
+/// Stand-in used when fontlift was built without the `google-fonts` feature.
+#[cfg(not(feature = "google-fonts"))]
+pub fn download_family(
+    _family: &GoogleFontFamily,
+    _variants: &[String],
+    _max_concurrent: usize,
+) -> FontResult<Vec<crate::fetch::FetchedFont>> {
+    Err(FontError::UnsupportedOperation(
+        "Google Fonts support requires the `google-fonts` feature".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"{
+        "kind": "webfonts#webfontList",
+        "items": [
+            {
+                "family": "Inter",
+                "category": "sans-serif",
+                "variants": ["regular", "700", "700italic"],
+                "files": {
+                    "regular": "http://fonts.gstatic.com/s/inter/v13/a.ttf",
+                    "700": "http://fonts.gstatic.com/s/inter/v13/b.ttf",
+                    "700italic": "http://fonts.gstatic.com/s/inter/v13/c.ttf"
+                }
+            },
+            { "family": "Inter Tight", "variants": ["regular"], "files": {} }
+        ]
+    }"#;
+
+    #[test]
+    fn parses_and_searches_family_list() {
+        let families = parse_family_list(SAMPLE).unwrap();
+        assert_eq!(search_families(&families, "inter").len(), 2);
+        assert_eq!(find_family(&families, "INTER").unwrap().family, "Inter");
+        assert!(find_family(&families, "Roboto").is_none());
+        assert!(parse_family_list("not json").is_err());
+    }
+
+    #[test]
+    fn selects_variant_files_and_rejects_unknown_variants() {
+        let families = parse_family_list(SAMPLE).unwrap();
+        let inter = find_family(&families, "Inter").unwrap();
+
+        assert_eq!(inter.variant_files(&[]).unwrap().len(), 3);
+        let bold = inter.variant_files(&["700".to_string()]).unwrap();
+        assert_eq!(bold[0].1, "http://fonts.gstatic.com/s/inter/v13/b.ttf");
+        assert!(inter.variant_files(&["300".to_string()]).is_err());
+    }
+
+    #[test]
+    fn names_files_after_family_and_style() {
+        assert_eq!(variant_file_name("Inter", "regular"), "Inter-Regular.ttf");
+        assert_eq!(variant_file_name("Inter", "italic"), "Inter-Italic.ttf");
+        assert_eq!(
+            variant_file_name("Noto Sans", "700italic"),
+            "NotoSans-BoldItalic.ttf"
+        );
+        assert_eq!(variant_style_name("250"), "250");
+    }
+}
@@ -0,0 +1,354 @@
+//! Append-only log of completed font operations (`fontlift history`).
+//!
+//! The journal only holds operations until they finish; once a font is
+//! installed, nothing says who installed it, when, or what else that run
+//! touched. On shared machines admins need that trail, so every install,
+//! uninstall, remove and cleanup that ran (not dry runs) appends one
+//! [`HistoryEntry`]: when, which user, the scope, the files it handled and
+//! how it ended.
+//!
+//! ## File format
+//!
+//! `history.jsonl` sits next to the journal (see
+//! [`crate::journal::journal_path`]); `FONTLIFT_HISTORY_PATH` overrides it.
+//! Each line is one JSON entry. Entries are only ever appended, each with a
+//! single write, so concurrent runs do not interleave within a line and a
+//! crash can at worst leave a torn last line, which [`load_history_from`]
+//! skips.
+
+use crate::journal::{self, systemtime_serde};
+use crate::{FontError, FontResult, FontScope};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// The kind of operation an entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryOperation {
+    Install,
+    Uninstall,
+    Remove,
+    Cleanup,
+}
+
+impl HistoryOperation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HistoryOperation::Install => "install",
+            HistoryOperation::Uninstall => "uninstall",
+            HistoryOperation::Remove => "remove",
+            HistoryOperation::Cleanup => "cleanup",
+        }
+    }
+}
+
+/// How an operation ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryOutcome {
+    Succeeded,
+    /// Some files were handled, others failed.
+    Partial,
+    Failed,
+}
+
+impl HistoryOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HistoryOutcome::Succeeded => "succeeded",
+            HistoryOutcome::Partial => "partial",
+            HistoryOutcome::Failed => "failed",
+        }
+    }
+}
+
+/// A file the operation could not handle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedFile {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// One completed operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: Uuid,
+    #[serde(with = "systemtime_serde")]
+    pub at: SystemTime,
+    /// The account the process ran as.
+    pub user: String,
+    /// The account that ran `sudo`, when it differs from `user`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sudo_user: Option<String>,
+    pub operation: HistoryOperation,
+    pub scope: FontScope,
+    /// Files handled successfully: installed, uninstalled, removed or, for
+    /// cleanup, whose stale registrations were pruned.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<PathBuf>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<FailedFile>,
+    pub outcome: HistoryOutcome,
+    /// The error that stopped the operation, if one did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HistoryEntry {
+    /// Start an entry for an operation running now as the current user.
+    pub fn start(operation: HistoryOperation, scope: FontScope) -> Self {
+        let user = current_user();
+        let sudo_user = std::env::var("SUDO_USER")
+            .ok()
+            .filter(|sudo| !sudo.is_empty() && *sudo != user);
+        Self {
+            id: Uuid::new_v4(),
+            at: SystemTime::now(),
+            user,
+            sudo_user,
+            operation,
+            scope,
+            files: Vec::new(),
+            failed: Vec::new(),
+            outcome: HistoryOutcome::Succeeded,
+            error: None,
+        }
+    }
+
+    /// Note a file the operation handled.
+    pub fn handled(&mut self, path: &Path) {
+        self.files.push(path.to_path_buf());
+    }
+
+    /// Note a file the operation could not handle, and why.
+    pub fn failed(&mut self, path: &Path, error: impl std::fmt::Display) {
+        self.failed.push(FailedFile {
+            path: path.to_path_buf(),
+            error: error.to_string(),
+        });
+    }
+
+    /// Settle the outcome from the files noted and the operation's result.
+    pub fn finish(mut self, result: Result<(), &FontError>) -> Self {
+        self.error = result.err().map(FontError::message);
+        self.outcome = match (
+            self.error.is_some() || !self.failed.is_empty(),
+            self.files.is_empty(),
+        ) {
+            (false, _) => HistoryOutcome::Succeeded,
+            (true, false) => HistoryOutcome::Partial,
+            (true, true) => HistoryOutcome::Failed,
+        };
+        self
+    }
+}
+
+/// The account the process runs as, from `USER`, `USERNAME` or `LOGNAME`.
+pub fn current_user() -> String {
+    ["USER", "USERNAME", "LOGNAME"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|user| !user.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Return the history log path.
+///
+/// `FONTLIFT_HISTORY_PATH` overrides the default, which sits next to the
+/// journal file.
+pub fn history_path() -> PathBuf {
+    if let Ok(override_path) = std::env::var("FONTLIFT_HISTORY_PATH") {
+        return PathBuf::from(override_path);
+    }
+
+    journal::journal_path().with_file_name("history.jsonl")
+}
+
+/// Append `entry` to the history log.
+pub fn append(entry: &HistoryEntry) -> FontResult<()> {
+    append_to(entry, &history_path())
+}
+
+/// [`append`] to an explicit path.
+pub fn append_to(entry: &HistoryEntry, path: &Path) -> FontResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(FontError::IoError)?;
+    }
+    let mut line = serde_json::to_string(entry)
+        .map_err(|e| FontError::InvalidFormat(format!("Failed to serialize history entry: {e}")))?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)
+        .map_err(FontError::IoError)?;
+    // Start a fresh line after a torn one, so only the torn entry is lost.
+    if !ends_with_newline(&mut file).map_err(FontError::IoError)? {
+        line.insert(0, '\n');
+    }
+    file.write_all(line.as_bytes()).map_err(FontError::IoError)
+}
+
+fn ends_with_newline(file: &mut fs::File) -> std::io::Result<bool> {
+    if file.metadata()?.len() == 0 {
+        return Ok(true);
+    }
+    file.seek(SeekFrom::End(-1))?;
+    let mut last = [0u8];
+    file.read_exact(&mut last)?;
+    Ok(last[0] == b'\n')
+}
+
+/// Load the history log, oldest entry first. A missing file is an empty
+/// history.
+pub fn load_history() -> FontResult<Vec<HistoryEntry>> {
+    load_history_from(&history_path())
+}
+
+/// [`load_history`] from an explicit path. Lines that do not parse, such as
+/// one torn by a crash mid-write, are skipped.
+pub fn load_history_from(path: &Path) -> FontResult<Vec<HistoryEntry>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(FontError::IoError(std::io::Error::new(
+                e.kind(),
+                format!("Failed to read history: {e}"),
+            )))
+        }
+    };
+
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Entries recorded at or after `cutoff`.
+pub fn since(entries: Vec<HistoryEntry>, cutoff: SystemTime) -> Vec<HistoryEntry> {
+    entries
+        .into_iter()
+        .filter(|entry| entry.at >= cutoff)
+        .collect()
+}
+
+/// Parse an age such as `90s`, `30m`, `12h`, `7d` or `2w`.
+pub fn parse_age(text: &str) -> FontResult<Duration> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => 0,
+    };
+    match number.parse::<u64>() {
+        Ok(count) if seconds > 0 => Ok(Duration::from_secs(count.saturating_mul(seconds))),
+        _ => Err(FontError::InvalidArgument(format!(
+            "'{text}' is not an age; use a number with s, m, h, d or w, such as 7d"
+        ))),
+    }
+}
+
+/// `time` as `YYYY-MM-DD HH:MM:SS` in UTC.
+pub fn format_utc(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+    let (days, rest) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        rest / 3_600,
+        rest % 3_600 / 60,
+        rest % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_entries_and_queries_them_by_age() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("state").join("history.jsonl");
+        assert!(load_history_from(&path).unwrap().is_empty());
+
+        let mut old = HistoryEntry::start(HistoryOperation::Install, FontScope::User);
+        old.at = SystemTime::now() - Duration::from_secs(10 * 24 * 60 * 60);
+        old.handled(Path::new("/fonts/Old-Regular.ttf"));
+        append_to(&old.finish(Ok(())), &path).unwrap();
+
+        let mut recent = HistoryEntry::start(HistoryOperation::Remove, FontScope::System);
+        recent.handled(Path::new("/fonts/A-Regular.ttf"));
+        recent.failed(Path::new("/fonts/B-Regular.ttf"), "permission denied");
+        let recent = recent.finish(Ok(()));
+        assert_eq!(recent.outcome, HistoryOutcome::Partial);
+        append_to(&recent, &path).unwrap();
+
+        // A crash mid-append leaves a torn line behind.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"id\":\"torn").unwrap();
+
+        let entries = load_history_from(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].id, recent.id);
+
+        // The next entry still gets a line of its own.
+        let next = HistoryEntry::start(HistoryOperation::Cleanup, FontScope::User).finish(Ok(()));
+        append_to(&next, &path).unwrap();
+        let entries = load_history_from(&path).unwrap();
+        assert_eq!(entries.len(), 3);
+
+        let week = SystemTime::now() - parse_age("7d").unwrap();
+        let ids: Vec<Uuid> = since(entries, week).iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![recent.id, next.id]);
+    }
+
+    #[test]
+    fn an_error_with_nothing_handled_is_a_failure() {
+        let entry = HistoryEntry::start(HistoryOperation::Cleanup, FontScope::User)
+            .finish(Err(&FontError::PermissionDenied("cache".to_string())));
+        assert_eq!(entry.outcome, HistoryOutcome::Failed);
+        assert!(entry.error.is_some());
+    }
+
+    #[test]
+    fn parses_ages_and_formats_times() {
+        assert_eq!(parse_age("90s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_age("2w").unwrap(), Duration::from_secs(14 * 86_400));
+        assert!(parse_age("7").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("3y").is_err());
+
+        assert_eq!(format_utc(UNIX_EPOCH), "1970-01-01 00:00:00");
+        let leap_day = UNIX_EPOCH + Duration::from_secs(1_709_210_096);
+        assert_eq!(format_utc(leap_day), "2024-02-29 12:34:56");
+    }
+}
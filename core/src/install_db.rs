@@ -0,0 +1,319 @@
+//! Record of fonts that fontlift installed, and why.
+//!
+//! The OS font registry only knows *that* a font is installed. Package
+//! managers also remember *why*: was it requested by name, or pulled in as
+//! part of something bigger? fontlift keeps the same distinction, so that a
+//! font somebody asked for explicitly stays explicit when a profile or
+//! bundle installs it again.
+//!
+//! Every successful install through the CLI records an [`InstallRecord`] with
+//! an [`InstallReason`]:
+//!
+//! - [`InstallReason::Explicit`] — the user asked for this font directly.
+//! - [`InstallReason::Profile`] — installed as part of a named profile.
+//! - [`InstallReason::Dependency`] — pulled in by a bundle or another package.
+//!
+//! `fontlift list --why <name>` uses [`InstallRecord::explain`] to tell the
+//! user why a font is present.
+//!
+//! ## File location
+//!
+//! The database lives next to the journal as `installed.json` (see
+//! [`crate::journal::journal_path`]). Override it with
+//! `FONTLIFT_INSTALL_DB_PATH`.
+
+use crate::journal::{self, systemtime_serde};
+use crate::{FontError, FontResult, FontScope};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+/// Why a font was installed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InstallReason {
+    /// The user asked for this font directly.
+    Explicit,
+    /// Installed as part of the named profile.
+    Profile { name: String },
+    /// Installed because the named bundle or package needs it.
+    Dependency { of: String },
+}
+
+impl InstallReason {
+    /// Whether the user asked for this font directly.
+    pub fn is_explicit(&self) -> bool {
+        matches!(self, InstallReason::Explicit)
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            InstallReason::Explicit => "explicitly requested".to_string(),
+            InstallReason::Profile { name } => format!("part of profile '{name}'"),
+            InstallReason::Dependency { of } => format!("dependency of '{of}'"),
+        }
+    }
+}
+
+/// One font file installed through fontlift.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallRecord {
+    pub path: PathBuf,
+    pub scope: FontScope,
+    pub reason: InstallReason,
+    #[serde(with = "systemtime_serde")]
+    pub installed_at: SystemTime,
+    /// Where the file came from when it was not a plain local path, such as
+    /// the URL it was downloaded from or the archive it was extracted from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    /// The file name before it was shortened to fit the path length limit
+    /// (see [`crate::file_names`]), so the font can still be uninstalled by
+    /// the name the user knows.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_file_name: Option<String>,
+    /// SHA-256 of the installed file, for `fontlift verify`. `None` for
+    /// records written before digests were kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+impl InstallRecord {
+    /// Human-readable answer to "why is this font here?".
+    pub fn explain(&self) -> String {
+        let mut explanation = format!(
+            "{} was installed ({}) because it was {}",
+            self.path.display(),
+            self.scope.description(),
+            self.reason.description()
+        );
+        if let Some(origin) = &self.origin {
+            explanation.push_str(&format!(" (from {origin})"));
+        }
+        explanation
+    }
+}
+
+/// All fonts fontlift currently manages.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstallDb {
+    pub records: Vec<InstallRecord>,
+}
+
+impl InstallDb {
+    pub fn new() -> Self {
+        Self {
+            records: Vec::new(),
+        }
+    }
+
+    /// Record an install, replacing any previous record for the same path.
+    ///
+    /// Like `apt-mark manual`, an explicit request always wins: re-installing
+    /// a profile font explicitly promotes it, but installing an explicit font
+    /// again as a dependency does not demote it.
+    ///
+    /// Returns the new record so callers can fill in optional fields.
+    pub fn record(
+        &mut self,
+        path: PathBuf,
+        scope: FontScope,
+        reason: InstallReason,
+    ) -> &mut InstallRecord {
+        let reason = match self.find(&path) {
+            Some(existing) if existing.reason.is_explicit() => InstallReason::Explicit,
+            _ => reason,
+        };
+
+        self.forget(&path);
+        self.records.push(InstallRecord {
+            path,
+            scope,
+            reason,
+            installed_at: SystemTime::now(),
+            origin: None,
+            original_file_name: None,
+            sha256: None,
+        });
+        self.records.last_mut().expect("record was just pushed")
+    }
+
+    /// Drop the record for `path`. Returns `true` if one existed.
+    pub fn forget(&mut self, path: &Path) -> bool {
+        let before = self.records.len();
+        self.records.retain(|r| r.path != path);
+        before != self.records.len()
+    }
+
+    pub fn find(&self, path: &Path) -> Option<&InstallRecord> {
+        self.records.iter().find(|r| r.path == path)
+    }
+
+    /// The record of a font installed under a shortened name whose original
+    /// file name was `file_name` (compared case-insensitively).
+    pub fn find_by_original_name(&self, file_name: &str) -> Option<&InstallRecord> {
+        self.records.iter().find(|r| {
+            r.original_file_name
+                .as_deref()
+                .is_some_and(|original| original.eq_ignore_ascii_case(file_name))
+        })
+    }
+}
+
+/// Return the install database path.
+///
+/// `FONTLIFT_INSTALL_DB_PATH` overrides the default, which sits next to the
+/// journal file.
+pub fn install_db_path() -> PathBuf {
+    if let Ok(override_path) = std::env::var("FONTLIFT_INSTALL_DB_PATH") {
+        return PathBuf::from(override_path);
+    }
+
+    journal::journal_path().with_file_name("installed.json")
+}
+
+/// Load the install database. Missing files are treated as empty.
+pub fn load_install_db() -> FontResult<InstallDb> {
+    load_install_db_from(&install_db_path())
+}
+
+/// [`load_install_db`] from an explicit path.
+pub fn load_install_db_from(path: &Path) -> FontResult<InstallDb> {
+    if !path.exists() {
+        return Ok(InstallDb::new());
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| {
+        FontError::IoError(std::io::Error::new(
+            e.kind(),
+            format!("Failed to read install database: {e}"),
+        ))
+    })?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| FontError::InvalidFormat(format!("Failed to parse install database: {e}")))
+}
+
+/// Save the install database with a temp-file-then-rename write.
+pub fn save_install_db(db: &InstallDb) -> FontResult<()> {
+    save_install_db_to(db, &install_db_path())
+}
+
+/// [`save_install_db`] to an explicit path.
+pub fn save_install_db_to(db: &InstallDb, path: &Path) -> FontResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(FontError::IoError)?;
+    }
+
+    let temp_path = path.with_file_name(format!(
+        "installed.json.tmp.{}.{}",
+        std::process::id(),
+        Uuid::new_v4()
+    ));
+
+    let content = serde_json::to_string_pretty(db).map_err(|e| {
+        FontError::InvalidFormat(format!("Failed to serialize install database: {e}"))
+    })?;
+
+    fs::write(&temp_path, &content).map_err(FontError::IoError)?;
+
+    if let Err(e) = fs::rename(&temp_path, path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(FontError::IoError(std::io::Error::new(
+            e.kind(),
+            format!("Failed to rename install database: {e}"),
+        )));
+    }
+
+    Ok(())
+}
+
+/// Load, mutate and save the install database under the journal lock.
+pub fn update_install_db<R>(f: impl FnOnce(&mut InstallDb) -> R) -> FontResult<R> {
+    journal::with_journal_lock(|| {
+        let mut db = load_install_db()?;
+        let result = f(&mut db);
+        save_install_db(&db)?;
+        Ok(result)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_reason_is_never_demoted() {
+        let mut db = InstallDb::new();
+        let path = PathBuf::from("/fonts/Alpha.ttf");
+
+        db.record(path.clone(), FontScope::User, InstallReason::Explicit);
+        db.record(
+            path.clone(),
+            FontScope::User,
+            InstallReason::Dependency {
+                of: "Brand Kit".into(),
+            },
+        );
+
+        assert_eq!(db.records.len(), 1);
+        assert_eq!(db.find(&path).unwrap().reason, InstallReason::Explicit);
+
+        let profile_path = PathBuf::from("/fonts/Beta.ttf");
+        db.record(
+            profile_path.clone(),
+            FontScope::User,
+            InstallReason::Profile { name: "web".into() },
+        );
+        db.record(
+            profile_path.clone(),
+            FontScope::User,
+            InstallReason::Explicit,
+        );
+        assert!(db.find(&profile_path).unwrap().reason.is_explicit());
+    }
+
+    #[test]
+    fn forgotten_records_are_gone() {
+        let mut db = InstallDb::new();
+        db.record(
+            PathBuf::from("/fonts/Alpha.ttf"),
+            FontScope::User,
+            InstallReason::Explicit,
+        );
+        db.record(
+            PathBuf::from("/fonts/Beta.ttf"),
+            FontScope::User,
+            InstallReason::Profile {
+                name: "print".into(),
+            },
+        );
+
+        assert!(db.forget(Path::new("/fonts/Beta.ttf")));
+        assert!(!db.forget(Path::new("/fonts/Beta.ttf")));
+        assert!(db.find(Path::new("/fonts/Beta.ttf")).is_none());
+        assert!(db.find(Path::new("/fonts/Alpha.ttf")).is_some());
+    }
+
+    #[test]
+    fn reasons_round_trip_through_json() {
+        let mut db = InstallDb::new();
+        db.record(
+            PathBuf::from("/fonts/Gamma.otf"),
+            FontScope::System,
+            InstallReason::Dependency {
+                of: "ui-kit".into(),
+            },
+        );
+
+        let json = serde_json::to_string(&db).unwrap();
+        assert!(json.contains("\"kind\":\"dependency\""));
+
+        let loaded: InstallDb = serde_json::from_str(&json).unwrap();
+        let record = &loaded.records[0];
+        assert_eq!(record.scope, FontScope::System);
+        assert!(record.explain().contains("dependency of 'ui-kit'"));
+    }
+}
@@ -0,0 +1,306 @@
+//! Integrity checks for installed font files (`fontlift verify --all`).
+//!
+//! A font file can change after it was installed: a disk error truncates
+//! it, a sync tool overwrites it with another version, or someone tampers
+//! with it. The registration still points at the path, so nothing notices
+//! until an application renders the wrong glyphs or refuses the font.
+//!
+//! [`verify_file`] re-reads a file and checks it two ways:
+//!
+//! - **Structure.** The file must still parse as a font, and every face
+//!   must have readable `head`, `maxp` and `name` tables.
+//! - **Baseline.** What was recorded about the file before, gathered into a
+//!   [`Baseline`]: the SHA-256 and size stored in the install database or a
+//!   manifest, and the PostScript names stored in the manifest or the
+//!   metadata cache. Any difference means the file changed since.
+//!
+//! A file with nothing recorded about it can only be checked for structure
+//! and is reported as [`IntegrityStatus::Unrecorded`].
+
+use crate::{digest, metadata, FontError, FontResult};
+use read_fonts::{tables::name::NameId, FileRef, TableProvider};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// What was recorded about a font file, and where.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// PostScript names of the faces the file held.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub postscript_names: BTreeSet<String>,
+    /// Where the expectations came from, e.g. `install database`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+}
+
+impl Baseline {
+    /// Record a digest and size from `source`. A digest already recorded is
+    /// kept: the first source added is the most trusted.
+    pub fn with_digest(mut self, sha256: String, size: Option<u64>, source: &str) -> Self {
+        if self.sha256.is_none() {
+            self.sha256 = Some(sha256);
+            self.size = size.or(self.size);
+            self.add_source(source);
+        }
+        self
+    }
+
+    /// Record PostScript names from `source`. Names from several sources
+    /// must all still be in the file.
+    pub fn with_postscript_names(
+        mut self,
+        names: impl IntoIterator<Item = String>,
+        source: &str,
+    ) -> Self {
+        let before = self.postscript_names.len();
+        self.postscript_names
+            .extend(names.into_iter().filter(|name| !name.is_empty()));
+        if self.postscript_names.len() > before {
+            self.add_source(source);
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sha256.is_none() && self.size.is_none() && self.postscript_names.is_empty()
+    }
+
+    fn add_source(&mut self, source: &str) {
+        if !self.sources.iter().any(|s| s == source) {
+            self.sources.push(source.to_string());
+        }
+    }
+}
+
+/// Outcome of checking one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityStatus {
+    /// Well-formed and matching everything recorded about it.
+    Verified,
+    /// Well-formed, but nothing was recorded to compare it with.
+    Unrecorded,
+    /// Well-formed, but different from what was recorded.
+    Modified,
+    /// No longer parses as a font.
+    Corrupt,
+    /// The file is gone.
+    Missing,
+}
+
+impl IntegrityStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IntegrityStatus::Verified => "verified",
+            IntegrityStatus::Unrecorded => "unrecorded",
+            IntegrityStatus::Modified => "modified",
+            IntegrityStatus::Corrupt => "corrupt",
+            IntegrityStatus::Missing => "missing",
+        }
+    }
+
+    /// Does this need looking at?
+    pub fn is_problem(self) -> bool {
+        matches!(
+            self,
+            IntegrityStatus::Modified | IntegrityStatus::Corrupt | IntegrityStatus::Missing
+        )
+    }
+}
+
+/// The result of [`verify_file`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityCheck {
+    pub path: PathBuf,
+    pub status: IntegrityStatus,
+    /// What is wrong, one entry per finding; empty when nothing is.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub problems: Vec<String>,
+    /// The file's SHA-256, when it could be read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Where the compared expectations came from.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub baseline_sources: Vec<String>,
+}
+
+/// Every check of a `fontlift verify` run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub checks: Vec<IntegrityCheck>,
+}
+
+impl IntegrityReport {
+    /// Checks with [`IntegrityStatus::is_problem`] set.
+    pub fn problems(&self) -> Vec<&IntegrityCheck> {
+        self.checks
+            .iter()
+            .filter(|check| check.status.is_problem())
+            .collect()
+    }
+
+    /// How many checks ended in `status`.
+    pub fn count(&self, status: IntegrityStatus) -> usize {
+        self.checks
+            .iter()
+            .filter(|check| check.status == status)
+            .count()
+    }
+}
+
+/// Check the font file at `path` for structure and against `baseline`.
+pub fn verify_file(path: &Path, baseline: &Baseline) -> IntegrityCheck {
+    let mut check = IntegrityCheck {
+        path: path.to_path_buf(),
+        status: IntegrityStatus::Verified,
+        problems: Vec::new(),
+        sha256: None,
+        baseline_sources: baseline.sources.clone(),
+    };
+
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            check.status = IntegrityStatus::Missing;
+            check.problems.push("file not found".to_string());
+            return check;
+        }
+        Err(e) => {
+            check.status = IntegrityStatus::Corrupt;
+            check.problems.push(format!("cannot read: {e}"));
+            return check;
+        }
+    };
+    check.sha256 = Some(digest::sha256_hex(&data));
+
+    let names = match face_names(&data) {
+        Ok(names) => names,
+        Err(problem) => {
+            check.status = IntegrityStatus::Corrupt;
+            check.problems.push(problem);
+            return check;
+        }
+    };
+
+    if let Some(size) = baseline.size.filter(|&size| size != data.len() as u64) {
+        check
+            .problems
+            .push(format!("size is {} bytes, recorded {size}", data.len()));
+    }
+    if let (Some(expected), Some(actual)) = (&baseline.sha256, &check.sha256) {
+        if !digest::digests_match(expected, actual) {
+            check
+                .problems
+                .push(format!("SHA-256 is {actual}, recorded {expected}"));
+        }
+    }
+    for name in baseline.postscript_names.difference(&names) {
+        check
+            .problems
+            .push(format!("face {name} is no longer in the file"));
+    }
+
+    check.status = if !check.problems.is_empty() {
+        IntegrityStatus::Modified
+    } else if baseline.is_empty() {
+        IntegrityStatus::Unrecorded
+    } else {
+        IntegrityStatus::Verified
+    };
+    check
+}
+
+/// PostScript names of every face in `data`, or what makes it unreadable.
+fn face_names(data: &[u8]) -> Result<BTreeSet<String>, String> {
+    let file = FileRef::new(data).map_err(|e| format!("not a font file: {e}"))?;
+    let mut names = BTreeSet::new();
+    let mut faces = 0;
+    for (index, font) in file.fonts().enumerate() {
+        let font = font.map_err(|e| format!("face {index} cannot be read: {e}"))?;
+        font.head()
+            .map_err(|e| format!("face {index}: bad head table: {e}"))?;
+        font.maxp()
+            .map_err(|e| format!("face {index}: bad maxp table: {e}"))?;
+        font.name()
+            .map_err(|e| format!("face {index}: bad name table: {e}"))?;
+        names.extend(metadata::name_string(&font, NameId::POSTSCRIPT_NAME));
+        faces += 1;
+    }
+    if faces == 0 {
+        return Err("no faces in the file".to_string());
+    }
+    Ok(names)
+}
+
+/// [`verify_file`] for a file given on the command line, which must exist.
+pub fn verify_path(path: &Path, baseline: &Baseline) -> FontResult<IntegrityCheck> {
+    if !path.exists() {
+        return Err(FontError::FontNotFound(path.to_path_buf()));
+    }
+    Ok(verify_file(path, baseline))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontlift_testfonts::TestFont;
+
+    #[test]
+    fn reports_modified_corrupt_and_missing_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = TestFont::new("Integrity Sans")
+            .write_to(tmp.path())
+            .unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let recorded = Baseline::default()
+            .with_digest(
+                digest::sha256_hex(&bytes),
+                Some(bytes.len() as u64),
+                "install database",
+            )
+            .with_postscript_names(["IntegritySans-Regular".to_string()], "metadata cache");
+
+        let check = verify_file(&path, &recorded);
+        assert_eq!(check.status, IntegrityStatus::Verified, "{check:?}");
+        assert_eq!(
+            check.baseline_sources,
+            vec!["install database", "metadata cache"]
+        );
+        assert_eq!(
+            verify_file(&path, &Baseline::default()).status,
+            IntegrityStatus::Unrecorded
+        );
+
+        // Same structure, different bytes: another font under the old name.
+        let other = TestFont::new("Other Sans").write_to(tmp.path()).unwrap();
+        std::fs::rename(&other, &path).unwrap();
+        let check = verify_file(&path, &recorded);
+        assert_eq!(check.status, IntegrityStatus::Modified);
+        assert!(check
+            .problems
+            .iter()
+            .any(|p| p.contains("IntegritySans-Regular")));
+        assert!(check.problems.iter().any(|p| p.starts_with("SHA-256")));
+
+        std::fs::write(&path, &bytes[..bytes.len() / 3]).unwrap();
+        assert_eq!(
+            verify_file(&path, &recorded).status,
+            IntegrityStatus::Corrupt
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            verify_file(&path, &recorded).status,
+            IntegrityStatus::Missing
+        );
+        assert!(matches!(
+            verify_path(&path, &recorded),
+            Err(FontError::FontNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,322 @@
+//! Paged listing of large inventories.
+//!
+//! A machine with 10k+ installed faces produces a multi-megabyte JSON
+//! array, which a GUI has to receive and parse in one go. [`InventoryPager`]
+//! hands the inventory out in pages instead:
+//!
+//! ```no_run
+//! # use fontlift_core::{inventory::{InventoryFilter, InventoryPager}, FontManager};
+//! # fn show(manager: &dyn FontManager) -> fontlift_core::FontResult<()> {
+//! let pager = InventoryPager::default();
+//! let filter = InventoryFilter::default();
+//! let mut page = pager.list(manager, 0, 500, &filter)?;
+//! loop {
+//!     // ... show page.faces ...
+//!     let Some(cursor) = page.next_cursor.take() else { break };
+//!     page = pager.resume(&cursor, 500, &filter)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The first call takes an [`InventorySnapshot`]: the inventory, deduped
+//! and sorted by family. Cursors name the snapshot they belong to, so later
+//! pages come from the same snapshot even if fonts are installed or removed
+//! in between; nothing shifts or repeats. The pager keeps the last few
+//! snapshots, and a cursor for one that has been dropped fails with
+//! [`FontError::StaleCursor`] instead of silently reading a newer one.
+
+use crate::{
+    digest, protection, sorting, FontError, FontManager, FontResult, FontliftFontFaceInfo,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Snapshots an [`InventoryPager`] keeps for open cursors by default.
+pub const DEFAULT_SNAPSHOTS_KEPT: usize = 4;
+
+/// Which faces a page listing includes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InventoryFilter {
+    /// Case-insensitive substring of the PostScript, full or family name,
+    /// or of the file path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+}
+
+impl InventoryFilter {
+    pub fn query(query: impl Into<String>) -> Self {
+        Self {
+            query: Some(query.into()),
+        }
+    }
+
+    pub fn matches(&self, face: &FontliftFontFaceInfo) -> bool {
+        let Some(query) = self.query.as_deref().map(str::trim) else {
+            return true;
+        };
+        if query.is_empty() {
+            return true;
+        }
+        let needle = query.to_lowercase();
+        [
+            face.postscript_name.as_str(),
+            face.full_name.as_str(),
+            face.family_name.as_str(),
+            &face.source.path.to_string_lossy(),
+        ]
+        .iter()
+        .any(|value| value.to_lowercase().contains(&needle))
+    }
+}
+
+/// The inventory at one moment, in listing order.
+#[derive(Debug, Clone)]
+pub struct InventorySnapshot {
+    id: String,
+    faces: Vec<FontliftFontFaceInfo>,
+}
+
+impl InventorySnapshot {
+    /// Dedupe and sort `faces` (family, weight, style, path). The id is a
+    /// hash of the result, so equal inventories get equal ids.
+    pub fn new(faces: Vec<FontliftFontFaceInfo>) -> Self {
+        let mut faces = protection::dedupe_fonts(faces);
+        faces.sort_by(sorting::compare_faces);
+        let listing: String = faces
+            .iter()
+            .map(|face| {
+                format!(
+                    "{}\t{}\t{}\n",
+                    face.postscript_name,
+                    face.source.path.display(),
+                    face.source.face_index.unwrap_or(0)
+                )
+            })
+            .collect();
+        let id = digest::sha256_hex(listing.as_bytes())[..16].to_string();
+        Self { id, faces }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn len(&self) -> usize {
+        self.faces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    /// Up to `limit` faces matching `filter`, skipping the first `offset`
+    /// matches. A `limit` of 0 means no limit.
+    pub fn page(&self, offset: usize, limit: usize, filter: &InventoryFilter) -> InventoryPage {
+        let matching: Vec<&FontliftFontFaceInfo> = self
+            .faces
+            .iter()
+            .filter(|face| filter.matches(face))
+            .collect();
+        let total = matching.len();
+        let end = if limit == 0 {
+            total
+        } else {
+            offset.saturating_add(limit).min(total)
+        };
+        let faces = matching
+            .get(offset.min(total)..end)
+            .unwrap_or_default()
+            .iter()
+            .map(|face| (*face).clone())
+            .collect();
+        InventoryPage {
+            snapshot: self.id.clone(),
+            offset,
+            total,
+            faces,
+            next_cursor: (end < total).then(|| encode_cursor(&self.id, end)),
+        }
+    }
+}
+
+/// One page of a listing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryPage {
+    /// Id of the snapshot the page was cut from.
+    pub snapshot: String,
+    /// Position of the first face among all matching faces.
+    pub offset: usize,
+    /// Matching faces in the whole snapshot.
+    pub total: usize,
+    pub faces: Vec<FontliftFontFaceInfo>,
+    /// Pass to [`InventoryPager::resume`] for the next page; `None` on the
+    /// last one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// `<snapshot>:<offset>`.
+fn encode_cursor(snapshot: &str, offset: usize) -> String {
+    format!("{snapshot}:{offset}")
+}
+
+/// The snapshot id and offset in a cursor.
+pub fn parse_cursor(cursor: &str) -> FontResult<(String, usize)> {
+    cursor
+        .rsplit_once(':')
+        .and_then(|(snapshot, offset)| Some((snapshot.to_string(), offset.parse().ok()?)))
+        .filter(|(snapshot, _)| !snapshot.is_empty())
+        .ok_or_else(|| FontError::StaleCursor(format!("'{cursor}' is not a listing cursor")))
+}
+
+/// Serves paged listings, keeping recent snapshots for their cursors.
+#[derive(Debug)]
+pub struct InventoryPager {
+    kept: usize,
+    snapshots: Mutex<VecDeque<Arc<InventorySnapshot>>>,
+}
+
+impl Default for InventoryPager {
+    fn default() -> Self {
+        Self::new(DEFAULT_SNAPSHOTS_KEPT)
+    }
+}
+
+impl InventoryPager {
+    /// A pager that keeps the `kept` most recent snapshots (at least one).
+    pub fn new(kept: usize) -> Self {
+        Self {
+            kept: kept.max(1),
+            snapshots: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Snapshot the installed fonts and return the page at `offset`.
+    pub fn list(
+        &self,
+        manager: &dyn FontManager,
+        offset: usize,
+        limit: usize,
+        filter: &InventoryFilter,
+    ) -> FontResult<InventoryPage> {
+        let snapshot = self.insert(InventorySnapshot::new(manager.list_installed_fonts()?));
+        Ok(snapshot.page(offset, limit, filter))
+    }
+
+    /// The page a cursor points at, from the snapshot it was issued for.
+    /// Use the same filter as for the first page.
+    pub fn resume(
+        &self,
+        cursor: &str,
+        limit: usize,
+        filter: &InventoryFilter,
+    ) -> FontResult<InventoryPage> {
+        let (id, offset) = parse_cursor(cursor)?;
+        let snapshot = self.snapshot(&id).ok_or_else(|| {
+            FontError::StaleCursor(format!("inventory snapshot {id} is no longer available"))
+        })?;
+        Ok(snapshot.page(offset, limit, filter))
+    }
+
+    /// A kept snapshot by id.
+    pub fn snapshot(&self, id: &str) -> Option<Arc<InventorySnapshot>> {
+        let snapshots = self.snapshots.lock().ok()?;
+        snapshots.iter().find(|s| s.id == id).cloned()
+    }
+
+    /// Keep `snapshot`; an identical inventory reuses the kept one.
+    pub fn insert(&self, snapshot: InventorySnapshot) -> Arc<InventorySnapshot> {
+        let Ok(mut snapshots) = self.snapshots.lock() else {
+            return Arc::new(snapshot);
+        };
+        if let Some(pos) = snapshots.iter().position(|s| s.id == snapshot.id) {
+            let existing = snapshots.remove(pos).expect("position is in range");
+            snapshots.push_back(existing.clone());
+            return existing;
+        }
+        let snapshot = Arc::new(snapshot);
+        snapshots.push_back(snapshot.clone());
+        while snapshots.len() > self.kept {
+            snapshots.pop_front();
+        }
+        snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FontliftFontSource;
+    use std::path::PathBuf;
+
+    fn face(family: &str, style: &str) -> FontliftFontFaceInfo {
+        let ps = format!("{family}-{style}");
+        FontliftFontFaceInfo::new(
+            FontliftFontSource::new(PathBuf::from(format!("/fonts/{ps}.otf"))),
+            ps,
+            format!("{family} {style}"),
+            family.to_string(),
+            style.to_string(),
+        )
+    }
+
+    fn names(page: &InventoryPage) -> Vec<&str> {
+        page.faces
+            .iter()
+            .map(|f| f.postscript_name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn pages_filter_and_end_with_no_cursor() {
+        let snapshot = InventorySnapshot::new(vec![
+            face("Inter", "Regular"),
+            face("Arial", "Regular"),
+            face("Inter", "Bold"),
+            face("Arial", "Regular"),
+        ]);
+        assert_eq!(snapshot.len(), 3);
+
+        let first = snapshot.page(0, 2, &InventoryFilter::default());
+        assert_eq!(names(&first), ["Arial-Regular", "Inter-Bold"]);
+        assert_eq!(first.total, 3);
+        let (id, offset) = parse_cursor(first.next_cursor.as_deref().unwrap()).unwrap();
+        assert_eq!((id.as_str(), offset), (snapshot.id(), 2));
+
+        let inter = snapshot.page(1, 0, &InventoryFilter::query("inter"));
+        assert_eq!(names(&inter), ["Inter-Regular"]);
+        assert_eq!(inter.total, 2);
+        assert!(inter.next_cursor.is_none());
+        assert!(snapshot
+            .page(9, 2, &InventoryFilter::default())
+            .faces
+            .is_empty());
+    }
+
+    #[test]
+    fn cursors_stay_on_their_snapshot() {
+        let pager = InventoryPager::new(1);
+        let all = InventoryFilter::default();
+        let before = pager.insert(InventorySnapshot::new(vec![
+            face("A", "Regular"),
+            face("B", "Regular"),
+        ]));
+        let cursor = before.page(0, 1, &all).next_cursor.unwrap();
+
+        // Same inventory, same snapshot: the cursor keeps working.
+        pager.insert(InventorySnapshot::new(vec![
+            face("B", "Regular"),
+            face("A", "Regular"),
+        ]));
+        let next = pager.resume(&cursor, 1, &all).unwrap();
+        assert_eq!(names(&next), ["B-Regular"]);
+
+        // A changed inventory evicts it (kept = 1).
+        pager.insert(InventorySnapshot::new(vec![face("0", "Regular")]));
+        let err = pager.resume(&cursor, 1, &all).unwrap_err();
+        assert!(matches!(err, FontError::StaleCursor(_)));
+        assert!(parse_cursor("nonsense").is_err());
+    }
+}
@@ -5,9 +5,10 @@
 //! halfway through, the journal preserves what was planned and how far the work
 //! got.
 //!
-//! `fontlift doctor` reads incomplete entries and asks recovery code to resume
-//! the remaining steps. The current built-in flow mostly rolls work forward or
-//! skips steps that already happened.
+//! `fontlift doctor` reads incomplete entries and either finishes them
+//! ([`recover_with`]: resume the remaining steps, skipping those that already
+//! happened) or undoes them ([`roll_back_with`]: reverse the steps that ran,
+//! newest first).
 //!
 //! ## How it works
 //!
@@ -20,14 +21,53 @@
 //!    [`recover_incomplete_operations`] finds it and resumes the remaining
 //!    steps according to [`RecoveryPolicy`].
 //!
+//! ## Batches
+//!
+//! Platform managers journal each font on its own. A [`Transaction`] puts a
+//! whole batch, such as a directory of fonts, under one entry instead: each
+//! font's actions are appended as a [`TransactionItem`] before they run, so
+//! an interrupted batch is one operation for `fontlift doctor` to finish or
+//! undo, and a batch that fails part-way can [`Transaction::roll_back`] the
+//! fonts it already installed.
+//!
+//! ## Changed files
+//!
+//! Recovery may run long after the crash, and the files involved may have
+//! changed in between: the user replaced the source font, or reinstalled a
+//! newer version over the one a removal was about to delete. When an entry
+//! is recorded, the size and SHA-256 of every file it will copy from or
+//! delete are stored with it ([`FileFingerprint`]). Before acting on such a
+//! file, recovery checks it still matches; if not, the step gets
+//! [`RecoveryPolicy::NeedsConfirmation`] instead of being replayed blindly.
+//!
 //! ## Running recovery
 //!
 //! ```text
 //! fontlift doctor
 //! ```
 //!
-//! That command calls [`recover_incomplete_operations`] and reports what it
-//! found and what recovery succeeded.
+//! That command rolls operations forward with [`recover_with`], or back with
+//! [`roll_back_with`] under `--strategy back`, and reports what it found and
+//! what recovery succeeded. Other tools can run recovery their own
+//! way by implementing [`RecoveryExecutor`] and calling [`recover_with`];
+//! [`determine_recovery_policy`] is the policy fontlift suggests for each
+//! step. `core/examples/custom_recovery.rs` shows a complete executor.
+//!
+//! ## Environment
+//!
+//! Each entry also keeps an [`OperationEnvironment`]: fontlift and OS
+//! versions, the scopes involved, whether the process was elevated, and a
+//! hash of the `FONTLIFT_*` configuration, so a later investigation can tell
+//! how an operation was run.
+//!
+//! ## Cache clears scheduled for the next restart
+//!
+//! Some cache files stay locked while sessions are running, so `fontlift
+//! cleanup --at-reboot` asks the OS to delete them during the next restart.
+//! Each request is kept as a [`ScheduledCacheClear`] next to the entries,
+//! with a fingerprint of every file involved. Once the machine has restarted,
+//! [`take_finished_cache_clears`] checks whether those files are gone (or
+//! were rebuilt) and removes the record, so the outcome is reported once.
 //!
 //! ## Journal file location
 //!
@@ -39,33 +79,159 @@
 //!
 //! Override with `FONTLIFT_JOURNAL_PATH`, which is especially handy in tests.
 //!
+//! ## Retention
+//!
+//! Completed entries are only kept for reference, so every save drops the
+//! ones a [`RetentionPolicy`] no longer covers: by default anything older
+//! than 30 days and all but the newest 500. Incomplete entries are never
+//! dropped. `fontlift doctor --compact` and `fontlift maintain` run
+//! [`compact`] on demand. A journal file larger than [`MAX_JOURNAL_BYTES`]
+//! is not parsed by read-only loads, so a runaway file cannot slow down
+//! every operation that looks at it. The next [`update_journal`] reads it in
+//! full once and saves it compacted, incomplete entries included.
+//!
 //! ## Atomic writes
 //!
 //! The journal is always written to a `.tmp` file first, then renamed into
 //! place. Within one filesystem, that rename is atomic, so readers see either
 //! the old journal or the new one, never a half-written mix.
-
-use crate::{FontError, FontResult, FontScope};
+//!
+//! ## Concurrent processes
+//!
+//! Atomic writes alone do not stop two processes from each loading the
+//! journal, adding an entry, and saving — the second save silently drops the
+//! first entry. Every read-modify-write therefore goes through
+//! [`update_journal`], which holds an exclusive advisory lock
+//! (`journal.lock`, via `flock`/`LockFileEx`) across the whole cycle.
+//! A journal that cannot be parsed is moved aside as
+//! `journal.json.corrupt-<uuid>` rather than overwritten, so a damaged file
+//! never blocks installs and is still there to inspect.
+//!
+//! ## Stability
+//!
+//! This module is public API and follows semantic versioning, so recovery
+//! and reporting tools can build on it. Within a major version:
+//!
+//! - These items — [`Journal`],
+//!   [`JournalEntry`], [`JournalAction`], [`FileFingerprint`],
+//!   [`RecoveryPolicy`], [`ActionRecoveryResult`], [`RecoveryExecutor`],
+//!   [`journal_path`], [`load_journal`], [`load_journal_from`],
+//!   [`save_journal`], [`update_journal`], [`with_journal_lock`],
+//!   [`recover_with`], [`roll_back_with`] and
+//!   [`recover_incomplete_operations`] — keep their
+//!   names, signatures and meaning.
+//! - Types may gain fields and enums may gain variants; they are
+//!   `#[non_exhaustive]`, so code outside fontlift cannot depend on the
+//!   current set. Match with a `_` arm and build values through their
+//!   constructors.
+//!
+//! The file itself carries a [`Journal::format_version`]. Additions that
+//! older readers can skip (a new optional field) keep the version; anything
+//! an older fontlift would misread, such as a new [`JournalAction`], raises
+//! [`JOURNAL_FORMAT_VERSION`]. [`load_journal_from`] refuses a journal from a
+//! newer format with [`FontError::UnsupportedOperation`] instead of guessing,
+//! and never moves such a file aside as corrupt. Files written before the
+//! version existed read as format 1.
+
+use crate::{
+    digest, environment::OperationEnvironment, prune::PruneCheckpoint, FontError, FontResult,
+    FontScope,
+};
 use fs2::FileExt;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
-use std::time::SystemTime;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
+/// The journal file format this build writes and the newest it reads.
+pub const JOURNAL_FORMAT_VERSION: u32 = 1;
+
+/// Journal files larger than this are only parsed by [`update_journal`],
+/// which compacts them.
+pub const MAX_JOURNAL_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Which completed journal entries are kept.
+///
+/// `FONTLIFT_JOURNAL_MAX_ENTRIES` and `FONTLIFT_JOURNAL_MAX_DAYS` override
+/// the defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Completed entries kept at most, newest first.
+    pub max_entries: usize,
+    /// Completed entries older than this are dropped.
+    pub max_age: Duration,
+}
+
+impl RetentionPolicy {
+    pub const DEFAULT_MAX_ENTRIES: usize = 500;
+    pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+    /// The default policy, with the environment's overrides.
+    pub fn from_env() -> Self {
+        let mut policy = Self::default();
+        if let Some(entries) = std::env::var("FONTLIFT_JOURNAL_MAX_ENTRIES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            policy.max_entries = entries;
+        }
+        if let Some(days) = std::env::var("FONTLIFT_JOURNAL_MAX_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+        {
+            policy.max_age = Duration::from_secs(days * 24 * 60 * 60);
+        }
+        policy
+    }
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_entries: Self::DEFAULT_MAX_ENTRIES,
+            max_age: Self::DEFAULT_MAX_AGE,
+        }
+    }
+}
+
 /// One recoverable step recorded in the journal.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum JournalAction {
-    CopyFile { from: PathBuf, to: PathBuf },
-    RegisterFont { path: PathBuf, scope: FontScope },
-    UnregisterFont { path: PathBuf, scope: FontScope },
-    DeleteFile { path: PathBuf },
-    ClearCache { scope: FontScope },
+    /// Move the file at `path` to `backup` before something overwrites it;
+    /// rolling back moves it back. See [`backup_path`].
+    BackupFile {
+        path: PathBuf,
+        backup: PathBuf,
+    },
+    CopyFile {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    RegisterFont {
+        path: PathBuf,
+        scope: FontScope,
+    },
+    UnregisterFont {
+        path: PathBuf,
+        scope: FontScope,
+    },
+    DeleteFile {
+        path: PathBuf,
+    },
+    ClearCache {
+        scope: FontScope,
+    },
 }
 
 impl JournalAction {
     pub fn description(&self) -> String {
         match self {
+            JournalAction::BackupFile { path, backup } => {
+                format!("Move {} aside to {}", path.display(), backup.display())
+            }
             JournalAction::CopyFile { from, to } => {
                 format!("Copy {} to {}", from.display(), to.display())
             }
@@ -85,8 +251,47 @@ impl JournalAction {
     }
 }
 
+/// Where [`JournalAction::BackupFile`] moves `path`: beside it, under a name
+/// with no font extension so font scans pass over it.
+pub fn backup_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(
+        "{name}.fontlift-backup-{}",
+        Uuid::new_v4().simple()
+    ))
+}
+
+/// Size and content hash of a file when its operation was recorded.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FileFingerprint {
+    pub size: u64,
+    pub sha256: String,
+}
+
+impl FileFingerprint {
+    /// Fingerprint of the file at `path`, if it can be read.
+    pub fn of(path: &Path) -> Option<Self> {
+        let size = fs::metadata(path).ok().filter(|m| m.is_file())?.len();
+        let sha256 = digest::sha256_file(path).ok()?;
+        Some(Self { size, sha256 })
+    }
+
+    /// Does the file at `path` still hold the same bytes?
+    ///
+    /// The size is compared first so most changes are caught without hashing.
+    pub fn matches(&self, path: &Path) -> bool {
+        fs::metadata(path).is_ok_and(|m| m.len() == self.size)
+            && digest::sha256_file(path).is_ok_and(|h| digest::digests_match(&h, &self.sha256))
+    }
+}
+
 /// Recorded state for one multi-step operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct JournalEntry {
     pub id: Uuid,
     #[serde(with = "systemtime_serde")]
@@ -99,10 +304,42 @@ pub struct JournalEntry {
     /// has finished.
     pub current_step: usize,
     pub description: Option<String>,
+    /// Files the actions copy from or delete, as they were when recorded.
+    ///
+    /// Entries written before fingerprints existed have none; their actions
+    /// are recovered without the check.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fingerprints: BTreeMap<PathBuf, FileFingerprint>,
+    /// fontlift version, OS, scopes and configuration at record time.
+    ///
+    /// `None` for entries written before this was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<OperationEnvironment>,
+    /// The fonts (or other items) a [`Transaction`] grouped its actions
+    /// into, in order. Empty for single-font entries.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<TransactionItem>,
+}
+
+/// One item of a [`Transaction`]: a run of consecutive actions in its
+/// entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TransactionItem {
+    /// What the actions are for, usually the font's path.
+    pub label: String,
+    /// Index of the item's first action in [`JournalEntry::actions`].
+    pub first_action: usize,
+    /// How many actions the item has.
+    pub action_count: usize,
 }
 
 impl JournalEntry {
+    /// Create an entry, fingerprinting the files its actions depend on.
     pub fn new(actions: Vec<JournalAction>, description: Option<String>) -> Self {
+        let fingerprints = fingerprint_inputs(&actions);
+        let environment = Some(OperationEnvironment::for_actions(&actions));
+
         Self {
             id: Uuid::new_v4(),
             started_at: SystemTime::now(),
@@ -110,6 +347,41 @@ impl JournalEntry {
             actions,
             current_step: 0,
             description,
+            fingerprints,
+            environment,
+            items: Vec::new(),
+        }
+    }
+
+    /// Append `actions` as one item labelled `label`, fingerprinting the
+    /// files they depend on.
+    pub fn push_item(&mut self, label: String, actions: Vec<JournalAction>) {
+        self.fingerprints.extend(fingerprint_inputs(&actions));
+        self.items.push(TransactionItem {
+            label,
+            first_action: self.actions.len(),
+            action_count: actions.len(),
+        });
+        self.actions.extend(actions);
+        self.environment = Some(OperationEnvironment::for_actions(&self.actions));
+    }
+
+    /// How many of the entry's items have finished every action.
+    pub fn finished_items(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.first_action + item.action_count <= self.current_step)
+            .count()
+    }
+
+    /// Has `path` changed since this entry was recorded?
+    ///
+    /// Files without a fingerprint, or that no longer exist, count as
+    /// unchanged: recovery already handles missing files on its own.
+    pub fn file_changed(&self, path: &Path) -> bool {
+        match self.fingerprints.get(path) {
+            Some(fingerprint) => path.exists() && !fingerprint.matches(path),
+            None => false,
         }
     }
 
@@ -130,8 +402,21 @@ impl JournalEntry {
     }
 }
 
+/// Fingerprints of the files `actions` copy from or delete.
+fn fingerprint_inputs(actions: &[JournalAction]) -> BTreeMap<PathBuf, FileFingerprint> {
+    actions
+        .iter()
+        .filter_map(|action| match action {
+            JournalAction::CopyFile { from, .. } => Some(from),
+            JournalAction::DeleteFile { path } => Some(path),
+            _ => None,
+        })
+        .filter_map(|path| Some((path.clone(), FileFingerprint::of(path)?)))
+        .collect()
+}
+
 /// Serde helpers for `SystemTime`.
-mod systemtime_serde {
+pub(crate) mod systemtime_serde {
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -152,15 +437,125 @@ mod systemtime_serde {
     }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+/// A cache file scheduled for deletion, as it was when scheduled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScheduledFile {
+    pub path: PathBuf,
+    /// `None` when the file could not be read, e.g. because it was locked.
+    #[serde(default)]
+    pub fingerprint: Option<FileFingerprint>,
+}
+
+impl ScheduledFile {
+    /// Is the file still there with the contents it had when scheduled?
+    ///
+    /// A file that was deleted and rebuilt since counts as cleared.
+    fn still_present(&self) -> bool {
+        self.path.exists()
+            && self
+                .fingerprint
+                .as_ref()
+                .map_or(true, |fingerprint| fingerprint.matches(&self.path))
+    }
+}
+
+/// Cache files the OS was asked to delete at the next restart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledCacheClear {
+    pub id: Uuid,
+    #[serde(with = "systemtime_serde")]
+    pub scheduled_at: SystemTime,
+    pub scope: FontScope,
+    pub files: Vec<ScheduledFile>,
+}
+
+/// Outcome of a [`ScheduledCacheClear`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduledClearStatus {
+    /// The machine has not restarted since the clear was scheduled.
+    Pending,
+    /// Every scheduled file is gone or was rebuilt.
+    Completed,
+    /// The machine restarted but these files are unchanged.
+    Incomplete { remaining: Vec<PathBuf> },
+}
+
+impl ScheduledCacheClear {
+    /// Record `paths`, fingerprinting each one now.
+    pub fn new(scope: FontScope, paths: &[PathBuf]) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            scheduled_at: SystemTime::now(),
+            scope,
+            files: paths
+                .iter()
+                .map(|path| ScheduledFile {
+                    path: path.clone(),
+                    fingerprint: FileFingerprint::of(path),
+                })
+                .collect(),
+        }
+    }
+
+    /// Status given the time the machine last started, if known.
+    ///
+    /// Without a boot time there is no telling whether the restart happened,
+    /// so the clear stays pending.
+    pub fn status(&self, last_boot: Option<SystemTime>) -> ScheduledClearStatus {
+        match last_boot {
+            Some(boot) if boot > self.scheduled_at => {}
+            _ => return ScheduledClearStatus::Pending,
+        }
+
+        let remaining: Vec<PathBuf> = self
+            .files
+            .iter()
+            .filter(|file| file.still_present())
+            .map(|file| file.path.clone())
+            .collect();
+        if remaining.is_empty() {
+            ScheduledClearStatus::Completed
+        } else {
+            ScheduledClearStatus::Incomplete { remaining }
+        }
+    }
+}
+
+/// Everything fontlift keeps in `journal.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Journal {
+    /// Format of the file this was read from; see the module's stability
+    /// notes. Always [`JOURNAL_FORMAT_VERSION`] once loaded or created.
+    #[serde(default = "unversioned_format")]
+    pub format_version: u32,
     pub entries: Vec<JournalEntry>,
+    /// Cache clears waiting for the next restart.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scheduled_cache_clears: Vec<ScheduledCacheClear>,
+    /// Prunes that stopped before removing everything they found.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prune_checkpoints: Vec<PruneCheckpoint>,
+}
+
+/// Journals written before the format was versioned are format 1.
+fn unversioned_format() -> u32 {
+    1
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Journal {
     pub fn new() -> Self {
         Self {
+            format_version: JOURNAL_FORMAT_VERSION,
             entries: Vec::new(),
+            scheduled_cache_clears: Vec::new(),
+            prune_checkpoints: Vec::new(),
         }
     }
 
@@ -186,7 +581,7 @@ impl Journal {
     pub fn mark_step(&mut self, id: Uuid, step: usize) -> FontResult<()> {
         let entry = self
             .find_entry_mut(id)
-            .ok_or_else(|| FontError::InvalidFormat(format!("Journal entry not found: {id}")))?;
+            .ok_or_else(|| FontError::InvalidArgument(format!("Journal entry not found: {id}")))?;
         entry.current_step = step;
         Ok(())
     }
@@ -194,7 +589,7 @@ impl Journal {
     pub fn mark_completed(&mut self, id: Uuid) -> FontResult<()> {
         let entry = self
             .find_entry_mut(id)
-            .ok_or_else(|| FontError::InvalidFormat(format!("Journal entry not found: {id}")))?;
+            .ok_or_else(|| FontError::InvalidArgument(format!("Journal entry not found: {id}")))?;
         entry.completed = true;
         Ok(())
     }
@@ -203,6 +598,47 @@ impl Journal {
         self.entries.iter().filter(|e| e.is_incomplete()).collect()
     }
 
+    /// Drop the completed entries `policy` does not keep, returning how
+    /// many were dropped. Incomplete entries always stay.
+    pub fn compact(&mut self, policy: &RetentionPolicy) -> usize {
+        let before = self.entries.len();
+        self.cleanup_old_entries(policy.max_age.as_secs());
+
+        // Entries are appended as operations start, so the newest are last.
+        let mut room = policy.max_entries;
+        let mut keep: Vec<bool> = self
+            .entries
+            .iter()
+            .rev()
+            .map(|e| {
+                if !e.completed {
+                    true
+                } else if room > 0 {
+                    room -= 1;
+                    true
+                } else {
+                    false
+                }
+            })
+            .collect();
+        keep.reverse();
+        let mut keep = keep.into_iter();
+        self.entries.retain(|_| keep.next().unwrap_or(true));
+
+        before - self.entries.len()
+    }
+
+    /// Would [`compact`](Self::compact) drop anything?
+    pub fn needs_compaction(&self, policy: &RetentionPolicy) -> bool {
+        let now = SystemTime::now();
+        let mut completed = self.entries.iter().filter(|e| e.completed);
+        completed.clone().count() > policy.max_entries
+            || completed.any(|e| {
+                now.duration_since(e.started_at)
+                    .is_ok_and(|age| age.as_secs() >= policy.max_age.as_secs())
+            })
+    }
+
     pub fn cleanup_old_entries(&mut self, max_age_secs: u64) {
         let now = SystemTime::now();
         self.entries.retain(|e| {
@@ -219,19 +655,13 @@ impl Journal {
 
 /// Return the journal path for the current platform.
 ///
-/// `FONTLIFT_JOURNAL_PATH` overrides the normal location. Test code can also
-/// redirect the journal via `FONTLIFT_FAKE_REGISTRY_ROOT`.
+/// `FONTLIFT_JOURNAL_PATH` overrides the normal location.
 pub fn journal_path() -> PathBuf {
     // Check for override (useful for testing)
     if let Ok(override_path) = std::env::var("FONTLIFT_JOURNAL_PATH") {
         return PathBuf::from(override_path);
     }
 
-    // Check for fake registry root (testing mode)
-    if let Ok(root) = std::env::var("FONTLIFT_FAKE_REGISTRY_ROOT") {
-        return PathBuf::from(root).join("journal.json");
-    }
-
     #[cfg(target_os = "macos")]
     {
         dirs::data_dir()
@@ -261,24 +691,72 @@ pub fn journal_path() -> PathBuf {
 ///
 /// Missing files are treated as an empty journal.
 pub fn load_journal() -> FontResult<Journal> {
-    let path = journal_path();
-    if !path.exists() {
-        return Ok(Journal::new());
+    load_journal_from(&journal_path())
+}
+
+/// [`load_journal`] from an explicit path.
+///
+/// A journal in a newer format than [`JOURNAL_FORMAT_VERSION`] or larger
+/// than [`MAX_JOURNAL_BYTES`] is an [`FontError::UnsupportedOperation`]; one
+/// that does not parse is an [`FontError::InvalidFormat`].
+pub fn load_journal_from(path: &Path) -> FontResult<Journal> {
+    let size = match fs::metadata(path) {
+        Ok(metadata) => metadata.len(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Journal::new()),
+        Err(e) => {
+            return Err(FontError::IoError(std::io::Error::new(
+                e.kind(),
+                format!("Failed to read journal: {e}"),
+            )))
+        }
+    };
+    if size > MAX_JOURNAL_BYTES {
+        return Err(FontError::UnsupportedOperation(format!(
+            "journal {} is {} MB, over the {} MB limit; run `fontlift doctor --compact` to shrink it",
+            path.display(),
+            size / (1024 * 1024),
+            MAX_JOURNAL_BYTES / (1024 * 1024)
+        )));
     }
+    read_journal(path)
+}
 
-    let content = fs::read_to_string(&path).map_err(|e| {
+/// Parse the journal at `path` whatever its size, streaming the file rather
+/// than reading it into one string first.
+fn read_journal(path: &Path) -> FontResult<Journal> {
+    let file = fs::File::open(path).map_err(|e| {
         FontError::IoError(std::io::Error::new(
             e.kind(),
             format!("Failed to read journal: {e}"),
         ))
     })?;
 
-    serde_json::from_str(&content)
-        .map_err(|e| FontError::InvalidFormat(format!("Failed to parse journal: {e}")))
+    let value: serde_json::Value = serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| FontError::InvalidFormat(format!("Failed to parse journal: {e}")))?;
+    // Checked before the full parse: a newer journal may hold actions this
+    // build cannot read, and must not look corrupt.
+    let version = value
+        .get("format_version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(1);
+    if version > u64::from(JOURNAL_FORMAT_VERSION) {
+        return Err(FontError::UnsupportedOperation(format!(
+            "journal {} is format {version}, written by a newer fontlift; this version reads up to format {JOURNAL_FORMAT_VERSION}",
+            path.display()
+        )));
+    }
+
+    let mut journal: Journal = serde_json::from_value(value)
+        .map_err(|e| FontError::InvalidFormat(format!("Failed to parse journal: {e}")))?;
+    journal.format_version = JOURNAL_FORMAT_VERSION;
+    Ok(journal)
 }
 
 /// Save the journal with a temp-file-then-rename write.
 ///
+/// Completed entries outside [`RetentionPolicy::from_env`] are left out of
+/// the file; see [`Journal::compact`].
+///
 /// The temp file name is unique per call (`journal.json.tmp.<pid>.<uuid>`) so
 /// concurrent processes never clobber each other's staging file.  On rename
 /// failure the temp file is removed before the error is returned so no
@@ -286,6 +764,17 @@ pub fn load_journal() -> FontResult<Journal> {
 pub fn save_journal(journal: &Journal) -> FontResult<()> {
     let path = journal_path();
 
+    let policy = RetentionPolicy::from_env();
+    let compacted;
+    let journal = if journal.needs_compaction(&policy) {
+        let mut copy = journal.clone();
+        copy.compact(&policy);
+        compacted = copy;
+        &compacted
+    } else {
+        journal
+    };
+
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(FontError::IoError)?;
@@ -367,96 +856,453 @@ pub fn with_journal_lock<R>(f: impl FnOnce() -> FontResult<R>) -> FontResult<R>
     result
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Load the journal, moving an unparseable file aside instead of failing.
+///
+/// Only call this while holding the journal lock.
+fn load_journal_or_quarantine() -> FontResult<Journal> {
+    load_journal_or_quarantine_at(&journal_path())
+}
+
+/// [`load_journal_or_quarantine`] for the journal at `path`.
+///
+/// An oversized journal is read in full rather than refused: the caller
+/// saves it, which compacts it back under the limit without dropping the
+/// incomplete entries recovery needs.
+fn load_journal_or_quarantine_at(path: &Path) -> FontResult<Journal> {
+    let oversized = fs::metadata(path).is_ok_and(|m| m.len() > MAX_JOURNAL_BYTES);
+    let loaded = if oversized {
+        log::warn!(
+            "Journal at {} is over {} MB; compacting it",
+            path.display(),
+            MAX_JOURNAL_BYTES / (1024 * 1024)
+        );
+        read_journal(path)
+    } else {
+        load_journal_from(path)
+    };
+    match loaded {
+        Err(FontError::InvalidFormat(reason)) => {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "journal.json".to_string());
+            let aside = path.with_file_name(format!("{name}.corrupt-{}", Uuid::new_v4()));
+            fs::rename(path, &aside).map_err(FontError::IoError)?;
+            log::warn!(
+                "Journal at {} could not be parsed ({reason}); moved it to {} and started a new one",
+                path.display(),
+                aside.display()
+            );
+            Ok(Journal::new())
+        }
+        other => other,
+    }
+}
+
+/// Load, mutate and save the journal as one locked step.
+///
+/// This is the only safe way to change the journal while other fontlift
+/// processes may be running. `f`'s error is returned without saving.
+pub fn update_journal<R>(f: impl FnOnce(&mut Journal) -> FontResult<R>) -> FontResult<R> {
+    with_journal_lock(|| {
+        let mut journal = load_journal_or_quarantine()?;
+        let result = f(&mut journal)?;
+        save_journal(&journal)?;
+        Ok(result)
+    })
+}
+
+/// Drop the completed entries `policy` does not keep from the journal on
+/// disk, returning how many were dropped.
+pub fn compact(policy: &RetentionPolicy) -> FontResult<usize> {
+    update_journal(|journal| Ok(journal.compact(policy)))
+}
+
+/// Work on many fonts journaled as one entry.
+///
+/// Each font's actions are added as a [`TransactionItem`] just before they
+/// run, and every finished action is recorded, so an interrupted batch
+/// shows up in `fontlift doctor` as one operation that can be finished or
+/// undone as a whole. [`Transaction::roll_back`] undoes what already ran
+/// when the batch fails part-way; [`Transaction::commit`] completes it.
+///
+/// Dropping a transaction without either leaves its entry incomplete,
+/// exactly as a crash would.
+#[derive(Debug)]
+pub struct Transaction {
+    id: Uuid,
+}
+
+impl Transaction {
+    /// Record an empty entry for a batch described by `description`.
+    pub fn begin(description: impl Into<String>) -> FontResult<Self> {
+        let description = description.into();
+        let id =
+            update_journal(|journal| Ok(journal.record_operation(Vec::new(), Some(description))))?;
+        Ok(Self { id })
+    }
+
+    /// The journal entry behind this transaction.
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Add `actions` for one item, labelled `label`; they are the next to
+    /// run, in order, after the actions already added.
+    pub fn add(&mut self, label: impl Into<String>, actions: Vec<JournalAction>) -> FontResult<()> {
+        let label = label.into();
+        self.update(|entry| {
+            entry.push_item(label, actions);
+            Ok(())
+        })
+    }
+
+    /// Record that the next action finished.
+    pub fn step_done(&mut self) -> FontResult<()> {
+        self.update(|entry| {
+            if entry.current_step < entry.actions.len() {
+                entry.current_step += 1;
+            }
+            Ok(())
+        })
+    }
+
+    /// Mark the batch finished. Actions that were added but not recorded
+    /// as done are taken as not needed.
+    pub fn commit(self) -> FontResult<()> {
+        update_journal(|journal| journal.mark_completed(self.id))
+    }
+
+    /// Undo the actions that ran, newest first, as [`roll_back_with`] does
+    /// for an interrupted entry, and return what happened to each.
+    ///
+    /// If an action cannot be undone the entry stays incomplete, so
+    /// `fontlift doctor` can retry later.
+    pub fn roll_back<E>(self, executor: &E) -> FontResult<Vec<ActionRecoveryResult>>
+    where
+        E: RecoveryExecutor + ?Sized,
+    {
+        let entry = with_journal_lock(|| {
+            load_journal()?
+                .find_entry(self.id)
+                .cloned()
+                .ok_or_else(|| journal_entry_not_found(self.id))
+        })?;
+        let mut results = Vec::new();
+        roll_back_entry(&entry, executor, &mut results)?;
+        Ok(results)
+    }
+
+    fn update(&self, f: impl FnOnce(&mut JournalEntry) -> FontResult<()>) -> FontResult<()> {
+        update_journal(|journal| {
+            let entry = journal
+                .find_entry_mut(self.id)
+                .ok_or_else(|| journal_entry_not_found(self.id))?;
+            f(entry)
+        })
+    }
+}
+
+fn journal_entry_not_found(id: Uuid) -> FontError {
+    FontError::InvalidArgument(format!("Journal entry not found: {id}"))
+}
+
+/// Record that `paths` were scheduled for deletion at the next restart.
+pub fn record_scheduled_cache_clear(scope: FontScope, paths: &[PathBuf]) -> FontResult<Uuid> {
+    let clear = ScheduledCacheClear::new(scope, paths);
+    let id = clear.id;
+    update_journal(|journal| {
+        journal.scheduled_cache_clears.push(clear);
+        Ok(())
+    })?;
+    Ok(id)
+}
+
+/// Remove and return the scheduled cache clears whose restart has happened,
+/// with their outcome. Clears still waiting for a restart stay recorded.
+pub fn take_finished_cache_clears() -> FontResult<Vec<(ScheduledCacheClear, ScheduledClearStatus)>>
+{
+    // Checked without the lock first: almost every run has nothing scheduled.
+    if load_journal()?.scheduled_cache_clears.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let last_boot = last_boot_time();
+    update_journal(|journal| {
+        let mut finished = Vec::new();
+        journal
+            .scheduled_cache_clears
+            .retain(|clear| match clear.status(last_boot) {
+                ScheduledClearStatus::Pending => true,
+                status => {
+                    finished.push((clear.clone(), status));
+                    false
+                }
+            });
+        Ok(finished)
+    })
+}
+
+/// When the machine last started.
+#[cfg(target_os = "linux")]
+pub fn last_boot_time() -> Option<SystemTime> {
+    let uptime = fs::read_to_string("/proc/uptime").ok()?;
+    let seconds: f64 = uptime.split_whitespace().next()?.parse().ok()?;
+    SystemTime::now().checked_sub(std::time::Duration::from_secs_f64(seconds))
+}
+
+/// When the machine last started.
+#[cfg(target_os = "macos")]
+pub fn last_boot_time() -> Option<SystemTime> {
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", "kern.boottime"])
+        .output()
+        .ok()?;
+    let seconds = parse_sysctl_boottime(&String::from_utf8_lossy(&output.stdout))?;
+    Some(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(seconds))
+}
+
+/// When the machine last started.
+#[cfg(windows)]
+pub fn last_boot_time() -> Option<SystemTime> {
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "[DateTimeOffset]::new((Get-CimInstance Win32_OperatingSystem).LastBootUpTime).ToUnixTimeSeconds()",
+        ])
+        .output()
+        .ok()?;
+    let seconds: u64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()?;
+    Some(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(seconds))
+}
+
+/// When the machine last started.
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn last_boot_time() -> Option<SystemTime> {
+    None
+}
+
+/// Seconds from `sysctl -n kern.boottime` output such as
+/// `{ sec = 1767225600, usec = 120000 } Thu Jan  1 00:00:00 2026`.
+#[cfg(any(target_os = "macos", test))]
+fn parse_sysctl_boottime(output: &str) -> Option<u64> {
+    let rest = &output[output.find("sec =")? + "sec =".len()..];
+    rest.trim_start()
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// What recovery should do with one remaining step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum RecoveryPolicy {
     RollForward,
     RollBack,
     Skip,
+    /// The file the action would copy or delete changed since the operation
+    /// was recorded. Replaying it needs the user's confirmation.
+    NeedsConfirmation,
 }
 
-#[derive(Debug)]
+/// What happened to one step during recovery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct ActionRecoveryResult {
+    /// The [`JournalEntry`] the step belongs to.
+    pub entry_id: Uuid,
     pub action: JournalAction,
     pub policy: RecoveryPolicy,
     pub success: bool,
     pub message: Option<String>,
 }
 
-/// Recover incomplete operations.
+/// Carries out the remaining steps of interrupted operations for
+/// [`recover_with`].
 ///
-/// For each incomplete entry, this walks the remaining actions from
-/// `current_step`, chooses a default [`RecoveryPolicy`] for each action, and
-/// calls `handler`. Successful actions advance the journal. The first failed
-/// action stops recovery for that entry. Updated journal state is saved before
-/// returning.
+/// Closures taking `(&JournalAction, RecoveryPolicy)` are executors too.
+pub trait RecoveryExecutor {
+    /// Carry out `action`, a remaining step of `entry`, as `policy`
+    /// suggests (see [`determine_recovery_policy`]).
+    ///
+    /// `Ok(true)` means the step is done and the entry moves past it;
+    /// `Ok(false)` stops the entry at this step, to be retried by the next
+    /// recovery. An error ends recovery; steps finished before it stay
+    /// recorded.
+    ///
+    /// The journal is not locked while this runs, so an executor may call
+    /// [`crate::FontManager`] methods, which journal their own work.
+    fn execute(
+        &self,
+        entry: &JournalEntry,
+        action: &JournalAction,
+        policy: RecoveryPolicy,
+    ) -> FontResult<bool>;
+}
+
+impl<F> RecoveryExecutor for F
+where
+    F: Fn(&JournalAction, RecoveryPolicy) -> FontResult<bool>,
+{
+    fn execute(
+        &self,
+        _entry: &JournalEntry,
+        action: &JournalAction,
+        policy: RecoveryPolicy,
+    ) -> FontResult<bool> {
+        self(action, policy)
+    }
+}
+
+/// Recover incomplete operations with a closure as the executor.
+///
+/// Same as [`recover_with`]; kept for callers that predate
+/// [`RecoveryExecutor`].
 pub fn recover_incomplete_operations<F>(handler: F) -> FontResult<Vec<ActionRecoveryResult>>
 where
     F: Fn(&JournalAction, RecoveryPolicy) -> FontResult<bool>,
 {
-    with_journal_lock(|| {
-        let mut journal = load_journal()?;
-        let mut results = Vec::new();
+    recover_with(&handler)
+}
 
-        let incomplete_ids: Vec<Uuid> = journal.incomplete_entries().iter().map(|e| e.id).collect();
-
-        for entry_id in incomplete_ids {
-            // Get entry details (we need to clone because we'll modify journal later)
-            let (remaining, current_step) = {
-                let entry = journal.find_entry(entry_id).unwrap();
-                (entry.remaining_actions().to_vec(), entry.current_step)
-            };
-
-            for (i, action) in remaining.iter().enumerate() {
-                let policy = determine_recovery_policy(action);
-                let success = handler(action, policy)?;
-
-                results.push(ActionRecoveryResult {
-                    action: action.clone(),
-                    policy,
-                    success,
-                    message: None,
-                });
-
-                if success {
-                    // Update step
-                    journal.mark_step(entry_id, current_step + i + 1)?;
-                } else {
-                    // Stop processing this entry on failure
-                    break;
-                }
+/// Recover incomplete operations.
+///
+/// For each incomplete entry, this walks the remaining actions from
+/// `current_step`, chooses a default [`RecoveryPolicy`] for each action, and
+/// hands both to `executor`. Each successful action advances the entry on
+/// disk before the next one runs, and the entry is completed after its last
+/// step. The first failed action stops recovery for that entry.
+pub fn recover_with<E>(executor: &E) -> FontResult<Vec<ActionRecoveryResult>>
+where
+    E: RecoveryExecutor + ?Sized,
+{
+    let mut results = Vec::new();
+    for entry in incomplete_snapshot()? {
+        for (i, action) in entry.remaining_actions().iter().enumerate() {
+            let policy = determine_recovery_policy(action, &entry);
+            let success = executor.execute(&entry, action, policy)?;
+
+            let message = (policy == RecoveryPolicy::NeedsConfirmation)
+                .then(|| "file changed since the operation was recorded".to_string());
+            results.push(ActionRecoveryResult {
+                entry_id: entry.id,
+                action: action.clone(),
+                policy,
+                success,
+                message,
+            });
+            if !success {
+                break;
             }
 
-            // Check if all actions completed
-            if let Some(entry) = journal.find_entry(entry_id) {
-                if entry.current_step >= entry.actions.len() {
-                    journal.mark_completed(entry_id)?;
+            let step = entry.current_step + i + 1;
+            update_journal(|journal| {
+                journal.mark_step(entry.id, step)?;
+                if step >= entry.actions.len() {
+                    journal.mark_completed(entry.id)?;
                 }
-            }
+                Ok(())
+            })?;
         }
+    }
+    Ok(results)
+}
 
-        // Save updated journal
-        save_journal(&journal)?;
+/// Undo incomplete operations instead of finishing them.
+///
+/// For each incomplete entry, this walks back from the interrupted step
+/// (which may have partly happened) to the first one, handing each action
+/// to `executor` with [`RecoveryPolicy::RollBack`]; cache clears get
+/// [`RecoveryPolicy::Skip`]. Once every step is undone the entry is
+/// completed. The first failed action leaves the entry as it was, so the
+/// next roll back starts again from the interrupted step: undoing a step
+/// that is already undone must succeed.
+pub fn roll_back_with<E>(executor: &E) -> FontResult<Vec<ActionRecoveryResult>>
+where
+    E: RecoveryExecutor + ?Sized,
+{
+    let mut results = Vec::new();
+    for entry in incomplete_snapshot()? {
+        roll_back_entry(&entry, executor, &mut results)?;
+    }
+    Ok(results)
+}
 
-        Ok(results)
+/// Undo `entry` for [`roll_back_with`] and [`Transaction::roll_back`].
+fn roll_back_entry<E>(
+    entry: &JournalEntry,
+    executor: &E,
+    results: &mut Vec<ActionRecoveryResult>,
+) -> FontResult<()>
+where
+    E: RecoveryExecutor + ?Sized,
+{
+    // The interrupted step may have partly happened; with nothing
+    // interrupted, every step ran.
+    let ran = (entry.current_step + 1).min(entry.actions.len());
+    for action in entry.actions[..ran].iter().rev() {
+        let policy = match action {
+            JournalAction::ClearCache { .. } => RecoveryPolicy::Skip,
+            _ => RecoveryPolicy::RollBack,
+        };
+        let success = executor.execute(entry, action, policy)?;
+        results.push(ActionRecoveryResult {
+            entry_id: entry.id,
+            action: action.clone(),
+            policy,
+            success,
+            message: None,
+        });
+        if !success {
+            return Ok(());
+        }
+    }
+    update_journal(|journal| journal.mark_completed(entry.id))
+}
+
+/// The incomplete entries as they are now, read under the journal lock.
+fn incomplete_snapshot() -> FontResult<Vec<JournalEntry>> {
+    with_journal_lock(|| {
+        let journal = load_journal()?;
+        Ok(journal.incomplete_entries().into_iter().cloned().collect())
     })
 }
 
-/// Choose the built-in recovery policy for one action.
+/// Choose the built-in recovery policy for one action of `entry`.
 ///
 /// The current strategy is conservative: continue missing file operations and
 /// registrations, skip cache clears, and skip steps that are already satisfied.
-fn determine_recovery_policy(action: &JournalAction) -> RecoveryPolicy {
+/// File operations whose input changed since `entry` was recorded need
+/// confirmation instead.
+pub fn determine_recovery_policy(action: &JournalAction, entry: &JournalEntry) -> RecoveryPolicy {
     match action {
+        JournalAction::BackupFile { path, backup } => {
+            if path.exists() && !backup.exists() {
+                RecoveryPolicy::RollForward
+            } else {
+                RecoveryPolicy::Skip // Already moved, or nothing to move
+            }
+        }
         // File operations: roll forward (complete if partially done)
-        JournalAction::CopyFile { to, .. } => {
+        JournalAction::CopyFile { from, to } => {
             if to.exists() {
                 RecoveryPolicy::Skip // Already done
+            } else if entry.file_changed(from) {
+                RecoveryPolicy::NeedsConfirmation
             } else {
                 RecoveryPolicy::RollForward
             }
         }
         JournalAction::DeleteFile { path } => {
-            if path.exists() {
+            if entry.file_changed(path) {
+                RecoveryPolicy::NeedsConfirmation
+            } else if path.exists() {
                 RecoveryPolicy::RollForward
             } else {
                 RecoveryPolicy::Skip // Already deleted
@@ -530,6 +1376,73 @@ mod tests {
         );
     }
 
+    const STRESS_CHILD_ENV: &str = "FONTLIFT_JOURNAL_STRESS_CHILD";
+
+    /// Child half of `concurrent_processes_preserve_all_entries`. Does nothing
+    /// unless that test spawned this binary with `STRESS_CHILD_ENV` set.
+    #[test]
+    fn journal_stress_child() {
+        let Ok(count) = std::env::var(STRESS_CHILD_ENV) else {
+            return;
+        };
+        for _ in 0..count.parse::<usize>().unwrap() {
+            update_journal(|j| {
+                Ok(j.record_operation(
+                    vec![JournalAction::ClearCache {
+                        scope: FontScope::User,
+                    }],
+                    Some(format!("stress {}", std::process::id())),
+                ))
+            })
+            .expect("locked update should not fail");
+        }
+    }
+
+    /// Separate processes hammering the same journal must not lose entries,
+    /// and a corrupt journal is moved aside instead of blocking them.
+    #[test]
+    fn concurrent_processes_preserve_all_entries() {
+        const CHILDREN: usize = 4;
+        const PER_CHILD: usize = 25;
+
+        let temp = TempDir::new().unwrap();
+        let journal_file = temp.path().join("journal.json");
+        fs::write(&journal_file, b"{ not json").unwrap();
+
+        let exe = std::env::current_exe().unwrap();
+        let children: Vec<_> = (0..CHILDREN)
+            .map(|_| {
+                std::process::Command::new(&exe)
+                    .args(["journal::tests::journal_stress_child", "--exact", "--quiet"])
+                    .env(STRESS_CHILD_ENV, PER_CHILD.to_string())
+                    .env("FONTLIFT_JOURNAL_PATH", &journal_file)
+                    .stdout(std::process::Stdio::null())
+                    .spawn()
+                    .expect("spawn child test process")
+            })
+            .collect();
+
+        for child in children {
+            let output = child.wait_with_output().unwrap();
+            assert!(output.status.success(), "child process failed");
+        }
+
+        let journal: Journal =
+            serde_json::from_str(&fs::read_to_string(&journal_file).unwrap()).unwrap();
+        assert_eq!(journal.entries.len(), CHILDREN * PER_CHILD);
+
+        let quarantined = fs::read_dir(temp.path())
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|e| {
+                e.file_name()
+                    .to_string_lossy()
+                    .starts_with("journal.json.corrupt-")
+            })
+            .count();
+        assert_eq!(quarantined, 1, "corrupt journal is kept for inspection");
+    }
+
     #[test]
     fn test_journal_entry_creation() {
         let actions = vec![
@@ -549,6 +1462,8 @@ mod tests {
         assert_eq!(entry.current_step, 0);
         assert_eq!(entry.actions.len(), 2);
         assert!(entry.is_incomplete());
+        let environment = entry.environment.as_ref().expect("environment recorded");
+        assert_eq!(environment.os, std::env::consts::OS);
     }
 
     #[test]
@@ -636,20 +1551,298 @@ mod tests {
         assert!(journal.entries[0].is_incomplete());
     }
 
+    #[test]
+    fn compaction_keeps_incomplete_and_newest_entries() {
+        let mut journal = Journal::new();
+        let mut ids = Vec::new();
+        for i in 0..5 {
+            let id = journal.record_operation(vec![], Some(format!("op {i}")));
+            if i != 1 {
+                journal.mark_completed(id).unwrap();
+            }
+            ids.push(id);
+        }
+        // An old completed entry goes by age, whatever its position.
+        journal.entries[4].started_at = SystemTime::now() - Duration::from_secs(40 * 86_400);
+
+        let policy = RetentionPolicy {
+            max_entries: 2,
+            max_age: RetentionPolicy::DEFAULT_MAX_AGE,
+        };
+        assert!(journal.needs_compaction(&policy));
+        assert_eq!(journal.compact(&policy), 2);
+        let kept: Vec<Uuid> = journal.entries.iter().map(|e| e.id).collect();
+        assert_eq!(kept, vec![ids[1], ids[2], ids[3]]);
+        assert!(!journal.needs_compaction(&policy));
+    }
+
+    #[test]
+    fn oversized_journals_are_refused_by_loads_and_compacted_by_updates() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("fontlift-journal.json");
+        let mut journal = Journal::new();
+        let incomplete = journal.record_operation(
+            vec![JournalAction::ClearCache {
+                scope: FontScope::User,
+            }],
+            Some("interrupted".to_string()),
+        );
+        let mut content = serde_json::to_string(&journal).unwrap();
+        content.push_str(&" ".repeat(MAX_JOURNAL_BYTES as usize));
+        fs::write(&path, content).unwrap();
+
+        assert!(matches!(
+            load_journal_from(&path),
+            Err(FontError::UnsupportedOperation(message)) if message.contains("limit")
+        ));
+        let loaded = load_journal_or_quarantine_at(&path).unwrap();
+        assert_eq!(loaded.entries.len(), 1);
+        assert_eq!(loaded.entries[0].id, incomplete);
+        assert!(path.exists(), "an oversized journal is not moved aside");
+
+        fs::write(&path, b"{ not json").unwrap();
+        assert!(load_journal_or_quarantine_at(&path)
+            .unwrap()
+            .entries
+            .is_empty());
+        let quarantined: Vec<_> = fs::read_dir(temp.path())
+            .unwrap()
+            .filter_map(Result::ok)
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        assert!(
+            quarantined[0].starts_with("fontlift-journal.json.corrupt-"),
+            "{quarantined:?}"
+        );
+    }
+
+    #[test]
+    fn format_version_guards_against_newer_journals() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("journal.json");
+
+        // Written before the format was versioned.
+        fs::write(&path, r#"{"entries": []}"#).unwrap();
+        let legacy = load_journal_from(&path).unwrap();
+        assert_eq!(legacy.format_version, JOURNAL_FORMAT_VERSION);
+        let saved = serde_json::to_value(&legacy).unwrap();
+        assert_eq!(saved["format_version"], JOURNAL_FORMAT_VERSION);
+
+        // A newer format may hold actions this build cannot parse; it is
+        // refused, not mistaken for a corrupt file.
+        fs::write(
+            &path,
+            r#"{"format_version": 99, "entries": [{"actions": [{"Teleport": {}}]}]}"#,
+        )
+        .unwrap();
+        let err = load_journal_from(&path).unwrap_err();
+        assert!(
+            matches!(err, FontError::UnsupportedOperation(ref m) if m.contains("format 99")),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn roll_back_undoes_finished_steps_newest_first() {
+        let (_temp, mut journal) = setup_test_journal();
+        let copy = JournalAction::CopyFile {
+            from: PathBuf::from("/downloads/Undo.ttf"),
+            to: PathBuf::from("/fonts/Undo.ttf"),
+        };
+        let register = JournalAction::RegisterFont {
+            path: PathBuf::from("/fonts/Undo.ttf"),
+            scope: FontScope::User,
+        };
+        let cache = JournalAction::ClearCache {
+            scope: FontScope::User,
+        };
+        let id = journal.record_operation(vec![copy.clone(), register.clone(), cache], None);
+        journal.mark_step(id, 1).unwrap();
+        save_journal(&journal).unwrap();
+
+        // The undo of the registration fails: nothing is marked done.
+        let seen = std::cell::RefCell::new(Vec::new());
+        let results = roll_back_with(&|action: &JournalAction, policy| {
+            seen.borrow_mut().push((action.clone(), policy));
+            Ok(!matches!(action, JournalAction::RegisterFont { .. }))
+        })
+        .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].success);
+        assert_eq!(load_journal().unwrap().incomplete_entries().len(), 1);
+
+        // The cache clear never ran, so it is not undone.
+        seen.borrow_mut().clear();
+        let results = roll_back_with(&|action: &JournalAction, policy| {
+            seen.borrow_mut().push((action.clone(), policy));
+            Ok(true)
+        })
+        .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            *seen.borrow(),
+            [
+                (register, RecoveryPolicy::RollBack),
+                (copy, RecoveryPolicy::RollBack)
+            ]
+        );
+        assert!(load_journal().unwrap().incomplete_entries().is_empty());
+    }
+
+    #[test]
+    fn transactions_group_fonts_and_roll_back_what_ran() {
+        let (_temp, _) = setup_test_journal();
+        let font = |name: &str| {
+            vec![
+                JournalAction::CopyFile {
+                    from: PathBuf::from(format!("/downloads/{name}")),
+                    to: PathBuf::from(format!("/fonts/{name}")),
+                },
+                JournalAction::RegisterFont {
+                    path: PathBuf::from(format!("/fonts/{name}")),
+                    scope: FontScope::User,
+                },
+            ]
+        };
+
+        let mut batch = Transaction::begin("Install 2 fonts").unwrap();
+        batch.add("A.ttf", font("A.ttf")).unwrap();
+        batch.step_done().unwrap();
+        batch.step_done().unwrap();
+        batch.add("B.ttf", font("B.ttf")).unwrap();
+        batch.step_done().unwrap();
+
+        let journal = load_journal().unwrap();
+        let entry = journal.find_entry(batch.id()).unwrap();
+        assert_eq!(entry.actions.len(), 4);
+        assert_eq!(entry.items.len(), 2);
+        assert_eq!(entry.items[1].first_action, 2);
+        assert_eq!(entry.finished_items(), 1);
+        assert_eq!(journal.incomplete_entries().len(), 1);
+
+        // B's registration was interrupted, so it is undone too.
+        let seen = std::cell::RefCell::new(Vec::new());
+        let results = batch
+            .roll_back(&|action: &JournalAction, _| {
+                seen.borrow_mut().push(action.description());
+                Ok(true)
+            })
+            .unwrap();
+        assert_eq!(results.len(), 4);
+        assert!(seen.borrow()[0].contains("/fonts/B.ttf"));
+        assert!(load_journal().unwrap().incomplete_entries().is_empty());
+
+        let mut batch = Transaction::begin("Install 1 font").unwrap();
+        batch.add("C.ttf", font("C.ttf")).unwrap();
+        let id = batch.id();
+        batch.commit().unwrap();
+        assert!(load_journal().unwrap().find_entry(id).unwrap().completed);
+    }
+
     #[test]
     fn test_recovery_policy_determination() {
         let copy_missing = JournalAction::CopyFile {
             from: PathBuf::from("/nonexistent"),
             to: PathBuf::from("/also_nonexistent"),
         };
+        let entry = JournalEntry::new(vec![copy_missing.clone()], None);
         assert_eq!(
-            determine_recovery_policy(&copy_missing),
+            determine_recovery_policy(&copy_missing, &entry),
             RecoveryPolicy::RollForward
         );
 
         let cache = JournalAction::ClearCache {
             scope: FontScope::User,
         };
-        assert_eq!(determine_recovery_policy(&cache), RecoveryPolicy::Skip);
+        assert_eq!(
+            determine_recovery_policy(&cache, &entry),
+            RecoveryPolicy::Skip
+        );
+    }
+
+    #[test]
+    fn changed_files_need_confirmation_before_recovery() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("Source.ttf");
+        let doomed = temp.path().join("Doomed.ttf");
+        fs::write(&source, b"original source").unwrap();
+        fs::write(&doomed, b"original target").unwrap();
+
+        let copy = JournalAction::CopyFile {
+            from: source.clone(),
+            to: temp.path().join("Fonts/Source.ttf"),
+        };
+        let delete = JournalAction::DeleteFile {
+            path: doomed.clone(),
+        };
+        let entry = JournalEntry::new(vec![copy.clone(), delete.clone()], None);
+        assert_eq!(entry.fingerprints.len(), 2);
+        assert_eq!(
+            determine_recovery_policy(&copy, &entry),
+            RecoveryPolicy::RollForward
+        );
+        assert_eq!(
+            determine_recovery_policy(&delete, &entry),
+            RecoveryPolicy::RollForward
+        );
+
+        // Same size, different bytes: only the hash can tell.
+        fs::write(&source, b"replaced source").unwrap();
+        fs::write(&doomed, b"a newer, longer target").unwrap();
+        assert_eq!(
+            determine_recovery_policy(&copy, &entry),
+            RecoveryPolicy::NeedsConfirmation
+        );
+        assert_eq!(
+            determine_recovery_policy(&delete, &entry),
+            RecoveryPolicy::NeedsConfirmation
+        );
+
+        // Entries from older journals carry no fingerprints.
+        let json = serde_json::to_value(&entry).unwrap();
+        let mut legacy = json.clone();
+        legacy.as_object_mut().unwrap().remove("fingerprints");
+        let legacy: JournalEntry = serde_json::from_value(legacy).unwrap();
+        assert_eq!(
+            determine_recovery_policy(&delete, &legacy),
+            RecoveryPolicy::RollForward
+        );
+    }
+
+    #[test]
+    fn scheduled_cache_clear_completes_after_restart_when_files_are_gone() {
+        let temp = TempDir::new().unwrap();
+        let deleted = temp.path().join("FNTCACHE.DAT");
+        let rebuilt = temp.path().join("FontCache-S-1-5-21.dat");
+        let locked = temp.path().join("AdobeFnt11.lst");
+        for path in [&deleted, &rebuilt, &locked] {
+            fs::write(path, b"cache").unwrap();
+        }
+
+        let clear = ScheduledCacheClear::new(
+            FontScope::System,
+            &[deleted.clone(), rebuilt.clone(), locked.clone()],
+        );
+        let before = clear.scheduled_at - std::time::Duration::from_secs(60);
+        let after = clear.scheduled_at + std::time::Duration::from_secs(60);
+        assert_eq!(clear.status(None), ScheduledClearStatus::Pending);
+        assert_eq!(clear.status(Some(before)), ScheduledClearStatus::Pending);
+
+        fs::remove_file(&deleted).unwrap();
+        fs::write(&rebuilt, b"fresh cache").unwrap();
+        assert_eq!(
+            clear.status(Some(after)),
+            ScheduledClearStatus::Incomplete {
+                remaining: vec![locked.clone()]
+            }
+        );
+
+        fs::remove_file(&locked).unwrap();
+        assert_eq!(clear.status(Some(after)), ScheduledClearStatus::Completed);
+
+        assert_eq!(
+            parse_sysctl_boottime("{ sec = 1767225600, usec = 120000 } Thu Jan  1 00:00:00 2026"),
+            Some(1767225600)
+        );
     }
 }
@@ -26,37 +26,45 @@
 //! **style**. Weight uses the common 100 to 900 scale where 400 is Regular and
 //! 700 is Bold.
 
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use thiserror::Error;
 
 /// Errors returned by fontlift's core API.
 ///
-/// The `Display` text includes a short suggestion because many callers surface
-/// it directly to users.
+/// The `Display` text is [`message`](FontError::message), then the
+/// [`suggestion`](FontError::suggestion) on a second line, because many
+/// callers surface it directly to users. Programs should match on
+/// [`code`](FontError::code) instead; serializing a `FontError` gives all
+/// three fields:
+///
+/// ```json
+/// { "code": "font_not_found", "message": "Font file not found: /tmp/x.ttf", "suggestion": "Check the path. Does the file exist? Was it moved?" }
+/// ```
 #[derive(Error, Debug)]
 pub enum FontError {
     /// The target path no longer exists.
-    #[error("Font file not found: {0}\n→ Check the path. Does the file exist? Was it moved?")]
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
     FontNotFound(PathBuf),
 
     /// The file exists but is not a supported font, or failed structural parsing.
-    #[error("Invalid font format: {0}\n→ Accepted formats: .ttf, .otf, .ttc, .otc, .woff, .woff2, .dfont")]
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
     InvalidFormat(String),
 
     /// The OS refused to register the font.
-    #[error("Font registration failed: {0}\n→ Try restarting your system, or run with admin/sudo privileges")]
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
     RegistrationFailed(String),
 
     /// You tried to modify a font in an OS-owned location.
-    #[error("System font protection: cannot modify {0}\n→ System fonts are off-limits for stability. Use user-level installation instead")]
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
     SystemFontProtection(PathBuf),
 
     /// A filesystem operation failed.
-    #[error("IO error: {0}\n→ Check file permissions and available disk space")]
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
     IoError(#[from] std::io::Error),
 
     /// The operation needs privileges the process does not have.
-    #[error("Permission denied: {0}\n→ On macOS: use sudo. On Windows: run as Administrator")]
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
     PermissionDenied(String),
 
     /// A font file with the same target name already exists in the destination
@@ -67,12 +75,190 @@ pub enum FontError {
     /// existing file instead (see [`FontManager::install_font`] for the full
     /// contract). OS-level "already registered" / "duplicate name" conflicts are
     /// resolved automatically and do **not** surface as this error.
-    #[error("Font already installed: {0}\n→ Uninstall it first with 'fontlift uninstall', or reinstall with --inplace")]
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
     AlreadyInstalled(PathBuf),
 
     /// This feature is not available on the current platform or build.
-    #[error("Unsupported operation: {0}\n→ This feature may not be available on your platform or in this version")]
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
     UnsupportedOperation(String),
+
+    /// Installed fonts do not match what a catalog or manifest expects.
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
+    VerificationFailed(String),
+
+    /// A user-supplied batch script failed to compile or run.
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
+    ScriptError(String),
+
+    /// A paged-listing cursor refers to a snapshot that is gone.
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
+    StaleCursor(String),
+
+    /// The machine's managed policy forbids the operation.
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
+    PolicyDenied(String),
+
+    /// The file is a PostScript Type 1 font, which current systems no
+    /// longer load.
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
+    LegacyType1(PathBuf),
+
+    /// An argument or request parameter is missing, malformed or conflicts
+    /// with another one.
+    #[error("{}\n→ {}", self.message(), self.suggestion())]
+    InvalidArgument(String),
+}
+
+/// Stable, machine-readable identifier for each [`FontError`] variant.
+///
+/// Serialized in `snake_case` (`"font_not_found"`); new codes may be added,
+/// existing ones are never renamed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    FontNotFound,
+    InvalidFormat,
+    RegistrationFailed,
+    SystemFontProtection,
+    Io,
+    PermissionDenied,
+    AlreadyInstalled,
+    UnsupportedOperation,
+    VerificationFailed,
+    ScriptError,
+    StaleCursor,
+    PolicyDenied,
+    LegacyType1,
+    InvalidArgument,
+}
+
+impl ErrorCode {
+    /// The serialized name, e.g. `"font_not_found"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::FontNotFound => "font_not_found",
+            ErrorCode::InvalidFormat => "invalid_format",
+            ErrorCode::RegistrationFailed => "registration_failed",
+            ErrorCode::SystemFontProtection => "system_font_protection",
+            ErrorCode::Io => "io",
+            ErrorCode::PermissionDenied => "permission_denied",
+            ErrorCode::AlreadyInstalled => "already_installed",
+            ErrorCode::UnsupportedOperation => "unsupported_operation",
+            ErrorCode::VerificationFailed => "verification_failed",
+            ErrorCode::ScriptError => "script_error",
+            ErrorCode::StaleCursor => "stale_cursor",
+            ErrorCode::PolicyDenied => "policy_denied",
+            ErrorCode::LegacyType1 => "legacy_type1",
+            ErrorCode::InvalidArgument => "invalid_argument",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FontError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            FontError::FontNotFound(_) => ErrorCode::FontNotFound,
+            FontError::InvalidFormat(_) => ErrorCode::InvalidFormat,
+            FontError::RegistrationFailed(_) => ErrorCode::RegistrationFailed,
+            FontError::SystemFontProtection(_) => ErrorCode::SystemFontProtection,
+            FontError::IoError(_) => ErrorCode::Io,
+            FontError::PermissionDenied(_) => ErrorCode::PermissionDenied,
+            FontError::AlreadyInstalled(_) => ErrorCode::AlreadyInstalled,
+            FontError::UnsupportedOperation(_) => ErrorCode::UnsupportedOperation,
+            FontError::VerificationFailed(_) => ErrorCode::VerificationFailed,
+            FontError::ScriptError(_) => ErrorCode::ScriptError,
+            FontError::StaleCursor(_) => ErrorCode::StaleCursor,
+            FontError::PolicyDenied(_) => ErrorCode::PolicyDenied,
+            FontError::LegacyType1(_) => ErrorCode::LegacyType1,
+            FontError::InvalidArgument(_) => ErrorCode::InvalidArgument,
+        }
+    }
+
+    /// What went wrong, without the suggestion.
+    pub fn message(&self) -> String {
+        match self {
+            FontError::FontNotFound(path) => format!("Font file not found: {}", path.display()),
+            FontError::InvalidFormat(detail) => format!("Invalid font format: {detail}"),
+            FontError::RegistrationFailed(detail) => {
+                format!("Font registration failed: {detail}")
+            }
+            FontError::SystemFontProtection(path) => {
+                format!("System font protection: cannot modify {}", path.display())
+            }
+            FontError::IoError(e) => format!("IO error: {e}"),
+            FontError::PermissionDenied(detail) => format!("Permission denied: {detail}"),
+            FontError::AlreadyInstalled(path) => {
+                format!("Font already installed: {}", path.display())
+            }
+            FontError::UnsupportedOperation(detail) => {
+                format!("Unsupported operation: {detail}")
+            }
+            FontError::VerificationFailed(detail) => format!("Verification failed: {detail}"),
+            FontError::ScriptError(detail) => format!("Script error: {detail}"),
+            FontError::StaleCursor(detail) => format!("Stale listing cursor: {detail}"),
+            FontError::PolicyDenied(detail) => format!("Denied by managed policy: {detail}"),
+            FontError::LegacyType1(path) => {
+                format!("PostScript Type 1 font: {}", path.display())
+            }
+            FontError::InvalidArgument(detail) => format!("Invalid argument: {detail}"),
+        }
+    }
+
+    /// What the user can do about it.
+    pub fn suggestion(&self) -> &'static str {
+        match self {
+            FontError::FontNotFound(_) => "Check the path. Does the file exist? Was it moved?",
+            FontError::InvalidFormat(_) => {
+                "Accepted formats: .ttf, .otf, .ttc, .otc, .woff, .woff2, .dfont"
+            }
+            FontError::RegistrationFailed(_) => {
+                "Try restarting your system, or run with admin/sudo privileges"
+            }
+            FontError::SystemFontProtection(_) => {
+                "System fonts are off-limits for stability. Use user-level installation instead"
+            }
+            FontError::IoError(_) => "Check file permissions and available disk space",
+            FontError::PermissionDenied(_) => {
+                "On macOS: use sudo. On Windows: run as Administrator"
+            }
+            FontError::AlreadyInstalled(_) => {
+                "Uninstall it first with 'fontlift uninstall', or reinstall with --inplace"
+            }
+            FontError::UnsupportedOperation(_) => {
+                "This feature may not be available on your platform or in this version"
+            }
+            FontError::VerificationFailed(_) => {
+                "Reinstall the affected fonts from the licensed source"
+            }
+            FontError::ScriptError(_) => "Fix the script, then preview its plan with --dry-run",
+            FontError::StaleCursor(_) => "Start the listing again from the first page",
+            FontError::PolicyDenied(_) => {
+                "Your administrator's fontlift policy forbids this; ask them to change it"
+            }
+            FontError::LegacyType1(_) => {
+                "macOS and Windows no longer load Type 1; install it with --convert to convert it to OpenType"
+            }
+            FontError::InvalidArgument(_) => "Check the command's --help for the accepted values",
+        }
+    }
+}
+
+impl Serialize for FontError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("FontError", 3)?;
+        state.serialize_field("code", &self.code())?;
+        state.serialize_field("message", &self.message())?;
+        state.serialize_field("suggestion", self.suggestion())?;
+        state.end()
+    }
 }
 
 /// Shorthand for `Result<T, FontError>`.
@@ -166,8 +352,31 @@ pub struct FontliftFontFaceInfo {
     pub full_name: String,
     pub family_name: String,
     pub style: String,
+    /// `OS/2.usWeightClass` on the 1–1000 scale (see
+    /// [`metadata::normalize_weight_class`]).
     pub weight: Option<u16>,
     pub italic: Option<bool>,
+    /// `OS/2.usWidthClass`, from 1 (ultra-condensed) through 5 (normal) to
+    /// 9 (ultra-expanded).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u16>,
+    /// Tags of the `fvar` axes (`wght`, `wdth`, ...) of a variable font;
+    /// empty for static fonts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub axes: Vec<String>,
+    /// License description, name ID 13.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// License URL, name ID 14.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_url: Option<String>,
+    /// Embedding permissions from `OS/2.fsType`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<metadata::Embedding>,
+    /// Version, vendor and copyright strings; only shown by
+    /// `fontlift list --details`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<metadata::FaceDetails>,
 }
 
 impl FontliftFontFaceInfo {
@@ -186,9 +395,20 @@ impl FontliftFontFaceInfo {
             style,
             weight: None,
             italic: None,
+            width: None,
+            axes: Vec::new(),
+            license: None,
+            license_url: None,
+            embedding: None,
+            details: None,
         }
     }
 
+    /// The face is a variable font.
+    pub fn is_variable(&self) -> bool {
+        !self.axes.is_empty()
+    }
+
     pub fn filename_stem(&self) -> Option<&str> {
         self.source.path.file_stem()?.to_str()
     }
@@ -248,18 +468,306 @@ pub trait FontManager: Send + Sync {
     /// produce several entries.
     fn list_installed_fonts(&self) -> FontResult<Vec<FontliftFontFaceInfo>>;
 
-    /// Flush the OS font cache for the given scope.
+    /// [`list_installed_fonts`](Self::list_installed_fonts), keeping only
+    /// the faces `filter` matches.
+    ///
+    /// The default filters the full listing. Platforms that parse each file
+    /// while listing skip files [`listing::ListFilter::matches_file`] rules
+    /// out instead.
+    fn list_installed_fonts_filtered(
+        &self,
+        filter: &listing::ListFilter,
+    ) -> FontResult<Vec<FontliftFontFaceInfo>> {
+        Ok(filter.apply(self.list_installed_fonts()?))
+    }
+
+    /// [`list_installed_fonts_filtered`](Self::list_installed_fonts_filtered)
+    /// with the font directories, registry keys and files that could not be
+    /// read reported as warnings instead of silently left out.
+    ///
+    /// The default reports no warnings. Platforms whose enumeration can fail
+    /// part-way, such as a locked-down Windows machine denying access to a
+    /// font directory, override it.
+    fn list_installed_fonts_report(
+        &self,
+        filter: &listing::ListFilter,
+    ) -> FontResult<listing::FontListing> {
+        Ok(listing::FontListing::new(
+            self.list_installed_fonts_filtered(filter)?,
+        ))
+    }
+
+    /// Flush the OS font caches `strategy` selects.
     ///
     /// Platform implementations may also clear common application caches where
-    /// that is practical.
-    fn clear_font_caches(&self, scope: FontScope) -> FontResult<()>;
+    /// that is practical. The result counts what was deleted, says whether a
+    /// restart is needed before it takes effect, and lists caches that could
+    /// not be cleared without failing the whole operation.
+    fn clear_font_caches(
+        &self,
+        strategy: cache::CacheClearStrategy,
+    ) -> FontResult<cache::CacheClearResult>;
+
+    /// Clear only the caches in `targets`, at the levels `strategy` selects,
+    /// so stale Adobe font lists can go without stopping system services.
+    ///
+    /// The default clears [`cache::CacheTargets::all`] through
+    /// [`clear_font_caches`](Self::clear_font_caches) and reports narrower
+    /// selections as unsupported.
+    fn clear_cache_targets(
+        &self,
+        strategy: cache::CacheClearStrategy,
+        targets: &cache::CacheTargets,
+    ) -> FontResult<cache::CacheClearResult> {
+        if targets.is_all() {
+            return self.clear_font_caches(strategy);
+        }
+        Err(FontError::UnsupportedOperation(format!(
+            "Clearing individual caches ({}) is not supported on this platform",
+            targets.describe()
+        )))
+    }
 
     /// Prune registrations whose backing files no longer exist.
     ///
-    /// Returns the number of pruned entries. The default implementation is a
-    /// no-op for platforms that do not need this cleanup.
-    fn prune_missing_fonts(&self, _scope: FontScope) -> FontResult<usize> {
-        Ok(0)
+    /// Returns every pruned entry with the reason it was removed. Runs
+    /// [`prune_stale_registrations`](Self::prune_stale_registrations) without
+    /// a limit.
+    fn prune_missing_fonts(&self, scope: FontScope) -> FontResult<prune::PruneReport> {
+        self.prune_stale_registrations(scope, &prune::PruneOptions::default(), &mut |_| {})
+    }
+
+    /// Prune in chunks, reporting progress and saving a journal checkpoint
+    /// so an interrupted or limited prune resumes where it stopped. See
+    /// [`prune::run`].
+    fn prune_stale_registrations(
+        &self,
+        scope: FontScope,
+        options: &prune::PruneOptions,
+        progress: &mut dyn FnMut(&prune::PruneProgress),
+    ) -> FontResult<prune::PruneReport> {
+        prune::run(self, scope, options, progress)
+    }
+
+    /// Registrations in `scope` whose file is missing, is not a font, or
+    /// cannot be resolved. Read-only. The default finds none, for platforms
+    /// that do not need this cleanup.
+    fn find_stale_registrations(
+        &self,
+        scope: FontScope,
+    ) -> FontResult<Vec<prune::PrunedRegistration>> {
+        let _ = scope;
+        Ok(Vec::new())
+    }
+
+    /// Remove one registration [`find_stale_registrations`](Self::find_stale_registrations)
+    /// returned. Removing one that is already gone succeeds, so resumed
+    /// prunes can repeat the last chunk.
+    fn remove_stale_registration(
+        &self,
+        scope: FontScope,
+        registration: &prune::PrunedRegistration,
+    ) -> FontResult<()> {
+        let _ = (scope, registration);
+        Err(FontError::UnsupportedOperation(
+            "Pruning registrations is not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Find the numbered copies (`arial_0.ttf`) the OS made of fonts in
+    /// `scope` whose file names collided, and unless `dry_run` merge the
+    /// identical ones into the original. See [`renamed_copies`].
+    ///
+    /// The default finds none, for platforms that never rename on collision.
+    fn repair_renamed_copies(
+        &self,
+        scope: FontScope,
+        dry_run: bool,
+    ) -> FontResult<renamed_copies::RepairReport> {
+        let _ = dry_run;
+        Ok(renamed_copies::RepairReport::new(scope))
+    }
+
+    /// The folder a caller copying fonts before
+    /// [`install_font`](Self::install_font) should put those of `scope` in,
+    /// when the manager keeps its own.
+    ///
+    /// The default, `None`, leaves the folder to the caller.
+    fn install_directory(&self, _scope: FontScope) -> Option<PathBuf> {
+        None
+    }
+
+    /// Describe what [`install_font`](Self::install_font) would do for
+    /// `source`, without changing anything.
+    ///
+    /// The default plan is a single registration step; platforms list their
+    /// copies, registry writes and notifications.
+    fn plan_install(&self, source: &FontliftFontSource) -> FontResult<Vec<plan::PlannedAction>> {
+        Ok(vec![plan::PlannedAction::RegisterFont {
+            path: source.path.clone(),
+            scope: source.scope.unwrap_or(FontScope::User),
+            api: "the platform font manager".to_string(),
+        }])
+    }
+
+    /// [`install_font`](Self::install_font), deciding what happens to
+    /// installed fonts it conflicts with by `policy`.
+    ///
+    /// Returns the conflicts found and where the font went, or
+    /// [`FontError::AlreadyInstalled`] under [`conflicts::ConflictPolicy::Fail`].
+    /// The default is [`conflicts::install_with_policy`].
+    fn install_font_with_policy(
+        &self,
+        source: &FontliftFontSource,
+        policy: conflicts::ConflictPolicy,
+    ) -> FontResult<conflicts::ConflictOutcome> {
+        conflicts::install_with_policy(self, source, policy)
+    }
+
+    /// Install a font that exists only in memory, e.g. one a CI job just
+    /// generated.
+    ///
+    /// `file_name` (`MyFont.ttf`) names the installed copy, and its
+    /// extension says what format the bytes are. The default writes the
+    /// bytes to a [`font_bytes::StagedFont`] and installs that file with
+    /// [`install_font`](Self::install_font); the staged file is deleted
+    /// once the platform has copied it.
+    fn install_font_bytes(&self, data: &[u8], file_name: &str, scope: FontScope) -> FontResult<()> {
+        let staged = font_bytes::StagedFont::write(data, file_name)?;
+        self.install_font(&FontliftFontSource::new(staged.path.clone()).with_scope(Some(scope)))
+    }
+
+    /// Register a font for the current login session only.
+    ///
+    /// The file is not copied and no persistent registration is written, so
+    /// the font disappears at logout or restart. Useful for trying a font
+    /// before installing it. The file must stay where it is while in use.
+    /// The default implementation reports the operation as unsupported.
+    fn install_font_session(&self, _source: &FontliftFontSource) -> FontResult<()> {
+        Err(FontError::UnsupportedOperation(
+            "Session-only font installation is not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Undo [`install_font_session`](Self::install_font_session) before the
+    /// session ends. The file is left alone. The default implementation
+    /// reports the operation as unsupported.
+    fn uninstall_font_session(&self, _source: &FontliftFontSource) -> FontResult<()> {
+        Err(FontError::UnsupportedOperation(
+            "Session-only font installation is not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Register font bytes for this process only, without a file on disk.
+    ///
+    /// Dropping the returned handle unregisters the fonts; see
+    /// [`memory`]. The default implementation reports the operation as
+    /// unsupported.
+    fn register_font_bytes(
+        &self,
+        _data: &[u8],
+        _options: &memory::MemoryFontOptions,
+    ) -> FontResult<memory::FontHandle> {
+        Err(FontError::UnsupportedOperation(
+            "Registering fonts from memory is not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Register the font file at `source.path` for this process only.
+    ///
+    /// Nothing is copied and nothing persistent is written, so the font
+    /// never shows up in [`list_installed_fonts`](Self::list_installed_fonts)
+    /// and other apps never see it. Dropping the returned handle unregisters
+    /// it. The default reads the file and passes the bytes to
+    /// [`register_font_bytes`](Self::register_font_bytes).
+    fn register_font_private(&self, source: &FontliftFontSource) -> FontResult<memory::FontHandle> {
+        let data = std::fs::read(&source.path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => FontError::FontNotFound(source.path.clone()),
+            _ => FontError::IoError(e),
+        })?;
+        let options =
+            memory::MemoryFontOptions::default().with_label(source.path.display().to_string());
+        self.register_font_bytes(&data, &options)
+    }
+
+    /// Load every face of the installed font at `source.path` through the
+    /// platform text APIs, so the OS builds its cache entries now rather
+    /// than when an application first asks for the font.
+    ///
+    /// Returns the number of faces loaded. The default implementation
+    /// reports the operation as unsupported.
+    fn warm_font_cache(&self, _source: &FontliftFontSource) -> FontResult<usize> {
+        Err(FontError::UnsupportedOperation(
+            "Warming font caches is not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Ask the OS to delete the font caches for `scope` at the next restart.
+    ///
+    /// For cache files that stay locked while sessions are running. Returns
+    /// the files scheduled for deletion, which the caller records in the
+    /// journal. The default implementation reports the operation as
+    /// unsupported.
+    fn schedule_cache_clear_at_reboot(&self, _scope: FontScope) -> FontResult<Vec<PathBuf>> {
+        Err(FontError::UnsupportedOperation(
+            "Scheduling cache clears for the next restart is not supported on this platform"
+                .to_string(),
+        ))
+    }
+
+    /// Install (or replace) a recurring task with the OS scheduler.
+    ///
+    /// Returns where the task was registered: a plist path or a Task
+    /// Scheduler path.
+    fn schedule_task(&self, _task: &schedule::ScheduledTask) -> FontResult<String> {
+        Err(FontError::UnsupportedOperation(
+            "Scheduled tasks are not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Tasks installed by [`schedule_task`](Self::schedule_task), in both
+    /// scopes.
+    fn list_scheduled_tasks(&self) -> FontResult<Vec<schedule::ScheduledTaskInfo>> {
+        Err(FontError::UnsupportedOperation(
+            "Scheduled tasks are not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Remove the task called `name` from `scope`.
+    fn remove_scheduled_task(&self, _name: &str, _scope: FontScope) -> FontResult<()> {
+        Err(FontError::UnsupportedOperation(
+            "Scheduled tasks are not supported on this platform".to_string(),
+        ))
+    }
+
+    /// The font folders for both scopes and the application font caches
+    /// this platform's `cleanup` clears, for `fontlift sources`.
+    fn source_roots(&self) -> FontResult<Vec<sources::SourceRoot>> {
+        Err(FontError::UnsupportedOperation(
+            "Listing font sources is not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Unregister a font whose file [`vault::disable`] is about to move
+    /// into the vault. The default is [`uninstall_font`](Self::uninstall_font).
+    fn deactivate_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+        self.uninstall_font(source)
+    }
+
+    /// Register a font [`vault::enable`] has just put back at `source.path`,
+    /// the OS font folder it was disabled from included. The default is
+    /// [`install_font`](Self::install_font).
+    fn reactivate_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+        self.install_font(source)
+    }
+
+    /// Post [`notify::NOTIFICATION_NAME`] through the platform's
+    /// notification center, for the `distributed_notification` hook. The
+    /// default implementation reports the operation as unsupported.
+    fn post_font_change(&self, _change: &notify::FontChange) -> FontResult<()> {
+        Err(FontError::UnsupportedOperation(
+            "Distributed notifications are only available on macOS".to_string(),
+        ))
     }
 }
 
@@ -298,6 +806,9 @@ pub mod validation {
 
     /// Verify that `path` exists, is a regular file (not a directory),
     /// and has a recognized font extension. Does *not* parse the file contents.
+    ///
+    /// Type 1 files fail with [`FontError::LegacyType1`] rather than as an
+    /// unknown extension.
     pub fn validate_font_file(path: &Path) -> FontResult<()> {
         if !path.exists() {
             return Err(FontError::FontNotFound(path.to_path_buf()));
@@ -308,6 +819,9 @@ pub mod validation {
         }
 
         if !is_valid_font_extension(path) {
+            if crate::type1::detect(path).is_some() {
+                return Err(FontError::LegacyType1(path.to_path_buf()));
+            }
             return Err(FontError::InvalidFormat(
                 "Invalid font extension".to_string(),
             ));
@@ -372,9 +886,166 @@ pub mod validation_ext;
 /// Font installation is multi-step: copy the file, then register with
 /// the OS. If fontlift is killed between those steps, the journal
 /// records what happened so `fontlift doctor` can finish or undo the
-/// interrupted operation on the next run.
+/// interrupted operation on the next run. Stable public API; see the
+/// module's "Stability" section.
 pub mod journal;
 
+/// Download stage for `fontlift install <URL>`.
+///
+/// The HTTP client is only compiled with the `fetch` feature; URL detection
+/// is always available so callers can explain why a URL was rejected.
+pub mod fetch;
+
+/// Extraction stage for `fontlift install FontPack.zip`.
+///
+/// Zip and tar support is only compiled with the `archive` feature.
+pub mod archive;
+
+/// Why each managed font was installed.
+///
+/// The journal only tracks in-flight work. The install database remembers
+/// finished installs and their reason (explicit, profile, dependency) so
+/// automated cleanup can tell user choices apart from side effects.
+pub mod install_db;
+
+/// SHA-256 helpers shared by downloads and catalog verification.
+pub mod digest;
+
+/// Hash-addressed store that keeps provider downloads between runs.
+pub mod content_store;
+
+/// Byte-identical install inputs, of each other or of installed fonts.
+pub mod dedupe;
+
+/// Read version fields from font files without the out-of-process validator.
+pub mod metadata;
+
+/// Per-user cache of face metadata so `list` does not re-parse unchanged files.
+pub mod metadata_cache;
+
+/// Compliance checks against a foundry catalog (`fontlift verify --catalog`).
+pub mod catalog;
+
+/// Portable font inventories for `fontlift export` and `install --manifest`.
+pub mod manifest;
+
+/// Which running applications need a restart to pick up font changes.
+pub mod restart_advisory;
+
+/// Pairing suggestions from the installed inventory (`fontlift pair`).
+pub mod pairing;
+
+/// Target file names that fit the platform's path length limits.
+pub mod file_names;
+
+/// Ready-to-paste CSS, PostScript and family names for an installed face.
+pub mod snippets;
+
+/// Integrity check and repair of fontlift's journal, install database and caches.
+pub mod state_check;
+
+/// Step-by-step install plans for `--dry-run`.
+pub mod plan;
+
+/// Reports of the stale registrations `prune_missing_fonts` removed.
+pub mod prune;
+
+/// Recurring fontlift runs installed with the OS scheduler.
+pub mod schedule;
+
+/// Path comparison that follows each volume's case sensitivity.
+pub mod path_case;
+
+/// Family/weight/italic ordering for list, search and audit output.
+pub mod sorting;
+
+/// Axes and named instances of variable fonts.
+pub mod variations;
+
+/// Reversible disabling of fonts into a journaled vault.
+pub mod vault;
+
+/// OS, version, scope and configuration recorded with each operation.
+pub mod environment;
+
+/// Detection of bitmap and pixel fonts that OS smoothing would blur.
+pub mod pixel_fonts;
+
+/// Renamed copies for fonts whose PostScript name a different font uses.
+pub mod rename;
+
+/// Numbered duplicates (`arial_0.ttf`) left by colliding file names, and their repair.
+pub mod renamed_copies;
+
+/// Every place fontlift keeps, finds or caches fonts, and what each holds.
+pub mod sources;
+
+/// Table, name, axis and version differences between two font files.
+pub mod diff;
+
+/// SHA-256 checksum manifests and minisign signatures for font packs.
+pub mod checksums;
+
+/// Installed fonts chosen by family glob or name pattern, and their batch uninstall.
+pub mod face_match;
+/// Installing fonts from byte buffers and stdin through a staged temp file.
+pub mod font_bytes;
+/// Append-only log of completed operations, for `fontlift history`.
+pub mod history;
+/// Structure and recorded-hash checks of installed font files.
+pub mod integrity;
+/// Paged listings over stable inventory snapshots.
+pub mod inventory;
+/// Hard-link-aware removal and copying of font files.
+pub mod links;
+/// Scope, family, format, style and date filters for font listings.
+pub mod listing;
+/// In-memory font registration with handles that unregister on drop.
+pub mod memory;
+/// Importing font libraries from other font managers.
+pub mod migrate;
+/// Hooks that tell running applications fonts changed (`fontlift.toml`).
+pub mod notify;
+/// Install, uninstall and remove with the managed policy applied, for every front end.
+pub mod operations;
+/// Machine-level restrictions set by an administrator or MDM profile.
+pub mod policy;
+/// Progress observers for long install, uninstall and cleanup runs.
+pub mod progress;
+/// Removed fonts kept for a while so `fontlift restore` can bring them back.
+pub mod quarantine;
+/// Font requirement lists from documents and design apps.
+pub mod requirements;
+/// Font files in a directory, optionally recursive and filtered by glob.
+pub mod scan;
+/// Long-lived daemon answering NDJSON requests over a local socket.
+pub mod serve;
+/// Session-scope installs that are undone when dropped.
+pub mod session;
+/// Type specimen PDFs for proofing a face or family (`fontlift specimen`).
+pub mod specimen;
+/// Symlinked font files: canonical locations and the resolve/preserve policy.
+pub mod symlinks;
+/// File-tree fake platform manager for tests (`testing` feature).
+#[cfg(feature = "testing")]
+pub mod testing;
+/// PostScript Type 1 detection and conversion to OpenType.
+pub mod type1;
+/// Fonts referenced by CSS, HTML, Word, IDML and PDF documents (`fontlift scan-usage`).
+pub mod usage;
+
+/// Installed-font inventories in fontconfig formats (`list --output`).
+pub mod fontconfig;
+
+/// Rule-based audit of installed fonts with baselines for CI.
+pub mod audit;
+
+/// Google Fonts family search and download.
+///
+/// Network access needs the `google-fonts` feature; parsing and file naming
+/// are always available.
+pub mod google_fonts;
+
 /// Font cache management.
 ///
 /// Operating systems and some desktop applications maintain
@@ -388,9 +1059,13 @@ pub mod journal;
 /// and other app-specific font cache locations.
 /// On Windows, it means restarting the Windows Font Cache Service.
 pub mod cache {
+    use super::FontScope;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeSet;
 
     /// Which caches to clear.
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
     pub enum CacheClearStrategy {
         /// Only the current user's caches. Safe, no admin needed.
         UserOnly,
@@ -400,8 +1075,131 @@ pub mod cache {
         Both,
     }
 
+    impl CacheClearStrategy {
+        /// The caches commands run with `scope` clear: the user's without
+        /// `--admin`, the system's with it.
+        pub fn for_scope(scope: FontScope) -> Self {
+            match scope {
+                FontScope::User => CacheClearStrategy::UserOnly,
+                FontScope::System => CacheClearStrategy::SystemOnly,
+            }
+        }
+
+        pub fn includes_user(self) -> bool {
+            matches!(
+                self,
+                CacheClearStrategy::UserOnly | CacheClearStrategy::Both
+            )
+        }
+
+        pub fn includes_system(self) -> bool {
+            matches!(
+                self,
+                CacheClearStrategy::SystemOnly | CacheClearStrategy::Both
+            )
+        }
+    }
+
+    /// One cache [`FontManager::clear_cache_targets`](super::FontManager::clear_cache_targets)
+    /// can clear on its own.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum CacheTarget {
+        /// The OS font cache: the `atsutil` databases on macOS, the Font
+        /// Cache Service files on Windows. The only target that restarts a
+        /// system service.
+        System,
+        /// Adobe's `AdobeFnt*.lst` font lists and font caches.
+        Adobe,
+        /// Microsoft Office's font cache.
+        Office,
+        /// Java's font configuration cache under `~/.java/fonts`.
+        Java,
+        /// Firefox's startup cache, which holds its font list, and the
+        /// Chromium font lookup tables.
+        Browser,
+    }
+
+    impl CacheTarget {
+        pub const ALL: [CacheTarget; 5] = [
+            CacheTarget::System,
+            CacheTarget::Adobe,
+            CacheTarget::Office,
+            CacheTarget::Java,
+            CacheTarget::Browser,
+        ];
+
+        pub fn as_str(self) -> &'static str {
+            match self {
+                CacheTarget::System => "system",
+                CacheTarget::Adobe => "adobe",
+                CacheTarget::Office => "office",
+                CacheTarget::Java => "java",
+                CacheTarget::Browser => "browser",
+            }
+        }
+    }
+
+    /// A set of [`CacheTarget`]s, e.g. `--cache adobe,office`.
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct CacheTargets {
+        targets: BTreeSet<CacheTarget>,
+        /// Clear every file of [`CacheTarget::System`] instead of only the
+        /// current user's (`cleanup --deep`). Only Windows tells them apart.
+        #[serde(default)]
+        deep: bool,
+    }
+
+    impl CacheTargets {
+        /// Every target: what [`FontManager::clear_font_caches`](super::FontManager::clear_font_caches)
+        /// clears.
+        pub fn all() -> Self {
+            Self::only(CacheTarget::ALL)
+        }
+
+        pub fn only(targets: impl IntoIterator<Item = CacheTarget>) -> Self {
+            Self {
+                targets: targets.into_iter().collect(),
+                deep: false,
+            }
+        }
+
+        pub fn with_deep(mut self, deep: bool) -> Self {
+            self.deep = deep;
+            self
+        }
+
+        pub fn is_deep(&self) -> bool {
+            self.deep
+        }
+
+        pub fn contains(&self, target: CacheTarget) -> bool {
+            self.targets.contains(&target)
+        }
+
+        pub fn is_all(&self) -> bool {
+            CacheTarget::ALL.iter().all(|target| self.contains(*target))
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.targets.is_empty()
+        }
+
+        pub fn iter(&self) -> impl Iterator<Item = CacheTarget> + '_ {
+            self.targets.iter().copied()
+        }
+
+        /// `adobe, office`, for messages.
+        pub fn describe(&self) -> String {
+            self.iter()
+                .map(CacheTarget::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    }
+
     /// What happened when we tried to clear caches.
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
     pub struct CacheClearResult {
         /// How many cache files or entries were deleted.
         pub entries_cleared: usize,
@@ -429,6 +1227,14 @@ pub mod cache {
             self.warnings.push(warning);
             self
         }
+
+        /// Add the counts and warnings of `other`, e.g. the system half of
+        /// [`CacheClearStrategy::Both`].
+        pub fn merge(&mut self, other: CacheClearResult) {
+            self.entries_cleared += other.entries_cleared;
+            self.restart_required |= other.restart_required;
+            self.warnings.extend(other.warnings);
+        }
     }
 }
 
@@ -439,12 +1245,17 @@ pub mod cache {
 /// on Windows makes dialog boxes unreadable. This module identifies those
 /// protected paths and refuses to touch them.
 ///
+/// Administrators can protect more than the OS directories with a
+/// [`ProtectionPolicy`](protection::ProtectionPolicy): extra directories and
+/// PostScript names (corporate UI fonts, say), enforced or only warned about.
+///
 /// It also handles deduplication: when listing fonts, the same face can
 /// appear multiple times (e.g. registered under both user and system scope).
 /// [`dedupe_fonts`] collapses those duplicates deterministically.
 pub mod protection {
-    use super::FontliftFontFaceInfo;
-    use std::path::Path;
+    use super::{path_case, symlinks, FontError, FontResult, FontliftFontFaceInfo};
+    use serde::{Deserialize, Serialize};
+    use std::path::{Path, PathBuf};
 
     /// Normalize a path for cross-platform comparison: lowercase,
     /// forward slashes, no doubled separators. This lets us compare
@@ -470,43 +1281,198 @@ pub mod protection {
     /// Fonts in `~/Library/Fonts/` (macOS) or user-installed fonts on
     /// Windows are *not* protected — the user put them there and can
     /// remove them.
+    ///
+    /// The path is also checked at its [`symlinks::canonical_location`], so
+    /// a linked directory cannot hide a system folder. A symlinked font is
+    /// judged by the folder the link is in, not by its target.
     pub fn is_protected_system_font_path(path: &Path) -> bool {
-        let normalized = normalize(path);
+        let protected = |path: &Path| {
+            let normalized = normalize(path);
+
+            normalized.starts_with("/system/library/fonts/")
+                || normalized.starts_with("/library/fonts/")
+                || normalized.starts_with("c:/windows/fonts/")
+        };
 
-        normalized.starts_with("/system/library/fonts/")
-            || normalized.starts_with("/library/fonts/")
-            || normalized.starts_with("c:/windows/fonts/")
+        protected(path) || protected(&symlinks::canonical_location(path))
     }
 
     /// Remove duplicate font entries and return them in a stable, sorted order.
     ///
     /// Two entries are considered duplicates if they share the same PostScript
-    /// name *and* the same file path (both compared case-insensitively).
-    /// This happens when the OS reports the same font through multiple
-    /// enumeration paths.
+    /// name (compared case-insensitively) *and* the same file path (compared
+    /// as [`path_case::comparison_key`] does, so case only matters on
+    /// case-sensitive volumes). This happens when the OS reports the same
+    /// font through multiple enumeration paths.
     ///
     /// The output is sorted by (PostScript name, path), so results are
     /// deterministic regardless of the order the OS returned them.
-    pub fn dedupe_fonts(mut fonts: Vec<FontliftFontFaceInfo>) -> Vec<FontliftFontFaceInfo> {
-        fonts.sort_by(|a, b| {
-            let name_a = a.postscript_name.to_lowercase();
-            let name_b = b.postscript_name.to_lowercase();
-            let path_a = normalize(&a.source.path);
-            let path_b = normalize(&b.source.path);
-            (name_a, path_a).cmp(&(name_b, path_b))
-        });
+    pub fn dedupe_fonts(fonts: Vec<FontliftFontFaceInfo>) -> Vec<FontliftFontFaceInfo> {
+        let mut keyed: Vec<((String, String), FontliftFontFaceInfo)> = fonts
+            .into_iter()
+            .map(|font| {
+                let key = (
+                    font.postscript_name.to_lowercase(),
+                    path_case::comparison_key(&font.source.path),
+                );
+                (key, font)
+            })
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        keyed.dedup_by(|(a, _), (b, _)| a == b);
 
-        fonts.dedup_by(|a, b| {
-            a.postscript_name.eq_ignore_ascii_case(&b.postscript_name)
-                && normalize(&a.source.path) == normalize(&b.source.path)
-        });
+        keyed.into_iter().map(|(_, font)| font).collect()
+    }
 
-        fonts
+    /// What happens when an operation touches a font the policy protects.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum ProtectionMode {
+        /// Refuse with [`FontError::SystemFontProtection`].
+        #[default]
+        Deny,
+        /// Go ahead, but warn.
+        Warn,
     }
 
-    // Re-export normalization for the `conflicts` module without making it public API.
-    pub(crate) fn normalize_for_tests(path: &Path) -> String {
-        normalize(path)
+    /// Site-specific protection on top of the built-in OS directories.
+    ///
+    /// Loaded from a JSON file that only administrators can write
+    /// (see [`protection_policy_path`]):
+    ///
+    /// ```json
+    /// {
+    ///   "mode": "deny",
+    ///   "protected_dirs": ["/opt/corp/fonts"],
+    ///   "protected_postscript_names": ["CorpSans-Regular", "CorpUI-*"]
+    /// }
+    /// ```
+    ///
+    /// Names match case-insensitively; a trailing `*` matches any suffix.
+    /// `mode` applies to these extra rules only: the OS directories stay
+    /// off-limits regardless, enforced by the platform managers.
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct ProtectionPolicy {
+        #[serde(default)]
+        pub mode: ProtectionMode,
+        #[serde(default)]
+        pub protected_dirs: Vec<PathBuf>,
+        #[serde(default)]
+        pub protected_postscript_names: Vec<String>,
+    }
+
+    /// Machine-wide location of the protection policy.
+    ///
+    /// `FONTLIFT_PROTECTION_POLICY_PATH` overrides it. Otherwise it lives
+    /// where only administrators can write:
+    /// - macOS: `/Library/Application Support/FontLift/protection.json`
+    /// - Windows: `%ProgramData%\FontLift\protection.json`
+    /// - Linux / other: `/etc/fontlift/protection.json`
+    pub fn protection_policy_path() -> PathBuf {
+        if let Ok(path) = std::env::var("FONTLIFT_PROTECTION_POLICY_PATH") {
+            return PathBuf::from(path);
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            PathBuf::from("/Library/Application Support/FontLift/protection.json")
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            std::env::var("ProgramData")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from(r"C:\ProgramData"))
+                .join("FontLift")
+                .join("protection.json")
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        {
+            PathBuf::from("/etc/fontlift/protection.json")
+        }
+    }
+
+    impl ProtectionPolicy {
+        /// Read the policy at `path`; a missing file means no extra rules.
+        ///
+        /// A file that exists but cannot be parsed is an error rather than
+        /// an empty policy, so a typo never silently lifts protection.
+        pub fn load(path: &Path) -> FontResult<Self> {
+            match std::fs::read_to_string(path) {
+                Ok(content) => serde_json::from_str(&content).map_err(|e| {
+                    FontError::InvalidFormat(format!(
+                        "Failed to parse protection policy {}: {e}",
+                        path.display()
+                    ))
+                }),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+                Err(e) => Err(FontError::IoError(e)),
+            }
+        }
+
+        /// The policy at [`protection_policy_path`].
+        pub fn load_default() -> FontResult<Self> {
+            Self::load(&protection_policy_path())
+        }
+
+        /// Is `path` in an OS directory or a policy directory?
+        pub fn is_protected_path(&self, path: &Path) -> bool {
+            is_protected_system_font_path(path) || self.protects_dir_of(path)
+        }
+
+        /// Compares both as written and at their canonical locations, so
+        /// neither a linked directory nor a link to the protected folder
+        /// gets around it.
+        fn protects_dir_of(&self, path: &Path) -> bool {
+            let paths = [
+                normalize(path),
+                normalize(&symlinks::canonical_location(path)),
+            ];
+            self.protected_dirs.iter().any(|dir| {
+                [normalize(dir), normalize(&symlinks::canonical_path(dir))]
+                    .iter()
+                    .any(|dir| {
+                        let dir = format!("{}/", dir.trim_end_matches('/'));
+                        paths.iter().any(|path| path.starts_with(&dir))
+                    })
+            })
+        }
+
+        /// Does the policy protect this PostScript name?
+        pub fn is_protected_name(&self, postscript_name: &str) -> bool {
+            let name = postscript_name.to_lowercase();
+            self.protected_postscript_names.iter().any(|pattern| {
+                let pattern = pattern.to_lowercase();
+                match pattern.strip_suffix('*') {
+                    Some(prefix) => name.starts_with(prefix),
+                    None => name == pattern,
+                }
+            })
+        }
+
+        /// Check an operation that would modify the font at `path` with the
+        /// given faces.
+        ///
+        /// Returns `Ok(None)` when the policy does not apply, `Ok(Some(warning))`
+        /// in warn mode, and [`FontError::SystemFontProtection`] in deny mode.
+        pub fn check(&self, path: &Path, postscript_names: &[&str]) -> FontResult<Option<String>> {
+            let reason = if self.protects_dir_of(path) {
+                format!("{} is in a protected directory", path.display())
+            } else if let Some(name) = postscript_names
+                .iter()
+                .find(|name| self.is_protected_name(name))
+            {
+                format!("{name} is a protected font")
+            } else {
+                return Ok(None);
+            };
+
+            match self.mode {
+                ProtectionMode::Deny => Err(FontError::SystemFontProtection(path.to_path_buf())),
+                ProtectionMode::Warn => Ok(Some(reason)),
+            }
+        }
     }
 }
 
@@ -523,28 +1489,332 @@ pub mod protection {
 /// 3. **Same family + style** — e.g. two different files both claiming to be
 ///    "Helvetica Bold". Applications would pick one arbitrarily.
 ///
-/// The install flow uses this to unregister conflicting fonts before
-/// registering the new one, avoiding unpredictable behavior.
+/// By default the install flow unregisters conflicting fonts before
+/// registering the new one, avoiding unpredictable behavior. A
+/// [`ConflictPolicy`] passed to
+/// [`FontManager::install_font_with_policy`] can skip the install, refuse
+/// it, or keep both fonts instead.
 pub mod conflicts {
     use super::*;
     use std::collections::BTreeSet;
     use std::path::Path;
 
-    fn normalize(path: &Path) -> String {
-        protection::normalize_for_tests(path)
+    /// What to do when a font being installed conflicts with an installed
+    /// one (`--on-conflict`).
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum ConflictPolicy {
+        /// Replace the conflicting install, as
+        /// [`FontManager::install_font`] always does.
+        #[default]
+        Replace,
+        /// Leave the installed font alone and install nothing.
+        Skip,
+        /// Refuse with [`FontError::AlreadyInstalled`].
+        Fail,
+        /// Install next to the conflicting font. If a file already sits at
+        /// the target path, the new one is installed as `Name (2).ttf`.
+        KeepBoth,
+    }
+
+    impl ConflictPolicy {
+        /// The `--on-conflict` value: `replace`, `skip`, `fail`, `keep-both`.
+        pub fn as_str(self) -> &'static str {
+            match self {
+                Self::Replace => "replace",
+                Self::Skip => "skip",
+                Self::Fail => "fail",
+                Self::KeepBoth => "keep-both",
+            }
+        }
+    }
+
+    impl std::fmt::Display for ConflictPolicy {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
+    /// What [`FontManager::install_font_with_policy`] did.
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct ConflictOutcome {
+        pub policy: ConflictPolicy,
+        /// Installed files the font conflicted with. Left empty under
+        /// [`ConflictPolicy::Replace`] by platforms that resolve conflicts
+        /// inside the OS font manager.
+        pub conflicts: Vec<PathBuf>,
+        /// Where the font is installed now; `None` when it was skipped.
+        pub installed: Option<PathBuf>,
+    }
+
+    impl ConflictOutcome {
+        pub fn installed(policy: ConflictPolicy, conflicts: Vec<PathBuf>, path: PathBuf) -> Self {
+            Self {
+                policy,
+                conflicts,
+                installed: Some(path),
+            }
+        }
+
+        pub fn skipped(policy: ConflictPolicy, conflicts: Vec<PathBuf>) -> Self {
+            Self {
+                policy,
+                conflicts,
+                installed: None,
+            }
+        }
+
+        pub fn is_skipped(&self) -> bool {
+            self.installed.is_none()
+        }
+    }
+
+    /// Installed fonts that conflict with any face of the file at `path`
+    /// once it is installed at `target`, each file once.
+    pub fn conflicts_for_file(
+        installed: &[FontliftFontFaceInfo],
+        path: &Path,
+        target: &Path,
+    ) -> FontResult<Vec<PathBuf>> {
+        let mut found: Vec<PathBuf> = Vec::new();
+        for mut face in metadata::read_all_face_info(path)? {
+            face.source.path = target.to_path_buf();
+            for conflict in detect_conflicts(installed, &face) {
+                if !found.contains(&conflict.source.path) {
+                    found.push(conflict.source.path.clone());
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// An installed font that shares a PostScript name with a face being
+    /// installed but is a different font.
+    ///
+    /// Sharing a name normally means the same font, possibly another
+    /// version of it. Files whose contents differ while their versions are
+    /// the same (or unreadable) are not that: installing one over the other
+    /// would silently swap one design for another.
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct NameCollision {
+        pub postscript_name: String,
+        pub installed: PathBuf,
+        /// [`metadata::FontVersion::label`] of each file.
+        pub installed_version: String,
+        pub candidate_version: String,
+    }
+
+    impl std::fmt::Display for NameCollision {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(
+                f,
+                "{} is already installed from {} (version {}); this is a different font with the same name (version {})",
+                self.postscript_name,
+                self.installed.display(),
+                self.installed_version,
+                self.candidate_version
+            )
+        }
+    }
+
+    /// Installed fonts that share a PostScript name with a face of the file
+    /// at `path` but are different fonts (see [`NameCollision`]), each
+    /// installed file once.
+    pub fn name_collisions(
+        installed: &[FontliftFontFaceInfo],
+        path: &Path,
+    ) -> FontResult<Vec<NameCollision>> {
+        let mut found: Vec<NameCollision> = Vec::new();
+        let mut candidate = None;
+        for face in metadata::read_all_face_info(path)? {
+            for font in installed.iter().filter(|font| {
+                font.postscript_name
+                    .eq_ignore_ascii_case(&face.postscript_name)
+            }) {
+                let other = &font.source.path;
+                if found.iter().any(|c| &c.installed == other) {
+                    continue;
+                }
+                // An installed file that is gone or unreadable has nothing
+                // to protect.
+                let (Ok(other_digest), Ok(other_version)) =
+                    (digest::sha256_file(other), metadata::read_version(other))
+                else {
+                    continue;
+                };
+                let (digest, version) = match &candidate {
+                    Some(known) => known,
+                    None => candidate
+                        .insert((digest::sha256_file(path)?, metadata::read_version(path)?)),
+                };
+                if digest::digests_match(digest, &other_digest) {
+                    continue;
+                }
+                if let (Some(a), Some(b)) = (version.number(), other_version.number()) {
+                    if a != b {
+                        continue;
+                    }
+                }
+                found.push(NameCollision {
+                    postscript_name: font.postscript_name.clone(),
+                    installed: other.clone(),
+                    installed_version: other_version.label(),
+                    candidate_version: version.label(),
+                });
+            }
+        }
+        Ok(found)
+    }
+
+    /// What `--upgrade-only` decides for a font that clashes with
+    /// installed files.
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(tag = "upgrade", rename_all = "snake_case")]
+    pub enum Upgrade {
+        /// The candidate is newer than every clashing install.
+        Replace {
+            installed: String,
+            candidate: String,
+        },
+        /// An installed version is the same or newer, or a version is
+        /// unknown.
+        Skip {
+            installed: String,
+            candidate: String,
+        },
+    }
+
+    impl std::fmt::Display for Upgrade {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Upgrade::Replace {
+                    installed,
+                    candidate,
+                } => write!(f, "upgrading {installed} to {candidate}"),
+                Upgrade::Skip {
+                    installed,
+                    candidate,
+                } if installed == "unknown" || candidate == "unknown" => write!(
+                    f,
+                    "cannot compare installed version {installed} with {candidate}"
+                ),
+                Upgrade::Skip {
+                    installed,
+                    candidate,
+                } => write!(f, "installed version {installed} ≥ {candidate}"),
+            }
+        }
+    }
+
+    /// Compare the font at `candidate` with the `installed` files it clashes
+    /// with, by [`metadata::FontVersion::number`]. Only a candidate newer
+    /// than all of them replaces them.
+    pub fn check_upgrade(candidate: &Path, installed: &[PathBuf]) -> FontResult<Upgrade> {
+        let candidate = metadata::read_version(candidate)?;
+        let mut newest: Option<metadata::FontVersion> = None;
+        for path in installed {
+            // An installed file that no longer parses has no version to keep.
+            let Ok(version) = metadata::read_version(path) else {
+                continue;
+            };
+            let newer = match (&newest, version.number()) {
+                (None, _) => true,
+                (Some(current), Some(n)) => current.number().map_or(true, |c| n > c),
+                (Some(_), None) => false,
+            };
+            if newer {
+                newest = Some(version);
+            }
+        }
+
+        let newest = newest.unwrap_or_default();
+        let is_newer = match (newest.number(), candidate.number()) {
+            (Some(installed), Some(candidate)) => candidate > installed,
+            _ => false,
+        };
+        let (installed, candidate) = (newest.label(), candidate.label());
+        Ok(if is_newer {
+            Upgrade::Replace {
+                installed,
+                candidate,
+            }
+        } else {
+            Upgrade::Skip {
+                installed,
+                candidate,
+            }
+        })
+    }
+
+    /// The first of `Name (2).ttf`, `Name (3).ttf`, ... next to `target`
+    /// that does not exist yet, for [`ConflictPolicy::KeepBoth`].
+    pub fn keep_both_path(target: &Path) -> PathBuf {
+        let stem = target
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let extension = target
+            .extension()
+            .map(|e| format!(".{}", e.to_string_lossy()))
+            .unwrap_or_default();
+        (2..)
+            .map(|n| target.with_file_name(format!("{stem} ({n}){extension}")))
+            .find(|candidate| !candidate.exists())
+            .expect("an unused file name")
+    }
+
+    /// The default [`FontManager::install_font_with_policy`]: checks for
+    /// conflicts, then installs with [`FontManager::install_font`].
+    ///
+    /// [`ConflictPolicy::Replace`] goes straight to `install_font`, which
+    /// resolves conflicts the platform's way. The other policies list the
+    /// installed fonts first; `source.path` is where the font is registered.
+    pub fn install_with_policy<M: FontManager + ?Sized>(
+        manager: &M,
+        source: &FontliftFontSource,
+        policy: ConflictPolicy,
+    ) -> FontResult<ConflictOutcome> {
+        if policy == ConflictPolicy::Replace {
+            manager.install_font(source)?;
+            return Ok(ConflictOutcome::installed(
+                policy,
+                Vec::new(),
+                source.path.clone(),
+            ));
+        }
+
+        let installed = manager.list_installed_fonts()?;
+        let conflicts = conflicts_for_file(&installed, &source.path, &source.path)?;
+        match policy {
+            ConflictPolicy::Skip if !conflicts.is_empty() => {
+                Ok(ConflictOutcome::skipped(policy, conflicts))
+            }
+            ConflictPolicy::Fail if !conflicts.is_empty() => {
+                Err(FontError::AlreadyInstalled(conflicts[0].clone()))
+            }
+            _ => {
+                manager.install_font(source)?;
+                Ok(ConflictOutcome::installed(
+                    policy,
+                    conflicts,
+                    source.path.clone(),
+                ))
+            }
+        }
     }
 
     /// Find installed fonts that would conflict with `candidate`.
     ///
     /// Returns references to entries in `installed` that share any of:
-    /// path, PostScript name, or family+style (all case-insensitive).
+    /// path, PostScript name, or family+style. Names compare
+    /// case-insensitively; paths only where the volume does.
     /// Each conflicting font appears at most once, even if it matches
     /// on multiple criteria.
     pub fn detect_conflicts<'a>(
         installed: &'a [FontliftFontFaceInfo],
         candidate: &FontliftFontFaceInfo,
     ) -> Vec<&'a FontliftFontFaceInfo> {
-        let candidate_path = normalize(&candidate.source.path);
+        let candidate_path = path_case::comparison_key(&candidate.source.path);
         let candidate_post = candidate.postscript_name.to_lowercase();
         let candidate_family = candidate.family_name.to_lowercase();
         let candidate_style = candidate.style.to_lowercase();
@@ -554,7 +1824,7 @@ pub mod conflicts {
         installed
             .iter()
             .filter(|font| {
-                let path = normalize(&font.source.path);
+                let path = path_case::comparison_key(&font.source.path);
                 let same_path = path == candidate_path;
                 let same_post = font.postscript_name.eq_ignore_ascii_case(&candidate_post);
                 let same_family_style = font.family_name.eq_ignore_ascii_case(&candidate_family)
@@ -564,7 +1834,7 @@ pub mod conflicts {
             })
             .filter(|font| {
                 // guarantee unique paths in output for predictable handling
-                seen_paths.insert(normalize(&font.source.path))
+                seen_paths.insert(path_case::comparison_key(&font.source.path))
             })
             .collect()
     }
@@ -609,7 +1879,10 @@ impl FontManager for DummyFontManager {
         ))
     }
 
-    fn clear_font_caches(&self, _scope: FontScope) -> FontResult<()> {
+    fn clear_font_caches(
+        &self,
+        _strategy: cache::CacheClearStrategy,
+    ) -> FontResult<cache::CacheClearResult> {
         Err(FontError::UnsupportedOperation(
             "Cache clearing not implemented for this platform".to_string(),
         ))
@@ -619,7 +1892,32 @@ impl FontManager for DummyFontManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn errors_serialize_code_message_and_suggestion_separately() {
+        let error = FontError::AlreadyInstalled(PathBuf::from("/Library/Fonts/A.otf"));
+        assert_eq!(error.code(), ErrorCode::AlreadyInstalled);
+        assert_eq!(
+            error.to_string(),
+            format!("{}\n→ {}", error.message(), error.suggestion())
+        );
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "already_installed");
+        assert_eq!(
+            json["message"],
+            "Font already installed: /Library/Fonts/A.otf"
+        );
+        assert!(json["suggestion"]
+            .as_str()
+            .unwrap()
+            .contains("fontlift uninstall"));
+
+        let io = FontError::from(std::io::Error::other("disk full"));
+        assert_eq!(serde_json::to_value(io.code()).unwrap(), "io");
+        assert_eq!(io.message(), "IO error: disk full");
+    }
 
     #[test]
     fn detects_protected_system_font_paths() {
@@ -640,6 +1938,66 @@ mod tests {
         assert!(!protection::is_protected_system_font_path(&win_user));
     }
 
+    #[test]
+    fn protection_policy_adds_dirs_and_names_in_deny_or_warn_mode() {
+        use protection::{ProtectionMode, ProtectionPolicy};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let policy_path = tmp.path().join("protection.json");
+        assert_eq!(
+            ProtectionPolicy::load(&policy_path).unwrap(),
+            ProtectionPolicy::default()
+        );
+
+        std::fs::write(
+            &policy_path,
+            r#"{ "protected_dirs": ["/opt/Corp/Fonts/"],
+                 "protected_postscript_names": ["CorpUI-*", "Brand-Regular"] }"#,
+        )
+        .unwrap();
+        let mut policy = ProtectionPolicy::load(&policy_path).unwrap();
+        assert_eq!(policy.mode, ProtectionMode::Deny);
+
+        let corp = PathBuf::from("/opt/corp/fonts/Sub/Corp.otf");
+        assert!(policy.is_protected_path(&corp));
+        assert!(policy.is_protected_path(Path::new("/System/Library/Fonts/SFNS.ttf")));
+        assert!(!policy.is_protected_path(Path::new("/opt/corp/fontsextra/X.otf")));
+
+        let user_font = PathBuf::from("/Users/me/Library/Fonts/CorpUI.otf");
+        assert!(matches!(
+            policy.check(&corp, &[]),
+            Err(FontError::SystemFontProtection(_))
+        ));
+        assert!(policy.check(&user_font, &["corpui-bold"]).is_err());
+        assert!(policy.check(&user_font, &["Brand-Bold"]).unwrap().is_none());
+
+        policy.mode = ProtectionMode::Warn;
+        let warning = policy.check(&user_font, &["CorpUI-Bold"]).unwrap();
+        assert!(warning.unwrap().contains("CorpUI-Bold"));
+
+        std::fs::write(&policy_path, "{ not json").unwrap();
+        assert!(ProtectionPolicy::load(&policy_path).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn protection_follows_linked_directories() {
+        use protection::ProtectionPolicy;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let protected = tmp.path().join("corp");
+        std::fs::create_dir_all(&protected).unwrap();
+        let alias = tmp.path().join("alias");
+        std::os::unix::fs::symlink(&protected, &alias).unwrap();
+        let policy = ProtectionPolicy {
+            protected_dirs: vec![protected.clone()],
+            ..ProtectionPolicy::default()
+        };
+
+        assert!(policy.is_protected_path(&alias.join("Corp.otf")));
+        assert!(!policy.is_protected_path(&tmp.path().join("Corp.otf")));
+    }
+
     #[test]
     fn deduplication_is_deterministic_by_name_and_path() {
         let fonts = vec![
@@ -780,6 +2138,144 @@ mod tests {
         assert!(paths.iter().all(|p| p.contains("alpha")));
     }
 
+    #[derive(Default)]
+    struct Installed(std::sync::Mutex<Vec<PathBuf>>);
+
+    impl FontManager for Installed {
+        fn install_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+            self.0.lock().unwrap().push(source.path.clone());
+            Ok(())
+        }
+        fn uninstall_font(&self, _source: &FontliftFontSource) -> FontResult<()> {
+            unreachable!()
+        }
+        fn remove_font(&self, _source: &FontliftFontSource) -> FontResult<()> {
+            unreachable!()
+        }
+        fn is_font_installed(&self, source: &FontliftFontSource) -> FontResult<bool> {
+            Ok(self.0.lock().unwrap().contains(&source.path))
+        }
+        fn list_installed_fonts(&self) -> FontResult<Vec<FontliftFontFaceInfo>> {
+            let paths = self.0.lock().unwrap().clone();
+            Ok(paths
+                .iter()
+                .flat_map(|p| metadata::read_all_face_info(p).unwrap())
+                .collect())
+        }
+        fn clear_font_caches(
+            &self,
+            _: crate::cache::CacheClearStrategy,
+        ) -> FontResult<crate::cache::CacheClearResult> {
+            Ok(crate::cache::CacheClearResult::default())
+        }
+    }
+
+    #[test]
+    fn conflict_policies_skip_fail_or_keep_both() {
+        use conflicts::{ConflictOutcome, ConflictPolicy};
+        use fontlift_testfonts::TestFont;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let (old_dir, new_dir) = (tmp.path().join("old"), tmp.path().join("new"));
+        std::fs::create_dir_all(&old_dir).unwrap();
+        std::fs::create_dir_all(&new_dir).unwrap();
+        let old = TestFont::new("Policy Sans").write_to(&old_dir).unwrap();
+        let new = TestFont::new("Policy Sans").write_to(&new_dir).unwrap();
+        let manager = Installed::default();
+        manager
+            .install_font(&FontliftFontSource::new(old.clone()))
+            .unwrap();
+        let source = FontliftFontSource::new(new.clone());
+
+        let skipped = manager
+            .install_font_with_policy(&source, ConflictPolicy::Skip)
+            .unwrap();
+        assert_eq!(
+            skipped,
+            ConflictOutcome::skipped(ConflictPolicy::Skip, vec![old.clone()])
+        );
+        let err = manager
+            .install_font_with_policy(&source, ConflictPolicy::Fail)
+            .unwrap_err();
+        assert!(matches!(err, FontError::AlreadyInstalled(ref p) if *p == old));
+        assert_eq!(manager.0.lock().unwrap().len(), 1);
+
+        let kept = manager
+            .install_font_with_policy(&source, ConflictPolicy::KeepBoth)
+            .unwrap();
+        assert_eq!(kept.conflicts, vec![old.clone()]);
+        assert_eq!(*manager.0.lock().unwrap(), [old.clone(), new]);
+
+        assert_eq!(
+            conflicts::keep_both_path(&old),
+            old.with_file_name("PolicySans-Regular (2).ttf")
+        );
+        assert_eq!(
+            serde_json::to_value(ConflictPolicy::KeepBoth).unwrap(),
+            "keep-both"
+        );
+    }
+
+    #[test]
+    fn upgrades_only_replace_older_versions() {
+        use conflicts::{check_upgrade, Upgrade};
+        use fontlift_testfonts::TestFont;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let write = |dir: &str, revision: f64| {
+            let dir = tmp.path().join(dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            TestFont::new("Upgrade Sans")
+                .with_revision(revision)
+                .write_to(&dir)
+                .unwrap()
+        };
+        let installed = write("installed", 2.003);
+        let older = write("older", 2.001);
+        let newer = write("newer", 2.1);
+
+        let skip = check_upgrade(&older, std::slice::from_ref(&installed)).unwrap();
+        assert_eq!(skip.to_string(), "installed version 2.003 ≥ 2.001");
+        assert!(matches!(skip, Upgrade::Skip { .. }));
+        assert!(matches!(
+            check_upgrade(&installed, std::slice::from_ref(&installed)).unwrap(),
+            Upgrade::Skip { .. }
+        ));
+        assert_eq!(
+            check_upgrade(&newer, &[older, installed]).unwrap(),
+            Upgrade::Replace {
+                installed: "2.003".to_string(),
+                candidate: "2.100".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn same_postscript_name_with_different_contents_collides() {
+        use conflicts::name_collisions;
+        use fontlift_testfonts::TestFont;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let write = |dir: &str, font: TestFont| {
+            let dir = tmp.path().join(dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            font.write_to(&dir).unwrap()
+        };
+        let installed = write("installed", TestFont::new("Twin Sans"));
+        let same = write("same", TestFont::new("Twin Sans"));
+        let upgrade = write("upgrade", TestFont::new("Twin Sans").with_revision(3.0));
+        let impostor = write("impostor", TestFont::new("Twin Sans").with_x_height(612));
+        let inventory = [metadata::read_face_info(&installed).unwrap()];
+
+        assert!(name_collisions(&inventory, &same).unwrap().is_empty());
+        assert!(name_collisions(&inventory, &upgrade).unwrap().is_empty());
+        let collisions = name_collisions(&inventory, &impostor).unwrap();
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].postscript_name, "TwinSans-Regular");
+        assert_eq!(collisions[0].installed, installed);
+        assert!(collisions[0].to_string().contains("a different font"));
+    }
+
     #[test]
     fn test_scope_description() {
         assert_eq!(FontScope::User.description(), "user-level");
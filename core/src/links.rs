@@ -0,0 +1,256 @@
+//! Hard-link-aware removal and copying of font files.
+//!
+//! A font file can have more than one name. Users hard-link fonts into their
+//! fonts folder, and a font linked out of the download store
+//! ([`crate::content_store`]) shares its bytes with the stored blob. Removing
+//! one name leaves the data alive under the others, and writing into one
+//! name changes every other name too.
+//!
+//! Code that deletes or overwrites files in managed directories therefore
+//! uses [`remove_file`], which reports whether the data went or only a link,
+//! and [`copy_file`], which writes a new file and renames it into place.
+//!
+//! The rename also makes the copy atomic: until it happens the destination
+//! keeps its previous contents, so a crash mid-copy leaves the old font
+//! installed plus a partial file next to it ([`partial_path`]) rather than
+//! no font at all. Journal recovery removes such leftovers with
+//! [`remove_partial`].
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How many names the file at `path` has; 1 for an ordinary file.
+#[cfg(unix)]
+pub fn link_count(path: &Path) -> io::Result<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Ok(fs::metadata(path)?.nlink())
+}
+
+/// How many names the file at `path` has; 1 for an ordinary file.
+#[cfg(windows)]
+pub fn link_count(path: &Path) -> io::Result<u64> {
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::Storage::FileSystem::{
+        GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+    };
+
+    let file = fs::File::open(path)?;
+    let mut info = BY_HANDLE_FILE_INFORMATION::default();
+    // SAFETY: the handle belongs to `file`, which is open for the whole call.
+    unsafe { GetFileInformationByHandle(HANDLE(file.as_raw_handle() as isize), &mut info) }
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(u64::from(info.nNumberOfLinks))
+}
+
+/// How many names the file at `path` has. Platforms without hard links
+/// always report 1.
+#[cfg(not(any(unix, windows)))]
+pub fn link_count(path: &Path) -> io::Result<u64> {
+    fs::metadata(path).map(|_| 1)
+}
+
+/// What [`remove_file`] removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "removed", rename_all = "snake_case")]
+pub enum Removed {
+    /// The file's only name: its data is gone.
+    File,
+    /// One of several hard links: `remaining` other names keep the data.
+    Link { remaining: u64 },
+    /// A symbolic link: the file it pointed to is untouched.
+    Symlink,
+}
+
+impl std::fmt::Display for Removed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Removed::File => f.write_str("deleted the file"),
+            Removed::Symlink => f.write_str("removed a symbolic link; its target is untouched"),
+            Removed::Link { remaining: 1 } => {
+                f.write_str("removed a hard link; 1 other link keeps the data")
+            }
+            Removed::Link { remaining } => write!(
+                f,
+                "removed a hard link; {remaining} other links keep the data"
+            ),
+        }
+    }
+}
+
+/// Remove `path` and say whether that deleted the data or one link to it.
+/// A symbolic link is removed as itself, never through to its target.
+pub fn remove_file(path: &Path) -> io::Result<Removed> {
+    if crate::symlinks::is_symlink(path) {
+        fs::remove_file(path)?;
+        return Ok(Removed::Symlink);
+    }
+    let links = link_count(path)?;
+    fs::remove_file(path)?;
+    Ok(match links {
+        0 | 1 => Removed::File,
+        n => Removed::Link { remaining: n - 1 },
+    })
+}
+
+/// Copy `from` to `to` like [`fs::copy`], but atomically: the bytes go to
+/// [`partial_path`] in the same directory, are flushed to disk, and the
+/// partial file is then renamed over `to`.
+///
+/// A `to` which is one of several hard links only loses that name in the
+/// rename, so the copy gets its own data instead of overwriting the file
+/// behind the other links.
+pub fn copy_file(from: &Path, to: &Path) -> io::Result<u64> {
+    copy_through_partial(from, to, io::copy)
+}
+
+/// Where [`copy_file`] writes `to` before renaming it into place: a hidden
+/// `.<name>.fontlift-partial` beside it.
+pub fn partial_path(to: &Path) -> PathBuf {
+    let name = to.file_name().unwrap_or_default().to_string_lossy();
+    to.with_file_name(format!(".{name}.fontlift-partial"))
+}
+
+/// Remove the partial file an interrupted [`copy_file`] to `to` left behind.
+/// Returns whether there was one.
+pub fn remove_partial(to: &Path) -> io::Result<bool> {
+    match fs::remove_file(partial_path(to)) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// [`copy_file`] with the byte copy supplied by the caller, so tests can
+/// fail it part way.
+fn copy_through_partial(
+    from: &Path,
+    to: &Path,
+    write: impl FnOnce(&mut fs::File, &mut fs::File) -> io::Result<u64>,
+) -> io::Result<u64> {
+    let mut source = fs::File::open(from)?;
+    let permissions = source.metadata()?.permissions();
+    let partial = partial_path(to);
+    let result = (|| {
+        let mut out = fs::File::create(&partial)?;
+        let copied = write(&mut source, &mut out)?;
+        out.sync_all()?;
+        drop(out);
+        fs::set_permissions(&partial, permissions)?;
+        fs::rename(&partial, to)?;
+        Ok(copied)
+    })();
+    match result {
+        Ok(copied) => {
+            sync_parent(to);
+            Ok(copied)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            Err(e)
+        }
+    }
+}
+
+/// Flush the directory entry of a rename to disk. Only Unix can open a
+/// directory for this; a failure leaves the rename as durable as the file
+/// system makes it anyway.
+fn sync_parent(path: &Path) {
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        if let Ok(dir) = fs::File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removing_a_link_keeps_the_data() {
+        let tmp = tempfile::tempdir().unwrap();
+        let original = tmp.path().join("Linked-Regular.ttf");
+        let link = tmp.path().join("Fonts-Linked-Regular.ttf");
+        fs::write(&original, b"font").unwrap();
+        fs::hard_link(&original, &link).unwrap();
+        assert_eq!(link_count(&link).unwrap(), 2);
+
+        assert_eq!(remove_file(&link).unwrap(), Removed::Link { remaining: 1 });
+        assert_eq!(fs::read(&original).unwrap(), b"font");
+        assert_eq!(remove_file(&original).unwrap(), Removed::File);
+        assert!(!original.exists());
+    }
+
+    #[test]
+    fn copying_over_a_link_leaves_the_other_names_alone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let blob = tmp.path().join("blob");
+        let installed = tmp.path().join("Shared-Regular.ttf");
+        let update = tmp.path().join("update.ttf");
+        fs::write(&blob, b"stored").unwrap();
+        fs::hard_link(&blob, &installed).unwrap();
+        fs::write(&update, b"updated").unwrap();
+
+        copy_file(&update, &installed).unwrap();
+        assert_eq!(fs::read(&installed).unwrap(), b"updated");
+        assert_eq!(fs::read(&blob).unwrap(), b"stored");
+        assert_eq!(link_count(&blob).unwrap(), 1);
+    }
+
+    #[test]
+    fn a_copy_that_dies_part_way_keeps_the_previous_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let installed = tmp.path().join("Atomic-Regular.ttf");
+        let update = tmp.path().join("update.ttf");
+        fs::write(&installed, b"version 1").unwrap();
+        fs::write(&update, b"version 2, somewhat longer").unwrap();
+
+        // The write fails after half the bytes, as a full disk or a killed
+        // process would leave it.
+        let err = copy_through_partial(&update, &installed, |_, partial| {
+            use std::io::Write;
+            partial.write_all(b"version 2, so")?;
+            Err(io::Error::other("simulated crash"))
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "simulated crash");
+        assert_eq!(fs::read(&installed).unwrap(), b"version 1");
+        assert!(!partial_path(&installed).exists());
+
+        // A process killed outright cannot clean up: the partial stays next
+        // to the untouched original until recovery removes it.
+        fs::write(partial_path(&installed), b"version 2, so").unwrap();
+        assert_eq!(fs::read(&installed).unwrap(), b"version 1");
+        assert!(remove_partial(&installed).unwrap());
+        assert!(!remove_partial(&installed).unwrap());
+
+        assert_eq!(copy_file(&update, &installed).unwrap(), 26);
+        assert_eq!(fs::read(&installed).unwrap(), b"version 2, somewhat longer");
+        assert!(!partial_path(&installed).exists());
+    }
+
+    #[test]
+    fn copies_keep_the_source_permissions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("ReadOnly-Regular.ttf");
+        let target = tmp.path().join("Fonts-ReadOnly-Regular.ttf");
+        fs::write(&source, b"font").unwrap();
+        let mut permissions = fs::metadata(&source).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&source, permissions).unwrap();
+
+        copy_file(&source, &target).unwrap();
+        assert!(fs::metadata(&target).unwrap().permissions().readonly());
+    }
+}
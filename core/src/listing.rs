@@ -0,0 +1,429 @@
+//! Filters for font listings (`fontlift list --scope/--family/...`).
+//!
+//! A [`ListFilter`] narrows [`FontManager::list_installed_fonts_filtered`]
+//! to the faces a caller asked for. Most criteria can be decided from the
+//! path and scope alone — the extension, the file's modification time,
+//! which font directory it sits in — so platforms that have to parse every
+//! file to build a listing check [`ListFilter::matches_file`] first and
+//! only open the files that can still match. The remaining criteria (family
+//! and style) need the parsed face and go through [`ListFilter::matches`].
+//!
+//! A [`FontListing`] is a listing together with the font directories,
+//! registry keys and files that could not be read while building it, so a
+//! partial enumeration on a locked-down machine says so instead of quietly
+//! returning fewer fonts.
+//!
+//! [`FontManager::list_installed_fonts_filtered`]: crate::FontManager::list_installed_fonts_filtered
+
+use crate::{FontError, FontResult, FontScope, FontliftFontFaceInfo};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// File formats `--format` selects by extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatFilter {
+    /// `.ttf` files.
+    Ttf,
+    /// `.otf` files.
+    Otf,
+    /// Collections: `.ttc` and `.otc` files.
+    Ttc,
+}
+
+impl FormatFilter {
+    /// Lowercase extensions this format covers.
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            FormatFilter::Ttf => &["ttf"],
+            FormatFilter::Otf => &["otf"],
+            FormatFilter::Ttc => &["ttc", "otc"],
+        }
+    }
+}
+
+/// Styles `--style` selects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StyleFilter {
+    /// Italic or oblique faces.
+    Italic,
+    /// Faces of weight 600 (SemiBold) or heavier.
+    Bold,
+}
+
+/// Which installed faces a listing includes. Every criterion that is set
+/// must match; the default matches everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListFilter {
+    /// Only faces registered in this scope.
+    pub scope: Option<FontScope>,
+    /// Case-insensitive glob (`*`, `?`) over the family name.
+    pub family: Option<String>,
+    /// Only files of this format.
+    pub format: Option<FormatFilter>,
+    /// Only faces of this style.
+    pub style: Option<StyleFilter>,
+    /// Only files modified at or after this time.
+    pub modified_since: Option<SystemTime>,
+}
+
+impl ListFilter {
+    /// Does this filter let everything through?
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Could a face in the file at `path`, registered in `scope`, match?
+    ///
+    /// Decided without opening the file; `false` means no face in it can
+    /// match, so the caller need not parse it.
+    pub fn matches_file(&self, path: &Path, scope: Option<FontScope>) -> bool {
+        if let Some(wanted) = self.scope {
+            if scope != Some(wanted) {
+                return false;
+            }
+        }
+        if let Some(format) = self.format {
+            let ext = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(str::to_lowercase)
+                .unwrap_or_default();
+            if !format.extensions().contains(&ext.as_str()) {
+                return false;
+            }
+        }
+        if let Some(cutoff) = self.modified_since {
+            match std::fs::metadata(path).and_then(|m| m.modified()) {
+                Ok(modified) if modified >= cutoff => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Does `face` match every criterion?
+    pub fn matches(&self, face: &FontliftFontFaceInfo) -> bool {
+        if !self.matches_file(&face.source.path, face.source.scope) {
+            return false;
+        }
+        if let Some(pattern) = &self.family {
+            if !glob_match(pattern, &face.family_name) {
+                return false;
+            }
+        }
+        match self.style {
+            Some(StyleFilter::Italic) => is_italic(face),
+            Some(StyleFilter::Bold) => is_bold(face),
+            None => true,
+        }
+    }
+
+    /// Keep the faces that match.
+    pub fn apply(&self, fonts: Vec<FontliftFontFaceInfo>) -> Vec<FontliftFontFaceInfo> {
+        if self.is_empty() {
+            return fonts;
+        }
+        fonts
+            .into_iter()
+            .filter(|face| self.matches(face))
+            .collect()
+    }
+}
+
+/// The faces of a listing and what was skipped while building it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FontListing {
+    pub fonts: Vec<FontliftFontFaceInfo>,
+    /// Directories, registry keys and files that could not be read, e.g.
+    /// "cannot read C:\Windows\Fonts: Access is denied". Fonts behind
+    /// them are missing from `fonts`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl FontListing {
+    /// A complete listing of `fonts`.
+    pub fn new(fonts: Vec<FontliftFontFaceInfo>) -> Self {
+        Self {
+            fonts,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Record something that could not be read, once: a file both
+    /// registered and in a font directory is only reported once.
+    pub fn warn(&mut self, warning: impl Into<String>) {
+        let warning = warning.into();
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Some fonts may be missing because something could not be read.
+    pub fn is_partial(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+}
+
+fn is_italic(face: &FontliftFontFaceInfo) -> bool {
+    let style = face.style.to_lowercase();
+    face.italic.unwrap_or(false) || style.contains("italic") || style.contains("oblique")
+}
+
+fn is_bold(face: &FontliftFontFaceInfo) -> bool {
+    match face.weight {
+        Some(weight) => weight >= 600,
+        None => face.style.to_lowercase().contains("bold"),
+    }
+}
+
+/// Case-insensitive match of `text` against `pattern`, where `*` matches
+/// any run of characters and `?` exactly one.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    // Greedy match, backtracking to the last `*` on a mismatch.
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Parse `--modified-since`: a UTC date (`2024-05-01`), a date and time
+/// (`2024-05-01T12:30:00`, optionally ending in `Z`), or an age before now
+/// (`7d`, see [`crate::history::parse_age`]).
+pub fn parse_since(text: &str) -> FontResult<SystemTime> {
+    let text = text.trim();
+    if let Ok(age) = crate::history::parse_age(text) {
+        return Ok(SystemTime::now().checked_sub(age).unwrap_or(UNIX_EPOCH));
+    }
+
+    let invalid = || {
+        FontError::InvalidArgument(format!(
+            "'{text}' is not a date; use YYYY-MM-DD, YYYY-MM-DDTHH:MM:SS or an age such as 7d"
+        ))
+    };
+    let (date, time) = match text.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time.trim_end_matches('Z'))),
+        None => (text, None),
+    };
+    let numbers = |part: &str, sep: char| -> Option<Vec<u32>> {
+        part.split(sep).map(|n| n.parse().ok()).collect()
+    };
+
+    let [year, month, day] = numbers(date, '-')
+        .and_then(|parts| <[u32; 3]>::try_from(parts).ok())
+        .ok_or_else(invalid)?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || year < 1970 {
+        return Err(invalid());
+    }
+    let seconds = match time {
+        None => 0,
+        Some(time) => {
+            let parts = numbers(time, ':').ok_or_else(invalid)?;
+            let (hour, minute, second) = match parts.as_slice() {
+                [h, m] => (*h, *m, 0),
+                [h, m, s] => (*h, *m, *s),
+                _ => return Err(invalid()),
+            };
+            if hour > 23 || minute > 59 || second > 60 {
+                return Err(invalid());
+            }
+            u64::from(hour * 3_600 + minute * 60 + second)
+        }
+    };
+
+    let days = days_from_civil(i64::from(year), month, day);
+    Ok(UNIX_EPOCH + Duration::from_secs(days as u64 * 86_400 + seconds))
+}
+
+/// Days since 1970-01-01 for a civil date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(month);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FontliftFontSource;
+    use std::path::PathBuf;
+
+    fn face(
+        path: &str,
+        family: &str,
+        style: &str,
+        weight: u16,
+        scope: FontScope,
+    ) -> FontliftFontFaceInfo {
+        let mut info = FontliftFontFaceInfo::new(
+            FontliftFontSource::new(PathBuf::from(path)).with_scope(Some(scope)),
+            format!("{}-{}", family.replace(' ', ""), style.replace(' ', "")),
+            format!("{family} {style}"),
+            family.to_string(),
+            style.to_string(),
+        );
+        info.weight = Some(weight);
+        info.italic = Some(style.contains("Italic"));
+        info
+    }
+
+    #[test]
+    fn filters_by_scope_family_format_and_style() {
+        let fonts = vec![
+            face(
+                "/u/Inter-Regular.ttf",
+                "Inter",
+                "Regular",
+                400,
+                FontScope::User,
+            ),
+            face(
+                "/u/Inter-BoldItalic.otf",
+                "Inter",
+                "Bold Italic",
+                700,
+                FontScope::User,
+            ),
+            face("/s/Roboto.ttc", "Roboto", "Italic", 400, FontScope::System),
+            face(
+                "/s/InterDisplay-Bold.TTF",
+                "Inter Display",
+                "Bold",
+                700,
+                FontScope::System,
+            ),
+        ];
+        let names = |filter: ListFilter| -> Vec<String> {
+            filter
+                .apply(fonts.clone())
+                .into_iter()
+                .map(|f| f.postscript_name)
+                .collect()
+        };
+
+        assert_eq!(names(ListFilter::default()).len(), 4);
+        assert_eq!(
+            names(ListFilter {
+                scope: Some(FontScope::System),
+                ..Default::default()
+            }),
+            vec!["Roboto-Italic", "InterDisplay-Bold"]
+        );
+        assert_eq!(
+            names(ListFilter {
+                family: Some("inter*".into()),
+                style: Some(StyleFilter::Bold),
+                ..Default::default()
+            }),
+            vec!["Inter-BoldItalic", "InterDisplay-Bold"]
+        );
+        assert_eq!(
+            names(ListFilter {
+                format: Some(FormatFilter::Ttf),
+                ..Default::default()
+            }),
+            vec!["Inter-Regular", "InterDisplay-Bold"]
+        );
+        assert_eq!(
+            names(ListFilter {
+                style: Some(StyleFilter::Italic),
+                format: Some(FormatFilter::Ttc),
+                ..Default::default()
+            }),
+            vec!["Roboto-Italic"]
+        );
+    }
+
+    #[test]
+    fn modified_since_checks_the_file_time() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("Fresh.ttf");
+        std::fs::write(&path, b"font").unwrap();
+
+        let past = ListFilter {
+            modified_since: Some(parse_since("2000-01-01").unwrap()),
+            ..Default::default()
+        };
+        assert!(past.matches_file(&path, None));
+        let future = ListFilter {
+            modified_since: Some(SystemTime::now() + Duration::from_secs(3_600)),
+            ..Default::default()
+        };
+        assert!(!future.matches_file(&path, None));
+        assert!(!past.matches_file(&tmp.path().join("missing.ttf"), None));
+    }
+
+    #[test]
+    fn listing_warnings_mark_it_partial_once_each() {
+        let mut listing = FontListing::new(vec![face(
+            "/u/Inter-Regular.ttf",
+            "Inter",
+            "Regular",
+            400,
+            FontScope::User,
+        )]);
+        assert!(!listing.is_partial());
+        assert!(!serde_json::to_string(&listing)
+            .unwrap()
+            .contains("warnings"));
+
+        listing.warn("skipped /s/Locked.ttf: Access is denied");
+        listing.warn("skipped /s/Locked.ttf: Access is denied");
+        listing.warn(r"cannot read HKLM\Software\Microsoft\Windows NT\CurrentVersion\Fonts");
+        assert!(listing.is_partial());
+        assert_eq!(listing.warnings.len(), 2);
+        assert_eq!(listing.fonts.len(), 1);
+    }
+
+    #[test]
+    fn globs_and_dates_parse() {
+        assert!(glob_match("Noto*", "Noto Sans CJK"));
+        assert!(glob_match("*sans*", "Noto Sans"));
+        assert!(glob_match("Inte?", "Inter"));
+        assert!(!glob_match("Inter", "Inter Display"));
+        assert!(!glob_match("*Mono", "Mono Sans"));
+
+        let day = |text| {
+            parse_since(text)
+                .unwrap()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        };
+        assert_eq!(day("1970-01-02"), 86_400);
+        assert_eq!(day("2024-03-01"), 1_709_251_200);
+        assert_eq!(day("2024-03-01T01:02:03Z"), 1_709_251_200 + 3_723);
+        assert!(parse_since("7d").unwrap() < SystemTime::now());
+        assert!(parse_since("2024-13-01").is_err());
+        assert!(parse_since("yesterday").is_err());
+    }
+}
@@ -0,0 +1,346 @@
+//! Portable inventory of installed fonts (`fontlift export` /
+//! `fontlift install --manifest`).
+//!
+//! A manifest records what a font setup looks like so it can be rebuilt on a
+//! new machine or after a reinstall:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "fontlift_version": "5.0.15",
+//!   "exported_at": 1767225600,
+//!   "fonts": [
+//!     {
+//!       "path": "/Users/ada/Library/Fonts/Inter-Bold.ttf",
+//!       "sha256": "9f86d0…",
+//!       "size": 412304,
+//!       "scope": "User",
+//!       "font_version": "4.000",
+//!       "reason": { "kind": "profile", "name": "web" },
+//!       "faces": [
+//!         { "postscript_name": "Inter-Bold", "full_name": "Inter Bold",
+//!           "family_name": "Inter", "style": "Bold" }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! There is one entry per file, so collections list all their faces under
+//! one path. Fonts in OS-owned directories are left out unless fontlift
+//! installed them: the new machine ships its own copies.
+//!
+//! When reinstalling, [`Manifest::resolve`] looks for each file at its
+//! recorded path first, then by SHA-256 (or, without a digest, by file name)
+//! in the directories the user points it at.
+
+use crate::install_db::{InstallDb, InstallReason};
+use crate::journal::systemtime_serde;
+use crate::{
+    digest, metadata, protection, validation, FontError, FontResult, FontScope,
+    FontliftFontFaceInfo,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Current manifest format version.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// One face inside a manifest entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestFace {
+    pub postscript_name: String,
+    pub full_name: String,
+    pub family_name: String,
+    pub style: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub face_index: Option<u32>,
+}
+
+/// One installed font file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFont {
+    pub path: PathBuf,
+    /// SHA-256 of the file; `None` when it could not be read at export time.
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub size: Option<u64>,
+    pub scope: FontScope,
+    /// `head.fontRevision`, formatted like `1.005`.
+    #[serde(default)]
+    pub font_version: Option<String>,
+    /// Why fontlift installed the file, when it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<InstallReason>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    pub faces: Vec<ManifestFace>,
+}
+
+impl ManifestFont {
+    /// First face's PostScript name, or the path, for messages.
+    pub fn label(&self) -> String {
+        self.faces
+            .first()
+            .map(|face| face.postscript_name.clone())
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+
+    fn accepts(&self, candidate: &Path) -> bool {
+        match &self.sha256 {
+            Some(expected) => digest::sha256_file(candidate)
+                .is_ok_and(|actual| digest::digests_match(&actual, expected)),
+            None => candidate.is_file(),
+        }
+    }
+}
+
+/// A font inventory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub fontlift_version: String,
+    #[serde(with = "systemtime_serde")]
+    pub exported_at: SystemTime,
+    pub fonts: Vec<ManifestFont>,
+}
+
+/// Where each manifest entry was found, and which ones were not.
+#[derive(Debug, Clone, Default)]
+pub struct ManifestResolution {
+    pub found: Vec<(ManifestFont, PathBuf)>,
+    pub missing: Vec<ManifestFont>,
+}
+
+impl Manifest {
+    /// Describe `installed` fonts, hashing each file once.
+    ///
+    /// Faces in OS-owned directories are skipped unless `db` records that
+    /// fontlift installed them. Install reasons and origins come from `db`.
+    pub fn from_installed(installed: &[FontliftFontFaceInfo], db: &InstallDb) -> Self {
+        let mut by_path: BTreeMap<PathBuf, ManifestFont> = BTreeMap::new();
+
+        for face in installed {
+            let path = &face.source.path;
+            let record = db.find(path);
+            if record.is_none() && protection::is_protected_system_font_path(path) {
+                continue;
+            }
+
+            let entry = by_path.entry(path.clone()).or_insert_with(|| ManifestFont {
+                path: path.clone(),
+                sha256: digest::sha256_file(path).ok(),
+                size: fs::metadata(path).ok().map(|m| m.len()),
+                scope: record
+                    .map(|r| r.scope)
+                    .or(face.source.scope)
+                    .unwrap_or(FontScope::User),
+                font_version: metadata::read_font_version(path).ok().flatten(),
+                reason: record.map(|r| r.reason.clone()),
+                origin: record.and_then(|r| r.origin.clone()),
+                faces: Vec::new(),
+            });
+
+            let manifest_face = ManifestFace {
+                postscript_name: face.postscript_name.clone(),
+                full_name: face.full_name.clone(),
+                family_name: face.family_name.clone(),
+                style: face.style.clone(),
+                face_index: face.source.face_index,
+            };
+            if !entry.faces.contains(&manifest_face) {
+                entry.faces.push(manifest_face);
+            }
+        }
+
+        Self {
+            version: MANIFEST_VERSION,
+            fontlift_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at: SystemTime::now(),
+            fonts: by_path.into_values().collect(),
+        }
+    }
+
+    /// Pretty-printed JSON.
+    pub fn to_json(&self) -> FontResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| FontError::InvalidFormat(format!("Failed to encode manifest: {e}")))
+    }
+
+    /// Write the manifest to `path` atomically.
+    pub fn save(&self, path: &Path) -> FontResult<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(FontError::IoError)?;
+        }
+        let tmp = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+        fs::write(&tmp, self.to_json()?).map_err(FontError::IoError)?;
+        fs::rename(&tmp, path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            FontError::IoError(e)
+        })
+    }
+
+    /// Find a local copy of every entry.
+    ///
+    /// The recorded path wins when it still holds the recorded bytes.
+    /// Otherwise the files under `search_roots` (recursively) are matched by
+    /// SHA-256, or by file name for entries exported without a digest.
+    pub fn resolve(&self, search_roots: &[PathBuf]) -> ManifestResolution {
+        let candidates = font_files_under(search_roots);
+        let mut resolution = ManifestResolution::default();
+
+        for font in &self.fonts {
+            let located = if font.accepts(&font.path) {
+                Some(font.path.clone())
+            } else {
+                let file_name = font.path.file_name();
+                candidates
+                    .iter()
+                    .filter(|c| font.sha256.is_some() || c.file_name() == file_name)
+                    .find(|c| font.accepts(c))
+                    .cloned()
+            };
+
+            match located {
+                Some(path) => resolution.found.push((font.clone(), path)),
+                None => resolution.missing.push(font.clone()),
+            }
+        }
+
+        resolution
+    }
+}
+
+/// Read a manifest JSON file.
+pub fn load_manifest(path: &Path) -> FontResult<Manifest> {
+    let content = fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let manifest: Manifest = serde_json::from_str(&content).map_err(|e| {
+        FontError::InvalidFormat(format!("Failed to parse manifest {}: {e}", path.display()))
+    })?;
+    if manifest.version > MANIFEST_VERSION {
+        return Err(FontError::UnsupportedOperation(format!(
+            "Manifest {} uses format version {}; this fontlift reads up to {MANIFEST_VERSION}",
+            path.display(),
+            manifest.version
+        )));
+    }
+    Ok(manifest)
+}
+
+pub(crate) fn font_files_under(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack: Vec<PathBuf> = roots.to_vec();
+    while let Some(path) = stack.pop() {
+        if path.is_dir() {
+            if let Ok(entries) = fs::read_dir(&path) {
+                stack.extend(entries.flatten().map(|entry| entry.path()));
+            }
+        } else if validation::is_valid_font_extension(&path) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FontliftFontSource;
+
+    fn face(path: &Path, name: &str, index: Option<u32>) -> FontliftFontFaceInfo {
+        FontliftFontFaceInfo::new(
+            FontliftFontSource::new(path.to_path_buf())
+                .with_face_index(index)
+                .with_scope(Some(FontScope::User)),
+            name.to_string(),
+            name.replace('-', " "),
+            "Test".to_string(),
+            "Regular".to_string(),
+        )
+    }
+
+    #[test]
+    fn groups_faces_by_file_and_skips_os_fonts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ttc = tmp.path().join("Pair.ttc");
+        fs::write(&ttc, b"collection").unwrap();
+        let os_font = PathBuf::from("/System/Library/Fonts/Helvetica.ttc");
+
+        let mut db = InstallDb::new();
+        db.record(
+            ttc.clone(),
+            FontScope::User,
+            InstallReason::Profile {
+                name: "web".to_string(),
+            },
+        );
+
+        let manifest = Manifest::from_installed(
+            &[
+                face(&ttc, "Pair-Regular", Some(0)),
+                face(&ttc, "Pair-Bold", Some(1)),
+                face(&os_font, "Helvetica", Some(0)),
+            ],
+            &db,
+        );
+
+        assert_eq!(manifest.fonts.len(), 1);
+        let entry = &manifest.fonts[0];
+        assert_eq!(entry.faces.len(), 2);
+        assert_eq!(entry.size, Some(10));
+        assert_eq!(
+            entry.sha256.as_deref(),
+            Some(digest::sha256_hex(b"collection").as_str())
+        );
+        assert!(matches!(entry.reason, Some(InstallReason::Profile { .. })));
+    }
+
+    #[test]
+    fn round_trips_and_resolves_moved_files_by_digest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let original = tmp.path().join("gone/Moved-Regular.ttf");
+        let backup_dir = tmp.path().join("backup/nested");
+        fs::create_dir_all(&backup_dir).unwrap();
+        let renamed = backup_dir.join("renamed.ttf");
+        fs::write(&renamed, b"moved font").unwrap();
+        fs::write(backup_dir.join("Decoy.ttf"), b"other font").unwrap();
+        let kept = tmp.path().join("Kept-Regular.otf");
+        fs::write(&kept, b"kept font").unwrap();
+
+        let mut manifest =
+            Manifest::from_installed(&[face(&kept, "Kept-Regular", None)], &InstallDb::new());
+        manifest.fonts.push(ManifestFont {
+            path: original,
+            sha256: Some(digest::sha256_hex(b"moved font")),
+            size: None,
+            scope: FontScope::User,
+            font_version: None,
+            reason: None,
+            origin: None,
+            faces: Vec::new(),
+        });
+
+        let path = tmp.path().join("manifest.json");
+        manifest.save(&path).unwrap();
+        let loaded = load_manifest(&path).unwrap();
+        assert_eq!(loaded.fonts.len(), 2);
+
+        let resolution = loaded.resolve(&[tmp.path().join("backup")]);
+        assert!(resolution.missing.is_empty());
+        let found: Vec<&PathBuf> = resolution.found.iter().map(|(_, p)| p).collect();
+        assert!(found.contains(&&kept));
+        assert!(found.contains(&&renamed));
+
+        fs::write(&kept, b"changed").unwrap();
+        let resolution = loaded.resolve(&[]);
+        assert_eq!(resolution.missing.len(), 2);
+    }
+}
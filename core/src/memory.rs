@@ -0,0 +1,217 @@
+//! Fonts registered straight from memory, with no file on disk.
+//!
+//! Apps that download, decrypt or generate fonts would otherwise have to
+//! write them to a temporary file first.
+//! [`FontManager::register_font_bytes`] hands the bytes to the OS instead
+//! and returns a [`FontHandle`] that unregisters them when dropped:
+//!
+//! ```no_run
+//! # use fontlift_core::{memory::MemoryFontOptions, FontManager};
+//! # fn render(manager: &dyn FontManager, data: &[u8]) -> fontlift_core::FontResult<()> {
+//! let font = manager.register_font_bytes(data, &MemoryFontOptions::default())?;
+//! println!("registered {:?}", font.postscript_names());
+//! // ... render with the font ...
+//! font.unregister()?; // or just drop it
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Memory registrations are private to the process: Windows loads them with
+//! `AddFontMemResourceEx`, macOS with process-scope Core Text registration.
+//! Other apps never see them and they end with the process at the latest.
+
+use crate::{metadata, FontError, FontResult};
+use read_fonts::{tables::name::NameId, FileRef};
+
+/// Settings for [`FontManager::register_font_bytes`](crate::FontManager::register_font_bytes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryFontOptions {
+    /// Parse the bytes before handing them to the OS and refuse data that is
+    /// not an OpenType font or collection. On by default.
+    pub validate: bool,
+    /// Name for log messages, e.g. where the bytes came from.
+    pub label: Option<String>,
+}
+
+impl Default for MemoryFontOptions {
+    fn default() -> Self {
+        Self {
+            validate: true,
+            label: None,
+        }
+    }
+}
+
+impl MemoryFontOptions {
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// The label, or `"<memory>"` when there is none.
+    pub fn display_name(&self) -> &str {
+        self.label.as_deref().unwrap_or("<memory>")
+    }
+}
+
+/// A platform's registration of font bytes; the part of a [`FontHandle`]
+/// that knows how to undo it.
+pub trait MemoryRegistration: Send + Sync {
+    /// Unregister the fonts. Called at most once.
+    fn unregister(&mut self) -> FontResult<()>;
+}
+
+/// Fonts registered from memory, unregistered on drop.
+pub struct FontHandle {
+    registration: Option<Box<dyn MemoryRegistration>>,
+    postscript_names: Vec<String>,
+    label: String,
+}
+
+impl FontHandle {
+    /// Wrap a platform registration of the fonts named `postscript_names`.
+    pub fn new(
+        registration: Box<dyn MemoryRegistration>,
+        postscript_names: Vec<String>,
+        label: impl Into<String>,
+    ) -> Self {
+        Self {
+            registration: Some(registration),
+            postscript_names,
+            label: label.into(),
+        }
+    }
+
+    /// PostScript names of the registered faces, in file order. Empty when
+    /// the bytes were registered without validation.
+    pub fn postscript_names(&self) -> &[String] {
+        &self.postscript_names
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Whether the fonts are still registered by this handle.
+    pub fn is_registered(&self) -> bool {
+        self.registration.is_some()
+    }
+
+    /// Unregister now and report failures, which dropping cannot do.
+    pub fn unregister(mut self) -> FontResult<()> {
+        self.release()
+    }
+
+    /// Unregister if still registered; later calls do nothing.
+    pub fn release(&mut self) -> FontResult<()> {
+        match self.registration.take() {
+            Some(mut registration) => registration.unregister(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Debug for FontHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FontHandle")
+            .field("label", &self.label)
+            .field("postscript_names", &self.postscript_names)
+            .field("registered", &self.is_registered())
+            .finish()
+    }
+}
+
+impl Drop for FontHandle {
+    fn drop(&mut self) {
+        if let Err(e) = self.release() {
+            log::warn!("Could not unregister in-memory font {}: {}", self.label, e);
+        }
+    }
+}
+
+/// PostScript names of every face in `data`, for
+/// [`MemoryFontOptions::validate`].
+///
+/// Fails with [`FontError::InvalidFormat`] unless `data` is a font or
+/// collection whose faces all parse. Parses in-process like
+/// [`metadata::read_face_info`]; a face without a PostScript name gets
+/// `"face<N>"`.
+pub fn inspect_font_bytes(data: &[u8], label: &str) -> FontResult<Vec<String>> {
+    let invalid = |e: &dyn std::fmt::Display| FontError::InvalidFormat(format!("{label}: {e}"));
+    let file = FileRef::new(data).map_err(|e| invalid(&e))?;
+    let mut names = Vec::new();
+    for (index, font) in file.fonts().enumerate() {
+        let font = font.map_err(|e| invalid(&e))?;
+        names.push(
+            metadata::name_string(&font, NameId::POSTSCRIPT_NAME)
+                .unwrap_or_else(|| format!("face{index}")),
+        );
+    }
+    if names.is_empty() {
+        return Err(invalid(&"no font faces"));
+    }
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontlift_testfonts::{build_collection, ribbi_family, TestFont};
+    use std::sync::{Arc, Mutex};
+
+    struct Recorded(Arc<Mutex<Vec<&'static str>>>);
+
+    impl MemoryRegistration for Recorded {
+        fn unregister(&mut self) -> FontResult<()> {
+            self.0.lock().unwrap().push("unregister");
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn handle_unregisters_once() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut handle = FontHandle::new(
+            Box::new(Recorded(calls.clone())),
+            vec!["Test-Regular".to_string()],
+            "test",
+        );
+        assert!(handle.is_registered());
+        handle.release().unwrap();
+        assert!(!handle.is_registered());
+        drop(handle);
+
+        drop(FontHandle::new(
+            Box::new(Recorded(calls.clone())),
+            vec![],
+            "dropped",
+        ));
+        assert_eq!(*calls.lock().unwrap(), ["unregister", "unregister"]);
+    }
+
+    #[test]
+    fn inspect_names_every_face() {
+        let single = TestFont::new("Memory Sans").build();
+        assert_eq!(
+            inspect_font_bytes(&single, "single").unwrap(),
+            ["MemorySans-Regular"]
+        );
+        let collection = build_collection(&ribbi_family("Memory Serif")[..2]);
+        assert_eq!(
+            inspect_font_bytes(&collection, "ttc").unwrap(),
+            ["MemorySerif-Regular", "MemorySerif-Italic"]
+        );
+    }
+
+    #[test]
+    fn inspect_rejects_non_fonts() {
+        let err = inspect_font_bytes(b"not a font at all", "junk").unwrap_err();
+        assert!(matches!(err, FontError::InvalidFormat(ref m) if m.starts_with("junk: ")));
+        assert!(inspect_font_bytes(&[], "empty").is_err());
+    }
+}
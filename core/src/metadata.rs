@@ -0,0 +1,929 @@
+//! Font metadata read straight from the font binary.
+//!
+//! The platform backends get most metadata from the OS (Core Text, GDI).
+//! Some questions need the font's own tables instead, such as "which version
+//! of this font is installed?" or "can this font set `Grüße`?". Those
+//! readers live here so every backend and command answers them the same way.
+
+use crate::{validation, FontError, FontResult, FontliftFontFaceInfo};
+use read_fonts::{tables::name::NameId, FileRef, FontRef, TableProvider};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Read `head.fontRevision` from the first face of the font at `path`,
+/// formatted with three decimals (`"2.003"`).
+///
+/// Returns `Ok(None)` when the file parses but has no usable `head` table.
+pub fn read_font_version(path: &Path) -> FontResult<Option<String>> {
+    let data = std::fs::read(path).map_err(FontError::IoError)?;
+    let file = FileRef::new(&data)
+        .map_err(|e| FontError::InvalidFormat(format!("{}: {e}", path.display())))?;
+
+    let Some(Ok(font)) = file.fonts().next() else {
+        return Ok(None);
+    };
+
+    Ok(font
+        .head()
+        .ok()
+        .map(|head| format!("{:.3}", head.font_revision().to_f64())))
+}
+
+/// A font's version as its `head` and `name` tables state it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontVersion {
+    /// `head.fontRevision`.
+    pub revision: Option<f64>,
+    /// The version string, name ID 5 (`"Version 2.003; ttfautohint"`).
+    pub version_string: Option<String>,
+}
+
+impl FontVersion {
+    /// The number versions compare by: name ID 5 when it parses, since
+    /// `head.fontRevision` is often left stale; otherwise the revision.
+    pub fn number(&self) -> Option<f64> {
+        self.version_string
+            .as_deref()
+            .and_then(parse_version)
+            .or(self.revision)
+    }
+
+    /// [`number`](Self::number) with three decimals (`"2.003"`), or
+    /// `"unknown"`.
+    pub fn label(&self) -> String {
+        self.number()
+            .map_or_else(|| "unknown".to_string(), |n| format!("{n:.3}"))
+    }
+}
+
+/// Read the [`FontVersion`] of the first face of the font at `path`.
+pub fn read_version(path: &Path) -> FontResult<FontVersion> {
+    let data = std::fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let file = FileRef::new(&data)
+        .map_err(|e| FontError::InvalidFormat(format!("{}: {e}", path.display())))?;
+    let Some(Ok(font)) = file.fonts().next() else {
+        return Ok(FontVersion::default());
+    };
+    Ok(FontVersion {
+        revision: font.head().ok().map(|head| head.font_revision().to_f64()),
+        version_string: name_string(&font, NameId::VERSION_STRING),
+    })
+}
+
+/// What a font's license lets documents do with it, from the permission
+/// bits 0–3 of `OS/2.fsType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmbeddingPermission {
+    /// No restriction: documents may embed it and install it permanently.
+    Installable,
+    /// "Restricted License embedding": documents must not embed it at all.
+    Restricted,
+    /// Documents may embed it for viewing and printing only.
+    PreviewAndPrint,
+    /// Documents may embed it and be edited with it.
+    Editable,
+}
+
+/// `OS/2.fsType` decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Embedding {
+    pub permission: EmbeddingPermission,
+    /// Bit 8: the font must be embedded whole, not subset.
+    pub no_subsetting: bool,
+    /// Bit 9: only the font's bitmaps may be embedded.
+    pub bitmap_only: bool,
+}
+
+impl Embedding {
+    /// Decode `fsType`. When several permission bits are set, the least
+    /// restrictive wins, as the OpenType specification asks.
+    pub fn from_fs_type(fs_type: u16) -> Self {
+        let permission = if fs_type & 0x0008 != 0 {
+            EmbeddingPermission::Editable
+        } else if fs_type & 0x0004 != 0 {
+            EmbeddingPermission::PreviewAndPrint
+        } else if fs_type & 0x0002 != 0 {
+            EmbeddingPermission::Restricted
+        } else {
+            EmbeddingPermission::Installable
+        };
+        Self {
+            permission,
+            no_subsetting: fs_type & 0x0100 != 0,
+            bitmap_only: fs_type & 0x0200 != 0,
+        }
+    }
+
+    /// Documents cannot embed the font's outlines.
+    pub fn is_restricted(&self) -> bool {
+        self.permission == EmbeddingPermission::Restricted || self.bitmap_only
+    }
+
+    /// `restricted license: no embedding`, `preview & print embedding, no
+    /// subsetting`, ...
+    pub fn description(&self) -> String {
+        let mut text = match self.permission {
+            EmbeddingPermission::Installable => "installable embedding",
+            EmbeddingPermission::Restricted => "restricted license: no embedding",
+            EmbeddingPermission::PreviewAndPrint => "preview & print embedding",
+            EmbeddingPermission::Editable => "editable embedding",
+        }
+        .to_string();
+        if self.bitmap_only {
+            text.push_str(", bitmaps only");
+        }
+        if self.no_subsetting {
+            text.push_str(", no subsetting");
+        }
+        text
+    }
+}
+
+/// Fill in the license (name IDs 13 and 14) and [`Embedding`] of `info`
+/// from `font`.
+pub fn read_licensing(info: &mut FontliftFontFaceInfo, font: &FontRef<'_>) {
+    info.license = name_string(font, NameId::LICENSE_DESCRIPTION);
+    info.license_url = name_string(font, NameId::LICENSE_URL);
+    info.embedding = font
+        .os2()
+        .ok()
+        .map(|os2| Embedding::from_fs_type(os2.fs_type()));
+}
+
+/// Version, vendor and copyright of a face: name IDs 5, 8, 9 and 0 and
+/// `OS/2.achVendID`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaceDetails {
+    /// Version string, name ID 5 (`Version 2.003`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Four-letter vendor tag from `OS/2.achVendID` (`ADBO`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor_id: Option<String>,
+    /// Manufacturer, name ID 8.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manufacturer: Option<String>,
+    /// Designer, name ID 9.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub designer: Option<String>,
+    /// Copyright notice, name ID 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<String>,
+}
+
+impl FaceDetails {
+    /// Read the `name` and `OS/2` tables of `font`; `None` when the font
+    /// carries none of the fields.
+    pub fn read(font: &FontRef<'_>) -> Option<Self> {
+        Self {
+            version: name_string(font, NameId::VERSION_STRING),
+            vendor_id: font
+                .os2()
+                .ok()
+                .and_then(|os2| vendor_id(os2.ach_vend_id().to_be_bytes())),
+            manufacturer: name_string(font, NameId::MANUFACTURER),
+            designer: name_string(font, NameId::DESIGNER),
+            copyright: name_string(font, NameId::COPYRIGHT_NOTICE),
+        }
+        .non_empty()
+    }
+
+    /// `Some(self)` unless every field is missing.
+    pub fn non_empty(self) -> Option<Self> {
+        (self != Self::default()).then_some(self)
+    }
+}
+
+/// `OS/2.achVendID` as text, without the space or NUL padding; `None` when
+/// the tag is blank or not printable ASCII.
+pub fn vendor_id(tag: [u8; 4]) -> Option<String> {
+    let text = std::str::from_utf8(&tag).ok()?;
+    let text = text.trim_end_matches([' ', '\0']);
+    (!text.is_empty() && text.bytes().all(|b| b.is_ascii_graphic() || b == b' '))
+        .then(|| text.to_string())
+}
+
+/// Weight, width, slope and variation axes of a face, read from its own
+/// tables so every backend reports the same values for the same file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FaceStyle {
+    pub weight: Option<u16>,
+    pub width: Option<u16>,
+    pub italic: Option<bool>,
+    pub axes: Vec<String>,
+}
+
+impl FaceStyle {
+    /// Read `OS/2`, `head` and `fvar` of `font`.
+    pub fn read(font: &FontRef<'_>) -> Self {
+        let os2 = font.os2().ok();
+        let axes = font
+            .fvar()
+            .and_then(|fvar| fvar.axes())
+            .map(|axes| {
+                axes.iter()
+                    .map(|axis| axis.axis_tag().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            weight: os2
+                .as_ref()
+                .and_then(|os2| normalize_weight_class(os2.us_weight_class())),
+            width: os2
+                .as_ref()
+                .and_then(|os2| normalize_width_class(os2.us_width_class())),
+            italic: is_italic(
+                os2.as_ref().map(|os2| os2.fs_selection().bits()),
+                font.head().ok().map(|head| head.mac_style().bits()),
+            ),
+            axes,
+        }
+    }
+
+    /// Copy the values the font provided into `info`, keeping what `info`
+    /// already has for the others.
+    pub fn apply(self, info: &mut FontliftFontFaceInfo) {
+        info.weight = self.weight.or(info.weight);
+        info.width = self.width.or(info.width);
+        info.italic = self.italic.or(info.italic);
+        info.axes = self.axes;
+    }
+}
+
+/// `OS/2.usWeightClass` on the 1–1000 scale. Old fonts store 1–9 for
+/// 100–900; 0 and values past 1000 are not weights.
+pub fn normalize_weight_class(raw: u16) -> Option<u16> {
+    match raw {
+        1..=9 => Some(raw * 100),
+        10..=1000 => Some(raw),
+        _ => None,
+    }
+}
+
+/// `OS/2.usWidthClass` when it is one of the nine defined classes.
+pub fn normalize_width_class(raw: u16) -> Option<u16> {
+    (1..=9).contains(&raw).then_some(raw)
+}
+
+/// Italic from `OS/2.fsSelection` (bit 0 ITALIC, bit 9 OBLIQUE), or from
+/// `head.macStyle` bit 1 when the font has no `OS/2`.
+pub fn is_italic(fs_selection: Option<u16>, mac_style: Option<u16>) -> Option<bool> {
+    match (fs_selection, mac_style) {
+        (Some(selection), _) => Some(selection & 0x0201 != 0),
+        (None, Some(style)) => Some(style & 0x0002 != 0),
+        (None, None) => None,
+    }
+}
+
+/// Describe the first face of the font at `path` from its own tables.
+///
+/// Names come from the `name` table and the [`FaceStyle`] from `OS/2`,
+/// `head` and `fvar`. Fields the font does not provide keep the
+/// filename-based guesses from
+/// [`validation::extract_basic_info_from_path`]. Unlike the out-of-process
+/// validator this parses in-process, so only use it on files that already
+/// passed validation or where a parser failure is acceptable.
+pub fn read_face_info(path: &Path) -> FontResult<FontliftFontFaceInfo> {
+    read_face_info_at(path, 0)
+}
+
+/// [`read_face_info`] for the face at `face_index` of a collection. Unlike
+/// face 0, a face index the file does not have is an error.
+pub fn read_face_info_at(path: &Path, face_index: u32) -> FontResult<FontliftFontFaceInfo> {
+    let data = std::fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let file = FileRef::new(&data)
+        .map_err(|e| FontError::InvalidFormat(format!("{}: {e}", path.display())))?;
+
+    let mut info = validation::extract_basic_info_from_path(path);
+    let Some(Ok(font)) = file.fonts().nth(face_index as usize) else {
+        if face_index == 0 {
+            return Ok(info);
+        }
+        return Err(FontError::InvalidFormat(format!(
+            "{}: no face {face_index}",
+            path.display()
+        )));
+    };
+    if face_index > 0 {
+        info.source.face_index = Some(face_index);
+    }
+
+    if let Some(ps) = name_string(&font, NameId::POSTSCRIPT_NAME) {
+        info.postscript_name = ps;
+    }
+    if let Some(family) = name_string(&font, NameId::FAMILY_NAME) {
+        info.family_name = family;
+    }
+    if let Some(style) = name_string(&font, NameId::SUBFAMILY_NAME) {
+        info.style = style;
+    }
+    if let Some(full) = name_string(&font, NameId::FULL_NAME) {
+        info.full_name = full;
+    }
+    FaceStyle::read(&font).apply(&mut info);
+    read_licensing(&mut info, &font);
+    info.details = FaceDetails::read(&font);
+
+    Ok(info)
+}
+
+/// [`read_face_info_at`] for every face of the file: one entry for a
+/// single font, one per face for a `.ttc`/`.otc` collection.
+pub fn read_all_face_info(path: &Path) -> FontResult<Vec<FontliftFontFaceInfo>> {
+    let data = std::fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let count = FileRef::new(&data)
+        .map_err(|e| FontError::InvalidFormat(format!("{}: {e}", path.display())))?
+        .fonts()
+        .count()
+        .max(1);
+    (0..count as u32)
+        .map(|index| read_face_info_at(path, index))
+        .collect()
+}
+
+/// Which requested characters a face maps to a glyph in its `cmap`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Coverage {
+    pub supported: Vec<char>,
+    pub missing: Vec<char>,
+}
+
+impl Coverage {
+    /// Every requested character is supported.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// The characters of `text` a coverage query checks: each once, in order of
+/// appearance. Whitespace and control characters are left out, so
+/// `"€ £"` asks for two characters.
+pub fn coverage_chars(text: &str) -> Vec<char> {
+    let mut chars = Vec::new();
+    for c in text.chars() {
+        if !c.is_whitespace() && !c.is_control() && !chars.contains(&c) {
+            chars.push(c);
+        }
+    }
+    chars
+}
+
+/// [`Coverage`] of [`coverage_chars`]`(text)` for the face at `face_index`
+/// of the font at `path`. A face without a usable `cmap` supports nothing.
+pub fn read_coverage(path: &Path, face_index: u32, text: &str) -> FontResult<Coverage> {
+    let data = std::fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let file = FileRef::new(&data)
+        .map_err(|e| FontError::InvalidFormat(format!("{}: {e}", path.display())))?;
+    let Some(Ok(font)) = file.fonts().nth(face_index as usize) else {
+        return Err(FontError::InvalidFormat(format!(
+            "{}: no face {face_index}",
+            path.display()
+        )));
+    };
+
+    let cmap = font.cmap().ok();
+    let (supported, missing) = coverage_chars(text).into_iter().partition(|&c| {
+        cmap.as_ref()
+            .and_then(|cmap| cmap.map_codepoint(c))
+            .is_some_and(|glyph| glyph.to_u32() != 0)
+    });
+    Ok(Coverage { supported, missing })
+}
+
+/// The record for `name_id` in the first of [`name_languages`] the font
+/// has, falling back to any Unicode record, then to any record.
+pub fn name_string(font: &FontRef<'_>, name_id: NameId) -> Option<String> {
+    name_string_in(font, name_id, &name_languages())
+}
+
+/// [`name_string`] preferring `languages` (BCP 47 tags, most preferred
+/// first) instead of [`name_languages`].
+pub fn name_string_in(font: &FontRef<'_>, name_id: NameId, languages: &[String]) -> Option<String> {
+    let names = decode_names(font, Some(name_id));
+    select_name(&names, name_id.to_u16(), languages).map(str::to_string)
+}
+
+/// One decoded record of a face's `name` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalizedName {
+    /// 1 family, 2 subfamily, 4 full name, 6 PostScript name, ...
+    pub name_id: u16,
+    /// 0 Unicode, 1 Macintosh, 3 Windows.
+    pub platform_id: u16,
+    /// The platform's language code, e.g. Windows `0x0411` for Japanese.
+    pub language_id: u16,
+    /// BCP 47 tag for `language_id` (`ja-JP`), when it is known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    pub value: String,
+    /// Unicode-encoded, as opposed to a legacy Macintosh encoding.
+    #[serde(skip)]
+    pub unicode: bool,
+}
+
+/// Every record of the `name` table of `font` that decodes, in table order.
+pub fn localized_names(font: &FontRef<'_>) -> Vec<LocalizedName> {
+    decode_names(font, None)
+}
+
+/// [`localized_names`] of face `face_index` of the font file at `path`.
+pub fn read_localized_names(path: &Path, face_index: u32) -> FontResult<Vec<LocalizedName>> {
+    let data = std::fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let file = FileRef::new(&data)
+        .map_err(|e| FontError::InvalidFormat(format!("{}: {e}", path.display())))?;
+    let font = file
+        .fonts()
+        .nth(face_index as usize)
+        .and_then(Result::ok)
+        .ok_or_else(|| {
+            FontError::InvalidFormat(format!("{}: no face {face_index}", path.display()))
+        })?;
+    Ok(localized_names(&font))
+}
+
+fn decode_names(font: &FontRef<'_>, only: Option<NameId>) -> Vec<LocalizedName> {
+    let Ok(name) = font.name() else {
+        return Vec::new();
+    };
+    let data = name.string_data();
+    // Name table version 1 numbers its own language tags from 0x8000.
+    let tags: Vec<String> = name
+        .lang_tag_record()
+        .unwrap_or_default()
+        .iter()
+        .map(|record| {
+            record
+                .lang_tag(data)
+                .map(|tag| tag.to_string())
+                .unwrap_or_default()
+        })
+        .collect();
+
+    name.name_record()
+        .iter()
+        .filter(|record| only.map_or(true, |id| record.name_id() == id))
+        .filter_map(|record| {
+            let value = record.string(data).ok()?.to_string();
+            let language = match record.language_id() {
+                id @ 0x8000.. => tags.get(usize::from(id - 0x8000)).cloned(),
+                id => language_tag(record.platform_id(), id).map(str::to_string),
+            };
+            Some(LocalizedName {
+                name_id: record.name_id().to_u16(),
+                platform_id: record.platform_id(),
+                language_id: record.language_id(),
+                language: language.filter(|tag| !tag.is_empty()),
+                value,
+                unicode: record.is_unicode(),
+            })
+        })
+        .collect()
+}
+
+/// The value of `name_id` in the first of `languages` that `names` has —
+/// an exact tag first, then the same primary language (`ja` for `ja-JP`) —
+/// else the first Unicode record, else the first record. Unicode records
+/// win over Macintosh ones in the same language.
+pub fn select_name<'a>(
+    names: &'a [LocalizedName],
+    name_id: u16,
+    languages: &[String],
+) -> Option<&'a str> {
+    let primary = |tag: &str| {
+        tag.split(['-', '_'])
+            .next()
+            .unwrap_or(tag)
+            .to_ascii_lowercase()
+    };
+    let language_rank = |name: &LocalizedName| {
+        let Some(tag) = name.language.as_deref() else {
+            return languages.len() * 2;
+        };
+        languages
+            .iter()
+            .enumerate()
+            .find_map(|(i, wanted)| {
+                if wanted.eq_ignore_ascii_case(tag) {
+                    Some(i * 2)
+                } else if primary(wanted) == primary(tag) {
+                    Some(i * 2 + 1)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(languages.len() * 2)
+    };
+    names
+        .iter()
+        .enumerate()
+        .filter(|(_, name)| name.name_id == name_id)
+        .min_by_key(|(i, name)| (language_rank(name), !name.unicode, *i))
+        .map(|(_, name)| name.value.as_str())
+}
+
+static PREFERRED_LANGUAGE: std::sync::RwLock<Option<String>> = std::sync::RwLock::new(None);
+
+/// Prefer names in `language` (a BCP 47 tag such as `ja` or `zh-Hant`)
+/// for this process, ahead of en-US and the locale (`fontlift --lang`).
+/// `FONTLIFT_LANG` does the same for processes that are not told.
+pub fn set_preferred_language(language: Option<&str>) {
+    *PREFERRED_LANGUAGE
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = language.map(str::to_string);
+}
+
+/// The language set with [`set_preferred_language`] or `FONTLIFT_LANG`.
+pub fn preferred_language() -> Option<String> {
+    PREFERRED_LANGUAGE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .or_else(|| std::env::var("FONTLIFT_LANG").ok())
+        .filter(|language| !language.is_empty())
+}
+
+/// Languages to pick names in, most preferred first: the
+/// [`preferred_language`], then en-US, which is what the OS font menus and
+/// registry use, then the user's locale (`LC_ALL`, `LC_MESSAGES`, `LANG`).
+pub fn name_languages() -> Vec<String> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| locale_language(&value));
+    let mut languages = Vec::new();
+    for language in [preferred_language(), Some("en-US".to_string()), locale]
+        .into_iter()
+        .flatten()
+    {
+        if !languages.contains(&language) {
+            languages.push(language);
+        }
+    }
+    languages
+}
+
+/// The BCP 47 tag of a POSIX locale such as `ja_JP.UTF-8`; `None` for the
+/// `C` and `POSIX` locales.
+pub fn locale_language(locale: &str) -> Option<String> {
+    let tag = locale.split(['.', '@']).next()?.replace('_', "-");
+    (!tag.is_empty() && tag != "C" && tag != "POSIX").then_some(tag)
+}
+
+/// BCP 47 tag of a `name` record's language code on `platform_id` (1
+/// Macintosh, 3 Windows); `None` for codes not listed here and for Unicode
+/// platform records, which carry no language.
+pub fn language_tag(platform_id: u16, language_id: u16) -> Option<&'static str> {
+    let tag = match (platform_id, language_id) {
+        (3, 0x0401) => "ar-SA",
+        (3, 0x0402) => "bg-BG",
+        (3, 0x0403) => "ca-ES",
+        (3, 0x0404) => "zh-TW",
+        (3, 0x0405) => "cs-CZ",
+        (3, 0x0406) => "da-DK",
+        (3, 0x0407) => "de-DE",
+        (3, 0x0408) => "el-GR",
+        (3, 0x0409) => "en-US",
+        (3, 0x040A) | (3, 0x0C0A) => "es-ES",
+        (3, 0x040B) => "fi-FI",
+        (3, 0x040C) => "fr-FR",
+        (3, 0x040D) => "he-IL",
+        (3, 0x040E) => "hu-HU",
+        (3, 0x040F) => "is-IS",
+        (3, 0x0410) => "it-IT",
+        (3, 0x0411) => "ja-JP",
+        (3, 0x0412) => "ko-KR",
+        (3, 0x0413) => "nl-NL",
+        (3, 0x0414) => "nb-NO",
+        (3, 0x0415) => "pl-PL",
+        (3, 0x0416) => "pt-BR",
+        (3, 0x0418) => "ro-RO",
+        (3, 0x0419) => "ru-RU",
+        (3, 0x041A) => "hr-HR",
+        (3, 0x041B) => "sk-SK",
+        (3, 0x041D) => "sv-SE",
+        (3, 0x041E) => "th-TH",
+        (3, 0x041F) => "tr-TR",
+        (3, 0x0421) => "id-ID",
+        (3, 0x0422) => "uk-UA",
+        (3, 0x0424) => "sl-SI",
+        (3, 0x0425) => "et-EE",
+        (3, 0x0426) => "lv-LV",
+        (3, 0x0427) => "lt-LT",
+        (3, 0x0429) => "fa-IR",
+        (3, 0x042A) => "vi-VN",
+        (3, 0x042D) => "eu-ES",
+        (3, 0x0439) => "hi-IN",
+        (3, 0x0456) => "gl-ES",
+        (3, 0x0804) => "zh-CN",
+        (3, 0x0809) => "en-GB",
+        (3, 0x080A) => "es-MX",
+        (3, 0x0816) => "pt-PT",
+        (3, 0x0C04) => "zh-HK",
+        (3, 0x0C09) => "en-AU",
+        (3, 0x0C0C) => "fr-CA",
+        (3, 0x1004) => "zh-SG",
+        (3, 0x1009) => "en-CA",
+        (3, 0x1404) => "zh-MO",
+        (1, 0) => "en",
+        (1, 1) => "fr",
+        (1, 2) => "de",
+        (1, 3) => "it",
+        (1, 4) => "nl",
+        (1, 5) => "sv",
+        (1, 6) => "es",
+        (1, 7) => "da",
+        (1, 8) => "pt",
+        (1, 9) => "no",
+        (1, 10) => "he",
+        (1, 11) => "ja",
+        (1, 12) => "ar",
+        (1, 13) => "fi",
+        (1, 14) => "el",
+        (1, 15) => "is",
+        (1, 17) => "tr",
+        (1, 18) => "hr",
+        (1, 19) => "zh-Hant",
+        (1, 21) => "hi",
+        (1, 22) => "th",
+        (1, 23) => "ko",
+        (1, 24) => "lt",
+        (1, 25) => "pl",
+        (1, 26) => "hu",
+        (1, 27) => "et",
+        (1, 28) => "lv",
+        (1, 31) => "fa",
+        (1, 32) => "ru",
+        (1, 33) => "zh-Hans",
+        _ => return None,
+    };
+    Some(tag)
+}
+
+/// Parse a version string such as `"2.003"` or `"Version 2.003; ttfautohint"`
+/// into a number that can be compared.
+pub fn parse_version(version: &str) -> Option<f64> {
+    let trimmed = version.trim();
+    let trimmed = trimmed
+        .strip_prefix("Version")
+        .or_else(|| trimmed.strip_prefix("version"))
+        .unwrap_or(trimmed)
+        .trim_start();
+
+    let numeric: String = trimmed
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    numeric.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parses_name_table_style_versions() {
+        assert_eq!(parse_version("2.003"), Some(2.003));
+        assert_eq!(parse_version("Version 1.10; ttfautohint"), Some(1.1));
+        assert_eq!(parse_version("beta"), None);
+    }
+
+    #[test]
+    fn reads_head_revision_from_fixture() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/fixtures/fonts/AtkinsonHyperlegible-Regular.ttf");
+        let version = read_font_version(&path).unwrap().expect("head table");
+        assert!(parse_version(&version).is_some());
+
+        let malformed =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/fonts/malformed.ttf");
+        assert!(read_font_version(&malformed).is_err());
+    }
+
+    #[test]
+    fn reads_names_and_weight_from_fixture() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/fixtures/fonts/AtkinsonHyperlegible-Regular.ttf");
+        let info = read_face_info(&path).unwrap();
+        assert_eq!(info.family_name, "Atkinson Hyperlegible");
+        assert_eq!(info.weight, Some(400));
+        assert_eq!(info.italic, Some(false));
+    }
+
+    #[test]
+    fn reads_generated_font_styles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = fontlift_testfonts::TestFont::new("Fixture Sans")
+            .with_style("Bold Italic")
+            .with_weight(700)
+            .with_italic(true)
+            .with_revision(2.5)
+            .write_to(dir.path())
+            .unwrap();
+
+        let info = read_face_info(&path).unwrap();
+        assert_eq!(info.postscript_name, "FixtureSans-BoldItalic");
+        assert_eq!(info.style, "Bold Italic");
+        assert_eq!(info.weight, Some(700));
+        assert_eq!(info.italic, Some(true));
+        assert_eq!(read_font_version(&path).unwrap().as_deref(), Some("2.500"));
+    }
+
+    #[test]
+    fn reads_width_and_axes_and_normalizes_weights() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = fontlift_testfonts::TestFont::new("Stretch Sans")
+            .with_width(3)
+            .with_weight_axis(100, 900)
+            .write_to(dir.path())
+            .unwrap();
+        let info = read_face_info(&path).unwrap();
+        assert_eq!(info.width, Some(3));
+        assert_eq!(info.axes, ["wght"]);
+        assert!(info.is_variable());
+
+        assert_eq!(normalize_weight_class(7), Some(700));
+        assert_eq!(normalize_weight_class(350), Some(350));
+        assert_eq!(normalize_weight_class(0), None);
+        assert_eq!(normalize_weight_class(1200), None);
+        assert_eq!(normalize_width_class(10), None);
+        assert_eq!(is_italic(Some(0x0200), Some(0)), Some(true));
+        assert_eq!(is_italic(Some(0x0040), Some(0x0002)), Some(false));
+        assert_eq!(is_italic(None, Some(0x0002)), Some(true));
+        assert_eq!(is_italic(None, None), None);
+    }
+
+    #[test]
+    fn versions_prefer_the_name_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = fontlift_testfonts::TestFont::new("Versioned Sans")
+            .with_revision(2.003)
+            .write_to(dir.path())
+            .unwrap();
+        let version = read_version(&path).unwrap();
+        assert_eq!(version.version_string.as_deref(), Some("Version 2.003"));
+        assert_eq!(version.label(), "2.003");
+
+        let stale = FontVersion {
+            revision: Some(1.0),
+            version_string: Some("Version 1.10".to_string()),
+        };
+        assert_eq!(stale.number(), Some(1.1));
+        assert_eq!(FontVersion::default().label(), "unknown");
+    }
+
+    #[test]
+    fn coverage_splits_supported_and_missing_characters() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = fontlift_testfonts::TestFont::new("Umlaut Sans")
+            .with_chars("Grüße€".chars())
+            .write_to(dir.path())
+            .unwrap();
+
+        assert_eq!(
+            coverage_chars("Grüße 北京\n€€"),
+            ['G', 'r', 'ü', 'ß', 'e', '北', '京', '€']
+        );
+        let coverage = read_coverage(&path, 0, "Grüße 北京").unwrap();
+        assert_eq!(coverage.supported, ['G', 'r', 'ü', 'ß', 'e']);
+        assert_eq!(coverage.missing, ['北', '京']);
+        assert!(!coverage.is_complete());
+        assert!(read_coverage(&path, 0, "€").unwrap().is_complete());
+        assert!(read_coverage(&path, 1, "€").is_err());
+    }
+
+    #[test]
+    fn decodes_embedding_permissions() {
+        let restricted = Embedding::from_fs_type(0x0002);
+        assert_eq!(restricted.permission, EmbeddingPermission::Restricted);
+        assert!(restricted.is_restricted());
+        assert_eq!(restricted.description(), "restricted license: no embedding");
+        // The least restrictive permission bit wins.
+        let mixed = Embedding::from_fs_type(0x0002 | 0x0004 | 0x0100);
+        assert_eq!(mixed.permission, EmbeddingPermission::PreviewAndPrint);
+        assert!(!mixed.is_restricted());
+        assert_eq!(
+            mixed.description(),
+            "preview & print embedding, no subsetting"
+        );
+        assert!(Embedding::from_fs_type(0x0200).is_restricted());
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/fixtures/fonts/AtkinsonHyperlegible-Regular.ttf");
+        let info = read_face_info(&path).unwrap();
+        assert!(info.embedding.is_some());
+        assert!(info.license.is_some(), "the fixture names its license");
+    }
+
+    #[test]
+    fn reads_version_and_vendor_details() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = fontlift_testfonts::TestFont::new("Detail Sans")
+            .with_revision(2.5)
+            .write_to(dir.path())
+            .unwrap();
+
+        let details = read_face_info(&path).unwrap().details.unwrap();
+        assert_eq!(details.version.as_deref(), Some("Version 2.500"));
+        assert_eq!(details.vendor_id.as_deref(), Some("FLFT"));
+        assert_eq!(details.copyright, None);
+
+        assert_eq!(vendor_id(*b"AB  ").as_deref(), Some("AB"));
+        assert_eq!(vendor_id(*b"    "), None);
+        assert_eq!(vendor_id([0; 4]), None);
+        assert_eq!(FaceDetails::default().non_empty(), None);
+    }
+
+    #[test]
+    fn selects_names_by_language() {
+        let name = |platform_id, language_id, value: &str| LocalizedName {
+            name_id: 1,
+            platform_id,
+            language_id,
+            language: language_tag(platform_id, language_id).map(str::to_string),
+            value: value.to_string(),
+            unicode: platform_id != 1,
+        };
+        let names = [
+            name(1, 0, "Mac Sans"),
+            name(3, 0x0411, "ゴシック"),
+            name(3, 0x0409, "Gothic"),
+            name(3, 0x0407, "Gotisch"),
+        ];
+        let langs = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            select_name(&names, 1, &langs(&["ja", "en-US"])),
+            Some("ゴシック")
+        );
+        assert_eq!(select_name(&names, 1, &langs(&["en-US"])), Some("Gothic"));
+        assert_eq!(select_name(&names, 1, &langs(&["de-AT"])), Some("Gotisch"));
+        // No language matches: the first Unicode record, not the Mac one.
+        assert_eq!(select_name(&names, 1, &langs(&["fr"])), Some("ゴシック"));
+        assert_eq!(
+            select_name(&names[..1], 1, &langs(&["fr"])),
+            Some("Mac Sans")
+        );
+        assert_eq!(select_name(&names, 2, &langs(&["en-US"])), None);
+
+        assert_eq!(language_tag(3, 0x0409), Some("en-US"));
+        assert_eq!(language_tag(1, 11), Some("ja"));
+        assert_eq!(language_tag(0, 0), None);
+        assert_eq!(locale_language("ja_JP.UTF-8").as_deref(), Some("ja-JP"));
+        assert_eq!(locale_language("de_DE@euro").as_deref(), Some("de-DE"));
+        assert_eq!(locale_language("C.UTF-8"), None);
+    }
+
+    #[test]
+    fn lists_localized_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = fontlift_testfonts::TestFont::new("Locale Sans")
+            .write_to(dir.path())
+            .unwrap();
+
+        let names = read_localized_names(&path, 0).unwrap();
+        let family = names.iter().find(|n| n.name_id == 1).unwrap();
+        assert_eq!(family.value, "Locale Sans");
+        assert_eq!(
+            name_string_in(
+                &FontRef::new(&std::fs::read(&path).unwrap()).unwrap(),
+                NameId::FAMILY_NAME,
+                &[]
+            ),
+            Some("Locale Sans".to_string())
+        );
+    }
+
+    #[test]
+    fn reads_every_face_of_a_collection() {
+        let dir = tempfile::tempdir().unwrap();
+        let faces = fontlift_testfonts::ribbi_family("Pack Serif");
+        let path = fontlift_testfonts::write_collection(dir.path(), "Pack.ttc", &faces).unwrap();
+
+        let infos = read_all_face_info(&path).unwrap();
+        assert_eq!(infos.len(), faces.len());
+        assert_eq!(infos[1].source.face_index, Some(1));
+        assert_eq!(infos[1].postscript_name, faces[1].postscript_name());
+
+        let single = faces[0].write_to(dir.path()).unwrap();
+        assert_eq!(read_all_face_info(&single).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,364 @@
+//! Per-user cache of face metadata read from font files.
+//!
+//! `fontlift list` describes every installed font, and the platform managers
+//! fall back to parsing `name` tables when the OS does not hand metadata
+//! over. Parsing hundreds of files on every run is wasted work when they have
+//! not changed, so managers route those reads through a [`MetadataCache`]:
+//!
+//! ```text
+//! let mut cache = MetadataCache::open();
+//! for path in font_files {
+//!     let info = cache.face_info(&path, |p| read_from_binary(p))?;
+//! }
+//! cache.finish();
+//! ```
+//!
+//! Entries are keyed by path and invalidated when the file's size or
+//! modification time changes. The whole cache is rebuilt when the languages
+//! names are picked in ([`metadata::name_languages`]) change. Files that no longer exist are dropped when
+//! the cache is saved. The cache is disposable: an unreadable or corrupt
+//! cache file is treated as empty and rebuilt.
+//!
+//! The file lives in the per-user cache directory
+//! (`FONTLIFT_METADATA_CACHE_PATH` overrides it). [`set_enabled`]`(false)`
+//! (`fontlift list --no-cache`) or `FONTLIFT_NO_METADATA_CACHE=1` bypass it.
+
+use crate::{metadata, FontError, FontResult, FontliftFontFaceInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::UNIX_EPOCH;
+
+const CACHE_VERSION: u32 = 5;
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Turn the cache on or off for this process.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// Whether managers should consult the cache.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+        && !std::env::var("FONTLIFT_NO_METADATA_CACHE").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Location of the cache file. `FONTLIFT_METADATA_CACHE_PATH` overrides it.
+pub fn metadata_cache_path() -> PathBuf {
+    if let Ok(path) = std::env::var("FONTLIFT_METADATA_CACHE_PATH") {
+        return PathBuf::from(path);
+    }
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("fontlift")
+        .join("metadata-cache.json")
+}
+
+/// What [`MetadataCache::load_from`] would make of a cache file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheFileHealth {
+    Missing,
+    /// Unreadable or not a cache file; it would be discarded.
+    Corrupt(String),
+    /// Written by a different fontlift version; it would be discarded.
+    WrongVersion(u32),
+    Usable {
+        entries: usize,
+        /// Entries for font files that no longer exist.
+        stale: usize,
+    },
+}
+
+/// Check the cache file at `path` without changing it.
+pub fn check_cache_file(path: &Path) -> CacheFileHealth {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return CacheFileHealth::Missing,
+        Err(e) => return CacheFileHealth::Corrupt(e.to_string()),
+    };
+    let file = match serde_json::from_str::<CacheFile<BTreeMap<PathBuf, CacheEntry>>>(&content) {
+        Ok(file) => file,
+        Err(e) => return CacheFileHealth::Corrupt(e.to_string()),
+    };
+    if file.version != CACHE_VERSION {
+        return CacheFileHealth::WrongVersion(file.version);
+    }
+    CacheFileHealth::Usable {
+        entries: file.entries.len(),
+        stale: file.entries.keys().filter(|path| !path.exists()).count(),
+    }
+}
+
+/// File identity the cached metadata was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStamp {
+    size: u64,
+    modified_secs: u64,
+    modified_nanos: u32,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            size: metadata.len(),
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    stamp: FileStamp,
+    /// Every face read from the file, in face order.
+    faces: Vec<FontliftFontFaceInfo>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile<E> {
+    version: u32,
+    /// [`metadata::name_languages`] the cached names were picked in.
+    #[serde(default)]
+    languages: Vec<String>,
+    entries: E,
+}
+
+/// An open metadata cache.
+#[derive(Debug)]
+pub struct MetadataCache {
+    path: Option<PathBuf>,
+    entries: BTreeMap<PathBuf, CacheEntry>,
+    dirty: bool,
+    hits: usize,
+    misses: usize,
+}
+
+impl MetadataCache {
+    /// Open the cache at [`metadata_cache_path`], or a pass-through cache
+    /// when caching is disabled.
+    pub fn open() -> Self {
+        if is_enabled() {
+            Self::load_from(metadata_cache_path())
+        } else {
+            Self::disabled()
+        }
+    }
+
+    /// A cache that never stores anything.
+    pub fn disabled() -> Self {
+        Self {
+            path: None,
+            entries: BTreeMap::new(),
+            dirty: false,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Open the cache stored at `path`. Missing or unreadable files start empty.
+    pub fn load_from(path: PathBuf) -> Self {
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| {
+                serde_json::from_str::<CacheFile<BTreeMap<PathBuf, CacheEntry>>>(&content).ok()
+            })
+            .filter(|file| {
+                file.version == CACHE_VERSION && file.languages == metadata::name_languages()
+            })
+            .map(|file| file.entries)
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            entries,
+            dirty: false,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Metadata for the font at `path`, from the cache when the file is
+    /// unchanged, otherwise from `read` (whose successful result is cached).
+    /// For a collection cached by [`faces`](Self::faces), the first face.
+    pub fn face_info(
+        &mut self,
+        path: &Path,
+        read: impl FnOnce(&Path) -> FontResult<FontliftFontFaceInfo>,
+    ) -> FontResult<FontliftFontFaceInfo> {
+        self.faces(path, |p| read(p).map(|info| vec![info]))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| FontError::InvalidFormat(format!("{}: no faces", path.display())))
+    }
+
+    /// [`face_info`](Self::face_info) for every face of the file, as `read`
+    /// returns them.
+    pub fn faces(
+        &mut self,
+        path: &Path,
+        read: impl FnOnce(&Path) -> FontResult<Vec<FontliftFontFaceInfo>>,
+    ) -> FontResult<Vec<FontliftFontFaceInfo>> {
+        if self.path.is_none() {
+            return read(path);
+        }
+
+        let stamp = FileStamp::of(path);
+        if let (Some(stamp), Some(entry)) = (stamp, self.entries.get(path)) {
+            if entry.stamp == stamp {
+                self.hits += 1;
+                return Ok(entry.faces.clone());
+            }
+        }
+
+        self.misses += 1;
+        let faces = read(path)?;
+        if let Some(stamp) = stamp {
+            self.entries.insert(
+                path.to_path_buf(),
+                CacheEntry {
+                    stamp,
+                    faces: faces.clone(),
+                },
+            );
+            self.dirty = true;
+        }
+        Ok(faces)
+    }
+
+    /// The faces last cached for `path`, whether or not the file has
+    /// changed since: what the file held when fontlift last read it.
+    pub fn recorded_faces(&self, path: &Path) -> Option<&[FontliftFontFaceInfo]> {
+        self.entries.get(path).map(|entry| entry.faces.as_slice())
+    }
+
+    /// Lookups answered from the cache so far.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Lookups that had to read the font file.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Drop entries for deleted files and write the cache if it changed.
+    pub fn save(&mut self) -> FontResult<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let before = self.entries.len();
+        self.entries.retain(|file, _| file.exists());
+        if !self.dirty && self.entries.len() == before {
+            return Ok(());
+        }
+
+        let json = serde_json::to_vec(&CacheFile {
+            version: CACHE_VERSION,
+            languages: metadata::name_languages(),
+            entries: &self.entries,
+        })
+        .map_err(|e| FontError::InvalidFormat(format!("Failed to encode metadata cache: {e}")))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(FontError::IoError)?;
+        }
+        let tmp = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+        fs::write(&tmp, json).map_err(FontError::IoError)?;
+        fs::rename(&tmp, path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            FontError::IoError(e)
+        })?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// [`save`](Self::save), logging instead of failing: a cache that cannot
+    /// be written must not break the listing it was meant to speed up.
+    pub fn finish(mut self) {
+        if let Err(e) = self.save() {
+            log::warn!("Could not write font metadata cache: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation;
+
+    #[test]
+    fn reuses_unchanged_entries_and_invalidates_changed_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_path = tmp.path().join("cache.json");
+        let font = tmp.path().join("Cached-Regular.ttf");
+        fs::write(&font, b"first").unwrap();
+        let read = |p: &Path| Ok(validation::extract_basic_info_from_path(p));
+
+        let mut cache = MetadataCache::load_from(cache_path.clone());
+        cache.face_info(&font, read).unwrap();
+        cache.finish();
+
+        let mut cache = MetadataCache::load_from(cache_path.clone());
+        let info = cache
+            .face_info(&font, |_| panic!("should be served from the cache"))
+            .unwrap();
+        assert_eq!(info.source.path, font);
+        assert_eq!((cache.hits(), cache.misses()), (1, 0));
+
+        fs::write(&font, b"changed size").unwrap();
+        cache.face_info(&font, read).unwrap();
+        assert_eq!(cache.misses(), 1);
+
+        fs::remove_file(&font).unwrap();
+        cache.save().unwrap();
+        assert!(MetadataCache::load_from(cache_path).entries.is_empty());
+    }
+
+    #[test]
+    fn caches_every_face_of_a_collection() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_path = tmp.path().join("cache.json");
+        let font = tmp.path().join("Family.ttc");
+        fs::write(&font, b"collection").unwrap();
+        let read = |p: &Path| {
+            let mut second = validation::extract_basic_info_from_path(p);
+            second.source.face_index = Some(1);
+            Ok(vec![validation::extract_basic_info_from_path(p), second])
+        };
+
+        let mut cache = MetadataCache::load_from(cache_path.clone());
+        assert_eq!(cache.faces(&font, read).unwrap().len(), 2);
+        cache.finish();
+
+        let mut cache = MetadataCache::load_from(cache_path);
+        let faces = cache
+            .faces(&font, |_| panic!("should be served from the cache"))
+            .unwrap();
+        assert_eq!(faces[1].source.face_index, Some(1));
+        let first = cache.face_info(&font, |_| unreachable!()).unwrap();
+        assert_eq!(first.source.face_index, None);
+    }
+
+    #[test]
+    fn corrupt_or_disabled_caches_read_through() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_path = tmp.path().join("cache.json");
+        fs::write(&cache_path, b"{ not json").unwrap();
+        assert!(MetadataCache::load_from(cache_path).entries.is_empty());
+
+        let font = tmp.path().join("Font.ttf");
+        fs::write(&font, b"x").unwrap();
+        let mut cache = MetadataCache::disabled();
+        for _ in 0..2 {
+            cache
+                .face_info(&font, |p| Ok(validation::extract_basic_info_from_path(p)))
+                .unwrap();
+        }
+        assert_eq!(cache.hits(), 0);
+    }
+}
@@ -0,0 +1,352 @@
+//! Moving a font library from another font manager into fontlift
+//! (`fontlift migrate`).
+//!
+//! FontBase, Suitcase Fusion and RightFont each keep their fonts in a library
+//! folder of their own:
+//!
+//! | Manager | Library | Fonts |
+//! |---|---|---|
+//! | FontBase | the folder chosen as FontBase's fonts folder | anywhere below it |
+//! | Suitcase Fusion | `Suitcase Fusion.fontvault` | in the vault's nested folders |
+//! | RightFont | `*.rightfontlibrary` | in its `fonts` folder |
+//!
+//! [`plan_migration`] collects every font file below the library, drops
+//! byte-identical copies (vaults often hold several), and reads activation
+//! state where the library keeps it in JSON metadata: an object naming a
+//! font file (`path`, `file`, `filePath`, `location`) next to a flag
+//! (`activated`, `active`, `enabled`, `isActive`). Managers that keep
+//! activation in a binary database leave it unknown, and those fonts are
+//! treated as active.
+//!
+//! Everything the plan cannot turn into an installable font — Type 1 and
+//! bitmap files, files that do not parse, metadata pointing at missing
+//! files — ends up in [`MigrationPlan::unmapped`] with the reason.
+
+use crate::digest::sha256_file;
+use crate::{metadata, validation, FontError, FontResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PATH_KEYS: &[&str] = &["path", "file", "filePath", "location"];
+const ACTIVE_KEYS: &[&str] = &["activated", "active", "enabled", "isActive"];
+
+/// Font files fontlift recognizes but cannot install.
+const UNSUPPORTED_EXTENSIONS: &[&str] = &["pfb", "pfa", "pfm", "afm", "lwfn", "suit", "bdf", "fon"];
+
+/// The font manager a library comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MigrationSource {
+    FontBase,
+    Suitcase,
+    RightFont,
+}
+
+impl MigrationSource {
+    /// `fontbase`, `suitcase` or `rightfont`; also the install origin suffix.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MigrationSource::FontBase => "fontbase",
+            MigrationSource::Suitcase => "suitcase",
+            MigrationSource::RightFont => "rightfont",
+        }
+    }
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            MigrationSource::FontBase => "FontBase",
+            MigrationSource::Suitcase => "Suitcase Fusion",
+            MigrationSource::RightFont => "RightFont",
+        }
+    }
+
+    /// Install origin recorded for imported fonts: `migrate:fontbase`.
+    pub fn origin(self) -> String {
+        format!("migrate:{}", self.as_str())
+    }
+
+    /// Where the fonts live inside `library`.
+    fn fonts_root(self, library: &Path) -> PathBuf {
+        match self {
+            MigrationSource::RightFont if library.join("fonts").is_dir() => library.join("fonts"),
+            _ => library.to_path_buf(),
+        }
+    }
+}
+
+/// One font file to bring over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigratedFont {
+    pub path: PathBuf,
+    pub sha256: String,
+    /// Whether the other manager had the font activated; `None` when the
+    /// library does not say.
+    pub active: Option<bool>,
+}
+
+impl MigratedFont {
+    /// Active, or unknown and so treated as active.
+    pub fn should_activate(&self) -> bool {
+        self.active != Some(false)
+    }
+}
+
+/// Something in the library that could not be migrated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Unmapped {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// What [`plan_migration`] found in a library.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationPlan {
+    pub source: MigrationSource,
+    pub library: PathBuf,
+    pub fonts: Vec<MigratedFont>,
+    pub unmapped: Vec<Unmapped>,
+}
+
+/// Outcome of a migration, for the report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationReport {
+    pub source: MigrationSource,
+    pub library: PathBuf,
+    /// Installed and active in fontlift.
+    pub installed: Vec<PathBuf>,
+    /// Inactive in the old manager, so left uninstalled.
+    pub inactive: Vec<PathBuf>,
+    /// Copied into the download store.
+    pub stored: Vec<PathBuf>,
+    pub unmapped: Vec<Unmapped>,
+}
+
+impl MigrationReport {
+    pub fn new(plan: &MigrationPlan) -> Self {
+        Self {
+            source: plan.source,
+            library: plan.library.clone(),
+            installed: Vec::new(),
+            inactive: Vec::new(),
+            stored: Vec::new(),
+            unmapped: plan.unmapped.clone(),
+        }
+    }
+}
+
+/// Read `library` as `source` lays it out.
+pub fn plan_migration(source: MigrationSource, library: &Path) -> FontResult<MigrationPlan> {
+    if !library.is_dir() {
+        return Err(FontError::FontNotFound(library.to_path_buf()));
+    }
+
+    let mut plan = MigrationPlan {
+        source,
+        library: library.to_path_buf(),
+        fonts: Vec::new(),
+        unmapped: Vec::new(),
+    };
+    let activation = read_activation(library, &mut plan.unmapped);
+
+    let mut seen: HashMap<String, PathBuf> = HashMap::new();
+    for path in files_under(&source.fonts_root(library)) {
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        if UNSUPPORTED_EXTENSIONS.contains(&extension.as_str()) {
+            plan.unmapped.push(Unmapped {
+                path,
+                reason: format!("fontlift cannot install .{extension} fonts"),
+            });
+            continue;
+        }
+        if !validation::is_valid_font_extension(&path) {
+            continue;
+        }
+        if let Err(e) = metadata::read_all_face_info(&path) {
+            plan.unmapped.push(Unmapped {
+                path,
+                reason: format!("not a readable font: {e}"),
+            });
+            continue;
+        }
+        let sha256 = sha256_file(&path)?;
+        if let Some(first) = seen.get(&sha256) {
+            plan.unmapped.push(Unmapped {
+                reason: format!("duplicate of {}", first.display()),
+                path,
+            });
+            continue;
+        }
+        seen.insert(sha256.clone(), path.clone());
+        let active = activation.get(&path).copied();
+        plan.fonts.push(MigratedFont {
+            path,
+            sha256,
+            active,
+        });
+    }
+    Ok(plan)
+}
+
+/// Activation flags from JSON metadata anywhere in `library`, keyed by font
+/// path. Entries whose font file is missing are reported as unmapped.
+fn read_activation(library: &Path, unmapped: &mut Vec<Unmapped>) -> HashMap<PathBuf, bool> {
+    let mut flags = HashMap::new();
+    for file in files_under(library) {
+        let is_json = file
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let Some(value) = is_json
+            .then(|| fs::read_to_string(&file).ok())
+            .flatten()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        else {
+            continue;
+        };
+        let base = file.parent().unwrap_or(library);
+        collect_flags(&value, base, library, &mut flags, unmapped);
+    }
+    flags
+}
+
+fn collect_flags(
+    value: &Value,
+    base: &Path,
+    library: &Path,
+    flags: &mut HashMap<PathBuf, bool>,
+    unmapped: &mut Vec<Unmapped>,
+) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                collect_flags(item, base, library, flags, unmapped);
+            }
+        }
+        Value::Object(object) => {
+            let path = PATH_KEYS
+                .iter()
+                .find_map(|key| object.get(*key).and_then(Value::as_str));
+            let active = ACTIVE_KEYS
+                .iter()
+                .find_map(|key| object.get(*key).and_then(Value::as_bool));
+            if let (Some(path), Some(active)) = (path, active) {
+                if validation::is_valid_font_extension(Path::new(path)) {
+                    match locate(path, base, library) {
+                        Some(found) => {
+                            flags.insert(found, active);
+                        }
+                        None => unmapped.push(Unmapped {
+                            path: PathBuf::from(path),
+                            reason: "listed in the library metadata, but the file is missing"
+                                .to_string(),
+                        }),
+                    }
+                }
+            }
+            for nested in object.values() {
+                collect_flags(nested, base, library, flags, unmapped);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A metadata path: absolute, or relative to the metadata file or library.
+fn locate(path: &str, base: &Path, library: &Path) -> Option<PathBuf> {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    [base.join(path), library.join(path)]
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+}
+
+/// Every file below `dir`, sorted. Hidden entries are skipped.
+fn files_under(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontlift_testfonts::TestFont;
+
+    #[test]
+    fn plans_fonts_activation_and_leftovers() {
+        let tmp = tempfile::tempdir().unwrap();
+        let library = tmp.path().join("Fonts.rightfontlibrary");
+        let fonts = library.join("fonts");
+        fs::create_dir_all(fonts.join("copies")).unwrap();
+        let active = TestFont::new("Moved Sans").write_to(&fonts).unwrap();
+        let inactive = TestFont::new("Moved Serif").write_to(&fonts).unwrap();
+        let unknown = TestFont::new("Moved Mono").write_to(&fonts).unwrap();
+        fs::copy(&active, fonts.join("copies/MovedSans-Regular.ttf")).unwrap();
+        fs::write(fonts.join("Old.pfb"), b"type 1").unwrap();
+        fs::write(fonts.join("Broken.otf"), b"not a font").unwrap();
+        fs::write(
+            library.join("metadata.json"),
+            serde_json::json!({"fonts": [
+                {"file": "fonts/MovedSans-Regular.ttf", "activated": true},
+                {"file": "fonts/MovedSerif-Regular.ttf", "activated": false},
+                {"file": "fonts/Gone-Regular.ttf", "activated": true},
+            ]})
+            .to_string(),
+        )
+        .unwrap();
+
+        let plan = plan_migration(MigrationSource::RightFont, &library).unwrap();
+        let state: Vec<_> = plan
+            .fonts
+            .iter()
+            .map(|f| (f.path.clone(), f.active))
+            .collect();
+        assert_eq!(
+            state,
+            [
+                (unknown, None),
+                (active, Some(true)),
+                (inactive, Some(false))
+            ]
+        );
+        assert!(plan.fonts[0].should_activate());
+
+        let reasons: Vec<_> = plan.unmapped.iter().map(|u| u.reason.as_str()).collect();
+        assert_eq!(reasons.len(), 4, "{reasons:?}");
+        assert!(reasons[0].contains("file is missing"));
+        assert!(reasons.iter().any(|r| r.contains(".pfb")));
+        assert!(reasons.iter().any(|r| r.starts_with("not a readable font")));
+        assert!(reasons.iter().any(|r| r.starts_with("duplicate of")));
+    }
+
+    #[test]
+    fn missing_library_is_an_error() {
+        let err = plan_migration(MigrationSource::FontBase, Path::new("/no/such/library"));
+        assert!(matches!(err, Err(FontError::FontNotFound(_))));
+        assert_eq!(MigrationSource::Suitcase.origin(), "migrate:suitcase");
+    }
+}
@@ -0,0 +1,268 @@
+//! Telling running applications that fonts changed.
+//!
+//! Windows managers broadcast `WM_FONTCHANGE` after every registration
+//! change, and macOS apps that use Core Text see new fonts on their own.
+//! Plenty of applications still cache their font menus — design tools,
+//! long-running renderers, in-house pipelines — and only reload when told.
+//! After each install, uninstall, remove or cleanup that changed something,
+//! fontlift can tell them, as configured in the `[notify]` table of
+//! `fontlift.toml`:
+//!
+//! ```toml
+//! [notify]
+//! # Run through the shell after each change.
+//! command = "pkill -HUP -x my-renderer"
+//! # Emit a `com.fontlaborg.fontlift.FontsChanged` signal on the session bus.
+//! dbus = true
+//! # Post a `com.fontlaborg.fontlift.FontsChanged` distributed notification
+//! # (macOS).
+//! distributed_notification = true
+//! ```
+//!
+//! The command gets the change in its environment: `FONTLIFT_EVENT`
+//! (`install`, `uninstall`, `remove` or `cleanup`), `FONTLIFT_SCOPE`
+//! (`user` or `system`) and `FONTLIFT_FONTS`, the changed files one per
+//! line. The D-Bus signal and the distributed notification carry the event
+//! name. The change has already happened when a hook runs, so a failing
+//! hook is reported, never turned into an error for the operation.
+//!
+//! `fontlift.toml` lives in the per-user configuration directory
+//! (`~/Library/Application Support/fontlift/` on macOS, `%APPDATA%\fontlift\`
+//! on Windows, `~/.config/fontlift/` elsewhere); `FONTLIFT_CONFIG_PATH`
+//! points somewhere else. Tables other than `[notify]` are ignored here.
+
+use crate::history::{HistoryEntry, HistoryOperation};
+use crate::{FontError, FontManager, FontResult, FontScope};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Name of the D-Bus signal and of the macOS distributed notification.
+pub const NOTIFICATION_NAME: &str = "com.fontlaborg.fontlift.FontsChanged";
+
+/// Object path the D-Bus signal is emitted from.
+pub const DBUS_OBJECT_PATH: &str = "/com/fontlaborg/fontlift";
+
+/// Where `fontlift.toml` is read from.
+pub fn config_path() -> PathBuf {
+    if let Some(path) = std::env::var_os("FONTLIFT_CONFIG_PATH") {
+        return PathBuf::from(path);
+    }
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("fontlift")
+        .join("fontlift.toml")
+}
+
+/// The `[notify]` table of `fontlift.toml`. Everything is off by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// Shell command to run after each change.
+    pub command: Option<String>,
+    /// Emit a D-Bus signal on the session bus.
+    pub dbus: bool,
+    /// Post a macOS distributed notification.
+    pub distributed_notification: bool,
+}
+
+#[derive(Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    notify: NotifyConfig,
+}
+
+impl NotifyConfig {
+    /// Read the `[notify]` table from [`config_path`]; no file means no
+    /// hooks.
+    pub fn load() -> FontResult<Self> {
+        Self::load_from(&config_path())
+    }
+
+    /// Read the `[notify]` table from `path`.
+    pub fn load_from(path: &Path) -> FontResult<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(FontError::IoError(e)),
+        };
+        toml::from_str::<ConfigFile>(&content)
+            .map(|file| file.notify)
+            .map_err(|e| FontError::InvalidFormat(format!("{}: {}", path.display(), e.message())))
+    }
+
+    /// Parse the `[notify]` table out of a `fontlift.toml`.
+    pub fn parse(content: &str) -> FontResult<Self> {
+        toml::from_str::<ConfigFile>(content)
+            .map(|file| file.notify)
+            .map_err(|e| FontError::InvalidFormat(e.message().to_string()))
+    }
+
+    /// Is any hook configured?
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Fonts an operation changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontChange {
+    pub operation: HistoryOperation,
+    pub scope: FontScope,
+    pub files: Vec<PathBuf>,
+}
+
+impl FontChange {
+    /// The change `entry` records, or `None` if it handled no files.
+    pub fn from_history(entry: &HistoryEntry) -> Option<Self> {
+        (!entry.files.is_empty()).then(|| Self {
+            operation: entry.operation,
+            scope: entry.scope,
+            files: entry.files.clone(),
+        })
+    }
+
+    fn scope_name(&self) -> &'static str {
+        match self.scope {
+            FontScope::User => "user",
+            FontScope::System => "system",
+        }
+    }
+}
+
+/// Run every hook `config` asks for, returning the ones that failed.
+///
+/// Hooks are independent: one failing does not stop the others.
+pub fn notify(
+    manager: &dyn FontManager,
+    config: &NotifyConfig,
+    change: &FontChange,
+) -> Vec<FontError> {
+    let mut failures = Vec::new();
+    if let Some(command) = &config.command {
+        if let Err(e) = run_command(command, change) {
+            failures.push(e);
+        }
+    }
+    if config.dbus {
+        if let Err(e) = emit_dbus_signal(change) {
+            failures.push(e);
+        }
+    }
+    if config.distributed_notification {
+        if let Err(e) = manager.post_font_change(change) {
+            failures.push(e);
+        }
+    }
+    failures
+}
+
+/// Run `command` through the shell with the change in its environment.
+pub fn run_command(command: &str, change: &FontChange) -> FontResult<()> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let files: Vec<String> = change
+        .files
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect();
+    let status = shell
+        .arg(command)
+        .env("FONTLIFT_EVENT", change.operation.as_str())
+        .env("FONTLIFT_SCOPE", change.scope_name())
+        .env("FONTLIFT_FONTS", files.join("\n"))
+        .status()
+        .map_err(|e| {
+            FontError::UnsupportedOperation(format!(
+                "Failed to run notify command '{command}': {e}"
+            ))
+        })?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(FontError::RegistrationFailed(format!(
+            "Notify command '{command}' failed ({status})"
+        )))
+    }
+}
+
+/// Emit [`NOTIFICATION_NAME`] on the session bus with `dbus-send`.
+fn emit_dbus_signal(change: &FontChange) -> FontResult<()> {
+    let output = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--type=signal",
+            DBUS_OBJECT_PATH,
+            NOTIFICATION_NAME,
+        ])
+        .arg(format!("string:{}", change.operation.as_str()))
+        .output()
+        .map_err(|e| FontError::UnsupportedOperation(format!("Failed to run dbus-send: {e}")))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(FontError::RegistrationFailed(format!(
+            "dbus-send failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_notify_table_and_ignores_the_rest() {
+        let config =
+            NotifyConfig::parse("[other]\nkey = 1\n\n[notify]\ncommand = \"true\"\ndbus = true\n")
+                .unwrap();
+        assert_eq!(config.command.as_deref(), Some("true"));
+        assert!(config.dbus);
+        assert!(!config.distributed_notification);
+
+        assert!(NotifyConfig::parse("").unwrap().is_empty());
+        assert!(NotifyConfig::parse("[notify]\ncomand = \"x\"\n").is_err());
+        let missing = tempfile::tempdir().unwrap().path().join("fontlift.toml");
+        assert!(NotifyConfig::load_from(&missing).unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commands_see_the_change_and_failures_are_collected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let out = tmp.path().join("event.txt");
+        let change = FontChange {
+            operation: HistoryOperation::Install,
+            scope: FontScope::User,
+            files: vec![PathBuf::from("/fonts/A.ttf"), PathBuf::from("/fonts/B.ttf")],
+        };
+        let config = NotifyConfig {
+            command: Some(format!(
+                "echo \"$FONTLIFT_EVENT $FONTLIFT_SCOPE\" > '{}'; echo \"$FONTLIFT_FONTS\" >> '{}'",
+                out.display(),
+                out.display()
+            )),
+            ..Default::default()
+        };
+        assert!(notify(&crate::DummyFontManager, &config, &change).is_empty());
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "install user\n/fonts/A.ttf\n/fonts/B.ttf\n"
+        );
+
+        let failing = NotifyConfig {
+            command: Some("exit 3".to_string()),
+            distributed_notification: true,
+            ..Default::default()
+        };
+        assert_eq!(notify(&crate::DummyFontManager, &failing, &change).len(), 2);
+    }
+}
@@ -0,0 +1,304 @@
+//! Install, uninstall and remove as every front end runs them.
+//!
+//! `fontlift install` and `uninstall` wrap a [`FontManager`] call in the
+//! same steps every time: the machine's [`ManagedPolicy`], validation in the
+//! out-of-process validator, the site [`ProtectionPolicy`], the install
+//! database and the history log. The daemon ([`crate::serve`]), the Python
+//! bindings and the C API change fonts through [`install`], [`uninstall`]
+//! and [`remove`], which run those steps around a single font, so what the
+//! CLI refuses or records they refuse and record too. The CLI installs and
+//! uninstalls in batches and calls the steps ([`validate`],
+//! [`check_protection`], [`record_install`], [`forget_install`],
+//! [`unregister`]) itself.
+//!
+//! Once the font has changed, a failed install database or history write
+//! is logged rather than returned, as in the CLI.
+
+use crate::history::{self, HistoryEntry, HistoryOperation};
+use crate::install_db::{self, InstallReason};
+use crate::plan::PlannedAction;
+use crate::policy::{self, ManagedPolicy};
+use crate::protection::ProtectionPolicy;
+use crate::validation_ext::{self, ValidationCache, ValidationStrictness, ValidatorConfig};
+use crate::{
+    digest, font_bytes, metadata, FontError, FontManager, FontResult, FontScope,
+    FontliftFontFaceInfo, FontliftFontSource,
+};
+use std::path::{Path, PathBuf};
+
+/// What [`validate`] found.
+#[derive(Debug)]
+pub enum Validation {
+    /// The validator checked every file: one result per path, in order.
+    /// `cached` of them passed before with the same contents and were not
+    /// checked again.
+    Checked {
+        results: Vec<FontResult<FontliftFontFaceInfo>>,
+        cached: usize,
+    },
+    /// No validator could be run, and the managed policy does not require
+    /// one.
+    Unavailable(FontError),
+}
+
+/// Validate `paths` with the out-of-process validator at `strictness`,
+/// skipping files the validation cache has seen pass unless `revalidate`.
+///
+/// An unavailable validator is only an error, a [`FontError::PolicyDenied`],
+/// when `managed` sets a validation preset.
+pub fn validate(
+    paths: &[PathBuf],
+    strictness: ValidationStrictness,
+    managed: &ManagedPolicy,
+    revalidate: bool,
+) -> FontResult<Validation> {
+    let config = ValidatorConfig::from_strictness(strictness);
+    let mut cache = ValidationCache::open().with_revalidate(revalidate);
+    match validation_ext::validate_and_introspect_cached(paths, &config, &mut cache) {
+        Ok(results) => {
+            let cached = cache.hits();
+            cache.finish();
+            Ok(Validation::Checked { results, cached })
+        }
+        Err(e) if managed.validation_strictness.is_some() => Err(FontError::PolicyDenied(format!(
+            "fonts must be validated, but the validator is unavailable: {}",
+            e.message()
+        ))),
+        Err(e) => Ok(Validation::Unavailable(e)),
+    }
+}
+
+/// Apply the site [`ProtectionPolicy`] before unregistering or deleting
+/// `path`, returning its warning in warn mode.
+///
+/// `postscript_names` are the faces being touched; when empty they are read
+/// from the file.
+pub fn check_protection(
+    policy: &ProtectionPolicy,
+    path: &Path,
+    postscript_names: &[&str],
+) -> FontResult<Option<String>> {
+    if !postscript_names.is_empty() {
+        return policy.check(path, postscript_names);
+    }
+    let names: Vec<String> = metadata::read_face_info(path)
+        .map(|info| vec![info.postscript_name])
+        .unwrap_or_default();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    policy.check(path, &names)
+}
+
+/// Record the font installed at `path` in the install database, with its
+/// SHA-256 and where it came from.
+pub fn record_install(
+    path: &Path,
+    scope: FontScope,
+    reason: InstallReason,
+    origin: Option<String>,
+    original_file_name: Option<String>,
+) -> FontResult<()> {
+    let sha256 = digest::sha256_file(path).ok();
+    install_db::update_install_db(|db| {
+        let record = db.record(path.to_path_buf(), scope, reason);
+        record.origin = origin;
+        record.original_file_name = original_file_name;
+        record.sha256 = sha256;
+    })
+}
+
+/// Drop the install database record of `path`, if it has one.
+pub fn forget_install(path: &Path) -> FontResult<()> {
+    // Most uninstalls target fonts fontlift never recorded; skip the locked
+    // write entirely in that case.
+    let tracked = install_db::load_install_db()
+        .map(|db| db.find(path).is_some())
+        .unwrap_or(true);
+    if !tracked {
+        return Ok(());
+    }
+    install_db::update_install_db(|db| {
+        db.forget(path);
+    })
+}
+
+/// The order [`unregister`] tries scopes in, starting with `preferred`.
+pub fn scope_order(preferred: FontScope) -> [FontScope; 2] {
+    match preferred {
+        FontScope::User => [FontScope::User, FontScope::System],
+        FontScope::System => [FontScope::System, FontScope::User],
+    }
+}
+
+/// Unregister `path` from the first of `scopes` that has it and return that
+/// scope. Scopes `managed` rules out are skipped.
+pub fn unregister(
+    manager: &dyn FontManager,
+    managed: &ManagedPolicy,
+    path: &Path,
+    scopes: &[FontScope],
+) -> FontResult<FontScope> {
+    let mut last_error: Option<FontError> = None;
+    for &scope in scopes {
+        if let Err(denied) = managed.check_scope(scope, "uninstall") {
+            last_error.get_or_insert(denied);
+            continue;
+        }
+        let source = FontliftFontSource::new(path.to_path_buf()).with_scope(Some(scope));
+        match manager.uninstall_font(&source) {
+            Ok(()) => return Ok(scope),
+            Err(err) => last_error = Some(err),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        FontError::RegistrationFailed(format!(
+            "Failed to uninstall font {} in any scope",
+            path.display()
+        ))
+    }))
+}
+
+/// Install `source` in its scope (user scope when it names none).
+///
+/// The file's path is the source [`ManagedPolicy::check_source`] judges. The
+/// font is validated at the policy's preset, or the default one, and
+/// replaces an installed font it conflicts with, as `fontlift install` does
+/// by default.
+pub fn install(manager: &dyn FontManager, source: &FontliftFontSource) -> FontResult<()> {
+    let scope = source.scope.unwrap_or(FontScope::User);
+    recorded_font(HistoryOperation::Install, scope, &source.path, |history| {
+        let managed = ManagedPolicy::load_default()?;
+        managed.check_source(&source.path.to_string_lossy())?;
+        managed.check_install(&source.path, scope)?;
+        let strictness = managed.enforce_validation(true, ValidationStrictness::default())?;
+        let paths = std::slice::from_ref(&source.path);
+        match validate(paths, strictness, &managed, false)? {
+            Validation::Checked { mut results, .. } => {
+                if let Some(Err(e)) = results.pop() {
+                    return Err(e);
+                }
+            }
+            Validation::Unavailable(e) => {
+                log::debug!("Validation skipped (validator unavailable): {e}");
+            }
+        }
+
+        let installed = installed_path(manager, source);
+        manager.install_font(source)?;
+        history.handled(&installed);
+        if let Err(e) = record_install(&installed, scope, InstallReason::Explicit, None, None) {
+            log::warn!("Could not record install reason: {e}");
+        }
+        Ok(())
+    })
+}
+
+/// [`install`] font `data` as a file named `file_name` (`MyFont.ttf`).
+///
+/// The bytes are staged in a temporary file first, which is the source the
+/// policy sees; a policy that lists allowed sources therefore refuses them.
+pub fn install_bytes(
+    manager: &dyn FontManager,
+    data: &[u8],
+    file_name: &str,
+    scope: FontScope,
+) -> FontResult<()> {
+    let staged = font_bytes::StagedFont::write(data, file_name)?;
+    install(
+        manager,
+        &FontliftFontSource::new(staged.path.clone()).with_scope(Some(scope)),
+    )
+}
+
+/// Unregister `source` from its scope, keeping the file.
+pub fn uninstall(manager: &dyn FontManager, source: &FontliftFontSource) -> FontResult<()> {
+    let scope = source.scope.unwrap_or(FontScope::User);
+    uninstall_across_scopes(manager, &source.path, &[scope]).map(drop)
+}
+
+/// [`uninstall`] `path` from the first of `scopes` that has it, as one
+/// operation in the history log, and return that scope.
+pub fn uninstall_across_scopes(
+    manager: &dyn FontManager,
+    path: &Path,
+    scopes: &[FontScope],
+) -> FontResult<FontScope> {
+    let first = scopes.first().copied().unwrap_or(FontScope::User);
+    let mut unregistered = first;
+    recorded_font(HistoryOperation::Uninstall, first, path, |history| {
+        let managed = ManagedPolicy::load_default()?;
+        if let Some(warning) = check_protection(&ProtectionPolicy::load_default()?, path, &[])? {
+            log::warn!("Protection policy: {warning}");
+        }
+        unregistered = unregister(manager, &managed, path, scopes)?;
+        history.handled(path);
+        if let Err(e) = forget_install(path) {
+            log::warn!("Could not update install database: {e}");
+        }
+        Ok(())
+    })?;
+    Ok(unregistered)
+}
+
+/// Unregister `source` from its scope and delete the file.
+pub fn remove(manager: &dyn FontManager, source: &FontliftFontSource) -> FontResult<()> {
+    let scope = source.scope.unwrap_or(FontScope::User);
+    recorded_font(HistoryOperation::Remove, scope, &source.path, |history| {
+        policy::check_managed_scope(scope, "removal")?;
+        let protection = ProtectionPolicy::load_default()?;
+        if let Some(warning) = check_protection(&protection, &source.path, &[])? {
+            log::warn!("Protection policy: {warning}");
+        }
+        manager.remove_font(source)?;
+        history.handled(&source.path);
+        if let Err(e) = forget_install(&source.path) {
+            log::warn!("Could not update install database: {e}");
+        }
+        Ok(())
+    })
+}
+
+/// Run `operation` and append it to the history log.
+pub(crate) fn recorded(
+    operation: HistoryOperation,
+    scope: FontScope,
+    run: impl FnOnce(&mut HistoryEntry) -> FontResult<()>,
+) -> FontResult<()> {
+    let mut history = HistoryEntry::start(operation, scope);
+    let result = run(&mut history);
+    if let Err(e) = history::append(&history.finish(result.as_ref().map(|_| ()))) {
+        log::warn!("Could not record history: {e}");
+    }
+    result
+}
+
+/// [`recorded`] for an operation on the font at `path`, which the history
+/// names as failed when the operation fails.
+fn recorded_font(
+    operation: HistoryOperation,
+    scope: FontScope,
+    path: &Path,
+    run: impl FnOnce(&mut HistoryEntry) -> FontResult<()>,
+) -> FontResult<()> {
+    recorded(operation, scope, |history| {
+        let result = run(history);
+        if let Err(e) = &result {
+            history.failed(path, e.message());
+        }
+        result
+    })
+}
+
+/// Where `manager` will register `source`: the copy its install plan makes,
+/// or the file itself.
+fn installed_path(manager: &dyn FontManager, source: &FontliftFontSource) -> PathBuf {
+    manager
+        .plan_install(source)
+        .ok()
+        .and_then(|plan| {
+            plan.into_iter().find_map(|action| match action {
+                PlannedAction::RegisterFont { path, .. } => Some(path),
+                _ => None,
+            })
+        })
+        .unwrap_or_else(|| source.path.clone())
+}
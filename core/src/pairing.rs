@@ -0,0 +1,451 @@
+//! Font pairing suggestions drawn from the installed inventory
+//! (`fontlift pair`).
+//!
+//! Pairing advice usually lives on websites that only know the fonts they
+//! host. This module answers from the fonts already on the machine, using
+//! the same cues a typographer would check first:
+//!
+//! - **Classification contrast.** A serif heading over sans-serif text (or
+//!   the reverse) reads as intentional; two serifs rarely do. Script and
+//!   display faces want a quiet text face underneath.
+//! - **x-height compatibility.** Faces set side by side at the same size
+//!   look mismatched when one has a much taller lowercase.
+//! - **Family range.** A partner with several weights and italics covers
+//!   more roles in a layout.
+//!
+//! Classification comes from `OS/2.sFamilyClass`, then PANOSE, then words in
+//! the family name ("Sans", "Slab", "Mono", ...), since many fonts leave the
+//! first two empty. Only one face per family is parsed; weights and italics
+//! come from the installed face list.
+
+use crate::{FontError, FontResult, FontliftFontFaceInfo};
+use read_fonts::{FileRef, TableProvider};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Broad design classification of a family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Classification {
+    Serif,
+    SlabSerif,
+    Sans,
+    Monospace,
+    Script,
+    Display,
+    Symbol,
+    Unknown,
+}
+
+impl Classification {
+    /// Serif, slab serif or sans: faces suited to running text.
+    pub fn is_text(self) -> bool {
+        matches!(
+            self,
+            Classification::Serif | Classification::SlabSerif | Classification::Sans
+        )
+    }
+
+    fn is_serif(self) -> bool {
+        matches!(self, Classification::Serif | Classification::SlabSerif)
+    }
+
+    /// From `OS/2.sFamilyClass` (class ID in the high byte).
+    fn from_family_class(value: i16) -> Option<Self> {
+        match value.to_be_bytes()[0] {
+            1..=4 | 7 => Some(Classification::Serif),
+            5 => Some(Classification::SlabSerif),
+            8 => Some(Classification::Sans),
+            9 => Some(Classification::Display),
+            10 => Some(Classification::Script),
+            12 => Some(Classification::Symbol),
+            _ => None,
+        }
+    }
+
+    /// From the PANOSE family kind, serif style and proportion digits.
+    fn from_panose(panose: &[u8]) -> Option<Self> {
+        match panose {
+            [2, _, _, 9, ..] => Some(Classification::Monospace),
+            [2, 11..=15, ..] => Some(Classification::Sans),
+            [2, 6, ..] => Some(Classification::SlabSerif),
+            [2, 2..=10, ..] => Some(Classification::Serif),
+            [3, ..] => Some(Classification::Script),
+            [4, ..] => Some(Classification::Display),
+            [5, ..] => Some(Classification::Symbol),
+            _ => None,
+        }
+    }
+
+    /// From words in the family name.
+    fn from_family_name(family: &str) -> Option<Self> {
+        let lower = family.to_lowercase();
+        let words: Vec<&str> = lower.split(|c: char| !c.is_alphanumeric()).collect();
+        let has = |candidates: &[&str]| words.iter().any(|w| candidates.contains(w));
+
+        if has(&["mono", "code", "monospace", "monospaced"]) {
+            Some(Classification::Monospace)
+        } else if has(&["slab"]) {
+            Some(Classification::SlabSerif)
+        } else if has(&["sans", "grotesk", "grotesque", "gothic"]) || lower.contains("sans") {
+            Some(Classification::Sans)
+        } else if has(&["serif", "antiqua"]) {
+            Some(Classification::Serif)
+        } else if has(&["script", "hand", "handwriting", "brush"]) {
+            Some(Classification::Script)
+        } else if has(&["display", "poster", "titling"]) {
+            Some(Classification::Display)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Classification::Serif => "serif",
+            Classification::SlabSerif => "slab serif",
+            Classification::Sans => "sans-serif",
+            Classification::Monospace => "monospace",
+            Classification::Script => "script",
+            Classification::Display => "display",
+            Classification::Symbol => "symbol",
+            Classification::Unknown => "unclassified",
+        })
+    }
+}
+
+/// Design traits read from one face.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaceTraits {
+    pub classification: Classification,
+    /// `OS/2.sxHeight` as a fraction of the em.
+    pub x_height: Option<f32>,
+    /// `OS/2.usWidthClass`: 1 (ultra-condensed) to 9 (ultra-expanded), 5 normal.
+    pub width: Option<u16>,
+}
+
+/// Read the traits of the face at `face_index` (0 for single-face files).
+///
+/// `family` is only used for name-based classification when the font's own
+/// tables do not say.
+pub fn read_face_traits(path: &Path, face_index: u32, family: &str) -> FontResult<FaceTraits> {
+    let data = std::fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let file = FileRef::new(&data)
+        .map_err(|e| FontError::InvalidFormat(format!("{}: {e}", path.display())))?;
+    let font = file
+        .fonts()
+        .nth(face_index as usize)
+        .and_then(Result::ok)
+        .ok_or_else(|| {
+            FontError::InvalidFormat(format!("{}: no face {face_index}", path.display()))
+        })?;
+
+    let os2 = font.os2().ok();
+    let fixed_pitch = font.post().is_ok_and(|post| post.is_fixed_pitch() != 0);
+    let classification = if fixed_pitch {
+        Some(Classification::Monospace)
+    } else {
+        os2.as_ref().and_then(|os2| {
+            Classification::from_family_class(os2.s_family_class())
+                .or_else(|| Classification::from_panose(os2.panose_10()))
+        })
+    }
+    .or_else(|| Classification::from_family_name(family))
+    .unwrap_or(Classification::Unknown);
+
+    let units_per_em = font.head().map(|head| head.units_per_em()).unwrap_or(0);
+    let width = os2
+        .as_ref()
+        .map(|os2| os2.us_width_class())
+        .filter(|w| (1..=9).contains(w));
+    let x_height = os2
+        .and_then(|os2| os2.sx_height())
+        .filter(|&x| x > 0 && units_per_em > 0)
+        .map(|x| x as f32 / units_per_em as f32);
+
+    Ok(FaceTraits {
+        classification,
+        x_height,
+        width,
+    })
+}
+
+/// What pairing needs to know about an installed family.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FamilyProfile {
+    pub family: String,
+    pub classification: Classification,
+    /// x-height as a fraction of the em, from the family's regular face.
+    pub x_height: Option<f32>,
+    /// Distinct `usWeightClass` values, ascending.
+    pub weights: Vec<u16>,
+    pub has_italic: bool,
+    pub faces: usize,
+}
+
+/// Group installed faces by family and read each family's traits from its
+/// most regular face (upright, closest to weight 400).
+///
+/// Families whose representative face cannot be read are classified from
+/// their name alone.
+pub fn profile_families(installed: &[FontliftFontFaceInfo]) -> Vec<FamilyProfile> {
+    let mut families: BTreeMap<String, Vec<&FontliftFontFaceInfo>> = BTreeMap::new();
+    for face in installed.iter().filter(|f| !f.family_name.is_empty()) {
+        families
+            .entry(face.family_name.to_lowercase())
+            .or_default()
+            .push(face);
+    }
+
+    families
+        .into_values()
+        .map(|faces| {
+            let regular = faces
+                .iter()
+                .min_by_key(|f| {
+                    (
+                        f.italic.unwrap_or(false),
+                        f.weight.unwrap_or(400).abs_diff(400),
+                    )
+                })
+                .expect("family has at least one face");
+            let family = regular.family_name.clone();
+            let traits = read_face_traits(
+                &regular.source.path,
+                regular.source.face_index.unwrap_or(0),
+                &family,
+            )
+            .unwrap_or_else(|e| {
+                log::debug!(
+                    "Pairing: could not read {}: {e}",
+                    regular.source.path.display()
+                );
+                FaceTraits {
+                    classification: Classification::from_family_name(&family)
+                        .unwrap_or(Classification::Unknown),
+                    x_height: None,
+                    width: None,
+                }
+            });
+
+            let mut weights: Vec<u16> = faces.iter().filter_map(|f| f.weight).collect();
+            weights.sort_unstable();
+            weights.dedup();
+
+            FamilyProfile {
+                family,
+                classification: traits.classification,
+                x_height: traits.x_height,
+                weights,
+                has_italic: faces.iter().any(|f| f.italic == Some(true)),
+                faces: faces.len(),
+            }
+        })
+        .collect()
+}
+
+/// Find a family by name, ignoring case.
+pub fn find_family<'a>(profiles: &'a [FamilyProfile], family: &str) -> Option<&'a FamilyProfile> {
+    profiles
+        .iter()
+        .find(|p| p.family.eq_ignore_ascii_case(family.trim()))
+}
+
+/// An installed family that complements the requested one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairingSuggestion {
+    pub family: String,
+    pub classification: Classification,
+    /// Higher is a better match; only positive scores are suggested.
+    pub score: i32,
+    /// One short reason per factor that moved the score.
+    pub rationale: Vec<String>,
+}
+
+/// Rank `candidates` as partners for `target`, best first, keeping at most
+/// `limit` suggestions.
+pub fn suggest_pairings(
+    target: &FamilyProfile,
+    candidates: &[FamilyProfile],
+    limit: usize,
+) -> Vec<PairingSuggestion> {
+    let mut suggestions: Vec<PairingSuggestion> = candidates
+        .iter()
+        .filter(|c| !c.family.eq_ignore_ascii_case(&target.family))
+        .filter(|c| c.classification != Classification::Symbol)
+        .filter_map(|candidate| {
+            let (score, rationale) = score_pair(target, candidate);
+            (score > 0).then(|| PairingSuggestion {
+                family: candidate.family.clone(),
+                classification: candidate.classification,
+                score,
+                rationale,
+            })
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.family.cmp(&b.family)));
+    suggestions.truncate(limit);
+    suggestions
+}
+
+fn score_pair(target: &FamilyProfile, candidate: &FamilyProfile) -> (i32, Vec<String>) {
+    use Classification::*;
+
+    let mut score = 0;
+    let mut rationale = Vec::new();
+    let (t, c) = (target.classification, candidate.classification);
+
+    if t.is_text() && c.is_text() && t.is_serif() != c.is_serif() {
+        score += 3;
+        rationale.push(format!("{c} contrasts with {t}"));
+    } else if matches!(t, Script | Display) && c.is_text() {
+        score += 3;
+        rationale.push(format!("a quiet {c} text face balances a {t} face"));
+    } else if t.is_text() && matches!(c, Script | Display) {
+        score += 1;
+        rationale.push(format!("{c} accent for headings"));
+    } else if c == Monospace && t != Monospace {
+        score += 1;
+        rationale.push("monospace for code and tabular data".to_string());
+    } else if t == c && t != Unknown {
+        score -= 2;
+        rationale.push(format!(
+            "both are {t}, so they compete rather than contrast"
+        ));
+    }
+
+    if let (Some(tx), Some(cx)) = (target.x_height, candidate.x_height) {
+        let diff = (tx - cx).abs();
+        if diff <= 0.02 {
+            score += 2;
+            rationale.push(format!("matching x-height ({cx:.2} vs {tx:.2} em)"));
+        } else if diff <= 0.05 {
+            score += 1;
+            rationale.push(format!("compatible x-height ({cx:.2} vs {tx:.2} em)"));
+        } else if diff > 0.08 {
+            score -= 1;
+            rationale.push(format!(
+                "x-height differs noticeably ({cx:.2} vs {tx:.2} em)"
+            ));
+        }
+    }
+
+    if c.is_text() {
+        if candidate.weights.len() >= 3 {
+            score += 1;
+            rationale.push(format!("{} weights", candidate.weights.len()));
+        }
+        if candidate.has_italic {
+            score += 1;
+            rationale.push("has italics".to_string());
+        }
+    }
+
+    (score, rationale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata;
+    use fontlift_testfonts::TestFont;
+
+    fn install(dir: &Path, fonts: &[TestFont]) -> Vec<FontliftFontFaceInfo> {
+        fonts
+            .iter()
+            .map(|font| metadata::read_face_info(&font.write_to(dir).unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn classifies_from_family_class_panose_and_name() {
+        assert_eq!(
+            Classification::from_family_class(i16::from_be_bytes([8, 1])),
+            Some(Classification::Sans)
+        );
+        assert_eq!(
+            Classification::from_panose(&[2, 6, 6, 3, 5, 4, 5, 2, 3, 4]),
+            Some(Classification::SlabSerif)
+        );
+        assert_eq!(Classification::from_panose(&[0; 10]), None);
+        assert_eq!(
+            Classification::from_family_name("Source Code Pro"),
+            Some(Classification::Monospace)
+        );
+        assert_eq!(
+            Classification::from_family_name("Open Sans"),
+            Some(Classification::Sans)
+        );
+        assert_eq!(
+            Classification::from_family_name("Playfair Display"),
+            Some(Classification::Display)
+        );
+
+        let tmp = tempfile::tempdir().unwrap();
+        let classed = TestFont::new("Plain")
+            .with_family_class(2, 1)
+            .write_to(tmp.path())
+            .unwrap();
+        let traits = read_face_traits(&classed, 0, "Plain Sans").unwrap();
+        assert_eq!(traits.classification, Classification::Serif);
+        assert_eq!(traits.x_height, Some(0.5));
+    }
+
+    #[test]
+    fn suggests_contrasting_families_with_compatible_x_heights() {
+        let tmp = tempfile::tempdir().unwrap();
+        let installed = install(
+            tmp.path(),
+            &[
+                TestFont::new("Heading Serif")
+                    .with_family_class(2, 0)
+                    .with_x_height(480),
+                TestFont::new("Body Grotesk")
+                    .with_family_class(8, 1)
+                    .with_x_height(490),
+                TestFont::new("Body Grotesk")
+                    .with_style("Bold")
+                    .with_weight(700)
+                    .with_family_class(8, 1)
+                    .with_x_height(490),
+                TestFont::new("Body Grotesk")
+                    .with_style("Light Italic")
+                    .with_weight(300)
+                    .with_italic(true)
+                    .with_family_class(8, 1)
+                    .with_x_height(490),
+                TestFont::new("Tall Sans").with_x_height(600),
+                TestFont::new("Other Serif")
+                    .with_family_class(3, 0)
+                    .with_x_height(480),
+            ],
+        );
+
+        let profiles = profile_families(&installed);
+        let grotesk = find_family(&profiles, "body grotesk").unwrap();
+        assert_eq!(grotesk.weights, [300, 400, 700]);
+        assert!(grotesk.has_italic);
+        assert_eq!(grotesk.faces, 3);
+
+        let target = find_family(&profiles, "Heading Serif").unwrap();
+        let suggestions = suggest_pairings(target, &profiles, 5);
+        let families: Vec<&str> = suggestions.iter().map(|s| s.family.as_str()).collect();
+        assert_eq!(families, ["Body Grotesk", "Tall Sans"]);
+        assert_eq!(suggestions[0].score, 7);
+        assert!(suggestions[0].rationale[0].contains("sans-serif contrasts with serif"));
+        assert!(suggestions[1]
+            .rationale
+            .iter()
+            .any(|r| r.contains("x-height differs")));
+
+        assert_eq!(suggest_pairings(target, &profiles, 1).len(), 1);
+    }
+}
@@ -0,0 +1,201 @@
+//! Path comparison that respects the volume's case sensitivity.
+//!
+//! macOS and Windows volumes are usually case-insensitive, so fontlift used
+//! to lowercase paths before comparing them. APFS and HFS+ can also be
+//! formatted case-sensitive, and there `/Volumes/Fonts/Inter.ttf` and
+//! `/Volumes/Fonts/inter.ttf` are two different files: lowercasing would
+//! dedupe one of them away, or report one as a conflict with the other.
+//!
+//! [`comparison_key`] lowercases only when the volume holding the path is
+//! case-insensitive. Sensitivity is probed once per volume, without
+//! writing anything: look up an existing name with its case flipped and see
+//! whether that finds the same file.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+/// What to assume when nothing on the volume can be probed.
+const DEFAULT_CASE_SENSITIVE: bool = !cfg!(any(target_os = "macos", windows));
+
+/// How many directory entries to try before giving up on a probe.
+const PROBE_ENTRIES: usize = 32;
+
+/// The path, or its closest ancestor, that exists.
+fn existing_ancestor(path: &Path) -> Option<&Path> {
+    path.ancestors()
+        .find(|candidate| !candidate.as_os_str().is_empty() && candidate.exists())
+}
+
+/// `Fonts` → `fONTS`, or `None` if the name has no cased letters.
+fn flip_case(name: &str) -> Option<String> {
+    let flipped: String = name
+        .chars()
+        .map(|c| {
+            if c.is_uppercase() {
+                c.to_lowercase().next().unwrap_or(c)
+            } else {
+                c.to_uppercase().next().unwrap_or(c)
+            }
+        })
+        .collect();
+    (flipped != name).then_some(flipped)
+}
+
+#[cfg(unix)]
+mod volume {
+    use std::os::unix::fs::MetadataExt;
+    use std::path::Path;
+
+    pub type VolumeId = u64;
+
+    pub fn id(path: &Path) -> Option<VolumeId> {
+        std::fs::metadata(path).ok().map(|m| m.dev())
+    }
+
+    /// Whether `a` and `b` name the same file. `b` may not exist.
+    pub fn same_file(a: &Path, b: &Path) -> Option<bool> {
+        let a = std::fs::metadata(a).ok()?;
+        match std::fs::metadata(b) {
+            Ok(b) => Some(a.dev() == b.dev() && a.ino() == b.ino()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Some(false),
+            Err(_) => None,
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod volume {
+    use std::path::{Component, Path};
+
+    pub type VolumeId = std::ffi::OsString;
+
+    /// The drive or UNC prefix; the standard library does not expose the
+    /// volume serial number.
+    pub fn id(path: &Path) -> Option<VolumeId> {
+        match path.components().next()? {
+            Component::Prefix(prefix) => Some(prefix.as_os_str().to_os_string()),
+            _ => None,
+        }
+    }
+
+    /// Without file ids, a flipped name that resolves is assumed to be the
+    /// same file.
+    pub fn same_file(a: &Path, b: &Path) -> Option<bool> {
+        std::fs::metadata(a).ok()?;
+        match std::fs::metadata(b) {
+            Ok(_) => Some(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Some(false),
+            Err(_) => None,
+        }
+    }
+}
+
+/// Probe `existing` (a path that exists) for case sensitivity: `Some(true)`
+/// if a case-flipped name does not find the same file.
+fn probe(existing: &Path) -> Option<bool> {
+    let flipped_sibling = |path: &Path| -> Option<bool> {
+        let name = path.file_name()?.to_str()?;
+        let flipped = path.with_file_name(flip_case(name)?);
+        volume::same_file(path, &flipped).map(|same| !same)
+    };
+
+    // Entries inside the directory are on its volume, so try those first. The
+    // directory's own name is looked up in its parent, which is a different
+    // volume when `existing` is a mount point.
+    if existing.is_dir() {
+        if let Ok(entries) = std::fs::read_dir(existing) {
+            let found = entries
+                .flatten()
+                .take(PROBE_ENTRIES)
+                .find_map(|entry| flipped_sibling(&entry.path()));
+            if found.is_some() {
+                return found;
+            }
+        }
+    }
+
+    let parent = existing.parent()?;
+    if volume::id(parent) != volume::id(existing) {
+        return None;
+    }
+    flipped_sibling(existing)
+}
+
+fn cache() -> &'static Mutex<HashMap<volume::VolumeId, bool>> {
+    static CACHE: OnceLock<Mutex<HashMap<volume::VolumeId, bool>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Whether the volume `path` is on (or would be on, if it does not exist
+/// yet) treats names that differ only in case as different files.
+///
+/// Probed once per volume and cached for the life of the process. Falls
+/// back to the platform default (insensitive on macOS and Windows) when
+/// nothing on the volume can be probed.
+pub fn is_case_sensitive(path: &Path) -> bool {
+    let Some(existing) = existing_ancestor(path) else {
+        return DEFAULT_CASE_SENSITIVE;
+    };
+    let Some(volume) = volume::id(existing) else {
+        return DEFAULT_CASE_SENSITIVE;
+    };
+
+    if let Some(known) = cache().lock().ok().and_then(|c| c.get(&volume).copied()) {
+        return known;
+    }
+    let sensitive = probe(existing).unwrap_or(DEFAULT_CASE_SENSITIVE);
+    if let Ok(mut cache) = cache().lock() {
+        cache.insert(volume, sensitive);
+    }
+    sensitive
+}
+
+/// A string that is equal for two paths exactly when they name the same
+/// location: forward slashes, no doubled separators, and lowercased only on
+/// case-insensitive volumes.
+pub fn comparison_key(path: &Path) -> String {
+    let mut key = path.to_string_lossy().replace('\\', "/");
+    while key.contains("//") {
+        key = key.replace("//", "/");
+    }
+    if is_case_sensitive(path) {
+        key
+    } else {
+        key.to_lowercase()
+    }
+}
+
+/// `a` and `b` name the same location, by [`comparison_key`].
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    comparison_key(a) == comparison_key(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flip_case_swaps_letters_only() {
+        assert_eq!(flip_case("Fonts-2").as_deref(), Some("fONTS-2"));
+        assert_eq!(flip_case("2024"), None);
+    }
+
+    #[test]
+    fn probe_matches_what_the_temp_volume_does() {
+        let tmp = tempfile::tempdir().unwrap();
+        let font = tmp.path().join("Inter-Regular.ttf");
+        std::fs::write(&font, b"font").unwrap();
+        let insensitive = tmp.path().join("inter-regular.ttf").exists();
+
+        assert_eq!(is_case_sensitive(&font), !insensitive);
+        // Paths that do not exist yet use their volume's answer.
+        let planned = tmp.path().join("New/Sub/Font.otf");
+        assert_eq!(is_case_sensitive(&planned), !insensitive);
+
+        assert_eq!(
+            same_path(&font, &tmp.path().join("INTER-REGULAR.TTF")),
+            insensitive
+        );
+    }
+}
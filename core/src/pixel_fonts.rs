@@ -0,0 +1,413 @@
+//! Pixel and bitmap fonts, which render badly when the OS smooths them.
+//!
+//! A pixel font is drawn for a few exact sizes, one design unit per screen
+//! pixel. Antialiasing blurs those edges, so such fonts carry only embedded
+//! bitmaps (`EBDT`/`EBLC`, no outlines), ask for unsmoothed rendering in
+//! their `gasp` table, or at least have outlines traced from pixels: square
+//! staircases on a coarse grid. [`analyze`] finds all three at install time,
+//! and [`PixelFontReport::warning`] explains what each platform will do:
+//!
+//! - macOS ignores `gasp` and antialiases everything; bitmap-only fonts show
+//!   up only at their strike sizes, if at all.
+//! - Windows GDI honors `gasp`, so a font that already turns smoothing off
+//!   renders crisply there; DirectWrite apps may still smooth it.
+//!
+//! Windows has no per-font registry switch for smoothing: the setting that
+//! exists (`FontSmoothing`) is global. The per-font equivalent is the font's
+//! own `gasp` table, so `fontlift install --no-smoothing` installs a copy
+//! whose `gasp` table turns smoothing off at every size
+//! ([`write_unsmoothed_copy`]). The source file is never modified.
+
+use crate::{FontError, FontResult};
+use read_fonts::{
+    tables::{
+        gasp::GaspRangeBehavior,
+        glyf::{CurvePoint, Glyph},
+    },
+    types::{GlyphId, Tag},
+    FileRef, FontRef, TableProvider,
+};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// `gasp` flags for an unsmoothed range: grid-fit, no grayscale.
+const UNSMOOTHED_GASP_FLAGS: u16 = 0x0001;
+
+/// Glyphs sampled when looking for pixel outlines.
+const SAMPLED_GLYPHS: usize = 256;
+
+/// Fewer outlined glyphs than this is not enough to call a font pixel-drawn.
+const MIN_PIXEL_GLYPHS: usize = 5;
+
+/// Plain rectangles (4 points) are not pixel art; staircases have more.
+const MIN_POINTS_PER_CONTOUR: usize = 6;
+
+/// What makes a font a pixel font.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PixelFontReport {
+    /// Embedded bitmaps and no outlines at all.
+    pub bitmap_only: bool,
+    /// TrueType outlines made only of axis-aligned steps on a coarse grid.
+    pub pixel_outlines: bool,
+    /// `gasp` turns off smoothing for every size up to this PPEM
+    /// (`u16::MAX` means all sizes).
+    pub unsmoothed_up_to_ppem: Option<u16>,
+}
+
+impl PixelFontReport {
+    /// Whether `--no-smoothing` changes anything: pixel outlines that the
+    /// font does not already ask to keep unsmoothed at every size.
+    pub fn benefits_from_no_smoothing(&self) -> bool {
+        self.pixel_outlines && self.unsmoothed_up_to_ppem != Some(u16::MAX)
+    }
+
+    /// One paragraph for the install output, with notes for the current
+    /// platform.
+    pub fn warning(&self) -> String {
+        let what = match (self.bitmap_only, self.unsmoothed_up_to_ppem) {
+            (true, _) => "is a bitmap-only font (no outlines)".to_string(),
+            (false, Some(u16::MAX)) => "asks for unsmoothed rendering at every size".to_string(),
+            (false, Some(ppem)) => {
+                format!("asks for unsmoothed rendering up to {ppem} px per em")
+            }
+            (false, None) => "has pixel-grid outlines, which smoothing blurs".to_string(),
+        };
+        let note = if cfg!(target_os = "macos") {
+            if self.bitmap_only {
+                "macOS cannot scale bitmap-only fonts and may not list them in font menus."
+            } else {
+                "macOS ignores that request and always antialiases; use it at its design sizes."
+            }
+        } else if cfg!(windows) {
+            if self.bitmap_only {
+                "Windows shows bitmap-only fonts only at their strike sizes."
+            } else if self.unsmoothed_up_to_ppem.is_some() {
+                "GDI apps honor that request; DirectWrite apps may still smooth the font."
+            } else {
+                "Windows smooths it unless its gasp table says otherwise (see --no-smoothing)."
+            }
+        } else {
+            "Most renderers antialias text regardless; use the font at its design sizes."
+        };
+        format!("{what}. {note}")
+    }
+}
+
+fn open(path: &Path) -> FontResult<Vec<u8>> {
+    std::fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })
+}
+
+fn report_for(font: &FontRef<'_>) -> Option<PixelFontReport> {
+    let has = |tag: &[u8; 4]| font.table_data(Tag::new(tag)).is_some();
+    let has_bitmaps = has(b"EBDT") || has(b"bdat");
+    let has_outlines = has(b"glyf") || has(b"CFF ") || has(b"CFF2");
+    let bitmap_only = has_bitmaps && !has_outlines;
+
+    let smoothing = GaspRangeBehavior::GASP_DOGRAY | GaspRangeBehavior::GASP_SYMMETRIC_SMOOTHING;
+    let unsmoothed_up_to_ppem = font.gasp().ok().and_then(|gasp| {
+        // Ranges are sorted by their upper bound; only a run of unsmoothed
+        // ranges starting at size 0 matters.
+        let mut up_to = None;
+        for range in gasp.gasp_ranges() {
+            if range.range_gasp_behavior().intersects(smoothing) {
+                break;
+            }
+            up_to = Some(range.range_max_ppem());
+        }
+        up_to
+    });
+
+    let pixel_outlines = !bitmap_only && has_pixel_outlines(font);
+
+    (bitmap_only || pixel_outlines || unsmoothed_up_to_ppem.is_some()).then_some(PixelFontReport {
+        bitmap_only,
+        pixel_outlines,
+        unsmoothed_up_to_ppem,
+    })
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The grid spacing of a contour drawn from pixels: every point on-curve,
+/// every edge horizontal or vertical. `None` for anything else.
+fn pixel_grid(contour: &[CurvePoint]) -> Option<u32> {
+    if contour.len() < 4 || contour.iter().any(|p| !p.on_curve) {
+        return None;
+    }
+    let closing = contour.last().zip(contour.first());
+    let rectilinear = contour
+        .windows(2)
+        .map(|pair| (&pair[0], &pair[1]))
+        .chain(closing)
+        .all(|(a, b)| a.x == b.x || a.y == b.y);
+    rectilinear.then(|| {
+        contour.iter().fold(0, |grid, p| {
+            gcd(
+                gcd(grid, p.x.unsigned_abs().into()),
+                p.y.unsigned_abs().into(),
+            )
+        })
+    })
+}
+
+/// Whether the sampled TrueType glyphs all look traced from pixels: only
+/// rectilinear contours, some of them staircases rather than rectangles, on
+/// a grid of at least 1/64 em.
+fn has_pixel_outlines(font: &FontRef<'_>) -> bool {
+    let (Ok(loca), Ok(glyf), Ok(head)) = (font.loca(None), font.glyf(), font.head()) else {
+        return false;
+    };
+    let mut glyphs = 0;
+    let mut grid = 0;
+    let mut points = 0;
+    let mut contours = 0;
+    for gid in 0..loca.len().saturating_sub(1).min(SAMPLED_GLYPHS) {
+        let Ok(Some(Glyph::Simple(glyph))) = loca.get_glyf(GlyphId::new(gid as u32), &glyf) else {
+            continue;
+        };
+        let all: Vec<CurvePoint> = glyph.points().collect();
+        let mut start = 0;
+        for end in glyph.end_pts_of_contours() {
+            let end = usize::from(end.get());
+            let Some(contour) = all.get(start..=end) else {
+                return false;
+            };
+            let Some(contour_grid) = pixel_grid(contour) else {
+                return false;
+            };
+            grid = gcd(grid, contour_grid);
+            points += contour.len();
+            contours += 1;
+            start = end + 1;
+        }
+        glyphs += 1;
+    }
+    let min_grid = (u32::from(head.units_per_em()) / 64).max(1);
+    glyphs >= MIN_PIXEL_GLYPHS && points >= contours * MIN_POINTS_PER_CONTOUR && grid >= min_grid
+}
+
+/// Check every face of the file at `path`. Returns `None` for ordinary
+/// outline fonts, and for files that cannot be parsed (validation reports
+/// those).
+pub fn analyze(path: &Path) -> FontResult<Option<PixelFontReport>> {
+    let data = open(path)?;
+    let Ok(file) = FileRef::new(&data) else {
+        return Ok(None);
+    };
+    let report = file.fonts().flatten().find_map(|font| report_for(&font));
+    Ok(report)
+}
+
+/// A copy of `data` whose `gasp` table turns off smoothing at every size.
+///
+/// Only single fonts are supported; collections share tables between faces
+/// and are rejected.
+pub fn without_smoothing(data: &[u8]) -> FontResult<Vec<u8>> {
+    let font = match FileRef::new(data) {
+        Ok(FileRef::Font(font)) => font,
+        Ok(FileRef::Collection(_)) => {
+            return Err(FontError::UnsupportedOperation(
+                "--no-smoothing does not support font collections".to_string(),
+            ))
+        }
+        Err(e) => return Err(FontError::InvalidFormat(e.to_string())),
+    };
+
+    let mut tables: BTreeMap<Tag, Vec<u8>> = font
+        .table_directory
+        .table_records()
+        .iter()
+        .filter_map(|record| {
+            let tag = record.tag();
+            Some((tag, font.table_data(tag)?.as_bytes().to_vec()))
+        })
+        .collect();
+
+    // Version 1, one range covering every size.
+    let mut gasp = Vec::with_capacity(8);
+    gasp.extend_from_slice(&1u16.to_be_bytes());
+    gasp.extend_from_slice(&1u16.to_be_bytes());
+    gasp.extend_from_slice(&u16::MAX.to_be_bytes());
+    gasp.extend_from_slice(&UNSMOOTHED_GASP_FLAGS.to_be_bytes());
+    tables.insert(Tag::new(b"gasp"), gasp);
+
+    Ok(write_sfnt(font.table_directory.sfnt_version(), &tables))
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// Serialize `tables` as a single sfnt, tables in tag order and 4-byte
+/// aligned, with table checksums and `head.checksumAdjustment` recomputed.
+pub(crate) fn write_sfnt(sfnt_version: u32, tables: &BTreeMap<Tag, Vec<u8>>) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.max(1).leading_zeros() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+    let range_shift = num_tables * 16 - search_range;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&sfnt_version.to_be_bytes());
+    for value in [num_tables, search_range, entry_selector, range_shift] {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+
+    let head_tag = Tag::new(b"head");
+    let mut offset = 12 + 16 * tables.len();
+    let mut head_offset = None;
+    for (tag, data) in tables {
+        let mut data = data.clone();
+        if *tag == head_tag && data.len() >= 12 {
+            data[8..12].fill(0);
+            head_offset = Some(offset);
+        }
+        out.extend_from_slice(&tag.to_be_bytes());
+        out.extend_from_slice(&checksum(&data).to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (tag, data) in tables {
+        let start = out.len();
+        out.extend_from_slice(data);
+        if *tag == head_tag && data.len() >= 12 {
+            out[start + 8..start + 12].fill(0);
+        }
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+
+    if let Some(head) = head_offset {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&out));
+        out[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    out
+}
+
+/// A `--no-smoothing` copy of a font in a private temp directory, removed
+/// when dropped.
+#[derive(Debug)]
+pub struct UnsmoothedCopy {
+    /// Same file name as the original.
+    pub path: PathBuf,
+    dir: PathBuf,
+}
+
+impl Drop for UnsmoothedCopy {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Write [`without_smoothing`] of the font at `path` to a temp directory,
+/// keeping its file name so it installs under the same name.
+pub fn write_unsmoothed_copy(path: &Path) -> FontResult<UnsmoothedCopy> {
+    let data = without_smoothing(&open(path)?)?;
+    let dir = std::env::temp_dir().join(format!("fontlift-unsmoothed-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).map_err(FontError::IoError)?;
+    let copy = UnsmoothedCopy {
+        path: dir.join(path.file_name().unwrap_or_default()),
+        dir,
+    };
+    std::fs::write(&copy.path, data).map_err(FontError::IoError)?;
+    Ok(copy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontlift_testfonts::TestFont;
+
+    #[test]
+    fn outline_fonts_are_not_pixel_fonts_until_smoothing_is_off() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = TestFont::new("Grid Sans").write_to(tmp.path()).unwrap();
+        assert_eq!(analyze(&path).unwrap(), None);
+
+        let copy = write_unsmoothed_copy(&path).unwrap();
+        assert_eq!(copy.path.file_name(), path.file_name());
+        let report = analyze(&copy.path)
+            .unwrap()
+            .expect("gasp turns smoothing off");
+        assert_eq!(report.unsmoothed_up_to_ppem, Some(u16::MAX));
+        assert!(!report.bitmap_only);
+        // Rectangles are not pixel art.
+        assert!(!report.pixel_outlines);
+        assert!(!report.benefits_from_no_smoothing());
+        assert!(report
+            .warning()
+            .contains("unsmoothed rendering at every size"));
+
+        // Still a well-formed font with the same names.
+        let info = crate::metadata::read_face_info(&copy.path).unwrap();
+        assert_eq!(info.postscript_name, "GridSans-Regular");
+        let data = std::fs::read(&copy.path).unwrap();
+        assert_eq!(checksum(&data), 0xB1B0_AFBA);
+
+        let dir = copy.path.parent().unwrap().to_path_buf();
+        drop(copy);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn pixel_grid_accepts_staircases_only() {
+        let p = CurvePoint::on_curve;
+        // An "L" drawn with 100-unit pixels.
+        let stairs = [
+            p(0, 0),
+            p(300, 0),
+            p(300, 100),
+            p(100, 100),
+            p(100, 500),
+            p(0, 500),
+        ];
+        assert_eq!(pixel_grid(&stairs), Some(100));
+
+        let diagonal = [p(0, 0), p(300, 0), p(100, 500), p(0, 500)];
+        assert_eq!(pixel_grid(&diagonal), None);
+        let curved = [
+            p(0, 0),
+            CurvePoint::off_curve(300, 0),
+            p(300, 300),
+            p(0, 300),
+        ];
+        assert_eq!(pixel_grid(&curved), None);
+    }
+
+    #[test]
+    fn bitmap_tables_without_outlines_are_bitmap_only() {
+        let data = TestFont::new("Bits").build();
+        let font = FontRef::new(&data).unwrap();
+        let mut tables: BTreeMap<Tag, Vec<u8>> = font
+            .table_directory
+            .table_records()
+            .iter()
+            .filter(|r| !matches!(&r.tag().to_be_bytes(), b"glyf" | b"loca"))
+            .map(|r| {
+                (
+                    r.tag(),
+                    font.table_data(r.tag()).unwrap().as_bytes().to_vec(),
+                )
+            })
+            .collect();
+        tables.insert(Tag::new(b"EBDT"), vec![0, 2, 0, 0]);
+        tables.insert(Tag::new(b"EBLC"), vec![0, 2, 0, 0, 0, 0, 0, 0]);
+        let rebuilt = write_sfnt(font.table_directory.sfnt_version(), &tables);
+
+        let report = report_for(&FontRef::new(&rebuilt).unwrap()).expect("pixel font");
+        assert!(report.bitmap_only);
+        assert!(report.warning().starts_with("is a bitmap-only font"));
+    }
+}
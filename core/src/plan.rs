@@ -0,0 +1,79 @@
+//! Install plans: what an install would do, step by step, without doing it.
+//!
+//! `--dry-run` used to stop before calling the platform manager, so all it
+//! could say was "would install X". [`crate::FontManager::plan_install`]
+//! asks the manager instead, and the answer names the platform mechanics:
+//!
+//! ```text
+//! DRY-RUN: would install font Inter-Regular.ttf (user-level)
+//!   1. copy Inter-Regular.ttf to C:\Users\ada\AppData\Local\Microsoft\Windows\Fonts\Inter-Regular.ttf
+//!   2. register ...\Inter-Regular.ttf (user-level) with GDI AddFontResourceW
+//!   3. write HKCU\Software\Microsoft\Windows NT\CurrentVersion\Fonts "Inter (TrueType)" = "...\Inter-Regular.ttf"
+//!   4. notify running apps (WM_FONTCHANGE broadcast)
+//! ```
+//!
+//! Planning reads state (installed fonts, target directories) but never
+//! changes it.
+
+use crate::FontScope;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// One step of an install plan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PlannedAction {
+    /// Uninstall an installed font the new one replaces.
+    RemoveConflict {
+        path: PathBuf,
+        postscript_name: String,
+    },
+    CopyFile {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    /// Register the font with the OS through `api`.
+    RegisterFont {
+        path: PathBuf,
+        scope: FontScope,
+        api: String,
+    },
+    /// Write a registry value (Windows).
+    WriteRegistryValue {
+        key: String,
+        name: String,
+        data: String,
+    },
+    /// Tell running applications that the font list changed.
+    Notify {
+        mechanism: String,
+    },
+}
+
+impl PlannedAction {
+    pub fn description(&self) -> String {
+        match self {
+            PlannedAction::RemoveConflict {
+                path,
+                postscript_name,
+            } => format!(
+                "remove conflicting {} ({})",
+                postscript_name,
+                path.display()
+            ),
+            PlannedAction::CopyFile { from, to } => {
+                format!("copy {} to {}", from.display(), to.display())
+            }
+            PlannedAction::RegisterFont { path, scope, api } => format!(
+                "register {} ({}) with {}",
+                path.display(),
+                scope.description(),
+                api
+            ),
+            PlannedAction::WriteRegistryValue { key, name, data } => {
+                format!("write {key} \"{name}\" = \"{data}\"")
+            }
+            PlannedAction::Notify { mechanism } => format!("notify running apps ({mechanism})"),
+        }
+    }
+}
@@ -0,0 +1,474 @@
+//! Machine-level policy set by an administrator or an MDM profile.
+//!
+//! A [`ManagedPolicy`] restricts what fontlift may do on a managed machine:
+//! whether system scope may be touched, which validation preset installs
+//! must at least pass, where fonts may come from, and which families may
+//! not be installed at all. It is loaded with [`ManagedPolicy::load_default`]
+//! and the matching `check_*` method runs before anything changes; a refusal
+//! is a [`FontError::PolicyDenied`]. The install, uninstall and remove paths
+//! in [`crate::operations`] apply it, so no front end can skip it.
+//!
+//! Where the policy lives:
+//!
+//! | Platform | Location |
+//! |---|---|
+//! | macOS | `/Library/Managed Preferences/com.fontlaborg.fontlift.plist`, as deployed by a configuration profile |
+//! | Windows | values under `HKLM\SOFTWARE\Policies\FontLift`, as set by Group Policy |
+//! | Linux / other | `/etc/fontlift/policy.json` |
+//!
+//! `FONTLIFT_POLICY_PATH` points at a JSON file to use instead, on any
+//! platform. The plist and JSON forms use the same keys:
+//!
+//! ```json
+//! {
+//!   "allow_system_scope": false,
+//!   "validation_strictness": "paranoid",
+//!   "allowed_sources": ["/Volumes/Fonts", "https://fonts.example.com/", "google-fonts"],
+//!   "blocked_families": ["Comic Sans*"]
+//! }
+//! ```
+//!
+//! In the registry they are `AllowSystemScope` (DWORD), `ValidationStrictness`
+//! (string), and `AllowedSources` and `BlockedFamilies` (multi-string).
+
+use crate::listing::glob_match;
+use crate::validation_ext::ValidationStrictness;
+use crate::{metadata, symlinks, FontError, FontResult, FontScope};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Registry key holding the policy on Windows, under `HKEY_LOCAL_MACHINE`.
+pub const POLICY_REGISTRY_KEY: &str = r"SOFTWARE\Policies\FontLift";
+
+/// What an administrator allows fontlift to do on this machine. The default
+/// allows everything.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ManagedPolicy {
+    /// Whether fonts may be installed into or removed from system scope.
+    pub allow_system_scope: bool,
+    /// The least strict validation preset installs may use. When set,
+    /// validation cannot be skipped.
+    pub validation_strictness: Option<ValidationStrictness>,
+    /// Where fonts may be installed from: directories, URL prefixes, or
+    /// provider names such as `google-fonts`. Empty allows any source.
+    pub allowed_sources: Vec<String>,
+    /// Family names (case-insensitive globs) that may not be installed.
+    pub blocked_families: Vec<String>,
+}
+
+impl Default for ManagedPolicy {
+    fn default() -> Self {
+        Self {
+            allow_system_scope: true,
+            validation_strictness: None,
+            allowed_sources: Vec::new(),
+            blocked_families: Vec::new(),
+        }
+    }
+}
+
+/// Where [`ManagedPolicy::load_default`] reads the policy file.
+///
+/// `None` on Windows without `FONTLIFT_POLICY_PATH`: the policy is read
+/// from the registry there.
+pub fn policy_path() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("FONTLIFT_POLICY_PATH") {
+        return Some(PathBuf::from(path));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        Some(PathBuf::from(
+            "/Library/Managed Preferences/com.fontlaborg.fontlift.plist",
+        ))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        None
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        Some(PathBuf::from("/etc/fontlift/policy.json"))
+    }
+}
+
+impl ManagedPolicy {
+    /// Read a JSON policy at `path`; a missing file means no restrictions.
+    ///
+    /// A file that exists but cannot be parsed is an error rather than an
+    /// empty policy, so a typo never silently lifts a restriction.
+    pub fn load(path: &Path) -> FontResult<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content, path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(FontError::IoError(e)),
+        }
+    }
+
+    /// The machine's policy, from wherever this platform keeps it.
+    pub fn load_default() -> FontResult<Self> {
+        match policy_path() {
+            #[cfg(target_os = "macos")]
+            Some(path) if path.extension().is_some_and(|ext| ext == "plist") => {
+                Self::load_plist(&path)
+            }
+            Some(path) => Self::load(&path),
+            None => Self::load_registry(),
+        }
+    }
+
+    fn parse(content: &str, path: &Path) -> FontResult<Self> {
+        serde_json::from_str(content).map_err(|e| {
+            FontError::InvalidFormat(format!(
+                "Failed to parse managed policy {}: {e}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Managed preferences are usually binary plists; `plutil` turns them
+    /// into JSON with the same keys.
+    #[cfg(target_os = "macos")]
+    fn load_plist(path: &Path) -> FontResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let output = std::process::Command::new("/usr/bin/plutil")
+            .args(["-convert", "json", "-o", "-"])
+            .arg(path)
+            .output()
+            .map_err(FontError::IoError)?;
+        if !output.status.success() {
+            return Err(FontError::InvalidFormat(format!(
+                "Failed to read managed policy {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Self::parse(&String::from_utf8_lossy(&output.stdout), path)
+    }
+
+    #[cfg(windows)]
+    fn load_registry() -> FontResult<Self> {
+        let mut policy = Self::default();
+        if let Some(allow) = registry::read_dword("AllowSystemScope") {
+            policy.allow_system_scope = allow != 0;
+        }
+        if let Some(preset) = registry::read_string("ValidationStrictness") {
+            policy.validation_strictness = Some(
+                serde_json::from_value(serde_json::Value::String(preset.to_lowercase())).map_err(
+                    |_| {
+                        FontError::InvalidFormat(format!(
+                            "Managed policy {POLICY_REGISTRY_KEY}: ValidationStrictness \
+                             '{preset}' is not lenient, normal or paranoid"
+                        ))
+                    },
+                )?,
+            );
+        }
+        policy.allowed_sources = registry::read_strings("AllowedSources").unwrap_or_default();
+        policy.blocked_families = registry::read_strings("BlockedFamilies").unwrap_or_default();
+        Ok(policy)
+    }
+
+    #[cfg(not(windows))]
+    fn load_registry() -> FontResult<Self> {
+        Ok(Self::default())
+    }
+
+    /// Does the policy restrict anything?
+    pub fn is_unrestricted(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Refuse `action` (e.g. "install") in `scope` when system scope is off
+    /// limits.
+    pub fn check_scope(&self, scope: FontScope, action: &str) -> FontResult<()> {
+        if scope == FontScope::System && !self.allow_system_scope {
+            return Err(FontError::PolicyDenied(format!(
+                "system-wide {action} is not allowed on this machine"
+            )));
+        }
+        Ok(())
+    }
+
+    /// The validation preset an install must use, given whether the caller
+    /// asked to validate and with which preset.
+    ///
+    /// Raises `requested` to the policy's preset when it is less strict, and
+    /// refuses an install that skips validation while the policy sets one.
+    pub fn enforce_validation(
+        &self,
+        validate: bool,
+        requested: ValidationStrictness,
+    ) -> FontResult<ValidationStrictness> {
+        let Some(minimum) = self.validation_strictness else {
+            return Ok(requested);
+        };
+        if !validate {
+            return Err(FontError::PolicyDenied(
+                "fonts must be validated before they are installed".to_string(),
+            ));
+        }
+        Ok(if rank(requested) < rank(minimum) {
+            minimum
+        } else {
+            requested
+        })
+    }
+
+    /// Refuse fonts from anywhere but the allowed sources.
+    ///
+    /// `source` is a local path, an http(s) URL, or a provider origin such as
+    /// `google-fonts:Inter`.
+    pub fn check_source(&self, source: &str) -> FontResult<()> {
+        if self.allowed_sources.is_empty()
+            || self
+                .allowed_sources
+                .iter()
+                .any(|allowed| source_matches(allowed, source))
+        {
+            return Ok(());
+        }
+        Err(FontError::PolicyDenied(format!(
+            "{source} is not an allowed font source"
+        )))
+    }
+
+    /// Refuse a blocked family.
+    pub fn check_family(&self, family: &str) -> FontResult<()> {
+        match self
+            .blocked_families
+            .iter()
+            .find(|pattern| glob_match(pattern, family))
+        {
+            Some(_) => Err(FontError::PolicyDenied(format!(
+                "the {family} family may not be installed"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Refuse to install the font file at `path` into `scope`: the scope,
+    /// then the family of every face in it. Files that cannot be read are
+    /// left to validation.
+    pub fn check_install(&self, path: &Path, scope: FontScope) -> FontResult<()> {
+        self.check_scope(scope, "install")?;
+        if self.blocked_families.is_empty() {
+            return Ok(());
+        }
+        for face in metadata::read_all_face_info(path).unwrap_or_default() {
+            self.check_family(&face.family_name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Load the machine's [`ManagedPolicy`] and refuse `action` in `scope` if it
+/// rules that scope out.
+pub fn check_managed_scope(scope: FontScope, action: &str) -> FontResult<ManagedPolicy> {
+    let managed = ManagedPolicy::load_default()?;
+    managed.check_scope(scope, action)?;
+    Ok(managed)
+}
+
+fn rank(strictness: ValidationStrictness) -> u8 {
+    match strictness {
+        ValidationStrictness::Lenient => 0,
+        ValidationStrictness::Normal => 1,
+        ValidationStrictness::Paranoid => 2,
+    }
+}
+
+/// URL entries match as case-insensitive prefixes, bare names as provider
+/// origins (`google-fonts` allows `google-fonts:Inter`), and anything else
+/// as a directory the source must be inside.
+fn source_matches(allowed: &str, source: &str) -> bool {
+    if allowed.contains("://") {
+        return source.to_lowercase().starts_with(&allowed.to_lowercase());
+    }
+    if !allowed.contains(['/', '\\']) {
+        return source == allowed
+            || source
+                .strip_prefix(allowed)
+                .is_some_and(|rest| rest.starts_with(':'));
+    }
+    !source.contains("://") && symlinks::is_within(Path::new(source), Path::new(allowed))
+}
+
+#[cfg(windows)]
+mod registry {
+    use super::POLICY_REGISTRY_KEY;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{
+        RegGetValueW, HKEY_LOCAL_MACHINE, REG_ROUTINE_FLAGS, RRF_RT_REG_DWORD, RRF_RT_REG_MULTI_SZ,
+        RRF_RT_REG_SZ,
+    };
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(Some(0)).collect()
+    }
+
+    /// The raw data of value `name`, or `None` when the key or value is
+    /// missing or has another type.
+    fn read(name: &str, flags: REG_ROUTINE_FLAGS) -> Option<Vec<u8>> {
+        let key = wide(POLICY_REGISTRY_KEY);
+        let name = wide(name);
+        let mut size = 0u32;
+        // SAFETY: both strings are NUL-terminated and outlive the calls; the
+        // second call writes at most `size` bytes into `data`.
+        unsafe {
+            let status = RegGetValueW(
+                HKEY_LOCAL_MACHINE,
+                PCWSTR(key.as_ptr()),
+                PCWSTR(name.as_ptr()),
+                flags,
+                None,
+                None,
+                Some(&mut size),
+            );
+            if status != ERROR_SUCCESS {
+                return None;
+            }
+            let mut data = vec![0u8; size as usize];
+            let status = RegGetValueW(
+                HKEY_LOCAL_MACHINE,
+                PCWSTR(key.as_ptr()),
+                PCWSTR(name.as_ptr()),
+                flags,
+                None,
+                Some(data.as_mut_ptr().cast()),
+                Some(&mut size),
+            );
+            if status != ERROR_SUCCESS {
+                return None;
+            }
+            data.truncate(size as usize);
+            Some(data)
+        }
+    }
+
+    fn utf16(data: &[u8]) -> Vec<u16> {
+        data.chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect()
+    }
+
+    pub(super) fn read_dword(name: &str) -> Option<u32> {
+        let data = read(name, RRF_RT_REG_DWORD)?;
+        Some(u32::from_le_bytes(data.get(..4)?.try_into().ok()?))
+    }
+
+    pub(super) fn read_string(name: &str) -> Option<String> {
+        let data = utf16(&read(name, RRF_RT_REG_SZ)?);
+        let end = data.iter().position(|&c| c == 0).unwrap_or(data.len());
+        Some(String::from_utf16_lossy(&data[..end]))
+    }
+
+    pub(super) fn read_strings(name: &str) -> Option<Vec<String>> {
+        let data = utf16(&read(name, RRF_RT_REG_MULTI_SZ)?);
+        Some(
+            data.split(|&c| c == 0)
+                .filter(|part| !part.is_empty())
+                .map(String::from_utf16_lossy)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_policy_allows_everything() {
+        let policy = ManagedPolicy::default();
+        assert!(policy.is_unrestricted());
+        assert!(policy.check_scope(FontScope::System, "install").is_ok());
+        assert_eq!(
+            policy
+                .enforce_validation(false, ValidationStrictness::Lenient)
+                .unwrap(),
+            ValidationStrictness::Lenient
+        );
+        assert!(policy.check_source("https://example.com/a.ttf").is_ok());
+        assert!(policy.check_family("Comic Sans MS").is_ok());
+    }
+
+    #[test]
+    fn restrictions_are_denied_with_their_own_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        let approved = tmp.path().join("approved");
+        std::fs::create_dir(&approved).unwrap();
+        let policy_path = tmp.path().join("policy.json");
+        std::fs::write(
+            &policy_path,
+            format!(
+                r#"{{
+                    "allow_system_scope": false,
+                    "validation_strictness": "normal",
+                    "allowed_sources": [{:?}, "https://fonts.example.com/", "google-fonts"],
+                    "blocked_families": ["comic sans*"]
+                }}"#,
+                approved.display().to_string()
+            ),
+        )
+        .unwrap();
+        let policy = ManagedPolicy::load(&policy_path).unwrap();
+
+        let denied = policy
+            .check_scope(FontScope::System, "install")
+            .unwrap_err();
+        assert!(matches!(denied, FontError::PolicyDenied(_)));
+        assert_eq!(denied.code().as_str(), "policy_denied");
+        assert!(policy.check_scope(FontScope::User, "install").is_ok());
+
+        assert!(policy
+            .enforce_validation(false, ValidationStrictness::Normal)
+            .is_err());
+        assert_eq!(
+            policy
+                .enforce_validation(true, ValidationStrictness::Lenient)
+                .unwrap(),
+            ValidationStrictness::Normal
+        );
+        assert_eq!(
+            policy
+                .enforce_validation(true, ValidationStrictness::Paranoid)
+                .unwrap(),
+            ValidationStrictness::Paranoid
+        );
+
+        let inside = approved.join("Inter.ttf");
+        assert!(policy.check_source(&inside.to_string_lossy()).is_ok());
+        assert!(policy
+            .check_source(&tmp.path().join("Inter.ttf").to_string_lossy())
+            .is_err());
+        assert!(policy
+            .check_source("HTTPS://fonts.example.com/inter.zip")
+            .is_ok());
+        assert!(policy
+            .check_source("https://fonts.example.com.evil/inter.zip")
+            .is_err());
+        assert!(policy.check_source("google-fonts:Inter").is_ok());
+        assert!(policy.check_source("google-fonts-mirror:Inter").is_err());
+
+        assert!(policy.check_family("Comic Sans MS").is_err());
+        assert!(policy.check_family("Inter").is_ok());
+    }
+
+    #[test]
+    fn unreadable_policy_is_an_error_and_missing_is_empty() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(ManagedPolicy::load(&tmp.path().join("none.json"))
+            .unwrap()
+            .is_unrestricted());
+        let broken = tmp.path().join("policy.json");
+        std::fs::write(&broken, "{ allow_system_scope: no }").unwrap();
+        assert!(ManagedPolicy::load(&broken).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+//! Progress reporting for long operations.
+//!
+//! Installing a directory of 800 fonts or pruning a bloated registry can
+//! take minutes. Front ends implement [`OperationObserver`] to show how far
+//! along it is: the CLI draws a progress bar, the Python bindings call a
+//! user-supplied function. The operation drives a [`Tracker`]:
+//!
+//! ```
+//! # use fontlift_core::progress::{Operation, OperationObserver, Tracker};
+//! # use std::path::PathBuf;
+//! # fn install_all(fonts: &[PathBuf], observer: &dyn OperationObserver) {
+//! let mut tracker = Tracker::start(observer, Operation::Install, fonts.len());
+//! for font in fonts {
+//!     let ok = true; // ... install it ...
+//!     tracker.item(Some(font), ok);
+//! }
+//! // on_done fires when the tracker is dropped, also on early returns.
+//! # }
+//! ```
+//!
+//! Observers only watch; they cannot cancel or change the operation.
+
+use crate::{cache, prune, FontManager, FontResult, FontScope};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// What kind of operation is reporting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Install,
+    Uninstall,
+    Remove,
+    /// Removing stale registrations; items arrive in chunks.
+    Prune,
+    ClearCache,
+}
+
+impl Operation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Operation::Install => "install",
+            Operation::Uninstall => "uninstall",
+            Operation::Remove => "remove",
+            Operation::Prune => "prune",
+            Operation::ClearCache => "clear_cache",
+        }
+    }
+
+    /// Present participle for progress lines: "Installing".
+    pub fn verb(self) -> &'static str {
+        match self {
+            Operation::Install => "Installing",
+            Operation::Uninstall => "Uninstalling",
+            Operation::Remove => "Removing",
+            Operation::Prune => "Pruning",
+            Operation::ClearCache => "Clearing caches",
+        }
+    }
+}
+
+/// One step of an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemProgress<'a> {
+    pub operation: Operation,
+    /// Items handled so far, this one included.
+    pub done: usize,
+    pub total: usize,
+    /// The font this step handled, when it was a single font.
+    pub path: Option<&'a Path>,
+    pub ok: bool,
+}
+
+/// How an operation ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationSummary {
+    pub operation: Operation,
+    pub done: usize,
+    pub total: usize,
+    pub failed: usize,
+}
+
+/// Receives progress events. Every method has an empty default.
+pub trait OperationObserver: Send + Sync {
+    /// The operation starts and will handle `total` items.
+    fn on_start(&self, operation: Operation, total: usize) {
+        let _ = (operation, total);
+    }
+
+    fn on_item(&self, item: &ItemProgress<'_>) {
+        let _ = item;
+    }
+
+    /// Called exactly once after `on_start`, whether the operation finished
+    /// or stopped early.
+    fn on_done(&self, summary: &OperationSummary) {
+        let _ = summary;
+    }
+}
+
+/// Observer that ignores everything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopObserver;
+
+impl OperationObserver for NoopObserver {}
+
+/// Counts an operation's items and forwards them to an observer.
+pub struct Tracker<'a> {
+    observer: &'a dyn OperationObserver,
+    operation: Operation,
+    total: usize,
+    done: usize,
+    failed: usize,
+}
+
+impl<'a> Tracker<'a> {
+    /// Send `on_start` and begin counting.
+    pub fn start(observer: &'a dyn OperationObserver, operation: Operation, total: usize) -> Self {
+        observer.on_start(operation, total);
+        Self {
+            observer,
+            operation,
+            total,
+            done: 0,
+            failed: 0,
+        }
+    }
+
+    /// One more item handled.
+    pub fn item(&mut self, path: Option<&Path>, ok: bool) {
+        self.done += 1;
+        if !ok {
+            self.failed += 1;
+        }
+        self.send(path, ok);
+    }
+
+    /// Jump to `done` items handled, for work reported in batches.
+    pub fn advance_to(&mut self, done: usize) {
+        self.done = done.max(self.done);
+        self.send(None, true);
+    }
+
+    fn send(&self, path: Option<&Path>, ok: bool) {
+        self.observer.on_item(&ItemProgress {
+            operation: self.operation,
+            done: self.done,
+            total: self.total,
+            path,
+            ok,
+        });
+    }
+
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+}
+
+impl Drop for Tracker<'_> {
+    fn drop(&mut self) {
+        self.observer.on_done(&OperationSummary {
+            operation: self.operation,
+            done: self.done,
+            total: self.total,
+            failed: self.failed,
+        });
+    }
+}
+
+/// [`FontManager::prune_stale_registrations`], reported to `observer` chunk
+/// by chunk. Nothing is reported when there is nothing to prune.
+pub fn prune_observed<M: FontManager + ?Sized>(
+    manager: &M,
+    scope: FontScope,
+    options: &prune::PruneOptions,
+    observer: &dyn OperationObserver,
+) -> FontResult<prune::PruneReport> {
+    let mut tracker: Option<Tracker<'_>> = None;
+    manager.prune_stale_registrations(scope, options, &mut |progress| {
+        tracker
+            .get_or_insert_with(|| Tracker::start(observer, Operation::Prune, progress.total))
+            .advance_to(progress.done);
+    })
+}
+
+/// [`FontManager::clear_cache_targets`] as a one-item operation.
+pub fn clear_caches_observed<M: FontManager + ?Sized>(
+    manager: &M,
+    strategy: cache::CacheClearStrategy,
+    targets: &cache::CacheTargets,
+    observer: &dyn OperationObserver,
+) -> FontResult<cache::CacheClearResult> {
+    let mut tracker = Tracker::start(observer, Operation::ClearCache, 1);
+    let result = manager.clear_cache_targets(strategy, targets);
+    tracker.item(None, result.is_ok());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl OperationObserver for Recorder {
+        fn on_start(&self, operation: Operation, total: usize) {
+            let event = format!("start {} {total}", operation.as_str());
+            self.0.lock().unwrap().push(event);
+        }
+        fn on_item(&self, item: &ItemProgress<'_>) {
+            let path = item.path.map(|p| p.display().to_string());
+            let event = format!("item {}/{} {:?} {}", item.done, item.total, path, item.ok);
+            self.0.lock().unwrap().push(event);
+        }
+        fn on_done(&self, summary: &OperationSummary) {
+            let event = format!(
+                "done {}/{} failed {}",
+                summary.done, summary.total, summary.failed
+            );
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    fn run(observer: &dyn OperationObserver) -> Result<(), ()> {
+        let mut tracker = Tracker::start(observer, Operation::Install, 3);
+        tracker.item(Some(Path::new("A.ttf")), true);
+        tracker.item(Some(Path::new("B.ttf")), false);
+        // Stops early: on_done still fires.
+        Err(())
+    }
+
+    #[test]
+    fn tracker_reports_items_and_always_finishes() {
+        let recorder = Recorder::default();
+        assert!(run(&recorder).is_err());
+        {
+            let mut tracker = Tracker::start(&recorder, Operation::Prune, 250);
+            tracker.advance_to(100);
+            tracker.advance_to(250);
+        }
+        assert_eq!(
+            recorder.0.into_inner().unwrap(),
+            vec![
+                "start install 3",
+                "item 1/3 Some(\"A.ttf\") true",
+                "item 2/3 Some(\"B.ttf\") false",
+                "done 2/3 failed 1",
+                "start prune 250",
+                "item 100/250 None true",
+                "item 250/250 None true",
+                "done 250/250 failed 0",
+            ]
+        );
+    }
+}
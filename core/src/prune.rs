@@ -0,0 +1,366 @@
+//! What `prune_missing_fonts` removed, and why.
+//!
+//! Pruning deletes OS registrations, so admins need to see exactly which
+//! ones went: `fontlift cleanup --json` prints the [`PruneReport`].
+//!
+//! ## Large registries
+//!
+//! After a migration a machine can have thousands of stale registrations.
+//! [`run`] removes them in chunks instead of in one pass:
+//!
+//! 1. The platform lists the stale registrations
+//!    ([`crate::FontManager::find_stale_registrations`]).
+//! 2. When the work spans more than one chunk, or `--limit` stops short of
+//!    the end, the list is saved in the journal as a [`PruneCheckpoint`].
+//! 3. After each chunk the checkpoint is trimmed and progress reported.
+//!
+//! An interrupted or limited prune leaves the checkpoint behind, and the
+//! next prune of that scope continues from it instead of rescanning the
+//! registry. Entries whose file came back in the meantime are skipped.
+
+use crate::{journal, FontError, FontManager, FontResult, FontScope};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Registrations removed between progress reports and checkpoint saves.
+pub const DEFAULT_CHUNK_SIZE: usize = 100;
+
+/// Why a registration was pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PruneReason {
+    /// The registered file no longer exists.
+    MissingFile,
+    /// The registered file is not a font file fontlift recognizes.
+    InvalidExtension,
+    /// The registered location could not be turned into a path.
+    MalformedPath,
+}
+
+impl PruneReason {
+    /// Why `path` should be pruned, or `None` to keep it.
+    pub fn for_path(path: &Path) -> Option<Self> {
+        if !path.exists() {
+            Some(PruneReason::MissingFile)
+        } else if !crate::validation::is_valid_font_extension(path) {
+            Some(PruneReason::InvalidExtension)
+        } else {
+            None
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            PruneReason::MissingFile => "file missing",
+            PruneReason::InvalidExtension => "not a font file",
+            PruneReason::MalformedPath => "malformed path",
+        }
+    }
+}
+
+/// One stale registration, found or removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrunedRegistration {
+    /// Registry value name on Windows, font URL on macOS.
+    pub name: String,
+    /// The file the registration pointed to, when it could be resolved.
+    pub path: Option<PathBuf>,
+    pub reason: PruneReason,
+}
+
+/// Result of [`crate::FontManager::prune_missing_fonts`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PruneReport {
+    pub scope: FontScope,
+    pub removed: Vec<PrunedRegistration>,
+    /// Stale registrations left for the next prune (`--limit`).
+    #[serde(default)]
+    pub remaining: usize,
+    /// This prune continued an earlier, unfinished one.
+    #[serde(default)]
+    pub resumed: bool,
+}
+
+impl PruneReport {
+    pub fn new(scope: FontScope) -> Self {
+        Self {
+            scope,
+            removed: Vec::new(),
+            remaining: 0,
+            resumed: false,
+        }
+    }
+
+    pub fn record(&mut self, name: impl Into<String>, path: Option<PathBuf>, reason: PruneReason) {
+        self.removed.push(PrunedRegistration {
+            name: name.into(),
+            path,
+            reason,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.removed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty()
+    }
+}
+
+/// How much to prune in one run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruneOptions {
+    /// Stop after removing this many registrations.
+    pub limit: Option<usize>,
+    pub chunk_size: usize,
+}
+
+impl Default for PruneOptions {
+    fn default() -> Self {
+        Self {
+            limit: None,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+}
+
+impl PruneOptions {
+    pub fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+}
+
+/// Reported after each chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruneProgress {
+    pub scope: FontScope,
+    /// Registrations handled so far in this run.
+    pub done: usize,
+    /// Registrations this run will handle.
+    pub total: usize,
+}
+
+/// Stale registrations an unfinished prune has yet to remove.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PruneCheckpoint {
+    pub scope: FontScope,
+    #[serde(with = "journal::systemtime_serde")]
+    pub started_at: SystemTime,
+    pub pending: Vec<PrunedRegistration>,
+}
+
+/// The saved checkpoint for `scope`, if an earlier prune did not finish.
+pub fn load_checkpoint(scope: FontScope) -> FontResult<Option<PruneCheckpoint>> {
+    Ok(journal::load_journal()?
+        .prune_checkpoints
+        .into_iter()
+        .find(|checkpoint| checkpoint.scope == scope))
+}
+
+/// Replace the checkpoint for `scope`; an empty `pending` removes it.
+fn save_checkpoint(
+    scope: FontScope,
+    started_at: SystemTime,
+    pending: &[PrunedRegistration],
+) -> FontResult<()> {
+    journal::update_journal(|journal| {
+        journal.prune_checkpoints.retain(|c| c.scope != scope);
+        if !pending.is_empty() {
+            journal.prune_checkpoints.push(PruneCheckpoint {
+                scope,
+                started_at,
+                pending: pending.to_vec(),
+            });
+        }
+        Ok(())
+    })
+}
+
+/// Prune `scope` in chunks, resuming a saved checkpoint if there is one.
+///
+/// Failing removals do not stop the run; they are reported together in the
+/// returned error once every chunk has been attempted, and are dropped from
+/// the checkpoint (the next full scan finds them again).
+pub fn run<M: FontManager + ?Sized>(
+    manager: &M,
+    scope: FontScope,
+    options: &PruneOptions,
+    progress: &mut dyn FnMut(&PruneProgress),
+) -> FontResult<PruneReport> {
+    let mut report = PruneReport::new(scope);
+    let (started_at, pending) = match load_checkpoint(scope)? {
+        Some(checkpoint) => {
+            report.resumed = true;
+            (checkpoint.started_at, checkpoint.pending)
+        }
+        None => (SystemTime::now(), manager.find_stale_registrations(scope)?),
+    };
+
+    let chunk_size = options.chunk_size.max(1);
+    let budget = options
+        .limit
+        .map_or(pending.len(), |l| l.min(pending.len()));
+    let checkpointed = report.resumed || pending.len() > chunk_size || budget < pending.len();
+    if checkpointed && !report.resumed {
+        save_checkpoint(scope, started_at, &pending)?;
+    }
+
+    let mut failures = Vec::new();
+    let mut done = 0;
+    for chunk in pending[..budget].chunks(chunk_size) {
+        for entry in chunk {
+            // The file may have been restored since the scan.
+            let still_stale = entry
+                .path
+                .as_deref()
+                .map_or(true, |path| PruneReason::for_path(path).is_some());
+            if !still_stale {
+                continue;
+            }
+            match manager.remove_stale_registration(scope, entry) {
+                Ok(()) => report.removed.push(entry.clone()),
+                Err(e) => failures.push(format!("{}: {}", entry.name, e)),
+            }
+        }
+        done += chunk.len();
+        if checkpointed {
+            save_checkpoint(scope, started_at, &pending[done..])?;
+        }
+        progress(&PruneProgress {
+            scope,
+            done,
+            total: budget,
+        });
+    }
+    report.remaining = pending.len() - done;
+
+    if failures.is_empty() {
+        Ok(report)
+    } else {
+        Err(FontError::RegistrationFailed(format!(
+            "Failed to prune some font registrations: {}",
+            failures.join("; ")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reasons_distinguish_missing_files_from_non_fonts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let font = tmp.path().join("Kept.ttf");
+        let readme = tmp.path().join("README.txt");
+        std::fs::write(&font, b"font").unwrap();
+        std::fs::write(&readme, b"text").unwrap();
+
+        assert_eq!(PruneReason::for_path(&font), None);
+        assert_eq!(
+            PruneReason::for_path(&readme),
+            Some(PruneReason::InvalidExtension)
+        );
+        assert_eq!(
+            PruneReason::for_path(&tmp.path().join("Gone.otf")),
+            Some(PruneReason::MissingFile)
+        );
+
+        let mut report = PruneReport::new(FontScope::User);
+        report.record("Gone (TrueType)", None, PruneReason::MalformedPath);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["removed"][0]["reason"], "malformed_path");
+    }
+
+    /// Registrations for five missing files; counts removals.
+    struct StaleRegistry {
+        stale: Vec<PrunedRegistration>,
+        removed: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl FontManager for StaleRegistry {
+        fn install_font(&self, _: &crate::FontliftFontSource) -> FontResult<()> {
+            Ok(())
+        }
+        fn uninstall_font(&self, _: &crate::FontliftFontSource) -> FontResult<()> {
+            Ok(())
+        }
+        fn remove_font(&self, _: &crate::FontliftFontSource) -> FontResult<()> {
+            Ok(())
+        }
+        fn is_font_installed(&self, _: &crate::FontliftFontSource) -> FontResult<bool> {
+            Ok(false)
+        }
+        fn list_installed_fonts(&self) -> FontResult<Vec<crate::FontliftFontFaceInfo>> {
+            Ok(vec![])
+        }
+        fn clear_font_caches(
+            &self,
+            _: crate::cache::CacheClearStrategy,
+        ) -> FontResult<crate::cache::CacheClearResult> {
+            Ok(crate::cache::CacheClearResult::default())
+        }
+        fn find_stale_registrations(&self, _: FontScope) -> FontResult<Vec<PrunedRegistration>> {
+            Ok(self.stale.clone())
+        }
+        fn remove_stale_registration(
+            &self,
+            _: FontScope,
+            entry: &PrunedRegistration,
+        ) -> FontResult<()> {
+            self.removed.lock().unwrap().push(entry.name.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn limited_prune_resumes_from_the_journal_checkpoint() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::env::set_var("FONTLIFT_JOURNAL_PATH", tmp.path().join("journal.json"));
+        let stale = (1..=5)
+            .map(|i| PrunedRegistration {
+                name: format!("Gone {i} (TrueType)"),
+                path: Some(tmp.path().join(format!("Gone{i}.ttf"))),
+                reason: PruneReason::MissingFile,
+            })
+            .collect();
+        let registry = StaleRegistry {
+            stale,
+            removed: Default::default(),
+        };
+        let options = PruneOptions {
+            limit: Some(3),
+            chunk_size: 2,
+        };
+
+        let mut seen = Vec::new();
+        let first = run(&registry, FontScope::User, &options, &mut |p| {
+            seen.push((p.done, p.total))
+        })
+        .unwrap();
+        assert_eq!((first.len(), first.remaining, first.resumed), (3, 2, false));
+        assert_eq!(seen, vec![(2, 3), (3, 3)]);
+        let checkpoint = load_checkpoint(FontScope::User).unwrap().expect("saved");
+        assert_eq!(checkpoint.pending.len(), 2);
+
+        // A file restored since the scan is left registered.
+        std::fs::write(tmp.path().join("Gone4.ttf"), b"font").unwrap();
+        let second = run(
+            &registry,
+            FontScope::User,
+            &PruneOptions::default(),
+            &mut |_| {},
+        )
+        .unwrap();
+        assert_eq!(
+            (second.len(), second.remaining, second.resumed),
+            (1, 0, true)
+        );
+        assert_eq!(second.removed[0].name, "Gone 5 (TrueType)");
+        assert!(load_checkpoint(FontScope::User).unwrap().is_none());
+        assert_eq!(registry.removed.lock().unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,335 @@
+//! Removed fonts waiting to be deleted (`fontlift remove` / `fontlift restore`).
+//!
+//! `remove` no longer deletes a font's file straight away. [`quarantine`]
+//! moves it into a per-user quarantine directory instead, and
+//! [`restore`] puts it back and registers it again. Fonts that sit in
+//! quarantine longer than [`retention`] are deleted by [`purge_expired`],
+//! which `remove` runs before each removal. `remove --purge` still deletes
+//! at once.
+//!
+//! The layout mirrors the [`vault`](crate::vault) of disabled fonts, one
+//! directory per removed file next to an index of what came from where:
+//!
+//! ```text
+//! <quarantine>/index.json
+//! <quarantine>/b81c04e2/OldFont-Regular.otf
+//! ```
+//!
+//! Fonts of both scopes share the user's quarantine; each entry remembers
+//! its scope, and its install-database record so a restored font keeps its
+//! install reason and origin.
+
+use crate::install_db::InstallRecord;
+use crate::journal;
+use crate::{links, vault, FontError, FontManager, FontResult, FontScope, FontliftFontSource};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+/// Days a removed font stays restorable unless `FONTLIFT_QUARANTINE_DAYS`
+/// says otherwise.
+pub const DEFAULT_RETENTION_DAYS: u64 = 30;
+
+/// One removed font.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuarantinedFont {
+    /// Short id, also the name of the subdirectory holding the file.
+    pub id: String,
+    /// Where the file was, and where [`restore`] puts it back.
+    pub original_path: PathBuf,
+    pub quarantine_path: PathBuf,
+    pub scope: FontScope,
+    /// Every face in the file; empty when the file did not parse.
+    pub postscript_names: Vec<String>,
+    #[serde(with = "journal::systemtime_serde")]
+    pub removed_at: SystemTime,
+    /// The install-database record the font had when it was removed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record: Option<InstallRecord>,
+}
+
+impl QuarantinedFont {
+    /// Does `query` name this font: its id, a PostScript name, its file
+    /// name, or its original path?
+    pub fn matches(&self, query: &str) -> bool {
+        self.id == query
+            || self
+                .postscript_names
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(query))
+            || self
+                .original_path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(query))
+            || self.original_path == Path::new(query)
+    }
+
+    /// When [`purge_expired`] deletes the font.
+    pub fn expires_at(&self, retention: Duration) -> SystemTime {
+        self.removed_at + retention
+    }
+}
+
+/// The quarantine's `index.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuarantineIndex {
+    pub fonts: Vec<QuarantinedFont>,
+}
+
+/// Where removed fonts are kept: `FONTLIFT_QUARANTINE_DIR`, or a
+/// `quarantine` directory next to the journal.
+pub fn quarantine_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("FONTLIFT_QUARANTINE_DIR") {
+        return PathBuf::from(dir);
+    }
+    journal::journal_path().with_file_name("quarantine")
+}
+
+/// How long removed fonts stay restorable: `FONTLIFT_QUARANTINE_DAYS`, or
+/// [`DEFAULT_RETENTION_DAYS`]. Zero days purges on the next removal.
+pub fn retention() -> Duration {
+    let days = std::env::var("FONTLIFT_QUARANTINE_DAYS")
+        .ok()
+        .and_then(|days| days.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS);
+    Duration::from_secs(days * 24 * 60 * 60)
+}
+
+fn index_path() -> PathBuf {
+    quarantine_dir().join("index.json")
+}
+
+/// The quarantine index. A missing index is an empty quarantine.
+pub fn load_index() -> FontResult<QuarantineIndex> {
+    let path = index_path();
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| {
+            FontError::InvalidFormat(format!(
+                "Failed to parse quarantine index {}: {e}",
+                path.display()
+            ))
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(QuarantineIndex::default()),
+        Err(e) => Err(FontError::IoError(e)),
+    }
+}
+
+/// Load, mutate and save the index under the journal lock.
+fn update_index<R>(f: impl FnOnce(&mut QuarantineIndex) -> R) -> FontResult<R> {
+    journal::with_journal_lock(|| {
+        let mut index = load_index()?;
+        let result = f(&mut index);
+
+        let path = index_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(FontError::IoError)?;
+        }
+        let content = serde_json::to_string_pretty(&index).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize quarantine index: {e}"))
+        })?;
+        let temp_path = path.with_file_name(format!("index.json.tmp.{}", std::process::id()));
+        fs::write(&temp_path, content).map_err(FontError::IoError)?;
+        fs::rename(&temp_path, &path).map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            FontError::IoError(e)
+        })?;
+        Ok(result)
+    })
+}
+
+/// Every quarantined font, oldest first.
+pub fn list_quarantined() -> FontResult<Vec<QuarantinedFont>> {
+    Ok(load_index()?.fonts)
+}
+
+/// The most recently removed font `query` names (see
+/// [`QuarantinedFont::matches`]).
+pub fn find_quarantined(query: &str) -> FontResult<Option<QuarantinedFont>> {
+    Ok(list_quarantined()?
+        .into_iter()
+        .rev()
+        .find(|font| font.matches(query)))
+}
+
+/// Move `from` to `to`: a rename, or a copy and delete across file systems.
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    links::copy_file(from, to)?;
+    if let Err(e) = links::remove_file(from) {
+        let _ = fs::remove_file(to);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Move the already unregistered font at `path` into quarantine. `record`
+/// is its install-database entry, if it had one.
+pub fn quarantine(
+    path: &Path,
+    scope: FontScope,
+    record: Option<InstallRecord>,
+) -> FontResult<QuarantinedFont> {
+    if !path.is_file() {
+        return Err(FontError::FontNotFound(path.to_path_buf()));
+    }
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| FontError::InvalidFormat(format!("{} has no file name", path.display())))?;
+    let id = Uuid::new_v4().simple().to_string()[..8].to_string();
+    let quarantine_path = quarantine_dir().join(&id).join(file_name);
+    let font = QuarantinedFont {
+        id,
+        original_path: path.to_path_buf(),
+        quarantine_path: quarantine_path.clone(),
+        scope,
+        postscript_names: vault::read_postscript_names(path).unwrap_or_default(),
+        removed_at: SystemTime::now(),
+        record,
+    };
+
+    move_file(path, &quarantine_path).map_err(FontError::IoError)?;
+    if let Err(e) = update_index(|index| index.fonts.push(font.clone())) {
+        let _ = move_file(&quarantine_path, path);
+        return Err(e);
+    }
+    Ok(font)
+}
+
+/// Move a quarantined font back to where it was and register it again.
+pub fn restore<M: FontManager + ?Sized>(manager: &M, font: &QuarantinedFont) -> FontResult<()> {
+    let original = &font.original_path;
+    if original.exists() {
+        return Err(FontError::AlreadyInstalled(original.clone()));
+    }
+    if !font.quarantine_path.is_file() {
+        return Err(FontError::FontNotFound(font.quarantine_path.clone()));
+    }
+
+    move_file(&font.quarantine_path, original).map_err(FontError::IoError)?;
+    let source = FontliftFontSource::new(original.clone()).with_scope(Some(font.scope));
+    if let Err(e) = manager.install_font(&source) {
+        let _ = move_file(original, &font.quarantine_path);
+        return Err(e);
+    }
+
+    update_index(|index| index.fonts.retain(|f| f.id != font.id))?;
+    if let Some(dir) = font.quarantine_path.parent() {
+        let _ = fs::remove_dir(dir);
+    }
+    Ok(())
+}
+
+/// Delete one quarantined font for good.
+pub fn purge(font: &QuarantinedFont) -> FontResult<()> {
+    match links::remove_file(&font.quarantine_path) {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(FontError::IoError(e)),
+    }
+    if let Some(dir) = font.quarantine_path.parent() {
+        let _ = fs::remove_dir(dir);
+    }
+    update_index(|index| index.fonts.retain(|f| f.id != font.id))
+}
+
+/// Delete the fonts removed more than `retention` before `now`, and return
+/// them.
+pub fn purge_expired(now: SystemTime, retention: Duration) -> FontResult<Vec<QuarantinedFont>> {
+    let expired: Vec<_> = list_quarantined()?
+        .into_iter()
+        .filter(|font| font.expires_at(retention) <= now)
+        .collect();
+    for font in &expired {
+        purge(font)?;
+    }
+    Ok(expired)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::install_db::InstallReason;
+    use fontlift_testfonts::TestFont;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Registered(Mutex<Vec<PathBuf>>);
+
+    impl FontManager for Registered {
+        fn install_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+            self.0.lock().unwrap().push(source.path.clone());
+            Ok(())
+        }
+        fn uninstall_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+            self.0.lock().unwrap().retain(|p| p != &source.path);
+            Ok(())
+        }
+        fn remove_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+            self.uninstall_font(source)
+        }
+        fn is_font_installed(&self, source: &FontliftFontSource) -> FontResult<bool> {
+            Ok(self.0.lock().unwrap().contains(&source.path))
+        }
+        fn list_installed_fonts(&self) -> FontResult<Vec<crate::FontliftFontFaceInfo>> {
+            Ok(vec![])
+        }
+        fn clear_font_caches(
+            &self,
+            _: crate::cache::CacheClearStrategy,
+        ) -> FontResult<crate::cache::CacheClearResult> {
+            Ok(crate::cache::CacheClearResult::default())
+        }
+    }
+
+    #[test]
+    fn removed_fonts_can_be_restored_until_purged() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::env::set_var("FONTLIFT_JOURNAL_PATH", tmp.path().join("journal.json"));
+        std::env::set_var("FONTLIFT_QUARANTINE_DIR", tmp.path().join("quarantine"));
+        let fonts = tmp.path().join("Fonts");
+        fs::create_dir_all(&fonts).unwrap();
+        let kept = TestFont::new("Kept Sans").write_to(&fonts).unwrap();
+        let old = TestFont::new("Old Sans").write_to(&fonts).unwrap();
+        let bytes = fs::read(&kept).unwrap();
+
+        let record = InstallRecord {
+            path: kept.clone(),
+            scope: FontScope::User,
+            reason: InstallReason::Profile {
+                name: "design".to_string(),
+            },
+            installed_at: SystemTime::UNIX_EPOCH,
+            origin: None,
+            original_file_name: None,
+            sha256: None,
+        };
+        let font = quarantine(&kept, FontScope::User, Some(record.clone())).unwrap();
+        assert!(!kept.exists());
+        assert!(font
+            .quarantine_path
+            .starts_with(tmp.path().join("quarantine")));
+        let aged = quarantine(&old, FontScope::User, None).unwrap();
+
+        let found = find_quarantined("keptsans-regular").unwrap().unwrap();
+        assert_eq!(found.record, Some(record));
+        let manager = Registered::default();
+        restore(&manager, &found).unwrap();
+        assert_eq!(fs::read(&kept).unwrap(), bytes);
+        assert_eq!(*manager.0.lock().unwrap(), [kept]);
+
+        let day = Duration::from_secs(24 * 60 * 60);
+        assert!(purge_expired(SystemTime::now(), day).unwrap().is_empty());
+        let purged = purge_expired(SystemTime::now() + 2 * day, day).unwrap();
+        assert_eq!(purged.len(), 1);
+        assert_eq!(purged[0].id, aged.id);
+        assert!(!aged.quarantine_path.exists());
+        assert!(list_quarantined().unwrap().is_empty());
+    }
+}
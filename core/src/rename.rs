@@ -0,0 +1,278 @@
+//! Renamed copies of fonts whose PostScript name another font already uses.
+//!
+//! Operating systems identify an installed font by its names: macOS by the
+//! PostScript name, Windows registry values by the full name. Two unrelated
+//! fonts can carry the same ones — a foundry reused a common name, or two
+//! vendors each shipped a `Helvetica-Bold` — and then installing the second
+//! silently takes the place of the first.
+//! [`conflicts::name_collisions`](crate::conflicts::name_collisions) finds
+//! such pairs at install time, and `fontlift install --rename-duplicates`
+//! installs a copy made by [`write_renamed_copy`] instead. The source file is
+//! never modified.
+//!
+//! The copy carries a tag, the first four hex digits of the file's SHA-256,
+//! in every name that identifies it:
+//!
+//! | Name | Before | After |
+//! |---|---|---|
+//! | Family (IDs 1, 16, 21) | `Helvetica` | `Helvetica 3FA2` |
+//! | Full name (ID 4) | `Helvetica Bold` | `Helvetica 3FA2 Bold` |
+//! | PostScript name (ID 6) | `Helvetica-Bold` | `Helvetica3FA2-Bold` |
+//! | Unique ID (ID 3) | `2.003;ADBO;Helvetica-Bold` | `2.003;ADBO;Helvetica-Bold;3FA2` |
+
+use crate::{digest, pixel_fonts, FontError, FontResult};
+use read_fonts::{
+    tables::name::{MacRomanMapping, NameId, NameRecord},
+    types::Tag,
+    FileRef, FontData, TableProvider,
+};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Family name IDs: legacy, typographic and WWS.
+const FAMILY_IDS: [NameId; 3] = [
+    NameId::FAMILY_NAME,
+    NameId::TYPOGRAPHIC_FAMILY_NAME,
+    NameId::WWS_FAMILY_NAME,
+];
+
+/// The tag [`with_tag`] adds for the font `data`: four uppercase hex digits.
+pub fn rename_tag(data: &[u8]) -> String {
+    digest::sha256_hex(data)[..4].to_ascii_uppercase()
+}
+
+/// The font `data` with `tag` added to its family, full, PostScript and
+/// unique names.
+///
+/// Only single fonts are supported; the faces of a collection share one
+/// `name` table more often than not.
+pub fn with_tag(data: &[u8], tag: &str) -> FontResult<Vec<u8>> {
+    let font = match FileRef::new(data) {
+        Ok(FileRef::Font(font)) => font,
+        Ok(FileRef::Collection(_)) => {
+            return Err(FontError::UnsupportedOperation(
+                "--rename-duplicates does not support font collections".to_string(),
+            ))
+        }
+        Err(e) => return Err(FontError::InvalidFormat(e.to_string())),
+    };
+    let name = font
+        .name()
+        .map_err(|e| FontError::InvalidFormat(format!("name table: {e}")))?;
+    let strings = name.string_data();
+
+    // Longest first, so "Helvetica Neue" wins over "Helvetica".
+    let mut families: Vec<String> = name
+        .name_record()
+        .iter()
+        .filter(|record| FAMILY_IDS.contains(&record.name_id()))
+        .filter_map(|record| record.string(strings).ok().map(|s| s.to_string()))
+        .collect();
+    families.sort_by_key(|family| std::cmp::Reverse(family.len()));
+
+    let mut records = Vec::new();
+    let mut storage = Vec::new();
+    for record in name.name_record() {
+        let bytes = renamed(record, strings, tag, &families)
+            .unwrap_or_else(|| raw_string(record, strings).to_vec());
+        records.push((record, storage.len(), bytes.len()));
+        storage.extend_from_slice(&bytes);
+    }
+    let lang_tags = name.lang_tag_record().unwrap_or_default();
+    let mut lang_tag_offsets = Vec::new();
+    for lang_tag in lang_tags {
+        let start = lang_tag.lang_tag_offset().to_u32() as usize;
+        let bytes = strings
+            .as_bytes()
+            .get(start..start + lang_tag.length() as usize)
+            .unwrap_or_default();
+        lang_tag_offsets.push((storage.len(), bytes.len()));
+        storage.extend_from_slice(bytes);
+    }
+    if storage.len() > usize::from(u16::MAX) {
+        return Err(FontError::InvalidFormat(
+            "renamed name table is too large".to_string(),
+        ));
+    }
+
+    let version = name.version();
+    let header_len = 6
+        + 12 * records.len()
+        + if version >= 1 {
+            2 + 4 * lang_tag_offsets.len()
+        } else {
+            0
+        };
+    let mut table = Vec::with_capacity(header_len + storage.len());
+    for value in [version, records.len() as u16, header_len as u16] {
+        table.extend_from_slice(&value.to_be_bytes());
+    }
+    for (record, offset, length) in &records {
+        for value in [
+            record.platform_id(),
+            record.encoding_id(),
+            record.language_id(),
+            record.name_id().to_u16(),
+            *length as u16,
+            *offset as u16,
+        ] {
+            table.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+    if version >= 1 {
+        table.extend_from_slice(&(lang_tag_offsets.len() as u16).to_be_bytes());
+        for (offset, length) in &lang_tag_offsets {
+            table.extend_from_slice(&(*length as u16).to_be_bytes());
+            table.extend_from_slice(&(*offset as u16).to_be_bytes());
+        }
+    }
+    table.extend_from_slice(&storage);
+
+    let mut tables: BTreeMap<Tag, Vec<u8>> = font
+        .table_directory
+        .table_records()
+        .iter()
+        .filter_map(|record| {
+            let tag = record.tag();
+            Some((tag, font.table_data(tag)?.as_bytes().to_vec()))
+        })
+        .collect();
+    tables.insert(Tag::new(b"name"), table);
+    Ok(pixel_fonts::write_sfnt(
+        font.table_directory.sfnt_version(),
+        &tables,
+    ))
+}
+
+fn raw_string<'a>(record: &NameRecord, strings: FontData<'a>) -> &'a [u8] {
+    let start = record.string_offset().to_u32() as usize;
+    strings
+        .as_bytes()
+        .get(start..start + record.length() as usize)
+        .unwrap_or_default()
+}
+
+/// The new encoded string for `record`, or `None` to keep it as it is.
+fn renamed(
+    record: &NameRecord,
+    strings: FontData<'_>,
+    tag: &str,
+    families: &[String],
+) -> Option<Vec<u8>> {
+    let id = record.name_id();
+    let mac_roman = (record.platform_id(), record.encoding_id()) == (1, 0);
+    if !record.is_unicode() && !mac_roman {
+        return None;
+    }
+    let old = record.string(strings).ok()?.to_string();
+    let new = if FAMILY_IDS.contains(&id) {
+        format!("{old} {tag}")
+    } else if id == NameId::FULL_NAME {
+        match families
+            .iter()
+            .find(|family| old.starts_with(family.as_str()))
+        {
+            Some(family) => format!("{family} {tag}{}", &old[family.len()..]),
+            None => format!("{old} {tag}"),
+        }
+    } else if id == NameId::POSTSCRIPT_NAME {
+        match old.split_once('-') {
+            Some((family, style)) => format!("{family}{tag}-{style}"),
+            None => format!("{old}{tag}"),
+        }
+    } else if id == NameId::UNIQUE_ID {
+        format!("{old};{tag}")
+    } else {
+        return None;
+    };
+
+    Some(if mac_roman {
+        new.chars()
+            .map(|c| MacRomanMapping.encode(c).unwrap_or(b'?'))
+            .collect()
+    } else {
+        new.encode_utf16().flat_map(u16::to_be_bytes).collect()
+    })
+}
+
+/// A `--rename-duplicates` copy of a font in a private temp directory,
+/// removed when dropped.
+#[derive(Debug)]
+pub struct RenamedCopy {
+    /// `<stem>-<tag>.<ext>`, so it installs next to the font it collides
+    /// with.
+    pub path: PathBuf,
+    /// What [`with_tag`] added to the names.
+    pub tag: String,
+    dir: PathBuf,
+}
+
+impl Drop for RenamedCopy {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Write [`with_tag`] of the font at `path`, tagged with [`rename_tag`], to a
+/// temp directory.
+pub fn write_renamed_copy(path: &Path) -> FontResult<RenamedCopy> {
+    let data = std::fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let tag = rename_tag(&data);
+    let renamed = with_tag(&data, &tag)?;
+
+    let dir = std::env::temp_dir().join(format!("fontlift-renamed-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).map_err(FontError::IoError)?;
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = match path.extension() {
+        Some(ext) => format!("{stem}-{tag}.{}", ext.to_string_lossy()),
+        None => format!("{stem}-{tag}"),
+    };
+    let copy = RenamedCopy {
+        path: dir.join(file_name),
+        tag,
+        dir,
+    };
+    std::fs::write(&copy.path, renamed).map_err(FontError::IoError)?;
+    Ok(copy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata;
+    use fontlift_testfonts::TestFont;
+
+    #[test]
+    fn renamed_copies_tag_every_identifying_name() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = TestFont::new("Twin Sans")
+            .with_style("Bold")
+            .write_to(tmp.path())
+            .unwrap();
+
+        let copy = write_renamed_copy(&path).unwrap();
+        let tag = copy.tag.clone();
+        assert_eq!(tag.len(), 4);
+        assert_eq!(
+            copy.path.file_name().unwrap().to_string_lossy(),
+            format!("TwinSans-Bold-{tag}.ttf")
+        );
+
+        let info = metadata::read_face_info(&copy.path).unwrap();
+        assert_eq!(info.postscript_name, format!("TwinSans{tag}-Bold"));
+        assert_eq!(info.family_name, format!("Twin Sans {tag}"));
+        assert_eq!(info.full_name, format!("Twin Sans {tag} Bold"));
+        assert_eq!(info.style, "Bold");
+        // The rest of the font is untouched and still checks out.
+        let original = metadata::read_version(&path).unwrap();
+        assert_eq!(metadata::read_version(&copy.path).unwrap(), original);
+        crate::validation::validate_font_file(&copy.path).unwrap();
+
+        let dir = copy.path.parent().unwrap().to_path_buf();
+        drop(copy);
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,211 @@
+//! Numbered copies Windows makes when font file names collide.
+//!
+//! Installing a font whose file name is already taken in the Fonts folder
+//! keeps both files: the new one is copied as `arial_0.ttf`, the next as
+//! `arial_1.ttf`, and each gets its own registry value. Reinstalling the
+//! same font a few times leaves several registrations pointing at identical
+//! bytes, which applications list as duplicate faces.
+//!
+//! [`plan_repair`] finds registrations of `<stem>_<n>.<ext>` files that sit
+//! next to `<stem>.<ext>` and compares the two files' SHA-256:
+//!
+//! - **Identical.** The copy is redundant. Its registry values are deleted
+//!   when the canonical file is registered already, and pointed at the
+//!   canonical file otherwise; then the copy is deleted.
+//! - **Different.** The copy is a different font that happens to share a
+//!   name, and is kept.
+//!
+//! `fontlift cleanup` applies the plan through
+//! [`FontManager::repair_renamed_copies`](crate::FontManager::repair_renamed_copies)
+//! and prints the [`RepairReport`].
+
+use crate::{digest, FontResult, FontScope};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A redundant copy and what merging it does.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedCopy {
+    /// The numbered copy, e.g. `C:\Windows\Fonts\arial_0.ttf`.
+    pub copy: PathBuf,
+    /// The file it duplicates, e.g. `C:\Windows\Fonts\arial.ttf`.
+    pub canonical: PathBuf,
+    /// SHA-256 both files share.
+    pub sha256: String,
+    /// Registry values that pointed at the copy.
+    pub registrations: Vec<String>,
+    /// Whether those values were deleted (the canonical file was registered
+    /// already) rather than pointed at the canonical file.
+    pub canonical_registered: bool,
+    /// Whether the copy was deleted; `false` in a dry run or when the
+    /// delete failed (see the report's warnings).
+    #[serde(default)]
+    pub deleted: bool,
+}
+
+/// A numbered file that is not a copy of its namesake.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeptCopy {
+    pub copy: PathBuf,
+    pub canonical: PathBuf,
+    /// Why it stays, e.g. `contents differ`.
+    pub reason: String,
+}
+
+/// What [`plan_repair`] found, and after a repair what was done.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepairReport {
+    pub scope: FontScope,
+    pub merged: Vec<MergedCopy>,
+    pub kept: Vec<KeptCopy>,
+    /// Merges that failed part-way, e.g. a copy that is still in use.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl RepairReport {
+    pub fn new(scope: FontScope) -> Self {
+        Self {
+            scope,
+            merged: Vec::new(),
+            kept: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.merged.is_empty() && self.kept.is_empty()
+    }
+}
+
+/// `dir/arial.ttf` for `dir/arial_0.ttf`; `None` when the stem does not end
+/// in `_` and digits.
+pub fn canonical_path(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_str()?;
+    let (base, number) = stem.rsplit_once('_')?;
+    if base.is_empty() || number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let name = match path.extension() {
+        Some(ext) => format!("{base}.{}", ext.to_string_lossy()),
+        None => base.to_string(),
+    };
+    Some(path.with_file_name(name))
+}
+
+/// Group `registrations` (registry value name, file) of `scope` by numbered
+/// copy and compare each copy with its namesake. Nothing is changed.
+///
+/// Copies whose namesake does not exist are not duplicates and are left out.
+pub fn plan_repair(
+    scope: FontScope,
+    registrations: &[(String, PathBuf)],
+) -> FontResult<RepairReport> {
+    let mut copies: BTreeMap<PathBuf, (PathBuf, Vec<String>)> = BTreeMap::new();
+    for (name, path) in registrations {
+        let Some(canonical) = canonical_path(path) else {
+            continue;
+        };
+        if path.is_file() && canonical.is_file() {
+            copies
+                .entry(path.clone())
+                .or_insert_with(|| (canonical, Vec::new()))
+                .1
+                .push(name.clone());
+        }
+    }
+
+    let mut report = RepairReport::new(scope);
+    for (copy, (canonical, names)) in copies {
+        let sha256 = digest::sha256_file(&copy)?;
+        if !digest::digests_match(&sha256, &digest::sha256_file(&canonical)?) {
+            report.kept.push(KeptCopy {
+                copy,
+                canonical,
+                reason: "contents differ".to_string(),
+            });
+            continue;
+        }
+        // Registry paths compare case-insensitively.
+        let canonical_lower = canonical.to_string_lossy().to_lowercase();
+        let canonical_registered = registrations
+            .iter()
+            .any(|(_, path)| path.to_string_lossy().to_lowercase() == canonical_lower);
+        report.merged.push(MergedCopy {
+            copy,
+            canonical,
+            sha256,
+            registrations: names,
+            canonical_registered,
+            deleted: false,
+        });
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn canonical_names_drop_the_collision_number() {
+        let dir = Path::new("Fonts");
+        assert_eq!(
+            canonical_path(&dir.join("arial_0.ttf")),
+            Some(dir.join("arial.ttf"))
+        );
+        assert_eq!(
+            canonical_path(&dir.join("Noto_Sans_12.otf")),
+            Some(dir.join("Noto_Sans.otf"))
+        );
+        assert_eq!(canonical_path(&dir.join("Noto_Sans.otf")), None);
+        assert_eq!(canonical_path(&dir.join("_0.ttf")), None);
+        assert_eq!(canonical_path(&dir.join("arial.ttf")), None);
+    }
+
+    #[test]
+    fn identical_copies_merge_and_different_ones_stay() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = |name: &str, bytes: &[u8]| {
+            let path = tmp.path().join(name);
+            fs::write(&path, bytes).unwrap();
+            path
+        };
+        let arial = file("arial.ttf", b"arial");
+        let arial_0 = file("arial_0.ttf", b"arial");
+        let arial_1 = file("arial_1.ttf", b"arial");
+        file("Serif.otf", b"serif");
+        let serif_0 = file("Serif_0.otf", b"another serif");
+        let orphan = file("Orphan_0.ttf", b"orphan");
+        let registrations = vec![
+            ("Arial (TrueType)".to_string(), arial.clone()),
+            ("Arial #2 (TrueType)".to_string(), arial_0.clone()),
+            ("Arial #3 (TrueType)".to_string(), arial_1.clone()),
+            ("Arial Copy (TrueType)".to_string(), arial_1.clone()),
+            ("Serif (TrueType)".to_string(), serif_0.clone()),
+            ("Orphan (TrueType)".to_string(), orphan),
+        ];
+
+        let report = plan_repair(FontScope::System, &registrations).unwrap();
+        let merged: Vec<_> = report
+            .merged
+            .iter()
+            .map(|m| {
+                (
+                    m.copy.clone(),
+                    m.registrations.len(),
+                    m.canonical_registered,
+                )
+            })
+            .collect();
+        assert_eq!(merged, vec![(arial_0, 1, true), (arial_1, 2, true)]);
+        assert!(report.merged.iter().all(|m| m.canonical == arial));
+        assert_eq!(report.kept.len(), 1);
+        assert_eq!(report.kept[0].copy, serif_0);
+
+        let unregistered = plan_repair(FontScope::User, &registrations[1..2]).unwrap();
+        assert!(!unregistered.merged[0].canonical_registered);
+    }
+}
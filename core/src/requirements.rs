@@ -0,0 +1,568 @@
+//! Font requirements of documents and design files
+//! (`fontlift install --requirements`).
+//!
+//! Layout and design apps know which fonts a document needs and can export
+//! that list. fontlift reads three shapes of it:
+//!
+//! - **Document fonts folders.** An InDesign package puts the fonts a
+//!   document uses into a `Document fonts` folder. Each face in it is a
+//!   requirement, and the file itself can satisfy it.
+//! - **JSON font lists**, as exported by Figma and Sketch plugins: an array,
+//!   or an object with a `fonts` (or `missingFonts`, `documentFonts`,
+//!   `requirements`) array. Entries are objects such as
+//!   `{"family": "Inter", "style": "Bold"}` or
+//!   `{"postScriptName": "Inter-Bold"}`, or plain names.
+//! - **Text files** with one font per line: a PostScript name (`Inter-Bold`),
+//!   a family (`Source Serif 4`) or `Family:Style` (`Inter:Bold Italic`).
+//!   `#` starts a comment.
+//!
+//! [`resolve`] then decides for each requirement, in this order: a face is
+//! already installed, a local file provides it (the bundled file or one
+//! under the search directories), Google Fonts has it, or it is unmet.
+
+use crate::google_fonts::{self, GoogleFontFamily};
+use crate::{manifest, metadata, FontError, FontResult, FontliftFontFaceInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Keys under which JSON exports list their fonts.
+const LIST_KEYS: &[&str] = &["fonts", "missingFonts", "documentFonts", "requirements"];
+const FAMILY_KEYS: &[&str] = &["family", "fontFamily", "family_name", "familyName"];
+const STYLE_KEYS: &[&str] = &["style", "fontStyle", "subfamily", "styleName"];
+const POSTSCRIPT_KEYS: &[&str] = &[
+    "postScriptName",
+    "postscriptName",
+    "postscript_name",
+    "fontName",
+];
+
+/// One font a document needs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Requirement {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    /// Only meaningful with `family`; any style of the family when `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postscript_name: Option<String>,
+    /// The file in a document fonts folder that provides this face.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundled: Option<PathBuf>,
+}
+
+impl Requirement {
+    /// A bare name: PostScript names have a hyphen and no spaces
+    /// (`Inter-Bold`), anything else is a family.
+    pub fn from_name(name: &str) -> Self {
+        let name = name.trim();
+        if name.contains('-') && !name.contains(char::is_whitespace) {
+            Self {
+                postscript_name: Some(name.to_string()),
+                ..Self::default()
+            }
+        } else {
+            Self {
+                family: Some(name.to_string()),
+                ..Self::default()
+            }
+        }
+    }
+
+    /// `Inter Bold`, `Inter`, or the PostScript name.
+    pub fn label(&self) -> String {
+        match (&self.family, &self.style, &self.postscript_name) {
+            (Some(family), Some(style), _) => format!("{family} {style}"),
+            (Some(family), None, _) => family.clone(),
+            (None, _, Some(ps)) => ps.clone(),
+            (None, _, None) => "(unnamed)".to_string(),
+        }
+    }
+
+    /// Whether `face` satisfies the requirement. A PostScript name, when
+    /// known, must match; otherwise family and (if given) style must.
+    pub fn matches(&self, face: &FontliftFontFaceInfo) -> bool {
+        if let Some(ps) = &self.postscript_name {
+            return face.postscript_name.eq_ignore_ascii_case(ps);
+        }
+        let Some(family) = &self.family else {
+            return false;
+        };
+        face.family_name.eq_ignore_ascii_case(family)
+            && self
+                .style
+                .as_deref()
+                .map_or(true, |style| style_key(&face.style) == style_key(style))
+    }
+}
+
+/// Style names compared without case or spaces: `Bold Italic` = `BoldItalic`.
+fn style_key(style: &str) -> String {
+    style
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The shape a requirements file had.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequirementsFormat {
+    DocumentFonts,
+    FontList,
+    Text,
+}
+
+/// The requirements read from one file or folder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Requirements {
+    pub source: PathBuf,
+    pub format: RequirementsFormat,
+    pub requirements: Vec<Requirement>,
+}
+
+/// Read requirements from a document fonts folder (or a package folder
+/// containing one), a `.json` font list, or a text file.
+pub fn load_requirements(path: &Path) -> FontResult<Requirements> {
+    if path.is_dir() {
+        let folder = path.join("Document fonts");
+        let folder = if folder.is_dir() {
+            folder
+        } else {
+            path.to_path_buf()
+        };
+        return Ok(Requirements {
+            source: path.to_path_buf(),
+            format: RequirementsFormat::DocumentFonts,
+            requirements: document_fonts(&folder),
+        });
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let (format, requirements) = if is_json {
+        let value: Value = serde_json::from_str(&content)
+            .map_err(|e| FontError::InvalidFormat(format!("{}: {e}", path.display())))?;
+        (RequirementsFormat::FontList, font_list(&value, path)?)
+    } else {
+        (RequirementsFormat::Text, text_list(&content))
+    };
+    Ok(Requirements {
+        source: path.to_path_buf(),
+        format,
+        requirements,
+    })
+}
+
+/// One requirement per face of every font file in `folder`. Files that do
+/// not parse are skipped with a warning.
+fn document_fonts(folder: &Path) -> Vec<Requirement> {
+    manifest::font_files_under(&[folder.to_path_buf()])
+        .into_iter()
+        .flat_map(|file| match metadata::read_all_face_info(&file) {
+            Ok(faces) => faces
+                .into_iter()
+                .map(|face| Requirement {
+                    family: Some(face.family_name),
+                    style: Some(face.style),
+                    postscript_name: Some(face.postscript_name),
+                    bundled: Some(file.clone()),
+                })
+                .collect(),
+            Err(e) => {
+                log::warn!("Skipping {}: {}", file.display(), e);
+                Vec::new()
+            }
+        })
+        .collect()
+}
+
+fn font_list(value: &Value, path: &Path) -> FontResult<Vec<Requirement>> {
+    let entries = match value {
+        Value::Array(entries) => entries,
+        Value::Object(object) => LIST_KEYS
+            .iter()
+            .find_map(|key| object.get(*key).and_then(Value::as_array))
+            .ok_or_else(|| {
+                FontError::InvalidFormat(format!(
+                    "{}: expected an array of fonts or a \"fonts\" key",
+                    path.display()
+                ))
+            })?,
+        _ => {
+            return Err(FontError::InvalidFormat(format!(
+                "{}: expected a JSON array or object",
+                path.display()
+            )))
+        }
+    };
+
+    let string = |object: &serde_json::Map<String, Value>, keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| object.get(*key).and_then(Value::as_str))
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let mut requirements = Vec::new();
+    for entry in entries {
+        match entry {
+            Value::String(name) if !name.trim().is_empty() => {
+                requirements.push(Requirement::from_name(name))
+            }
+            Value::Object(object) => {
+                let requirement = Requirement {
+                    family: string(object, FAMILY_KEYS),
+                    style: string(object, STYLE_KEYS),
+                    postscript_name: string(object, POSTSCRIPT_KEYS),
+                    bundled: None,
+                };
+                if requirement.family.is_some() || requirement.postscript_name.is_some() {
+                    requirements.push(requirement);
+                } else {
+                    log::warn!(
+                        "{}: skipping entry without a font name: {entry}",
+                        path.display()
+                    );
+                }
+            }
+            other => log::warn!("{}: skipping entry {other}", path.display()),
+        }
+    }
+    Ok(requirements)
+}
+
+fn text_list(content: &str) -> Vec<Requirement> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.split_once(':') {
+            Some((family, style)) => Requirement {
+                family: Some(family.trim().to_string()),
+                style: Some(style.trim().to_string()).filter(|s| !s.is_empty()),
+                ..Requirement::default()
+            },
+            None => Requirement::from_name(line),
+        })
+        .collect()
+}
+
+/// How a requirement is, or can be, satisfied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Resolution {
+    /// An installed face matches.
+    Installed {
+        path: PathBuf,
+    },
+    /// Installing this local file satisfies it.
+    Local {
+        path: PathBuf,
+    },
+    /// A provider can download it.
+    Provider {
+        provider: String,
+        family: String,
+        variant: String,
+    },
+    Unmet {
+        reason: String,
+    },
+}
+
+/// A requirement and its resolution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedRequirement {
+    pub requirement: Requirement,
+    #[serde(flatten)]
+    pub resolution: Resolution,
+}
+
+/// Result of [`resolve`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequirementsReport {
+    pub source: PathBuf,
+    pub format: RequirementsFormat,
+    pub resolved: Vec<ResolvedRequirement>,
+}
+
+impl RequirementsReport {
+    pub fn unmet(&self) -> Vec<&ResolvedRequirement> {
+        self.resolved
+            .iter()
+            .filter(|r| matches!(r.resolution, Resolution::Unmet { .. }))
+            .collect()
+    }
+
+    /// Whether some requirement could only be met by a provider that was
+    /// not consulted.
+    pub fn wants_provider(&self) -> bool {
+        self.unmet().iter().any(|r| r.requirement.family.is_some())
+    }
+
+    /// Local files to install, each once, in requirement order.
+    pub fn local_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        for resolved in &self.resolved {
+            if let Resolution::Local { path } = &resolved.resolution {
+                if !files.contains(path) {
+                    files.push(path.clone());
+                }
+            }
+        }
+        files
+    }
+
+    /// Provider downloads as `(family, variants)`, one entry per family.
+    pub fn provider_downloads(&self) -> Vec<(String, Vec<String>)> {
+        let mut downloads: Vec<(String, Vec<String>)> = Vec::new();
+        for resolved in &self.resolved {
+            let Resolution::Provider {
+                family, variant, ..
+            } = &resolved.resolution
+            else {
+                continue;
+            };
+            match downloads.iter_mut().find(|(f, _)| f == family) {
+                Some((_, variants)) if !variants.contains(variant) => {
+                    variants.push(variant.clone())
+                }
+                Some(_) => {}
+                None => downloads.push((family.clone(), vec![variant.clone()])),
+            }
+        }
+        downloads
+    }
+}
+
+/// Faces of the font files under `roots`, for [`resolve`].
+pub fn local_faces(roots: &[PathBuf]) -> Vec<FontliftFontFaceInfo> {
+    manifest::font_files_under(roots)
+        .iter()
+        .filter_map(|file| metadata::read_all_face_info(file).ok())
+        .flatten()
+        .collect()
+}
+
+/// Decide how each requirement is satisfied: by an `installed` face, by a
+/// bundled file or one of the `local` faces, by a `google` family (when the
+/// family list was fetched), or not at all.
+pub fn resolve(
+    requirements: &Requirements,
+    installed: &[FontliftFontFaceInfo],
+    local: &[FontliftFontFaceInfo],
+    google: Option<&[GoogleFontFamily]>,
+) -> RequirementsReport {
+    let resolved = requirements
+        .requirements
+        .iter()
+        .map(|requirement| ResolvedRequirement {
+            requirement: requirement.clone(),
+            resolution: resolve_one(requirement, installed, local, google),
+        })
+        .collect();
+    RequirementsReport {
+        source: requirements.source.clone(),
+        format: requirements.format,
+        resolved,
+    }
+}
+
+fn resolve_one(
+    requirement: &Requirement,
+    installed: &[FontliftFontFaceInfo],
+    local: &[FontliftFontFaceInfo],
+    google: Option<&[GoogleFontFamily]>,
+) -> Resolution {
+    if let Some(face) = installed.iter().find(|face| requirement.matches(face)) {
+        return Resolution::Installed {
+            path: face.source.path.clone(),
+        };
+    }
+    if let Some(path) = &requirement.bundled {
+        return Resolution::Local { path: path.clone() };
+    }
+    if let Some(face) = local.iter().find(|face| requirement.matches(face)) {
+        return Resolution::Local {
+            path: face.source.path.clone(),
+        };
+    }
+
+    let Some(family) = &requirement.family else {
+        return Resolution::Unmet {
+            reason: "not installed, and no family name to look up".to_string(),
+        };
+    };
+    let Some(google) = google else {
+        return Resolution::Unmet {
+            reason: "not installed or found locally".to_string(),
+        };
+    };
+    let Some(found) = google_fonts::find_family(google, family) else {
+        return Resolution::Unmet {
+            reason: "not installed, not found locally or on Google Fonts".to_string(),
+        };
+    };
+    let variant = match &requirement.style {
+        Some(style) => found.variants.iter().find(|variant| {
+            style_key(&google_fonts::variant_style_name(variant)) == style_key(style)
+        }),
+        None => found
+            .variants
+            .iter()
+            .find(|v| *v == "regular")
+            .or(found.variants.first()),
+    };
+    match variant {
+        Some(variant) => Resolution::Provider {
+            provider: "google-fonts".to_string(),
+            family: found.family.clone(),
+            variant: variant.clone(),
+        },
+        None => Resolution::Unmet {
+            reason: format!(
+                "Google Fonts has {} but not that style (available: {})",
+                found.family,
+                found.variants.join(", ")
+            ),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FontliftFontSource;
+    use fontlift_testfonts::TestFont;
+    use std::collections::BTreeMap;
+
+    fn face(family: &str, style: &str, path: &str) -> FontliftFontFaceInfo {
+        FontliftFontFaceInfo::new(
+            FontliftFontSource::new(PathBuf::from(path)),
+            format!("{}-{}", family.replace(' ', ""), style.replace(' ', "")),
+            format!("{family} {style}"),
+            family.to_string(),
+            style.to_string(),
+        )
+    }
+
+    fn statuses(report: &RequirementsReport) -> Vec<String> {
+        report
+            .resolved
+            .iter()
+            .map(|r| serde_json::to_value(&r.resolution).unwrap()["status"].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn reads_figma_sketch_and_text_lists() {
+        let tmp = tempfile::tempdir().unwrap();
+        let figma = tmp.path().join("figma.json");
+        fs::write(
+            &figma,
+            r#"{"fonts": [{"family": "Inter", "style": "Bold"}, {"postScriptName": "Lora-Italic"}, {}]}"#,
+        )
+        .unwrap();
+        let sketch = tmp.path().join("sketch.json");
+        fs::write(&sketch, r#"["Inter-Bold", "Source Serif 4"]"#).unwrap();
+        let text = tmp.path().join("fonts.txt");
+        fs::write(
+            &text,
+            "# brand fonts\nInter:Bold Italic\nLora-Regular  # body\n\n",
+        )
+        .unwrap();
+
+        let figma = load_requirements(&figma).unwrap();
+        assert_eq!(figma.format, RequirementsFormat::FontList);
+        assert_eq!(figma.requirements.len(), 2);
+        assert_eq!(figma.requirements[0].label(), "Inter Bold");
+        assert_eq!(
+            figma.requirements[1].postscript_name.as_deref(),
+            Some("Lora-Italic")
+        );
+
+        let sketch = load_requirements(&sketch).unwrap();
+        assert_eq!(
+            sketch.requirements[0].postscript_name.as_deref(),
+            Some("Inter-Bold")
+        );
+        assert_eq!(
+            sketch.requirements[1].family.as_deref(),
+            Some("Source Serif 4")
+        );
+
+        let text = load_requirements(&text).unwrap();
+        assert_eq!(text.format, RequirementsFormat::Text);
+        assert_eq!(text.requirements[0].style.as_deref(), Some("Bold Italic"));
+        assert_eq!(
+            text.requirements[1].postscript_name.as_deref(),
+            Some("Lora-Regular")
+        );
+    }
+
+    #[test]
+    fn document_fonts_folders_bundle_their_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let folder = tmp.path().join("Brochure Folder/Document fonts");
+        fs::create_dir_all(&folder).unwrap();
+        let file = TestFont::new("Package Sans").write_to(&folder).unwrap();
+
+        let requirements = load_requirements(&tmp.path().join("Brochure Folder")).unwrap();
+        assert_eq!(requirements.format, RequirementsFormat::DocumentFonts);
+        assert_eq!(requirements.requirements.len(), 1);
+        assert_eq!(requirements.requirements[0].bundled.as_ref(), Some(&file));
+
+        let report = resolve(&requirements, &[], &[], None);
+        assert_eq!(report.local_files(), vec![file]);
+        assert!(report.unmet().is_empty());
+    }
+
+    #[test]
+    fn resolves_installed_then_local_then_provider() {
+        let requirements = Requirements {
+            source: PathBuf::from("fonts.txt"),
+            format: RequirementsFormat::Text,
+            requirements: text_list(
+                "Inter:Bold\nLora:Italic\nRoboto:BoldItalic\nRoboto:Thin\nSecret-Regular\n",
+            ),
+        };
+        let installed = [face("Inter", "Bold", "/fonts/Inter-Bold.ttf")];
+        let local = [face("Lora", "Italic", "/dl/Lora-Italic.ttf")];
+        let roboto = GoogleFontFamily {
+            family: "Roboto".to_string(),
+            category: None,
+            variants: vec!["regular".to_string(), "700italic".to_string()],
+            files: BTreeMap::new(),
+        };
+
+        let offline = resolve(&requirements, &installed, &local, None);
+        assert!(offline.wants_provider());
+        let report = resolve(&requirements, &installed, &local, Some(&[roboto]));
+        assert_eq!(
+            statuses(&report),
+            [
+                "\"installed\"",
+                "\"local\"",
+                "\"provider\"",
+                "\"unmet\"",
+                "\"unmet\""
+            ]
+        );
+        assert_eq!(
+            report.provider_downloads(),
+            vec![("Roboto".to_string(), vec!["700italic".to_string()])]
+        );
+        let json = serde_json::to_value(&report.resolved[2]).unwrap();
+        assert_eq!(json["requirement"]["family"], "Roboto");
+        assert_eq!(json["variant"], "700italic");
+    }
+}
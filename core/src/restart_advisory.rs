@@ -0,0 +1,293 @@
+//! Which running applications need a restart to see font changes.
+//!
+//! The OS announces new and removed fonts, but many applications read the
+//! font list once at launch: Office, the Adobe suite, browsers, design tools.
+//! After fontlift changes fonts, [`restart_advisories`] checks the running
+//! processes against a table of such applications and returns the ones the
+//! user should restart.
+//!
+//! The built-in table ([`default_app_rules`]) can be extended or replaced with
+//! a JSON file at [`app_rules_path`] (`FONTLIFT_RESTART_APPS_PATH` overrides
+//! the location):
+//!
+//! ```json
+//! {
+//!   "replace_defaults": false,
+//!   "apps": [
+//!     { "name": "Sketch", "processes": ["Sketch"] },
+//!     { "name": "Google Chrome", "processes": [] }
+//!   ]
+//! }
+//! ```
+//!
+//! An entry with the same name as a built-in one replaces it, so an empty
+//! `processes` list switches that application off.
+
+use crate::{FontError, FontResult};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// An application that caches the font list, and the process names it runs as.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppRule {
+    pub name: String,
+    /// Executable names, compared case-insensitively without `.exe`.
+    pub processes: Vec<String>,
+}
+
+impl AppRule {
+    pub fn new(name: &str, processes: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            processes: processes.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+/// Contents of the app rules file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppRulesFile {
+    /// Use only `apps`, ignoring the built-in table.
+    #[serde(default)]
+    pub replace_defaults: bool,
+    #[serde(default)]
+    pub apps: Vec<AppRule>,
+}
+
+/// An application that is running and should be restarted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestartAdvisory {
+    pub app: String,
+    /// The running processes that matched.
+    pub processes: Vec<String>,
+}
+
+/// Applications known to read the font list only at launch.
+pub fn default_app_rules() -> Vec<AppRule> {
+    vec![
+        AppRule::new("Microsoft Word", &["WINWORD", "Microsoft Word"]),
+        AppRule::new("Microsoft Excel", &["EXCEL", "Microsoft Excel"]),
+        AppRule::new(
+            "Microsoft PowerPoint",
+            &["POWERPNT", "Microsoft PowerPoint"],
+        ),
+        AppRule::new("Microsoft Outlook", &["OUTLOOK", "Microsoft Outlook"]),
+        AppRule::new("Adobe Photoshop", &["Photoshop", "Adobe Photoshop"]),
+        AppRule::new("Adobe Illustrator", &["Illustrator", "Adobe Illustrator"]),
+        AppRule::new("Adobe InDesign", &["InDesign", "Adobe InDesign"]),
+        AppRule::new(
+            "Adobe Acrobat",
+            &["Acrobat", "AcroRd32", "AdobeAcrobat", "Adobe Acrobat"],
+        ),
+        AppRule::new("Google Chrome", &["chrome", "Google Chrome"]),
+        AppRule::new("Microsoft Edge", &["msedge", "Microsoft Edge"]),
+        AppRule::new("Mozilla Firefox", &["firefox"]),
+        AppRule::new("Figma", &["Figma"]),
+        AppRule::new("Sketch", &["Sketch"]),
+        AppRule::new(
+            "Affinity",
+            &["Affinity Designer", "Affinity Publisher", "Affinity Photo"],
+        ),
+        AppRule::new("LibreOffice", &["soffice", "soffice.bin"]),
+        AppRule::new("Pages", &["Pages"]),
+        AppRule::new("Keynote", &["Keynote"]),
+    ]
+}
+
+/// Location of the app rules file.
+pub fn app_rules_path() -> PathBuf {
+    if let Ok(path) = std::env::var("FONTLIFT_RESTART_APPS_PATH") {
+        return PathBuf::from(path);
+    }
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("fontlift")
+        .join("restart-apps.json")
+}
+
+/// The built-in table merged with the rules file at `path`, if it exists.
+pub fn load_app_rules(path: &Path) -> FontResult<Vec<AppRule>> {
+    let file = match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str::<AppRulesFile>(&content).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to parse {}: {e}", path.display()))
+        })?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => AppRulesFile::default(),
+        Err(e) => return Err(FontError::IoError(e)),
+    };
+    Ok(merge_rules(file))
+}
+
+fn merge_rules(file: AppRulesFile) -> Vec<AppRule> {
+    let mut rules = if file.replace_defaults {
+        Vec::new()
+    } else {
+        default_app_rules()
+    };
+    for app in file.apps {
+        rules.retain(|rule| !rule.name.eq_ignore_ascii_case(&app.name));
+        rules.push(app);
+    }
+    rules
+}
+
+fn normalize_process(name: &str) -> String {
+    let lower = name.trim().to_lowercase();
+    lower.strip_suffix(".exe").unwrap_or(&lower).to_string()
+}
+
+/// Match `running` process names against `rules`.
+pub fn match_running_apps(rules: &[AppRule], running: &[String]) -> Vec<RestartAdvisory> {
+    let running: Vec<(String, &String)> =
+        running.iter().map(|p| (normalize_process(p), p)).collect();
+
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let mut processes: Vec<String> = running
+                .iter()
+                .filter(|(normalized, _)| {
+                    rule.processes
+                        .iter()
+                        .any(|p| normalize_process(p) == *normalized)
+                })
+                .map(|(_, original)| (*original).clone())
+                .collect();
+            processes.sort();
+            processes.dedup();
+            (!processes.is_empty()).then(|| RestartAdvisory {
+                app: rule.name.clone(),
+                processes,
+            })
+        })
+        .collect()
+}
+
+/// Executable names of the processes currently running.
+pub fn running_process_names() -> FontResult<Vec<String>> {
+    #[cfg(windows)]
+    let output = Command::new("tasklist")
+        .args(["/fo", "csv", "/nh"])
+        .output();
+    #[cfg(not(windows))]
+    let output = Command::new("ps").args(["-A", "-o", "comm="]).output();
+
+    let output = output.map_err(FontError::IoError)?;
+    if !output.status.success() {
+        return Err(FontError::UnsupportedOperation(
+            "Could not list running processes".to_string(),
+        ));
+    }
+    Ok(parse_process_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `ps -o comm=` (full paths on macOS) or `tasklist /fo csv` output.
+fn parse_process_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let name = match line.strip_prefix('"') {
+                Some(rest) => rest.split('"').next()?,
+                None => line.rsplit('/').next()?,
+            };
+            (!name.is_empty()).then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Running applications that should be restarted to see font changes.
+pub fn restart_advisories() -> FontResult<Vec<RestartAdvisory>> {
+    let rules = load_app_rules(&app_rules_path())?;
+    Ok(match_running_apps(&rules, &running_process_names()?))
+}
+
+/// Show a desktop notification. Best effort: the caller decides whether a
+/// failure matters.
+pub fn notify_desktop(title: &str, message: &str) -> FontResult<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification \"{}\" with title \"{}\"",
+            quote(message),
+            quote(title)
+        ));
+        command
+    };
+
+    #[cfg(windows)]
+    let mut command = {
+        let quote = |s: &str| s.replace('\'', "''");
+        let mut command = Command::new("powershell");
+        command
+            .args(["-NoProfile", "-NonInteractive", "-Command"])
+            .arg(format!(
+                "Add-Type -AssemblyName System.Windows.Forms; \
+             $n = New-Object System.Windows.Forms.NotifyIcon; \
+             $n.Icon = [System.Drawing.SystemIcons]::Information; $n.Visible = $true; \
+             $n.ShowBalloonTip(10000, '{}', '{}', 'Info'); Start-Sleep -Seconds 10; $n.Dispose()",
+                quote(title),
+                quote(message)
+            ));
+        command
+    };
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let mut command = {
+        let mut command = Command::new("notify-send");
+        command.arg(title).arg(message);
+        command
+    };
+
+    // The Windows balloon has to outlive this process, so nothing waits on it.
+    command.spawn().map(|_| ()).map_err(FontError::IoError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_processes_from_ps_and_tasklist() {
+        let ps = "/sbin/launchd\n/Applications/Microsoft Word.app/Contents/MacOS/Microsoft Word\n\
+                  /Applications/Google Chrome.app/Contents/MacOS/Google Chrome\n";
+        let tasklist = "\"WINWORD.EXE\",\"4120\",\"Console\",\"1\",\"182,412 K\"\n\
+                        \"explorer.exe\",\"5032\",\"Console\",\"1\",\"98,120 K\"\n";
+        let mut running = parse_process_list(ps);
+        running.extend(parse_process_list(tasklist));
+
+        let advisories = match_running_apps(&default_app_rules(), &running);
+        let apps: Vec<&str> = advisories.iter().map(|a| a.app.as_str()).collect();
+        assert_eq!(apps, ["Microsoft Word", "Google Chrome"]);
+        assert_eq!(
+            advisories[0].processes,
+            ["Microsoft Word".to_string(), "WINWORD.EXE".to_string()]
+        );
+    }
+
+    #[test]
+    fn rules_file_extends_overrides_or_replaces_defaults() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("restart-apps.json");
+        let running = vec!["chrome".to_string(), "Glyphs 3".to_string()];
+
+        assert_eq!(load_app_rules(&path).unwrap(), default_app_rules());
+
+        std::fs::write(
+            &path,
+            r#"{ "apps": [
+                { "name": "Glyphs", "processes": ["Glyphs 3"] },
+                { "name": "google chrome", "processes": [] }
+            ] }"#,
+        )
+        .unwrap();
+        let advisories = match_running_apps(&load_app_rules(&path).unwrap(), &running);
+        assert_eq!(advisories.len(), 1);
+        assert_eq!(advisories[0].app, "Glyphs");
+
+        std::fs::write(&path, r#"{ "replace_defaults": true }"#).unwrap();
+        assert!(load_app_rules(&path).unwrap().is_empty());
+    }
+}
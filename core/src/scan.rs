@@ -0,0 +1,218 @@
+//! Finding the font files in a directory for `fontlift install DIR`.
+//!
+//! By default only the directory's own files count. [`ScanOptions`] can
+//! walk nested folders too (`-r`, optionally limited by `--max-depth`) and
+//! keep or drop files by glob (`--include`, `--exclude`), so a foundry
+//! library of family, format and version folders installs in one go:
+//!
+//! ```text
+//! fontlift install ~/FontLibrary -r --exclude "*Trial*"
+//! ```
+//!
+//! Symlinked folders are followed, but each directory is read once: a link
+//! back up the tree ends the walk there instead of looping.
+
+use crate::listing::glob_match;
+use crate::{validation, FontError, FontResult};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How [`font_files_in`] walks a directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// Descend into subdirectories.
+    pub recursive: bool,
+    /// With `recursive`, how many levels of subdirectories to enter; `None`
+    /// for no limit. `1` reads the directory and its direct subfolders.
+    pub max_depth: Option<usize>,
+    /// Globs (`*`, `?`, case-insensitive); when any are given, only files
+    /// matching one are kept.
+    pub include: Vec<String>,
+    /// Globs for files and folders to leave out.
+    pub exclude: Vec<String>,
+}
+
+impl ScanOptions {
+    /// Walk subdirectories, at most `max_depth` levels deep.
+    pub fn recursive(max_depth: Option<usize>) -> Self {
+        Self {
+            recursive: true,
+            max_depth,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_include(mut self, include: Vec<String>) -> Self {
+        self.include = include;
+        self
+    }
+
+    pub fn with_exclude(mut self, exclude: Vec<String>) -> Self {
+        self.exclude = exclude;
+        self
+    }
+
+    /// Does one of `patterns` match the entry's name or its path relative to
+    /// the scanned directory (`Trial/Sans.otf`)?
+    fn any_match(patterns: &[String], name: &str, relative: &str) -> bool {
+        patterns
+            .iter()
+            .any(|pattern| glob_match(pattern, name) || glob_match(pattern, relative))
+    }
+
+    fn keeps_file(&self, name: &str, relative: &str) -> bool {
+        (self.include.is_empty() || Self::any_match(&self.include, name, relative))
+            && !Self::any_match(&self.exclude, name, relative)
+    }
+
+    fn enters_dir(&self, name: &str, relative: &str, depth: usize) -> bool {
+        self.recursive
+            && self.max_depth.map_or(true, |max| depth <= max)
+            && !Self::any_match(&self.exclude, name, relative)
+    }
+}
+
+/// Font files (by extension) in `dir`, sorted, as `options` selects them.
+///
+/// An unreadable `dir` is an error; an unreadable subdirectory is skipped
+/// with a warning in the log, so one locked folder does not stop a large
+/// library from installing.
+pub fn font_files_in(dir: &Path, options: &ScanOptions) -> FontResult<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    visited.insert(fs::canonicalize(dir).map_err(FontError::IoError)?);
+    // (directory, its path relative to `dir`, depth below `dir`)
+    let mut pending = vec![(dir.to_path_buf(), String::new(), 0)];
+    while let Some((current, prefix, depth)) = pending.pop() {
+        let entries = match fs::read_dir(&current) {
+            Ok(entries) => entries,
+            Err(e) if depth == 0 => return Err(FontError::IoError(e)),
+            Err(e) => {
+                log::warn!("Skipping {}: {e}", current.display());
+                continue;
+            }
+        };
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) if depth == 0 => return Err(FontError::IoError(e)),
+                Err(e) => {
+                    log::warn!("Skipping an entry of {}: {e}", current.display());
+                    continue;
+                }
+            };
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let relative = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{prefix}/{name}")
+            };
+
+            if path.is_dir() {
+                if !options.enters_dir(&name, &relative, depth + 1) {
+                    continue;
+                }
+                // A symlink back to a folder already read would loop.
+                match fs::canonicalize(&path) {
+                    Ok(real) => {
+                        if visited.insert(real) {
+                            pending.push((path, relative, depth + 1));
+                        } else {
+                            log::debug!("Not re-reading {} (already scanned)", path.display());
+                        }
+                    }
+                    Err(e) => log::warn!("Skipping {}: {e}", path.display()),
+                }
+            } else if path.is_file()
+                && validation::is_valid_font_extension(&path)
+                && options.keeps_file(&name, &relative)
+            {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        for file in [
+            "Top.ttf",
+            "notes.txt",
+            "Acme/Sans/Sans-Regular.otf",
+            "Acme/Sans/Trial/Sans-Bold.otf",
+            "Acme/Serif/Serif-TrialItalic.ttf",
+            "Acme/Serif/v2/Serif-Regular.ttf",
+        ] {
+            let path = tmp.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"font").unwrap();
+        }
+        tmp
+    }
+
+    fn names(tmp: &tempfile::TempDir, options: &ScanOptions) -> Vec<String> {
+        font_files_in(tmp.path(), options)
+            .unwrap()
+            .iter()
+            .map(|p| {
+                p.strip_prefix(tmp.path())
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn walks_nested_folders_to_the_depth_asked() {
+        let tmp = library();
+        assert_eq!(names(&tmp, &ScanOptions::default()), ["Top.ttf"]);
+        assert_eq!(
+            names(&tmp, &ScanOptions::recursive(Some(2))),
+            [
+                "Acme/Sans/Sans-Regular.otf",
+                "Acme/Serif/Serif-TrialItalic.ttf",
+                "Top.ttf"
+            ]
+        );
+        assert_eq!(names(&tmp, &ScanOptions::recursive(None)).len(), 5);
+    }
+
+    #[test]
+    fn include_and_exclude_globs_filter_files_and_folders() {
+        let tmp = library();
+        let no_trials = ScanOptions::recursive(None).with_exclude(vec!["*Trial*".to_string()]);
+        assert_eq!(
+            names(&tmp, &no_trials),
+            [
+                "Acme/Sans/Sans-Regular.otf",
+                "Acme/Serif/v2/Serif-Regular.ttf",
+                "Top.ttf"
+            ]
+        );
+
+        let ttf_only = ScanOptions::recursive(None)
+            .with_include(vec!["*.ttf".to_string()])
+            .with_exclude(vec!["Acme/Serif/v2".to_string()]);
+        assert_eq!(
+            names(&tmp, &ttf_only),
+            ["Acme/Serif/Serif-TrialItalic.ttf", "Top.ttf"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_cycles_are_read_once() {
+        let tmp = library();
+        std::os::unix::fs::symlink(tmp.path(), tmp.path().join("Acme/loop")).unwrap();
+        assert_eq!(names(&tmp, &ScanOptions::recursive(None)).len(), 5);
+    }
+}
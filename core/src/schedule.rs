@@ -0,0 +1,396 @@
+//! Recurring fontlift runs through the OS scheduler (`fontlift schedule`).
+//!
+//! Studios want font hygiene to happen on its own rather than whenever
+//! someone remembers to run `fontlift cleanup`. A [`ScheduledTask`] describes
+//! the run; platform managers install it natively:
+//!
+//! | Platform | Mechanism | Where |
+//! |---|---|---|
+//! | macOS | launchd `StartCalendarInterval` job | `~/Library/LaunchAgents` (user), `/Library/LaunchDaemons` (system) |
+//! | Windows | Task Scheduler task | `\fontlift\<user>\<name>` (user), `\fontlift\system\<name>` (system) |
+//!
+//! The scheduler appends each run's output (the `--json` report) to the
+//! task's log file, so admins can see what every run removed.
+
+use crate::{journal, FontScope};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// launchd labels (and plist file names) start with this.
+pub const TASK_PREFIX: &str = "com.fontlaborg.fontlift.schedule.";
+
+/// Task Scheduler folder holding fontlift's tasks.
+pub const TASK_FOLDER: &str = r"\fontlift\";
+
+/// Subfolder of [`TASK_FOLDER`] for system-scope tasks. User tasks go in a
+/// subfolder named after the user, because Task Scheduler's namespace is
+/// shared by everyone on the machine.
+pub const SYSTEM_TASK_FOLDER: &str = "system";
+
+/// Hour of day (local time) scheduled runs start at.
+pub const RUN_HOUR: u8 = 10;
+
+/// How often a task runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Frequency {
+    Daily,
+    /// Mondays.
+    Weekly,
+}
+
+impl Frequency {
+    pub fn description(self) -> &'static str {
+        match self {
+            Frequency::Daily => "daily",
+            Frequency::Weekly => "weekly",
+        }
+    }
+}
+
+/// A recurring fontlift invocation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledTask {
+    /// Short name, e.g. `cleanup`; unique per scope.
+    pub name: String,
+    pub scope: FontScope,
+    pub frequency: Frequency,
+    /// The `fontlift` binary to run.
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    /// Each run's stdout and stderr are appended here.
+    pub log_file: PathBuf,
+}
+
+impl ScheduledTask {
+    /// `fontlift cleanup --quiet --json`, plus `--admin` for system scope.
+    pub fn cleanup(
+        program: PathBuf,
+        scope: FontScope,
+        frequency: Frequency,
+        log_file: PathBuf,
+    ) -> Self {
+        Self::subcommand("cleanup", program, scope, frequency, log_file)
+    }
+
+    /// `fontlift maintain --quiet --json`, plus `--admin` for system scope.
+    pub fn maintain(
+        program: PathBuf,
+        scope: FontScope,
+        frequency: Frequency,
+        log_file: PathBuf,
+    ) -> Self {
+        Self::subcommand("maintain", program, scope, frequency, log_file)
+    }
+
+    /// A task named after the `fontlift` subcommand it runs.
+    fn subcommand(
+        name: &str,
+        program: PathBuf,
+        scope: FontScope,
+        frequency: Frequency,
+        log_file: PathBuf,
+    ) -> Self {
+        let mut args = vec![name.to_string()];
+        if scope == FontScope::System {
+            args.push("--admin".to_string());
+        }
+        args.extend(["--quiet".to_string(), "--json".to_string()]);
+        Self {
+            name: name.to_string(),
+            scope,
+            frequency,
+            program,
+            args,
+            log_file,
+        }
+    }
+
+    /// launchd label, e.g. `com.fontlaborg.fontlift.schedule.cleanup`.
+    pub fn label(&self) -> String {
+        format!("{TASK_PREFIX}{}", self.name)
+    }
+
+    /// Task Scheduler path, e.g. `\fontlift\system\cleanup`.
+    pub fn task_path(&self) -> String {
+        task_path(&self.name, self.scope)
+    }
+
+    /// The command line, for display.
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.program.to_string_lossy().into_owned())
+            .chain(self.args.iter().cloned())
+            .map(|arg| {
+                if arg.contains(' ') {
+                    format!("\"{arg}\"")
+                } else {
+                    arg
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A task found by [`crate::FontManager::list_scheduled_tasks`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledTaskInfo {
+    pub name: String,
+    pub scope: FontScope,
+    /// `None` when the definition could not be read back.
+    pub frequency: Option<Frequency>,
+    /// Plist path on macOS, task path on Windows.
+    pub location: String,
+}
+
+fn current_user() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_else(|_| "user".to_string())
+}
+
+/// The [`TASK_FOLDER`] subfolder for `scope`.
+pub fn task_folder(scope: FontScope) -> String {
+    match scope {
+        FontScope::User => current_user(),
+        FontScope::System => SYSTEM_TASK_FOLDER.to_string(),
+    }
+}
+
+/// Task Scheduler path of task `name` in `scope`.
+pub fn task_path(name: &str, scope: FontScope) -> String {
+    format!("{TASK_FOLDER}{}\\{name}", task_folder(scope))
+}
+
+/// fontlift's tasks in `schtasks /Query /FO CSV /NH` output: the system
+/// tasks and those of `user`.
+pub fn schtasks_task_infos(csv: &str, user: &str) -> Vec<ScheduledTaskInfo> {
+    let mut tasks: Vec<ScheduledTaskInfo> = csv
+        .lines()
+        .filter_map(|line| line.split("\",\"").next())
+        .map(|field| field.trim_matches('"'))
+        .filter_map(|path| {
+            let rest = path.strip_prefix(TASK_FOLDER)?;
+            let (folder, name) = rest.split_once('\\')?;
+            let scope = if folder.eq_ignore_ascii_case(SYSTEM_TASK_FOLDER) {
+                FontScope::System
+            } else if folder.eq_ignore_ascii_case(user) {
+                FontScope::User
+            } else {
+                return None;
+            };
+            Some(ScheduledTaskInfo {
+                name: name.to_string(),
+                scope,
+                frequency: None,
+                location: path.to_string(),
+            })
+        })
+        .collect();
+    // schtasks repeats a task once per trigger.
+    tasks.dedup();
+    tasks
+}
+
+/// [`schtasks_task_infos`] for the current user.
+pub fn schtasks_task_infos_for_current_user(csv: &str) -> Vec<ScheduledTaskInfo> {
+    schtasks_task_infos(csv, &current_user())
+}
+
+/// Default log file for task `name`, next to the journal.
+pub fn default_log_file(name: &str) -> PathBuf {
+    journal::journal_path()
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join("logs")
+        .join(format!("{name}.log"))
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// launchd plist running `task` on its calendar interval.
+pub fn launchd_plist(task: &ScheduledTask) -> String {
+    let arguments: String = std::iter::once(task.program.to_string_lossy().into_owned())
+        .chain(task.args.iter().cloned())
+        .map(|arg| format!("        <string>{}</string>\n", xml_escape(&arg)))
+        .collect();
+    let weekday = match task.frequency {
+        Frequency::Daily => String::new(),
+        Frequency::Weekly => {
+            "        <key>Weekday</key>\n        <integer>1</integer>\n".to_string()
+        }
+    };
+    let log = xml_escape(&task.log_file.to_string_lossy());
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>StartCalendarInterval</key>
+    <dict>
+{weekday}        <key>Hour</key>
+        <integer>{RUN_HOUR}</integer>
+        <key>Minute</key>
+        <integer>0</integer>
+    </dict>
+    <key>StandardOutPath</key>
+    <string>{log}</string>
+    <key>StandardErrorPath</key>
+    <string>{log}</string>
+</dict>
+</plist>
+"#,
+        label = xml_escape(&task.label()),
+    )
+}
+
+/// Read the frequency back from a plist [`launchd_plist`] wrote.
+pub fn launchd_plist_frequency(plist: &str) -> Option<Frequency> {
+    if !plist.contains("<key>StartCalendarInterval</key>") {
+        None
+    } else if plist.contains("<key>Weekday</key>") {
+        Some(Frequency::Weekly)
+    } else {
+        Some(Frequency::Daily)
+    }
+}
+
+/// `schtasks.exe` arguments that create (or replace) `task`.
+///
+/// Task Scheduler cannot redirect output itself, so the task runs
+/// `cmd.exe /c` with `>>` into the log file. System-scope tasks run as
+/// `SYSTEM` whether or not anyone is logged in.
+pub fn schtasks_create_args(task: &ScheduledTask) -> Vec<String> {
+    let command = format!(
+        "cmd.exe /c \"{} >> \"{}\" 2>&1\"",
+        std::iter::once(format!("\"{}\"", task.program.display()))
+            .chain(task.args.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" "),
+        task.log_file.display()
+    );
+
+    let mut args: Vec<String> = ["/Create", "/F", "/TN"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    args.push(task.task_path());
+    args.push("/SC".to_string());
+    match task.frequency {
+        Frequency::Daily => args.push("DAILY".to_string()),
+        Frequency::Weekly => {
+            args.extend(["WEEKLY", "/D", "MON"].map(String::from));
+        }
+    }
+    args.extend([
+        "/ST".to_string(),
+        format!("{RUN_HOUR:02}:00"),
+        "/TR".to_string(),
+        command,
+    ]);
+    if task.scope == FontScope::System {
+        args.extend(["/RU", "SYSTEM"].map(String::from));
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weekly_cleanup() -> ScheduledTask {
+        ScheduledTask::cleanup(
+            PathBuf::from("/usr/local/bin/fontlift"),
+            FontScope::User,
+            Frequency::Weekly,
+            PathBuf::from("/Users/ada/Library/Application Support/FontLift/logs/cleanup.log"),
+        )
+    }
+
+    #[test]
+    fn launchd_plist_runs_cleanup_weekly_with_a_log() {
+        let task = weekly_cleanup();
+        let plist = launchd_plist(&task);
+
+        assert!(plist.contains("<string>com.fontlaborg.fontlift.schedule.cleanup</string>"));
+        assert!(plist.contains(
+            "        <string>/usr/local/bin/fontlift</string>\n        <string>cleanup</string>\n        <string>--quiet</string>\n        <string>--json</string>\n"
+        ));
+        assert!(plist.contains(
+            "<key>StandardOutPath</key>\n    <string>/Users/ada/Library/Application Support/FontLift/logs/cleanup.log</string>"
+        ));
+        assert_eq!(launchd_plist_frequency(&plist), Some(Frequency::Weekly));
+
+        let daily = ScheduledTask {
+            frequency: Frequency::Daily,
+            ..task
+        };
+        assert_eq!(
+            launchd_plist_frequency(&launchd_plist(&daily)),
+            Some(Frequency::Daily)
+        );
+    }
+
+    #[test]
+    fn schtasks_listing_keeps_system_tasks_and_the_users_own() {
+        let csv = concat!(
+            "\"\\Microsoft\\Windows\\Defrag\\ScheduledDefrag\",\"N/A\",\"Ready\"\n",
+            "\"\\fontlift\\system\\cleanup\",\"20/10/2026 10:00:00\",\"Ready\"\n",
+            "\"\\fontlift\\ada\\cleanup\",\"20/10/2026 10:00:00\",\"Ready\"\n",
+            "\"\\fontlift\\grace\\cleanup\",\"20/10/2026 10:00:00\",\"Ready\"\n",
+        );
+        let tasks = schtasks_task_infos(csv, "Ada");
+        assert_eq!(
+            tasks
+                .iter()
+                .map(|t| (t.name.as_str(), t.scope, t.location.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("cleanup", FontScope::System, r"\fontlift\system\cleanup"),
+                ("cleanup", FontScope::User, r"\fontlift\ada\cleanup"),
+            ]
+        );
+    }
+
+    #[test]
+    fn schtasks_arguments_redirect_output_and_run_system_tasks_as_system() {
+        let task = ScheduledTask::cleanup(
+            PathBuf::from(r"C:\Program Files\fontlift\fontlift.exe"),
+            FontScope::System,
+            Frequency::Weekly,
+            PathBuf::from(r"C:\ProgramData\FontLift\logs\cleanup.log"),
+        );
+        let args = schtasks_create_args(&task);
+
+        assert_eq!(
+            args,
+            vec![
+                "/Create",
+                "/F",
+                "/TN",
+                r"\fontlift\system\cleanup",
+                "/SC",
+                "WEEKLY",
+                "/D",
+                "MON",
+                "/ST",
+                "10:00",
+                "/TR",
+                r#"cmd.exe /c ""C:\Program Files\fontlift\fontlift.exe" cleanup --admin --quiet --json >> "C:\ProgramData\FontLift\logs\cleanup.log" 2>&1""#,
+                "/RU",
+                "SYSTEM",
+            ]
+        );
+    }
+}
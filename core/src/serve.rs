@@ -0,0 +1,814 @@
+//! `fontlift serve`: a long-lived process that answers requests over a local
+//! socket.
+//!
+//! GUI front ends and editor plugins that manage fonts would otherwise start
+//! a `fontlift` process per call, and every `list` would enumerate all
+//! installed fonts again. The daemon keeps one [`FontManager`] and one
+//! inventory snapshot alive and speaks newline-delimited JSON: one request
+//! object per line, one response line per request, in order.
+//!
+//! ```text
+//! → {"id": 1, "method": "list", "params": {"limit": 100, "query": "inter"}}
+//! ← {"id": 1, "result": {"snapshot": "…", "offset": 0, "total": 12, "faces": […]}}
+//! → {"id": 2, "method": "install", "params": {"path": "/tmp/Inter.ttf"}}
+//! ← {"id": 2, "error": {"code": "already_installed", "message": "…", "suggestion": "…"}}
+//! ```
+//!
+//! Methods:
+//!
+//! | Method | Params | Result |
+//! |---|---|---|
+//! | `ping` | – | `{"version"}` |
+//! | `list` | `offset`, `limit` (500), `query`, `cursor`, `refresh` | an [`InventoryPage`] |
+//! | `install` | `path`, `admin` | `{"path", "scope"}` |
+//! | `uninstall` | `path` or `name`, `admin` | `{"path", "scope"}` |
+//! | `cleanup` | `prune` (true), `cache` (true), `admin` | `{"pruned", "cache"}` |
+//! | `shutdown` | – | `{}`; the daemon stops after answering |
+//!
+//! `list` pages come from the snapshot taken by the first `list` call, and
+//! later calls reuse it until an `install`, `uninstall` or `cleanup` changes
+//! the inventory, or the request passes `"refresh": true`.
+//!
+//! `install` and `uninstall` go through [`crate::operations`], the steps
+//! `fontlift install` and `uninstall` take: the managed and protection
+//! policies apply, installs are validated, and every change, like every
+//! `cleanup`, is recorded in the install database and the history log.
+//!
+//! Only the user who started the daemon may talk to it. On Unix it listens
+//! on a socket in a directory only that user can enter, and the socket
+//! itself is `rw-------`. Windows has no Unix sockets in the standard
+//! library, so there it listens on loopback TCP and writes the port and a
+//! random token to an endpoint file in the user's profile; each connection
+//! must send `{"method": "auth", "params": {"token": "…"}}` first.
+
+use crate::history::HistoryOperation;
+use crate::inventory::{InventoryFilter, InventoryPage, InventoryPager, InventorySnapshot};
+use crate::{
+    cache, operations, policy, prune, FontError, FontManager, FontResult, FontScope,
+    FontliftFontSource,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Page size of `list` when the request sets none.
+pub const DEFAULT_PAGE_SIZE: usize = 500;
+
+/// One request line.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Request {
+    /// Echoed back in the response; any JSON value.
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// One response line: `result` on success, `error` otherwise.
+#[derive(Debug, Serialize)]
+pub struct Response {
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<FontError>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ListParams {
+    offset: usize,
+    limit: Option<usize>,
+    query: Option<String>,
+    cursor: Option<String>,
+    refresh: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TargetParams {
+    path: Option<PathBuf>,
+    name: Option<String>,
+    admin: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CleanupParams {
+    prune: bool,
+    cache: bool,
+    admin: bool,
+}
+
+impl Default for CleanupParams {
+    fn default() -> Self {
+        Self {
+            prune: true,
+            cache: true,
+            admin: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AuthParams {
+    token: String,
+}
+
+fn scope_for(admin: bool) -> FontScope {
+    if admin {
+        FontScope::System
+    } else {
+        FontScope::User
+    }
+}
+
+fn params<T: Default + for<'de> Deserialize<'de>>(request: &Request) -> FontResult<T> {
+    if request.params.is_null() {
+        return Ok(T::default());
+    }
+    serde_json::from_value(request.params.clone()).map_err(|e| {
+        FontError::InvalidArgument(format!("invalid params for '{}': {e}", request.method))
+    })
+}
+
+/// Answers requests for one [`FontManager`].
+pub struct Daemon {
+    manager: Arc<dyn FontManager>,
+    pager: InventoryPager,
+    /// The snapshot `list` pages from, until something changes the inventory.
+    current: Mutex<Option<Arc<InventorySnapshot>>>,
+    /// Serializes operations that change the inventory.
+    writes: Mutex<()>,
+    /// Required by `auth` before anything else, when set.
+    token: Option<String>,
+    stopping: AtomicBool,
+}
+
+impl Daemon {
+    pub fn new(manager: Arc<dyn FontManager>) -> Self {
+        Self {
+            manager,
+            pager: InventoryPager::default(),
+            current: Mutex::new(None),
+            writes: Mutex::new(()),
+            token: None,
+            stopping: AtomicBool::new(false),
+        }
+    }
+
+    /// Require every connection to authenticate with `token` first.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Whether a `shutdown` request was answered.
+    pub fn is_stopping(&self) -> bool {
+        self.stopping.load(Ordering::SeqCst)
+    }
+
+    /// Answer one request.
+    pub fn handle(&self, request: &Request) -> Response {
+        let outcome = self.dispatch(request);
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Response {
+            id: request.id.clone(),
+            result,
+            error,
+        }
+    }
+
+    fn dispatch(&self, request: &Request) -> FontResult<Value> {
+        match request.method.as_str() {
+            "ping" => Ok(json!({ "version": env!("CARGO_PKG_VERSION") })),
+            "auth" => Ok(json!({})),
+            "list" => to_value(self.list(params(request)?)?),
+            "install" => self.install(params(request)?),
+            "uninstall" => self.uninstall(params(request)?),
+            "cleanup" => self.cleanup(params(request)?),
+            "shutdown" => {
+                self.stopping.store(true, Ordering::SeqCst);
+                Ok(json!({}))
+            }
+            other => Err(FontError::UnsupportedOperation(format!(
+                "unknown method '{other}'"
+            ))),
+        }
+    }
+
+    fn list(&self, params: ListParams) -> FontResult<InventoryPage> {
+        let filter = InventoryFilter {
+            query: params.query,
+        };
+        let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if let Some(cursor) = params.cursor {
+            return self.pager.resume(&cursor, limit, &filter);
+        }
+        Ok(self
+            .snapshot(params.refresh)?
+            .page(params.offset, limit, &filter))
+    }
+
+    /// The current snapshot, taking a new one if there is none or `refresh`.
+    fn snapshot(&self, refresh: bool) -> FontResult<Arc<InventorySnapshot>> {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        match current.as_ref() {
+            Some(snapshot) if !refresh => Ok(snapshot.clone()),
+            _ => {
+                let faces = self.manager.list_installed_fonts()?;
+                let snapshot = self.pager.insert(InventorySnapshot::new(faces));
+                *current = Some(snapshot.clone());
+                Ok(snapshot)
+            }
+        }
+    }
+
+    /// Forget the current snapshot; open cursors keep working.
+    fn invalidate(&self) {
+        *self.current.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    fn install(&self, params: TargetParams) -> FontResult<Value> {
+        let path = params.path.ok_or_else(|| {
+            FontError::InvalidArgument("install needs a 'path' parameter".to_string())
+        })?;
+        let scope = scope_for(params.admin);
+        let _write = self.writes.lock().unwrap_or_else(|e| e.into_inner());
+        let source = FontliftFontSource::new(path.clone()).with_scope(Some(scope));
+        let result = operations::install(self.manager.as_ref(), &source);
+        self.invalidate();
+        result?;
+        Ok(json!({ "path": path, "scope": scope }))
+    }
+
+    fn uninstall(&self, params: TargetParams) -> FontResult<Value> {
+        let (path, scope) = match (params.path, params.name) {
+            (Some(path), None) => (path, scope_for(params.admin)),
+            (None, Some(name)) => self.find_by_name(&name, scope_for(params.admin))?,
+            _ => {
+                return Err(FontError::InvalidArgument(
+                    "uninstall needs exactly one of 'path' or 'name'".to_string(),
+                ))
+            }
+        };
+        let _write = self.writes.lock().unwrap_or_else(|e| e.into_inner());
+        let source = FontliftFontSource::new(path.clone()).with_scope(Some(scope));
+        let result = operations::uninstall(self.manager.as_ref(), &source);
+        self.invalidate();
+        result?;
+        Ok(json!({ "path": path, "scope": scope }))
+    }
+
+    /// Path and scope of the installed face whose PostScript or full name
+    /// is `name`.
+    fn find_by_name(
+        &self,
+        name: &str,
+        default_scope: FontScope,
+    ) -> FontResult<(PathBuf, FontScope)> {
+        let snapshot = self.snapshot(false)?;
+        snapshot
+            .page(0, 0, &InventoryFilter::query(name))
+            .faces
+            .into_iter()
+            .find(|face| face.postscript_name == name || face.full_name == name)
+            .map(|face| {
+                let scope = face.source.scope.unwrap_or(default_scope);
+                (face.source.path, scope)
+            })
+            .ok_or_else(|| FontError::FontNotFound(PathBuf::from(name)))
+    }
+
+    fn cleanup(&self, params: CleanupParams) -> FontResult<Value> {
+        if !params.prune && !params.cache {
+            return Err(FontError::InvalidArgument(
+                "cleanup needs at least one of 'prune' or 'cache'".to_string(),
+            ));
+        }
+        let scope = scope_for(params.admin);
+        let _write = self.writes.lock().unwrap_or_else(|e| e.into_inner());
+        let mut pruned = 0;
+        let mut cleared = None;
+        operations::recorded(HistoryOperation::Cleanup, scope, |history| {
+            policy::check_managed_scope(scope, "cleanup")?;
+            if params.prune {
+                let report = self.manager.prune_stale_registrations(
+                    scope,
+                    &prune::PruneOptions::default(),
+                    &mut |_| {},
+                );
+                self.invalidate();
+                let report = report?;
+                for path in report
+                    .removed
+                    .iter()
+                    .filter_map(|entry| entry.path.as_ref())
+                {
+                    history.handled(path);
+                }
+                pruned = report.removed.len();
+            }
+            if params.cache {
+                let strategy = cache::CacheClearStrategy::for_scope(scope);
+                cleared = Some(self.manager.clear_font_caches(strategy)?);
+            }
+            Ok(())
+        })?;
+        Ok(json!({ "pruned": pruned, "cache": cleared }))
+    }
+
+    /// Serve one connection until it closes or the daemon stops.
+    pub fn serve_connection<R: BufRead, W: Write>(
+        &self,
+        reader: R,
+        mut writer: W,
+    ) -> std::io::Result<()> {
+        let mut authenticated = self.token.is_none();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str::<Request>(&line) {
+                Err(e) => Response {
+                    id: Value::Null,
+                    result: None,
+                    error: Some(FontError::InvalidArgument(format!("invalid request: {e}"))),
+                },
+                Ok(request) if !authenticated => {
+                    authenticated = request.method == "auth"
+                        && params::<AuthParams>(&request)
+                            .is_ok_and(|p| Some(p.token.as_str()) == self.token.as_deref());
+                    let (result, error) = if authenticated {
+                        (Some(json!({})), None)
+                    } else {
+                        let refusal = FontError::PermissionDenied(
+                            "authenticate with 'auth' and the token from the endpoint file first"
+                                .to_string(),
+                        );
+                        (None, Some(refusal))
+                    };
+                    Response {
+                        id: request.id,
+                        result,
+                        error,
+                    }
+                }
+                Ok(request) => self.handle(&request),
+            };
+            let mut text = serde_json::to_string(&response)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            text.push('\n');
+            writer.write_all(text.as_bytes())?;
+            writer.flush()?;
+            if self.is_stopping() || !authenticated {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn to_value<T: Serialize>(value: T) -> FontResult<Value> {
+    serde_json::to_value(value)
+        .map_err(|e| FontError::InvalidFormat(format!("cannot encode result: {e}")))
+}
+
+/// Where `fontlift serve` listens by default: next to the journal.
+pub fn default_socket_path() -> PathBuf {
+    crate::journal::journal_path().with_file_name("fontlift.sock")
+}
+
+/// Where `fontlift serve --tcp` writes its port and token by default.
+pub fn default_endpoint_path() -> PathBuf {
+    crate::journal::journal_path().with_file_name("serve-endpoint.json")
+}
+
+/// Accept connections on `socket` until a `shutdown` request, one thread
+/// per connection. The socket's directory is created `rwx------` and the
+/// socket `rw-------`; a stale socket file from a dead daemon is replaced.
+#[cfg(unix)]
+pub fn serve_unix(daemon: Arc<Daemon>, socket: &Path) -> FontResult<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if let Some(dir) = socket.parent() {
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)
+            .map_err(FontError::IoError)?;
+    }
+    if UnixStream::connect(socket).is_ok() {
+        return Err(FontError::IoError(std::io::Error::new(
+            std::io::ErrorKind::AddrInUse,
+            format!("another daemon is listening on {}", socket.display()),
+        )));
+    }
+    let _ = std::fs::remove_file(socket);
+    let listener = UnixListener::bind(socket).map_err(FontError::IoError)?;
+    std::fs::set_permissions(socket, std::fs::Permissions::from_mode(0o600))
+        .map_err(FontError::IoError)?;
+
+    let wake = socket.to_path_buf();
+    let result = accept_loop(&daemon, listener.incoming(), move || {
+        let _ = UnixStream::connect(&wake);
+    });
+    let _ = std::fs::remove_file(socket);
+    result
+}
+
+/// Accept loopback TCP connections until a `shutdown` request. Writes
+/// `{"port", "token"}` to `endpoint` (`rw-------` on Unix) and removes it
+/// again on exit; every connection must authenticate with the token.
+pub fn serve_tcp(manager: Arc<dyn FontManager>, endpoint: &Path) -> FontResult<()> {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).map_err(FontError::IoError)?;
+    let port = listener.local_addr().map_err(FontError::IoError)?.port();
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    write_endpoint_file(endpoint, port, &token)?;
+
+    let daemon = Arc::new(Daemon::new(manager).with_token(token));
+    let result = accept_loop(&daemon, listener.incoming(), move || {
+        let _ = TcpStream::connect(("127.0.0.1", port));
+    });
+    let _ = std::fs::remove_file(endpoint);
+    result
+}
+
+fn write_endpoint_file(endpoint: &Path, port: u16, token: &str) -> FontResult<()> {
+    if let Some(dir) = endpoint.parent() {
+        std::fs::create_dir_all(dir).map_err(FontError::IoError)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(endpoint).map_err(FontError::IoError)?;
+    let text = json!({ "port": port, "token": token }).to_string();
+    file.write_all(text.as_bytes()).map_err(FontError::IoError)
+}
+
+/// Hand each connection to a thread. `wake` connects to the listener so a
+/// blocked `accept` notices that a connection thread asked to stop.
+fn accept_loop<S, I>(
+    daemon: &Arc<Daemon>,
+    incoming: I,
+    wake: impl Fn() + Send + Sync + 'static,
+) -> FontResult<()>
+where
+    S: std::io::Read + Write + TryClone + Send + 'static,
+    I: Iterator<Item = std::io::Result<S>>,
+{
+    let wake = Arc::new(wake);
+    for stream in incoming {
+        if daemon.is_stopping() {
+            break;
+        }
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::warn!("Could not accept connection: {e}");
+                continue;
+            }
+        };
+        let daemon = daemon.clone();
+        let wake = wake.clone();
+        std::thread::spawn(move || {
+            let reader = match stream.try_clone() {
+                Ok(reader) => BufReader::new(reader),
+                Err(e) => return log::warn!("Could not read from connection: {e}"),
+            };
+            if let Err(e) = daemon.serve_connection(reader, stream) {
+                log::debug!("Connection closed: {e}");
+            }
+            if daemon.is_stopping() {
+                wake();
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Streams that can be split into a reader and a writer.
+trait TryClone: Sized {
+    fn try_clone(&self) -> std::io::Result<Self>;
+}
+
+impl TryClone for TcpStream {
+    fn try_clone(&self) -> std::io::Result<Self> {
+        TcpStream::try_clone(self)
+    }
+}
+
+#[cfg(unix)]
+impl TryClone for std::os::unix::net::UnixStream {
+    fn try_clone(&self) -> std::io::Result<Self> {
+        std::os::unix::net::UnixStream::try_clone(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{history, install_db, FontliftFontFaceInfo, FontliftFontSource};
+    use fontlift_testfonts::TestFont;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::OnceLock;
+
+    #[derive(Default)]
+    struct Registry {
+        fonts: Mutex<Vec<PathBuf>>,
+        listings: AtomicUsize,
+    }
+
+    impl FontManager for Registry {
+        fn install_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+            self.fonts.lock().unwrap().push(source.path.clone());
+            Ok(())
+        }
+        fn uninstall_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+            let mut fonts = self.fonts.lock().unwrap();
+            let before = fonts.len();
+            fonts.retain(|p| p != &source.path);
+            if fonts.len() == before {
+                return Err(FontError::FontNotFound(source.path.clone()));
+            }
+            Ok(())
+        }
+        fn remove_font(&self, _source: &FontliftFontSource) -> FontResult<()> {
+            unreachable!()
+        }
+        fn is_font_installed(&self, source: &FontliftFontSource) -> FontResult<bool> {
+            Ok(self.fonts.lock().unwrap().contains(&source.path))
+        }
+        fn list_installed_fonts(&self) -> FontResult<Vec<FontliftFontFaceInfo>> {
+            self.listings.fetch_add(1, Ordering::SeqCst);
+            Ok(self
+                .fonts
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|p| crate::validation::extract_basic_info_from_path(p))
+                .collect())
+        }
+        fn clear_font_caches(
+            &self,
+            _: crate::cache::CacheClearStrategy,
+        ) -> FontResult<crate::cache::CacheClearResult> {
+            Ok(crate::cache::CacheClearResult::default())
+        }
+    }
+
+    /// Where the daemon's install database, history and validation cache
+    /// go, shared by every test since the variables are process-wide.
+    fn records_dir() -> &'static Path {
+        static DIR: OnceLock<tempfile::TempDir> = OnceLock::new();
+        DIR.get_or_init(|| {
+            let dir = tempfile::tempdir().unwrap();
+            std::env::set_var(
+                "FONTLIFT_INSTALL_DB_PATH",
+                dir.path().join("installed.json"),
+            );
+            std::env::set_var("FONTLIFT_HISTORY_PATH", dir.path().join("history.jsonl"));
+            std::env::set_var(
+                "FONTLIFT_VALIDATION_CACHE_PATH",
+                dir.path().join("validation-cache.json"),
+            );
+            dir
+        })
+        .path()
+    }
+
+    /// Write `family` Regular into `dir` and return an `install` request
+    /// for it.
+    fn install_request(dir: &Path, id: u32, family: &str) -> (PathBuf, String) {
+        let path = TestFont::new(family).write_to(dir).unwrap();
+        let request = json!({"id": id, "method": "install", "params": {"path": path}});
+        (path, request.to_string())
+    }
+
+    fn converse(daemon: &Daemon, requests: &[&str]) -> Vec<Value> {
+        records_dir();
+        let input = requests.join("\n");
+        let mut output = Vec::new();
+        daemon
+            .serve_connection(input.as_bytes(), &mut output)
+            .unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn lists_from_one_snapshot_until_the_inventory_changes() {
+        let fonts = tempfile::tempdir().unwrap();
+        let (alpha, install_alpha) = install_request(fonts.path(), 1, "Alpha");
+        let (_, install_beta) = install_request(fonts.path(), 2, "Beta");
+        let registry = Arc::new(Registry::default());
+        let daemon = Daemon::new(registry.clone());
+        let replies = converse(
+            &daemon,
+            &[
+                &install_alpha,
+                &install_beta,
+                r#"{"id": 3, "method": "list", "params": {"limit": 1}}"#,
+                r#"{"id": 4, "method": "list", "params": {"query": "beta"}}"#,
+                r#"{"id": 5, "method": "uninstall", "params": {"name": "Alpha-Regular"}}"#,
+                r#"{"id": 6, "method": "list"}"#,
+            ],
+        );
+        assert_eq!(replies[1]["result"]["scope"], "User");
+        assert_eq!(replies[2]["id"], 3);
+        assert_eq!(replies[2]["result"]["total"], 2);
+        assert!(replies[2]["result"]["next_cursor"].is_string());
+        assert_eq!(
+            replies[3]["result"]["faces"][0]["postscript_name"],
+            "Beta-Regular"
+        );
+        assert_eq!(replies[4]["result"]["path"], json!(alpha));
+        assert_eq!(replies[5]["result"]["total"], 1);
+        // id 3 and 4 shared a snapshot; the uninstall invalidated it.
+        assert_eq!(registry.listings.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn errors_are_structured_and_shutdown_ends_the_connection() {
+        let daemon = Daemon::new(Arc::new(Registry::default()));
+        let replies = converse(
+            &daemon,
+            &[
+                "not json",
+                r#"{"id": "a", "method": "explode"}"#,
+                r#"{"id": "b", "method": "uninstall", "params": {"path": "/f/Nope.ttf"}}"#,
+                r#"{"id": "c", "method": "list", "params": {"bogus": 1}}"#,
+                r#"{"id": "d", "method": "shutdown"}"#,
+                r#"{"id": "e", "method": "ping"}"#,
+            ],
+        );
+        let codes: Vec<&Value> = replies.iter().map(|r| &r["error"]["code"]).collect();
+        assert_eq!(
+            codes[..4],
+            [
+                "invalid_argument",
+                "unsupported_operation",
+                "font_not_found",
+                "invalid_argument"
+            ]
+        );
+        assert_eq!(replies.len(), 5, "nothing is answered after shutdown");
+        assert!(daemon.is_stopping());
+    }
+
+    #[test]
+    fn managed_policy_refuses_locked_scopes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let policy_path = tmp.path().join("policy.json");
+        std::fs::write(&policy_path, r#"{"allow_system_scope": false}"#).unwrap();
+        let (alpha, install) = install_request(tmp.path(), 4, "Alpha");
+        let admin = |id: u32, method: &str| {
+            json!({"id": id, "method": method, "params": {"path": alpha, "admin": true}})
+                .to_string()
+        };
+        let (install_admin, uninstall_admin) = (admin(1, "install"), admin(2, "uninstall"));
+
+        std::env::set_var("FONTLIFT_POLICY_PATH", &policy_path);
+        let registry = Arc::new(Registry::default());
+        let daemon = Daemon::new(registry.clone());
+        let replies = converse(
+            &daemon,
+            &[
+                &install_admin,
+                &uninstall_admin,
+                r#"{"id": 3, "method": "cleanup", "params": {"admin": true}}"#,
+                &install,
+            ],
+        );
+        std::env::remove_var("FONTLIFT_POLICY_PATH");
+
+        let codes: Vec<&Value> = replies[..3].iter().map(|r| &r["error"]["code"]).collect();
+        assert_eq!(codes, ["policy_denied"; 3]);
+        assert_eq!(replies[3]["result"]["scope"], "User");
+        assert_eq!(*registry.fonts.lock().unwrap(), [alpha]);
+    }
+
+    #[test]
+    fn changes_are_protected_and_recorded_like_the_cli() {
+        let tmp = tempfile::tempdir().unwrap();
+        let fonts = tmp.path().join("fonts");
+        std::fs::create_dir(&fonts).unwrap();
+        let (gamma, install) = install_request(&fonts, 1, "Gamma");
+        let uninstall = json!({"id": 2, "method": "uninstall", "params": {"path": gamma}});
+        let daemon = Daemon::new(Arc::new(Registry::default()));
+        let replies = converse(&daemon, &[&install]);
+        assert_eq!(replies[0]["result"]["path"], json!(gamma));
+
+        let db = install_db::load_install_db().unwrap();
+        let record = db.find(&gamma).expect("the install is recorded");
+        assert!(record.reason.is_explicit());
+        assert!(record.sha256.is_some());
+
+        let protection = tmp.path().join("protection.json");
+        std::fs::write(&protection, json!({"protected_dirs": [fonts]}).to_string()).unwrap();
+        std::env::set_var("FONTLIFT_PROTECTION_POLICY_PATH", &protection);
+        let refused = converse(&daemon, &[&uninstall.to_string()]);
+        std::env::remove_var("FONTLIFT_PROTECTION_POLICY_PATH");
+        assert_eq!(refused[0]["error"]["code"], "system_font_protection");
+
+        let replies = converse(&daemon, &[&uninstall.to_string()]);
+        assert!(replies[0]["result"].is_object());
+        let db = install_db::load_install_db().unwrap();
+        assert!(db.find(&gamma).is_none(), "the record is forgotten");
+
+        let outcomes: Vec<String> = history::load_history()
+            .unwrap()
+            .into_iter()
+            .filter(|entry| {
+                entry.files.contains(&gamma) || entry.failed.iter().any(|f| f.path == gamma)
+            })
+            .map(|entry| format!("{} {:?}", entry.operation.as_str(), entry.outcome))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                "install Succeeded",
+                "uninstall Failed",
+                "uninstall Succeeded"
+            ]
+        );
+    }
+
+    #[test]
+    fn token_is_required_before_anything_else() {
+        let daemon = Daemon::new(Arc::new(Registry::default())).with_token("secret");
+        let refused = converse(
+            &daemon,
+            &[
+                r#"{"id": 1, "method": "ping"}"#,
+                r#"{"id": 2, "method": "ping"}"#,
+            ],
+        );
+        assert_eq!(refused.len(), 1);
+        assert_eq!(refused[0]["error"]["code"], "permission_denied");
+
+        let accepted = converse(
+            &daemon,
+            &[
+                r#"{"id": 1, "method": "auth", "params": {"token": "secret"}}"#,
+                r#"{"id": 2, "method": "ping"}"#,
+            ],
+        );
+        assert!(accepted[0]["error"].is_null());
+        assert!(accepted[1]["result"]["version"].is_string());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_socket_round_trip() {
+        use std::os::unix::fs::PermissionsExt;
+        use std::os::unix::net::UnixStream;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let socket = tmp.path().join("run/fontlift.sock");
+        let daemon = Arc::new(Daemon::new(Arc::new(Registry::default())));
+        let server = {
+            let socket = socket.clone();
+            std::thread::spawn(move || serve_unix(daemon, &socket))
+        };
+        let stream = (0..200)
+            .find_map(|_| {
+                UnixStream::connect(&socket).ok().or_else(|| {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                    None
+                })
+            })
+            .expect("daemon listens");
+        let mode = std::fs::metadata(&socket).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let mut writer = stream.try_clone().unwrap();
+        writer
+            .write_all(
+                b"{\"id\": 1, \"method\": \"ping\"}\n{\"id\": 2, \"method\": \"shutdown\"}\n",
+            )
+            .unwrap();
+        let lines: Vec<String> = BufReader::new(stream).lines().map(Result::unwrap).collect();
+        assert_eq!(lines.len(), 2);
+        server.join().unwrap().unwrap();
+        assert!(!socket.exists());
+    }
+}
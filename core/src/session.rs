@@ -0,0 +1,296 @@
+//! Fonts installed for a moment, then removed again.
+//!
+//! Rendering and test pipelines often need a font loaded only while they
+//! run. [`TemporaryFont`] registers the file for the login session
+//! ([`FontManager::install_font_session`]: nothing copied, nothing
+//! persistent) and unregisters it when dropped:
+//!
+//! ```no_run
+//! # use fontlift_core::{session::TemporaryFont, FontManager};
+//! # use std::sync::Arc;
+//! # fn render(manager: Arc<dyn FontManager>) -> fontlift_core::FontResult<()> {
+//! let font = TemporaryFont::install(manager, "Specimen.ttf".as_ref())?;
+//! // ... render with the font ...
+//! font.uninstall()?; // or just drop it
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! If the process dies before that, the registration still goes away at
+//! logout, because session registrations never outlive the session.
+//!
+//! Fonts fetched from a server for a preview need not be written to a temp
+//! file first: [`TemporaryFont::install_bytes`] registers the bytes through
+//! [`FontManager::register_font_bytes`] (`AddFontMemResourceEx` on Windows)
+//! and is released the same way. Such fonts are private to the process,
+//! as are files registered with [`TemporaryFont::install_private`].
+
+use crate::memory::{FontHandle, MemoryFontOptions};
+use crate::{FontManager, FontResult, FontliftFontSource};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// What a [`TemporaryFont`] registered.
+enum Registration {
+    File(FontliftFontSource),
+    Memory(FontHandle),
+    Private(PathBuf, FontHandle),
+}
+
+/// A session-scope font registration, undone on drop.
+pub struct TemporaryFont {
+    manager: Arc<dyn FontManager>,
+    registration: Registration,
+    active: bool,
+}
+
+impl TemporaryFont {
+    /// Register `path` for the current session. The file must stay where it
+    /// is until the font is uninstalled.
+    pub fn install(manager: Arc<dyn FontManager>, path: &Path) -> FontResult<Self> {
+        let source = FontliftFontSource::new(path.to_path_buf());
+        manager.install_font_session(&source)?;
+        Ok(Self {
+            manager,
+            registration: Registration::File(source),
+            active: true,
+        })
+    }
+
+    /// Register font `data` for this process, with no file on disk.
+    pub fn install_bytes(
+        manager: Arc<dyn FontManager>,
+        data: &[u8],
+        options: &MemoryFontOptions,
+    ) -> FontResult<Self> {
+        let handle = manager.register_font_bytes(data, options)?;
+        Ok(Self {
+            manager,
+            registration: Registration::Memory(handle),
+            active: true,
+        })
+    }
+
+    /// Register `path` for this process only, through
+    /// [`FontManager::register_font_private`].
+    pub fn install_private(manager: Arc<dyn FontManager>, path: &Path) -> FontResult<Self> {
+        let handle = manager.register_font_private(&FontliftFontSource::new(path.to_path_buf()))?;
+        Ok(Self {
+            manager,
+            registration: Registration::Private(path.to_path_buf(), handle),
+            active: true,
+        })
+    }
+
+    /// The registered file; `None` for fonts registered from bytes.
+    pub fn path(&self) -> Option<&Path> {
+        match &self.registration {
+            Registration::File(source) => Some(&source.path),
+            Registration::Private(path, _) => Some(path),
+            Registration::Memory(_) => None,
+        }
+    }
+
+    /// PostScript names of the faces the OS loaded, for fonts registered
+    /// privately or from bytes; empty for session registrations.
+    pub fn postscript_names(&self) -> &[String] {
+        match &self.registration {
+            Registration::File(_) => &[],
+            Registration::Memory(handle) | Registration::Private(_, handle) => {
+                handle.postscript_names()
+            }
+        }
+    }
+
+    /// The path, or the label of fonts registered from bytes, for messages.
+    pub fn label(&self) -> String {
+        match &self.registration {
+            Registration::File(source) => source.path.display().to_string(),
+            Registration::Private(path, _) => path.display().to_string(),
+            Registration::Memory(handle) => handle.label().to_string(),
+        }
+    }
+
+    /// Whether the font is still registered by this guard.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Unregister now and report failures, which dropping cannot do.
+    pub fn uninstall(mut self) -> FontResult<()> {
+        self.release()
+    }
+
+    /// Unregister if still registered; later calls do nothing.
+    pub fn release(&mut self) -> FontResult<()> {
+        if !self.active {
+            return Ok(());
+        }
+        self.active = false;
+        match &mut self.registration {
+            Registration::File(source) => self.manager.uninstall_font_session(source),
+            Registration::Memory(handle) | Registration::Private(_, handle) => handle.release(),
+        }
+    }
+}
+
+impl Drop for TemporaryFont {
+    fn drop(&mut self) {
+        if let Err(e) = self.release() {
+            log::warn!(
+                "Could not unregister temporary font {}: {}",
+                self.label(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryRegistration;
+    use crate::{FontError, FontliftFontFaceInfo};
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct SessionRecorder {
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    struct RecordedMemoryFont(Arc<Mutex<Vec<String>>>);
+
+    impl MemoryRegistration for RecordedMemoryFont {
+        fn unregister(&mut self) -> FontResult<()> {
+            self.0.lock().unwrap().push("unregister bytes".to_string());
+            Ok(())
+        }
+    }
+
+    impl SessionRecorder {
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl FontManager for SessionRecorder {
+        fn install_font(&self, _source: &FontliftFontSource) -> FontResult<()> {
+            unreachable!()
+        }
+        fn uninstall_font(&self, _source: &FontliftFontSource) -> FontResult<()> {
+            unreachable!()
+        }
+        fn remove_font(&self, _source: &FontliftFontSource) -> FontResult<()> {
+            unreachable!()
+        }
+        fn is_font_installed(&self, _source: &FontliftFontSource) -> FontResult<bool> {
+            Ok(false)
+        }
+        fn list_installed_fonts(&self) -> FontResult<Vec<FontliftFontFaceInfo>> {
+            Ok(Vec::new())
+        }
+        fn clear_font_caches(
+            &self,
+            _: crate::cache::CacheClearStrategy,
+        ) -> FontResult<crate::cache::CacheClearResult> {
+            Ok(crate::cache::CacheClearResult::default())
+        }
+        fn install_font_session(&self, source: &FontliftFontSource) -> FontResult<()> {
+            if source.path.ends_with("Broken.ttf") {
+                return Err(FontError::InvalidFormat("broken".to_string()));
+            }
+            let call = format!("install {}", source.path.display());
+            self.calls.lock().unwrap().push(call);
+            Ok(())
+        }
+        fn uninstall_font_session(&self, source: &FontliftFontSource) -> FontResult<()> {
+            let call = format!("uninstall {}", source.path.display());
+            self.calls.lock().unwrap().push(call);
+            Ok(())
+        }
+        fn register_font_bytes(
+            &self,
+            data: &[u8],
+            options: &MemoryFontOptions,
+        ) -> FontResult<FontHandle> {
+            let call = format!("register {} bytes", data.len());
+            self.calls.lock().unwrap().push(call);
+            Ok(FontHandle::new(
+                Box::new(RecordedMemoryFont(self.calls.clone())),
+                Vec::new(),
+                options.display_name(),
+            ))
+        }
+    }
+
+    #[test]
+    fn uninstalls_once_on_release_or_drop() {
+        let recorder = Arc::new(SessionRecorder::default());
+        let manager: Arc<dyn FontManager> = recorder.clone();
+
+        let mut font = TemporaryFont::install(manager.clone(), Path::new("/t/A.ttf")).unwrap();
+        assert_eq!(font.path(), Some(PathBuf::from("/t/A.ttf").as_path()));
+        font.release().unwrap();
+        assert!(!font.is_active());
+        drop(font);
+
+        drop(TemporaryFont::install(manager.clone(), Path::new("/t/B.ttf")).unwrap());
+        assert!(TemporaryFont::install(manager, Path::new("/t/Broken.ttf")).is_err());
+
+        assert_eq!(
+            recorder.calls(),
+            vec![
+                "install /t/A.ttf",
+                "uninstall /t/A.ttf",
+                "install /t/B.ttf",
+                "uninstall /t/B.ttf",
+            ]
+        );
+    }
+
+    #[test]
+    fn fonts_from_bytes_are_released_like_files() {
+        let recorder = Arc::new(SessionRecorder::default());
+        let manager: Arc<dyn FontManager> = recorder.clone();
+        let options = MemoryFontOptions::default().with_label("preview.otf");
+
+        let font = TemporaryFont::install_bytes(manager.clone(), b"font", &options).unwrap();
+        assert_eq!(font.path(), None);
+        assert_eq!(font.label(), "preview.otf");
+        font.uninstall().unwrap();
+        drop(TemporaryFont::install_bytes(manager, b"font", &options).unwrap());
+
+        assert_eq!(
+            recorder.calls(),
+            vec![
+                "register 4 bytes",
+                "unregister bytes",
+                "register 4 bytes",
+                "unregister bytes",
+            ]
+        );
+    }
+
+    #[test]
+    fn private_files_go_through_the_bytes_path_by_default() {
+        let recorder = Arc::new(SessionRecorder::default());
+        let manager: Arc<dyn FontManager> = recorder.clone();
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("Preview.ttf");
+        std::fs::write(&path, b"fontdata").unwrap();
+
+        let font = TemporaryFont::install_private(manager.clone(), &path).unwrap();
+        assert_eq!(font.path(), Some(path.as_path()));
+        font.uninstall().unwrap();
+        assert!(matches!(
+            TemporaryFont::install_private(manager, &tmp.path().join("Missing.ttf")),
+            Err(FontError::FontNotFound(_))
+        ));
+
+        assert_eq!(
+            recorder.calls(),
+            vec!["register 8 bytes", "unregister bytes"]
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! Ready-to-paste names for an installed face (`fontlift copy-name`).
+//!
+//! Designers and developers constantly retype font names into stylesheets,
+//! scripts and design tools, and each place wants a different name:
+//!
+//! | Snippet | Example | Used by |
+//! |---|---|---|
+//! | CSS | `font-family: "Atkinson Hyperlegible", sans-serif;` | stylesheets |
+//! | PostScript | `AtkinsonHyperlegible-Bold` | InDesign/Illustrator scripts, `CTFontCreateWithName` |
+//! | Family | `Atkinson Hyperlegible` | font menus, `font-family` lists |
+//! | Full name | `Atkinson Hyperlegible Bold` | Word, PowerPoint, `@font-face { src: local() }` |
+//!
+//! [`resolve_face`] finds the face from whatever name the user typed;
+//! [`name_snippets`] builds every snippet for it.
+
+use crate::pairing::{self, Classification};
+use crate::{FontError, FontResult, FontliftFontFaceInfo};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Which snippet to produce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetKind {
+    Css,
+    PostScript,
+    Family,
+    FullName,
+}
+
+/// Every snippet for one face.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameSnippets {
+    pub postscript_name: String,
+    pub family: String,
+    pub full_name: String,
+    /// `font-family` with a generic fallback, plus `font-weight` and
+    /// `font-style` when known.
+    pub css: String,
+}
+
+impl NameSnippets {
+    pub fn get(&self, kind: SnippetKind) -> &str {
+        match kind {
+            SnippetKind::Css => &self.css,
+            SnippetKind::PostScript => &self.postscript_name,
+            SnippetKind::Family => &self.family,
+            SnippetKind::FullName => &self.full_name,
+        }
+    }
+}
+
+/// Case-insensitive, with runs of whitespace collapsed.
+fn normalize(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Find the installed face `query` names.
+///
+/// Tried in order: PostScript name, full name, "family style", and finally
+/// the family name alone, which picks the family's upright regular face.
+/// Fails with [`FontError::FontNotFound`] when nothing matches.
+pub fn resolve_face<'a>(
+    installed: &'a [FontliftFontFaceInfo],
+    query: &str,
+) -> FontResult<&'a FontliftFontFaceInfo> {
+    let wanted = normalize(query);
+
+    let exact = installed
+        .iter()
+        .find(|face| face.postscript_name == query)
+        .or_else(|| {
+            installed
+                .iter()
+                .find(|face| normalize(&face.postscript_name) == wanted)
+        })
+        .or_else(|| {
+            installed
+                .iter()
+                .find(|face| normalize(&face.full_name) == wanted)
+        })
+        .or_else(|| {
+            installed
+                .iter()
+                .find(|face| normalize(&format!("{} {}", face.family_name, face.style)) == wanted)
+        });
+    if let Some(face) = exact {
+        return Ok(face);
+    }
+
+    installed
+        .iter()
+        .filter(|face| normalize(&face.family_name) == wanted)
+        .min_by_key(|face| {
+            let italic = face
+                .italic
+                .unwrap_or_else(|| normalize(&face.style).contains("italic"));
+            let weight = face.weight.unwrap_or(400);
+            (italic, weight.abs_diff(400))
+        })
+        .ok_or_else(|| FontError::FontNotFound(PathBuf::from(query)))
+}
+
+/// The CSS generic family to fall back on for `classification`.
+pub fn css_generic_family(classification: Classification) -> &'static str {
+    match classification {
+        Classification::Serif | Classification::SlabSerif => "serif",
+        Classification::Monospace => "monospace",
+        Classification::Script => "cursive",
+        Classification::Sans
+        | Classification::Display
+        | Classification::Symbol
+        | Classification::Unknown => "sans-serif",
+    }
+}
+
+fn css_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Build every snippet for `face`.
+///
+/// The face file is read for its classification (for the CSS fallback); an
+/// unreadable file falls back to `sans-serif`.
+pub fn name_snippets(face: &FontliftFontFaceInfo) -> NameSnippets {
+    let classification = pairing::read_face_traits(
+        &face.source.path,
+        face.source.face_index.unwrap_or(0),
+        &face.family_name,
+    )
+    .map(|traits| traits.classification)
+    .unwrap_or(Classification::Unknown);
+
+    let mut css = format!(
+        "font-family: {}, {};",
+        css_string(&face.family_name),
+        css_generic_family(classification)
+    );
+    if let Some(weight) = face.weight {
+        css.push_str(&format!("\nfont-weight: {weight};"));
+    }
+    if let Some(italic) = face.italic {
+        css.push_str(if italic {
+            "\nfont-style: italic;"
+        } else {
+            "\nfont-style: normal;"
+        });
+    }
+
+    NameSnippets {
+        postscript_name: face.postscript_name.clone(),
+        family: face.family_name.clone(),
+        full_name: face.full_name.clone(),
+        css,
+    }
+}
+
+/// Put `text` on the system clipboard.
+///
+/// Uses `pbcopy` on macOS, PowerShell's `Set-Clipboard` on Windows, and
+/// `wl-copy` or `xclip` elsewhere.
+pub fn copy_to_clipboard(text: &str) -> FontResult<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("pbcopy");
+
+    // Passed as an argument rather than through stdin: arguments reach
+    // PowerShell as UTF-16, so non-ASCII names survive.
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("powershell");
+        command
+            .args(["-NoProfile", "-NonInteractive", "-Command"])
+            .arg(format!(
+                "Set-Clipboard -Value '{}'",
+                text.replace('\'', "''")
+            ));
+        command
+    };
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let mut command = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        Command::new("wl-copy")
+    } else {
+        let mut command = Command::new("xclip");
+        command.args(["-selection", "clipboard"]);
+        command
+    };
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(FontError::IoError)?;
+    if !cfg!(windows) {
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .map_err(FontError::IoError)?;
+        }
+    }
+    // Close stdin so the clipboard tool sees end of input.
+    drop(child.stdin.take());
+
+    let status = child.wait().map_err(FontError::IoError)?;
+    if !status.success() {
+        return Err(FontError::UnsupportedOperation(
+            "The clipboard tool exited with an error".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata;
+    use fontlift_testfonts::TestFont;
+
+    #[test]
+    fn resolves_postscript_full_and_family_names() {
+        let tmp = tempfile::tempdir().unwrap();
+        let installed: Vec<FontliftFontFaceInfo> = fontlift_testfonts::ribbi_family("Atkinson")
+            .iter()
+            .map(|font| metadata::read_face_info(&font.write_to(tmp.path()).unwrap()).unwrap())
+            .collect();
+
+        let bold = resolve_face(&installed, "Atkinson-Bold").unwrap();
+        assert_eq!(bold.style, "Bold");
+        assert_eq!(
+            resolve_face(&installed, "atkinson  bold italic")
+                .unwrap()
+                .postscript_name,
+            "Atkinson-BoldItalic"
+        );
+        assert_eq!(
+            resolve_face(&installed, "ATKINSON")
+                .unwrap()
+                .postscript_name,
+            "Atkinson-Regular"
+        );
+        assert!(matches!(
+            resolve_face(&installed, "Hyperlegible"),
+            Err(FontError::FontNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn css_snippet_falls_back_to_the_classified_generic_family() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = TestFont::new("Book \"Serif\"")
+            .with_postscript_name("BookSerif-Bold")
+            .with_family_class(1, 0)
+            .with_weight(700)
+            .with_style("Bold")
+            .write_to(tmp.path())
+            .unwrap();
+        let face = metadata::read_face_info(&path).unwrap();
+
+        let snippets = name_snippets(&face);
+        assert_eq!(
+            snippets.get(SnippetKind::Css),
+            "font-family: \"Book \\\"Serif\\\"\", serif;\nfont-weight: 700;\nfont-style: normal;"
+        );
+        assert_eq!(snippets.get(SnippetKind::Family), "Book \"Serif\"");
+    }
+}
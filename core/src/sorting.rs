@@ -0,0 +1,216 @@
+//! Shared ordering for font listings (`--sort-by`).
+//!
+//! Sorting output lines lexicographically interleaves styles oddly:
+//! `Inter-Black`, `Inter-Bold`, `Inter-ExtraLight`, `Inter-Italic`. The
+//! comparators here order faces the way a font menu does, by family, then
+//! weight, then upright before italic:
+//!
+//! ```text
+//! Inter-ExtraLight   Inter-Regular   Inter-Italic   Inter-Bold   Inter-Black
+//! ```
+//!
+//! [`compare_faces`] is that order. [`sort_faces`] puts another key first
+//! (width, file size, install date) and keeps the family order as the
+//! tie-break, so sorting is stable and deterministic whatever the key.
+
+use crate::{install_db, pairing, FontliftFontFaceInfo};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// What to order a listing by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortKey {
+    /// Family, then weight, then upright before italic.
+    Family,
+    /// Lightest first.
+    Weight,
+    /// `OS/2.usWidthClass`, narrowest first.
+    Width,
+    /// Newest install first; fonts fontlift did not install go last.
+    RecentlyInstalled,
+    /// Smallest file first.
+    Size,
+}
+
+/// Case-insensitive name order, with the exact spelling as the tie-break so
+/// the result never depends on input order.
+pub fn compare_names(a: &str, b: &str) -> Ordering {
+    a.to_lowercase()
+        .cmp(&b.to_lowercase())
+        .then_with(|| a.cmp(b))
+}
+
+fn is_italic(face: &FontliftFontFaceInfo) -> bool {
+    face.italic
+        .unwrap_or_else(|| face.style.to_lowercase().contains("italic"))
+}
+
+/// Family, weight (unknown counts as 400), upright before italic, then
+/// style, PostScript name and path.
+pub fn compare_faces(a: &FontliftFontFaceInfo, b: &FontliftFontFaceInfo) -> Ordering {
+    compare_names(&a.family_name, &b.family_name)
+        .then_with(|| a.weight.unwrap_or(400).cmp(&b.weight.unwrap_or(400)))
+        .then_with(|| is_italic(a).cmp(&is_italic(b)))
+        .then_with(|| compare_names(&a.style, &b.style))
+        .then_with(|| a.postscript_name.cmp(&b.postscript_name))
+        .then_with(|| a.source.path.cmp(&b.source.path))
+        .then_with(|| a.source.face_index.cmp(&b.source.face_index))
+}
+
+/// Per-face values for keys that are not in [`FontliftFontFaceInfo`].
+/// `None` sorts after every known value.
+fn primary_key(
+    face: &FontliftFontFaceInfo,
+    key: SortKey,
+    installed_at: &HashMap<PathBuf, SystemTime>,
+) -> Option<u128> {
+    match key {
+        SortKey::Family => Some(0),
+        SortKey::Weight => Some(face.weight.unwrap_or(400).into()),
+        SortKey::Width => pairing::read_face_traits(
+            &face.source.path,
+            face.source.face_index.unwrap_or(0),
+            &face.family_name,
+        )
+        .ok()
+        .and_then(|traits| traits.width)
+        .map(u128::from),
+        SortKey::Size => std::fs::metadata(&face.source.path)
+            .ok()
+            .map(|m| m.len().into()),
+        SortKey::RecentlyInstalled => installed_at.get(&face.source.path).map(|time| {
+            // Newest first: invert the age.
+            let since_epoch = time
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos();
+            u128::MAX - since_epoch
+        }),
+    }
+}
+
+/// Sort `faces` by `key`, then by [`compare_faces`].
+///
+/// Width reads each face's `OS/2` table, size stats each file, and
+/// recently-installed reads the install database; faces whose value cannot
+/// be found go last.
+pub fn sort_faces(faces: &mut [FontliftFontFaceInfo], key: SortKey) {
+    let installed_at: HashMap<PathBuf, SystemTime> = if key == SortKey::RecentlyInstalled {
+        install_db::load_install_db()
+            .map(|db| {
+                db.records
+                    .into_iter()
+                    .map(|record| (record.path, record.installed_at))
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else {
+        HashMap::new()
+    };
+
+    let mut keyed: Vec<(Option<u128>, FontliftFontFaceInfo)> = faces
+        .iter()
+        .map(|face| (primary_key(face, key, &installed_at), face.clone()))
+        .collect();
+    keyed.sort_by(|(ka, a), (kb, b)| {
+        let primary = match (ka, kb) {
+            (Some(x), Some(y)) => x.cmp(y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        primary.then_with(|| compare_faces(a, b))
+    });
+
+    for (slot, (_, face)) in faces.iter_mut().zip(keyed) {
+        *slot = face;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata;
+    use fontlift_testfonts::TestFont;
+
+    fn names(faces: &[FontliftFontFaceInfo]) -> Vec<&str> {
+        faces.iter().map(|f| f.postscript_name.as_str()).collect()
+    }
+
+    #[test]
+    fn family_order_follows_weight_then_italic() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut faces: Vec<FontliftFontFaceInfo> = [
+            ("Inter-Black", "Black", 900, false),
+            ("Inter-Italic", "Italic", 400, true),
+            ("Inter-ExtraLight", "ExtraLight", 200, false),
+            ("Inter-Bold", "Bold", 700, false),
+            ("Inter-Regular", "Regular", 400, false),
+            ("abel-Regular", "Regular", 400, false),
+        ]
+        .into_iter()
+        .map(|(ps, style, weight, italic)| {
+            let family = ps.split('-').next().unwrap();
+            let path = TestFont::new(family)
+                .with_postscript_name(ps)
+                .with_style(style)
+                .with_weight(weight)
+                .with_italic(italic)
+                .write_to(tmp.path())
+                .unwrap();
+            metadata::read_face_info(&path).unwrap()
+        })
+        .collect();
+
+        sort_faces(&mut faces, SortKey::Family);
+        assert_eq!(
+            names(&faces),
+            vec![
+                "abel-Regular",
+                "Inter-ExtraLight",
+                "Inter-Regular",
+                "Inter-Italic",
+                "Inter-Bold",
+                "Inter-Black",
+            ]
+        );
+
+        sort_faces(&mut faces, SortKey::Weight);
+        assert_eq!(names(&faces)[..2], ["Inter-ExtraLight", "abel-Regular"]);
+    }
+
+    #[test]
+    fn width_and_size_keys_fall_back_to_family_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let condensed = TestFont::new("Narrow")
+            .with_width(3)
+            .with_chars('a'..='z')
+            .write_to(tmp.path())
+            .unwrap();
+        let normal = TestFont::new("Base").write_to(tmp.path()).unwrap();
+        let mut faces = vec![
+            metadata::read_face_info(&normal).unwrap(),
+            metadata::read_face_info(&condensed).unwrap(),
+        ];
+        let mut missing = faces[0].clone();
+        missing.source.path = tmp.path().join("Gone.ttf");
+        missing.postscript_name = "Gone-Regular".to_string();
+        faces.push(missing);
+
+        sort_faces(&mut faces, SortKey::Width);
+        assert_eq!(
+            names(&faces),
+            vec!["Narrow-Regular", "Base-Regular", "Gone-Regular"]
+        );
+
+        sort_faces(&mut faces, SortKey::Size);
+        assert_eq!(
+            names(&faces),
+            vec!["Base-Regular", "Narrow-Regular", "Gone-Regular"]
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Every place fontlift keeps, finds or caches fonts (`fontlift sources`).
+//!
+//! Fonts end up in more places than the two font folders. `fontlift sources`
+//! lists them all, so "where did that font go?" and "which folder does
+//! `--admin` change?" have one answer:
+//!
+//! | Kind | What lives there | Scope |
+//! |---|---|---|
+//! | `fonts` | fonts `install` copies and registers | user, or system with `--admin` |
+//! | `os-fonts` | fonts the OS ships; fontlift never changes them | system |
+//! | `extra` | directories named in `FONTLIFT_ADDITIONAL_FONTS` | — |
+//! | `content-store` | downloads from URLs and providers, by SHA-256 | — |
+//! | `quarantine` | fonts `remove` took away, until `restore` or expiry | both |
+//! | `vault` | fonts `disable` set aside | user, or system with `--admin` |
+//! | `metadata-cache` | font names and versions read on earlier runs | — |
+//! | `app-cache` | an application's own font cache, cleared by `cleanup` | user or system |
+//!
+//! The platform managers report the font folders and application caches
+//! through [`FontManager::source_roots`](crate::FontManager::source_roots);
+//! [`fontlift_roots`] adds fontlift's own directories. [`measure`] then
+//! counts what each one holds.
+
+use crate::{content_store, metadata_cache, quarantine, validation, vault, FontScope};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What a directory is to fontlift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceKind {
+    Fonts,
+    OsFonts,
+    Extra,
+    ContentStore,
+    Quarantine,
+    Vault,
+    MetadataCache,
+    AppCache,
+}
+
+impl SourceKind {
+    /// `fonts`, `os-fonts`, ...: the name in `fontlift sources` output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SourceKind::Fonts => "fonts",
+            SourceKind::OsFonts => "os-fonts",
+            SourceKind::Extra => "extra",
+            SourceKind::ContentStore => "content-store",
+            SourceKind::Quarantine => "quarantine",
+            SourceKind::Vault => "vault",
+            SourceKind::MetadataCache => "metadata-cache",
+            SourceKind::AppCache => "app-cache",
+        }
+    }
+}
+
+/// A directory (or, for the metadata cache, a file) fontlift knows about.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceRoot {
+    pub kind: SourceKind,
+    pub path: PathBuf,
+    /// The scope whose commands change it; `None` when it is shared.
+    pub scope: Option<FontScope>,
+    /// Who else uses it, for application caches: `Adobe`, `Microsoft Office`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+}
+
+impl SourceRoot {
+    pub fn new(kind: SourceKind, path: impl Into<PathBuf>, scope: Option<FontScope>) -> Self {
+        Self {
+            kind,
+            path: path.into(),
+            scope,
+            owner: None,
+        }
+    }
+
+    pub fn with_owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+}
+
+/// A [`SourceRoot`] and what it holds right now.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceUsage {
+    #[serde(flatten)]
+    pub root: SourceRoot,
+    pub exists: bool,
+    /// Whether this process could create files there; for a missing
+    /// directory, whether it could create it.
+    pub writable: bool,
+    /// Every file below it.
+    pub files: u64,
+    /// Files with a font extension among them.
+    pub font_files: u64,
+    pub bytes: u64,
+}
+
+/// fontlift's own directories, and the extra directories named in
+/// `FONTLIFT_ADDITIONAL_FONTS` (separated like `PATH`).
+pub fn fontlift_roots() -> Vec<SourceRoot> {
+    let mut roots = vec![
+        SourceRoot::new(
+            SourceKind::ContentStore,
+            content_store::default_store_path(),
+            None,
+        ),
+        SourceRoot::new(SourceKind::Quarantine, quarantine::quarantine_dir(), None),
+        SourceRoot::new(
+            SourceKind::Vault,
+            vault::vault_dir(FontScope::User),
+            Some(FontScope::User),
+        ),
+        SourceRoot::new(
+            SourceKind::Vault,
+            vault::vault_dir(FontScope::System),
+            Some(FontScope::System),
+        ),
+        SourceRoot::new(
+            SourceKind::MetadataCache,
+            metadata_cache::metadata_cache_path(),
+            None,
+        ),
+    ];
+    if let Some(dirs) = std::env::var_os("FONTLIFT_ADDITIONAL_FONTS") {
+        roots.extend(
+            std::env::split_paths(&dirs)
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .map(|dir| SourceRoot::new(SourceKind::Extra, dir, None)),
+        );
+    }
+    roots
+}
+
+/// Count the files below `root` and check whether it is writable.
+pub fn measure(root: SourceRoot) -> SourceUsage {
+    let mut usage = SourceUsage {
+        exists: root.path.exists(),
+        writable: is_writable(&root.path),
+        files: 0,
+        font_files: 0,
+        bytes: 0,
+        root,
+    };
+    let mut stack = vec![usage.root.path.clone()];
+    while let Some(path) = stack.pop() {
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if meta.is_dir() {
+            if let Ok(entries) = fs::read_dir(&path) {
+                stack.extend(entries.flatten().map(|entry| entry.path()));
+            }
+        } else if meta.is_file() {
+            usage.files += 1;
+            usage.bytes += meta.len();
+            if validation::is_valid_font_extension(&path) {
+                usage.font_files += 1;
+            }
+        }
+    }
+    usage
+}
+
+/// Whether a file could be created at `path` (a directory) or next to it (a
+/// file), trying the nearest existing ancestor when it does not exist yet.
+///
+/// Permission bits do not tell the whole story (ACLs, read-only volumes,
+/// SIP), so this creates and removes a probe file.
+pub fn is_writable(path: &Path) -> bool {
+    let dir = if path.is_file() {
+        path.parent()
+    } else {
+        path.ancestors().find(|dir| dir.is_dir())
+    };
+    let Some(dir) = dir else {
+        return false;
+    };
+    let probe = dir.join(format!(".fontlift-probe-{}", uuid::Uuid::new_v4()));
+    match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+    {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_files_fonts_and_writability() {
+        let tmp = tempfile::tempdir().unwrap();
+        let fonts = tmp.path().join("Fonts");
+        fs::create_dir_all(fonts.join("nested")).unwrap();
+        fs::write(fonts.join("A.ttf"), b"abcd").unwrap();
+        fs::write(fonts.join("nested/B.otf"), b"ef").unwrap();
+        fs::write(fonts.join("README.txt"), b"g").unwrap();
+
+        let usage = measure(SourceRoot::new(
+            SourceKind::Fonts,
+            &fonts,
+            Some(FontScope::User),
+        ));
+        assert!(usage.exists && usage.writable);
+        assert_eq!((usage.files, usage.font_files, usage.bytes), (3, 2, 7));
+        // The probe file is gone again.
+        assert_eq!(fs::read_dir(&fonts).unwrap().count(), 3);
+
+        let missing = measure(SourceRoot::new(
+            SourceKind::Quarantine,
+            fonts.join("not/yet"),
+            None,
+        ));
+        assert!(!missing.exists && missing.writable);
+        assert_eq!(missing.files, 0);
+
+        let json = serde_json::to_value(&usage).unwrap();
+        assert_eq!(json["kind"], "fonts");
+        assert_eq!(json["scope"], "User");
+    }
+}
@@ -0,0 +1,846 @@
+//! Type specimen PDFs for proofing fonts without a design application.
+//!
+//! [`build_specimen`] lays out an A4 proofing sheet for a face or a whole
+//! family: the family's styles one line each, a sample paragraph at several
+//! sizes in the regular face, and that face's character set with code
+//! points. `fontlift specimen` writes it with [`write_specimen`].
+//!
+//! The PDF is written directly — a specimen is a few dozen objects — and
+//! each face is embedded whole as an OpenType font program (`/FontFile3`
+//! with `/Subtype /OpenType`, PDF 1.6), addressed by glyph ID through an
+//! `Identity-H` CID font with a `ToUnicode` map so the text can be copied.
+//! Faces of a collection are embedded as standalone fonts.
+//!
+//! Text is set glyph by glyph from the `cmap` and the `hmtx` advances.
+//! Nothing is shaped: kerning, ligatures and scripts that need contextual
+//! forms are not represented, and characters the face lacks show its
+//! `.notdef` glyph. A face whose `OS/2.fsType` forbids embedding is refused.
+
+use crate::metadata::{self, Embedding};
+use crate::{snippets, sorting, FontError, FontResult, FontliftFontFaceInfo};
+use read_fonts::tables::cmap::Cmap;
+use read_fonts::types::GlyphId;
+use read_fonts::{FileRef, FontRef, TableProvider};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Sizes, in points, the sample paragraph is set at by default.
+pub const DEFAULT_SIZES: [f32; 6] = [9.0, 12.0, 18.0, 24.0, 36.0, 48.0];
+
+/// Default sample paragraph.
+pub const DEFAULT_SAMPLE: &str = "The quick brown fox jumps over the lazy dog. \
+Pack my box with five dozen liquor jugs! How vexingly quick daft zebras jump; \
+sphinx of black quartz, judge my vow. 0123456789 (&) [@] {#} $ % ? “Quoted” — ‘single’.";
+
+/// Line set in every style of the family.
+const STYLE_LINE: &str = "The quick brown fox jumps over the lazy dog 0123456789";
+
+/// Character set entries shown at most; larger fonts are cut off there.
+pub const MAX_CHARACTERS: usize = 4096;
+
+const PAGE_WIDTH: f32 = 595.28;
+const PAGE_HEIGHT: f32 = 841.89;
+const MARGIN: f32 = 50.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+const CHARSET_COLUMNS: usize = 12;
+
+/// What to put on the sheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecimenOptions {
+    /// Sizes, in points, of the sample paragraph.
+    pub sizes: Vec<f32>,
+    /// The sample paragraph.
+    pub sample: String,
+}
+
+impl Default for SpecimenOptions {
+    fn default() -> Self {
+        Self {
+            sizes: DEFAULT_SIZES.to_vec(),
+            sample: DEFAULT_SAMPLE.to_string(),
+        }
+    }
+}
+
+/// A laid-out specimen.
+#[derive(Debug, Clone)]
+pub struct Specimen {
+    /// The PDF file.
+    pub pdf: Vec<u8>,
+    pub pages: usize,
+    /// Characters shown in the character set.
+    pub characters: usize,
+    /// The face's mapped characters beyond [`MAX_CHARACTERS`] left out.
+    pub characters_omitted: usize,
+}
+
+/// What [`write_specimen`] wrote.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SpecimenReport {
+    pub output: PathBuf,
+    /// Full names of the faces shown, in order.
+    pub faces: Vec<String>,
+    pub pages: usize,
+    pub characters: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub characters_omitted: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// The faces a specimen of `query` shows, in family order.
+///
+/// `query` is a font file (every face of it), an installed family name
+/// (every face of the family), or anything [`snippets::resolve_face`]
+/// finds (that one face).
+pub fn specimen_faces(
+    installed: &[FontliftFontFaceInfo],
+    query: &str,
+) -> FontResult<Vec<FontliftFontFaceInfo>> {
+    let path = Path::new(query);
+    let mut faces = if path.is_file() {
+        metadata::read_all_face_info(path)?
+    } else {
+        let family: Vec<FontliftFontFaceInfo> = installed
+            .iter()
+            .filter(|face| face.family_name.eq_ignore_ascii_case(query.trim()))
+            .cloned()
+            .collect();
+        if family.is_empty() {
+            vec![snippets::resolve_face(installed, query)?.clone()]
+        } else {
+            family
+        }
+    };
+    faces.sort_by(sorting::compare_faces);
+    faces.dedup_by(|a, b| {
+        a.source.path == b.source.path && a.source.face_index == b.source.face_index
+    });
+    Ok(faces)
+}
+
+/// Lay out the specimen of `faces` (in the order given).
+pub fn build_specimen(
+    faces: &[FontliftFontFaceInfo],
+    options: &SpecimenOptions,
+) -> FontResult<Specimen> {
+    if faces.is_empty() {
+        return Err(FontError::InvalidArgument(
+            "A specimen needs at least one face".to_string(),
+        ));
+    }
+    let files = faces
+        .iter()
+        .map(|face| {
+            std::fs::read(&face.source.path).map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => FontError::FontNotFound(face.source.path.clone()),
+                _ => FontError::IoError(e),
+            })
+        })
+        .collect::<FontResult<Vec<_>>>()?;
+    let mut embedded = faces
+        .iter()
+        .zip(&files)
+        .enumerate()
+        .map(|(index, (info, data))| EmbeddedFace::new(index, info, data))
+        .collect::<FontResult<Vec<_>>>()?;
+
+    let family = &faces[0].family_name;
+    let main = snippets::resolve_face(faces, family)
+        .ok()
+        .and_then(|face| faces.iter().position(|f| std::ptr::eq(f, face)))
+        .unwrap_or(0);
+
+    let mut layout = Layout::new();
+    layout.label(true, 22.0, family);
+    let styles = if faces.len() == 1 {
+        faces[0].style.clone()
+    } else {
+        format!("{} styles", faces.len())
+    };
+    layout.label(false, 9.0, &format!("{styles} - fontlift specimen"));
+    layout.gap(18.0);
+
+    layout.heading("Styles");
+    for face in &mut embedded {
+        let caption = match face.info.weight {
+            Some(weight) => format!("{} {weight}", face.info.style),
+            None => face.info.style.clone(),
+        };
+        layout.ensure(8.0 + 24.0 * 1.3);
+        layout.label(false, 7.0, &caption);
+        layout.line(face, 24.0, STYLE_LINE);
+        layout.gap(8.0);
+    }
+
+    let regular = &mut embedded[main];
+    layout.gap(10.0);
+    layout.heading(&format!("Text - {}", regular.info.full_name));
+    for &size in &options.sizes {
+        let lines = regular.wrap(&options.sample, size);
+        layout.ensure(10.0 + size * 1.3);
+        layout.label(false, 7.0, &format!("{size} pt"));
+        for line in lines {
+            layout.line(regular, size, &line);
+        }
+        layout.gap(10.0);
+    }
+
+    let mut characters = regular.characters();
+    let characters_omitted = characters.len().saturating_sub(MAX_CHARACTERS);
+    characters.truncate(MAX_CHARACTERS);
+    layout.gap(10.0);
+    layout.heading(&format!("Character set - {} characters", characters.len()));
+    let cell = CONTENT_WIDTH / CHARSET_COLUMNS as f32;
+    for row in characters.chunks(CHARSET_COLUMNS) {
+        layout.ensure(cell);
+        let top = layout.y;
+        for (column, &c) in row.iter().enumerate() {
+            let x = MARGIN + column as f32 * cell;
+            let glyphs = regular.encode(&c.to_string());
+            layout.glyphs_at(regular, 20.0, x + 4.0, top - 22.0, &glyphs);
+            layout.text_at(
+                false,
+                5.0,
+                x + 4.0,
+                top - 32.0,
+                &format!("U+{:04X}", c as u32),
+            );
+        }
+        layout.y = top - cell;
+    }
+
+    let pages = layout.finish();
+    let page_count = pages.len();
+    Ok(Specimen {
+        pdf: write_pdf(&pages, &embedded, family),
+        pages: page_count,
+        characters: characters.len(),
+        characters_omitted,
+    })
+}
+
+/// Lay out the specimen of `faces` and write it to `output`.
+pub fn write_specimen(
+    faces: &[FontliftFontFaceInfo],
+    options: &SpecimenOptions,
+    output: &Path,
+) -> FontResult<SpecimenReport> {
+    let specimen = build_specimen(faces, options)?;
+    std::fs::write(output, &specimen.pdf).map_err(FontError::IoError)?;
+    Ok(SpecimenReport {
+        output: output.to_path_buf(),
+        faces: faces.iter().map(|face| face.full_name.clone()).collect(),
+        pages: specimen.pages,
+        characters: specimen.characters,
+        characters_omitted: specimen.characters_omitted,
+    })
+}
+
+/// A face being set, with the glyphs used so far.
+struct EmbeddedFace<'a> {
+    resource: String,
+    info: &'a FontliftFontFaceInfo,
+    font: FontRef<'a>,
+    cmap: Option<Cmap<'a>>,
+    program: Vec<u8>,
+    units_per_em: f32,
+    used: BTreeMap<u16, Option<char>>,
+}
+
+impl<'a> EmbeddedFace<'a> {
+    fn new(index: usize, info: &'a FontliftFontFaceInfo, data: &'a [u8]) -> FontResult<Self> {
+        let invalid = |e: read_fonts::ReadError| {
+            FontError::InvalidFormat(format!("{}: {e}", info.source.path.display()))
+        };
+        let file = FileRef::new(data).map_err(invalid)?;
+        let face_index = info.source.face_index.unwrap_or(0) as usize;
+        let (font, program) = match file {
+            FileRef::Font(font) => (font, data.to_vec()),
+            FileRef::Collection(collection) => {
+                let font = collection.get(face_index as u32).map_err(invalid)?;
+                let program = standalone_font(&font);
+                (font, program)
+            }
+        };
+        if let Ok(os2) = font.os2() {
+            if Embedding::from_fs_type(os2.fs_type()).is_restricted() {
+                return Err(FontError::PermissionDenied(format!(
+                    "{}: the font's license (OS/2 fsType) does not allow embedding it in a PDF",
+                    info.full_name
+                )));
+            }
+        }
+        let units_per_em = font
+            .head()
+            .map(|head| head.units_per_em())
+            .unwrap_or(1000)
+            .max(1) as f32;
+        Ok(Self {
+            resource: format!("E{index}"),
+            info,
+            cmap: font.cmap().ok(),
+            font,
+            program,
+            units_per_em,
+            used: BTreeMap::new(),
+        })
+    }
+
+    fn glyph(&self, c: char) -> u16 {
+        self.cmap
+            .as_ref()
+            .and_then(|cmap| cmap.map_codepoint(c))
+            .and_then(|glyph| u16::try_from(glyph.to_u32()).ok())
+            .unwrap_or(0)
+    }
+
+    /// Advance of `glyph` in em.
+    fn advance(&self, glyph: u16) -> f32 {
+        let units = self
+            .font
+            .hmtx()
+            .ok()
+            .and_then(|hmtx| hmtx.advance(GlyphId::new(glyph.into())))
+            .unwrap_or(0);
+        f32::from(units) / self.units_per_em
+    }
+
+    /// Glyphs for `text`, recorded as used.
+    fn encode(&mut self, text: &str) -> Vec<u16> {
+        text.chars()
+            .filter(|c| !c.is_control())
+            .map(|c| {
+                let glyph = self.glyph(c);
+                let entry = self.used.entry(glyph).or_insert(None);
+                if glyph != 0 {
+                    entry.get_or_insert(c);
+                }
+                glyph
+            })
+            .collect()
+    }
+
+    fn width(&self, text: &str, size: f32) -> f32 {
+        text.chars()
+            .map(|c| self.advance(self.glyph(c)))
+            .sum::<f32>()
+            * size
+    }
+
+    /// `text` broken into lines that fit the page at `size`.
+    fn wrap(&self, text: &str, size: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        for word in text.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{line} {word}")
+            };
+            if !line.is_empty() && self.width(&candidate, size) > CONTENT_WIDTH {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            } else {
+                line = candidate;
+            }
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Every printable character the face maps, in code point order.
+    fn characters(&self) -> Vec<char> {
+        let Some((_, _, subtable)) = self.cmap.as_ref().and_then(|cmap| cmap.best_subtable())
+        else {
+            return Vec::new();
+        };
+        let mut characters: Vec<char> = subtable
+            .iter()
+            .filter(|(_, glyph)| glyph.to_u32() != 0)
+            .filter_map(|(codepoint, _)| char::from_u32(codepoint))
+            .filter(|c| !c.is_control() && !c.is_whitespace())
+            .collect();
+        characters.sort_unstable();
+        characters.dedup();
+        characters
+    }
+
+    /// Glyph `width` values, in PDF's thousandths of an em, of the glyphs used.
+    fn widths(&self) -> String {
+        let mut widths = String::new();
+        for &glyph in self.used.keys() {
+            let _ = write!(
+                widths,
+                "{glyph} [{}] ",
+                (self.advance(glyph) * 1000.0).round()
+            );
+        }
+        widths
+    }
+
+    fn is_cff(&self) -> bool {
+        self.font
+            .table_data(read_fonts::types::Tag::new(b"CFF "))
+            .is_some()
+            || self
+                .font
+                .table_data(read_fonts::types::Tag::new(b"CFF2"))
+                .is_some()
+    }
+}
+
+/// One face of a collection as a font file of its own.
+fn standalone_font(font: &FontRef<'_>) -> Vec<u8> {
+    let records = font.table_directory().table_records();
+    let count = records.len() as u16;
+    let power = if count == 0 {
+        0
+    } else {
+        15 - count.leading_zeros() as u16
+    };
+    let search_range = (1u16 << power) * 16;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&font.table_directory().sfnt_version().to_be_bytes());
+    out.extend_from_slice(&count.to_be_bytes());
+    out.extend_from_slice(&search_range.to_be_bytes());
+    out.extend_from_slice(&power.to_be_bytes());
+    out.extend_from_slice(&(count * 16).saturating_sub(search_range).to_be_bytes());
+
+    let mut offset = 12 + 16 * records.len();
+    let mut tables = Vec::new();
+    for record in records {
+        let data = font
+            .table_data(record.tag())
+            .map(|data| data.as_bytes())
+            .unwrap_or_default();
+        out.extend_from_slice(&record.tag().into_bytes());
+        out.extend_from_slice(&record.checksum().to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        tables.extend_from_slice(data);
+        let padded = (data.len() + 3) & !3;
+        tables.resize(tables.len() + padded - data.len(), 0);
+        offset += padded;
+    }
+    out.extend_from_slice(&tables);
+    out
+}
+
+/// Page content streams being filled top to bottom.
+struct Layout {
+    pages: Vec<String>,
+    current: String,
+    y: f32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            current: String::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    /// Start a new page unless `height` more points fit on this one.
+    fn ensure(&mut self, height: f32) {
+        if self.y - height < MARGIN && !self.current.is_empty() {
+            self.pages.push(std::mem::take(&mut self.current));
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.ensure(40.0);
+        self.label(true, 12.0, text);
+        self.gap(6.0);
+    }
+
+    /// A line of Helvetica at `size`.
+    fn label(&mut self, bold: bool, size: f32, text: &str) {
+        self.ensure(size * 1.3);
+        self.y -= size * 1.3;
+        self.text_at(bold, size, MARGIN, self.y + size * 0.3, text);
+    }
+
+    fn text_at(&mut self, bold: bool, size: f32, x: f32, y: f32, text: &str) {
+        let font = if bold { "F1" } else { "F0" };
+        let _ = writeln!(
+            self.current,
+            "BT /{font} {size} Tf {x:.2} {y:.2} Td ({}) Tj ET",
+            pdf_text(text)
+        );
+    }
+
+    /// A line of `face` at `size`, cut off at the right margin.
+    fn line(&mut self, face: &mut EmbeddedFace<'_>, size: f32, text: &str) {
+        let mut fitting = String::new();
+        let mut width = 0.0;
+        for c in text.chars() {
+            width += face.advance(face.glyph(c)) * size;
+            if width > CONTENT_WIDTH {
+                break;
+            }
+            fitting.push(c);
+        }
+        self.ensure(size * 1.3);
+        self.y -= size * 1.3;
+        let glyphs = face.encode(&fitting);
+        self.glyphs_at(face, size, MARGIN, self.y + size * 0.3, &glyphs);
+    }
+
+    fn glyphs_at(&mut self, face: &EmbeddedFace<'_>, size: f32, x: f32, y: f32, glyphs: &[u16]) {
+        let hex: String = glyphs.iter().map(|glyph| format!("{glyph:04X}")).collect();
+        let _ = writeln!(
+            self.current,
+            "BT /{} {size} Tf {x:.2} {y:.2} Td <{hex}> Tj ET",
+            face.resource
+        );
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        if !self.current.is_empty() || self.pages.is_empty() {
+            self.pages.push(self.current);
+        }
+        self.pages
+    }
+}
+
+/// `text` as the body of a PDF literal string in WinAnsi: parentheses and
+/// backslashes escaped, characters outside Latin-1 replaced with `?`.
+fn pdf_text(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            _ => out.push('?'),
+        }
+    }
+    out
+}
+
+/// `name` as a PDF name object body, with delimiters and non-ASCII bytes
+/// written as `#xx`.
+fn pdf_name(name: &str) -> String {
+    let mut out = String::new();
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'+') {
+            out.push(byte as char);
+        } else {
+            let _ = write!(out, "#{byte:02X}");
+        }
+    }
+    if out.is_empty() {
+        out.push_str("Font");
+    }
+    out
+}
+
+/// The `ToUnicode` CMap of the glyphs `face` used.
+fn to_unicode_cmap(face: &EmbeddedFace<'_>) -> String {
+    let mappings: Vec<(u16, char)> = face
+        .used
+        .iter()
+        .filter_map(|(&glyph, &c)| c.map(|c| (glyph, c)))
+        .collect();
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+/CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+    for chunk in mappings.chunks(100) {
+        let _ = writeln!(cmap, "{} beginbfchar", chunk.len());
+        for (glyph, c) in chunk {
+            let mut units = [0u16; 2];
+            let unicode: String = c
+                .encode_utf16(&mut units)
+                .iter()
+                .map(|unit| format!("{unit:04X}"))
+                .collect();
+            let _ = writeln!(cmap, "<{glyph:04X}> <{unicode}>");
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    cmap
+}
+
+/// PDF objects, numbered from 1.
+struct PdfObjects {
+    objects: Vec<Vec<u8>>,
+}
+
+impl PdfObjects {
+    /// Reserve an object number to fill in with [`Self::set`].
+    fn reserve(&mut self) -> usize {
+        self.objects.push(Vec::new());
+        self.objects.len()
+    }
+
+    fn set(&mut self, id: usize, body: impl Into<Vec<u8>>) {
+        self.objects[id - 1] = body.into();
+    }
+
+    fn add(&mut self, body: impl Into<Vec<u8>>) -> usize {
+        let id = self.reserve();
+        self.set(id, body);
+        id
+    }
+
+    fn add_stream(&mut self, dictionary: &str, data: &[u8]) -> usize {
+        let mut body = format!("<< {dictionary} /Length {} >>\nstream\n", data.len()).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        self.add(body)
+    }
+
+    fn finish(self, root: usize, info: usize) -> Vec<u8> {
+        let mut out = b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::new();
+        for (index, body) in self.objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+            out.extend_from_slice(body);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = out.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", self.objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(table, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            table,
+            "trailer\n<< /Size {} /Root {root} 0 R /Info {info} 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            self.objects.len() + 1
+        );
+        out.extend_from_slice(table.as_bytes());
+        out
+    }
+}
+
+fn write_pdf(pages: &[String], faces: &[EmbeddedFace<'_>], title: &str) -> Vec<u8> {
+    let mut pdf = PdfObjects {
+        objects: Vec::new(),
+    };
+    let catalog = pdf.reserve();
+    let page_tree = pdf.reserve();
+    let info = pdf.add(format!(
+        "<< /Title ({} specimen) /Producer (fontlift {}) >>",
+        pdf_text(title),
+        env!("CARGO_PKG_VERSION")
+    ));
+
+    let mut fonts = String::new();
+    for (resource, base) in [("F0", "Helvetica"), ("F1", "Helvetica-Bold")] {
+        let id = pdf.add(format!(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /{base} /Encoding /WinAnsiEncoding >>"
+        ));
+        let _ = write!(fonts, "/{resource} {id} 0 R ");
+    }
+    for face in faces {
+        let id = embed_face(&mut pdf, face);
+        let _ = write!(fonts, "/{} {id} 0 R ", face.resource);
+    }
+
+    let mut kids = String::new();
+    for content in pages {
+        let stream = pdf.add_stream("", content.as_bytes());
+        let page = pdf.add(format!(
+            "<< /Type /Page /Parent {page_tree} 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+/Resources << /Font << {fonts}>> >> /Contents {stream} 0 R >>"
+        ));
+        let _ = write!(kids, "{page} 0 R ");
+    }
+    pdf.set(
+        page_tree,
+        format!("<< /Type /Pages /Kids [{kids}] /Count {} >>", pages.len()),
+    );
+    pdf.set(
+        catalog,
+        format!("<< /Type /Catalog /Pages {page_tree} 0 R >>"),
+    );
+    pdf.finish(catalog, info)
+}
+
+/// The `Type0` font object for `face`, with its descendant CID font,
+/// descriptor, font program and `ToUnicode` map.
+fn embed_face(pdf: &mut PdfObjects, face: &EmbeddedFace<'_>) -> usize {
+    let scale = |units: i16| (f32::from(units) * 1000.0 / face.units_per_em).round();
+    let name = pdf_name(&face.info.postscript_name);
+    let program = pdf.add_stream("/Subtype /OpenType", &face.program);
+
+    let (bbox, ascent, descent, cap_height) = {
+        let head = face.font.head().ok();
+        let os2 = face.font.os2().ok();
+        let bbox = head
+            .map(|head| {
+                format!(
+                    "{} {} {} {}",
+                    scale(head.x_min()),
+                    scale(head.y_min()),
+                    scale(head.x_max()),
+                    scale(head.y_max())
+                )
+            })
+            .unwrap_or_else(|| "0 -250 1000 1000".to_string());
+        let ascent = os2.as_ref().map(|os2| os2.s_typo_ascender()).unwrap_or(800);
+        let descent = os2
+            .as_ref()
+            .map(|os2| os2.s_typo_descender())
+            .unwrap_or(-200);
+        let cap_height = os2
+            .as_ref()
+            .and_then(|os2| os2.s_cap_height())
+            .unwrap_or(ascent);
+        (bbox, scale(ascent), scale(descent), scale(cap_height))
+    };
+    let italic_angle = face
+        .font
+        .post()
+        .map(|post| post.italic_angle().to_f64())
+        .unwrap_or(0.0);
+    // Flag 6 is Nonsymbolic, flag 7 Italic.
+    let flags = if italic_angle != 0.0 || face.info.italic == Some(true) {
+        32 | 64
+    } else {
+        32
+    };
+    let descriptor = pdf.add(format!(
+        "<< /Type /FontDescriptor /FontName /{name} /Flags {flags} /FontBBox [{bbox}] \
+/ItalicAngle {italic_angle} /Ascent {ascent} /Descent {descent} /CapHeight {cap_height} \
+/StemV 80 /FontFile3 {program} 0 R >>"
+    ));
+
+    let subtype = if face.is_cff() {
+        "/CIDFontType0"
+    } else {
+        "/CIDFontType2 /CIDToGIDMap /Identity"
+    };
+    let cid_font = pdf.add(format!(
+        "<< /Type /Font /Subtype {subtype} /BaseFont /{name} \
+/CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> \
+/FontDescriptor {descriptor} 0 R /DW 0 /W [{}] >>",
+        face.widths()
+    ));
+    let to_unicode = pdf.add_stream("", to_unicode_cmap(face).as_bytes());
+    pdf.add(format!(
+        "<< /Type /Font /Subtype /Type0 /BaseFont /{name} /Encoding /Identity-H \
+/DescendantFonts [{cid_font} 0 R] /ToUnicode {to_unicode} 0 R >>"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontlift_testfonts::{Outlines, TestFont};
+
+    fn face(path: &Path) -> Vec<FontliftFontFaceInfo> {
+        metadata::read_all_face_info(path).unwrap()
+    }
+
+    /// Offsets in the cross-reference table point at their objects.
+    fn assert_valid_xref(pdf: &[u8]) {
+        let tail_at = pdf.len() - 64.min(pdf.len());
+        let tail = String::from_utf8_lossy(&pdf[tail_at..]);
+        let start: usize = tail
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .and_then(|line| line.parse().ok())
+            .unwrap();
+        let table = String::from_utf8_lossy(&pdf[start..]);
+        assert!(table.starts_with("xref\n"));
+        for (index, line) in table.lines().skip(3).enumerate() {
+            if line.starts_with("trailer") {
+                break;
+            }
+            let offset: usize = line[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{} 0 obj", index + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn family_specimens_embed_every_style() {
+        let tmp = tempfile::tempdir().unwrap();
+        let chars: Vec<char> = (' '..='~').collect();
+        let mut installed = Vec::new();
+        for (style, weight, outlines) in [
+            ("Bold", 700, Outlines::Cff),
+            ("Regular", 400, Outlines::TrueType),
+        ] {
+            let font = TestFont::new("Proof Sans")
+                .with_style(style)
+                .with_weight(weight)
+                .with_chars(chars.clone())
+                .with_outlines(outlines);
+            installed.extend(face(&font.write_to(tmp.path()).unwrap()));
+        }
+
+        let faces = specimen_faces(&installed, "proof sans").unwrap();
+        assert_eq!(
+            faces.iter().map(|f| f.style.as_str()).collect::<Vec<_>>(),
+            ["Regular", "Bold"]
+        );
+        let single = specimen_faces(&installed, "ProofSans-Bold").unwrap();
+        assert_eq!(single.len(), 1);
+
+        let out = tmp.path().join("specimen.pdf");
+        let report = write_specimen(&faces, &SpecimenOptions::default(), &out).unwrap();
+        assert_eq!(report.faces, ["Proof Sans Regular", "Proof Sans Bold"]);
+        assert_eq!(report.characters, chars.len() - 1);
+
+        let pdf = std::fs::read(&out).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-1.7"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert_eq!(text.matches("/Subtype /OpenType").count(), 2);
+        assert_eq!(text.matches("/CIDFontType0").count(), 1);
+        assert_eq!(text.matches("/Type /Page ").count(), report.pages);
+        assert!(text.contains("<0002> <0021>"));
+        assert_valid_xref(&pdf);
+    }
+
+    #[test]
+    fn collection_faces_are_embedded_standalone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let fonts = fontlift_testfonts::ribbi_family("Proof Serif");
+        let path = fontlift_testfonts::write_collection(tmp.path(), "Proof.ttc", &fonts).unwrap();
+        let faces = specimen_faces(&[], path.to_str().unwrap()).unwrap();
+        assert_eq!(faces.len(), 4);
+
+        let data = std::fs::read(&path).unwrap();
+        let FileRef::Collection(collection) = FileRef::new(&data).unwrap() else {
+            panic!("not a collection");
+        };
+        let standalone = standalone_font(&collection.get(2).unwrap());
+        let FileRef::Font(font) = FileRef::new(&standalone).unwrap() else {
+            panic!("not a single font");
+        };
+        assert_eq!(
+            metadata::name_string(&font, read_fonts::tables::name::NameId::SUBFAMILY_NAME),
+            metadata::name_string(
+                &collection.get(2).unwrap(),
+                read_fonts::tables::name::NameId::SUBFAMILY_NAME
+            )
+        );
+
+        let specimen = build_specimen(&faces, &SpecimenOptions::default()).unwrap();
+        assert_valid_xref(&specimen.pdf);
+    }
+}
@@ -0,0 +1,590 @@
+//! Integrity check of fontlift's own state stores (`fontlift state check`).
+//!
+//! fontlift keeps four stores on disk:
+//!
+//! | Store | Kind | On damage |
+//! |---|---|---|
+//! | Journal ([`crate::journal`]) | authoritative | reported; `fontlift doctor` resumes incomplete entries |
+//! | Install database ([`crate::install_db`]) | authoritative | cross-checked and repaired record by record |
+//! | Metadata cache ([`crate::metadata_cache`]) | derived | rebuilt |
+//! | Download store ([`crate::content_store`]) | derived | dangling and corrupt entries dropped |
+//!
+//! A crash or a full disk can leave them out of step with each other and with
+//! the font files. [`check_state`] looks for:
+//!
+//! - files that cannot be parsed with the current schema, and a metadata
+//!   cache written with another version (the journal and install database
+//!   carry no version number; parsing them is the schema check),
+//! - install records whose font file is gone, and duplicate records,
+//! - fonts the journal shows fontlift installed that have no install record,
+//! - cache entries for deleted files, download records whose blob is missing
+//!   or corrupt, and temp files left behind by interrupted writes.
+//!
+//! With `repair`, derived stores are rebuilt and fixable records corrected.
+//! Problems that would lose information if "fixed" automatically, such as an
+//! unparseable journal, are reported as [`Resolution::Manual`].
+
+use crate::content_store::{self, UrlRecord};
+use crate::digest::{digests_match, sha256_file};
+use crate::install_db::{self, InstallReason};
+use crate::journal::{self, JournalAction};
+use crate::metadata_cache::{self, CacheFileHealth, MetadataCache};
+use crate::{FontError, FontResult, FontScope};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Temp files younger than this may belong to a write still in progress.
+const TEMP_FILE_GRACE: Duration = Duration::from_secs(60 * 60);
+
+/// Where each store lives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatePaths {
+    pub journal: PathBuf,
+    pub install_db: PathBuf,
+    pub metadata_cache: PathBuf,
+    pub content_store: PathBuf,
+}
+
+impl StatePaths {
+    /// The locations this process uses, honouring the usual overrides.
+    pub fn current() -> Self {
+        Self {
+            journal: journal::journal_path(),
+            install_db: install_db::install_db_path(),
+            metadata_cache: metadata_cache::metadata_cache_path(),
+            content_store: content_store::default_store_path(),
+        }
+    }
+}
+
+/// One of fontlift's state stores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateStore {
+    Journal,
+    InstallDb,
+    MetadataCache,
+    ContentStore,
+}
+
+impl StateStore {
+    pub fn description(self) -> &'static str {
+        match self {
+            StateStore::Journal => "journal",
+            StateStore::InstallDb => "install database",
+            StateStore::MetadataCache => "metadata cache",
+            StateStore::ContentStore => "download store",
+        }
+    }
+}
+
+/// What happened, or has to happen, about a problem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "detail", rename_all = "snake_case")]
+pub enum Resolution {
+    /// `--repair` would fix it.
+    Repairable,
+    /// Fixed during this check.
+    Repaired,
+    /// Needs a person; the detail says what to do.
+    Manual(String),
+}
+
+/// One problem found in a store.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateFinding {
+    pub store: StateStore,
+    /// The file the problem is about.
+    pub path: PathBuf,
+    pub problem: String,
+    pub resolution: Resolution,
+}
+
+/// Everything [`check_state`] found.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateReport {
+    pub findings: Vec<StateFinding>,
+}
+
+impl StateReport {
+    /// Findings that are still open after the check.
+    pub fn unresolved(&self) -> Vec<&StateFinding> {
+        self.findings
+            .iter()
+            .filter(|f| f.resolution != Resolution::Repaired)
+            .collect()
+    }
+
+    fn push(&mut self, store: StateStore, path: &Path, problem: String, resolution: Resolution) {
+        self.findings.push(StateFinding {
+            store,
+            path: path.to_path_buf(),
+            problem,
+            resolution,
+        });
+    }
+
+    /// Record a fixable problem, running `fix` first when repairing.
+    fn fixable(
+        &mut self,
+        store: StateStore,
+        path: &Path,
+        problem: String,
+        repair: bool,
+        fix: impl FnOnce() -> FontResult<()>,
+    ) {
+        let resolution = if !repair {
+            Resolution::Repairable
+        } else {
+            match fix() {
+                Ok(()) => Resolution::Repaired,
+                Err(e) => Resolution::Manual(format!("repair failed: {e}")),
+            }
+        };
+        self.push(store, path, problem, resolution);
+    }
+}
+
+/// Check every store and, with `repair`, fix what can be fixed safely.
+///
+/// Callers repairing live state should hold the journal lock
+/// ([`journal::with_journal_lock`]) so no install runs concurrently.
+pub fn check_state(paths: &StatePaths, repair: bool) -> StateReport {
+    let mut report = StateReport::default();
+
+    let journal = check_journal(paths, repair, &mut report);
+    check_install_db(paths, journal.as_ref(), repair, &mut report);
+    check_metadata_cache(paths, repair, &mut report);
+    check_content_store(paths, repair, &mut report);
+
+    report
+}
+
+fn check_journal(
+    paths: &StatePaths,
+    repair: bool,
+    report: &mut StateReport,
+) -> Option<journal::Journal> {
+    remove_stale_temp_files(
+        paths.journal.parent(),
+        "journal.json.tmp.",
+        StateStore::Journal,
+        repair,
+        report,
+    );
+
+    let journal = match journal::load_journal_from(&paths.journal) {
+        Ok(journal) => journal,
+        Err(e) => {
+            report.push(
+                StateStore::Journal,
+                &paths.journal,
+                format!("cannot be read: {e}"),
+                Resolution::Manual(
+                    "inspect it, then delete it; the next install moves an unreadable journal aside"
+                        .to_string(),
+                ),
+            );
+            return None;
+        }
+    };
+
+    let incomplete = journal.incomplete_entries().len();
+    if incomplete > 0 {
+        report.push(
+            StateStore::Journal,
+            &paths.journal,
+            format!("{incomplete} interrupted operation(s)"),
+            Resolution::Manual("run `fontlift doctor` to finish or roll them back".to_string()),
+        );
+    }
+    Some(journal)
+}
+
+/// Fonts the journal shows as installed by a completed operation and not
+/// removed since, with the scope they were registered in when known.
+fn journal_installed_fonts(journal: &journal::Journal) -> BTreeMap<PathBuf, Option<FontScope>> {
+    let mut installed = BTreeMap::new();
+    for entry in journal.entries.iter().filter(|e| e.completed) {
+        for action in &entry.actions {
+            match action {
+                JournalAction::CopyFile { to, .. } => {
+                    installed.entry(to.clone()).or_insert(None);
+                }
+                JournalAction::RegisterFont { path, scope } => {
+                    installed.insert(path.clone(), Some(*scope));
+                }
+                JournalAction::UnregisterFont { path, .. } | JournalAction::DeleteFile { path } => {
+                    installed.remove(path);
+                }
+                JournalAction::BackupFile { .. } | JournalAction::ClearCache { .. } => {}
+            }
+        }
+    }
+    installed
+}
+
+fn check_install_db(
+    paths: &StatePaths,
+    journal: Option<&journal::Journal>,
+    repair: bool,
+    report: &mut StateReport,
+) {
+    let store = StateStore::InstallDb;
+    remove_stale_temp_files(
+        paths.install_db.parent(),
+        "installed.json.tmp.",
+        store,
+        repair,
+        report,
+    );
+
+    let mut db = match install_db::load_install_db_from(&paths.install_db) {
+        Ok(db) => db,
+        Err(e) => {
+            report.push(
+                store,
+                &paths.install_db,
+                format!("cannot be read: {e}"),
+                Resolution::Manual(
+                    "fix or delete it; why each font was installed cannot be rebuilt".to_string(),
+                ),
+            );
+            return;
+        }
+    };
+    let mut fixes = Vec::new();
+
+    // Duplicates: keep the newest record for each path.
+    let mut newest_first = std::mem::take(&mut db.records);
+    newest_first.sort_by_key(|r| std::cmp::Reverse(r.installed_at));
+    let mut seen = BTreeSet::new();
+    for record in newest_first {
+        if seen.insert(record.path.clone()) {
+            db.records.push(record);
+        } else {
+            fixes.push((record.path, "duplicate install record".to_string()));
+        }
+    }
+    db.records.reverse();
+
+    // Every record has a file.
+    let missing: Vec<PathBuf> = db
+        .records
+        .iter()
+        .filter(|r| !r.path.exists())
+        .map(|r| r.path.clone())
+        .collect();
+    for path in &missing {
+        fixes.push((
+            path.clone(),
+            "install record for a missing font file".to_string(),
+        ));
+        db.forget(path);
+    }
+
+    // Every font fontlift installed has a record.
+    if let Some(journal) = journal {
+        for (path, scope) in journal_installed_fonts(journal) {
+            if path.exists() && db.find(&path).is_none() {
+                fixes.push((
+                    path.clone(),
+                    "installed font has no install record".to_string(),
+                ));
+                // Explicit: automated cleanup never removes a font on a guess.
+                db.record(
+                    path,
+                    scope.unwrap_or(FontScope::User),
+                    InstallReason::Explicit,
+                );
+            }
+        }
+    }
+
+    // All fixes land in one save; each finding reports its outcome.
+    let save_error = (repair && !fixes.is_empty())
+        .then(|| install_db::save_install_db_to(&db, &paths.install_db).err())
+        .flatten()
+        .map(|e| e.to_string());
+    for (path, problem) in fixes {
+        report.fixable(store, &path, problem, repair, || match &save_error {
+            Some(e) => Err(FontError::IoError(std::io::Error::other(e.clone()))),
+            None => Ok(()),
+        });
+    }
+}
+
+fn check_metadata_cache(paths: &StatePaths, repair: bool, report: &mut StateReport) {
+    let store = StateStore::MetadataCache;
+    let path = &paths.metadata_cache;
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    remove_stale_temp_files(
+        path.parent(),
+        &format!("{stem}.tmp-"),
+        store,
+        repair,
+        report,
+    );
+
+    let problem = match metadata_cache::check_cache_file(path) {
+        CacheFileHealth::Missing | CacheFileHealth::Usable { stale: 0, .. } => return,
+        CacheFileHealth::Usable { stale, .. } => {
+            report.fixable(
+                store,
+                path,
+                format!("{stale} entries for deleted font files"),
+                repair,
+                || MetadataCache::load_from(path.clone()).save(),
+            );
+            return;
+        }
+        CacheFileHealth::Corrupt(reason) => format!("cannot be read: {reason}"),
+        CacheFileHealth::WrongVersion(version) => {
+            format!("written by another fontlift version (cache version {version})")
+        }
+    };
+    report.fixable(store, path, problem, repair, || {
+        fs::remove_file(path).map_err(FontError::IoError)
+    });
+}
+
+fn check_content_store(paths: &StatePaths, repair: bool, report: &mut StateReport) {
+    let store = StateStore::ContentStore;
+    let root = &paths.content_store;
+
+    for blob in files_under(&root.join("sha256")) {
+        let name = blob
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        if name.contains(".tmp-") {
+            if is_stale_temp_file(&blob) {
+                report.fixable(
+                    store,
+                    &blob,
+                    "leftover temp file".to_string(),
+                    repair,
+                    || fs::remove_file(&blob).map_err(FontError::IoError),
+                );
+            }
+            continue;
+        }
+        let intact = sha256_file(&blob).is_ok_and(|digest| digests_match(&digest, &name));
+        if !intact {
+            // Dropping the blob only removes the store's name; installed
+            // hard links to it keep their (damaged) bytes.
+            let problem = match content_store::ContentStore::at(root).installed_links(&name) {
+                0 => "stored download does not match its digest".to_string(),
+                shared => format!(
+                    "stored download does not match its digest ({shared} installed font file(s) share its bytes)"
+                ),
+            };
+            report.fixable(store, &blob, problem, repair, || {
+                fs::remove_file(&blob).map_err(FontError::IoError)
+            });
+        }
+    }
+
+    let blobs = content_store::ContentStore::at(root);
+    for record_path in files_under(&root.join("urls")) {
+        let record = fs::read_to_string(&record_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<UrlRecord>(&content).ok());
+        let problem = match record {
+            None => "download record cannot be read".to_string(),
+            Some(record) if !blobs.blob_path(&record.sha256).is_file() => {
+                format!(
+                    "download record for {} points to a missing file",
+                    record.url
+                )
+            }
+            Some(_) => continue,
+        };
+        report.fixable(store, &record_path, problem, repair, || {
+            fs::remove_file(&record_path).map_err(FontError::IoError)
+        });
+    }
+}
+
+/// Every file below `dir`, recursively. Missing directories are empty.
+fn files_under(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn is_stale_temp_file(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age >= TEMP_FILE_GRACE)
+}
+
+/// Temp files in `dir` whose names start with `prefix` and that are too old
+/// to belong to a write in progress.
+fn remove_stale_temp_files(
+    dir: Option<&Path>,
+    prefix: &str,
+    store: StateStore,
+    repair: bool,
+    report: &mut StateReport,
+) {
+    let Some(Ok(entries)) = dir.map(fs::read_dir) else {
+        return;
+    };
+    let mut leftovers: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(prefix))
+                && is_stale_temp_file(path)
+        })
+        .collect();
+    leftovers.sort();
+    for path in leftovers {
+        report.fixable(
+            store,
+            &path,
+            "leftover temp file".to_string(),
+            repair,
+            || fs::remove_file(&path).map_err(FontError::IoError),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::install_db::InstallDb;
+
+    fn paths_in(dir: &Path) -> StatePaths {
+        StatePaths {
+            journal: dir.join("journal.json"),
+            install_db: dir.join("installed.json"),
+            metadata_cache: dir.join("cache").join("metadata-cache.json"),
+            content_store: dir.join("store"),
+        }
+    }
+
+    #[test]
+    fn healthy_and_empty_state_has_no_findings() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(check_state(&paths_in(tmp.path()), true).findings.is_empty());
+    }
+
+    #[test]
+    fn cross_references_and_repairs_the_stores() {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = paths_in(tmp.path());
+        let kept = tmp.path().join("Kept.ttf");
+        let unrecorded = tmp.path().join("Unrecorded.ttf");
+        let gone = tmp.path().join("Gone.ttf");
+        fs::write(&kept, b"font").unwrap();
+        fs::write(&unrecorded, b"font").unwrap();
+
+        let mut db = InstallDb::new();
+        db.record(kept.clone(), FontScope::User, InstallReason::Explicit);
+        db.record(gone.clone(), FontScope::User, InstallReason::Explicit);
+        install_db::save_install_db_to(&db, &paths.install_db).unwrap();
+
+        let mut journal = journal::Journal::new();
+        let id = journal.record_operation(
+            vec![JournalAction::RegisterFont {
+                path: unrecorded.clone(),
+                scope: FontScope::System,
+            }],
+            None,
+        );
+        journal.mark_completed(id).unwrap();
+        fs::write(&paths.journal, serde_json::to_string(&journal).unwrap()).unwrap();
+
+        fs::create_dir_all(paths.metadata_cache.parent().unwrap()).unwrap();
+        fs::write(&paths.metadata_cache, "{ not json").unwrap();
+
+        let store = content_store::ContentStore::at(&paths.content_store);
+        store
+            .record_url(&UrlRecord {
+                url: "https://example.com/Gone.ttf".to_string(),
+                sha256: "ab".repeat(32),
+                length: 4,
+                etag: None,
+                last_modified: None,
+            })
+            .unwrap();
+
+        let report = check_state(&paths, false);
+        let problems: Vec<(StateStore, &str)> = report
+            .findings
+            .iter()
+            .map(|f| (f.store, f.problem.split(':').next().unwrap()))
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                (
+                    StateStore::InstallDb,
+                    "install record for a missing font file"
+                ),
+                (
+                    StateStore::InstallDb,
+                    "installed font has no install record"
+                ),
+                (StateStore::MetadataCache, "cannot be read"),
+                (StateStore::ContentStore, "download record for https"),
+            ]
+        );
+        assert!(report
+            .findings
+            .iter()
+            .all(|f| f.resolution == Resolution::Repairable));
+        assert!(
+            paths.metadata_cache.exists(),
+            "a plain check changes nothing"
+        );
+
+        let repaired = check_state(&paths, true);
+        assert!(repaired.unresolved().is_empty(), "{repaired:?}");
+        let db = install_db::load_install_db_from(&paths.install_db).unwrap();
+        assert!(db.find(&gone).is_none());
+        assert_eq!(db.find(&unrecorded).unwrap().scope, FontScope::System);
+        assert!(db.find(&kept).is_some());
+        assert!(!paths.metadata_cache.exists());
+
+        assert!(check_state(&paths, false).findings.is_empty());
+    }
+
+    #[test]
+    fn unreadable_journal_needs_a_person() {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = paths_in(tmp.path());
+        fs::write(&paths.journal, "{ truncated").unwrap();
+
+        let report = check_state(&paths, true);
+        assert_eq!(report.findings.len(), 1);
+        assert!(matches!(
+            report.findings[0].resolution,
+            Resolution::Manual(_)
+        ));
+        assert!(paths.journal.exists(), "the journal is left for inspection");
+    }
+}
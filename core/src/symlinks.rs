@@ -0,0 +1,208 @@
+//! Font files that are symbolic links.
+//!
+//! Homebrew casks, Nix profiles and designers' sync folders often put fonts
+//! in place as symlinks rather than copies. A link and the file it points to
+//! are then two paths for one font, and neither says much about the other:
+//! a link in `~/Library/Fonts` can point into a package store, and a linked
+//! directory can make a system folder look like a user one.
+//!
+//! fontlift treats such paths explicitly:
+//!
+//! - Scope detection and system-font protection look at
+//!   [`canonical_location`]: where the file really sits once every linked
+//!   directory is resolved, keeping the final name so a link is judged by
+//!   the folder it is in rather than by its target.
+//! - Operations on a path that is itself a link follow a [`SymlinkPolicy`]:
+//!   [`Preserve`](SymlinkPolicy::Preserve) works on the link, so removing it
+//!   never deletes its target; [`Resolve`](SymlinkPolicy::Resolve) works on
+//!   the file it points to.
+//! - [`link_file`] installs a font as a link instead of a copy
+//!   (`fontlift install --link`).
+
+use crate::links;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// What a path that is a symbolic link stands for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymlinkPolicy {
+    /// The link itself: removing it removes the link and leaves its target.
+    #[default]
+    Preserve,
+    /// The file the link points to, through any chain of links.
+    Resolve,
+}
+
+impl SymlinkPolicy {
+    /// The path an operation on `path` should act on. Paths that are not
+    /// links come back unchanged under either policy.
+    pub fn apply(self, path: &Path) -> PathBuf {
+        match self {
+            SymlinkPolicy::Resolve if is_symlink(path) => canonical_path(path),
+            _ => path.to_path_buf(),
+        }
+    }
+}
+
+/// Is `path` itself a symbolic link (whether or not its target exists)?
+pub fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_symlink())
+}
+
+/// `path` with every symbolic link resolved, as [`fs::canonicalize`] does,
+/// except that a path which does not exist yet keeps its missing tail after
+/// the longest prefix that does.
+pub fn canonical_path(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut current = path;
+    loop {
+        if let Ok(resolved) = fs::canonicalize(current) {
+            let mut resolved = strip_verbatim(resolved);
+            resolved.extend(missing.iter().rev());
+            return resolved;
+        }
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                current = if parent.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    parent
+                };
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// `path` with its directories resolved but its final name kept, so a link
+/// stays a link: the place the directory entry really lives.
+pub fn canonical_location(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+            canonical_path(parent).join(name)
+        }
+        (Some(_), Some(name)) => canonical_path(Path::new(".")).join(name),
+        _ => canonical_path(path),
+    }
+}
+
+/// Is `path` inside `dir`, either as written or once both are resolved?
+pub fn is_within(path: &Path, dir: &Path) -> bool {
+    path.starts_with(dir) || canonical_location(path).starts_with(canonical_path(dir))
+}
+
+/// Windows canonical paths come back as `\\?\C:\...`; the rest of fontlift
+/// compares against `C:\...`.
+#[cfg(windows)]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    let stripped = path
+        .to_str()
+        .and_then(|text| text.strip_prefix(r"\\?\"))
+        .filter(|rest| !rest.starts_with(r"UNC\"))
+        .map(PathBuf::from);
+    stripped.unwrap_or(path)
+}
+
+#[cfg(not(windows))]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    path
+}
+
+/// Make `link` a symbolic link to `target`, replacing whatever `link` was.
+///
+/// The link points at `target`'s [`canonical_path`], so it keeps working
+/// when `target` was itself reached through a link, and is created beside
+/// `link` then renamed over it, like [`links::copy_file`]. Windows only
+/// allows this to administrators or with Developer Mode on.
+pub fn link_file(target: &Path, link: &Path) -> io::Result<()> {
+    let target = canonical_path(target);
+    let partial = links::partial_path(link);
+    links::remove_partial(link)?;
+    create_symlink(&target, &partial)?;
+    fs::rename(&partial, link).map_err(|e| {
+        let _ = fs::remove_file(&partial);
+        e
+    })
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn create_symlink(_target: &Path, _link: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symbolic links are not supported on this platform",
+    ))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn resolves_linked_directories_but_keeps_linked_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = canonical_path(tmp.path());
+        let store = root.join("store");
+        let fonts = root.join("fonts");
+        fs::create_dir_all(&store).unwrap();
+        fs::create_dir_all(&fonts).unwrap();
+        let real = store.join("Linked-Regular.ttf");
+        fs::write(&real, b"font").unwrap();
+        let link = fonts.join("Linked-Regular.ttf");
+        symlink(&real, &link).unwrap();
+        let alias = root.join("alias");
+        symlink(&fonts, &alias).unwrap();
+
+        assert!(is_symlink(&link) && !is_symlink(&real));
+        assert_eq!(canonical_path(&alias.join("Linked-Regular.ttf")), real);
+        assert_eq!(canonical_location(&alias.join("Linked-Regular.ttf")), link);
+        assert_eq!(
+            canonical_path(&alias.join("New-Regular.ttf")),
+            fonts.join("New-Regular.ttf")
+        );
+        assert!(is_within(&alias.join("Linked-Regular.ttf"), &fonts));
+        assert!(!is_within(&link, &store));
+
+        assert_eq!(SymlinkPolicy::Preserve.apply(&link), link);
+        assert_eq!(SymlinkPolicy::Resolve.apply(&link), real);
+        assert_eq!(SymlinkPolicy::Resolve.apply(&real), real);
+    }
+
+    #[test]
+    fn linking_replaces_the_destination_and_removal_keeps_the_target() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = canonical_path(tmp.path());
+        let real = root.join("Source-Regular.ttf");
+        let hop = root.join("Hop-Regular.ttf");
+        let installed = root.join("Installed-Regular.ttf");
+        fs::write(&real, b"new").unwrap();
+        symlink(&real, &hop).unwrap();
+        fs::write(&installed, b"old").unwrap();
+
+        link_file(&hop, &installed).unwrap();
+        assert_eq!(fs::read_link(&installed).unwrap(), real);
+        assert_eq!(fs::read(&installed).unwrap(), b"new");
+        assert!(!links::partial_path(&installed).exists());
+
+        assert_eq!(
+            links::remove_file(&installed).unwrap(),
+            links::Removed::Symlink
+        );
+        assert!(!installed.exists());
+        assert_eq!(fs::read(&real).unwrap(), b"new");
+    }
+}
@@ -0,0 +1,346 @@
+//! An in-process [`FontManager`] for tests, behind the `testing` feature.
+//!
+//! [`FakeFontManager`] keeps its registry as a file tree under a root
+//! directory, laid out like a macOS home:
+//!
+//! - **User scope:** `<root>/Library/Fonts`
+//! - **System scope:** `<root>/System/Library/Fonts`
+//!
+//! Installing copies the file into the scope's folder, uninstalling and
+//! removing delete it, and listing parses whatever is there. Nothing touches
+//! the OS, no privileges are needed, and the registry vanishes with the
+//! temporary directory it lives in. It follows the trait's re-install
+//! contract: user-scope installs overwrite, system-scope installs fail with
+//! [`FontError::AlreadyInstalled`].
+//!
+//! Tests for slow or flaky platforms can add a delay to every call with
+//! [`FakeFontManager::with_latency`], and make the next call of an
+//! operation fail with [`FakeFontManager::fail_next`]:
+//!
+//! ```no_run
+//! use fontlift_core::testing::{FakeFontManager, FakeOperation};
+//! use fontlift_core::{FontError, FontManager, FontScope, FontliftFontSource};
+//! use std::time::Duration;
+//!
+//! let root = std::env::temp_dir().join("fontlift-fake");
+//! let manager = FakeFontManager::new(&root).with_latency(Duration::from_millis(5));
+//! manager.fail_next(
+//!     FakeOperation::Install,
+//!     FontError::RegistrationFailed("font server busy".to_string()),
+//! );
+//!
+//! let source = FontliftFontSource::new("Demo.ttf".into()).with_scope(Some(FontScope::System));
+//! assert!(manager.install_font(&source).is_err());
+//! manager.install_font(&source)?;
+//! # Ok::<(), FontError>(())
+//! ```
+//!
+//! The `fontlift` binary uses it in place of the OS when run with the hidden
+//! `--fake-registry DIR` flag, which is how the CLI's end-to-end tests drive
+//! it.
+
+use crate::{
+    cache, metadata, protection, validation, FontError, FontManager, FontResult, FontScope,
+    FontliftFontFaceInfo, FontliftFontSource,
+};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The [`FontManager`] calls [`FakeFontManager::fail_next`] can target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FakeOperation {
+    Install,
+    Uninstall,
+    Remove,
+    IsInstalled,
+    List,
+    ClearCaches,
+}
+
+/// A file-tree font registry for tests. See the [module docs](self).
+#[derive(Debug)]
+pub struct FakeFontManager {
+    root: PathBuf,
+    latency: Duration,
+    failures: Mutex<HashMap<FakeOperation, VecDeque<FontError>>>,
+}
+
+impl FakeFontManager {
+    /// A registry rooted at `root`. The directories are created on the
+    /// first install.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            latency: Duration::ZERO,
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sleep for `latency` at the start of every call, before any injected
+    /// failure is returned.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Make the next call of `operation` fail with `error`. Queued errors are
+    /// returned one per call, in order; calls after the queue is empty
+    /// behave normally again.
+    pub fn fail_next(&self, operation: FakeOperation, error: FontError) {
+        self.failures
+            .lock()
+            .expect("fake failures lock")
+            .entry(operation)
+            .or_default()
+            .push_back(error);
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The folder fonts installed in `scope` are copied to.
+    pub fn font_dir(&self, scope: FontScope) -> PathBuf {
+        match scope {
+            FontScope::User => self.root.join("Library/Fonts"),
+            FontScope::System => self.root.join("System/Library/Fonts"),
+        }
+    }
+
+    /// Where `source` lives once installed in its scope (user by default).
+    pub fn installed_path(&self, source: &FontliftFontSource) -> FontResult<PathBuf> {
+        let file_name = source.path.file_name().ok_or_else(|| {
+            FontError::InvalidFormat("Font path must include a file name".to_string())
+        })?;
+        Ok(self
+            .font_dir(source.scope.unwrap_or(FontScope::User))
+            .join(file_name))
+    }
+
+    /// Wait out the latency, then hand back the next injected failure.
+    fn begin(&self, operation: FakeOperation) -> FontResult<()> {
+        if !self.latency.is_zero() {
+            std::thread::sleep(self.latency);
+        }
+        let injected = self
+            .failures
+            .lock()
+            .expect("fake failures lock")
+            .get_mut(&operation)
+            .and_then(VecDeque::pop_front);
+        match injected {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    fn delete_installed(&self, source: &FontliftFontSource) -> FontResult<()> {
+        let target = self.installed_path(source)?;
+        if !target.exists() {
+            return Err(FontError::FontNotFound(target));
+        }
+        fs::remove_file(&target).map_err(FontError::IoError)
+    }
+
+    fn list_scope(&self, scope: FontScope) -> FontResult<Vec<FontliftFontFaceInfo>> {
+        let dir = self.font_dir(scope);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(&dir).map_err(FontError::IoError)? {
+            let path = entry.map_err(FontError::IoError)?.path();
+            if path.is_file() && validation::is_valid_font_extension(&path) {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut fonts = Vec::new();
+        for path in paths {
+            // Placeholder bytes are common in tests; name those by file name.
+            let faces = metadata::read_all_face_info(&path)
+                .unwrap_or_else(|_| vec![validation::extract_basic_info_from_path(&path)]);
+            fonts.extend(faces.into_iter().map(|face| face.with_scope(Some(scope))));
+        }
+        Ok(fonts)
+    }
+}
+
+impl FontManager for FakeFontManager {
+    fn install_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+        self.begin(FakeOperation::Install)?;
+        validation::validate_font_file(&source.path)?;
+
+        let target = self.installed_path(source)?;
+        if target == source.path {
+            return Ok(());
+        }
+        if target.exists() && source.scope == Some(FontScope::System) {
+            return Err(FontError::AlreadyInstalled(target));
+        }
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir).map_err(FontError::IoError)?;
+        }
+        fs::copy(&source.path, &target).map_err(FontError::IoError)?;
+        Ok(())
+    }
+
+    fn uninstall_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+        self.begin(FakeOperation::Uninstall)?;
+        self.delete_installed(source)
+    }
+
+    fn remove_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+        self.begin(FakeOperation::Remove)?;
+        self.delete_installed(source)
+    }
+
+    fn is_font_installed(&self, source: &FontliftFontSource) -> FontResult<bool> {
+        self.begin(FakeOperation::IsInstalled)?;
+        Ok(self.installed_path(source)?.exists())
+    }
+
+    fn list_installed_fonts(&self) -> FontResult<Vec<FontliftFontFaceInfo>> {
+        self.begin(FakeOperation::List)?;
+        let mut fonts = self.list_scope(FontScope::User)?;
+        fonts.extend(self.list_scope(FontScope::System)?);
+        Ok(protection::dedupe_fonts(fonts))
+    }
+
+    fn install_directory(&self, scope: FontScope) -> Option<PathBuf> {
+        Some(self.font_dir(scope))
+    }
+
+    /// There are no caches to clear; succeeds with nothing cleared.
+    fn clear_font_caches(
+        &self,
+        _strategy: cache::CacheClearStrategy,
+    ) -> FontResult<cache::CacheClearResult> {
+        self.begin(FakeOperation::ClearCaches)?;
+        Ok(cache::CacheClearResult::success(0, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontlift_testfonts::TestFont;
+    use std::time::Instant;
+
+    fn scoped(path: &Path, scope: FontScope) -> FontliftFontSource {
+        FontliftFontSource::new(path.to_path_buf()).with_scope(Some(scope))
+    }
+
+    #[test]
+    fn installs_lists_and_removes_in_both_scopes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let manager = FakeFontManager::new(tmp.path().join("root"));
+        let user_font = TestFont::new("Fake User").write_to(tmp.path()).unwrap();
+        let system_font = tmp.path().join("FakeSystem-Bold.otf");
+        fs::write(&system_font, b"placeholder").unwrap();
+
+        manager
+            .install_font(&scoped(&user_font, FontScope::User))
+            .unwrap();
+        manager
+            .install_font(&scoped(&system_font, FontScope::System))
+            .unwrap();
+        assert!(manager
+            .font_dir(FontScope::User)
+            .join(user_font.file_name().unwrap())
+            .is_file());
+
+        let listed: Vec<_> = manager
+            .list_installed_fonts()
+            .unwrap()
+            .into_iter()
+            .map(|face| (face.family_name, face.source.scope))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                ("FakeSystem".to_string(), Some(FontScope::System)),
+                ("Fake User".to_string(), Some(FontScope::User)),
+            ]
+        );
+
+        manager
+            .install_font(&scoped(&user_font, FontScope::User))
+            .expect("user re-installs overwrite");
+        assert!(matches!(
+            manager.install_font(&scoped(&system_font, FontScope::System)),
+            Err(FontError::AlreadyInstalled(_))
+        ));
+
+        manager
+            .remove_font(&scoped(&system_font, FontScope::System))
+            .unwrap();
+        assert!(!manager
+            .is_font_installed(&scoped(&system_font, FontScope::System))
+            .unwrap());
+        assert!(matches!(
+            manager.uninstall_font(&scoped(&system_font, FontScope::System)),
+            Err(FontError::FontNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn user_reinstalls_replace_the_installed_copy() {
+        let tmp = tempfile::tempdir().unwrap();
+        let manager = FakeFontManager::new(tmp.path().join("root"));
+        let font = tmp.path().join("Demo.ttf");
+        let source = scoped(&font, FontScope::User);
+
+        fs::write(&font, b"version-one").unwrap();
+        assert!(!manager.is_font_installed(&source).unwrap());
+        manager.install_font(&source).unwrap();
+        fs::write(&font, b"version-two").unwrap();
+        manager.install_font(&source).unwrap();
+
+        let installed = manager.installed_path(&source).unwrap();
+        assert_eq!(fs::read(&installed).unwrap(), b"version-two");
+        manager
+            .uninstall_font(&scoped(&installed, FontScope::User))
+            .unwrap();
+        assert!(!manager.is_font_installed(&source).unwrap());
+    }
+
+    #[test]
+    fn injected_failures_are_returned_once_each_after_the_latency() {
+        let tmp = tempfile::tempdir().unwrap();
+        let font = tmp.path().join("Flaky.ttf");
+        fs::write(&font, b"placeholder").unwrap();
+        let manager =
+            FakeFontManager::new(tmp.path().join("root")).with_latency(Duration::from_millis(20));
+        manager.fail_next(
+            FakeOperation::Install,
+            FontError::RegistrationFailed("busy".to_string()),
+        );
+        manager.fail_next(
+            FakeOperation::Install,
+            FontError::PermissionDenied("locked".to_string()),
+        );
+
+        let started = Instant::now();
+        let source = FontliftFontSource::new(font);
+        assert!(matches!(
+            manager.install_font(&source),
+            Err(FontError::RegistrationFailed(_))
+        ));
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        assert!(
+            manager.is_font_installed(&source).is_ok(),
+            "other operations are unaffected"
+        );
+        assert!(matches!(
+            manager.install_font(&source),
+            Err(FontError::PermissionDenied(_))
+        ));
+        manager.install_font(&source).unwrap();
+        assert!(manager.is_font_installed(&source).unwrap());
+    }
+}
@@ -0,0 +1,290 @@
+//! PostScript Type 1 fonts, and converting them to OpenType
+//! (`fontlift install --convert`).
+//!
+//! Type 1 is the pre-OpenType PostScript format: outlines in a `.pfb`
+//! (binary) or `.pfa` (ASCII) file, metrics beside them in a `.pfm` or
+//! `.afm`. macOS stopped loading Type 1 in Sonoma and Windows is dropping
+//! it too, so fontlift does not install it. [`detect`] recognizes these
+//! files by extension or, for outline files under another name, by their
+//! leading bytes, so callers can report [`FontError::LegacyType1`] instead
+//! of a bare "invalid extension".
+//!
+//! fontlift has no Type 1 rasterizer of its own; a [`Converter`] runs an
+//! external tool to turn the outlines into an `.otf`. By default that is
+//! FontForge, which also picks up the kerning in a matching `.afm`. Set
+//! `FONTLIFT_TYPE1_CONVERTER` to a command line containing `{input}` and
+//! `{output}` to use something else, e.g. a wrapper around AFDKO's
+//! `makeotf`.
+
+use crate::{FontError, FontResult};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Environment variable naming the converter command line.
+pub const CONVERTER_ENV: &str = "FONTLIFT_TYPE1_CONVERTER";
+
+/// The kinds of Type 1 file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Type1Kind {
+    /// Binary outlines (`.pfb`).
+    Pfb,
+    /// ASCII outlines (`.pfa`).
+    Pfa,
+    /// Windows metrics (`.pfm`); no outlines.
+    Pfm,
+    /// Adobe font metrics (`.afm`); no outlines.
+    Afm,
+}
+
+impl Type1Kind {
+    /// Does the file hold glyph outlines, i.e. can it be converted?
+    pub fn has_outlines(self) -> bool {
+        matches!(self, Type1Kind::Pfb | Type1Kind::Pfa)
+    }
+}
+
+/// Recognize Type 1 data from its first bytes: the segment marker of a
+/// `.pfb`, or the `%!` header of a `.pfa`.
+pub fn sniff(data: &[u8]) -> Option<Type1Kind> {
+    if data.starts_with(&[0x80, 0x01]) {
+        Some(Type1Kind::Pfb)
+    } else if data.starts_with(b"%!PS-AdobeFont") || data.starts_with(b"%!FontType1") {
+        Some(Type1Kind::Pfa)
+    } else {
+        None
+    }
+}
+
+/// Is the file at `path` a Type 1 font file? Decided by extension, or by
+/// [`sniff`]ing files whose extension says nothing about them.
+pub fn detect(path: &Path) -> Option<Type1Kind> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase);
+    match ext.as_deref() {
+        Some("pfb") => return Some(Type1Kind::Pfb),
+        Some("pfa") => return Some(Type1Kind::Pfa),
+        Some("pfm") => return Some(Type1Kind::Pfm),
+        Some("afm") => return Some(Type1Kind::Afm),
+        _ => {}
+    }
+    if crate::validation::is_valid_font_extension(path) {
+        return None;
+    }
+    let mut head = [0u8; 16];
+    let read = std::fs::File::open(path)
+        .and_then(|mut file| std::io::Read::read(&mut file, &mut head))
+        .ok()?;
+    sniff(&head[..read])
+}
+
+/// An external tool that turns Type 1 outlines into an OpenType font.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Converter {
+    program: PathBuf,
+    /// Arguments, with `{input}` and `{output}` still to fill in.
+    args: Vec<String>,
+}
+
+impl Converter {
+    /// Use the command line `template`, split on whitespace; `{input}` and
+    /// `{output}` stand for the Type 1 file and the `.otf` to write.
+    pub fn from_command(template: &str) -> FontResult<Self> {
+        let mut words = template.split_whitespace().map(str::to_string);
+        let program = words.next().ok_or_else(|| {
+            FontError::InvalidArgument(format!("{CONVERTER_ENV} is set but empty"))
+        })?;
+        let args: Vec<String> = words.collect();
+        for placeholder in ["{input}", "{output}"] {
+            if !args.iter().any(|arg| arg.contains(placeholder)) {
+                return Err(FontError::InvalidArgument(format!(
+                    "Type 1 converter command '{template}' has no {placeholder} placeholder"
+                )));
+            }
+        }
+        Ok(Self {
+            program: PathBuf::from(program),
+            args,
+        })
+    }
+
+    /// FontForge at `program`.
+    pub fn fontforge(program: PathBuf) -> Self {
+        Self {
+            program,
+            args: [
+                "-quiet",
+                "-lang=ff",
+                "-c",
+                "Open($1); Generate($2)",
+                "{input}",
+                "{output}",
+            ]
+            .map(str::to_string)
+            .to_vec(),
+        }
+    }
+
+    /// The converter `FONTLIFT_TYPE1_CONVERTER` names, or else FontForge
+    /// if it is installed.
+    pub fn find() -> FontResult<Self> {
+        if let Some(template) = std::env::var_os(CONVERTER_ENV) {
+            return Self::from_command(&template.to_string_lossy());
+        }
+        find_fontforge().map(Self::fontforge).ok_or_else(|| {
+            FontError::UnsupportedOperation(format!(
+                "Converting Type 1 fonts needs FontForge (https://fontforge.org) on the PATH, \
+                 or a converter command in {CONVERTER_ENV}"
+            ))
+        })
+    }
+
+    /// Convert the Type 1 font `source` to `<stem>.otf` in `out_dir`.
+    pub fn convert(&self, source: &Path, out_dir: &Path) -> FontResult<PathBuf> {
+        match detect(source) {
+            Some(kind) if kind.has_outlines() => {}
+            Some(_) => {
+                return Err(FontError::InvalidFormat(format!(
+                    "{} only holds metrics; convert the .pfb or .pfa beside it",
+                    source.display()
+                )))
+            }
+            None => {
+                return Err(FontError::InvalidFormat(format!(
+                    "{} is not a Type 1 font",
+                    source.display()
+                )))
+            }
+        }
+
+        let stem = source.file_stem().unwrap_or_default();
+        let output = out_dir.join(Path::new(stem).with_extension("otf"));
+        let fill = |arg: &String| {
+            arg.replace("{input}", &source.to_string_lossy())
+                .replace("{output}", &output.to_string_lossy())
+        };
+        let result = Command::new(&self.program)
+            .args(self.args.iter().map(fill))
+            .output()
+            .map_err(|e| {
+                FontError::UnsupportedOperation(format!(
+                    "Failed to run the Type 1 converter {}: {e}",
+                    self.program.display()
+                ))
+            })?;
+
+        if !result.status.success() || !output.is_file() {
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            return Err(FontError::InvalidFormat(format!(
+                "Converting {} to OpenType failed: {}",
+                source.display(),
+                stderr.trim().lines().last().unwrap_or("no output written")
+            )));
+        }
+        Ok(output)
+    }
+}
+
+/// FontForge on the PATH, or in the macOS application bundle.
+fn find_fontforge() -> Option<PathBuf> {
+    let name = if cfg!(windows) {
+        "fontforge.exe"
+    } else {
+        "fontforge"
+    };
+    let on_path = std::env::var_os("PATH")
+        .into_iter()
+        .flat_map(|dirs| std::env::split_paths(&dirs).collect::<Vec<_>>())
+        .map(|dir| dir.join(name));
+    let bundled = cfg!(target_os = "macos")
+        .then(|| PathBuf::from("/Applications/FontForge.app/Contents/MacOS/FontForge"));
+    on_path.chain(bundled).find(|candidate| candidate.is_file())
+}
+
+/// A Type 1 font converted for installation. The converted file is deleted
+/// when this is dropped.
+#[derive(Debug)]
+pub struct ConvertedFont {
+    /// The Type 1 file it was converted from.
+    pub source: PathBuf,
+    /// The `.otf` to install.
+    pub path: PathBuf,
+    dir: PathBuf,
+}
+
+impl Drop for ConvertedFont {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Convert `source` with `converter` into a temporary directory of its own.
+pub fn convert_for_install(converter: &Converter, source: &Path) -> FontResult<ConvertedFont> {
+    let dir = std::env::temp_dir().join(format!("fontlift-type1-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).map_err(FontError::IoError)?;
+    // Built before converting so a failure still cleans up the directory.
+    let mut converted = ConvertedFont {
+        source: source.to_path_buf(),
+        path: PathBuf::new(),
+        dir,
+    };
+    converted.path = converter.convert(source, &converted.dir)?;
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn detects_type1_by_extension_and_content() {
+        let tmp = tempfile::tempdir().unwrap();
+        let binary = tmp.path().join("Garamond");
+        fs::write(&binary, [0x80, 0x01, 0x10, 0x00, 0x00, 0x00, b'%', b'!']).unwrap();
+        let ascii = tmp.path().join("Garamond.txt");
+        fs::write(&ascii, b"%!PS-AdobeFont-1.0: Garamond 001.000\n").unwrap();
+        let other = tmp.path().join("notes.txt");
+        fs::write(&other, b"hello").unwrap();
+
+        assert_eq!(detect(&binary), Some(Type1Kind::Pfb));
+        assert_eq!(detect(&ascii), Some(Type1Kind::Pfa));
+        assert_eq!(detect(Path::new("Old.PFM")), Some(Type1Kind::Pfm));
+        assert_eq!(detect(&other), None);
+        assert_eq!(detect(Path::new("Inter.ttf")), None);
+        assert!(!Type1Kind::Afm.has_outlines());
+    }
+
+    #[test]
+    fn converter_commands_need_both_placeholders() {
+        assert!(Converter::from_command("tx -cff {input} {output}").is_ok());
+        assert!(Converter::from_command("tx -cff {input}").is_err());
+        assert!(Converter::from_command("  ").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn converts_through_the_external_command() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("Old.pfb");
+        fs::write(&source, [0x80, 0x01, 0x00]).unwrap();
+
+        let copy = Converter::from_command("cp {input} {output}").unwrap();
+        let converted = convert_for_install(&copy, &source).unwrap();
+        assert_eq!(converted.path.file_name().unwrap(), "Old.otf");
+        assert!(converted.path.is_file());
+        let dir = converted.path.parent().unwrap().to_path_buf();
+        drop(converted);
+        assert!(!dir.exists());
+
+        let failing = Converter::from_command("false {input} {output}").unwrap();
+        let err = failing.convert(&source, tmp.path()).unwrap_err();
+        assert!(err.message().contains("Converting"), "{err}");
+        assert!(copy
+            .convert(&tmp.path().join("Old.pfm"), tmp.path())
+            .is_err());
+    }
+}
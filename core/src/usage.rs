@@ -0,0 +1,545 @@
+//! Which fonts a folder of documents uses (`fontlift scan-usage`).
+//!
+//! Before a cleanup it helps to know which fonts a team's files actually
+//! reference. [`scan`] walks a directory and reads the font names out of
+//! the documents it understands:
+//!
+//! - **CSS** (`.css`, `.scss`, `.less`): every `font-family` declaration,
+//!   without generic families such as `sans-serif`.
+//! - **HTML** (`.html`, `.htm`): the same in `<style>` blocks and `style`
+//!   attributes, plus `<font face="...">`.
+//! - **Word** (`.docx`): the fonts in `word/fontTable.xml`.
+//! - **InDesign markup** (`.idml`): the families in `Resources/Fonts.xml`.
+//! - **PDF**: the PostScript name of every `/BaseFont` and `/FontName`, with
+//!   the `ABCDEF+` subset prefix removed. Font dictionaries inside
+//!   compressed object streams are not seen.
+//!
+//! `.docx` and `.idml` files are zip archives, so reading them needs the
+//! `archive` feature; without it they are reported as skipped.
+//! [`UsageReport::cross_reference`] then marks each name as installed or
+//! missing using the same matching as [`crate::requirements`].
+
+use crate::requirements::Requirement;
+use crate::{FontError, FontResult, FontliftFontFaceInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Documents larger than this are skipped rather than read into memory.
+pub const MAX_DOCUMENT_BYTES: u64 = 256 * 1024 * 1024;
+
+/// CSS keywords and generic families that name no installable font.
+const GENERIC_FAMILIES: &[&str] = &[
+    "serif",
+    "sans-serif",
+    "monospace",
+    "cursive",
+    "fantasy",
+    "system-ui",
+    "ui-serif",
+    "ui-sans-serif",
+    "ui-monospace",
+    "ui-rounded",
+    "emoji",
+    "math",
+    "fangsong",
+    "inherit",
+    "initial",
+    "unset",
+    "revert",
+    "revert-layer",
+    "-apple-system",
+    "blinkmacsystemfont",
+];
+
+/// The kinds of document [`scan`] reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentKind {
+    Css,
+    Html,
+    Docx,
+    Idml,
+    Pdf,
+}
+
+impl DocumentKind {
+    /// The kind of document at `path`, by extension.
+    pub fn of(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "css" | "scss" | "less" => Some(Self::Css),
+            "html" | "htm" => Some(Self::Html),
+            "docx" => Some(Self::Docx),
+            "idml" => Some(Self::Idml),
+            "pdf" => Some(Self::Pdf),
+            _ => None,
+        }
+    }
+}
+
+/// A font the scanned documents reference.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FontReference {
+    /// The family or, for PDFs, the PostScript name.
+    #[serde(flatten)]
+    pub requirement: Requirement,
+    /// The documents that reference it, sorted.
+    pub documents: Vec<PathBuf>,
+    /// Set by [`UsageReport::cross_reference`].
+    pub installed: bool,
+}
+
+/// A document [`scan`] could not read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedDocument {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// What [`scan`] found.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageReport {
+    /// Documents read.
+    pub documents: usize,
+    /// Every referenced font, sorted by name.
+    pub references: Vec<FontReference>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<SkippedDocument>,
+}
+
+impl UsageReport {
+    /// Mark each reference installed if one of `installed` satisfies it.
+    pub fn cross_reference(&mut self, installed: &[FontliftFontFaceInfo]) {
+        for reference in &mut self.references {
+            reference.installed = installed
+                .iter()
+                .any(|face| reference.requirement.matches(face));
+        }
+    }
+
+    /// References no installed face satisfies.
+    pub fn missing(&self) -> Vec<&FontReference> {
+        self.references.iter().filter(|r| !r.installed).collect()
+    }
+}
+
+/// Read the font names from every document under `root` (or from `root`
+/// itself, if it is a document).
+pub fn scan(root: &Path) -> FontResult<UsageReport> {
+    if !root.exists() {
+        return Err(FontError::FontNotFound(root.to_path_buf()));
+    }
+    let mut documents = Vec::new();
+    collect_documents(root, &mut documents)?;
+    documents.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut report = UsageReport::default();
+    let mut found: BTreeMap<(bool, String), FontReference> = BTreeMap::new();
+    for (path, kind) in documents {
+        let requirements = match read_document(&path, kind) {
+            Ok(requirements) => requirements,
+            Err(e) => {
+                report.skipped.push(SkippedDocument {
+                    path,
+                    reason: e.message(),
+                });
+                continue;
+            }
+        };
+        report.documents += 1;
+        for requirement in requirements {
+            let key = (
+                requirement.postscript_name.is_some(),
+                requirement.label().to_lowercase(),
+            );
+            let reference = found.entry(key).or_insert_with(|| FontReference {
+                requirement,
+                documents: Vec::new(),
+                installed: false,
+            });
+            if reference.documents.last() != Some(&path) {
+                reference.documents.push(path.clone());
+            }
+        }
+    }
+    report.references = found.into_values().collect();
+    Ok(report)
+}
+
+fn collect_documents(path: &Path, out: &mut Vec<(PathBuf, DocumentKind)>) -> FontResult<()> {
+    if path.is_file() {
+        if let Some(kind) = DocumentKind::of(path) {
+            out.push((path.to_path_buf(), kind));
+        }
+        return Ok(());
+    }
+    for entry in fs::read_dir(path).map_err(FontError::IoError)? {
+        let entry = entry.map_err(FontError::IoError)?;
+        let file_type = entry.file_type().map_err(FontError::IoError)?;
+        // Symlinks are not followed, so a link cycle cannot loop.
+        if file_type.is_dir() || file_type.is_file() {
+            collect_documents(&entry.path(), out)?;
+        }
+    }
+    Ok(())
+}
+
+/// The fonts one document references.
+pub fn read_document(path: &Path, kind: DocumentKind) -> FontResult<Vec<Requirement>> {
+    let size = fs::metadata(path).map_err(FontError::IoError)?.len();
+    if size > MAX_DOCUMENT_BYTES {
+        return Err(FontError::InvalidFormat(format!(
+            "larger than {} MB",
+            MAX_DOCUMENT_BYTES / (1024 * 1024)
+        )));
+    }
+    let families = |names: Vec<String>| -> Vec<Requirement> {
+        names
+            .into_iter()
+            .map(|family| Requirement {
+                family: Some(family),
+                ..Requirement::default()
+            })
+            .collect()
+    };
+    match kind {
+        DocumentKind::Css => Ok(families(css_families(&read_text(path)?))),
+        DocumentKind::Html => Ok(families(html_families(&read_text(path)?))),
+        DocumentKind::Docx => Ok(families(xml_attribute_values(
+            &zipped::read_entry(path, "word/fontTable.xml")?,
+            "w:font",
+            "w:name",
+        ))),
+        DocumentKind::Idml => Ok(families(xml_attribute_values(
+            &zipped::read_entry(path, "Resources/Fonts.xml")?,
+            "FontFamily",
+            "Name",
+        ))),
+        DocumentKind::Pdf => {
+            let data = fs::read(path).map_err(FontError::IoError)?;
+            Ok(pdf_font_names(&data)
+                .into_iter()
+                .map(|name| Requirement {
+                    postscript_name: Some(name),
+                    ..Requirement::default()
+                })
+                .collect())
+        }
+    }
+}
+
+fn read_text(path: &Path) -> FontResult<String> {
+    let data = fs::read(path).map_err(FontError::IoError)?;
+    Ok(String::from_utf8_lossy(&data).into_owned())
+}
+
+/// The families named in `font-family` declarations.
+pub fn css_families(css: &str) -> Vec<String> {
+    let lower = css.to_ascii_lowercase();
+    let mut families = Vec::new();
+    let mut from = 0;
+    while let Some(found) = lower[from..].find("font-family") {
+        let start = from + found + "font-family".len();
+        from = start;
+        let rest = css[start..].trim_start();
+        let Some(value) = rest.strip_prefix(':') else {
+            continue;
+        };
+        let end = value.find([';', '}', '\n', '>']).unwrap_or(value.len());
+        for name in value[..end].split(',') {
+            let name = name
+                .replace("!important", "")
+                .trim()
+                .trim_matches(['"', '\''])
+                .trim()
+                .to_string();
+            if is_font_name(&name) {
+                push_unique(&mut families, name);
+            }
+        }
+    }
+    families
+}
+
+/// The families named in an HTML page's CSS and `<font face>` tags.
+pub fn html_families(html: &str) -> Vec<String> {
+    let mut families = css_families(html);
+    let lower = html.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(found) = lower[from..].find("<font") {
+        let tag_start = from + found;
+        let tag_end = lower[tag_start..]
+            .find('>')
+            .map_or(lower.len(), |end| tag_start + end);
+        from = tag_end;
+        let tag = &html[tag_start..tag_end];
+        let Some(face) = tag.to_ascii_lowercase().find("face=") else {
+            continue;
+        };
+        let value = &tag[face + "face=".len()..];
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or(""),
+            _ => value.split_whitespace().next().unwrap_or(""),
+        };
+        for name in value.split(',') {
+            let name = name.trim().to_string();
+            if is_font_name(&name) {
+                push_unique(&mut families, name);
+            }
+        }
+    }
+    families
+}
+
+/// The values of `attribute` on every `element` in `xml`, unescaped.
+pub fn xml_attribute_values(xml: &str, element: &str, attribute: &str) -> Vec<String> {
+    let open = format!("<{element}");
+    let mut values = Vec::new();
+    for (start, _) in xml.match_indices(&open) {
+        let tag = &xml[start + open.len()..];
+        // `<w:font` must not match `<w:fonts`.
+        if !tag.starts_with(|c: char| c.is_whitespace()) {
+            continue;
+        }
+        let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+        let needle = format!("{attribute}=\"");
+        let Some((at, _)) = tag
+            .match_indices(&needle)
+            .find(|(at, _)| tag[..*at].ends_with(char::is_whitespace))
+        else {
+            continue;
+        };
+        let value = &tag[at + needle.len()..];
+        let value = unescape_xml(&value[..value.find('"').unwrap_or(value.len())]);
+        if is_font_name(&value) {
+            push_unique(&mut values, value);
+        }
+    }
+    values
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// The PostScript names after `/BaseFont` and `/FontName` keys.
+pub fn pdf_font_names(data: &[u8]) -> Vec<String> {
+    let mut names = Vec::new();
+    for key in [&b"/BaseFont"[..], &b"/FontName"[..]] {
+        let mut from = 0;
+        while let Some(found) = find_bytes(&data[from..], key) {
+            let mut at = from + found + key.len();
+            from = at;
+            while data.get(at).is_some_and(u8::is_ascii_whitespace) {
+                at += 1;
+            }
+            if data.get(at) != Some(&b'/') {
+                continue;
+            }
+            let end = data[at + 1..]
+                .iter()
+                .position(|&b| b.is_ascii_whitespace() || b"/[]<>()%".contains(&b))
+                .map_or(data.len(), |end| at + 1 + end);
+            let name = decode_pdf_name(&data[at + 1..end]);
+            // Subsets are tagged with six capitals and a plus: ABCDEF+Name.
+            let name = match name.split_once('+') {
+                Some((tag, rest))
+                    if tag.len() == 6 && tag.chars().all(|c| c.is_ascii_uppercase()) =>
+                {
+                    rest.to_string()
+                }
+                _ => name,
+            };
+            if is_font_name(&name) {
+                push_unique(&mut names, name);
+            }
+        }
+    }
+    names
+}
+
+/// Undo the `#xx` escapes of a PDF name.
+fn decode_pdf_name(raw: &[u8]) -> String {
+    let mut out = Vec::with_capacity(raw.len());
+    let mut i = 0;
+    while i < raw.len() {
+        let escaped = (raw[i] == b'#' && i + 2 < raw.len())
+            .then(|| std::str::from_utf8(&raw[i + 1..i + 3]).ok())
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(byte) = escaped {
+            out.push(byte);
+            i += 3;
+            continue;
+        }
+        out.push(raw[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn is_font_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with("var(")
+        && !GENERIC_FAMILIES.contains(&name.to_ascii_lowercase().as_str())
+}
+
+fn push_unique(names: &mut Vec<String>, name: String) {
+    if !names.iter().any(|known| known.eq_ignore_ascii_case(&name)) {
+        names.push(name);
+    }
+}
+
+/// Entries of zip-based documents.
+mod zipped {
+    use super::*;
+
+    /// The text of `entry` in the zip archive at `path`.
+    #[cfg(feature = "archive")]
+    pub fn read_entry(path: &Path, entry: &str) -> FontResult<String> {
+        use std::io::Read;
+
+        let file = fs::File::open(path).map_err(FontError::IoError)?;
+        let mut zip = zip::ZipArchive::new(file)
+            .map_err(|e| FontError::InvalidFormat(format!("Invalid zip archive: {e}")))?;
+        let file = zip
+            .by_name(entry)
+            .map_err(|e| FontError::InvalidFormat(format!("No {entry}: {e}")))?;
+        let mut text = String::new();
+        file.take(MAX_DOCUMENT_BYTES)
+            .read_to_string(&mut text)
+            .map_err(FontError::IoError)?;
+        Ok(text)
+    }
+
+    #[cfg(not(feature = "archive"))]
+    pub fn read_entry(_path: &Path, _entry: &str) -> FontResult<String> {
+        Err(FontError::UnsupportedOperation(
+            "Reading .docx and .idml files needs the archive feature".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_families_from_css_and_html() {
+        let css = r#"
+            body { font-family: "Inter Display", Inter, sans-serif; }
+            code { FONT-FAMILY : 'JetBrains Mono' !important ; }
+            h1 { font-family: var(--heading), serif }
+        "#;
+        assert_eq!(
+            css_families(css),
+            ["Inter Display", "Inter", "JetBrains Mono"]
+        );
+
+        let html = r#"<p style="font-family: 'Source Serif 4', Georgia">x</p>
+            <font face="Comic Sans MS, Arial">y</font><font size=2>z</font>"#;
+        assert_eq!(
+            html_families(html),
+            ["Source Serif 4", "Georgia", "Comic Sans MS", "Arial"]
+        );
+    }
+
+    #[test]
+    fn reads_fonts_from_office_xml_and_pdf() {
+        let font_table = r#"<w:fonts><w:font w:name="Calibri"><w:panose1/></w:font>
+            <w:font w:name="Times New Roman"/><w:font w:name="A &amp; B"/></w:fonts>"#;
+        assert_eq!(
+            xml_attribute_values(font_table, "w:font", "w:name"),
+            ["Calibri", "Times New Roman", "A & B"]
+        );
+
+        let pdf = b"<< /Type /Font /BaseFont /ABCDEF+Inter-Bold /Subtype /Type0 >>\n\
+            << /BaseFont/Helvetica >> << /FontName /Minion#20Pro >> /BaseFont [";
+        assert_eq!(
+            pdf_font_names(pdf),
+            ["Inter-Bold", "Helvetica", "Minion Pro"]
+        );
+    }
+
+    #[cfg(feature = "archive")]
+    #[test]
+    fn reads_word_and_indesign_packages() {
+        use std::io::Write;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let package = |name: &str, entry: &str, xml: &str| {
+            let path = tmp.path().join(name);
+            let mut zip = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+            zip.start_file(entry, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+            zip.finish().unwrap();
+            path
+        };
+        let docx = package(
+            "Report.docx",
+            "word/fontTable.xml",
+            r#"<w:fonts><w:font w:name="Aptos"/></w:fonts>"#,
+        );
+        let idml = package(
+            "Brochure.idml",
+            "Resources/Fonts.xml",
+            r#"<idPkg:Fonts><FontFamily Self="di1" Name="Minion Pro"><Font/></FontFamily></idPkg:Fonts>"#,
+        );
+
+        let family =
+            |path: &Path| read_document(path, DocumentKind::of(path).unwrap()).unwrap()[0].label();
+        assert_eq!(family(&docx), "Aptos");
+        assert_eq!(family(&idml), "Minion Pro");
+    }
+
+    #[test]
+    fn scans_a_folder_and_cross_references_installed_fonts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let site = tmp.path().join("site");
+        fs::create_dir_all(&site).unwrap();
+        fs::write(site.join("main.css"), "body { font-family: inter, serif }").unwrap();
+        fs::write(
+            tmp.path().join("index.html"),
+            "<style>h1 { font-family: Inter; } p { font-family: Lora }</style>",
+        )
+        .unwrap();
+        fs::write(tmp.path().join("notes.txt"), "font-family: Ignored").unwrap();
+
+        let mut report = scan(tmp.path()).unwrap();
+        assert_eq!(report.documents, 2);
+        let labels: Vec<_> = report
+            .references
+            .iter()
+            .map(|r| (r.requirement.label(), r.documents.len()))
+            .collect();
+        assert_eq!(labels, [("Inter".to_string(), 2), ("Lora".to_string(), 1)]);
+
+        let inter = FontliftFontFaceInfo::new(
+            crate::FontliftFontSource::new(PathBuf::from("/fonts/Inter.ttf")),
+            "Inter-Regular".into(),
+            "Inter Regular".into(),
+            "Inter".into(),
+            "Regular".into(),
+        );
+        report.cross_reference(&[inter]);
+        let missing: Vec<_> = report
+            .missing()
+            .iter()
+            .map(|r| r.requirement.label())
+            .collect();
+        assert_eq!(missing, ["Lora"]);
+    }
+}
@@ -12,12 +12,29 @@
 //! [`FontError`], a hang is bounded by the per-font timeout, and the
 //! `fontlift` process itself stays alive. See the `fontlift-validator` crate
 //! for the wire protocol and the parsing details.
-
-use crate::{FontError, FontResult, FontliftFontFaceInfo};
+//!
+//! The validator reports collections face by face. Callers still get one
+//! result per path: a collection passes only if every face does, and the
+//! error names the faces that failed.
+//!
+//! Spawning the validator and parsing every table is the slow part of an
+//! install, and retried batch runs validate the same files again. A
+//! [`ValidationCache`] remembers which file contents passed, keyed by
+//! SHA-256, validator version and strictness, so
+//! [`validate_and_introspect_cached`] only sends new or changed files to the
+//! validator. Failures are not cached: a timeout on a busy machine should
+//! not stick. The cache lives in the per-user cache directory
+//! (`FONTLIFT_VALIDATION_CACHE_PATH` overrides it) and, like the metadata
+//! cache, is rebuilt when unreadable.
+
+use crate::{digest, FontError, FontResult, FontliftFontFaceInfo};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Default maximum file size (64 MB)
 pub const DEFAULT_MAX_SIZE: u64 = 64 * 1024 * 1024;
@@ -32,13 +49,17 @@ pub struct ValidatorConfig {
     #[serde(default = "default_max_size")]
     pub max_file_size_bytes: u64,
 
-    /// Timeout per font in milliseconds (default: 5000)
+    /// Timeout per face in milliseconds (default: 5000)
     #[serde(default = "default_timeout_ms")]
     pub timeout_ms: u64,
 
     /// Whether to allow font collections (TTC/OTC)
     #[serde(default = "default_allow_collections")]
     pub allow_collections: bool,
+
+    /// How thoroughly the validator inspects each face's tables
+    #[serde(default)]
+    pub strictness: ValidationStrictness,
 }
 
 fn default_max_size() -> u64 {
@@ -57,18 +78,28 @@ impl Default for ValidatorConfig {
             max_file_size_bytes: DEFAULT_MAX_SIZE,
             timeout_ms: DEFAULT_TIMEOUT_MS,
             allow_collections: true,
+            strictness: ValidationStrictness::Normal,
         }
     }
 }
 
 /// Validation strictness presets
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Besides the limits set by [`ValidatorConfig::from_strictness`], the
+/// preset decides which table checks the validator runs and whether their
+/// findings fail the font.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ValidationStrictness {
-    /// Lenient: larger size limits, longer timeouts, allow collections
+    /// Lenient: larger size limits, longer timeouts, allow collections;
+    /// structural problems are reported as warnings only
     Lenient,
-    /// Normal: default settings
+    /// Normal: default settings; required tables, cmap, metrics and loca
+    /// must be consistent
+    #[default]
     Normal,
-    /// Paranoid: strict limits, shorter timeouts
+    /// Paranoid: strict limits, shorter timeouts, and every glyph outline
+    /// is parsed as well
     Paranoid,
 }
 
@@ -80,12 +111,14 @@ impl ValidatorConfig {
                 max_file_size_bytes: 128 * 1024 * 1024, // 128 MB
                 timeout_ms: 10000,                      // 10 seconds
                 allow_collections: true,
+                strictness,
             },
             ValidationStrictness::Normal => Self::default(),
             ValidationStrictness::Paranoid => Self {
                 max_file_size_bytes: 32 * 1024 * 1024, // 32 MB
                 timeout_ms: 2000,                      // 2 seconds
                 allow_collections: true,
+                strictness,
             },
         }
     }
@@ -98,11 +131,14 @@ struct ValidatorInput {
     config: ValidatorConfig,
 }
 
-/// Result from validator for a single font
+/// Result from validator for a single face
 #[derive(Debug, Deserialize)]
 struct ValidationResult {
     #[allow(dead_code)] // kept for debugging/future use
     path: PathBuf,
+    /// Absent when the file failed before it was split into faces.
+    #[serde(default)]
+    face_index: Option<u32>,
     ok: bool,
     info: Option<FontliftFontFaceInfo>,
     error: Option<String>,
@@ -119,7 +155,8 @@ struct ValidationResult {
 /// * `config` - Validation configuration (size limits, timeouts, etc.)
 ///
 /// # Returns
-/// A vector of results, one per input path, in the same order
+/// A vector of results, one per input path, in the same order. For a
+/// collection the metadata is face 0's, and any failing face fails the path.
 pub fn validate_and_introspect(
     paths: &[PathBuf],
     config: &ValidatorConfig,
@@ -139,8 +176,12 @@ pub fn validate_and_introspect(
     let input_json = serde_json::to_string(&input)
         .map_err(|e| FontError::InvalidFormat(format!("Failed to serialize input: {e}")))?;
 
-    // Spawn validator process
-    let mut child = Command::new(&validator_path)
+    // Spawn validator process, telling it which language to read names in
+    let mut command = Command::new(&validator_path);
+    if let Some(language) = crate::metadata::preferred_language() {
+        command.env("FONTLIFT_LANG", language);
+    }
+    let mut child = command
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -175,21 +216,53 @@ pub fn validate_and_introspect(
     let results: Vec<ValidationResult> = serde_json::from_str(&stdout)
         .map_err(|e| FontError::InvalidFormat(format!("Failed to parse validator output: {e}")))?;
 
-    // Convert to FontResult per font
-    Ok(results
+    let aggregated = aggregate_faces(results);
+    if aggregated.len() != paths.len() {
+        return Err(FontError::InvalidFormat(format!(
+            "Validator returned results for {} fonts, expected {}",
+            aggregated.len(),
+            paths.len()
+        )));
+    }
+    Ok(aggregated)
+}
+
+/// Fold per-face results into one result per path.
+///
+/// A path's results are contiguous, and each starts with face 0 (or with a
+/// result that has no face index, when the whole file failed), which is
+/// where one path ends and the next begins.
+fn aggregate_faces(results: Vec<ValidationResult>) -> Vec<Result<FontliftFontFaceInfo, FontError>> {
+    let mut groups: Vec<Vec<ValidationResult>> = Vec::new();
+    for result in results {
+        match (groups.last_mut(), result.face_index) {
+            (Some(group), Some(index)) if index > 0 => group.push(result),
+            _ => groups.push(vec![result]),
+        }
+    }
+
+    groups
         .into_iter()
-        .map(|r| {
-            if r.ok {
-                r.info
-                    .ok_or_else(|| FontError::InvalidFormat("Missing font info".to_string()))
-            } else {
-                Err(FontError::InvalidFormat(
-                    r.error
-                        .unwrap_or_else(|| "Unknown validation error".to_string()),
-                ))
+        .map(|faces| {
+            let errors: Vec<String> = faces
+                .iter()
+                .filter(|face| !face.ok)
+                .map(|face| {
+                    face.error
+                        .clone()
+                        .unwrap_or_else(|| "Unknown validation error".to_string())
+                })
+                .collect();
+            if !errors.is_empty() {
+                return Err(FontError::InvalidFormat(errors.join("; ")));
             }
+            faces
+                .into_iter()
+                .next()
+                .and_then(|face| face.info)
+                .ok_or_else(|| FontError::InvalidFormat("Missing font info".to_string()))
         })
-        .collect())
+        .collect()
 }
 
 /// Find the fontlift-validator binary
@@ -265,6 +338,216 @@ pub fn validate_single(path: &Path, config: &ValidatorConfig) -> FontResult<Font
         .ok_or_else(|| FontError::InvalidFormat("No validation result".to_string()))?
 }
 
+/// Validator version cache entries are recorded against. The validator
+/// ships with fontlift, so this is fontlift's version.
+pub const VALIDATOR_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Entries kept in the validation cache; the least recently validated
+/// files are dropped beyond this.
+pub const MAX_CACHED_VALIDATIONS: usize = 10_000;
+
+/// Location of the validation cache. `FONTLIFT_VALIDATION_CACHE_PATH`
+/// overrides it.
+pub fn validation_cache_path() -> PathBuf {
+    if let Ok(path) = std::env::var("FONTLIFT_VALIDATION_CACHE_PATH") {
+        return PathBuf::from(path);
+    }
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("fontlift")
+        .join("validation-cache.json")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedValidation {
+    /// What the validator reported, face 0 for a collection.
+    info: FontliftFontFaceInfo,
+    /// Seconds since the Unix epoch.
+    validated_at: u64,
+}
+
+/// Results of earlier successful validations, by file content.
+#[derive(Debug)]
+pub struct ValidationCache {
+    path: Option<PathBuf>,
+    entries: BTreeMap<String, CachedValidation>,
+    /// Validate everything again, recording the fresh results.
+    revalidate: bool,
+    dirty: bool,
+    hits: usize,
+}
+
+impl ValidationCache {
+    /// Open the cache at [`validation_cache_path`].
+    pub fn open() -> Self {
+        Self::load_from(validation_cache_path())
+    }
+
+    /// A cache that never stores anything.
+    pub fn disabled() -> Self {
+        Self {
+            path: None,
+            entries: BTreeMap::new(),
+            revalidate: false,
+            dirty: false,
+            hits: 0,
+        }
+    }
+
+    /// Open the cache stored at `path`. Missing or unreadable files start empty.
+    pub fn load_from(path: PathBuf) -> Self {
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            entries,
+            revalidate: false,
+            dirty: false,
+            hits: 0,
+        }
+    }
+
+    fn key(sha256: &str, strictness: ValidationStrictness) -> String {
+        let strictness = match strictness {
+            ValidationStrictness::Lenient => "lenient",
+            ValidationStrictness::Normal => "normal",
+            ValidationStrictness::Paranoid => "paranoid",
+        };
+        // Names are read in the preferred languages, so results are too.
+        let languages = crate::metadata::name_languages().join(",");
+        format!("{sha256}:{VALIDATOR_VERSION}:{strictness}:{languages}")
+    }
+
+    /// Files whose validation was answered from the cache so far.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Ignore the recorded results and validate every file again
+    /// (`--revalidate`); the fresh results still replace them.
+    pub fn with_revalidate(mut self, revalidate: bool) -> Self {
+        self.revalidate = revalidate;
+        self
+    }
+
+    /// Trim the cache to [`MAX_CACHED_VALIDATIONS`] and write it if it changed.
+    pub fn save(&mut self) -> FontResult<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.dirty {
+            return Ok(());
+        }
+
+        if self.entries.len() > MAX_CACHED_VALIDATIONS {
+            let mut ages: Vec<u64> = self.entries.values().map(|e| e.validated_at).collect();
+            ages.sort_unstable_by(|a, b| b.cmp(a));
+            let cutoff = ages[MAX_CACHED_VALIDATIONS - 1];
+            self.entries.retain(|_, entry| entry.validated_at >= cutoff);
+        }
+
+        let json = serde_json::to_vec(&self.entries).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to encode validation cache: {e}"))
+        })?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(FontError::IoError)?;
+        }
+        let tmp = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+        fs::write(&tmp, json).map_err(FontError::IoError)?;
+        fs::rename(&tmp, path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            FontError::IoError(e)
+        })?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// [`save`](Self::save), logging instead of failing: a cache that cannot
+    /// be written must not fail the install it was meant to speed up.
+    pub fn finish(mut self) {
+        if let Err(e) = self.save() {
+            log::warn!("Could not write font validation cache: {e}");
+        }
+    }
+}
+
+/// [`validate_and_introspect`], skipping the validator for files whose
+/// content `cache` has already seen pass at `config.strictness`.
+///
+/// Files that cannot be hashed are validated as usual, so the validator
+/// still reports what is wrong with them.
+pub fn validate_and_introspect_cached(
+    paths: &[PathBuf],
+    config: &ValidatorConfig,
+    cache: &mut ValidationCache,
+) -> FontResult<Vec<Result<FontliftFontFaceInfo, FontError>>> {
+    if cache.path.is_none() {
+        return validate_and_introspect(paths, config);
+    }
+
+    let keys: Vec<Option<String>> = paths
+        .iter()
+        .map(|path| {
+            digest::sha256_file(path)
+                .ok()
+                .map(|sha256| ValidationCache::key(&sha256, config.strictness))
+        })
+        .collect();
+
+    let mut results: Vec<Option<Result<FontliftFontFaceInfo, FontError>>> = Vec::new();
+    let mut misses = Vec::new();
+    for (path, key) in paths.iter().zip(&keys) {
+        let cached = key
+            .as_ref()
+            .filter(|_| !cache.revalidate)
+            .and_then(|key| cache.entries.get(key));
+        match cached {
+            Some(cached) => {
+                // The same bytes may have been validated under another name.
+                let mut info = cached.info.clone();
+                info.source.path = path.clone();
+                cache.hits += 1;
+                results.push(Some(Ok(info)));
+            }
+            None => {
+                misses.push(path.clone());
+                results.push(None);
+            }
+        }
+    }
+
+    let mut fresh = validate_and_introspect(&misses, config)?.into_iter();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Ok(results
+        .into_iter()
+        .zip(keys)
+        .map(|(result, key)| {
+            if let Some(result) = result {
+                return result;
+            }
+            let result = fresh
+                .next()
+                .unwrap_or_else(|| Err(FontError::InvalidFormat("No validation result".into())));
+            if let (Ok(info), Some(key)) = (&result, key) {
+                cache.entries.insert(
+                    key,
+                    CachedValidation {
+                        info: info.clone(),
+                        validated_at: now,
+                    },
+                );
+                cache.dirty = true;
+            }
+            result
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -279,6 +562,8 @@ mod tests {
         assert!(normal.max_file_size_bytes > paranoid.max_file_size_bytes);
         assert!(lenient.timeout_ms > normal.timeout_ms);
         assert!(normal.timeout_ms > paranoid.timeout_ms);
+        assert_eq!(lenient.strictness, ValidationStrictness::Lenient);
+        assert_eq!(paranoid.strictness, ValidationStrictness::Paranoid);
     }
 
     #[test]
@@ -289,6 +574,92 @@ mod tests {
         assert_eq!(default.max_file_size_bytes, normal.max_file_size_bytes);
         assert_eq!(default.timeout_ms, normal.timeout_ms);
         assert_eq!(default.allow_collections, normal.allow_collections);
+        assert_eq!(default.strictness, normal.strictness);
+    }
+
+    #[test]
+    fn collections_fail_when_any_face_fails() {
+        let face = |path: &str, index: Option<u32>, error: Option<&str>| ValidationResult {
+            path: PathBuf::from(path),
+            face_index: index,
+            ok: error.is_none(),
+            info: error.is_none().then(|| {
+                FontliftFontFaceInfo::new(
+                    crate::FontliftFontSource::new(PathBuf::from(path)).with_face_index(index),
+                    format!("Face{}", index.unwrap_or(0)),
+                    "Face".into(),
+                    "Family".into(),
+                    "Regular".into(),
+                )
+            }),
+            error: error.map(str::to_string),
+        };
+
+        let results = aggregate_faces(vec![
+            face("/a.ttc", Some(0), None),
+            face("/a.ttc", Some(1), Some("Cannot read face 1: out of bounds")),
+            face("/a.ttc", Some(2), None),
+            face("/b.ttc", Some(0), None),
+            face("/b.ttc", Some(1), None),
+            face("/missing.ttf", None, Some("File not found")),
+            face("/c.ttf", Some(0), None),
+        ]);
+
+        assert_eq!(results.len(), 4);
+        assert!(matches!(&results[0], Err(FontError::InvalidFormat(e)) if e.contains("face 1")));
+        assert_eq!(results[1].as_ref().unwrap().postscript_name, "Face0");
+        assert!(results[2].is_err());
+        assert!(results[3].is_ok());
+    }
+
+    #[test]
+    fn cached_validations_skip_the_validator() {
+        let tmp = tempfile::tempdir().unwrap();
+        let font = tmp.path().join("Known.ttf");
+        fs::write(&font, b"validated before").unwrap();
+        let copy = tmp.path().join("Known copy.ttf");
+        fs::write(&copy, b"validated before").unwrap();
+        let cache_path = tmp.path().join("cache.json");
+
+        let mut cache = ValidationCache::load_from(cache_path.clone());
+        let sha256 = digest::sha256_file(&font).unwrap();
+        cache.entries.insert(
+            ValidationCache::key(&sha256, ValidationStrictness::Normal),
+            CachedValidation {
+                info: crate::validation::extract_basic_info_from_path(&font),
+                validated_at: 1,
+            },
+        );
+        cache.dirty = true;
+        cache.save().unwrap();
+
+        // Both files hit, so no validator binary is needed.
+        let mut cache = ValidationCache::load_from(cache_path);
+        let results = validate_and_introspect_cached(
+            &[font.clone(), copy.clone()],
+            &ValidatorConfig::default(),
+            &mut cache,
+        )
+        .unwrap();
+        assert_eq!(cache.hits(), 2);
+        assert_eq!(results[1].as_ref().unwrap().source.path, copy);
+
+        // Another strictness, or changed content, misses.
+        let paranoid = ValidationCache::key(&sha256, ValidationStrictness::Paranoid);
+        assert!(!cache.entries.contains_key(&paranoid));
+        fs::write(&font, b"changed").unwrap();
+        let changed = ValidationCache::key(
+            &digest::sha256_file(&font).unwrap(),
+            ValidationStrictness::Normal,
+        );
+        assert!(!cache.entries.contains_key(&changed));
+
+        // --revalidate sends even known files to the validator.
+        let mut cache = cache.with_revalidate(true);
+        let revalidated =
+            validate_and_introspect_cached(&[copy], &ValidatorConfig::default(), &mut cache);
+        assert_eq!(cache.hits(), 2);
+        assert!(revalidated.map_or(true, |results| results[0].is_err()));
     }
 
     #[test]
@@ -0,0 +1,168 @@
+//! Variation axes and named instances of variable fonts (`fontlift info`).
+//!
+//! A variable font carries its design space in `fvar`: the axes it varies
+//! along and the named instances ("Condensed Bold") the designer picked
+//! out. Menus show the named instances, so they are what users ask for:
+//!
+//! ```text
+//! wght  Weight   100 … 900  (default 400)
+//! Thin           wght=100
+//! Regular        wght=400
+//! Black          wght=900
+//! ```
+//!
+//! Static fonts have no `fvar`, and [`read_variations`] returns `None` for
+//! them.
+
+use crate::{metadata, FontError, FontResult};
+use read_fonts::{tables::name::NameId, FileRef, TableProvider};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// `fvar` axis flag: hide the axis from user interfaces.
+const HIDDEN_AXIS: u16 = 0x0001;
+
+/// One design axis, in user coordinates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariationAxis {
+    /// Four-character tag such as `wght` or `wdth`.
+    pub tag: String,
+    /// Display name from the `name` table, when the font has one.
+    pub name: Option<String>,
+    pub min: f32,
+    pub default: f32,
+    pub max: f32,
+    /// The font asks applications not to show this axis.
+    pub hidden: bool,
+}
+
+/// A named point in the design space.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedInstance {
+    /// Subfamily name, such as `Condensed Bold`.
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postscript_name: Option<String>,
+    /// Axis tag to user coordinate.
+    pub coordinates: BTreeMap<String, f32>,
+}
+
+impl NamedInstance {
+    /// `wght=700 wdth=75`, in tag order.
+    pub fn coordinates_label(&self) -> String {
+        self.coordinates
+            .iter()
+            .map(|(tag, value)| format!("{tag}={value}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariationInfo {
+    pub axes: Vec<VariationAxis>,
+    pub instances: Vec<NamedInstance>,
+}
+
+impl VariationInfo {
+    /// The instance whose name or PostScript name is `query`,
+    /// case-insensitively.
+    pub fn find_instance(&self, query: &str) -> Option<&NamedInstance> {
+        self.instances.iter().find(|instance| {
+            instance.name.eq_ignore_ascii_case(query)
+                || instance
+                    .postscript_name
+                    .as_deref()
+                    .is_some_and(|ps| ps.eq_ignore_ascii_case(query))
+        })
+    }
+}
+
+/// Read the axes and named instances of the face at `face_index` (0 for
+/// single-face files). Returns `Ok(None)` for static fonts.
+pub fn read_variations(path: &Path, face_index: u32) -> FontResult<Option<VariationInfo>> {
+    let data = std::fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let file = FileRef::new(&data)
+        .map_err(|e| FontError::InvalidFormat(format!("{}: {e}", path.display())))?;
+    let font = file
+        .fonts()
+        .nth(face_index as usize)
+        .and_then(Result::ok)
+        .ok_or_else(|| {
+            FontError::InvalidFormat(format!("{}: no face {face_index}", path.display()))
+        })?;
+
+    let Ok(fvar) = font.fvar() else {
+        return Ok(None);
+    };
+    let invalid = |e| FontError::InvalidFormat(format!("{}: fvar: {e}", path.display()));
+    let name = |id: NameId| metadata::name_string(&font, id);
+
+    let axes: Vec<VariationAxis> = fvar
+        .axes()
+        .map_err(invalid)?
+        .iter()
+        .map(|axis| VariationAxis {
+            tag: axis.axis_tag().to_string(),
+            name: name(axis.axis_name_id()),
+            min: axis.min_value().to_f32(),
+            default: axis.default_value().to_f32(),
+            max: axis.max_value().to_f32(),
+            hidden: axis.flags() & HIDDEN_AXIS != 0,
+        })
+        .collect();
+
+    let mut instances = Vec::new();
+    for record in fvar.instances().map_err(invalid)?.iter() {
+        let record = record.map_err(invalid)?;
+        let coordinates = axes
+            .iter()
+            .zip(record.coordinates)
+            .map(|(axis, value)| (axis.tag.clone(), value.get().to_f32()))
+            .collect();
+        instances.push(NamedInstance {
+            name: name(record.subfamily_name_id)
+                .unwrap_or_else(|| format!("Instance {}", instances.len() + 1)),
+            postscript_name: record.post_script_name_id.and_then(name),
+            coordinates,
+        });
+    }
+
+    Ok(Some(VariationInfo { axes, instances }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontlift_testfonts::TestFont;
+
+    #[test]
+    fn reads_axes_and_named_instances() {
+        let tmp = tempfile::tempdir().unwrap();
+        let variable = TestFont::new("Roboto Flex")
+            .with_weight_axis(100, 900)
+            .write_to(tmp.path())
+            .unwrap();
+        let info = read_variations(&variable, 0).unwrap().expect("variable");
+
+        assert_eq!(info.axes.len(), 1);
+        let axis = &info.axes[0];
+        assert_eq!(axis.tag, "wght");
+        assert_eq!(axis.name.as_deref(), Some("Weight"));
+        assert_eq!((axis.min, axis.default, axis.max), (100.0, 400.0, 900.0));
+        assert!(!axis.hidden);
+
+        assert_eq!(info.instances.len(), 9);
+        let bold = info.find_instance("bold").expect("named Bold instance");
+        assert_eq!(bold.coordinates_label(), "wght=700");
+        assert!(info.find_instance("Condensed Bold").is_none());
+
+        let fixed = TestFont::new("Static").write_to(tmp.path()).unwrap();
+        assert_eq!(read_variations(&fixed, 0).unwrap(), None);
+        assert!(read_variations(&fixed, 1).is_err());
+    }
+}
@@ -0,0 +1,489 @@
+//! Disabled fonts (`fontlift disable` / `fontlift enable`).
+//!
+//! Disabling is the reversible alternative to `remove`: a corrupt copy of a
+//! system font that crashes applications can be taken out of service and
+//! put back later. [`disable`] unregisters the font and moves its file into
+//! a per-scope vault; [`enable`] moves it back and registers it again.
+//!
+//! Both are journaled like installs, as a copy followed by a delete, so
+//! `fontlift doctor` can finish a move that was interrupted. The vault keeps
+//! an index of what it holds and where each file came from:
+//!
+//! ```text
+//! <vault>/index.json
+//! <vault>/3f2a91c0/Helvetica.ttc
+//! ```
+//!
+//! Faces the desktop cannot work without (the system UI fonts, the emoji
+//! font, the Windows symbol fonts) cannot be disabled, whatever the
+//! [`ProtectionPolicy`] says; the policy can protect more on top.
+
+use crate::journal::{self, JournalAction};
+use crate::links;
+use crate::protection::ProtectionPolicy;
+use crate::{metadata, FontError, FontManager, FontResult, FontScope, FontliftFontSource};
+use read_fonts::{tables::name::NameId, FileRef};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+/// PostScript names of faces the system UI depends on. A trailing `*`
+/// matches any suffix.
+pub const UI_CRITICAL_FONTS: &[&str] = &[
+    // macOS system UI, menus and emoji
+    ".SF*",
+    ".AppleSystemUIFont*",
+    "SFNS*",
+    "LucidaGrande*",
+    ".LucidaGrande*",
+    "AppleColorEmoji",
+    // Windows shell, dialogs and icon fonts
+    "SegoeUI*",
+    "SegoeMDL2Assets",
+    "SegoeFluentIcons",
+    "Marlett",
+    "Tahoma*",
+    "MicrosoftSansSerif",
+];
+
+/// One font in the vault.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisabledFont {
+    /// Short id, also the name of the vault subdirectory holding the file.
+    pub id: String,
+    /// Where the file was, and where [`enable`] puts it back.
+    pub original_path: PathBuf,
+    pub vault_path: PathBuf,
+    pub scope: FontScope,
+    /// Every face in the file.
+    pub postscript_names: Vec<String>,
+    #[serde(with = "journal::systemtime_serde")]
+    pub disabled_at: SystemTime,
+}
+
+impl DisabledFont {
+    /// Does `query` name this font: its id, a PostScript name, its file
+    /// name, or its original path?
+    pub fn matches(&self, query: &str) -> bool {
+        self.id == query
+            || self
+                .postscript_names
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(query))
+            || self
+                .original_path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(query))
+            || self.original_path == Path::new(query)
+    }
+}
+
+/// The vault's `index.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VaultIndex {
+    pub fonts: Vec<DisabledFont>,
+}
+
+/// Result of [`disable`].
+#[derive(Debug, Clone)]
+pub struct DisableOutcome {
+    pub font: DisabledFont,
+    /// Set when a warn-mode [`ProtectionPolicy`] rule matched.
+    pub warning: Option<String>,
+}
+
+/// Where disabled fonts of `scope` are kept.
+///
+/// `FONTLIFT_VAULT_DIR` overrides the location (with `user` and `system`
+/// subdirectories). Otherwise user fonts go next to the journal, and system
+/// fonts where only administrators can write:
+/// - macOS: `/Library/Application Support/FontLift/Disabled`
+/// - Windows: `%ProgramData%\FontLift\Disabled`
+/// - Linux / other: `/var/lib/fontlift/disabled`
+pub fn vault_dir(scope: FontScope) -> PathBuf {
+    if let Ok(dir) = std::env::var("FONTLIFT_VAULT_DIR") {
+        let sub = match scope {
+            FontScope::User => "user",
+            FontScope::System => "system",
+        };
+        return PathBuf::from(dir).join(sub);
+    }
+
+    if scope == FontScope::User {
+        return journal::journal_path().with_file_name("disabled");
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        PathBuf::from("/Library/Application Support/FontLift/Disabled")
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(r"C:\ProgramData"))
+            .join("FontLift")
+            .join("Disabled")
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        PathBuf::from("/var/lib/fontlift/disabled")
+    }
+}
+
+fn index_path(scope: FontScope) -> PathBuf {
+    vault_dir(scope).join("index.json")
+}
+
+/// The vault index for `scope`. A missing index is an empty vault.
+pub fn load_index(scope: FontScope) -> FontResult<VaultIndex> {
+    let path = index_path(scope);
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| {
+            FontError::InvalidFormat(format!(
+                "Failed to parse disabled-font index {}: {e}",
+                path.display()
+            ))
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(VaultIndex::default()),
+        Err(e) => Err(FontError::IoError(e)),
+    }
+}
+
+/// Load, mutate and save the index for `scope` under the journal lock.
+fn update_index<R>(scope: FontScope, f: impl FnOnce(&mut VaultIndex) -> R) -> FontResult<R> {
+    journal::with_journal_lock(|| {
+        let mut index = load_index(scope)?;
+        let result = f(&mut index);
+
+        let path = index_path(scope);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(FontError::IoError)?;
+        }
+        let content = serde_json::to_string_pretty(&index).map_err(|e| {
+            FontError::InvalidFormat(format!("Failed to serialize disabled-font index: {e}"))
+        })?;
+        let temp_path = path.with_file_name(format!("index.json.tmp.{}", std::process::id()));
+        fs::write(&temp_path, content).map_err(FontError::IoError)?;
+        fs::rename(&temp_path, &path).map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            FontError::IoError(e)
+        })?;
+        Ok(result)
+    })
+}
+
+/// Disabled fonts of both scopes, user first.
+pub fn list_disabled() -> FontResult<Vec<DisabledFont>> {
+    let mut fonts = load_index(FontScope::User)?.fonts;
+    fonts.extend(load_index(FontScope::System)?.fonts);
+    Ok(fonts)
+}
+
+/// The disabled font `query` names (see [`DisabledFont::matches`]).
+pub fn find_disabled(query: &str) -> FontResult<Option<DisabledFont>> {
+    Ok(list_disabled()?
+        .into_iter()
+        .find(|font| font.matches(query)))
+}
+
+/// PostScript names of every face in the file.
+pub fn read_postscript_names(path: &Path) -> FontResult<Vec<String>> {
+    let data = fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => FontError::FontNotFound(path.to_path_buf()),
+        _ => FontError::IoError(e),
+    })?;
+    let file = FileRef::new(&data)
+        .map_err(|e| FontError::InvalidFormat(format!("{}: {e}", path.display())))?;
+    Ok(file
+        .fonts()
+        .flatten()
+        .filter_map(|font| metadata::name_string(&font, NameId::POSTSCRIPT_NAME))
+        .collect())
+}
+
+/// Is one of `postscript_names` a face the system UI needs?
+pub fn is_ui_critical(postscript_names: &[&str]) -> bool {
+    let critical = ProtectionPolicy {
+        protected_postscript_names: UI_CRITICAL_FONTS.iter().map(|s| s.to_string()).collect(),
+        ..ProtectionPolicy::default()
+    };
+    postscript_names
+        .iter()
+        .any(|name| critical.is_protected_name(name))
+}
+
+/// Refuse to disable the font at `path` if the system UI depends on one of
+/// its faces, it is on the sealed macOS system volume, or `policy` denies
+/// it. Returns the policy's warning in warn mode.
+pub fn check_can_disable(
+    path: &Path,
+    postscript_names: &[&str],
+    policy: &ProtectionPolicy,
+) -> FontResult<Option<String>> {
+    let sealed = path
+        .to_string_lossy()
+        .to_lowercase()
+        .starts_with("/system/library/fonts/");
+    if sealed || is_ui_critical(postscript_names) {
+        return Err(FontError::SystemFontProtection(path.to_path_buf()));
+    }
+    policy.check(path, postscript_names)
+}
+
+/// Unregister the font at `path` and move it into the vault for `scope`.
+pub fn disable<M: FontManager + ?Sized>(
+    manager: &M,
+    path: &Path,
+    scope: FontScope,
+    policy: &ProtectionPolicy,
+) -> FontResult<DisableOutcome> {
+    if !path.is_file() {
+        return Err(FontError::FontNotFound(path.to_path_buf()));
+    }
+    let postscript_names = read_postscript_names(path)?;
+    let names: Vec<&str> = postscript_names.iter().map(String::as_str).collect();
+    let warning = check_can_disable(path, &names, policy)?;
+
+    let id = Uuid::new_v4().simple().to_string()[..8].to_string();
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| FontError::InvalidFormat(format!("{} has no file name", path.display())))?;
+    let vault_path = vault_dir(scope).join(&id).join(file_name);
+    let font = DisabledFont {
+        id,
+        original_path: path.to_path_buf(),
+        vault_path: vault_path.clone(),
+        scope,
+        postscript_names,
+        disabled_at: SystemTime::now(),
+    };
+
+    let actions = vec![
+        JournalAction::UnregisterFont {
+            path: path.to_path_buf(),
+            scope,
+        },
+        JournalAction::CopyFile {
+            from: path.to_path_buf(),
+            to: vault_path.clone(),
+        },
+        JournalAction::DeleteFile {
+            path: path.to_path_buf(),
+        },
+    ];
+    let entry_id = journal::update_journal(|j| {
+        Ok(j.record_operation(actions, Some(format!("Disable {}", path.display()))))
+    })?;
+    let finish = || journal::update_journal(|j| j.mark_completed(entry_id));
+
+    let source = FontliftFontSource::new(path.to_path_buf()).with_scope(Some(scope));
+    if let Err(e) = manager.deactivate_font(&source) {
+        let _ = finish();
+        return Err(e);
+    }
+    let _ = journal::update_journal(|j| j.mark_step(entry_id, 1));
+
+    // Put the file back in service if it cannot be moved.
+    let restore = |e: FontError| {
+        let _ = fs::remove_file(&vault_path);
+        let _ = manager.reactivate_font(&source);
+        let _ = finish();
+        e
+    };
+    let copied = vault_path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| links::copy_file(path, &vault_path));
+    if let Err(e) = copied {
+        return Err(restore(FontError::IoError(e)));
+    }
+    let _ = journal::update_journal(|j| j.mark_step(entry_id, 2));
+
+    // Index before deleting, so the vault never holds a file it cannot name.
+    update_index(scope, |index| index.fonts.push(font.clone())).map_err(restore)?;
+    if let Err(e) = fs::remove_file(path) {
+        let _ = update_index(scope, |index| index.fonts.retain(|f| f.id != font.id));
+        return Err(restore(FontError::IoError(e)));
+    }
+    finish()?;
+
+    Ok(DisableOutcome { font, warning })
+}
+
+/// Move a disabled font back to where it was and register it again.
+pub fn enable<M: FontManager + ?Sized>(manager: &M, font: &DisabledFont) -> FontResult<()> {
+    let original = &font.original_path;
+    if original.exists() {
+        return Err(FontError::AlreadyInstalled(original.clone()));
+    }
+    if !font.vault_path.is_file() {
+        return Err(FontError::FontNotFound(font.vault_path.clone()));
+    }
+
+    let actions = vec![
+        JournalAction::CopyFile {
+            from: font.vault_path.clone(),
+            to: original.clone(),
+        },
+        JournalAction::RegisterFont {
+            path: original.clone(),
+            scope: font.scope,
+        },
+        JournalAction::DeleteFile {
+            path: font.vault_path.clone(),
+        },
+    ];
+    let entry_id = journal::update_journal(|j| {
+        Ok(j.record_operation(actions, Some(format!("Enable {}", original.display()))))
+    })?;
+    let finish = || journal::update_journal(|j| j.mark_completed(entry_id));
+
+    let copied = original
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| links::copy_file(&font.vault_path, original));
+    if let Err(e) = copied {
+        let _ = finish();
+        return Err(FontError::IoError(e));
+    }
+    let _ = journal::update_journal(|j| j.mark_step(entry_id, 1));
+
+    let source = FontliftFontSource::new(original.clone()).with_scope(Some(font.scope));
+    if let Err(e) = manager.reactivate_font(&source) {
+        let _ = fs::remove_file(original);
+        let _ = finish();
+        return Err(e);
+    }
+    let _ = journal::update_journal(|j| j.mark_step(entry_id, 2));
+
+    update_index(font.scope, |index| index.fonts.retain(|f| f.id != font.id))?;
+    let _ = fs::remove_file(&font.vault_path);
+    if let Some(dir) = font.vault_path.parent() {
+        let _ = fs::remove_dir(dir);
+    }
+    finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontlift_testfonts::TestFont;
+    use std::sync::Mutex;
+
+    /// Records (de)activations; the font "is registered" while listed.
+    #[derive(Default)]
+    struct RegistryManager {
+        registered: Mutex<Vec<PathBuf>>,
+    }
+
+    impl FontManager for RegistryManager {
+        fn install_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+            self.registered.lock().unwrap().push(source.path.clone());
+            Ok(())
+        }
+        fn uninstall_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+            self.registered
+                .lock()
+                .unwrap()
+                .retain(|p| p != &source.path);
+            Ok(())
+        }
+        fn remove_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+            self.uninstall_font(source)
+        }
+        fn is_font_installed(&self, source: &FontliftFontSource) -> FontResult<bool> {
+            Ok(self.registered.lock().unwrap().contains(&source.path))
+        }
+        fn list_installed_fonts(&self) -> FontResult<Vec<crate::FontliftFontFaceInfo>> {
+            Ok(vec![])
+        }
+        fn clear_font_caches(
+            &self,
+            _: crate::cache::CacheClearStrategy,
+        ) -> FontResult<crate::cache::CacheClearResult> {
+            Ok(crate::cache::CacheClearResult::default())
+        }
+    }
+
+    #[test]
+    fn disable_and_enable_round_trip_through_the_vault() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::env::set_var("FONTLIFT_JOURNAL_PATH", tmp.path().join("journal.json"));
+        std::env::set_var("FONTLIFT_VAULT_DIR", tmp.path().join("vault"));
+        let fonts = tmp.path().join("Fonts");
+        fs::create_dir_all(&fonts).unwrap();
+        let path = TestFont::new("Helvetica").write_to(&fonts).unwrap();
+        let bytes = fs::read(&path).unwrap();
+
+        let manager = RegistryManager::default();
+        manager.registered.lock().unwrap().push(path.clone());
+
+        let outcome = disable(
+            &manager,
+            &path,
+            FontScope::User,
+            &ProtectionPolicy::default(),
+        )
+        .expect("disable");
+        assert!(!path.exists());
+        assert!(outcome
+            .font
+            .vault_path
+            .starts_with(tmp.path().join("vault/user")));
+        assert!(!manager
+            .is_font_installed(&FontliftFontSource::new(path.clone()))
+            .unwrap());
+        let found = find_disabled("helvetica-regular")
+            .unwrap()
+            .expect("indexed");
+        assert_eq!(found.id, outcome.font.id);
+        assert_eq!(found.original_path, path);
+        assert!(journal::load_journal()
+            .unwrap()
+            .incomplete_entries()
+            .is_empty());
+
+        enable(&manager, &found).expect("enable");
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+        assert!(manager
+            .is_font_installed(&FontliftFontSource::new(path.clone()))
+            .unwrap());
+        assert!(list_disabled().unwrap().is_empty());
+        assert!(!found.vault_path.exists());
+    }
+
+    #[test]
+    fn ui_fonts_cannot_be_disabled() {
+        let policy = ProtectionPolicy::default();
+        for (path, name) in [
+            ("/Library/Fonts/SFNS.ttf", "SFNS-Regular"),
+            (r"C:\Windows\Fonts\segoeui.ttf", "SegoeUI"),
+            ("/Library/Fonts/Emoji.ttc", "AppleColorEmoji"),
+        ] {
+            assert!(matches!(
+                check_can_disable(Path::new(path), &[name], &policy),
+                Err(FontError::SystemFontProtection(_))
+            ));
+        }
+        assert!(check_can_disable(
+            Path::new("/System/Library/Fonts/Helvetica.ttc"),
+            &["Helvetica"],
+            &policy
+        )
+        .is_err());
+        assert_eq!(
+            check_can_disable(
+                Path::new("/Library/Fonts/Helvetica.ttc"),
+                &["Helvetica"],
+                &policy
+            )
+            .unwrap(),
+            None
+        );
+    }
+}
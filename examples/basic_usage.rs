@@ -14,7 +14,7 @@
 //! back to `./demo-font.ttf` in the current directory.
 
 use anyhow::Result;
-use fontlift_core::{FontManager, FontScope};
+use fontlift_core::{cache::CacheClearStrategy, FontManager, FontScope};
 use std::path::PathBuf;
 
 fn main() -> Result<()> {
@@ -136,8 +136,8 @@ fn uninstall_font(manager: &std::sync::Arc<dyn FontManager>, font_path: &PathBuf
 
 /// Flush font caches so apps re-read the fonts directory.
 fn clear_caches(manager: &std::sync::Arc<dyn FontManager>) -> Result<()> {
-    match manager.clear_font_caches(FontScope::User) {
-        Ok(()) => println!("  Caches cleared."),
+    match manager.clear_font_caches(CacheClearStrategy::UserOnly) {
+        Ok(result) => println!("  Cleared {} cache entries.", result.entries_cleared),
         Err(e) => println!("  Cache clear failed: {}", e),
     }
 
@@ -0,0 +1,15 @@
+fn main() {
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    // Keep include/fontlift.h in step with the exported functions. A parse
+    // failure should not break the library build; the checked-in header
+    // stays as it was.
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into());
+    match cbindgen::generate(&crate_dir) {
+        Ok(bindings) => {
+            bindings.write_to_file(std::path::Path::new(&crate_dir).join("include/fontlift.h"));
+        }
+        Err(e) => println!("cargo:warning=fontlift.h not regenerated: {e}"),
+    }
+}
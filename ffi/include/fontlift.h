@@ -0,0 +1,130 @@
+/* fontlift C API. Generated by cbindgen from ffi/src/lib.rs; do not edit. */
+
+#ifndef FONTLIFT_H
+#define FONTLIFT_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Version of the C ABI described by `fontlift.h`.
+#define FONTLIFT_ABI_VERSION 1
+
+// Result of every fallible call. `FONTLIFT_STATUS_OK` is 0; errors 1–99
+// mirror fontlift's error codes, 100 and up are FFI-specific.
+typedef enum FontliftStatus {
+  FONTLIFT_STATUS_OK = 0,
+  FONTLIFT_STATUS_FONT_NOT_FOUND = 1,
+  FONTLIFT_STATUS_INVALID_FORMAT = 2,
+  FONTLIFT_STATUS_REGISTRATION_FAILED = 3,
+  FONTLIFT_STATUS_SYSTEM_FONT_PROTECTION = 4,
+  FONTLIFT_STATUS_IO = 5,
+  FONTLIFT_STATUS_PERMISSION_DENIED = 6,
+  FONTLIFT_STATUS_ALREADY_INSTALLED = 7,
+  FONTLIFT_STATUS_UNSUPPORTED_OPERATION = 8,
+  FONTLIFT_STATUS_VERIFICATION_FAILED = 9,
+  FONTLIFT_STATUS_SCRIPT_ERROR = 10,
+  FONTLIFT_STATUS_STALE_CURSOR = 11,
+  FONTLIFT_STATUS_POLICY_DENIED = 12,
+  FONTLIFT_STATUS_LEGACY_TYPE1 = 13,
+  // A NULL pointer, a string that is not UTF-8, or another invalid
+  // argument.
+  FONTLIFT_STATUS_INVALID_ARGUMENT = 100,
+  // fontlift panicked; the message says where.
+  FONTLIFT_STATUS_PANIC = 101,
+} FontliftStatus;
+
+// Where a font is installed.
+typedef enum FontliftScope {
+  // The current user only; no admin rights needed.
+  FONTLIFT_SCOPE_USER = 0,
+  // All users; needs root/Administrator.
+  FONTLIFT_SCOPE_SYSTEM = 1,
+} FontliftScope;
+
+// Opaque handle to a platform font manager.
+typedef struct FontliftManager FontliftManager;
+
+// Bytes owned by the caller; release with [`fontlift_buffer_free`].
+// `data[len]` is a NUL byte, so text can be read as a C string.
+typedef struct FontliftBuffer {
+  uint8_t *data;
+  size_t len;
+} FontliftBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// [`FONTLIFT_ABI_VERSION`] of the loaded library, to compare with the
+// header's at startup.
+uint32_t fontlift_abi_version(void);
+
+// The fontlift release, such as `"5.0.15"`. Static; do not free.
+const char *fontlift_version(void);
+
+// Stable name of a status, such as `"font_not_found"`. Static; do not
+// free.
+const char *fontlift_status_name(enum FontliftStatus status);
+
+// Message and suggestion of the calling thread's last error, or NULL if
+// there was none. Valid until the next failing call on this thread.
+const char *fontlift_last_error_message(void);
+
+// A manager for the current platform, or NULL where fontlift has no
+// platform support (see [`fontlift_last_error_message`]). Release it with
+// [`fontlift_manager_free`].
+struct FontliftManager *fontlift_manager_new(void);
+
+// Release a manager. NULL is ignored.
+//
+// # Safety
+// `manager` must be NULL or come from [`fontlift_manager_new`], and must
+// not be used afterwards.
+void fontlift_manager_free(struct FontliftManager *manager);
+
+// Install the font at `path` in `scope`, like `fontlift install`: the file
+// is validated, copied into the scope's font folder, registered and
+// recorded in the install database and history. A scope, source or family
+// the managed policy rules out fails with `PolicyDenied`.
+//
+// # Safety
+// `manager` must come from [`fontlift_manager_new`]; `path` must be a
+// NUL-terminated UTF-8 string.
+enum FontliftStatus fontlift_install(const struct FontliftManager *manager,
+                                     const char *path,
+                                     enum FontliftScope scope);
+
+// Unregister the font at `path` from `scope`, like `fontlift uninstall`. The
+// file stays on disk. A scope the managed policy rules out fails with
+// `PolicyDenied`, a font the protection policy covers with
+// `SystemFontProtection`.
+//
+// # Safety
+// As for [`fontlift_install`].
+enum FontliftStatus fontlift_uninstall(const struct FontliftManager *manager,
+                                       const char *path,
+                                       enum FontliftScope scope);
+
+// Write the installed faces to `out` as a UTF-8 JSON array, the format of
+// `fontlift list --json`. On failure `out` is left empty.
+//
+// # Safety
+// `manager` must come from [`fontlift_manager_new`]; `out` must point to
+// writable memory for one [`FontliftBuffer`].
+enum FontliftStatus fontlift_list(const struct FontliftManager *manager,
+                                  struct FontliftBuffer *out);
+
+// Release a buffer returned by fontlift. Empty buffers are ignored.
+//
+// # Safety
+// `buffer` must come from fontlift and not have been freed before.
+void fontlift_buffer_free(struct FontliftBuffer buffer);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FONTLIFT_H */
@@ -0,0 +1,555 @@
+//! fontlift-ffi — a C ABI for applications that are not written in Rust.
+//!
+//! C, C++, Swift (via a module map) and C# (via P/Invoke) link against
+//! `libfontlift` and include `include/fontlift.h`, which cbindgen regenerates
+//! from this file on every build.
+//!
+//! ```c
+//! #include "fontlift.h"
+//!
+//! FontliftManager *manager = fontlift_manager_new();
+//! if (fontlift_install(manager, "/tmp/Inter.ttf", FONTLIFT_SCOPE_USER) != FONTLIFT_STATUS_OK) {
+//!     fprintf(stderr, "%s\n", fontlift_last_error_message());
+//! }
+//!
+//! FontliftBuffer json;
+//! if (fontlift_list(manager, &json) == FONTLIFT_STATUS_OK) {
+//!     puts((const char *)json.data);  // same JSON as `fontlift list --json`
+//!     fontlift_buffer_free(json);
+//! }
+//! fontlift_manager_free(manager);
+//! ```
+//!
+//! # Conventions
+//!
+//! - Every fallible function returns a [`FontliftStatus`]. The numbers are
+//!   part of the ABI: existing ones never change, new ones may be added.
+//! - On failure, [`fontlift_last_error_message`] returns the message and
+//!   suggestion for the calling thread's last error.
+//! - Strings are NUL-terminated UTF-8. Buffers the library returns belong to
+//!   the caller and go back through [`fontlift_buffer_free`].
+//! - Panics never cross the boundary; they become
+//!   [`FontliftStatus::Panic`].
+//! - [`FONTLIFT_ABI_VERSION`] changes only when a signature changes
+//!   incompatibly.
+
+use fontlift_core::{
+    operations, protection, ErrorCode, FontError, FontManager, FontScope, FontliftFontSource,
+};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::ptr;
+use std::sync::Arc;
+
+/// Version of the C ABI described by `fontlift.h`.
+pub const FONTLIFT_ABI_VERSION: u32 = 1;
+
+/// Result of every fallible call. `FONTLIFT_STATUS_OK` is 0; errors 1–99
+/// mirror fontlift's error codes, 100 and up are FFI-specific.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontliftStatus {
+    Ok = 0,
+    FontNotFound = 1,
+    InvalidFormat = 2,
+    RegistrationFailed = 3,
+    SystemFontProtection = 4,
+    Io = 5,
+    PermissionDenied = 6,
+    AlreadyInstalled = 7,
+    UnsupportedOperation = 8,
+    VerificationFailed = 9,
+    ScriptError = 10,
+    StaleCursor = 11,
+    PolicyDenied = 12,
+    LegacyType1 = 13,
+    /// A NULL pointer, a string that is not UTF-8, or another invalid
+    /// argument.
+    InvalidArgument = 100,
+    /// fontlift panicked; the message says where.
+    Panic = 101,
+}
+
+impl From<ErrorCode> for FontliftStatus {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::FontNotFound => FontliftStatus::FontNotFound,
+            ErrorCode::InvalidFormat => FontliftStatus::InvalidFormat,
+            ErrorCode::RegistrationFailed => FontliftStatus::RegistrationFailed,
+            ErrorCode::SystemFontProtection => FontliftStatus::SystemFontProtection,
+            ErrorCode::Io => FontliftStatus::Io,
+            ErrorCode::PermissionDenied => FontliftStatus::PermissionDenied,
+            ErrorCode::AlreadyInstalled => FontliftStatus::AlreadyInstalled,
+            ErrorCode::UnsupportedOperation => FontliftStatus::UnsupportedOperation,
+            ErrorCode::VerificationFailed => FontliftStatus::VerificationFailed,
+            ErrorCode::ScriptError => FontliftStatus::ScriptError,
+            ErrorCode::StaleCursor => FontliftStatus::StaleCursor,
+            ErrorCode::PolicyDenied => FontliftStatus::PolicyDenied,
+            ErrorCode::LegacyType1 => FontliftStatus::LegacyType1,
+            ErrorCode::InvalidArgument => FontliftStatus::InvalidArgument,
+        }
+    }
+}
+
+/// Where a font is installed.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontliftScope {
+    /// The current user only; no admin rights needed.
+    User = 0,
+    /// All users; needs root/Administrator.
+    System = 1,
+}
+
+impl From<FontliftScope> for FontScope {
+    fn from(scope: FontliftScope) -> Self {
+        match scope {
+            FontliftScope::User => FontScope::User,
+            FontliftScope::System => FontScope::System,
+        }
+    }
+}
+
+/// Bytes owned by the caller; release with [`fontlift_buffer_free`].
+/// `data[len]` is a NUL byte, so text can be read as a C string.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FontliftBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl FontliftBuffer {
+    fn empty() -> Self {
+        Self {
+            data: ptr::null_mut(),
+            len: 0,
+        }
+    }
+
+    fn from_text(text: String) -> Self {
+        let len = text.len();
+        let mut bytes = text.into_bytes();
+        bytes.push(0);
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        Self { data, len }
+    }
+}
+
+/// Opaque handle to a platform font manager.
+pub struct FontliftManager {
+    inner: Arc<dyn FontManager>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    // Interior NULs would truncate the C string anyway.
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|slot| *slot.borrow_mut() = Some(message));
+}
+
+fn fail(status: FontliftStatus, message: String) -> FontliftStatus {
+    set_last_error(message);
+    status
+}
+
+fn font_error(err: FontError) -> FontliftStatus {
+    fail(err.code().into(), err.to_string())
+}
+
+/// Run `body`, turning panics into [`FontliftStatus::Panic`].
+fn guard(body: impl FnOnce() -> FontliftStatus) -> FontliftStatus {
+    catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|panic| {
+        let detail = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        fail(
+            FontliftStatus::Panic,
+            format!("fontlift panicked: {detail}"),
+        )
+    })
+}
+
+/// # Safety
+/// `manager` must be NULL or come from [`fontlift_manager_new`].
+unsafe fn manager_ref<'a>(
+    manager: *const FontliftManager,
+) -> Result<&'a FontliftManager, FontliftStatus> {
+    // SAFETY: the caller guarantees a valid or NULL pointer.
+    unsafe { manager.as_ref() }.ok_or_else(|| {
+        fail(
+            FontliftStatus::InvalidArgument,
+            "manager is NULL".to_string(),
+        )
+    })
+}
+
+/// # Safety
+/// `path` must be NULL or a NUL-terminated string.
+unsafe fn path_arg(path: *const c_char) -> Result<PathBuf, FontliftStatus> {
+    if path.is_null() {
+        return Err(fail(
+            FontliftStatus::InvalidArgument,
+            "path is NULL".to_string(),
+        ));
+    }
+    // SAFETY: non-NULL and NUL-terminated per the caller's contract.
+    let text = unsafe { CStr::from_ptr(path) };
+    text.to_str().map(PathBuf::from).map_err(|_| {
+        fail(
+            FontliftStatus::InvalidArgument,
+            "path is not valid UTF-8".to_string(),
+        )
+    })
+}
+
+fn platform_manager() -> Option<Arc<dyn FontManager>> {
+    #[cfg(target_os = "macos")]
+    {
+        Some(Arc::new(fontlift_platform_mac::MacFontManager::new()))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        Some(Arc::new(fontlift_platform_win::WinFontManager::new()))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+/// [`FONTLIFT_ABI_VERSION`] of the loaded library, to compare with the
+/// header's at startup.
+#[no_mangle]
+pub extern "C" fn fontlift_abi_version() -> u32 {
+    FONTLIFT_ABI_VERSION
+}
+
+/// The fontlift release, such as `"5.0.15"`. Static; do not free.
+#[no_mangle]
+pub extern "C" fn fontlift_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Stable name of a status, such as `"font_not_found"`. Static; do not
+/// free.
+#[no_mangle]
+pub extern "C" fn fontlift_status_name(status: FontliftStatus) -> *const c_char {
+    let name: &'static str = match status {
+        FontliftStatus::Ok => "ok\0",
+        FontliftStatus::FontNotFound => "font_not_found\0",
+        FontliftStatus::InvalidFormat => "invalid_format\0",
+        FontliftStatus::RegistrationFailed => "registration_failed\0",
+        FontliftStatus::SystemFontProtection => "system_font_protection\0",
+        FontliftStatus::Io => "io\0",
+        FontliftStatus::PermissionDenied => "permission_denied\0",
+        FontliftStatus::AlreadyInstalled => "already_installed\0",
+        FontliftStatus::UnsupportedOperation => "unsupported_operation\0",
+        FontliftStatus::VerificationFailed => "verification_failed\0",
+        FontliftStatus::ScriptError => "script_error\0",
+        FontliftStatus::StaleCursor => "stale_cursor\0",
+        FontliftStatus::PolicyDenied => "policy_denied\0",
+        FontliftStatus::LegacyType1 => "legacy_type1\0",
+        FontliftStatus::InvalidArgument => "invalid_argument\0",
+        FontliftStatus::Panic => "panic\0",
+    };
+    name.as_ptr().cast()
+}
+
+/// Message and suggestion of the calling thread's last error, or NULL if
+/// there was none. Valid until the next failing call on this thread.
+#[no_mangle]
+pub extern "C" fn fontlift_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|slot| {
+        slot.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// A manager for the current platform, or NULL where fontlift has no
+/// platform support (see [`fontlift_last_error_message`]). Release it with
+/// [`fontlift_manager_free`].
+#[no_mangle]
+pub extern "C" fn fontlift_manager_new() -> *mut FontliftManager {
+    let mut manager = None;
+    let status = guard(|| match platform_manager() {
+        Some(inner) => {
+            manager = Some(inner);
+            FontliftStatus::Ok
+        }
+        None => fail(
+            FontliftStatus::UnsupportedOperation,
+            "fontlift has no font manager for this platform".to_string(),
+        ),
+    });
+    match (status, manager) {
+        (FontliftStatus::Ok, Some(inner)) => Box::into_raw(Box::new(FontliftManager { inner })),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Release a manager. NULL is ignored.
+///
+/// # Safety
+/// `manager` must be NULL or come from [`fontlift_manager_new`], and must
+/// not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fontlift_manager_free(manager: *mut FontliftManager) {
+    if !manager.is_null() {
+        // SAFETY: allocated by `fontlift_manager_new`, freed once.
+        drop(unsafe { Box::from_raw(manager) });
+    }
+}
+
+/// Install the font at `path` in `scope`, like `fontlift install`: the file
+/// is validated, copied into the scope's font folder, registered and
+/// recorded in the install database and history. A scope, source or family
+/// the managed policy rules out fails with `PolicyDenied`.
+///
+/// # Safety
+/// `manager` must come from [`fontlift_manager_new`]; `path` must be a
+/// NUL-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn fontlift_install(
+    manager: *const FontliftManager,
+    path: *const c_char,
+    scope: FontliftScope,
+) -> FontliftStatus {
+    guard(|| {
+        // SAFETY: forwarded from this function's contract.
+        let (manager, path) = match unsafe { (manager_ref(manager), path_arg(path)) } {
+            (Ok(manager), Ok(path)) => (manager, path),
+            (Err(status), _) | (_, Err(status)) => return status,
+        };
+        let source = FontliftFontSource::new(path).with_scope(Some(scope.into()));
+        match operations::install(manager.inner.as_ref(), &source) {
+            Ok(()) => FontliftStatus::Ok,
+            Err(err) => font_error(err),
+        }
+    })
+}
+
+/// Unregister the font at `path` from `scope`, like `fontlift uninstall`. The
+/// file stays on disk. A scope the managed policy rules out fails with
+/// `PolicyDenied`, a font the protection policy covers with
+/// `SystemFontProtection`.
+///
+/// # Safety
+/// As for [`fontlift_install`].
+#[no_mangle]
+pub unsafe extern "C" fn fontlift_uninstall(
+    manager: *const FontliftManager,
+    path: *const c_char,
+    scope: FontliftScope,
+) -> FontliftStatus {
+    guard(|| {
+        // SAFETY: forwarded from this function's contract.
+        let (manager, path) = match unsafe { (manager_ref(manager), path_arg(path)) } {
+            (Ok(manager), Ok(path)) => (manager, path),
+            (Err(status), _) | (_, Err(status)) => return status,
+        };
+        let source = FontliftFontSource::new(path).with_scope(Some(scope.into()));
+        match operations::uninstall(manager.inner.as_ref(), &source) {
+            Ok(()) => FontliftStatus::Ok,
+            Err(err) => font_error(err),
+        }
+    })
+}
+
+/// Write the installed faces to `out` as a UTF-8 JSON array, the format of
+/// `fontlift list --json`. On failure `out` is left empty.
+///
+/// # Safety
+/// `manager` must come from [`fontlift_manager_new`]; `out` must point to
+/// writable memory for one [`FontliftBuffer`].
+#[no_mangle]
+pub unsafe extern "C" fn fontlift_list(
+    manager: *const FontliftManager,
+    out: *mut FontliftBuffer,
+) -> FontliftStatus {
+    guard(|| {
+        // SAFETY: `out` is writable per the caller's contract.
+        let Some(out) = (unsafe { out.as_mut() }) else {
+            return fail(FontliftStatus::InvalidArgument, "out is NULL".to_string());
+        };
+        *out = FontliftBuffer::empty();
+        // SAFETY: forwarded from this function's contract.
+        let manager = match unsafe { manager_ref(manager) } {
+            Ok(manager) => manager,
+            Err(status) => return status,
+        };
+        let fonts = match manager.inner.list_installed_fonts() {
+            Ok(fonts) => protection::dedupe_fonts(fonts),
+            Err(err) => return font_error(err),
+        };
+        match serde_json::to_string(&fonts) {
+            Ok(json) => {
+                *out = FontliftBuffer::from_text(json);
+                FontliftStatus::Ok
+            }
+            Err(e) => font_error(FontError::InvalidFormat(format!(
+                "Failed to serialize font list to JSON: {e}"
+            ))),
+        }
+    })
+}
+
+/// Release a buffer returned by fontlift. Empty buffers are ignored.
+///
+/// # Safety
+/// `buffer` must come from fontlift and not have been freed before.
+#[no_mangle]
+pub unsafe extern "C" fn fontlift_buffer_free(buffer: FontliftBuffer) {
+    if buffer.data.is_null() {
+        return;
+    }
+    // SAFETY: `from_text` allocated `len + 1` bytes as a boxed slice.
+    let slice = ptr::slice_from_raw_parts_mut(buffer.data, buffer.len + 1);
+    drop(unsafe { Box::from_raw(slice) });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontlift_core::{DummyFontManager, FontResult, FontliftFontFaceInfo};
+    use fontlift_testfonts::TestFont;
+
+    struct OneFont;
+
+    impl FontManager for OneFont {
+        fn install_font(&self, _source: &FontliftFontSource) -> FontResult<()> {
+            Ok(())
+        }
+        fn uninstall_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+            Err(FontError::FontNotFound(source.path.clone()))
+        }
+        fn remove_font(&self, _source: &FontliftFontSource) -> FontResult<()> {
+            Ok(())
+        }
+        fn is_font_installed(&self, _source: &FontliftFontSource) -> FontResult<bool> {
+            Ok(true)
+        }
+        fn list_installed_fonts(&self) -> FontResult<Vec<FontliftFontFaceInfo>> {
+            Ok(vec![FontliftFontFaceInfo::new(
+                FontliftFontSource::new(PathBuf::from("/fonts/Inter.ttf")),
+                "Inter-Regular".to_string(),
+                "Inter Regular".to_string(),
+                "Inter".to_string(),
+                "Regular".to_string(),
+            )])
+        }
+        fn clear_font_caches(
+            &self,
+            _: fontlift_core::cache::CacheClearStrategy,
+        ) -> FontResult<fontlift_core::cache::CacheClearResult> {
+            Ok(fontlift_core::cache::CacheClearResult::default())
+        }
+    }
+
+    fn handle(inner: Arc<dyn FontManager>) -> *mut FontliftManager {
+        Box::into_raw(Box::new(FontliftManager { inner }))
+    }
+
+    fn last_error() -> String {
+        let message = fontlift_last_error_message();
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn calls_map_errors_to_statuses_and_messages() {
+        // Installs are validated and recorded, so install a real font and
+        // keep the records out of the user's own.
+        let tmp = tempfile::tempdir().unwrap();
+        std::env::set_var(
+            "FONTLIFT_INSTALL_DB_PATH",
+            tmp.path().join("installed.json"),
+        );
+        std::env::set_var("FONTLIFT_HISTORY_PATH", tmp.path().join("history.jsonl"));
+        let font = TestFont::new("Inter").write_to(tmp.path()).unwrap();
+        let path = CString::new(font.to_str().unwrap()).unwrap();
+        let one = handle(Arc::new(OneFont));
+        unsafe {
+            assert_eq!(
+                fontlift_install(one, path.as_ptr(), FontliftScope::User),
+                FontliftStatus::Ok
+            );
+            let status = fontlift_uninstall(one, path.as_ptr(), FontliftScope::System);
+            assert_eq!(status, FontliftStatus::FontNotFound);
+            assert!(last_error().contains("Inter-Regular.ttf"));
+            let name = CStr::from_ptr(fontlift_status_name(status));
+            assert_eq!(name.to_str().unwrap(), "font_not_found");
+
+            assert_eq!(
+                fontlift_install(one, ptr::null(), FontliftScope::User),
+                FontliftStatus::InvalidArgument
+            );
+            assert_eq!(
+                fontlift_install(ptr::null(), path.as_ptr(), FontliftScope::User),
+                FontliftStatus::InvalidArgument
+            );
+            fontlift_manager_free(one);
+
+            let dummy = handle(Arc::new(DummyFontManager));
+            assert_eq!(
+                fontlift_install(dummy, path.as_ptr(), FontliftScope::User),
+                FontliftStatus::UnsupportedOperation
+            );
+            assert!(last_error().contains("not implemented"));
+            fontlift_manager_free(dummy);
+        }
+
+        let policy = tmp.path().join("policy.json");
+        std::fs::write(&policy, r#"{"allow_system_scope": false}"#).unwrap();
+        std::env::set_var("FONTLIFT_POLICY_PATH", &policy);
+        let one = handle(Arc::new(OneFont));
+        let statuses = unsafe {
+            let statuses = [
+                fontlift_install(one, path.as_ptr(), FontliftScope::System),
+                fontlift_uninstall(one, path.as_ptr(), FontliftScope::System),
+            ];
+            fontlift_manager_free(one);
+            statuses
+        };
+        std::env::remove_var("FONTLIFT_POLICY_PATH");
+        assert_eq!(statuses, [FontliftStatus::PolicyDenied; 2]);
+    }
+
+    #[test]
+    fn list_returns_nul_terminated_json() {
+        let one = handle(Arc::new(OneFont));
+        let mut out = FontliftBuffer::empty();
+        unsafe {
+            assert_eq!(fontlift_list(one, &mut out), FontliftStatus::Ok);
+            let json = CStr::from_ptr(out.data.cast()).to_str().unwrap();
+            assert_eq!(json.len(), out.len);
+            let fonts: serde_json::Value = serde_json::from_str(json).unwrap();
+            assert_eq!(fonts[0]["postscript_name"], "Inter-Regular");
+            fontlift_buffer_free(out);
+
+            let dummy = handle(Arc::new(DummyFontManager));
+            let mut out = FontliftBuffer::empty();
+            assert_eq!(
+                fontlift_list(dummy, &mut out),
+                FontliftStatus::UnsupportedOperation
+            );
+            assert!(out.data.is_null());
+            fontlift_buffer_free(out);
+            fontlift_manager_free(one);
+            fontlift_manager_free(dummy);
+        }
+        assert_eq!(FONTLIFT_ABI_VERSION, fontlift_abi_version());
+    }
+}
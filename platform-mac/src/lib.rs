@@ -27,8 +27,22 @@
 //!   primarily for browsers; system-wide use is not guaranteed
 
 use fontlift_core::{
+    cache::{CacheClearResult, CacheClearStrategy, CacheTarget, CacheTargets},
+    environment::{self, ProviderInfo},
+    file_names,
     journal::{self, JournalAction},
-    protection, validation,
+    links,
+    listing::{FontListing, ListFilter},
+    memory::{self, FontHandle, MemoryFontOptions, MemoryRegistration},
+    metadata,
+    metadata_cache::MetadataCache,
+    path_case,
+    plan::PlannedAction,
+    protection,
+    prune::{PruneReason, PrunedRegistration},
+    schedule::{self, ScheduledTask, ScheduledTaskInfo},
+    sources::{SourceKind, SourceRoot},
+    symlinks, validation,
     validation_ext::{self, ValidatorConfig},
     FontError, FontManager, FontResult, FontScope, FontliftFontFaceInfo, FontliftFontSource,
 };
@@ -37,13 +51,17 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use objc2_core_foundation::{
-    CFDictionary, CFError, CFIndex, CFNumber, CFRetained, CFString, CFType, CFURLPathStyle, CFURL,
+    CFData, CFDictionary, CFError, CFIndex, CFNumber, CFRetained, CFString, CFType, CFURLPathStyle,
+    CFURL,
 };
+use objc2_core_graphics::{CGDataProvider, CGFont};
 use objc2_core_text::{
     kCTFontDisplayNameAttribute, kCTFontFamilyNameAttribute, kCTFontFormatAttribute,
     kCTFontNameAttribute, kCTFontStyleNameAttribute, kCTFontSymbolicTrait, kCTFontTraitsAttribute,
-    kCTFontURLAttribute, kCTFontWeightTrait, CTFontDescriptor, CTFontFormat,
-    CTFontManagerRegisterFontsForURL, CTFontManagerScope, CTFontManagerUnregisterFontsForURL,
+    kCTFontURLAttribute, kCTFontWeightTrait, CTFont, CTFontDescriptor, CTFontFormat,
+    CTFontManagerCreateFontDescriptorFromData, CTFontManagerRegisterFontsForURL,
+    CTFontManagerRegisterGraphicsFont, CTFontManagerScope, CTFontManagerUnregisterFontsForURL,
+    CTFontManagerUnregisterGraphicsFont,
 };
 
 // Core Text error codes returned when a font is already known to the system.
@@ -52,6 +70,9 @@ use objc2_core_text::{
 //       the same PostScript name — installing both would confuse applications.
 const K_CT_FONT_MANAGER_ERROR_ALREADY_REGISTERED: isize = 105;
 const K_CT_FONT_MANAGER_ERROR_DUPLICATED_NAME: isize = 305;
+// 201 = kCTFontManagerErrorNotRegistered: unregistering a URL that is not
+//       registered, e.g. one a resumed prune already removed.
+const K_CT_FONT_MANAGER_ERROR_NOT_REGISTERED: isize = 201;
 
 fn test_cache_root() -> Option<PathBuf> {
     env::var_os("FONTLIFT_TEST_CACHE_ROOT").map(PathBuf::from)
@@ -69,24 +90,6 @@ fn user_home(test_root: &Option<PathBuf>) -> FontResult<PathBuf> {
     })
 }
 
-fn fake_registry_root() -> Option<PathBuf> {
-    env::var_os("FONTLIFT_FAKE_REGISTRY_ROOT").map(PathBuf::from)
-}
-
-#[allow(dead_code)]
-fn fake_registry_target(root: &Path, scope: FontScope, source: &Path) -> FontResult<PathBuf> {
-    let file_name = source.file_name().ok_or_else(|| {
-        FontError::InvalidFormat("Font path must include a file name".to_string())
-    })?;
-
-    let base = match scope {
-        FontScope::User => root.join("Library/Fonts"),
-        FontScope::System => root.join("System/Library/Fonts"),
-    };
-
-    Ok(base.join(file_name))
-}
-
 fn delete_matching_files(root: &Path, predicate: impl Fn(&Path) -> bool) -> FontResult<usize> {
     if !root.exists() {
         return Ok(0);
@@ -182,19 +185,242 @@ fn clear_office_font_cache(home: &Path) -> FontResult<usize> {
     purge_directory_contents(&office_cache)
 }
 
-/// Map fontlift scope to the Core Text registration scope.
+fn clear_java_font_cache(home: &Path) -> FontResult<usize> {
+    // Java runtimes keep their font configuration cache (fcinfo*.properties)
+    // under ~/.java/fonts, one directory per Java version.
+    purge_directory_contents(&home.join(".java/fonts"))
+}
+
+fn clear_browser_font_caches(home: &Path) -> FontResult<usize> {
+    // Firefox keeps its system font list in each profile's startupCache and
+    // rebuilds it when the cache is gone.
+    let profiles = home.join("Library/Caches/Firefox/Profiles");
+    let Ok(entries) = fs::read_dir(&profiles) else {
+        return Ok(0);
+    };
+    let mut removed = 0usize;
+    for entry in entries.flatten() {
+        removed += purge_directory_contents(&entry.path().join("startupCache"))?;
+    }
+    Ok(removed)
+}
+
+/// Clear one of the per-user application caches `target` names.
+fn clear_app_cache(home: &Path, target: CacheTarget) -> FontResult<usize> {
+    match target {
+        CacheTarget::System => Ok(0),
+        CacheTarget::Adobe => clear_adobe_font_caches(home),
+        CacheTarget::Office => clear_office_font_cache(home),
+        CacheTarget::Java => clear_java_font_cache(home),
+        CacheTarget::Browser => clear_browser_font_caches(home),
+    }
+}
+
+// launchd label of the one-shot job behind `cleanup --at-reboot`.
+const REBOOT_CACHE_CLEAR_LABEL: &str = "com.fontlaborg.fontlift.reboot-cache-clear";
+
+/// Files under `root`, recursively, that satisfy `predicate`. Unreadable
+/// directories are skipped.
+fn matching_files(root: &Path, predicate: impl Fn(&Path) -> bool) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                stack.push(path);
+            } else if predicate(&path) {
+                files.push(path);
+            }
+        }
+    }
+
+    files
+}
+
+/// The per-user vendor cache files that [`clear_adobe_font_caches`] and
+/// [`clear_office_font_cache`] delete.
+fn vendor_cache_files(home: &Path) -> Vec<PathBuf> {
+    let mut files = matching_files(
+        &home.join("Library/Application Support/Adobe/TypeSupport"),
+        |path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .map(|name| name.starts_with("AdobeFnt") && name.ends_with(".lst"))
+                .unwrap_or(false)
+        },
+    );
+    files.extend(matching_files(
+        &home.join("Library/Caches/Adobe/Fonts"),
+        |_| true,
+    ));
+    files.extend(matching_files(
+        &home.join("Library/Group Containers/UBF8T346G9.Office/FontCache"),
+        |_| true,
+    ));
+    files
+}
+
+/// The per-user vendor cache directories [`vendor_cache_files`] looks in.
+fn vendor_cache_roots(home: &Path) -> Vec<SourceRoot> {
+    [
+        ("Library/Application Support/Adobe/TypeSupport", "Adobe"),
+        ("Library/Caches/Adobe/Fonts", "Adobe"),
+        (
+            "Library/Group Containers/UBF8T346G9.Office/FontCache",
+            "Microsoft Office",
+        ),
+    ]
+    .into_iter()
+    .map(|(dir, owner)| {
+        SourceRoot::new(SourceKind::AppCache, home.join(dir), Some(FontScope::User))
+            .with_owner(owner)
+    })
+    .collect()
+}
+
+/// Where launchd jobs for `scope` live: LaunchAgents for the user,
+/// LaunchDaemons for the system. `FONTLIFT_TEST_CACHE_ROOT` redirects both.
+fn launchd_dir_unchecked(scope: FontScope) -> FontResult<PathBuf> {
+    let test_root = test_cache_root();
+    Ok(match scope {
+        FontScope::User => user_home(&test_root)?.join("Library/LaunchAgents"),
+        FontScope::System => test_root
+            .unwrap_or_else(|| PathBuf::from("/"))
+            .join("Library/LaunchDaemons"),
+    })
+}
+
+/// `launchctl load|unload -w PLIST`; skipped under `FONTLIFT_TEST_CACHE_ROOT`.
+fn launchctl(verb: &str, plist: &Path) -> FontResult<()> {
+    if test_cache_root().is_some() {
+        return Ok(());
+    }
+    let status = std::process::Command::new("/bin/launchctl")
+        .args([verb, "-w"])
+        .arg(plist)
+        .status()
+        .map_err(FontError::IoError)?;
+    if !status.success() {
+        return Err(FontError::RegistrationFailed(format!(
+            "launchctl {verb} failed for {}",
+            plist.display()
+        )));
+    }
+    Ok(())
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// A launchd job that runs `script` once when it is loaded at the next login
+/// (LaunchAgent) or boot (LaunchDaemon). The script is expected to delete the
+/// plist itself so it never runs again.
+fn one_shot_launchd_plist(script: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>/bin/sh</string>
+        <string>-c</string>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        REBOOT_CACHE_CLEAR_LABEL,
+        xml_escape(script)
+    )
+}
+
+/// How a font installed in each fontlift scope becomes visible.
 ///
-/// `CTFontManagerScope::User` registers the font for the current user only,
-/// stored in `~/Library/Fonts`. No admin required.
+/// Core Text has no all-users registration scope. `CTFontManagerScope::User`
+/// and `CTFontManagerScope::Persistent` are the same constant (2): both
+/// register the font for the calling user, across logins. Under `sudo` the
+/// calling user is root, so registering a `/Library/Fonts` copy that way
+/// reached nobody else.
 ///
-/// `CTFontManagerScope::Persistent` registers system-wide under `/Library/Fonts`
-/// so all users on the machine see the font. Requires root or an entitlement.
-/// Despite the name, both scopes persist across reboots; "Persistent" here means
-/// machine-wide rather than session-only.
-fn ct_scope(scope: FontScope) -> CTFontManagerScope {
+/// Fonts in `/Library/Fonts` are instead activated for every user by the
+/// font daemon, simply because the file is there. System-scope installs copy
+/// the file in, make it readable to all users and skip Core Text
+/// registration; removing the file is what deactivates it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Activation {
+    /// `CTFontManagerRegisterFontsForURL` in the persistent per-user scope.
+    UserRegistration,
+    /// Presence in `/Library/Fonts`.
+    FontDirectory,
+}
+
+fn activation(scope: FontScope) -> Activation {
     match scope {
-        FontScope::User => CTFontManagerScope::User,
-        FontScope::System => CTFontManagerScope::Persistent,
+        FontScope::User => Activation::UserRegistration,
+        FontScope::System => Activation::FontDirectory,
+    }
+}
+
+/// Delete an installed font file. When it is one of several hard links,
+/// only that link goes and the other names keep the data; the debug log says
+/// which happened.
+fn remove_installed_file(path: &Path) -> FontResult<()> {
+    let removed = links::remove_file(path).map_err(FontError::IoError)?;
+    log::debug!("{}: {}", path.display(), removed);
+    Ok(())
+}
+
+/// `rw-r--r--`, so every user's font daemon can read a machine-wide font
+/// whatever the permissions of the file it was copied from.
+fn make_world_readable(path: &Path) -> FontResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o644)).map_err(FontError::IoError)
+}
+
+/// A `CGFont` registered with Core Text for this process.
+struct GraphicsFont(CFRetained<CGFont>);
+
+// SAFETY: CGFont is an immutable Core Foundation object, and CF reference
+// counting is thread-safe; Core Text's register/unregister calls may be made
+// from any thread.
+unsafe impl Send for GraphicsFont {}
+unsafe impl Sync for GraphicsFont {}
+
+impl MemoryRegistration for GraphicsFont {
+    fn unregister(&mut self) -> FontResult<()> {
+        let mut error: *mut CFError = std::ptr::null_mut();
+        #[allow(deprecated)]
+        let result = unsafe { CTFontManagerUnregisterGraphicsFont(&self.0, &mut error) };
+        if result {
+            return Ok(());
+        }
+        let err = if error.is_null() {
+            None
+        } else {
+            Some(unsafe { &*error })
+        };
+        Err(FontError::RegistrationFailed(format!(
+            "Core Text failed to unregister in-memory font: {}",
+            cf_error_to_string(err)
+        )))
     }
 }
 
@@ -278,28 +504,19 @@ fn cfurl_to_path(url: &CFURL) -> Option<PathBuf> {
     cf_str.map(|s| PathBuf::from(cf_string_to_rust(&s)))
 }
 
+/// The scope of the folder `path` really sits in: linked directories are
+/// resolved, and a symlinked font counts where the link is.
 fn scope_from_path(path: &Path) -> FontScope {
-    if let Some(fake_root) = fake_registry_root() {
-        let user_fonts = fake_root.join("Library/Fonts");
-        let system_fonts = fake_root.join("System/Library/Fonts");
-
-        if path.starts_with(&user_fonts) {
-            return FontScope::User;
-        }
-
-        if path.starts_with(&system_fonts) {
-            return FontScope::System;
-        }
-    }
-
     if let Ok(home) = std::env::var("HOME") {
         let user_fonts = PathBuf::from(home).join("Library/Fonts");
-        if path.starts_with(&user_fonts) {
+        if symlinks::is_within(path, &user_fonts) {
             return FontScope::User;
         }
     }
 
-    if path.starts_with("/System/Library/Fonts") || path.starts_with("/Library/Fonts") {
+    if symlinks::is_within(path, Path::new("/System/Library/Fonts"))
+        || symlinks::is_within(path, Path::new("/Library/Fonts"))
+    {
         FontScope::System
     } else {
         // Default to user to avoid over-reporting system scope for custom paths
@@ -307,16 +524,6 @@ fn scope_from_path(path: &Path) -> FontScope {
     }
 }
 
-fn normalize_path(path: &Path) -> String {
-    let mut normalized = path.to_string_lossy().replace('\\', "/").to_lowercase();
-
-    while normalized.contains("//") {
-        normalized = normalized.replace("//", "/");
-    }
-
-    normalized
-}
-
 fn font_format_to_string(format: CTFontFormat) -> Option<String> {
     if format == CTFontFormat::OpenTypePostScript {
         Some("OpenTypePostScript".to_string())
@@ -411,6 +618,48 @@ fn get_descriptor_format_attribute(descriptor: &CTFontDescriptor) -> Option<CTFo
     })
 }
 
+/// Take the license and version fields Core Text does not report from
+/// `read`, the same face parsed from the file, and the weight, width, slope
+/// and axes from its tables, which every platform reports alike; Core Text's
+/// own weight is a rough mapping of a float trait.
+fn copy_file_metadata(info: &mut FontliftFontFaceInfo, read: FontliftFontFaceInfo) {
+    info.weight = read.weight.or(info.weight);
+    info.width = read.width.or(info.width);
+    info.italic = read.italic.or(info.italic);
+    info.axes = read.axes;
+    info.license = read.license;
+    info.license_url = read.license_url;
+    info.embedding = read.embedding;
+    info.details = read.details;
+}
+
+/// One [`FontliftFontFaceInfo`] per face Core Text finds in the file at
+/// `url`, in file order, with `face_index` set on the faces after the first
+/// as [`metadata::read_face_info_at`] does. Empty when Core Text cannot
+/// read the file.
+fn descriptor_faces(url: &CFURL) -> Vec<FontliftFontFaceInfo> {
+    let Some(descriptors) =
+        (unsafe { objc2_core_text::CTFontManagerCreateFontDescriptorsFromURL(url) })
+    else {
+        return Vec::new();
+    };
+    let mut faces = Vec::new();
+    for idx in 0..descriptors.count() {
+        let desc_value = unsafe { descriptors.value_at_index(idx) };
+        if desc_value.is_null() {
+            continue;
+        }
+        let descriptor: &CTFontDescriptor = unsafe { &*(desc_value as *const CTFontDescriptor) };
+        if let Some(mut info) = descriptor_to_font_face_info(descriptor) {
+            if idx > 0 {
+                info.source.face_index = Some(idx as u32);
+            }
+            faces.push(info);
+        }
+    }
+    faces
+}
+
 fn descriptor_to_font_face_info(descriptor: &CTFontDescriptor) -> Option<FontliftFontFaceInfo> {
     let path = get_descriptor_url_attribute(descriptor)?;
     let postscript_name =
@@ -486,8 +735,9 @@ fn descriptor_to_font_face_info(descriptor: &CTFontDescriptor) -> Option<Fontlif
                     // Map to the 1–1000 CSS weight scale:
                     //   weight_css = weight_ct * 400 + 500
                     // So -1.0 → 100 (Thin), 0.0 → 500 (Medium), 1.0 → 900 (Black).
-                    // This is an approximation; actual numeric weight comes
-                    // from the font's OS/2 `usWeightClass` table entry.
+                    // This is an approximation, kept only for files fontlift
+                    // cannot parse: `copy_file_metadata` replaces it with
+                    // the font's OS/2 `usWeightClass`.
                     let weight_int = (weight * 400.0 + 500.0).round();
                     if weight_int.is_finite() {
                         let clamped = weight_int.clamp(1.0, 1000.0) as u16;
@@ -511,11 +761,9 @@ fn descriptor_to_font_face_info(descriptor: &CTFontDescriptor) -> Option<Fontlif
 /// - Remove: unregister then delete the file.
 /// - List: query all font descriptors from `CTFontCollectionCreateFromAvailableFonts`.
 ///
-/// `fake_root` exists for testing: set `FONTLIFT_FAKE_REGISTRY_ROOT` to a
-/// temp directory and the manager reads/writes a local file tree instead of
-/// calling Core Text APIs. This makes tests deterministic and portable.
+/// Tests that must not touch Core Text use
+/// `fontlift_core::testing::FakeFontManager` instead.
 pub struct MacFontManager {
-    fake_root: Option<PathBuf>,
     /// Out-of-process font validator. When `Some`, fontlift spawns
     /// `fontlift-validator` before each install to catch malformed files
     /// without risking a crash in the main process.
@@ -525,20 +773,41 @@ pub struct MacFontManager {
 impl MacFontManager {
     /// Create a new macOS font manager
     pub fn new() -> Self {
-        let fake_root = std::env::var_os("FONTLIFT_FAKE_REGISTRY_ROOT").map(PathBuf::from);
-        Self {
-            fake_root,
+        let manager = Self {
             validation_config: None,
-        }
+        };
+        manager.register_environment();
+        manager
     }
 
     /// Create a manager with validation enabled
     pub fn with_validation(config: ValidatorConfig) -> Self {
-        let fake_root = std::env::var_os("FONTLIFT_FAKE_REGISTRY_ROOT").map(PathBuf::from);
-        Self {
-            fake_root,
+        let manager = Self {
             validation_config: Some(config),
-        }
+        };
+        manager.register_environment();
+        manager
+    }
+
+    /// Report this back end and the process's rights for the environment
+    /// recorded with each journal entry.
+    fn register_environment(&self) {
+        let core_text = format!(
+            "macos {}",
+            environment::os_version().unwrap_or_else(|| "unknown".to_string())
+        );
+        environment::register_provider(ProviderInfo {
+            versions: [
+                (
+                    "fontlift-platform-mac".to_string(),
+                    env!("CARGO_PKG_VERSION").to_string(),
+                ),
+                ("CoreText".to_string(), core_text),
+            ]
+            .into_iter()
+            .collect(),
+            elevated: Some(self.has_admin_privileges()),
+        });
     }
 
     /// Enable validation on this manager
@@ -546,11 +815,6 @@ impl MacFontManager {
         self.validation_config = config;
     }
 
-    /// Whether the manager should avoid CoreText and operate against a fake registry root
-    pub fn is_fake_registry_enabled(&self) -> bool {
-        self.fake_root.is_some()
-    }
-
     /// Return the directory where fonts for `scope` should be copied.
     ///
     /// Real paths:
@@ -559,18 +823,7 @@ impl MacFontManager {
     ///
     /// Note: `/System/Library/Fonts` is managed by macOS itself (protected by
     /// SIP). fontlift never installs into that directory.
-    ///
-    /// When `FONTLIFT_FAKE_REGISTRY_ROOT` is set the paths are rooted there
-    /// so tests never touch the real system font directories.
     fn target_directory(&self, scope: FontScope) -> FontResult<PathBuf> {
-        if let Some(root) = &self.fake_root {
-            let dir = match scope {
-                FontScope::User => root.join("Library/Fonts"),
-                FontScope::System => root.join("System/Library/Fonts"),
-            };
-            return Ok(dir);
-        }
-
         let target_dir = match scope {
             FontScope::User => {
                 let home_dir = std::env::var("HOME").map_err(|_| {
@@ -593,18 +846,46 @@ impl MacFontManager {
             FontError::InvalidFormat("Font path must include a file name".to_string())
         })?;
 
-        Ok(self.target_directory(scope)?.join(file_name))
+        // Overlong names get the same deterministic short name on install
+        // and uninstall.
+        file_names::fit_target_path(&self.target_directory(scope)?, &file_name.to_string_lossy())
     }
 
-    /// Extract font information using basic filename parsing as fallback
+    /// Extract font information using basic filename parsing as fallback,
+    /// plus the style, license and embedding bits read from the file.
     fn get_font_info_from_path(&self, path: &Path) -> FontResult<FontliftFontFaceInfo> {
         validation::validate_font_file(path)?;
 
         let mut info = validation::extract_basic_info_from_path(path);
         info.source.scope = Some(scope_from_path(path));
+        if let Ok(read) = metadata::read_face_info(path) {
+            copy_file_metadata(&mut info, read);
+        }
         Ok(info)
     }
 
+    /// Every face of the font at `path`: Core Text's descriptors for the
+    /// file, so each face of a `.ttc` gets its own entry, with the style and
+    /// license read from the matching face of the file. Files Core Text cannot read
+    /// fall back to [`get_font_info_from_path`](Self::get_font_info_from_path).
+    fn get_face_infos_from_path(&self, path: &Path) -> FontResult<Vec<FontliftFontFaceInfo>> {
+        validation::validate_font_file(path)?;
+
+        let mut faces = path_to_cfurl(path)
+            .map(|url| descriptor_faces(&url))
+            .unwrap_or_default();
+        if faces.is_empty() {
+            return Ok(vec![self.get_font_info_from_path(path)?]);
+        }
+        let read = metadata::read_all_face_info(path).unwrap_or_default();
+        for (info, read) in faces.iter_mut().zip(read) {
+            info.source.path = path.to_path_buf();
+            info.source.scope = Some(scope_from_path(path));
+            copy_file_metadata(info, read);
+        }
+        Ok(faces)
+    }
+
     /// Check if path is in system font directory
     fn is_system_font_path(&self, path: &Path) -> bool {
         protection::is_protected_system_font_path(path)
@@ -627,10 +908,7 @@ impl MacFontManager {
             FontError::InvalidFormat("Font path must include a file name".to_string())
         })?;
 
-        if scope == FontScope::System
-            && !self.is_fake_registry_enabled()
-            && !self.has_admin_privileges()
-        {
+        if scope == FontScope::System && !self.has_admin_privileges() {
             return Err(FontError::PermissionDenied(
                 "System-level font installation requires administrator privileges. Run with --admin or use sudo.".to_string(),
             ));
@@ -642,22 +920,19 @@ impl MacFontManager {
         }
 
         // Check if font already exists in target location
-        let target_path = target_dir.join(file_name);
-        if target_path.exists() {
-            if replace_existing {
-                fs::remove_file(&target_path).map_err(FontError::IoError)?;
-            } else {
-                return Err(FontError::AlreadyInstalled(target_path));
-            }
+        let target_path = file_names::fit_target_path(&target_dir, &file_name.to_string_lossy())?;
+        if target_path.exists() && !replace_existing {
+            return Err(FontError::AlreadyInstalled(target_path));
         }
 
-        // Copy font file
-        fs::copy(source_path, &target_path).map_err(FontError::IoError)?;
+        // Copy through a partial file renamed over the target, so a crash
+        // leaves the previous font in place rather than nothing
+        links::copy_file(source_path, &target_path).map_err(FontError::IoError)?;
 
         Ok(target_path)
     }
 
-    fn install_font_core_text(&self, path: &Path, scope: FontScope) -> FontResult<()> {
+    fn install_font_core_text(&self, path: &Path, scope: CTFontManagerScope) -> FontResult<()> {
         // Validate the font prior to registration
         validation::validate_font_file(path)?;
 
@@ -673,8 +948,7 @@ impl MacFontManager {
         };
 
         let mut error: *mut CFError = std::ptr::null_mut();
-        let result =
-            unsafe { CTFontManagerRegisterFontsForURL(&cf_url, ct_scope(scope), &mut error) };
+        let result = unsafe { CTFontManagerRegisterFontsForURL(&cf_url, scope, &mut error) };
 
         if result {
             return Ok(());
@@ -691,7 +965,7 @@ impl MacFontManager {
         if is_conflict_error(error_ref) {
             let mut unregister_error: *mut CFError = std::ptr::null_mut();
             let unregistered = unsafe {
-                CTFontManagerUnregisterFontsForURL(&cf_url, ct_scope(scope), &mut unregister_error)
+                CTFontManagerUnregisterFontsForURL(&cf_url, scope, &mut unregister_error)
             };
 
             if !unregistered {
@@ -708,9 +982,8 @@ impl MacFontManager {
             }
 
             let mut retry_error: *mut CFError = std::ptr::null_mut();
-            let retry = unsafe {
-                CTFontManagerRegisterFontsForURL(&cf_url, ct_scope(scope), &mut retry_error)
-            };
+            let retry =
+                unsafe { CTFontManagerRegisterFontsForURL(&cf_url, scope, &mut retry_error) };
 
             if retry {
                 return Ok(());
@@ -735,56 +1008,33 @@ impl MacFontManager {
         )))
     }
 
-    fn install_font_fake(&self, source: &FontliftFontSource, scope: FontScope) -> FontResult<()> {
-        let path = &source.path;
-        self.copy_font_to_target_directory(path, scope, true)?;
-        Ok(())
-    }
-
-    fn uninstall_font_fake(&self, source: &FontliftFontSource, scope: FontScope) -> FontResult<()> {
-        let target_path = self.installed_target_path(source, scope)?;
-        if target_path.exists() {
-            std::fs::remove_file(&target_path).map_err(FontError::IoError)?;
-            Ok(())
-        } else {
-            Err(FontError::FontNotFound(target_path))
+    /// Make an installed copy visible, as [`activation`] prescribes for
+    /// `scope`.
+    fn activate(&self, target_path: &Path, scope: FontScope) -> FontResult<()> {
+        match activation(scope) {
+            Activation::FontDirectory => make_world_readable(target_path),
+            Activation::UserRegistration => {
+                self.install_font_core_text(target_path, CTFontManagerScope::Persistent)
+            }
         }
     }
 
-    #[allow(dead_code)]
-    fn list_installed_fonts_fake(&self) -> FontResult<Vec<FontliftFontFaceInfo>> {
-        let mut fonts = Vec::new();
-
-        for scope in [FontScope::User, FontScope::System] {
-            let dir = self.target_directory(scope)?;
-            if !dir.exists() {
-                continue;
-            }
-
-            for entry in fs::read_dir(&dir).map_err(FontError::IoError)? {
-                let entry = entry.map_err(FontError::IoError)?;
-                let path = entry.path();
-
-                if !validation::is_valid_font_extension(&path) {
-                    continue;
-                }
-
-                match self.get_font_info_from_path(&path) {
-                    Ok(font) => fonts.push(font.with_scope(Some(scope))),
-                    Err(_) => continue,
-                }
-            }
+    /// Validate system operation permissions
+    /// [`launchd_dir_unchecked`], refusing system scope without admin
+    /// rights (outside tests).
+    fn launchd_dir(&self, scope: FontScope) -> FontResult<PathBuf> {
+        if scope == FontScope::System && test_cache_root().is_none() && !self.has_admin_privileges()
+        {
+            return Err(FontError::PermissionDenied(
+                "System-wide scheduled tasks require administrator privileges. Run with sudo."
+                    .to_string(),
+            ));
         }
-
-        Ok(protection::dedupe_fonts(fonts))
+        launchd_dir_unchecked(scope)
     }
 
-    /// Validate system operation permissions
     fn validate_system_operation(&self, scope: FontScope) -> FontResult<()> {
-        if scope == FontScope::System
-            && !self.is_fake_registry_enabled()
-            && !self.has_admin_privileges()
-        {
+        if scope == FontScope::System && !self.has_admin_privileges() {
             return Err(FontError::PermissionDenied(
                 "System-level font operations require administrator privileges. Run with --admin or use sudo.".to_string()
             ));
@@ -812,16 +1062,12 @@ impl FontManager for MacFontManager {
             validation_ext::validate_single(path, config)?;
         }
 
-        if self.is_system_font_path(path) && !self.is_fake_registry_enabled() {
+        if self.is_system_font_path(path) {
             return Err(FontError::SystemFontProtection(path.to_path_buf()));
         }
 
         let target_path = self.installed_target_path(source, scope)?;
-        let replace_existing = self.is_fake_registry_enabled() || scope == FontScope::User;
-
-        if self.is_fake_registry_enabled() {
-            return self.install_font_fake(source, scope);
-        }
+        let replace_existing = scope == FontScope::User;
 
         // Build journal actions
         let needs_copy = target_path != *path;
@@ -832,17 +1078,16 @@ impl FontManager for MacFontManager {
                 to: target_path.clone(),
             });
         }
-        actions.push(JournalAction::RegisterFont {
-            path: target_path.clone(),
-            scope,
-        });
+        if activation(scope) == Activation::UserRegistration {
+            actions.push(JournalAction::RegisterFont {
+                path: target_path.clone(),
+                scope,
+            });
+        }
 
         // Record operation in journal
-        let entry_id = journal::with_journal_lock(|| {
-            let mut journal = journal::load_journal().unwrap_or_default();
-            let id = journal.record_operation(actions, Some(format!("Install {}", path.display())));
-            journal::save_journal(&journal)?;
-            Ok(id)
+        let entry_id = journal::update_journal(|j| {
+            Ok(j.record_operation(actions, Some(format!("Install {}", path.display()))))
         })?;
 
         // Step 0: Copy file (if needed)
@@ -851,22 +1096,12 @@ impl FontManager for MacFontManager {
             match result {
                 Ok(copied_path) => {
                     // Mark step 0 complete
-                    let _ = journal::with_journal_lock(|| {
-                        let mut j = journal::load_journal().unwrap_or_default();
-                        let _ = j.mark_step(entry_id, 1);
-                        let _ = journal::save_journal(&j);
-                        Ok(())
-                    });
+                    let _ = journal::update_journal(|j| j.mark_step(entry_id, 1));
                     (copied_path, true)
                 }
                 Err(e) => {
                     // Cleanup journal entry on failure
-                    let _ = journal::with_journal_lock(|| {
-                        let mut j = journal::load_journal().unwrap_or_default();
-                        let _ = j.mark_completed(entry_id);
-                        let _ = journal::save_journal(&j);
-                        Ok(())
-                    });
+                    let _ = journal::update_journal(|j| j.mark_completed(entry_id));
                     return Err(e);
                 }
             }
@@ -875,7 +1110,7 @@ impl FontManager for MacFontManager {
         };
 
         // Step 1 (or 0 if no copy): Register font
-        let result = self.install_font_core_text(&target_path, scope);
+        let result = self.activate(&target_path, scope);
 
         // Update journal
         if result.is_err() {
@@ -884,12 +1119,7 @@ impl FontManager for MacFontManager {
                 let _ = fs::remove_file(&target_path);
             }
         }
-        let _ = journal::with_journal_lock(|| {
-            let mut j = journal::load_journal().unwrap_or_default();
-            let _ = j.mark_completed(entry_id);
-            let _ = journal::save_journal(&j);
-            Ok(())
-        });
+        let _ = journal::update_journal(|j| j.mark_completed(entry_id));
 
         result
     }
@@ -900,14 +1130,18 @@ impl FontManager for MacFontManager {
 
         let target_path = self.installed_target_path(source, scope)?;
 
-        if self.is_fake_registry_enabled() {
-            return self.uninstall_font_fake(source, scope);
-        }
-
         if !target_path.exists() {
             return Err(FontError::FontNotFound(target_path));
         }
 
+        if activation(scope) == Activation::FontDirectory {
+            return Err(FontError::UnsupportedOperation(format!(
+                "{} is active for every user while it is in {}; use `fontlift remove --admin` to deactivate it",
+                target_path.display(),
+                self.target_directory(scope)?.display()
+            )));
+        }
+
         // Convert path to CFURL for Core Text
         let cf_url = match path_to_cfurl(&target_path) {
             Some(url) => url,
@@ -920,8 +1154,9 @@ impl FontManager for MacFontManager {
         };
 
         let mut error: *mut CFError = std::ptr::null_mut();
-        let result =
-            unsafe { CTFontManagerUnregisterFontsForURL(&cf_url, ct_scope(scope), &mut error) };
+        let result = unsafe {
+            CTFontManagerUnregisterFontsForURL(&cf_url, CTFontManagerScope::Persistent, &mut error)
+        };
 
         if result {
             Ok(())
@@ -945,72 +1180,49 @@ impl FontManager for MacFontManager {
         let target_path = self.installed_target_path(source, scope)?;
         let installed_source = FontliftFontSource::new(target_path.clone()).with_scope(Some(scope));
 
-        if self.is_system_font_path(&target_path) && !self.is_fake_registry_enabled() {
+        if self.is_system_font_path(&target_path) {
             return Err(FontError::SystemFontProtection(target_path));
         }
 
-        // Skip journaling for fake registry
-        if self.is_fake_registry_enabled() {
-            self.uninstall_font(&installed_source)?;
-            if target_path.exists() {
-                std::fs::remove_file(&target_path).map_err(FontError::IoError)?;
-            }
-            return Ok(());
-        }
-
-        // Build journal actions: UnregisterFont → DeleteFile
-        let actions = vec![
-            JournalAction::UnregisterFont {
+        // Build journal actions: UnregisterFont → DeleteFile. Machine-wide
+        // fonts are never registered; deleting the file deactivates them.
+        let registered = activation(scope) == Activation::UserRegistration;
+        let mut actions = Vec::new();
+        if registered {
+            actions.push(JournalAction::UnregisterFont {
                 path: target_path.clone(),
                 scope,
-            },
-            JournalAction::DeleteFile {
-                path: target_path.clone(),
-            },
-        ];
+            });
+        }
+        actions.push(JournalAction::DeleteFile {
+            path: target_path.clone(),
+        });
 
         // Record operation in journal
-        let entry_id = journal::with_journal_lock(|| {
-            let mut journal = journal::load_journal().unwrap_or_default();
-            let id = journal
-                .record_operation(actions, Some(format!("Remove {}", target_path.display())));
-            journal::save_journal(&journal)?;
-            Ok(id)
+        let entry_id = journal::update_journal(|j| {
+            Ok(j.record_operation(actions, Some(format!("Remove {}", target_path.display()))))
         })?;
 
-        // Step 0: Unregister font
-        let unregister_result = self.uninstall_font(&installed_source);
-        if let Err(e) = unregister_result {
-            // Mark completed (nothing to recover from unregister failure)
-            let _ = journal::with_journal_lock(|| {
-                let mut j = journal::load_journal().unwrap_or_default();
-                let _ = j.mark_completed(entry_id);
-                let _ = journal::save_journal(&j);
-                Ok(())
-            });
-            return Err(e);
-        }
+        if registered {
+            // Step 0: Unregister font
+            let unregister_result = self.uninstall_font(&installed_source);
+            if let Err(e) = unregister_result {
+                // Mark completed (nothing to recover from unregister failure)
+                let _ = journal::update_journal(|j| j.mark_completed(entry_id));
+                return Err(e);
+            }
 
-        // Mark step 0 complete
-        let _ = journal::with_journal_lock(|| {
-            let mut j = journal::load_journal().unwrap_or_default();
-            let _ = j.mark_step(entry_id, 1);
-            let _ = journal::save_journal(&j);
-            Ok(())
-        });
+            // Mark step 0 complete
+            let _ = journal::update_journal(|j| j.mark_step(entry_id, 1));
+        }
 
         // Step 1: Delete file
         if target_path.exists() {
-            std::fs::remove_file(&target_path).map_err(FontError::IoError)?;
+            remove_installed_file(&target_path)?;
         }
 
         // Mark operation completed
-        let _ = journal::with_journal_lock(|| {
-            let mut j = journal::load_journal().unwrap_or_default();
-            let _ = j.mark_completed(entry_id);
-            let _ = journal::save_journal(&j);
-            Ok(())
-        });
+        let _ = journal::update_journal(|j| j.mark_completed(entry_id));
 
         Ok(())
     }
@@ -1019,17 +1231,13 @@ impl FontManager for MacFontManager {
         let scope = source.scope.unwrap_or(FontScope::User);
         let target_path = self.installed_target_path(source, scope)?;
 
-        if self.is_fake_registry_enabled() {
-            return Ok(target_path.exists());
-        }
-
         if target_path.exists() {
             return Ok(true);
         }
 
         let font_array = unsafe { objc2_core_text::CTFontManagerCopyAvailableFontURLs() };
 
-        let normalized_target = normalize_path(&target_path);
+        let normalized_target = path_case::comparison_key(&target_path);
         let count = font_array.count();
 
         for i in 0..count {
@@ -1048,7 +1256,7 @@ impl FontManager for MacFontManager {
 
             let cf_url: &CFURL = unsafe { &*(value as *const CFURL) };
             if let Some(path) = cfurl_to_path(cf_url) {
-                if normalize_path(&path) == normalized_target {
+                if path_case::comparison_key(&path) == normalized_target {
                     return Ok(true);
                 }
             }
@@ -1058,14 +1266,20 @@ impl FontManager for MacFontManager {
     }
 
     fn list_installed_fonts(&self) -> FontResult<Vec<FontliftFontFaceInfo>> {
-        if self.is_fake_registry_enabled() {
-            return self.list_installed_fonts_fake();
-        }
+        Ok(self
+            .list_installed_fonts_report(&ListFilter::default())?
+            .fonts)
+    }
 
+    /// Core Text lists every font URL it knows; files among them that
+    /// cannot be read are warnings instead of silently left out.
+    fn list_installed_fonts_report(&self, filter: &ListFilter) -> FontResult<FontListing> {
         // Get all available font URLs from Core Text
         let font_array = unsafe { objc2_core_text::CTFontManagerCopyAvailableFontURLs() };
 
+        let mut listing = FontListing::default();
         let mut fonts = Vec::new();
+        let mut cache = MetadataCache::open();
         let count = font_array.count();
 
         for i in 0..count {
@@ -1083,194 +1297,536 @@ impl FontManager for MacFontManager {
             }
 
             let cf_url: &CFURL = unsafe { &*(value as *const CFURL) };
-
-            // Try to pull rich metadata via font descriptors first
-            let descriptors =
-                unsafe { objc2_core_text::CTFontManagerCreateFontDescriptorsFromURL(cf_url) };
-
-            if let Some(descriptor_array) = descriptors {
-                let desc_count = descriptor_array.count();
-
-                for idx in 0..desc_count {
-                    let desc_value = unsafe { descriptor_array.value_at_index(idx) };
-                    if desc_value.is_null() {
-                        continue;
-                    }
-
-                    let descriptor: &CTFontDescriptor =
-                        unsafe { &*(desc_value as *const CTFontDescriptor) };
-                    if let Some(info) = descriptor_to_font_face_info(descriptor) {
-                        fonts.push(info);
-                        continue;
-                    }
-                }
+            let Some(path) = cfurl_to_path(cf_url) else {
+                continue;
+            };
+            if !path.exists() || !validation::is_valid_font_extension(&path) {
+                continue;
             }
 
-            // Fallback: basic info from path
-            if let Some(path) = cfurl_to_path(cf_url) {
-                if !path.exists() || !validation::is_valid_font_extension(&path) {
-                    continue;
-                }
-
-                match self.get_font_info_from_path(&path) {
-                    Ok(mut font_info) => {
-                        font_info.source.scope = Some(scope_from_path(&path));
-                        fonts.push(font_info);
-                    }
-                    Err(_) => {
-                        // Skip fonts we can't read, but don't fail the entire operation
-                        continue;
-                    }
-                }
+            // One entry per face (every face of a collection), from the
+            // cache or Core Text's descriptors for the file.
+            match cache.faces(&path, |p| self.get_face_infos_from_path(p)) {
+                Ok(faces) => fonts.extend(faces),
+                // Skip fonts we can't read, but don't fail the entire operation
+                Err(e) => listing.warn(format!("skipped {}: {e}", path.display())),
             }
         }
+        cache.finish();
 
-        Ok(protection::dedupe_fonts(fonts))
+        listing.fonts = filter.apply(protection::dedupe_fonts(fonts));
+        Ok(listing)
     }
 
-    fn prune_missing_fonts(&self, scope: FontScope) -> FontResult<usize> {
-        if self.is_fake_registry_enabled() {
-            return Ok(0);
-        }
-
-        let font_array = unsafe { objc2_core_text::CTFontManagerCopyAvailableFontURLs() };
+    /// The copy into the scope's font folder, then the [`activation`] step.
+    fn plan_install(&self, source: &FontliftFontSource) -> FontResult<Vec<PlannedAction>> {
+        let scope = source.scope.unwrap_or(FontScope::User);
+        let path = &source.path;
+        validation::validate_font_file(path)?;
+        self.validate_system_operation(scope)?;
 
-        let mut pruned = 0usize;
-        let mut failures = Vec::new();
-        let count = font_array.count();
+        if self.is_system_font_path(path) {
+            return Err(FontError::SystemFontProtection(path.to_path_buf()));
+        }
 
-        for i in 0..count {
-            let value = unsafe { font_array.value_at_index(i) };
-            if value.is_null() {
-                continue;
+        let target_path = self.installed_target_path(source, scope)?;
+        let mut plan = Vec::new();
+        if target_path != *path {
+            plan.push(PlannedAction::CopyFile {
+                from: path.clone(),
+                to: target_path.clone(),
+            });
+        }
+        let api = match activation(scope) {
+            Activation::UserRegistration => {
+                "Core Text CTFontManagerRegisterFontsForURL (persistent)"
             }
-
-            let cf_type: &CFType = unsafe { &*(value as *const CFType) };
-            let type_id = objc2_core_foundation::CFGetTypeID(Some(cf_type));
-            let url_type_id = CFURL::type_id();
-            if type_id != url_type_id {
-                continue;
+            Activation::FontDirectory => {
+                "the font daemon (copy in /Library/Fonts, readable by all users)"
             }
+        };
+        plan.push(PlannedAction::RegisterFont {
+            path: target_path,
+            scope,
+            api: api.to_string(),
+        });
+        Ok(plan)
+    }
 
-            let cf_url: &CFURL = unsafe { &*(value as *const CFURL) };
-            let path = cfurl_to_path(cf_url);
+    /// Register the font with `kCTFontManagerScopeSession`: every app in this
+    /// login session sees it, nothing is copied, and it is gone after logout.
+    fn install_font_session(&self, source: &FontliftFontSource) -> FontResult<()> {
+        let path = &source.path;
+        validation::validate_font_file(path)?;
+        if let Some(ref config) = self.validation_config {
+            validation_ext::validate_single(path, config)?;
+        }
 
-            if let Some(ref existing_path) = path {
-                if scope_from_path(existing_path) != scope {
-                    continue;
-                }
+        self.install_font_core_text(path, CTFontManagerScope::Session)
+    }
 
-                // Skip registrations that still have a backing file
-                if existing_path.exists() {
-                    continue;
-                }
-            } else if scope == FontScope::System && !self.has_admin_privileges() {
-                // Don't attempt system pruning without privileges
+    fn uninstall_font_session(&self, source: &FontliftFontSource) -> FontResult<()> {
+        let path = &source.path;
+        let cf_url = path_to_cfurl(path).ok_or_else(|| {
+            FontError::InvalidFormat(format!("Cannot create CFURL from path: {}", path.display()))
+        })?;
+
+        let mut error: *mut CFError = std::ptr::null_mut();
+        let result = unsafe {
+            CTFontManagerUnregisterFontsForURL(&cf_url, CTFontManagerScope::Session, &mut error)
+        };
+        if result {
+            return Ok(());
+        }
+        let err = if error.is_null() {
+            None
+        } else {
+            Some(unsafe { &*error })
+        };
+        Err(FontError::RegistrationFailed(format!(
+            "Core Text failed to unregister session font {}: {}",
+            path.display(),
+            cf_error_to_string(err)
+        )))
+    }
+
+    /// Register the bytes with `kCTFontManagerScopeProcess` through a
+    /// `CGFont`, the only Core Text route that takes data instead of a URL.
+    /// A `CGFont` holds one face, so collections with several faces are
+    /// refused; install those from a file.
+    fn register_font_bytes(
+        &self,
+        data: &[u8],
+        options: &MemoryFontOptions,
+    ) -> FontResult<FontHandle> {
+        let label = options.display_name();
+        let names = if options.validate {
+            memory::inspect_font_bytes(data, label)?
+        } else {
+            Vec::new()
+        };
+        if names.len() > 1 {
+            return Err(FontError::UnsupportedOperation(format!(
+                "{label} is a collection of {} faces; Core Text registers one face per in-memory font",
+                names.len()
+            )));
+        }
+
+        let cf_data = CFData::from_bytes(data);
+        if unsafe { CTFontManagerCreateFontDescriptorFromData(&cf_data) }.is_none() {
+            return Err(FontError::InvalidFormat(format!(
+                "{label}: Core Text cannot read the font data"
+            )));
+        }
+        let font = CGDataProvider::with_cf_data(Some(&cf_data))
+            .and_then(|provider| CGFont::with_data_provider(&provider))
+            .ok_or_else(|| {
+                FontError::InvalidFormat(format!("{label}: Core Graphics cannot load the font"))
+            })?;
+
+        let mut error: *mut CFError = std::ptr::null_mut();
+        #[allow(deprecated)]
+        let registered = unsafe { CTFontManagerRegisterGraphicsFont(&font, &mut error) };
+        if !registered {
+            let err = if error.is_null() {
+                None
+            } else {
+                Some(unsafe { &*error })
+            };
+            return Err(FontError::RegistrationFailed(format!(
+                "Core Text failed to register {label}: {}",
+                cf_error_to_string(err)
+            )));
+        }
+        Ok(FontHandle::new(Box::new(GraphicsFont(font)), names, label))
+    }
+
+    /// Create a `CTFont` for every face in the file. Core Text parses and
+    /// caches a font the first time one is created; asking for the glyph
+    /// count makes it load the tables instead of deferring the work.
+    fn warm_font_cache(&self, source: &FontliftFontSource) -> FontResult<usize> {
+        let path = &source.path;
+        let url = path_to_cfurl(path).ok_or_else(|| {
+            FontError::InvalidFormat(format!("Invalid font path: {}", path.display()))
+        })?;
+        let descriptors =
+            unsafe { objc2_core_text::CTFontManagerCreateFontDescriptorsFromURL(&url) }
+                .ok_or_else(|| {
+                    FontError::InvalidFormat(format!(
+                        "Core Text found no fonts in {}",
+                        path.display()
+                    ))
+                })?;
+
+        let mut warmed = 0;
+        for idx in 0..descriptors.count() {
+            let value = unsafe { descriptors.value_at_index(idx) };
+            if value.is_null() {
                 continue;
             }
+            let descriptor: &CTFontDescriptor = unsafe { &*(value as *const CTFontDescriptor) };
+            let font = unsafe { CTFont::with_font_descriptor(descriptor, 12.0, std::ptr::null()) };
+            let _ = unsafe { font.glyph_count() };
+            warmed += 1;
+        }
+        Ok(warmed)
+    }
 
-            let mut error: *mut CFError = std::ptr::null_mut();
-            let ok =
-                unsafe { CTFontManagerUnregisterFontsForURL(cf_url, ct_scope(scope), &mut error) };
+    /// Post to the distributed notification center, with the event name as
+    /// the notification's object.
+    fn post_font_change(&self, change: &fontlift_core::notify::FontChange) -> FontResult<()> {
+        let center =
+            objc2_core_foundation::CFNotificationCenter::distributed_center().ok_or_else(|| {
+                FontError::UnsupportedOperation(
+                    "The distributed notification center is unavailable".to_string(),
+                )
+            })?;
+        let name = rust_string_to_cf(fontlift_core::notify::NOTIFICATION_NAME);
+        let event = rust_string_to_cf(change.operation.as_str());
+        let object: &CFString = &event;
+        unsafe {
+            center.post_notification(
+                Some(&name),
+                object as *const CFString as *const std::ffi::c_void,
+                None,
+                true,
+            );
+        }
+        Ok(())
+    }
 
-            if ok {
-                pruned += 1;
-            } else {
-                let err = if error.is_null() {
-                    None
-                } else {
-                    Some(unsafe { &*error })
+    /// Install a one-shot launchd job that clears the caches at the next
+    /// login (user scope, LaunchAgent) or boot (system scope, LaunchDaemon).
+    ///
+    /// The job deletes its own plist when it finishes, so the plist is
+    /// returned with the cache files: once it is gone the job has run.
+    fn schedule_cache_clear_at_reboot(&self, scope: FontScope) -> FontResult<Vec<PathBuf>> {
+        let test_root = test_cache_root();
+        let home = user_home(&test_root)?;
+        let (plist, mut files, atsutil) = match scope {
+            FontScope::User => (
+                home.join("Library/LaunchAgents"),
+                vendor_cache_files(&home),
+                "/usr/bin/atsutil databases -removeUser",
+            ),
+            FontScope::System => {
+                if test_root.is_none() && !self.has_admin_privileges() {
+                    return Err(FontError::PermissionDenied(
+                        "Scheduling a system cache clear requires administrator privileges"
+                            .to_string(),
+                    ));
+                }
+                (
+                    test_root
+                        .unwrap_or_else(|| PathBuf::from("/"))
+                        .join("Library/LaunchDaemons"),
+                    Vec::new(),
+                    "/usr/bin/atsutil databases -remove",
+                )
+            }
+        };
+        fs::create_dir_all(&plist).map_err(FontError::IoError)?;
+        let plist = plist.join(format!("{REBOOT_CACHE_CLEAR_LABEL}.plist"));
+
+        let mut script = String::new();
+        if !files.is_empty() {
+            script.push_str("/bin/rm -f");
+            for file in &files {
+                script.push(' ');
+                script.push_str(&shell_quote(&file.to_string_lossy()));
+            }
+            script.push_str("; ");
+        }
+        script.push_str(atsutil);
+        script.push_str("; /bin/rm -f ");
+        script.push_str(&shell_quote(&plist.to_string_lossy()));
+
+        fs::write(&plist, one_shot_launchd_plist(&script)).map_err(FontError::IoError)?;
+        files.push(plist);
+        Ok(files)
+    }
+
+    /// Write a calendar-interval launchd job and load it. User tasks are
+    /// LaunchAgents, system tasks LaunchDaemons (run as root).
+    fn source_roots(&self) -> FontResult<Vec<SourceRoot>> {
+        let mut roots = vec![
+            SourceRoot::new(
+                SourceKind::Fonts,
+                self.target_directory(FontScope::User)?,
+                Some(FontScope::User),
+            ),
+            SourceRoot::new(
+                SourceKind::Fonts,
+                self.target_directory(FontScope::System)?,
+                Some(FontScope::System),
+            ),
+            SourceRoot::new(
+                SourceKind::OsFonts,
+                "/System/Library/Fonts",
+                Some(FontScope::System),
+            ),
+        ];
+        roots.extend(vendor_cache_roots(&user_home(&test_cache_root())?));
+        Ok(roots)
+    }
+
+    fn schedule_task(&self, task: &ScheduledTask) -> FontResult<String> {
+        let dir = self.launchd_dir(task.scope)?;
+        fs::create_dir_all(&dir).map_err(FontError::IoError)?;
+        if let Some(parent) = task.log_file.parent() {
+            fs::create_dir_all(parent).map_err(FontError::IoError)?;
+        }
+
+        let plist = dir.join(format!("{}.plist", task.label()));
+        if plist.exists() {
+            // Reload so launchd picks up the new schedule.
+            let _ = launchctl("unload", &plist);
+        }
+        fs::write(&plist, schedule::launchd_plist(task)).map_err(FontError::IoError)?;
+        launchctl("load", &plist)?;
+        Ok(plist.display().to_string())
+    }
+
+    fn list_scheduled_tasks(&self) -> FontResult<Vec<ScheduledTaskInfo>> {
+        let mut tasks = Vec::new();
+        for scope in [FontScope::User, FontScope::System] {
+            let Ok(entries) = fs::read_dir(launchd_dir_unchecked(scope)?) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Some(name) = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(|n| n.strip_prefix(schedule::TASK_PREFIX))
+                    .and_then(|n| n.strip_suffix(".plist"))
+                else {
+                    continue;
                 };
-                failures.push(cf_error_to_string(err));
+                let frequency = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|plist| schedule::launchd_plist_frequency(&plist));
+                tasks.push(ScheduledTaskInfo {
+                    name: name.to_string(),
+                    scope,
+                    frequency,
+                    location: path.display().to_string(),
+                });
             }
         }
+        tasks.sort_by(|a, b| a.location.cmp(&b.location));
+        Ok(tasks)
+    }
 
-        if failures.is_empty() {
-            Ok(pruned)
-        } else {
-            Err(FontError::RegistrationFailed(format!(
-                "Failed to prune some font registrations: {}",
-                failures.join("; ")
-            )))
+    fn remove_scheduled_task(&self, name: &str, scope: FontScope) -> FontResult<()> {
+        let plist = self
+            .launchd_dir(scope)?
+            .join(format!("{}{name}.plist", schedule::TASK_PREFIX));
+        if !plist.exists() {
+            return Err(FontError::FontNotFound(plist));
+        }
+        let _ = launchctl("unload", &plist);
+        fs::remove_file(&plist).map_err(FontError::IoError)
+    }
+
+    /// Fonts activated by their folder go out of service when the vault
+    /// moves the file; only Core Text registrations need removing first.
+    fn deactivate_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+        let scope = source.scope.unwrap_or(FontScope::User);
+        self.validate_system_operation(scope)?;
+        if activation(scope) == Activation::FontDirectory {
+            return Ok(());
         }
+        self.uninstall_font(source)
     }
 
-    fn clear_font_caches(&self, scope: FontScope) -> FontResult<()> {
-        if self.is_fake_registry_enabled() {
+    fn reactivate_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+        let scope = source.scope.unwrap_or(FontScope::User);
+        self.validate_system_operation(scope)?;
+        if activation(scope) == Activation::FontDirectory {
             return Ok(());
         }
+        self.install_font(source)
+    }
+
+    fn find_stale_registrations(&self, scope: FontScope) -> FontResult<Vec<PrunedRegistration>> {
+        let font_array = unsafe { objc2_core_text::CTFontManagerCopyAvailableFontURLs() };
+
+        let mut stale = Vec::new();
+        let count = font_array.count();
+
+        for i in 0..count {
+            let value = unsafe { font_array.value_at_index(i) };
+            if value.is_null() {
+                continue;
+            }
 
+            let cf_type: &CFType = unsafe { &*(value as *const CFType) };
+            let type_id = objc2_core_foundation::CFGetTypeID(Some(cf_type));
+            let url_type_id = CFURL::type_id();
+            if type_id != url_type_id {
+                continue;
+            }
+
+            let cf_url: &CFURL = unsafe { &*(value as *const CFURL) };
+            let path = cfurl_to_path(cf_url);
+
+            let reason = if let Some(ref existing_path) = path {
+                if scope_from_path(existing_path) != scope {
+                    continue;
+                }
+
+                // Skip registrations that still have a backing file
+                if existing_path.exists() {
+                    continue;
+                }
+                PruneReason::MissingFile
+            } else if scope == FontScope::System && !self.has_admin_privileges() {
+                // Don't attempt system pruning without privileges
+                continue;
+            } else {
+                PruneReason::MalformedPath
+            };
+
+            stale.push(PrunedRegistration {
+                name: cf_string_to_rust(&cf_url.string()),
+                path,
+                reason,
+            });
+        }
+
+        Ok(stale)
+    }
+
+    fn remove_stale_registration(
+        &self,
+        _scope: FontScope,
+        registration: &PrunedRegistration,
+    ) -> FontResult<()> {
+        let cf_url = CFURL::from_string(None, &rust_string_to_cf(&registration.name), None)
+            .or_else(|| registration.path.as_deref().and_then(path_to_cfurl))
+            .ok_or_else(|| {
+                FontError::InvalidFormat(format!(
+                    "Cannot build a URL for registration {}",
+                    registration.name
+                ))
+            })?;
+
+        let mut error: *mut CFError = std::ptr::null_mut();
+        // Stale registrations of either scope were made per user.
+        let ok = unsafe {
+            CTFontManagerUnregisterFontsForURL(&cf_url, CTFontManagerScope::Persistent, &mut error)
+        };
+        if ok {
+            return Ok(());
+        }
+
+        let err = if error.is_null() {
+            None
+        } else {
+            Some(unsafe { &*error })
+        };
+        // Already unregistered, e.g. by the interrupted run being resumed.
+        if err.is_some_and(|e| e.code() == K_CT_FONT_MANAGER_ERROR_NOT_REGISTERED) {
+            return Ok(());
+        }
+        Err(FontError::RegistrationFailed(cf_error_to_string(err)))
+    }
+
+    fn clear_font_caches(&self, strategy: CacheClearStrategy) -> FontResult<CacheClearResult> {
+        self.clear_cache_targets(strategy, &CacheTargets::all())
+    }
+
+    /// Only [`CacheTarget::System`] runs `atsutil`. The application caches
+    /// live in the user's home, so they are cleared with the user caches;
+    /// a system-only clear that names them explicitly warns that they were
+    /// skipped.
+    fn clear_cache_targets(
+        &self,
+        strategy: CacheClearStrategy,
+        targets: &CacheTargets,
+    ) -> FontResult<CacheClearResult> {
         let test_root = test_cache_root();
         let home = user_home(&test_root)?;
-        let should_touch_system = test_root.is_none();
+        let touch_system_cache = test_root.is_none() && targets.contains(CacheTarget::System);
+        let mut result = CacheClearResult::default();
+
+        if touch_system_cache && strategy.includes_user() {
+            // Clear user font cache using atsutil
+            let output = std::process::Command::new("atsutil")
+                .args(["databases", "-removeUser"])
+                .output()
+                .map_err(FontError::IoError)?;
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(FontError::RegistrationFailed(format!(
+                    "Failed to clear user font cache: {}",
+                    stderr
+                )));
+            }
 
-        match scope {
-            FontScope::User => {
-                if should_touch_system {
-                    // Clear user font cache using atsutil
-                    let output = std::process::Command::new("atsutil")
-                        .args(["databases", "-removeUser"])
-                        .output()
-                        .map_err(FontError::IoError)?;
-
-                    if !output.status.success() {
-                        let stderr = String::from_utf8_lossy(&output.stderr);
-                        return Err(FontError::RegistrationFailed(format!(
-                            "Failed to clear user font cache: {}",
-                            stderr
-                        )));
-                    }
+            // Restart ATS server for user session
+            let _ = std::process::Command::new("atsutil")
+                .args(["server", "-shutdown"])
+                .output();
 
-                    // Restart ATS server for user session
-                    let _ = std::process::Command::new("atsutil")
-                        .args(["server", "-shutdown"])
-                        .output();
+            let _ = std::process::Command::new("atsutil")
+                .args(["server", "-ping"])
+                .output();
+        }
 
-                    let _ = std::process::Command::new("atsutil")
-                        .args(["server", "-ping"])
-                        .output();
-                }
+        if touch_system_cache && strategy.includes_system() {
+            // System cache clearing requires admin privileges
+            if !self.has_admin_privileges() {
+                return Err(FontError::PermissionDenied(
+                    "System cache clearing requires administrator privileges".to_string(),
+                ));
+            }
+
+            // Clear system font cache using atsutil
+            let output = std::process::Command::new("atsutil")
+                .args(["databases", "-remove"])
+                .output()
+                .map_err(FontError::IoError)?;
 
-                // Vendor caches (Adobe/Microsoft) are per-user; remove safely under the resolved home dir
-                clear_adobe_font_caches(&home)?;
-                clear_office_font_cache(&home)?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(FontError::RegistrationFailed(format!(
+                    "Failed to clear system font cache: {}",
+                    stderr
+                )));
             }
-            FontScope::System => {
-                if should_touch_system {
-                    // System cache clearing requires admin privileges
-                    if !self.has_admin_privileges() {
-                        return Err(FontError::PermissionDenied(
-                            "System cache clearing requires administrator privileges".to_string(),
-                        ));
-                    }
 
-                    // Clear system font cache using atsutil
-                    let output = std::process::Command::new("atsutil")
-                        .args(["databases", "-remove"])
-                        .output()
-                        .map_err(FontError::IoError)?;
-
-                    if !output.status.success() {
-                        let stderr = String::from_utf8_lossy(&output.stderr);
-                        return Err(FontError::RegistrationFailed(format!(
-                            "Failed to clear system font cache: {}",
-                            stderr
-                        )));
-                    }
+            // Restart ATS server for system
+            let _ = std::process::Command::new("atsutil")
+                .args(["server", "-shutdown"])
+                .output();
 
-                    // Restart ATS server for system
-                    let _ = std::process::Command::new("atsutil")
-                        .args(["server", "-shutdown"])
-                        .output();
+            let _ = std::process::Command::new("atsutil")
+                .args(["server", "-ping"])
+                .output();
+        }
 
-                    let _ = std::process::Command::new("atsutil")
-                        .args(["server", "-ping"])
-                        .output();
-                }
+        // Vendor caches are per-user; remove safely under the resolved home dir
+        let app_caches = targets
+            .iter()
+            .filter(|target| *target != CacheTarget::System);
+        if strategy.includes_user() {
+            for target in app_caches {
+                result.entries_cleared += clear_app_cache(&home, target)?;
+            }
+        } else if !targets.is_all() {
+            let skipped: Vec<&str> = app_caches.map(CacheTarget::as_str).collect();
+            if !skipped.is_empty() {
+                result = result.with_warning(format!(
+                    "Skipped per-user caches in a system-only clear: {}",
+                    skipped.join(", ")
+                ));
             }
         }
 
-        Ok(())
+        Ok(result)
     }
 }
 
@@ -1281,16 +1837,15 @@ mod tests {
     use std::path::PathBuf;
     use std::sync::{Mutex, OnceLock};
 
-    fn fake_env_lock() -> &'static Mutex<()> {
+    fn env_lock() -> &'static Mutex<()> {
         static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
         LOCK.get_or_init(|| Mutex::new(()))
     }
 
     #[test]
     fn test_mac_font_manager_creation() {
-        std::env::remove_var("FONTLIFT_FAKE_REGISTRY_ROOT");
         let manager = MacFontManager::new();
-        assert!(!manager.is_fake_registry_enabled());
+        assert!(manager.validation_config.is_none());
     }
 
     #[test]
@@ -1312,6 +1867,23 @@ mod tests {
         assert!(!manager.is_system_font_path(&temp_path));
     }
 
+    #[test]
+    fn face_infos_come_from_core_text_with_the_file_license() {
+        let manager = MacFontManager::new();
+        let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/fixtures/fonts/AtkinsonHyperlegible-Regular.ttf");
+
+        let faces = manager
+            .get_face_infos_from_path(&fixture)
+            .expect("metadata should parse");
+
+        assert_eq!(faces.len(), 1);
+        assert_eq!(faces[0].postscript_name, "AtkinsonHyperlegible-Regular");
+        assert_eq!(faces[0].source.face_index, None);
+        assert_eq!(faces[0].source.path, fixture);
+        assert!(faces[0].embedding.is_some());
+    }
+
     #[test]
     fn test_admin_detection() {
         let manager = MacFontManager::new();
@@ -1335,6 +1907,19 @@ mod tests {
         assert!(!validation::is_valid_font_extension(&invalid_path));
     }
 
+    #[test]
+    fn manager_with_validation_rejects_malformed_font() {
+        let manager = MacFontManager::with_validation(ValidatorConfig::default());
+        let malformed =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/fonts/malformed.ttf");
+        let source = FontliftFontSource::new(malformed).with_scope(Some(FontScope::User));
+
+        assert!(
+            manager.install_font(&source).is_err(),
+            "manager with validation should reject malformed font"
+        );
+    }
+
     #[test]
     fn scope_detection_maps_user_and_system_paths() {
         let user_path = PathBuf::from("/Users/demo/Library/Fonts/Custom.otf");
@@ -1347,213 +1932,269 @@ mod tests {
     }
 
     #[test]
-    fn fake_registry_install_list_uninstall_round_trip() {
-        let _env_lock = fake_env_lock().lock().expect("env lock");
-        let temp = tempfile::tempdir().expect("tempdir");
-        let fake_root = temp.path().join("fake-root");
-        std::env::set_var("FONTLIFT_FAKE_REGISTRY_ROOT", &fake_root);
-
-        let manager = MacFontManager::new();
-        let source_font = temp.path().join("DemoFake.ttf");
-        fs::write(&source_font, b"dummy font").expect("write font");
-
-        let source = FontliftFontSource::new(source_font.clone()).with_scope(Some(FontScope::User));
-
-        manager
-            .install_font(&source)
-            .expect("install in fake registry");
-
-        let installed_path = fake_root.join("Library/Fonts/DemoFake.ttf");
-        assert!(
-            installed_path.exists(),
-            "font should be copied into fake registry"
-        );
-
-        let listed = manager.list_installed_fonts().expect("list");
-        assert_eq!(listed.len(), 1);
-        assert_eq!(listed[0].source.scope, Some(FontScope::User));
-
-        let installed_source =
-            FontliftFontSource::new(installed_path.clone()).with_scope(Some(FontScope::User));
-
-        manager
-            .uninstall_font(&installed_source)
-            .expect("uninstall from fake registry");
-        assert!(
-            !installed_path.exists(),
-            "font file should be removed in fake registry"
-        );
+    fn system_copies_are_readable_by_every_user() {
+        use std::os::unix::fs::PermissionsExt;
 
-        std::env::remove_var("FONTLIFT_FAKE_REGISTRY_ROOT");
-    }
+        assert_eq!(activation(FontScope::User), Activation::UserRegistration);
+        assert_eq!(activation(FontScope::System), Activation::FontDirectory);
 
-    #[test]
-    fn fake_registry_allows_system_scope_without_admin() {
-        let _env_lock = fake_env_lock().lock().expect("env lock");
         let temp = tempfile::tempdir().expect("tempdir");
-        let fake_root = temp.path().join("fake-root");
-        std::env::set_var("FONTLIFT_FAKE_REGISTRY_ROOT", &fake_root);
-
-        let manager = MacFontManager::new();
-        let source_font = temp.path().join("DemoSystem.ttf");
-        fs::write(&source_font, b"dummy font").expect("write font");
-
-        let source =
-            FontliftFontSource::new(source_font.clone()).with_scope(Some(FontScope::System));
+        let copy = temp.path().join("Private.ttf");
+        fs::write(&copy, b"dummy font").expect("write font");
+        fs::set_permissions(&copy, fs::Permissions::from_mode(0o600)).expect("chmod");
 
-        manager
-            .install_font(&source)
-            .expect("system install should bypass admin in fake mode");
-
-        let installed_path = fake_root.join("System/Library/Fonts/DemoSystem.ttf");
-        assert!(installed_path.exists());
-
-        std::env::remove_var("FONTLIFT_FAKE_REGISTRY_ROOT");
+        make_world_readable(&copy).expect("chmod copy");
+        let mode = fs::metadata(&copy).expect("copy").permissions().mode() & 0o777;
+        assert_eq!(mode, 0o644);
     }
 
     #[test]
-    fn is_font_installed_tracks_fake_registry_state() {
+    fn clear_font_caches_removes_vendor_caches_under_override_root() {
+        use std::env;
+
         struct EnvGuard;
         impl Drop for EnvGuard {
             fn drop(&mut self) {
-                std::env::remove_var("FONTLIFT_FAKE_REGISTRY_ROOT");
+                env::remove_var("FONTLIFT_TEST_CACHE_ROOT");
             }
         }
 
-        let _lock = fake_env_lock().lock().expect("env lock");
+        let _lock = env_lock().lock().expect("env lock");
         let _guard = EnvGuard;
-        let temp = tempfile::tempdir().expect("tempdir");
-        let fake_root = temp.path().join("fake-root");
-        std::env::set_var("FONTLIFT_FAKE_REGISTRY_ROOT", &fake_root);
 
-        let manager = MacFontManager::new();
-        let source_font = temp.path().join("DemoFake.ttf");
-        fs::write(&source_font, b"dummy font").expect("write font");
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let root = tmp.path();
+
+        let adobe_type_support = root.join("Library/Application Support/Adobe/TypeSupport/More");
+        fs::create_dir_all(&adobe_type_support).expect("adobe type support dir");
+        let adobe_list = adobe_type_support.join("AdobeFnt11.lst");
+        fs::write(&adobe_list, b"cache").expect("adobe list");
+
+        let adobe_cache_dir = root.join("Library/Caches/Adobe/Fonts");
+        fs::create_dir_all(&adobe_cache_dir).expect("adobe cache dir");
+        let adobe_cache_file = adobe_cache_dir.join("fonts.bin");
+        fs::write(&adobe_cache_file, b"cache").expect("adobe cache");
 
-        let source = FontliftFontSource::new(source_font.clone()).with_scope(Some(FontScope::User));
+        let office_cache_dir = root.join("Library/Group Containers/UBF8T346G9.Office/FontCache");
+        fs::create_dir_all(&office_cache_dir).expect("office cache dir");
+        let office_cache_file = office_cache_dir.join("fontcache.dat");
+        fs::write(&office_cache_file, b"cache").expect("office cache");
+
+        env::set_var("FONTLIFT_TEST_CACHE_ROOT", root);
+        let manager = MacFontManager::new();
+        let result = manager
+            .clear_font_caches(CacheClearStrategy::UserOnly)
+            .expect("clear caches");
+        assert_eq!(result.entries_cleared, 3);
 
         assert!(
-            !manager
-                .is_font_installed(&source)
-                .expect("check before install"),
-            "font should not be marked installed before copying"
+            !adobe_list.exists(),
+            "Adobe font list cache should be removed"
         );
-
-        manager
-            .install_font(&source)
-            .expect("install in fake registry");
         assert!(
-            manager
-                .is_font_installed(&source)
-                .expect("check after install"),
-            "font should be marked installed after copy"
+            !adobe_cache_file.exists(),
+            "Adobe font cache file should be removed"
         );
-
-        let installed_source =
-            FontliftFontSource::new(fake_root.join("Library/Fonts/DemoFake.ttf"))
-                .with_scope(Some(FontScope::User));
-
-        manager
-            .uninstall_font(&installed_source)
-            .expect("uninstall from fake registry");
-
         assert!(
-            !manager
-                .is_font_installed(&source)
-                .expect("check after uninstall"),
-            "font should be absent after uninstall"
+            fs::read_dir(&office_cache_dir)
+                .expect("office dir")
+                .next()
+                .is_none(),
+            "Office font cache directory should be emptied"
         );
     }
 
     #[test]
-    fn reinstall_overwrites_existing_file_in_fake_registry() {
+    fn clear_cache_targets_clears_only_the_named_caches() {
+        use std::env;
+
         struct EnvGuard;
         impl Drop for EnvGuard {
             fn drop(&mut self) {
-                std::env::remove_var("FONTLIFT_FAKE_REGISTRY_ROOT");
+                env::remove_var("FONTLIFT_TEST_CACHE_ROOT");
             }
         }
 
-        let _lock = fake_env_lock().lock().expect("env lock");
+        let _lock = env_lock().lock().expect("env lock");
         let _guard = EnvGuard;
-        let temp = tempfile::tempdir().expect("tempdir");
-        let fake_root = temp.path().join("fake-root");
-        std::env::set_var("FONTLIFT_FAKE_REGISTRY_ROOT", &fake_root);
-
-        let manager = MacFontManager::new();
-        let source_font = temp.path().join("DemoFake.ttf");
 
-        fs::write(&source_font, b"version-one").expect("write v1");
-        let source = FontliftFontSource::new(source_font.clone()).with_scope(Some(FontScope::User));
-        manager.install_font(&source).expect("initial install");
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let root = tmp.path();
+        let adobe_cache_dir = root.join("Library/Caches/Adobe/Fonts");
+        fs::create_dir_all(&adobe_cache_dir).expect("adobe cache dir");
+        let adobe_cache_file = adobe_cache_dir.join("fonts.bin");
+        fs::write(&adobe_cache_file, b"cache").expect("adobe cache");
+        let office_cache_dir = root.join("Library/Group Containers/UBF8T346G9.Office/FontCache");
+        fs::create_dir_all(&office_cache_dir).expect("office cache dir");
+        let office_cache_file = office_cache_dir.join("fontcache.dat");
+        fs::write(&office_cache_file, b"cache").expect("office cache");
+        let startup_cache = root.join("Library/Caches/Firefox/Profiles/abc.default/startupCache");
+        fs::create_dir_all(&startup_cache).expect("firefox startup cache");
+        fs::write(startup_cache.join("startupCache.8.little"), b"cache").expect("firefox cache");
 
-        fs::write(&source_font, b"version-two").expect("write v2");
-        manager
-            .install_font(&source)
-            .expect("reinstall should replace");
-
-        let installed_path = fake_root.join("Library/Fonts/DemoFake.ttf");
-        let contents = fs::read(&installed_path).expect("read installed copy");
-        assert_eq!(
-            contents, b"version-two",
-            "reinstall should replace the existing file in fake registry"
-        );
+        env::set_var("FONTLIFT_TEST_CACHE_ROOT", root);
+        let manager = MacFontManager::new();
+        let targets = CacheTargets::only([CacheTarget::Adobe, CacheTarget::Browser]);
+        let result = manager
+            .clear_cache_targets(CacheClearStrategy::UserOnly, &targets)
+            .expect("clear adobe and browser caches");
+
+        assert_eq!(result.entries_cleared, 2);
+        assert!(!adobe_cache_file.exists());
+        assert!(office_cache_file.exists(), "Office cache was not selected");
+
+        let result = manager
+            .clear_cache_targets(CacheClearStrategy::SystemOnly, &targets)
+            .expect("system-only clear");
+        assert_eq!(result.entries_cleared, 0);
+        assert_eq!(result.warnings.len(), 1, "{:?}", result.warnings);
     }
 
     #[test]
-    fn clear_font_caches_removes_vendor_caches_under_override_root() {
+    fn schedule_cache_clear_at_reboot_writes_one_shot_launch_agent() {
         use std::env;
 
         struct EnvGuard;
         impl Drop for EnvGuard {
             fn drop(&mut self) {
-                env::remove_var("FONTLIFT_FAKE_REGISTRY_ROOT");
                 env::remove_var("FONTLIFT_TEST_CACHE_ROOT");
             }
         }
 
-        let _lock = fake_env_lock().lock().expect("env lock");
+        let _lock = env_lock().lock().expect("env lock");
         let _guard = EnvGuard;
-        env::remove_var("FONTLIFT_FAKE_REGISTRY_ROOT");
 
         let tmp = tempfile::tempdir().expect("tempdir");
         let root = tmp.path();
-
-        let adobe_type_support = root.join("Library/Application Support/Adobe/TypeSupport/More");
-        fs::create_dir_all(&adobe_type_support).expect("adobe type support dir");
-        let adobe_list = adobe_type_support.join("AdobeFnt11.lst");
+        let type_support = root.join("Library/Application Support/Adobe/TypeSupport");
+        fs::create_dir_all(&type_support).expect("adobe type support dir");
+        let adobe_list = type_support.join("AdobeFnt11.lst");
         fs::write(&adobe_list, b"cache").expect("adobe list");
 
-        let adobe_cache_dir = root.join("Library/Caches/Adobe/Fonts");
-        fs::create_dir_all(&adobe_cache_dir).expect("adobe cache dir");
-        let adobe_cache_file = adobe_cache_dir.join("fonts.bin");
-        fs::write(&adobe_cache_file, b"cache").expect("adobe cache");
+        env::set_var("FONTLIFT_TEST_CACHE_ROOT", root);
+        let files = MacFontManager::new()
+            .schedule_cache_clear_at_reboot(FontScope::User)
+            .expect("schedule");
+
+        let plist = root
+            .join("Library/LaunchAgents")
+            .join(format!("{REBOOT_CACHE_CLEAR_LABEL}.plist"));
+        assert_eq!(files, vec![adobe_list.clone(), plist.clone()]);
+        assert!(adobe_list.exists(), "nothing is deleted until the restart");
+
+        let contents = fs::read_to_string(&plist).expect("plist");
+        assert!(contents.contains("<key>RunAtLoad</key>"));
+        assert!(contents.contains("atsutil databases -removeUser"));
+        assert!(contents.contains(&format!("/bin/rm -f '{}'", plist.display())));
+    }
 
-        let office_cache_dir = root.join("Library/Group Containers/UBF8T346G9.Office/FontCache");
-        fs::create_dir_all(&office_cache_dir).expect("office cache dir");
-        let office_cache_file = office_cache_dir.join("fontcache.dat");
-        fs::write(&office_cache_file, b"cache").expect("office cache");
+    #[test]
+    fn scheduled_tasks_round_trip_through_launch_agents() {
+        use std::env;
+
+        struct EnvGuard;
+        impl Drop for EnvGuard {
+            fn drop(&mut self) {
+                env::remove_var("FONTLIFT_TEST_CACHE_ROOT");
+            }
+        }
+
+        let _lock = env_lock().lock().expect("env lock");
+        let _guard = EnvGuard;
+        let tmp = tempfile::tempdir().expect("tempdir");
+        env::set_var("FONTLIFT_TEST_CACHE_ROOT", tmp.path());
 
-        env::set_var("FONTLIFT_TEST_CACHE_ROOT", root);
         let manager = MacFontManager::new();
+        let task = ScheduledTask::cleanup(
+            PathBuf::from("/usr/local/bin/fontlift"),
+            FontScope::User,
+            schedule::Frequency::Weekly,
+            tmp.path().join("logs/cleanup.log"),
+        );
+        let location = manager.schedule_task(&task).expect("schedule");
+        assert!(location
+            .ends_with("Library/LaunchAgents/com.fontlaborg.fontlift.schedule.cleanup.plist"));
+
+        let tasks = manager.list_scheduled_tasks().expect("list");
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].name, "cleanup");
+        assert_eq!(tasks[0].frequency, Some(schedule::Frequency::Weekly));
+
         manager
-            .clear_font_caches(FontScope::User)
-            .expect("clear caches");
+            .remove_scheduled_task("cleanup", FontScope::User)
+            .expect("remove");
+        assert!(manager.list_scheduled_tasks().expect("list").is_empty());
+    }
 
-        assert!(
-            !adobe_list.exists(),
-            "Adobe font list cache should be removed"
-        );
-        assert!(
-            !adobe_cache_file.exists(),
-            "Adobe font cache file should be removed"
-        );
-        assert!(
-            fs::read_dir(&office_cache_dir)
-                .expect("office dir")
-                .next()
-                .is_none(),
-            "Office font cache directory should be emptied"
-        );
+    /// Mounts a small case-sensitive APFS image, where `Inter.ttf` and
+    /// `inter.ttf` are two files, and checks that path matching keeps them
+    /// apart. Skipped when `hdiutil` cannot create or attach images (CI
+    /// sandboxes).
+    #[test]
+    fn case_sensitive_volume_keeps_case_variants_apart() {
+        use std::process::Command;
+
+        struct Detach(PathBuf);
+        impl Drop for Detach {
+            fn drop(&mut self) {
+                let _ = Command::new("hdiutil")
+                    .args(["detach", "-force"])
+                    .arg(&self.0)
+                    .status();
+            }
+        }
+
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let image = tmp.path().join("case-sensitive.dmg");
+        let mount = tmp.path().join("volume");
+        let created = Command::new("hdiutil")
+            .args([
+                "create",
+                "-quiet",
+                "-size",
+                "16m",
+                "-fs",
+                "Case-sensitive APFS",
+            ])
+            .args(["-volname", "FontliftCaseSensitive"])
+            .arg(&image)
+            .status();
+        if !matches!(created, Ok(status) if status.success()) {
+            eprintln!("skipping: hdiutil could not create a case-sensitive image");
+            return;
+        }
+        fs::create_dir_all(&mount).expect("mount point");
+        let attached = Command::new("hdiutil")
+            .args(["attach", "-quiet", "-nobrowse", "-mountpoint"])
+            .arg(&mount)
+            .arg(&image)
+            .status();
+        if !matches!(attached, Ok(status) if status.success()) {
+            eprintln!("skipping: hdiutil could not attach the image");
+            return;
+        }
+        let _detach = Detach(mount.clone());
+
+        let fonts_dir = mount.join("Fonts");
+        fs::create_dir_all(&fonts_dir).expect("fonts dir");
+        let upper = fonts_dir.join("Inter.ttf");
+        let lower = fonts_dir.join("inter.ttf");
+        fs::write(&upper, b"upper").expect("write Inter.ttf");
+        fs::write(&lower, b"lower").expect("write inter.ttf");
+
+        assert!(path_case::is_case_sensitive(&upper));
+        assert!(!path_case::same_path(&upper, &lower));
+
+        let face = |path: &Path| {
+            FontliftFontFaceInfo::new(
+                FontliftFontSource::new(path.to_path_buf()),
+                "Inter-Regular".into(),
+                "Inter Regular".into(),
+                "Inter".into(),
+                "Regular".into(),
+            )
+        };
+        let deduped = protection::dedupe_fonts(vec![face(&upper), face(&lower), face(&upper)]);
+        assert_eq!(deduped.len(), 2, "case variants are different files here");
     }
 }
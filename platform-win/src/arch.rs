@@ -0,0 +1,298 @@
+//! Processor architecture detection and the cache paths that depend on it.
+//!
+//! Windows on ARM64 runs three kinds of programs side by side: native ARM64,
+//! emulated x64 and emulated x86. Each sees a slightly different filesystem:
+//!
+//! | Process on OS      | `ProgramFiles`          | Also present                                   | `System32` |
+//! |--------------------|-------------------------|------------------------------------------------|------------|
+//! | x64 on x64         | `Program Files`         | `ProgramFiles(x86)`                            | native     |
+//! | x86 on x64         | `Program Files (x86)`   | `ProgramW6432`                                 | redirected |
+//! | ARM64 on ARM64     | `Program Files`         | `ProgramFiles(x86)`, `ProgramFiles(Arm)`       | native     |
+//! | x64 on ARM64       | `Program Files`         | `ProgramFiles(x86)`, `ProgramFiles(Arm)`       | native     |
+//! | x86 on ARM64       | `Program Files (x86)`   | `ProgramW6432`, `ProgramFiles(Arm)`            | redirected |
+//!
+//! Adobe applications keep their font lists under whichever Program Files
+//! tree matches *their* architecture, so an x86 Acrobat and a native ARM64
+//! Photoshop on the same Surface write to different roots. Cache cleanup
+//! therefore visits every root the environment names, not just the one this
+//! process was started with.
+//!
+//! A 32-bit process sees `SysWOW64` when it asks for `System32`; the real
+//! directory is only reachable through the `Sysnative` alias. The Font Cache
+//! services keep the same names on every architecture.
+//!
+//! Detection reads the environment Windows sets for every process
+//! (`PROCESSOR_ARCHITECTURE`, `PROCESSOR_ARCHITEW6432`,
+//! `PROCESSOR_IDENTIFIER`, `ProgramFiles(Arm)`), which keeps it testable on
+//! any host. Under x64 emulation `PROCESSOR_ARCHITECTURE` says `AMD64`; the
+//! processor identifier and the presence of `ProgramFiles(Arm)` still reveal
+//! the ARM64 host.
+
+use std::path::{Path, PathBuf};
+
+/// Font cache services and whether clearing caches requires them.
+///
+/// `FontCache` is the Windows Font Cache Service; `FontCache3.0.0.0` is the
+/// optional WPF font cache.
+pub const FONT_CACHE_SERVICES: [(&str, bool); 2] =
+    [("FontCache", true), ("FontCache3.0.0.0", false)];
+
+/// A Windows processor architecture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WinArch {
+    X86,
+    X64,
+    Arm,
+    Arm64,
+    Unknown,
+}
+
+impl WinArch {
+    /// Parse a `PROCESSOR_ARCHITECTURE` value.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "X86" => WinArch::X86,
+            "AMD64" | "X64" => WinArch::X64,
+            "ARM" => WinArch::Arm,
+            "ARM64" => WinArch::Arm64,
+            _ => WinArch::Unknown,
+        }
+    }
+
+    pub fn is_32_bit(self) -> bool {
+        matches!(self, WinArch::X86 | WinArch::Arm)
+    }
+}
+
+/// Architecture of the operating system and of the current process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchInfo {
+    pub os: WinArch,
+    pub process: WinArch,
+}
+
+impl ArchInfo {
+    /// Detect from the current process environment.
+    pub fn detect() -> Self {
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    /// Detect from an environment lookup.
+    pub fn from_env(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let process = lookup("PROCESSOR_ARCHITECTURE")
+            .map(|v| WinArch::parse(&v))
+            .unwrap_or(WinArch::Unknown);
+
+        let arm_host = lookup("PROCESSOR_IDENTIFIER")
+            .is_some_and(|id| id.to_ascii_uppercase().starts_with("ARM"))
+            || lookup("ProgramFiles(Arm)").is_some();
+
+        let os = match lookup("PROCESSOR_ARCHITEW6432").map(|v| WinArch::parse(&v)) {
+            Some(arch) if arch != WinArch::Unknown => arch,
+            _ if arm_host => WinArch::Arm64,
+            _ => process,
+        };
+
+        Self { os, process }
+    }
+
+    /// Is this process running under emulation (x86 or x64 on ARM64)?
+    pub fn is_emulated(&self) -> bool {
+        self.os == WinArch::Arm64 && matches!(self.process, WinArch::X86 | WinArch::X64)
+    }
+
+    /// Does the OS redirect `System32` to `SysWOW64` for this process?
+    pub fn has_system32_redirection(&self) -> bool {
+        self.process.is_32_bit() && !self.os.is_32_bit() && self.os != WinArch::Unknown
+    }
+}
+
+fn collect_roots(lookup: impl Fn(&str) -> Option<String>, names: &[&str]) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for value in names.iter().filter_map(|name| lookup(name)) {
+        if value.trim().is_empty() {
+            continue;
+        }
+        let already_present = roots
+            .iter()
+            .any(|p| p.to_string_lossy().eq_ignore_ascii_case(&value));
+        if !already_present {
+            roots.push(PathBuf::from(value));
+        }
+    }
+    roots
+}
+
+/// Every Program Files tree the environment names, deduplicated
+/// case-insensitively, starting with this process's own `ProgramFiles`.
+pub fn program_files_roots(lookup: impl Fn(&str) -> Option<String>) -> Vec<PathBuf> {
+    collect_roots(
+        lookup,
+        &[
+            "ProgramFiles",
+            "ProgramW6432",
+            "ProgramFiles(x86)",
+            "ProgramFiles(Arm)",
+        ],
+    )
+}
+
+/// Every `Common Files` tree the environment names, deduplicated the same way.
+pub fn common_files_roots(lookup: impl Fn(&str) -> Option<String>) -> Vec<PathBuf> {
+    collect_roots(
+        lookup,
+        &[
+            "CommonProgramFiles",
+            "CommonProgramW6432",
+            "CommonProgramFiles(x86)",
+            "CommonProgramFiles(Arm)",
+        ],
+    )
+}
+
+/// The real `System32` directory for a process with `arch`.
+pub fn native_system32(windows_root: &Path, arch: ArchInfo) -> PathBuf {
+    if arch.has_system32_redirection() {
+        windows_root.join("Sysnative")
+    } else {
+        windows_root.join("System32")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Environment snapshots of the layouts in the module table.
+    struct Layout {
+        name: &'static str,
+        env: &'static [(&'static str, &'static str)],
+        os: WinArch,
+        process: WinArch,
+        program_files: &'static [&'static str],
+    }
+
+    const PF: &str = r"C:\Program Files";
+    const PF86: &str = r"C:\Program Files (x86)";
+    const PFARM: &str = r"C:\Program Files (Arm)";
+    const ARM_ID: &str =
+        "ARMv8 (64-bit) Family 8 Model D4B Revision   0, Qualcomm Technologies Inc";
+
+    const LAYOUTS: &[Layout] = &[
+        Layout {
+            name: "x64 on x64",
+            env: &[
+                ("PROCESSOR_ARCHITECTURE", "AMD64"),
+                (
+                    "PROCESSOR_IDENTIFIER",
+                    "Intel64 Family 6 Model 154 Stepping 3, GenuineIntel",
+                ),
+                ("ProgramFiles", PF),
+                ("ProgramW6432", PF),
+                ("ProgramFiles(x86)", PF86),
+            ],
+            os: WinArch::X64,
+            process: WinArch::X64,
+            program_files: &[PF, PF86],
+        },
+        Layout {
+            name: "x86 on x64",
+            env: &[
+                ("PROCESSOR_ARCHITECTURE", "x86"),
+                ("PROCESSOR_ARCHITEW6432", "AMD64"),
+                ("ProgramFiles", PF86),
+                ("ProgramW6432", PF),
+                ("ProgramFiles(x86)", PF86),
+            ],
+            os: WinArch::X64,
+            process: WinArch::X86,
+            program_files: &[PF86, PF],
+        },
+        Layout {
+            name: "ARM64 on ARM64",
+            env: &[
+                ("PROCESSOR_ARCHITECTURE", "ARM64"),
+                ("PROCESSOR_IDENTIFIER", ARM_ID),
+                ("ProgramFiles", PF),
+                ("ProgramW6432", PF),
+                ("ProgramFiles(x86)", PF86),
+                ("ProgramFiles(Arm)", PFARM),
+            ],
+            os: WinArch::Arm64,
+            process: WinArch::Arm64,
+            program_files: &[PF, PF86, PFARM],
+        },
+        Layout {
+            name: "x64 on ARM64",
+            env: &[
+                ("PROCESSOR_ARCHITECTURE", "AMD64"),
+                ("PROCESSOR_IDENTIFIER", ARM_ID),
+                ("ProgramFiles", PF),
+                ("ProgramW6432", PF),
+                ("ProgramFiles(x86)", PF86),
+                ("ProgramFiles(Arm)", PFARM),
+            ],
+            os: WinArch::Arm64,
+            process: WinArch::X64,
+            program_files: &[PF, PF86, PFARM],
+        },
+        Layout {
+            name: "x86 on ARM64",
+            env: &[
+                ("PROCESSOR_ARCHITECTURE", "x86"),
+                ("PROCESSOR_ARCHITEW6432", "ARM64"),
+                ("ProgramFiles", PF86),
+                ("ProgramW6432", PF),
+                ("ProgramFiles(x86)", PF86),
+                ("ProgramFiles(Arm)", PFARM),
+            ],
+            os: WinArch::Arm64,
+            process: WinArch::X86,
+            program_files: &[PF86, PF, PFARM],
+        },
+    ];
+
+    fn lookup(layout: &Layout) -> impl Fn(&str) -> Option<String> {
+        let env: HashMap<&str, &str> = layout.env.iter().copied().collect();
+        move |name| env.get(name).map(|v| v.to_string())
+    }
+
+    #[test]
+    fn detects_architecture_for_each_layout() {
+        for layout in LAYOUTS {
+            let arch = ArchInfo::from_env(lookup(layout));
+            assert_eq!(arch.os, layout.os, "{}", layout.name);
+            assert_eq!(arch.process, layout.process, "{}", layout.name);
+            assert_eq!(
+                arch.is_emulated(),
+                layout.os == WinArch::Arm64 && layout.process != WinArch::Arm64,
+                "{}",
+                layout.name
+            );
+        }
+    }
+
+    #[test]
+    fn discovers_every_program_files_root() {
+        for layout in LAYOUTS {
+            let roots = program_files_roots(lookup(layout));
+            let expected: Vec<PathBuf> = layout.program_files.iter().map(PathBuf::from).collect();
+            assert_eq!(roots, expected, "{}", layout.name);
+        }
+    }
+
+    #[test]
+    fn uses_sysnative_only_for_redirected_processes() {
+        let windows = Path::new(r"C:\Windows");
+        for layout in LAYOUTS {
+            let system32 = native_system32(windows, ArchInfo::from_env(lookup(layout)));
+            let expected = if layout.process == WinArch::X86 {
+                "Sysnative"
+            } else {
+                "System32"
+            };
+            assert!(system32.ends_with(expected), "{}", layout.name);
+        }
+    }
+}
@@ -11,7 +11,7 @@
 //!
 //! 2. **Write a registry entry** so the font survives reboots:
 //!    - System scope: `HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft\Windows NT\
-//!      CurrentVersion\Fonts` — key name is `"Family Name (TrueType)"`,
+//!      CurrentVersion\Fonts` — key name is `"Full Name (TrueType)"`,
 //!      value is the filename (relative to `C:\Windows\Fonts\`) or a full path.
 //!    - User scope: same key path under `HKEY_CURRENT_USER`.
 //!
@@ -34,22 +34,63 @@
 //!
 //! Font caches: Windows maintains the Font Cache Service (`FontCache`) and
 //! binary cache files under `ServiceProfiles\LocalService\AppData\Local\FontCache\`.
-//! `clear_font_caches` stops the service, deletes cache files, and restarts it.
+//! `clear_font_caches` stops the service, deletes the current user's cache
+//! files (every cache file and `FNTCACHE.DAT` with
+//! [`CacheTargets::with_deep`](fontlift_core::cache::CacheTargets::with_deep)),
+//! and restarts it, also when the clear fails part-way.
 //! A reboot may be required for all applications to pick up the changes.
+//! Cache paths depend on the OS and process architecture (ARM64 machines
+//! add a `Program Files (Arm)` tree); see [`arch`].
+//!
+//! An elevated administrator can manage another user's per-user fonts with
+//! [`WinFontManager::for_user`]; see [`target_user`].
+
+pub mod arch;
+pub mod target_user;
 
+#[cfg(any(windows, test))]
+use arch::ArchInfo;
+#[cfg(any(windows, test))]
+use fontlift_core::cache::CacheTarget;
 #[cfg(windows)]
-use fontlift_core::conflicts;
+use fontlift_core::cache::CacheTargets;
+#[cfg(windows)]
+use fontlift_core::conflicts::{self, ConflictOutcome, ConflictPolicy};
+#[cfg(windows)]
+use fontlift_core::file_names;
 #[cfg(windows)]
 use fontlift_core::journal;
 use fontlift_core::journal::JournalAction;
+#[cfg(windows)]
+use fontlift_core::links;
+#[cfg(windows)]
+use fontlift_core::listing::{FontListing, ListFilter};
+#[cfg(windows)]
+use fontlift_core::memory::{self, FontHandle, MemoryFontOptions, MemoryRegistration};
+use fontlift_core::metadata::{name_string, name_string_in};
+#[cfg(windows)]
+use fontlift_core::metadata_cache::MetadataCache;
+use fontlift_core::plan::PlannedAction;
+#[cfg(windows)]
+use fontlift_core::prune::{PruneReason, PrunedRegistration};
+#[cfg(windows)]
+use fontlift_core::renamed_copies::{self, RepairReport};
+#[cfg(windows)]
+use fontlift_core::schedule::{self, ScheduledTask, ScheduledTaskInfo};
+#[cfg(windows)]
+use fontlift_core::sources::{SourceKind, SourceRoot};
+use fontlift_core::symlinks;
 use fontlift_core::validation;
 use fontlift_core::validation_ext::{self, ValidatorConfig};
 use fontlift_core::{
+    cache::{CacheClearResult, CacheClearStrategy},
+    environment::{self, ProviderInfo},
     FontError, FontManager, FontResult, FontScope, FontliftFontFaceInfo, FontliftFontSource,
 };
-use read_fonts::{tables::name::NameId, FileRef, FontRef, TableProvider};
+use read_fonts::{tables::name::NameId, types::Tag, FileRef, FontRef};
 
 use std::path::{Path, PathBuf};
+use target_user::TargetUser;
 
 #[cfg(windows)]
 use std::collections::BTreeSet;
@@ -76,14 +117,25 @@ use winreg::RegKey;
 // to either a bare filename ("arial.ttf", resolved relative to %WINDIR%\Fonts)
 // or an absolute path (used for user-scope fonts in modern Windows 10/11).
 // System scope lives under HKLM; user scope lives under the same path in HKCU.
-#[cfg(windows)]
+#[cfg(any(windows, test))]
 const FONTS_REGISTRY_KEY: &str = r"Software\Microsoft\Windows NT\CurrentVersion\Fonts";
 
+/// The fonts registry key for `scope` as shown in plans, e.g.
+/// `HKCU\Software\Microsoft\Windows NT\CurrentVersion\Fonts`.
+#[cfg(any(windows, test))]
+fn registry_key_display(scope: FontScope) -> String {
+    let hive = match scope {
+        FontScope::User => "HKCU",
+        FontScope::System => "HKLM",
+    };
+    format!(r"{hive}\{FONTS_REGISTRY_KEY}")
+}
+
 // Directory where the Windows Font Cache Service stores its binary cache.
 // The service (FontCache / FontCache3.0.0.0) pre-parses font files and stores
 // the result here so apps load faster. fontlift stops the service, deletes
 // these files, then restarts the service to force a clean rebuild.
-#[cfg(windows)]
+#[cfg(any(windows, test))]
 const FONT_CACHE_DIR: &str = r"ServiceProfiles\\LocalService\\AppData\\Local\\FontCache";
 
 /// Return the Adobe font cache directories to clear under each Program Files root.
@@ -97,15 +149,26 @@ const FONT_CACHE_DIR: &str = r"ServiceProfiles\\LocalService\\AppData\\Local\\Fo
 /// - `Program Files\Common Files\Adobe\TypeSpt`
 /// - `Program Files\Common Files\Adobe\TypeSupport`
 /// - `Program Files\Common Files\Adobe\PDFL`
-/// - Same paths under `Program Files (x86)` if it differs
+/// - Same paths under `Program Files (x86)` and `Program Files (Arm)` if they differ
+/// - `Adobe\TypeSpt`, `Adobe\TypeSupport`, `Adobe\PDFL` under each
+///   `CommonProgramFiles*` root, in case Common Files was relocated
 #[cfg(any(windows, test))]
 fn adobe_cache_roots(program_files_dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let common_files: Vec<PathBuf> = program_files_dirs
+        .iter()
+        .map(|base| base.join("Common Files"))
+        .collect();
+    adobe_common_files_cache_roots(&common_files)
+}
+
+#[cfg(any(windows, test))]
+fn adobe_common_files_cache_roots(common_files_dirs: &[PathBuf]) -> Vec<PathBuf> {
     let mut roots = Vec::new();
 
-    for base in program_files_dirs {
-        roots.push(base.join("Common Files/Adobe/TypeSpt"));
-        roots.push(base.join("Common Files/Adobe/TypeSupport"));
-        roots.push(base.join("Common Files/Adobe/PDFL"));
+    for base in common_files_dirs {
+        roots.push(base.join("Adobe/TypeSpt"));
+        roots.push(base.join("Adobe/TypeSupport"));
+        roots.push(base.join("Adobe/PDFL"));
     }
 
     roots
@@ -130,29 +193,91 @@ pub struct WinFontManager {
     /// `fontlift-validator` before each install to catch malformed files
     /// without risking a crash in the main process.
     validation_config: Option<ValidatorConfig>,
+    /// The user whose per-user scope this manager manages, when it is not
+    /// the calling user (`--for-user`).
+    target_user: Option<TargetUser>,
 }
 
 impl WinFontManager {
     /// Create a new Windows font manager with no pre-install validation.
     pub fn new() -> Self {
-        Self {
+        let manager = Self {
             _private: (),
             validation_config: None,
-        }
+            target_user: None,
+        };
+        manager.register_environment();
+        manager
     }
 
     /// Create a manager that runs the out-of-process validator before installs.
     pub fn with_validation(config: ValidatorConfig) -> Self {
-        Self {
+        let manager = Self {
             _private: (),
             validation_config: Some(config),
-        }
+            target_user: None,
+        };
+        manager.register_environment();
+        manager
+    }
+
+    /// Report this back end and the process's rights for the environment
+    /// recorded with each journal entry.
+    fn register_environment(&self) {
+        #[cfg(windows)]
+        let elevated = Some(self.has_admin_privileges());
+        #[cfg(not(windows))]
+        let elevated = None;
+        environment::register_provider(ProviderInfo {
+            versions: [
+                (
+                    "fontlift-platform-win".to_string(),
+                    env!("CARGO_PKG_VERSION").to_string(),
+                ),
+                (
+                    "GDI".to_string(),
+                    format!(
+                        "windows {}",
+                        environment::os_version().unwrap_or_else(|| "unknown".to_string())
+                    ),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            elevated,
+        });
     }
 
     /// Enable or disable validation on this manager
     pub fn set_validation_config(&mut self, config: Option<ValidatorConfig>) {
         self.validation_config = config;
     }
+
+    /// Manage the per-user fonts of `user` — a SID or an account name —
+    /// instead of the calling user's: its profile's Fonts directory and its
+    /// registry hive, loaded if the user is signed out.
+    ///
+    /// Requires an elevated (Administrator) process.
+    pub fn for_user(self, user: &str) -> FontResult<Self> {
+        #[cfg(windows)]
+        {
+            if !self.has_admin_privileges() {
+                return Err(FontError::PermissionDenied(format!(
+                    "Managing the fonts of user '{user}' requires administrator privileges; rerun from an elevated prompt"
+                )));
+            }
+            let mut manager = self;
+            manager.target_user = Some(TargetUser::resolve(user)?);
+            Ok(manager)
+        }
+        #[cfg(not(windows))]
+        {
+            let _ = self;
+            Err(FontError::UnsupportedOperation(format!(
+                "Cannot manage the fonts of user '{user}': another user's profile is only reachable on Windows"
+            )))
+        }
+    }
 }
 
 impl Default for WinFontManager {
@@ -183,12 +308,17 @@ impl WinFontManager {
         }
     }
 
+    /// Checked as written and at the path's canonical location, so a
+    /// junction or linked folder cannot hide the Windows directory.
     fn is_system_font_path(&self, path: &Path) -> bool {
-        let lower = path.to_string_lossy().to_lowercase();
         let root = self.system_root().to_string_lossy().to_lowercase();
-        lower.starts_with(format!(r"{}\\fonts", root).as_str())
-            || lower.starts_with(format!(r"{}\\system32", root).as_str())
-            || lower.starts_with(format!(r"{}\\syswow64", root).as_str())
+        let in_system_dirs = |path: &Path| {
+            let lower = path.to_string_lossy().to_lowercase();
+            lower.starts_with(format!(r"{}\\fonts", root).as_str())
+                || lower.starts_with(format!(r"{}\\system32", root).as_str())
+                || lower.starts_with(format!(r"{}\\syswow64", root).as_str())
+        };
+        in_system_dirs(path) || in_system_dirs(&symlinks::canonical_location(path))
     }
 
     /// Return the system-wide Fonts directory (`%WINDIR%\Fonts`).
@@ -214,7 +344,12 @@ impl WinFontManager {
     /// Update). Fonts installed here are visible only to the current user and
     /// do not require Administrator rights. On older Windows builds this path
     /// may not exist; fontlift falls back to the system directory in that case.
+    ///
+    /// With a target user ([`Self::for_user`]) this is that user's directory.
     fn user_fonts_directory(&self) -> FontResult<PathBuf> {
+        if let Some(user) = &self.target_user {
+            return Ok(user.fonts_directory());
+        }
         let local_appdata = std::env::var("LOCALAPPDATA").map_err(|_| {
             FontError::PermissionDenied(
                 "Cannot determine LOCALAPPDATA directory for per-user fonts".to_string(),
@@ -298,12 +433,13 @@ impl WinFontManager {
             .unwrap_or_default();
 
         if matches!(ext.as_str(), "ttf" | "otf" | "ttc" | "otc") {
-            if let Ok(data) = std::fs::read(path) {
-                if let Ok(file) = FileRef::new(&data) {
-                    // Prefer first font in the file/collection for metadata
-                    if let Some(Ok(font)) = file.fonts().next() {
-                        enrich_from_fontref(&mut info, &font);
-                    }
+            // A file that cannot be read (access denied) is an error rather
+            // than a face named after its file.
+            let data = std::fs::read(path)?;
+            if let Ok(file) = FileRef::new(&data) {
+                // Prefer first font in the file/collection for metadata
+                if let Some(Ok(font)) = file.fonts().next() {
+                    enrich_from_fontref(&mut info, &font);
                 }
             }
         }
@@ -326,35 +462,63 @@ fn enrich_from_fontref(info: &mut FontliftFontFaceInfo, font: &FontRef<'_>) {
     if let Some(full) = name_string(font, NameId::FULL_NAME) {
         info.full_name = full;
     }
+    fontlift_core::metadata::FaceStyle::read(font).apply(info);
+    fontlift_core::metadata::read_licensing(info, font);
+    info.details = fontlift_core::metadata::FaceDetails::read(font);
 }
 
-#[cfg_attr(not(windows), allow(dead_code))]
-fn name_string(font: &FontRef<'_>, name_id: NameId) -> Option<String> {
-    let name = font.name().ok()?;
-    let data = name.string_data();
-
-    let mut fallback: Option<String> = None;
-
-    for record in name.name_record() {
-        if record.name_id() != name_id {
-            continue;
-        }
-
-        let Ok(name_str) = record.string(data) else {
-            continue;
-        };
-        let rendered = name_str.to_string();
-
-        if record.is_unicode() {
-            return Some(rendered);
-        }
-
-        if fallback.is_none() {
-            fallback = Some(rendered);
+/// The registry value name Windows gives a font file: the face's full name,
+/// or a collection's full names joined with ` & `, then `(OpenType)` for
+/// PostScript outlines and `(TrueType)` otherwise, e.g.
+/// `"Atkinson Hyperlegible Bold (TrueType)"` or
+/// `"Cambria & Cambria Math (TrueType)"`. `fallback` (the file stem) names
+/// a file whose names cannot be read.
+#[cfg_attr(not(any(windows, test)), allow(dead_code))]
+fn registry_display_name(data: &[u8], fallback: &str) -> String {
+    let mut names: Vec<String> = Vec::new();
+    let mut postscript_outlines = false;
+    // Windows names registry values in English whatever `--lang` says.
+    let english = ["en-US".to_string()];
+    let name_string = |font: &FontRef<'_>, id| name_string_in(font, id, &english);
+    if let Ok(file) = FileRef::new(data) {
+        for font in file.fonts().flatten() {
+            postscript_outlines |= font.table_data(Tag::new(b"CFF ")).is_some()
+                || font.table_data(Tag::new(b"CFF2")).is_some();
+            let name = name_string(&font, NameId::FULL_NAME).or_else(|| {
+                let family = name_string(&font, NameId::FAMILY_NAME)?;
+                Some(match name_string(&font, NameId::SUBFAMILY_NAME) {
+                    Some(style) => format!("{family} {style}"),
+                    None => family,
+                })
+            });
+            if let Some(name) = name.filter(|name| !names.contains(name)) {
+                names.push(name);
+            }
         }
     }
+    if names.is_empty() {
+        names.push(fallback.to_string());
+    }
+    let kind = if postscript_outlines {
+        "OpenType"
+    } else {
+        "TrueType"
+    };
+    format!("{} ({kind})", names.join(" & "))
+}
 
-    fallback
+/// `name`, or `name` with ` #2`, ` #3`… before its `(TrueType)` suffix
+/// while `taken` says another font already uses it.
+#[cfg_attr(not(any(windows, test)), allow(dead_code))]
+fn unique_registry_value_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(name) {
+        return name.to_string();
+    }
+    let (stem, suffix) = name.rfind(" (").map_or((name, ""), |i| name.split_at(i));
+    (2..)
+        .map(|n| format!("{stem} #{n}{suffix}"))
+        .find(|candidate| !taken(candidate))
+        .expect("some numbered name is free")
 }
 
 #[cfg_attr(not(any(windows, test)), allow(dead_code))]
@@ -363,39 +527,147 @@ fn paths_equal_case_insensitive(left: &Path, right: &Path) -> bool {
         .eq_ignore_ascii_case(&right.to_string_lossy())
 }
 
+/// Delete an installed font file. When it is one of several hard links,
+/// only that link goes and the other names keep the data; the debug log says
+/// which happened.
+#[cfg(windows)]
+fn remove_installed_file(path: &Path) -> FontResult<()> {
+    let removed = links::remove_file(path).map_err(FontError::IoError)?;
+    log::debug!("{}: {}", path.display(), removed);
+    Ok(())
+}
+
+/// The files in the Font Cache Service folder `cache_dir` a cache clear
+/// deletes: every file for all users (`user_sid` is `None`), otherwise only
+/// the `*.dat` caches named after that user, such as
+/// `~FontCache-S-1-5-21-…-1001.dat`.
 #[cfg(any(windows, test))]
-impl WinFontManager {
-    fn program_files_roots(&self) -> Vec<PathBuf> {
-        let mut roots = Vec::new();
+fn service_cache_files(cache_dir: &Path, user_sid: Option<&str>) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(cache_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            let Some(sid) = user_sid else {
+                return true;
+            };
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_ascii_uppercase())
+                .unwrap_or_default();
+            name.ends_with(".DAT") && names_sid(&name, &sid.to_ascii_uppercase())
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Whether `name` contains `sid` as a whole SID: followed by `.`, `-` or
+/// the end of the name, so `…-1105` does not pick up `…-11050`'s files.
+#[cfg(any(windows, test))]
+fn names_sid(name: &str, sid: &str) -> bool {
+    name.match_indices(sid).any(|(start, _)| {
+        matches!(
+            name[start + sid.len()..].chars().next(),
+            None | Some('.') | Some('-')
+        )
+    })
+}
 
-        if let Ok(pf) = std::env::var("ProgramFiles") {
-            roots.push(PathBuf::from(pf));
+/// Delete `files` one by one, counting the ones that were there in `result`
+/// and adding a warning for each that could not be deleted.
+#[cfg(any(windows, test))]
+fn delete_cache_files_reporting(files: Vec<PathBuf>, result: &mut CacheClearResult) {
+    for path in files {
+        match fs::remove_file(&path) {
+            Ok(_) => result.entries_cleared += 1,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => result
+                .warnings
+                .push(format!("Could not delete {}: {err}", path.display())),
         }
+    }
+}
 
-        if let Ok(pf86) = std::env::var("ProgramFiles(x86)") {
-            let candidate = PathBuf::from(&pf86);
-            let lower_candidate = pf86.to_lowercase();
-            let already_present = roots
-                .iter()
-                .any(|p| p.to_string_lossy().to_lowercase() == lower_candidate);
-            if !already_present {
-                roots.push(candidate);
-            }
+/// Restarts the Font Cache Service when dropped, so a cache clear that fails
+/// or panics part-way never leaves the service stopped.
+#[cfg(windows)]
+struct FontCacheServiceGuard<'a> {
+    manager: &'a WinFontManager,
+    restarted: bool,
+}
+
+#[cfg(windows)]
+impl<'a> FontCacheServiceGuard<'a> {
+    fn stop(manager: &'a WinFontManager) -> FontResult<Self> {
+        manager.stop_font_cache_service()?;
+        Ok(Self {
+            manager,
+            restarted: false,
+        })
+    }
+
+    /// Start the service again now, reporting whether it came up.
+    fn restart(mut self) -> FontResult<()> {
+        self.restarted = true;
+        self.manager.start_font_cache_service()
+    }
+}
+
+#[cfg(windows)]
+impl Drop for FontCacheServiceGuard<'_> {
+    fn drop(&mut self) {
+        if self.restarted {
+            return;
         }
+        if let Err(err) = self.manager.start_font_cache_service() {
+            log::warn!("The Font Cache Service did not restart: {err}");
+        }
+    }
+}
 
-        roots
+/// Delete `files`, counting the ones that were there.
+#[cfg(any(windows, test))]
+fn delete_cache_files(files: Vec<PathBuf>) -> FontResult<usize> {
+    let mut removed = 0usize;
+
+    for path in files {
+        match fs::remove_file(&path) {
+            Ok(_) => removed += 1,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(FontError::IoError(err)),
+        }
     }
 
-    fn delete_matching_files(
+    Ok(removed)
+}
+
+#[cfg(any(windows, test))]
+impl WinFontManager {
+    /// Every Program Files tree on this machine, whatever this process's
+    /// architecture (see [`arch::program_files_roots`]).
+    fn program_files_roots(&self) -> Vec<PathBuf> {
+        arch::program_files_roots(|name| std::env::var(name).ok())
+    }
+
+    fn common_files_roots(&self) -> Vec<PathBuf> {
+        arch::common_files_roots(|name| std::env::var(name).ok())
+    }
+
+    /// Files under `root`, recursively, that satisfy `predicate`.
+    fn matching_files(
         &self,
         root: &Path,
         predicate: impl Fn(&Path) -> bool,
-    ) -> FontResult<usize> {
+    ) -> FontResult<Vec<PathBuf>> {
+        let mut files = Vec::new();
         if !root.exists() {
-            return Ok(0);
+            return Ok(files);
         }
 
-        let mut removed = 0usize;
         let mut stack = vec![root.to_path_buf()];
 
         while let Some(dir) = stack.pop() {
@@ -411,35 +683,94 @@ impl WinFontManager {
 
                 if path.is_dir() {
                     stack.push(path);
-                    continue;
-                }
-
-                if predicate(&path) {
-                    match fs::remove_file(&path) {
-                        Ok(_) => removed += 1,
-                        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
-                        Err(err) => return Err(FontError::IoError(err)),
-                    }
+                } else if predicate(&path) {
+                    files.push(path);
                 }
             }
         }
 
-        Ok(removed)
+        Ok(files)
     }
 
-    fn clear_adobe_font_caches(&self) -> FontResult<usize> {
-        let mut removed = 0usize;
-
-        for root in adobe_cache_roots(&self.program_files_roots()) {
-            removed += self.delete_matching_files(&root, |path| {
+    /// Every `AdobeFnt*.lst` font list under the Adobe cache roots.
+    ///
+    /// Roots reached both through Program Files and through Common Files are
+    /// only listed once.
+    fn adobe_cache_files(&self) -> FontResult<Vec<PathBuf>> {
+        let mut roots = adobe_cache_roots(&self.program_files_roots());
+        roots.extend(adobe_common_files_cache_roots(&self.common_files_roots()));
+
+        let mut files = Vec::new();
+        for root in roots {
+            files.extend(self.matching_files(&root, |path| {
                 path.file_name()
                     .and_then(|n| n.to_str())
                     .map(|name| name.starts_with("AdobeFnt") && name.ends_with(".lst"))
                     .unwrap_or(false)
-            })?;
+            })?);
         }
+        files.sort_by_key(|path| path.to_string_lossy().to_lowercase());
+        files.dedup_by(|a, b| paths_equal_case_insensitive(a, b));
+        Ok(files)
+    }
 
-        Ok(removed)
+    fn clear_adobe_font_caches(&self) -> FontResult<usize> {
+        delete_cache_files(self.adobe_cache_files()?)
+    }
+
+    /// The files of a per-user application cache: Java's font configuration
+    /// cache under `%USERPROFILE%\.java\fonts`, and for browsers the
+    /// Chrome and Edge `FontLookupTableCache` and each Firefox profile's
+    /// `startupCache`, which holds its font list. Other targets have none.
+    fn user_app_cache_files(&self, target: CacheTarget) -> FontResult<Vec<PathBuf>> {
+        let env_dir = |name: &str| std::env::var_os(name).map(PathBuf::from);
+        let mut files = Vec::new();
+        match target {
+            CacheTarget::Java => {
+                if let Some(profile) = env_dir("USERPROFILE") {
+                    files.extend(
+                        self.matching_files(&profile.join(".java").join("fonts"), |_| true)?,
+                    );
+                }
+            }
+            CacheTarget::Browser => {
+                let Some(local) = env_dir("LOCALAPPDATA") else {
+                    return Ok(files);
+                };
+                for browser in [["Google", "Chrome"], ["Microsoft", "Edge"]] {
+                    let dir = local
+                        .join(browser[0])
+                        .join(browser[1])
+                        .join("User Data")
+                        .join("FontLookupTableCache");
+                    files.extend(self.matching_files(&dir, |_| true)?);
+                }
+                let profiles = local.join("Mozilla").join("Firefox").join("Profiles");
+                if let Ok(entries) = fs::read_dir(&profiles) {
+                    for entry in entries.flatten() {
+                        let startup_cache = entry.path().join("startupCache");
+                        files.extend(self.matching_files(&startup_cache, |_| true)?);
+                    }
+                }
+            }
+            CacheTarget::System | CacheTarget::Adobe | CacheTarget::Office => {}
+        }
+        Ok(files)
+    }
+
+    /// The cache files a system cache clear deletes: the Font Cache Service
+    /// files, `FNTCACHE.DAT` and the Adobe font lists.
+    fn font_cache_files(&self) -> FontResult<Vec<PathBuf>> {
+        let root = self.system_root();
+        let mut files = self.matching_files(&root.join(FONT_CACHE_DIR), |_| true)?;
+
+        let system_cache = arch::native_system32(&root, ArchInfo::detect()).join("FNTCACHE.DAT");
+        if system_cache.exists() {
+            files.push(system_cache);
+        }
+
+        files.extend(self.adobe_cache_files()?);
+        Ok(files)
     }
 
     /// Determine whether a registry value refers to the given path (handles filename-only entries)
@@ -501,14 +832,37 @@ impl WinFontManager {
     }
 
     fn registry_key(&self, scope: FontScope, access: REGSAM) -> FontResult<RegKey> {
+        self.open_registry_key(scope, access)
+            .map_err(|e| match (scope, &self.target_user) {
+                (FontScope::User, Some(user)) => FontError::RegistrationFailed(format!(
+                    "Cannot open registry key of user {}: {}",
+                    user.sid(),
+                    e
+                )),
+                _ => FontError::RegistrationFailed(format!("Cannot open registry key: {}", e)),
+            })
+    }
+
+    fn open_registry_key(&self, scope: FontScope, access: REGSAM) -> std::io::Result<RegKey> {
+        if let (FontScope::User, Some(user)) = (scope, &self.target_user) {
+            return RegKey::predef(HKEY_USERS)
+                .open_subkey_with_flags(user.hive_subkey(FONTS_REGISTRY_KEY), access);
+        }
         let hive = match scope {
             FontScope::User => HKEY_CURRENT_USER,
             FontScope::System => HKEY_LOCAL_MACHINE,
         };
 
-        RegKey::predef(hive)
-            .open_subkey_with_flags(FONTS_REGISTRY_KEY, access)
-            .map_err(|e| FontError::RegistrationFailed(format!("Cannot open registry key: {}", e)))
+        RegKey::predef(hive).open_subkey_with_flags(FONTS_REGISTRY_KEY, access)
+    }
+
+    /// The fonts registry key of `scope` as shown to users; with
+    /// [`for_user`](Self::for_user) the user scope is the target's hive.
+    fn registry_key_label(&self, scope: FontScope) -> String {
+        match (scope, &self.target_user) {
+            (FontScope::User, Some(user)) => user.hive_display(FONTS_REGISTRY_KEY),
+            _ => registry_key_display(scope),
+        }
     }
 
     fn registry_entries(&self, scope: FontScope) -> FontResult<Vec<(String, PathBuf)>> {
@@ -526,6 +880,46 @@ impl WinFontManager {
         Ok(entries)
     }
 
+    /// [`registry_entries`](Self::registry_entries) for a listing: a key or
+    /// value that cannot be read, such as `HKLM` on a machine that denies
+    /// access, becomes a warning in `listing` instead of failing or being
+    /// skipped. A missing key has no entries.
+    fn listed_registry_entries(
+        &self,
+        scope: FontScope,
+        listing: &mut FontListing,
+    ) -> Vec<(String, PathBuf)> {
+        let label = self.registry_key_label(scope);
+        let key = match self.open_registry_key(scope, KEY_READ) {
+            Ok(key) => key,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+            Err(e) => {
+                listing.warn(format!("cannot read {label}: {e}"));
+                return Vec::new();
+            }
+        };
+
+        let mut entries = Vec::new();
+        for entry in key.enum_values() {
+            let name = match entry {
+                Ok((name, _)) => name,
+                Err(e) => {
+                    listing.warn(format!("cannot read a value of {label}: {e}"));
+                    continue;
+                }
+            };
+            let path = key
+                .get_value::<String, _>(&name)
+                .map_err(FontError::from)
+                .and_then(|raw| self.normalize_registry_path(&raw, scope));
+            match path {
+                Ok(path) => entries.push((name, path)),
+                Err(e) => listing.warn(format!("cannot read {label}\\{name}: {e}")),
+            }
+        }
+        entries
+    }
+
     fn resolve_installed_path(
         &self,
         source: &FontliftFontSource,
@@ -559,6 +953,13 @@ impl WinFontManager {
             if candidate_path.exists() {
                 return Ok((candidate_path, scope));
             }
+            // Installed under a shortened name (see `target_path_for_scope`).
+            if let Ok(shortened) = file_names::fit_target_path(&base, &file_name.to_string_lossy())
+            {
+                if shortened.exists() {
+                    return Ok((shortened, scope));
+                }
+            }
         }
 
         // Fallback to registry entries in either scope to handle renamed fonts (e.g., arial_0.ttf)
@@ -579,6 +980,23 @@ impl WinFontManager {
         Err(FontError::FontNotFound(candidate.clone()))
     }
 
+    /// Run `schtasks.exe` and return its stdout.
+    fn schtasks(&self, args: &[String]) -> FontResult<String> {
+        let output = Command::new("schtasks")
+            .args(args)
+            .output()
+            .map_err(FontError::IoError)?;
+
+        if !output.status.success() {
+            return Err(FontError::RegistrationFailed(format!(
+                "schtasks {} failed: {}",
+                args.first().map(String::as_str).unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
     fn control_service(&self, name: &str, action: &str, fail_on_missing: bool) -> FontResult<()> {
         let output = Command::new("sc")
             .args([action, name])
@@ -608,22 +1026,29 @@ impl WinFontManager {
 
     /// Stop the Windows Font Cache Service before deleting cache files.
     ///
-    /// Two services may be present:
+    /// Two services may be present (same names on x86, x64 and ARM64):
     /// - `FontCache` — the main Windows font cache service (always present on
     ///   Vista+). Required; fail if it cannot be stopped.
     /// - `FontCache3.0.0.0` — the WPF (Windows Presentation Foundation) font
     ///   cache service. Optional; silently skip if it isn't installed.
     fn stop_font_cache_service(&self) -> FontResult<()> {
-        self.control_service("FontCache", "stop", true)?;
-        // WPF font cache service is optional; tolerate missing service
-        let _ = self.control_service("FontCache3.0.0.0", "stop", false);
-        Ok(())
+        self.control_font_cache_services("stop")
     }
 
     /// Restart the Font Cache Service after cache files have been deleted.
     fn start_font_cache_service(&self) -> FontResult<()> {
-        self.control_service("FontCache", "start", true)?;
-        let _ = self.control_service("FontCache3.0.0.0", "start", false);
+        self.control_font_cache_services("start")
+    }
+
+    fn control_font_cache_services(&self, action: &str) -> FontResult<()> {
+        for (name, required) in arch::FONT_CACHE_SERVICES {
+            if required {
+                self.control_service(name, action, true)?;
+            } else {
+                // Optional services are tolerated when missing or stuck
+                let _ = self.control_service(name, action, false);
+            }
+        }
         Ok(())
     }
 
@@ -631,32 +1056,55 @@ impl WinFontManager {
     ///
     /// Two locations:
     /// - `ServiceProfiles\LocalService\AppData\Local\FontCache\` — per-session
-    ///   cache files written by the FontCache service.
-    /// - `System32\FNTCACHE.DAT` — a legacy GDI font cache file. Its removal
-    ///   forces Windows to rebuild font metrics on next boot.
+    ///   cache files written by the FontCache service. Only the `*.dat`
+    ///   files named after `user_sid` go, or every file when it is `None`.
+    /// - `System32\FNTCACHE.DAT` — a legacy GDI font cache file, deleted only
+    ///   for all users. Its removal forces Windows to rebuild font metrics on
+    ///   next boot. A 32-bit build reaches the real `System32` through
+    ///   `Sysnative`, otherwise it would look in `SysWOW64` and silently miss
+    ///   the file.
     ///
-    /// This must be called while the FontCache service is stopped, otherwise
-    /// Windows holds locks on these files and the delete will fail.
-    fn clear_font_cache_files(&self) -> FontResult<()> {
+    /// A clear for all users must run while the FontCache service is
+    /// stopped, otherwise Windows holds locks on these files and the delete
+    /// will fail. A per-user clear leaves the service, and everyone else's
+    /// fonts, running. A file that cannot be deleted becomes a warning in
+    /// `result`; the others are still deleted.
+    fn clear_font_cache_files(&self, user_sid: Option<&str>, result: &mut CacheClearResult) {
         let root = self.system_root();
-        let cache_dir = root.join(FONT_CACHE_DIR);
-        if cache_dir.exists() {
-            if let Ok(entries) = std::fs::read_dir(&cache_dir) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if path.is_file() {
-                        std::fs::remove_file(&path).map_err(FontError::IoError)?;
-                    }
-                }
+        let mut files = service_cache_files(&root.join(FONT_CACHE_DIR), user_sid);
+        if user_sid.is_none() {
+            let system_cache =
+                arch::native_system32(&root, ArchInfo::detect()).join("FNTCACHE.DAT");
+            if system_cache.exists() {
+                files.push(system_cache);
             }
         }
+        delete_cache_files_reporting(files, result);
+    }
 
-        let system_cache = root.join(r"System32\FNTCACHE.DAT");
-        if system_cache.exists() {
-            std::fs::remove_file(&system_cache).map_err(FontError::IoError)?;
-        }
+    /// Mark `path` for deletion during the next restart
+    /// (`MoveFileExW` with `MOVEFILE_DELAY_UNTIL_REBOOT`).
+    ///
+    /// Windows records the request under `PendingFileRenameOperations` in
+    /// HKLM, which is why this needs administrator rights.
+    fn delete_at_reboot(&self, path: &Path) -> FontResult<()> {
+        let path_str = path.to_string_lossy().to_string();
+        let path_wide: Vec<u16> = path_str.encode_utf16().chain(std::iter::once(0)).collect();
 
-        Ok(())
+        unsafe {
+            MoveFileExW(
+                PCWSTR(path_wide.as_ptr()),
+                PCWSTR::null(),
+                MOVEFILE_DELAY_UNTIL_REBOOT,
+            )
+        }
+        .map_err(|e| {
+            FontError::RegistrationFailed(format!(
+                "Cannot schedule {} for deletion at restart: {}",
+                path.display(),
+                e
+            ))
+        })
     }
 
     fn target_path_for_scope(&self, source_path: &Path, scope: FontScope) -> FontResult<PathBuf> {
@@ -669,7 +1117,9 @@ impl WinFontManager {
             FontScope::System => self.get_fonts_directory()?,
         };
 
-        Ok(base.join(file_name))
+        // Deep profile directories plus long file names can exceed MAX_PATH;
+        // the registry value keeps the family name either way.
+        file_names::fit_target_path(&base, &file_name.to_string_lossy())
     }
 
     /// Copy font to target directory based on scope
@@ -691,15 +1141,13 @@ impl WinFontManager {
             }
         }
 
-        if target_path.exists() {
-            if self.is_system_font_path(target_path) {
-                return Err(FontError::SystemFontProtection(target_path.to_path_buf()));
-            }
-
-            fs::remove_file(target_path).map_err(FontError::IoError)?;
+        if target_path.exists() && self.is_system_font_path(target_path) {
+            return Err(FontError::SystemFontProtection(target_path.to_path_buf()));
         }
 
-        fs::copy(source_path, target_path).map_err(FontError::IoError)?;
+        // The copy is renamed over any previous file, so a crash leaves that
+        // file in place rather than nothing.
+        links::copy_file(source_path, target_path).map_err(FontError::IoError)?;
 
         Ok(())
     }
@@ -788,7 +1236,7 @@ impl WinFontManager {
         self.unregister_known_locations(path, scope)?;
 
         if self.is_in_installation_roots(path)? && path.exists() {
-            fs::remove_file(path).map_err(FontError::IoError)?;
+            remove_installed_file(path)?;
         }
 
         Ok(())
@@ -796,25 +1244,51 @@ impl WinFontManager {
 
     /// Write a font entry to the Windows registry so the font survives reboot.
     ///
-    /// The registry value name follows the Windows convention:
-    ///   `"Family Name (TrueType)"` or `"Family Name (OpenType)"`.
-    /// The value data is the font file path. For system-scope fonts Windows
+    /// The registry value name follows the Windows convention (see
+    /// [`registry_display_name`]): `"Full Name (TrueType)"` or
+    /// `"Full Name (OpenType)"`, so each style of a family gets its own
+    /// value. The value data is the font file path. For system-scope fonts Windows
     /// traditionally stores just the filename (e.g. `"arial.ttf"`) because
     /// `C:\Windows\Fonts\` is implied, but fontlift stores full paths to be
     /// unambiguous for both user and system scopes.
-    fn register_font_in_registry(
+    fn register_font_in_registry(&self, path: &Path, scope: FontScope) -> FontResult<()> {
+        let registry_key = self.registry_key(scope, KEY_SET_VALUE)?;
+        let (registry_name, path_str) = self.registry_value(path, path, scope)?;
+        registry_key
+            .set_value(&registry_name, &path_str)
+            .map_err(|e| {
+                FontError::RegistrationFailed(format!("Cannot set registry value: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Registry value name and data [`Self::register_font_in_registry`]
+    /// writes for `path`, naming it from `font_file` (the file that will be
+    /// copied there, when it is not there yet).
+    ///
+    /// A name another file already uses in `scope` gets a ` #2` suffix
+    /// rather than overwriting that font's value; a value that already
+    /// points at `path` keeps its name.
+    fn registry_value(
         &self,
+        font_file: &Path,
         path: &Path,
-        font_info: &FontliftFontFaceInfo,
         scope: FontScope,
-    ) -> FontResult<()> {
-        let registry_key = self.registry_key(scope, KEY_SET_VALUE)?;
-
-        let registry_name = format!(
-            "{} ({})",
-            font_info.family_name,
-            font_info.source.format.as_deref().unwrap_or("TrueType")
-        );
+    ) -> FontResult<(String, String)> {
+        let data = fs::read(font_file).map_err(FontError::IoError)?;
+        let stem = font_file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let existing = self.registry_entries(scope).unwrap_or_default();
+        let registry_name =
+            unique_registry_value_name(&registry_display_name(&data, &stem), |name| {
+                existing.iter().any(|(existing_name, existing_path)| {
+                    existing_name.eq_ignore_ascii_case(name)
+                        && !paths_equal_case_insensitive(existing_path, path)
+                })
+            });
 
         let path_str = if self.is_in_installation_roots(path)? {
             path.file_name()
@@ -824,13 +1298,7 @@ impl WinFontManager {
         } else {
             path.to_string_lossy().to_string()
         };
-        registry_key
-            .set_value(&registry_name, &path_str)
-            .map_err(|e| {
-                FontError::RegistrationFailed(format!("Cannot set registry value: {}", e))
-            })?;
-
-        Ok(())
+        Ok((registry_name, path_str))
     }
 
     /// Determine whether a registry value refers to the given path (handles filename-only entries)
@@ -854,27 +1322,38 @@ impl WinFontManager {
         Ok(())
     }
 
-    /// Enumerate fonts from Windows Registry
-    fn enumerate_fonts_from_registry(&self) -> FontResult<Vec<FontliftFontFaceInfo>> {
+    /// Enumerate fonts from Windows Registry, parsing only the files that
+    /// `filter` has not already ruled out by path, scope and date. Keys and
+    /// files that cannot be read are warnings in `listing`.
+    fn enumerate_fonts_from_registry(
+        &self,
+        cache: &mut MetadataCache,
+        filter: &ListFilter,
+        listing: &mut FontListing,
+    ) -> Vec<FontliftFontFaceInfo> {
         let mut fonts = Vec::new();
 
         for scope in [FontScope::User, FontScope::System] {
-            if let Ok(entries) = self.registry_entries(scope) {
-                for (value_name, path) in entries {
-                    if path.exists() && validation::is_valid_font_extension(&path) {
-                        if let Ok(mut font_info) = self.get_font_info_from_path(&path) {
-                            if let Some(paren_pos) = value_name.find('(') {
-                                font_info.family_name = value_name[..paren_pos].trim().to_string();
-                            }
+            if filter.scope.is_some_and(|wanted| wanted != scope) {
+                continue;
+            }
+            for (_, path) in self.listed_registry_entries(scope, listing) {
+                if path.exists()
+                    && validation::is_valid_font_extension(&path)
+                    && filter.matches_file(&path, Some(scope))
+                {
+                    match cache.face_info(&path, |p| self.get_font_info_from_path(p)) {
+                        Ok(mut font_info) => {
                             font_info.source.scope = Some(scope);
                             fonts.push(font_info);
                         }
+                        Err(e) => listing.warn(format!("skipped {}: {e}", path.display())),
                     }
                 }
             }
         }
 
-        Ok(fonts)
+        fonts
     }
 
     /// Validate system operation permissions
@@ -888,6 +1367,41 @@ impl WinFontManager {
     }
 }
 
+/// A GDI memory font handle (`HANDLE.0`), removed on unregister.
+#[cfg(windows)]
+struct GdiMemoryFont(isize);
+
+#[cfg(windows)]
+impl MemoryRegistration for GdiMemoryFont {
+    fn unregister(&mut self) -> FontResult<()> {
+        if unsafe { RemoveFontMemResourceEx(HANDLE(self.0)) }.as_bool() {
+            Ok(())
+        } else {
+            Err(FontError::RegistrationFailed(
+                "GDI failed to remove the in-memory font".to_string(),
+            ))
+        }
+    }
+}
+
+/// A font file GDI loaded with `FR_PRIVATE` (its NUL-terminated UTF-16
+/// path), removed with the same flag.
+#[cfg(windows)]
+struct GdiPrivateFont(Vec<u16>);
+
+#[cfg(windows)]
+impl MemoryRegistration for GdiPrivateFont {
+    fn unregister(&mut self) -> FontResult<()> {
+        if unsafe { RemoveFontResourceExW(PCWSTR(self.0.as_ptr()), FR_PRIVATE.0, None) }.as_bool() {
+            Ok(())
+        } else {
+            Err(FontError::RegistrationFailed(
+                "GDI failed to remove the private font".to_string(),
+            ))
+        }
+    }
+}
+
 #[cfg(not(windows))]
 impl WinFontManager {
     fn unsupported<T>(&self) -> FontResult<T> {
@@ -900,6 +1414,15 @@ impl WinFontManager {
 #[cfg(windows)]
 impl FontManager for WinFontManager {
     fn install_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+        self.install_font_with_policy(source, ConflictPolicy::Replace)
+            .map(|_| ())
+    }
+
+    fn install_font_with_policy(
+        &self,
+        source: &FontliftFontSource,
+        policy: ConflictPolicy,
+    ) -> FontResult<ConflictOutcome> {
         let scope = source.scope.unwrap_or(FontScope::User);
         let path = &source.path;
         validation::validate_font_file(path)?;
@@ -913,14 +1436,31 @@ impl FontManager for WinFontManager {
         let mut font_info = self.get_font_info_from_path(path)?;
         font_info.source.scope = Some(scope);
 
-        // Remove conflicting installs (same PostScript or family/style) before copying
+        // Conflicting installs (same PostScript or family/style) are handled
+        // by `policy` before copying
         let installed_fonts = self.list_installed_fonts()?;
         let conflicts = conflicts::detect_conflicts(&installed_fonts, &font_info);
-        for conflict in conflicts {
-            self.remove_conflicting_install(conflict)?;
+        let conflict_paths: Vec<PathBuf> =
+            conflicts.iter().map(|c| c.source.path.clone()).collect();
+        let mut target_path = self.target_path_for_scope(path, scope)?;
+        match policy {
+            _ if conflicts.is_empty() => {}
+            ConflictPolicy::Replace => {
+                for conflict in conflicts {
+                    self.remove_conflicting_install(conflict)?;
+                }
+            }
+            ConflictPolicy::Skip => return Ok(ConflictOutcome::skipped(policy, conflict_paths)),
+            ConflictPolicy::Fail => {
+                return Err(FontError::AlreadyInstalled(conflict_paths[0].clone()))
+            }
+            ConflictPolicy::KeepBoth => {
+                if target_path.exists() && !paths_equal_case_insensitive(path, &target_path) {
+                    target_path = conflicts::keep_both_path(&target_path);
+                }
+            }
         }
 
-        let target_path = self.target_path_for_scope(path, scope)?;
         let actions = self.install_journal_actions(path, &target_path, scope);
         let needs_copy = actions
             .first()
@@ -928,31 +1468,25 @@ impl FontManager for WinFontManager {
             .unwrap_or(false);
 
         // Record operation in journal
-        let entry_id = journal::with_journal_lock(|| {
-            let mut j = journal::load_journal().unwrap_or_default();
-            let id = j.record_operation(actions, Some(format!("Install {}", path.display())));
-            journal::save_journal(&j)?;
-            Ok(id)
+        let entry_id = journal::update_journal(|j| {
+            Ok(j.record_operation(
+                actions,
+                Some(format!(
+                    "Install {} (on conflict: {})",
+                    path.display(),
+                    policy
+                )),
+            ))
         })?;
 
         if needs_copy {
             let copy_result = self.copy_font_to_target_directory(path, &target_path, scope);
             match copy_result {
                 Ok(_) => {
-                    let _ = journal::with_journal_lock(|| {
-                        let mut j = journal::load_journal().unwrap_or_default();
-                        let _ = j.mark_step(entry_id, 1);
-                        let _ = journal::save_journal(&j);
-                        Ok(())
-                    });
+                    let _ = journal::update_journal(|j| j.mark_step(entry_id, 1));
                 }
                 Err(e) => {
-                    let _ = journal::with_journal_lock(|| {
-                        let mut j = journal::load_journal().unwrap_or_default();
-                        let _ = j.mark_completed(entry_id);
-                        let _ = journal::save_journal(&j);
-                        Ok(())
-                    });
+                    let _ = journal::update_journal(|j| j.mark_completed(entry_id));
                     return Err(e);
                 }
             }
@@ -963,44 +1497,34 @@ impl FontManager for WinFontManager {
                 .to_string_lossy()
                 .eq_ignore_ascii_case(&target_path.to_string_lossy())
         }) {
-            let _ = journal::with_journal_lock(|| {
-                let mut j = journal::load_journal().unwrap_or_default();
-                let _ = j.mark_completed(entry_id);
-                let _ = journal::save_journal(&j);
-                Ok(())
-            });
+            let _ = journal::update_journal(|j| j.mark_completed(entry_id));
             return Err(FontError::AlreadyInstalled(target_path));
         }
 
         let register_result = (|| {
             self.register_font_with_gdi(&target_path)?;
-            self.register_font_in_registry(&target_path, &font_info, scope)?;
+            self.register_font_in_registry(&target_path, scope)?;
             Ok(())
         })();
 
         // Update journal and clean up on failure
         match &register_result {
             Ok(_) => {
-                let _ = journal::with_journal_lock(|| {
-                    let mut j = journal::load_journal().unwrap_or_default();
-                    let _ = j.mark_completed(entry_id);
-                    let _ = journal::save_journal(&j);
-                    Ok(())
-                });
+                let _ = journal::update_journal(|j| j.mark_completed(entry_id));
             }
             Err(_) => {
                 if needs_copy {
                     let _ = fs::remove_file(&target_path);
                 }
-                let _ = journal::with_journal_lock(|| {
-                    let mut j = journal::load_journal().unwrap_or_default();
-                    let _ = j.mark_completed(entry_id);
-                    let _ = journal::save_journal(&j);
-                    Ok(())
-                });
+                let _ = journal::update_journal(|j| j.mark_completed(entry_id));
             }
         }
-        register_result
+        register_result?;
+        Ok(ConflictOutcome::installed(
+            policy,
+            conflict_paths,
+            target_path,
+        ))
     }
 
     fn uninstall_font(&self, source: &FontliftFontSource) -> FontResult<()> {
@@ -1035,44 +1559,26 @@ impl FontManager for WinFontManager {
 
         // Build journal actions: UnregisterFont -> DeleteFile
         let actions = self.remove_journal_actions(&installed_path, installed_scope);
-        let entry_id = journal::with_journal_lock(|| {
-            let mut j = journal::load_journal().unwrap_or_default();
-            let id = j.record_operation(
+        let entry_id = journal::update_journal(|j| {
+            Ok(j.record_operation(
                 actions,
                 Some(format!("Remove {}", installed_path.display())),
-            );
-            journal::save_journal(&j)?;
-            Ok(id)
+            ))
         })?;
 
         let resolved_source =
             FontliftFontSource::new(installed_path.clone()).with_scope(Some(installed_scope));
         let uninstall_result = self.uninstall_font(&resolved_source);
         if let Err(e) = uninstall_result {
-            let _ = journal::with_journal_lock(|| {
-                let mut j = journal::load_journal().unwrap_or_default();
-                let _ = j.mark_completed(entry_id);
-                let _ = journal::save_journal(&j);
-                Ok(())
-            });
+            let _ = journal::update_journal(|j| j.mark_completed(entry_id));
             return Err(e);
         }
 
-        let _ = journal::with_journal_lock(|| {
-            let mut j = journal::load_journal().unwrap_or_default();
-            let _ = j.mark_step(entry_id, 1);
-            let _ = journal::save_journal(&j);
-            Ok(())
-        });
+        let _ = journal::update_journal(|j| j.mark_step(entry_id, 1));
 
-        std::fs::remove_file(installed_path).map_err(FontError::IoError)?;
+        remove_installed_file(&installed_path)?;
 
-        let _ = journal::with_journal_lock(|| {
-            let mut j = journal::load_journal().unwrap_or_default();
-            let _ = j.mark_completed(entry_id);
-            let _ = journal::save_journal(&j);
-            Ok(())
-        });
+        let _ = journal::update_journal(|j| j.mark_completed(entry_id));
 
         Ok(())
     }
@@ -1108,17 +1614,34 @@ impl FontManager for WinFontManager {
     }
 
     fn list_installed_fonts(&self) -> FontResult<Vec<FontliftFontFaceInfo>> {
+        self.list_installed_fonts_filtered(&ListFilter::default())
+    }
+
+    fn list_installed_fonts_filtered(
+        &self,
+        filter: &ListFilter,
+    ) -> FontResult<Vec<FontliftFontFaceInfo>> {
+        Ok(self.list_installed_fonts_report(filter)?.fonts)
+    }
+
+    /// Building a listing means parsing every registered file, so files the
+    /// filter rules out by scope, extension or date are never opened.
+    /// Registry keys, font directories and files that cannot be read are
+    /// reported as warnings; the listing keeps everything else.
+    fn list_installed_fonts_report(&self, filter: &ListFilter) -> FontResult<FontListing> {
+        let mut listing = FontListing::default();
         let mut fonts = Vec::new();
         let mut seen: BTreeSet<String> = BTreeSet::new();
 
         let mut push_if_new = |mut font: FontliftFontFaceInfo| {
             let key = font.source.path.to_string_lossy().to_lowercase();
-            if seen.insert(key) {
+            if filter.matches(&font) && seen.insert(key) {
                 fonts.push(font);
             }
         };
 
-        for font in self.enumerate_fonts_from_registry()? {
+        let mut cache = MetadataCache::open();
+        for font in self.enumerate_fonts_from_registry(&mut cache, filter, &mut listing) {
             push_if_new(font);
         }
 
@@ -1128,83 +1651,521 @@ impl FontManager for WinFontManager {
         ];
 
         for (scope, dir) in sources {
-            if let Ok(entries) = std::fs::read_dir(&dir) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if path.is_file() && validation::is_valid_font_extension(&path) {
-                        if let Ok(mut info) = self.get_font_info_from_path(&path) {
+            if filter.scope.is_some_and(|wanted| wanted != scope) {
+                continue;
+            }
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    listing.warn(format!("cannot read {}: {e}", dir.display()));
+                    continue;
+                }
+            };
+            for entry in entries {
+                let path = match entry {
+                    Ok(entry) => entry.path(),
+                    Err(e) => {
+                        listing.warn(format!("cannot read an entry of {}: {e}", dir.display()));
+                        continue;
+                    }
+                };
+                if path.is_file()
+                    && validation::is_valid_font_extension(&path)
+                    && filter.matches_file(&path, Some(scope))
+                {
+                    match cache.face_info(&path, |p| self.get_font_info_from_path(p)) {
+                        Ok(mut info) => {
                             info.source.scope = Some(scope);
                             push_if_new(info);
                         }
+                        Err(e) => listing.warn(format!("skipped {}: {e}", path.display())),
                     }
                 }
             }
         }
+        cache.finish();
 
-        Ok(fonts)
+        listing.fonts = fonts;
+        Ok(listing)
     }
 
-    fn clear_font_caches(&self, scope: FontScope) -> FontResult<()> {
-        match scope {
-            FontScope::User => {
+    fn clear_font_caches(&self, strategy: CacheClearStrategy) -> FontResult<CacheClearResult> {
+        self.clear_cache_targets(strategy, &CacheTargets::all())
+    }
+
+    /// Windows keeps no per-user OS font cache, so clearing
+    /// [`CacheTarget::System`] with [`CacheClearStrategy::UserOnly`] is
+    /// refused. The Font Cache Service files of the current user are
+    /// deleted, or all of them and `FNTCACHE.DAT` when `targets` is deep.
+    /// Files that cannot be deleted are reported as warnings. If the service
+    /// does not come back up afterwards, the caches are still gone: the
+    /// result carries a warning and asks for a restart instead of failing.
+    ///
+    /// The Adobe font lists live under Program Files and are cleared
+    /// whatever the strategy; the Java and browser caches are per-user and
+    /// only cleared with the user caches. Office keeps no separate font
+    /// cache on Windows.
+    fn clear_cache_targets(
+        &self,
+        strategy: CacheClearStrategy,
+        targets: &CacheTargets,
+    ) -> FontResult<CacheClearResult> {
+        let mut result = CacheClearResult::default();
+        if targets.contains(CacheTarget::System) {
+            if strategy == CacheClearStrategy::UserOnly {
                 return Err(FontError::PermissionDenied(
                     "Font cache clearing requires administrator privileges on Windows; rerun with --admin"
                         .to_string(),
                 ));
             }
-            FontScope::System => {
-                if !self.has_admin_privileges() {
-                    return Err(FontError::PermissionDenied(
-                        "System cache clearing requires administrator privileges".to_string(),
-                    ));
+            if !self.has_admin_privileges() {
+                return Err(FontError::PermissionDenied(
+                    "System cache clearing requires administrator privileges".to_string(),
+                ));
+            }
+
+            let detected = ArchInfo::detect();
+            log::debug!(
+                "Clearing font caches (OS {:?}, process {:?}{})",
+                detected.os,
+                detected.process,
+                if detected.is_emulated() {
+                    ", emulated"
+                } else {
+                    ""
                 }
+            );
 
-                self.stop_font_cache_service()?;
-                self.clear_font_cache_files()?;
-                let _ = self.clear_adobe_font_caches()?;
-                self.start_font_cache_service()?;
+            // Scoped clears leave other users' caches, FNTCACHE.DAT and the
+            // shared service alone; only the deep clear stops the service.
+            if targets.is_deep() {
+                let service = FontCacheServiceGuard::stop(self)?;
+                self.clear_font_cache_files(None, &mut result);
+                if let Err(err) = service.restart() {
+                    result.restart_required = true;
+                    result = result
+                        .with_warning(format!("The Font Cache Service did not restart: {err}"));
+                }
+            } else {
+                let user_sid = target_user::current_user_sid()?;
+                self.clear_font_cache_files(Some(user_sid.as_str()), &mut result);
+            }
+        }
+
+        if targets.contains(CacheTarget::Adobe) {
+            result.entries_cleared += self.clear_adobe_font_caches()?;
+        }
+
+        let explicit = !targets.is_all();
+        if explicit && targets.contains(CacheTarget::Office) {
+            result = result.with_warning(
+                "Microsoft Office keeps no separate font cache on Windows".to_string(),
+            );
+        }
+        for target in [CacheTarget::Java, CacheTarget::Browser] {
+            if !targets.contains(target) {
+                continue;
+            }
+            if strategy.includes_user() {
+                result.entries_cleared += delete_cache_files(self.user_app_cache_files(target)?)?;
+            } else if explicit {
+                result = result.with_warning(format!(
+                    "Skipped the per-user {} cache in a system-only clear",
+                    target.as_str()
+                ));
             }
         }
 
+        Ok(result)
+    }
+
+    /// The same checks as [`Self::install_font`], then its steps in order:
+    /// conflict removal, copy, GDI registration, registry value, broadcast.
+    fn plan_install(&self, source: &FontliftFontSource) -> FontResult<Vec<PlannedAction>> {
+        let scope = source.scope.unwrap_or(FontScope::User);
+        let path = &source.path;
+        validation::validate_font_file(path)?;
+        self.validate_system_operation(scope)?;
+
+        if self.is_system_font_path(path) {
+            return Err(FontError::SystemFontProtection(path.to_path_buf()));
+        }
+
+        let font_info = self.get_font_info_from_path(path)?;
+        let installed_fonts = self.list_installed_fonts()?;
+        let mut plan: Vec<PlannedAction> =
+            conflicts::detect_conflicts(&installed_fonts, &font_info)
+                .into_iter()
+                .map(|conflict| PlannedAction::RemoveConflict {
+                    path: conflict.source.path.clone(),
+                    postscript_name: conflict.postscript_name.clone(),
+                })
+                .collect();
+
+        let target_path = self.target_path_for_scope(path, scope)?;
+        for action in self.install_journal_actions(path, &target_path, scope) {
+            match action {
+                JournalAction::CopyFile { from, to } => {
+                    plan.push(PlannedAction::CopyFile { from, to })
+                }
+                JournalAction::RegisterFont { path, scope } => {
+                    plan.push(PlannedAction::RegisterFont {
+                        path,
+                        scope,
+                        api: "GDI AddFontResourceW".to_string(),
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        let (name, data) = self.registry_value(path, &target_path, scope)?;
+        plan.push(PlannedAction::WriteRegistryValue {
+            key: self.registry_key_label(scope),
+            name,
+            data,
+        });
+        plan.push(PlannedAction::Notify {
+            mechanism: "WM_FONTCHANGE broadcast".to_string(),
+        });
+
+        Ok(plan)
+    }
+
+    /// Load the font into GDI without copying it or writing a registry entry.
+    ///
+    /// GDI forgets it at logout. Unlike
+    /// [`register_font_private`](FontManager::register_font_private), every
+    /// app in the session sees the font, since fontlift itself exits
+    /// straight away. Long-running callers previewing downloaded fonts can
+    /// skip the file altogether with
+    /// [`TemporaryFont::install_bytes`](fontlift_core::session::TemporaryFont::install_bytes).
+    fn install_font_session(&self, source: &FontliftFontSource) -> FontResult<()> {
+        let path = &source.path;
+        validation::validate_font_file(path)?;
+        self.validate_preinstall(path)?;
+        self.register_font_with_gdi(path)
+    }
+
+    /// Drop the font from GDI again; there is no registry entry to remove.
+    fn uninstall_font_session(&self, source: &FontliftFontSource) -> FontResult<()> {
+        self.unregister_font_from_gdi(&source.path)
+    }
+
+    /// Load the file with `AddFontResourceExW` and `FR_PRIVATE`: only this
+    /// process can use it, nothing is written to the registry and no
+    /// `WM_FONTCHANGE` is broadcast. The listing reads the registry and the
+    /// font directories, so the font never appears in it; the file is not
+    /// copied anywhere.
+    fn register_font_private(&self, source: &FontliftFontSource) -> FontResult<FontHandle> {
+        let path = &source.path;
+        validation::validate_font_file(path)?;
+        let names = fontlift_core::metadata::read_all_face_info(path)
+            .map(|faces| faces.into_iter().map(|face| face.postscript_name).collect())
+            .unwrap_or_default();
+        let path_wide: Vec<u16> = path
+            .to_string_lossy()
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+
+        let count = unsafe { AddFontResourceExW(PCWSTR(path_wide.as_ptr()), FR_PRIVATE, None) };
+        if count == 0 {
+            return Err(FontError::RegistrationFailed(format!(
+                "GDI failed to load font privately: {}",
+                path.display()
+            )));
+        }
+        log::debug!("Loaded {count} face(s) from {} privately", path.display());
+        Ok(FontHandle::new(
+            Box::new(GdiPrivateFont(path_wide)),
+            names,
+            path.display().to_string(),
+        ))
+    }
+
+    /// Load the bytes with `AddFontMemResourceEx`. GDI keeps its own copy,
+    /// the fonts are visible to this process only and no `WM_FONTCHANGE` is
+    /// broadcast.
+    fn register_font_bytes(
+        &self,
+        data: &[u8],
+        options: &MemoryFontOptions,
+    ) -> FontResult<FontHandle> {
+        let label = options.display_name();
+        let names = if options.validate {
+            memory::inspect_font_bytes(data, label)?
+        } else {
+            Vec::new()
+        };
+        let len = u32::try_from(data.len()).map_err(|_| {
+            FontError::InvalidFormat(format!("{label}: font data is larger than 4 GB"))
+        })?;
+
+        let mut count: u32 = 0;
+        let handle = unsafe { AddFontMemResourceEx(data.as_ptr().cast(), len, None, &mut count) };
+        if handle.is_invalid() || count == 0 {
+            return Err(FontError::RegistrationFailed(format!(
+                "GDI failed to load font data: {label}"
+            )));
+        }
+        log::debug!("Loaded {count} face(s) from {label} into GDI");
+        Ok(FontHandle::new(
+            Box::new(GdiMemoryFont(handle.0)),
+            names,
+            label,
+        ))
+    }
+
+    /// Create an `IDWriteFontFace` for every face in the file through the
+    /// shared DirectWrite factory, which hands the work to the Windows Font
+    /// Cache Service. GDI applications are not helped by this; they load
+    /// fonts from the registration `install_font` already made.
+    fn warm_font_cache(&self, source: &FontliftFontSource) -> FontResult<usize> {
+        use windows::Win32::Graphics::DirectWrite::{
+            DWriteCreateFactory, IDWriteFactory, DWRITE_FACTORY_TYPE_SHARED, DWRITE_FONT_FACE_TYPE,
+            DWRITE_FONT_FILE_TYPE, DWRITE_FONT_SIMULATIONS_NONE,
+        };
+
+        let path = &source.path;
+        let failed = |e: windows::core::Error| {
+            FontError::InvalidFormat(format!("DirectWrite cannot load {}: {e}", path.display()))
+        };
+        let path_wide: Vec<u16> = path
+            .to_string_lossy()
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+
+        unsafe {
+            let factory: IDWriteFactory =
+                DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED).map_err(failed)?;
+            let file = factory
+                .CreateFontFileReference(PCWSTR(path_wide.as_ptr()), None)
+                .map_err(failed)?;
+
+            let mut supported = BOOL(0);
+            let mut file_type = DWRITE_FONT_FILE_TYPE::default();
+            let mut face_type = DWRITE_FONT_FACE_TYPE::default();
+            let mut faces = 0u32;
+            file.Analyze(
+                &mut supported,
+                &mut file_type,
+                Some(&mut face_type),
+                &mut faces,
+            )
+            .map_err(failed)?;
+            if !supported.as_bool() {
+                return Err(FontError::InvalidFormat(format!(
+                    "DirectWrite does not support {}",
+                    path.display()
+                )));
+            }
+
+            let files = [Some(file)];
+            for index in 0..faces {
+                let face = factory
+                    .CreateFontFace(face_type, &files, index, DWRITE_FONT_SIMULATIONS_NONE)
+                    .map_err(failed)?;
+                let _ = face.GetGlyphCount();
+            }
+            Ok(faces as usize)
+        }
+    }
+
+    fn source_roots(&self) -> FontResult<Vec<SourceRoot>> {
+        let mut roots = vec![
+            SourceRoot::new(
+                SourceKind::Fonts,
+                self.user_fonts_directory()?,
+                Some(FontScope::User),
+            ),
+            SourceRoot::new(
+                SourceKind::Fonts,
+                self.get_fonts_directory()?,
+                Some(FontScope::System),
+            ),
+            SourceRoot::new(
+                SourceKind::AppCache,
+                self.system_root().join(FONT_CACHE_DIR),
+                Some(FontScope::System),
+            )
+            .with_owner("Windows Font Cache Service"),
+        ];
+        let mut adobe = adobe_cache_roots(&self.program_files_roots());
+        adobe.extend(adobe_common_files_cache_roots(&self.common_files_roots()));
+        adobe.sort_by_key(|path| path.to_string_lossy().to_lowercase());
+        adobe.dedup_by(|a, b| paths_equal_case_insensitive(a, b));
+        roots.extend(adobe.into_iter().map(|dir| {
+            SourceRoot::new(SourceKind::AppCache, dir, Some(FontScope::System)).with_owner("Adobe")
+        }));
+        Ok(roots)
+    }
+
+    fn schedule_cache_clear_at_reboot(&self, scope: FontScope) -> FontResult<Vec<PathBuf>> {
+        if scope == FontScope::User || !self.has_admin_privileges() {
+            return Err(FontError::PermissionDenied(
+                "Scheduling a cache clear at restart requires administrator privileges on Windows; rerun with --admin"
+                    .to_string(),
+            ));
+        }
+
+        let files = self.font_cache_files()?;
+        for file in &files {
+            self.delete_at_reboot(file)?;
+        }
+        Ok(files)
+    }
+
+    /// Create (or replace) a Task Scheduler task. System tasks run as
+    /// `SYSTEM` and need administrator rights to create.
+    fn schedule_task(&self, task: &ScheduledTask) -> FontResult<String> {
+        self.validate_system_operation(task.scope)?;
+        if let Some(parent) = task.log_file.parent() {
+            fs::create_dir_all(parent).map_err(FontError::IoError)?;
+        }
+        self.schtasks(&schedule::schtasks_create_args(task))?;
+        Ok(task.task_path())
+    }
+
+    fn list_scheduled_tasks(&self) -> FontResult<Vec<ScheduledTaskInfo>> {
+        let csv = self.schtasks(&["/Query", "/FO", "CSV", "/NH"].map(String::from))?;
+        Ok(schedule::schtasks_task_infos_for_current_user(&csv))
+    }
+
+    fn remove_scheduled_task(&self, name: &str, scope: FontScope) -> FontResult<()> {
+        self.validate_system_operation(scope)?;
+        let path = schedule::task_path(name, scope);
+        self.schtasks(&[
+            "/Delete".to_string(),
+            "/TN".to_string(),
+            path,
+            "/F".to_string(),
+        ])
+        .map(|_| ())
+    }
+
+    /// Registry first: a font Windows loaded at sign-in may refuse
+    /// `RemoveFontResourceW` while it is in use, and without its registry
+    /// value it stays off from the next sign-in either way.
+    fn deactivate_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+        let scope = source.scope.unwrap_or(FontScope::User);
+        self.validate_system_operation(scope)?;
+        self.unregister_font_from_registry(&source.path, scope)?;
+        let _ = self.unregister_font_from_gdi(&source.path);
         Ok(())
     }
 
-    fn prune_missing_fonts(&self, scope: FontScope) -> FontResult<usize> {
+    /// Register in place: `install_font` refuses files already in
+    /// `C:\Windows\Fonts`, which is where a disabled system font goes back to.
+    fn reactivate_font(&self, source: &FontliftFontSource) -> FontResult<()> {
+        let scope = source.scope.unwrap_or(FontScope::User);
+        self.validate_system_operation(scope)?;
+        self.register_font_in_registry(&source.path, scope)?;
+        self.register_font_with_gdi(&source.path)
+    }
+
+    fn find_stale_registrations(&self, scope: FontScope) -> FontResult<Vec<PrunedRegistration>> {
         self.validate_system_operation(scope)?;
 
-        let key = self.registry_key(scope, KEY_READ | KEY_SET_VALUE)?;
-        let mut removed = 0usize;
+        let key = self.registry_key(scope, KEY_READ)?;
+        let mut stale = Vec::new();
 
         for value in key.enum_values().flatten() {
             let name = value.0;
             if let Ok(path_str) = key.get_value::<String, _>(&name) {
-                let normalized = match self.normalize_registry_path(&path_str, scope) {
-                    Ok(p) => p,
-                    Err(_) => {
-                        key.delete_value(name).map_err(|e| {
-                            FontError::RegistrationFailed(format!(
-                                "Cannot delete registry value for malformed path: {}",
-                                e
-                            ))
-                        })?;
-                        removed += 1;
-                        continue;
+                match self.normalize_registry_path(&path_str, scope) {
+                    Ok(normalized) => {
+                        if let Some(reason) = PruneReason::for_path(&normalized) {
+                            stale.push(PrunedRegistration {
+                                name,
+                                path: Some(normalized),
+                                reason,
+                            });
+                        }
                     }
-                };
-
-                if !normalized.exists() || !validation::is_valid_font_extension(&normalized) {
-                    key.delete_value(name).map_err(|e| {
-                        FontError::RegistrationFailed(format!(
-                            "Cannot delete registry value for missing font: {}",
-                            e
-                        ))
-                    })?;
-                    removed += 1;
+                    Err(_) => stale.push(PrunedRegistration {
+                        name,
+                        path: None,
+                        reason: PruneReason::MalformedPath,
+                    }),
                 }
             }
         }
 
-        Ok(removed)
+        Ok(stale)
+    }
+
+    fn remove_stale_registration(
+        &self,
+        scope: FontScope,
+        registration: &PrunedRegistration,
+    ) -> FontResult<()> {
+        self.validate_system_operation(scope)?;
+
+        let key = self.registry_key(scope, KEY_READ | KEY_SET_VALUE)?;
+        match key.delete_value(&registration.name) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(FontError::RegistrationFailed(format!(
+                "Cannot delete registry value for {} ({}): {}",
+                registration.name,
+                registration.reason.description(),
+                e
+            ))),
+        }
+    }
+
+    /// Each copy is merged on its own: its registry values are deleted or
+    /// pointed at the original, GDI drops the copy, then the file goes. A
+    /// value that cannot be changed keeps its copy on disk; either failure
+    /// becomes a warning and the other copies are still merged.
+    fn repair_renamed_copies(&self, scope: FontScope, dry_run: bool) -> FontResult<RepairReport> {
+        self.validate_system_operation(scope)?;
+
+        let mut report = renamed_copies::plan_repair(scope, &self.registry_entries(scope)?)?;
+        if dry_run {
+            return Ok(report);
+        }
+
+        let key = self.registry_key(scope, KEY_READ | KEY_SET_VALUE)?;
+        for merge in report.merged.iter_mut() {
+            let data = if merge.canonical_registered {
+                None
+            } else {
+                Some(
+                    self.registry_value(&merge.canonical, &merge.canonical, scope)?
+                        .1,
+                )
+            };
+            let updated = merge.registrations.iter().try_for_each(|name| {
+                let written = match &data {
+                    Some(data) => key.set_value(name, data),
+                    None => key.delete_value(name),
+                };
+                written.map_err(|e| format!("cannot update registry value {name}: {e}"))
+            });
+            if let Err(e) = updated {
+                report
+                    .warnings
+                    .push(format!("{} stays registered: {e}", merge.copy.display()));
+                continue;
+            }
+
+            // The copy may not be loaded in this session; that is fine.
+            let _ = self.unregister_font_from_gdi(&merge.copy);
+            if !merge.canonical_registered {
+                let _ = self.register_font_with_gdi(&merge.canonical);
+            }
+            match remove_installed_file(&merge.copy) {
+                Ok(()) => merge.deleted = true,
+                Err(e) => report
+                    .warnings
+                    .push(format!("{} stays on disk: {e}", merge.copy.display())),
+            }
+        }
+        Ok(report)
     }
 }
 
@@ -1215,6 +2176,11 @@ impl FontManager for WinFontManager {
         self.unsupported()
     }
 
+    fn plan_install(&self, source: &FontliftFontSource) -> FontResult<Vec<PlannedAction>> {
+        let _ = source;
+        self.unsupported()
+    }
+
     fn uninstall_font(&self, source: &FontliftFontSource) -> FontResult<()> {
         let _ = source;
         self.unsupported()
@@ -1234,8 +2200,8 @@ impl FontManager for WinFontManager {
         self.unsupported()
     }
 
-    fn clear_font_caches(&self, scope: FontScope) -> FontResult<()> {
-        let _ = scope;
+    fn clear_font_caches(&self, strategy: CacheClearStrategy) -> FontResult<CacheClearResult> {
+        let _ = strategy;
         self.unsupported()
     }
 }
@@ -1283,6 +2249,7 @@ mod tests {
         let upper = temp.path().to_string_lossy().to_uppercase();
         let _guard_pf = EnvGuard::set("ProgramFiles", temp.path());
         let _guard_pf86 = EnvGuard::set("ProgramFiles(x86)", upper);
+        let _guard_other = EnvGuard::unset_all(&["ProgramW6432", "ProgramFiles(Arm)"]);
 
         let roots = manager.program_files_roots();
         assert_eq!(roots.len(), 1);
@@ -1310,6 +2277,7 @@ mod tests {
 
         let _guard_pf = EnvGuard::set("ProgramFiles", pf.path());
         let _guard_pf86 = EnvGuard::set("ProgramFiles(x86)", pf86.path());
+        let _guard_other = EnvGuard::unset_all(&ARCH_SPECIFIC_ROOTS);
 
         let removed = manager
             .clear_adobe_font_caches()
@@ -1321,6 +2289,193 @@ mod tests {
         assert!(keep.exists());
     }
 
+    #[test]
+    fn user_app_cache_files_find_java_and_browser_font_caches() {
+        let _env_lock = lock_env();
+        let manager = WinFontManager::new();
+        let profile = TempDir::new().expect("userprofile");
+        let local = TempDir::new().expect("localappdata");
+
+        let java = profile.path().join(".java/fonts/21");
+        fs::create_dir_all(&java).unwrap();
+        let fcinfo = java.join("fcinfo-1-host-Windows-10.0-en.properties");
+        fs::write(&fcinfo, b"cache").unwrap();
+
+        let chrome = local
+            .path()
+            .join("Google/Chrome/User Data/FontLookupTableCache");
+        fs::create_dir_all(&chrome).unwrap();
+        let table = chrome.join("font_unique_name_table.pb");
+        fs::write(&table, b"cache").unwrap();
+        let firefox = local
+            .path()
+            .join("Mozilla/Firefox/Profiles/abc.default/startupCache");
+        fs::create_dir_all(&firefox).unwrap();
+        let startup = firefox.join("startupCache.8.little");
+        fs::write(&startup, b"cache").unwrap();
+
+        let _guard_profile = EnvGuard::set("USERPROFILE", profile.path());
+        let _guard_local = EnvGuard::set("LOCALAPPDATA", local.path());
+
+        assert_eq!(
+            manager.user_app_cache_files(CacheTarget::Java).unwrap(),
+            vec![fcinfo]
+        );
+        let mut browser = manager.user_app_cache_files(CacheTarget::Browser).unwrap();
+        browser.sort();
+        let mut expected = vec![table, startup];
+        expected.sort();
+        assert_eq!(browser, expected);
+        assert!(manager
+            .user_app_cache_files(CacheTarget::Office)
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(delete_cache_files(browser).unwrap(), 2);
+    }
+
+    #[test]
+    fn scoped_cache_clears_pick_the_users_dat_files_and_report_failures() {
+        let dir = TempDir::new().expect("cache dir");
+        let sid = "S-1-5-21-1-2-3-1001";
+        let mine = dir.path().join(format!("~FontCache-{sid}.dat"));
+        let other = dir.path().join("~FontCache-S-1-5-21-1-2-3-1002.dat");
+        let system = dir.path().join("~FontCache-System.dat");
+        let log = dir.path().join(format!("~FontCache-{sid}.log"));
+        for file in [&mine, &other, &system, &log] {
+            fs::write(file, b"cache").unwrap();
+        }
+
+        assert_eq!(
+            service_cache_files(dir.path(), Some(&sid.to_lowercase())),
+            vec![mine.clone()]
+        );
+        assert_eq!(service_cache_files(dir.path(), None).len(), 4);
+        assert!(service_cache_files(&dir.path().join("missing"), None).is_empty());
+
+        let mut result = CacheClearResult::default();
+        let locked = dir.path().join("locked");
+        fs::create_dir(&locked).unwrap();
+        delete_cache_files_reporting(
+            vec![
+                mine.clone(),
+                dir.path().join("gone.dat"),
+                locked,
+                other.clone(),
+            ],
+            &mut result,
+        );
+        assert_eq!(result.entries_cleared, 2);
+        assert_eq!(result.warnings.len(), 1, "{:?}", result.warnings);
+        assert!(!mine.exists() && !other.exists() && system.exists());
+    }
+
+    #[test]
+    fn scoped_cache_clears_match_the_whole_sid() {
+        let dir = TempDir::new().expect("cache dir");
+        let short = "S-1-5-21-1-2-3-1105";
+        let long = "S-1-5-21-1-2-3-11050";
+        let mine = dir.path().join(format!("~FontCache-{short}.dat"));
+        let mine_part = dir.path().join(format!("~FontCache-{short}-1.dat"));
+        let theirs = dir.path().join(format!("~FontCache-{long}.dat"));
+        for file in [&mine, &mine_part, &theirs] {
+            fs::write(file, b"cache").unwrap();
+        }
+
+        assert_eq!(
+            service_cache_files(dir.path(), Some(short)),
+            vec![mine_part, mine]
+        );
+        assert_eq!(service_cache_files(dir.path(), Some(long)), vec![theirs]);
+    }
+
+    #[test]
+    fn font_cache_files_lists_service_gdi_and_adobe_caches() {
+        let _env_lock = lock_env();
+        let manager = WinFontManager::new();
+        let windir = TempDir::new().expect("windir");
+        let pf = TempDir::new().expect("pf dir");
+
+        let service_dir = windir.path().join(FONT_CACHE_DIR);
+        fs::create_dir_all(&service_dir).unwrap();
+        let service_cache = service_dir.join("FontCache-System.dat");
+        fs::write(&service_cache, b"cache").unwrap();
+        let system32 = windir.path().join("System32");
+        fs::create_dir_all(&system32).unwrap();
+        let fntcache = system32.join("FNTCACHE.DAT");
+        fs::write(&fntcache, b"cache").unwrap();
+        let typespt = pf.path().join("Common Files/Adobe/TypeSpt");
+        fs::create_dir_all(&typespt).unwrap();
+        let adobe_list = typespt.join("AdobeFnt13.lst");
+        fs::write(&adobe_list, b"cache").unwrap();
+
+        let _guard_windir = EnvGuard::set("WINDIR", windir.path());
+        let _guard_pf = EnvGuard::set("ProgramFiles", pf.path());
+        let _guard_common = EnvGuard::set("CommonProgramFiles", pf.path().join("Common Files"));
+        let _guard_other = EnvGuard::unset_all(&[
+            "ProgramW6432",
+            "ProgramFiles(x86)",
+            "ProgramFiles(Arm)",
+            "CommonProgramW6432",
+            "CommonProgramFiles(x86)",
+            "CommonProgramFiles(Arm)",
+            "PROCESSOR_ARCHITECTURE",
+            "PROCESSOR_ARCHITEW6432",
+        ]);
+
+        let files = manager.font_cache_files().expect("cache files");
+        assert_eq!(files, vec![service_cache, fntcache, adobe_list]);
+    }
+
+    const ARCH_SPECIFIC_ROOTS: [&str; 6] = [
+        "ProgramW6432",
+        "ProgramFiles(Arm)",
+        "CommonProgramFiles",
+        "CommonProgramW6432",
+        "CommonProgramFiles(x86)",
+        "CommonProgramFiles(Arm)",
+    ];
+
+    #[test]
+    fn clear_adobe_font_caches_covers_arm64_layout_seen_from_x86_process() {
+        let _env_lock = lock_env();
+        let manager = WinFontManager::new();
+        // An x86 process on ARM64: its own ProgramFiles is the x86 tree, the
+        // native and Arm trees are only named by the extra variables.
+        let native = TempDir::new().expect("native dir");
+        let x86 = TempDir::new().expect("x86 dir");
+        let arm = TempDir::new().expect("arm dir");
+        let relocated_common = TempDir::new().expect("common dir");
+
+        let mut lst_files = Vec::new();
+        for (base, sub) in [
+            (native.path(), "Common Files/Adobe/TypeSupport"),
+            (x86.path(), "Common Files/Adobe/PDFL/9.9"),
+            (arm.path(), "Common Files/Adobe/TypeSpt"),
+            (relocated_common.path(), "Adobe/TypeSpt"),
+        ] {
+            let dir = base.join(sub);
+            fs::create_dir_all(&dir).unwrap();
+            let lst = dir.join("AdobeFnt13.lst");
+            fs::write(&lst, b"dummy").unwrap();
+            lst_files.push(lst);
+        }
+
+        let _guard_unset = EnvGuard::unset_all(&ARCH_SPECIFIC_ROOTS);
+        let _guard_pf = EnvGuard::set("ProgramFiles", x86.path());
+        let _guard_pf86 = EnvGuard::set("ProgramFiles(x86)", x86.path());
+        let _guard_w6432 = EnvGuard::set("ProgramW6432", native.path());
+        let _guard_arm = EnvGuard::set("ProgramFiles(Arm)", arm.path());
+        let _guard_common = EnvGuard::set("CommonProgramW6432", relocated_common.path());
+
+        let removed = manager
+            .clear_adobe_font_caches()
+            .expect("cache cleanup should succeed");
+
+        assert_eq!(removed, lst_files.len());
+        assert!(lst_files.iter().all(|lst| !lst.exists()));
+    }
+
     #[cfg(windows)]
     #[test]
     fn test_system_font_path_detection() {
@@ -1375,6 +2530,53 @@ mod tests {
         assert_eq!(info.source.format.as_deref(), Some("TTF"));
     }
 
+    #[test]
+    fn registry_value_names_follow_windows_conventions() {
+        let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/fonts");
+        let ttf = fs::read(fixtures.join("AtkinsonHyperlegible-Regular.ttf")).unwrap();
+        assert_eq!(
+            registry_display_name(&ttf, "x"),
+            "Atkinson Hyperlegible Regular (TrueType)"
+        );
+        let otf = fs::read(fixtures.join("AtkinsonHyperlegible-Regular.otf")).unwrap();
+        assert_eq!(
+            registry_display_name(&otf, "x"),
+            "Atkinson Hyperlegible Regular (OpenType)"
+        );
+
+        let bold = fontlift_testfonts::TestFont::new("Registry Sans").with_style("Bold");
+        assert_eq!(
+            registry_display_name(&bold.build(), "x"),
+            "Registry Sans Bold (TrueType)"
+        );
+        let collection = fontlift_testfonts::build_collection(&[
+            fontlift_testfonts::TestFont::new("Registry Serif"),
+            fontlift_testfonts::TestFont::new("Registry Serif").with_style("Italic"),
+        ]);
+        assert_eq!(
+            registry_display_name(&collection, "x"),
+            "Registry Serif Regular & Registry Serif Italic (TrueType)"
+        );
+        assert_eq!(
+            registry_display_name(b"not a font", "broken"),
+            "broken (TrueType)"
+        );
+
+        let taken = [
+            "Registry Sans Bold (TrueType)",
+            "registry sans bold #2 (TrueType)",
+        ];
+        let is_taken = |name: &str| taken.iter().any(|t| t.eq_ignore_ascii_case(name));
+        assert_eq!(
+            unique_registry_value_name("Registry Sans Bold (TrueType)", is_taken),
+            "Registry Sans Bold #3 (TrueType)"
+        );
+        assert_eq!(
+            unique_registry_value_name("Registry Sans Italic (TrueType)", is_taken),
+            "Registry Sans Italic (TrueType)"
+        );
+    }
+
     #[test]
     fn get_font_info_from_path_extracts_metadata_from_otf_fixture() {
         let manager = WinFontManager::new();
@@ -1507,6 +2709,15 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn plan_registry_keys_name_the_scope_hive() {
+        assert_eq!(
+            registry_key_display(FontScope::User),
+            r"HKCU\Software\Microsoft\Windows NT\CurrentVersion\Fonts"
+        );
+        assert!(registry_key_display(FontScope::System).starts_with(r"HKLM\Software\"));
+    }
+
     #[test]
     fn validation_preinstall_rejects_malformed_font_when_enabled() {
         let manager = WinFontManager::with_validation(ValidatorConfig::default());
@@ -1550,6 +2761,16 @@ mod tests {
             std::env::set_var(key, value);
             Self { key, previous }
         }
+
+        fn unset_all(keys: &[&'static str]) -> Vec<Self> {
+            keys.iter()
+                .map(|&key| {
+                    let previous = std::env::var(key).ok();
+                    std::env::remove_var(key);
+                    Self { key, previous }
+                })
+                .collect()
+        }
     }
 
     impl Drop for EnvGuard {
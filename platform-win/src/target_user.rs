@@ -0,0 +1,352 @@
+//! Installing fonts into another user's profile.
+//!
+//! IT administrators usually run fontlift elevated, so the per-user scope
+//! would be the administrator's own. [`TargetUser`] points the user scope
+//! at someone else instead:
+//!
+//! - Files go to `<profile>\AppData\Local\Microsoft\Windows\Fonts`, where
+//!   `<profile>` is the user's `ProfileImagePath` under
+//!   `HKLM\SOFTWARE\Microsoft\Windows NT\CurrentVersion\ProfileList\<SID>`.
+//! - Registry values go to `HKEY_USERS\<SID>\Software\Microsoft\Windows NT\
+//!   CurrentVersion\Fonts`. A signed-in user's hive is already loaded there.
+//!   For anyone else fontlift loads `<profile>\NTUSER.DAT` under
+//!   `HKEY_USERS\fontlift-<SID>` (which needs the backup and restore
+//!   privileges an elevated administrator holds) and unloads it when the
+//!   [`TargetUser`] is dropped.
+//!
+//! The user is named by SID (`S-1-5-21-…`) or by account name (`alice`,
+//! `CORP\alice`). The profile must already exist: the user has to have
+//! signed in on this machine once. GDI registration still happens in the
+//! administrator's session, so the target user sees the fonts from their
+//! next sign-in.
+
+#[cfg(windows)]
+use fontlift_core::{FontError, FontResult};
+use std::path::{Path, PathBuf};
+
+#[cfg(windows)]
+use windows::core::{HSTRING, PCWSTR, PWSTR};
+#[cfg(windows)]
+use windows::Win32::Foundation::{
+    CloseHandle, LocalFree, ERROR_SUCCESS, HANDLE, HLOCAL, LUID, PSID,
+};
+#[cfg(windows)]
+use windows::Win32::Security::Authorization::ConvertSidToStringSidW;
+#[cfg(windows)]
+use windows::Win32::Security::{
+    AdjustTokenPrivileges, LookupAccountNameW, LookupPrivilegeValueW, SE_BACKUP_NAME,
+    SE_PRIVILEGE_ENABLED, SE_RESTORE_NAME, SID_NAME_USE, TOKEN_ADJUST_PRIVILEGES, TOKEN_PRIVILEGES,
+    TOKEN_QUERY,
+};
+#[cfg(windows)]
+use windows::Win32::System::Registry::{RegLoadKeyW, RegUnLoadKeyW, HKEY_USERS};
+#[cfg(windows)]
+use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+/// Registry key listing every profile on the machine, one subkey per SID.
+#[cfg_attr(not(windows), allow(dead_code))]
+const PROFILE_LIST_KEY: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\ProfileList";
+
+/// A user whose per-user fonts fontlift manages instead of the caller's.
+#[derive(Debug)]
+pub struct TargetUser {
+    sid: String,
+    profile_dir: PathBuf,
+    /// Name under `HKEY_USERS` of the hive fontlift loaded itself, unloaded
+    /// on drop; `None` when the user's hive was already loaded.
+    mounted_hive: Option<String>,
+}
+
+impl TargetUser {
+    /// The user's SID, e.g. `S-1-5-21-…-1001`.
+    pub fn sid(&self) -> &str {
+        &self.sid
+    }
+
+    /// The user's profile directory, e.g. `C:\Users\alice`.
+    pub fn profile_dir(&self) -> &Path {
+        &self.profile_dir
+    }
+
+    /// The user's per-user Fonts directory.
+    pub fn fonts_directory(&self) -> PathBuf {
+        self.profile_dir
+            .join("AppData")
+            .join("Local")
+            .join("Microsoft")
+            .join("Windows")
+            .join("Fonts")
+    }
+
+    /// `subkey` of the user's hive, relative to `HKEY_USERS`.
+    pub fn hive_subkey(&self, subkey: &str) -> String {
+        let root = self.mounted_hive.as_deref().unwrap_or(&self.sid);
+        format!(r"{root}\{subkey}")
+    }
+
+    /// `subkey` of the user's hive as shown in plans, e.g.
+    /// `HKU\S-1-5-21-…\Software\…`.
+    pub fn hive_display(&self, subkey: &str) -> String {
+        format!(r"HKU\{}\{subkey}", self.sid)
+    }
+}
+
+/// Does `value` look like a string SID (`S-1-5-21-…`)?
+pub fn is_sid(value: &str) -> bool {
+    let mut parts = value.split('-');
+    let prefix_ok = parts
+        .next()
+        .map(|s| s.eq_ignore_ascii_case("S"))
+        .unwrap_or(false);
+    let rest: Vec<&str> = parts.collect();
+    prefix_ok
+        && rest.len() >= 2
+        && rest
+            .iter()
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Expand the `%VAR%` references in a `REG_EXPAND_SZ` value such as
+/// `%SystemDrive%\Users\alice`. Unknown variables are left as written.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn expand_environment(value: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        expanded.push_str(&rest[..start]);
+        match lookup(name).filter(|_| !name.is_empty()) {
+            Some(value) => expanded.push_str(&value),
+            None => expanded.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(windows)]
+impl TargetUser {
+    /// Resolve `user` (a SID or an account name) and make its hive
+    /// available under `HKEY_USERS`, loading it if the user is signed out.
+    ///
+    /// The caller checks for elevation first; loading a hive fails without
+    /// it anyway.
+    pub fn resolve(user: &str) -> FontResult<Self> {
+        let sid = if is_sid(user) {
+            user.to_ascii_uppercase()
+        } else {
+            lookup_account_sid(user)?
+        };
+        let profile_dir = profile_directory(&sid)?;
+
+        let users = winreg::RegKey::predef(winreg::enums::HKEY_USERS);
+        let mounted_hive = if users.open_subkey(&sid).is_ok() {
+            None
+        } else {
+            let name = format!("fontlift-{sid}");
+            let hive_file = profile_dir.join("NTUSER.DAT");
+            enable_privilege(SE_BACKUP_NAME)?;
+            enable_privilege(SE_RESTORE_NAME)?;
+            let status = unsafe {
+                RegLoadKeyW(
+                    HKEY_USERS,
+                    &HSTRING::from(name.as_str()),
+                    &HSTRING::from(hive_file.as_os_str()),
+                )
+            };
+            if status != ERROR_SUCCESS {
+                return Err(FontError::PermissionDenied(format!(
+                    "Cannot load the registry hive {} of {user}: {}",
+                    hive_file.display(),
+                    windows::core::Error::from(status.to_hresult())
+                )));
+            }
+            Some(name)
+        };
+
+        Ok(Self {
+            sid,
+            profile_dir,
+            mounted_hive,
+        })
+    }
+}
+
+#[cfg(windows)]
+impl Drop for TargetUser {
+    fn drop(&mut self) {
+        if let Some(name) = self.mounted_hive.take() {
+            let status = unsafe { RegUnLoadKeyW(HKEY_USERS, &HSTRING::from(name.as_str())) };
+            if status != ERROR_SUCCESS {
+                log::warn!(
+                    "Could not unload the registry hive of {} (HKU\\{name})",
+                    self.sid
+                );
+            }
+        }
+    }
+}
+
+/// The string SID of the account fontlift runs as.
+#[cfg(windows)]
+pub fn current_user_sid() -> FontResult<String> {
+    let user = std::env::var("USERNAME").map_err(|_| {
+        FontError::InvalidArgument("Cannot tell the current user: USERNAME is not set".to_string())
+    })?;
+    match std::env::var("USERDOMAIN") {
+        Ok(domain) if !domain.is_empty() => lookup_account_sid(&format!(r"{domain}\{user}")),
+        _ => lookup_account_sid(&user),
+    }
+}
+
+/// The string SID of the account `name`.
+#[cfg(windows)]
+fn lookup_account_sid(name: &str) -> FontResult<String> {
+    let account = HSTRING::from(name);
+    let not_found = |e: windows::core::Error| {
+        FontError::InvalidArgument(format!("Unknown user account '{name}': {e}"))
+    };
+    unsafe {
+        let mut sid_len = 0u32;
+        let mut domain_len = 0u32;
+        let mut sid_use = SID_NAME_USE::default();
+        // The first call only reports the buffer sizes.
+        let _ = LookupAccountNameW(
+            PCWSTR::null(),
+            &account,
+            PSID::default(),
+            &mut sid_len,
+            PWSTR::null(),
+            &mut domain_len,
+            &mut sid_use,
+        );
+        let mut sid = vec![0u8; sid_len as usize];
+        let mut domain = vec![0u16; domain_len as usize];
+        LookupAccountNameW(
+            PCWSTR::null(),
+            &account,
+            PSID(sid.as_mut_ptr().cast()),
+            &mut sid_len,
+            PWSTR(domain.as_mut_ptr()),
+            &mut domain_len,
+            &mut sid_use,
+        )
+        .map_err(not_found)?;
+
+        let mut string_sid = PWSTR::null();
+        ConvertSidToStringSidW(PSID(sid.as_mut_ptr().cast()), &mut string_sid)
+            .map_err(not_found)?;
+        let text = string_sid.to_string();
+        let _ = LocalFree(HLOCAL(string_sid.0.cast()));
+        text.map_err(|e| FontError::InvalidArgument(format!("Invalid SID for '{name}': {e}")))
+    }
+}
+
+/// The profile directory `ProfileList` records for `sid`.
+#[cfg(windows)]
+fn profile_directory(sid: &str) -> FontResult<PathBuf> {
+    let key = winreg::RegKey::predef(winreg::enums::HKEY_LOCAL_MACHINE)
+        .open_subkey(format!(r"{PROFILE_LIST_KEY}\{sid}"))
+        .map_err(|_| {
+            FontError::InvalidFormat(format!(
+                "No user profile for {sid}; the user has to sign in on this machine once"
+            ))
+        })?;
+    let raw: String = key.get_value("ProfileImagePath").map_err(|e| {
+        FontError::InvalidFormat(format!("Cannot read the profile path of {sid}: {e}"))
+    })?;
+    Ok(PathBuf::from(expand_environment(&raw, |name| {
+        std::env::var(name).ok()
+    })))
+}
+
+/// Enable `privilege` on this process's token.
+#[cfg(windows)]
+fn enable_privilege(privilege: PCWSTR) -> FontResult<()> {
+    let denied = |e: windows::core::Error| {
+        FontError::PermissionDenied(format!(
+            "Loading another user's registry hive needs an elevated prompt: {e}"
+        ))
+    };
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(
+            GetCurrentProcess(),
+            TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY,
+            &mut token,
+        )
+        .map_err(denied)?;
+        let mut luid = LUID::default();
+        let result = LookupPrivilegeValueW(PCWSTR::null(), privilege, &mut luid).and_then(|_| {
+            let mut privileges = TOKEN_PRIVILEGES {
+                PrivilegeCount: 1,
+                ..Default::default()
+            };
+            privileges.Privileges[0].Luid = luid;
+            privileges.Privileges[0].Attributes = SE_PRIVILEGE_ENABLED;
+            AdjustTokenPrivileges(token, false, Some(&privileges), 0, None, None)
+        });
+        let _ = CloseHandle(token);
+        result.map_err(denied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_string_sids() {
+        assert!(is_sid("S-1-5-21-3623811015-3361044348-30300820-1013"));
+        assert!(is_sid("s-1-5-18"));
+        assert!(!is_sid("alice"));
+        assert!(!is_sid(r"CORP\alice"));
+        assert!(!is_sid("S-1"));
+        assert!(!is_sid("S-1-5-x"));
+        assert!(!is_sid("S-1--5"));
+    }
+
+    #[test]
+    fn expands_profile_paths_and_points_into_the_users_hive() {
+        let lookup = |name: &str| (name == "SystemDrive").then(|| "C:".to_string());
+        assert_eq!(
+            expand_environment(r"%SystemDrive%\Users\alice", lookup),
+            r"C:\Users\alice"
+        );
+        assert_eq!(
+            expand_environment(r"%Unknown%\x\100%", lookup),
+            r"%Unknown%\x\100%"
+        );
+
+        let user = TargetUser {
+            sid: "S-1-5-21-1-2-3-1001".to_string(),
+            profile_dir: PathBuf::from("profile"),
+            mounted_hive: None,
+        };
+        assert!(user
+            .fonts_directory()
+            .ends_with(Path::new("AppData/Local/Microsoft/Windows/Fonts")));
+        assert_eq!(
+            user.hive_subkey("Software"),
+            r"S-1-5-21-1-2-3-1001\Software"
+        );
+        assert_eq!(
+            user.hive_display("Software"),
+            r"HKU\S-1-5-21-1-2-3-1001\Software"
+        );
+
+        let signed_out = TargetUser {
+            sid: user.sid.clone(),
+            profile_dir: user.profile_dir.clone(),
+            mounted_hive: Some("fontlift-S-1-5-21-1-2-3-1001".to_string()),
+        };
+        assert_eq!(
+            signed_out.hive_subkey("Software"),
+            r"fontlift-S-1-5-21-1-2-3-1001\Software"
+        );
+    }
+}
@@ -18,18 +18,41 @@ Platform mechanics (invisible to callers):
 Scope terminology:
 - "user" (default): font is visible to the current account only. No admin needed.
 - "system": font is visible to all users. Requires sudo / Administrator rights.
+- "auto": "system" when the process is elevated (see :func:`is_elevated`),
+  "user" otherwise — for deployment scripts that run under either.
+
+:func:`install`, :func:`uninstall` and :func:`remove` take ``scope="user"``,
+``"system"`` or ``"auto"`` and return a :class:`FontSource` whose ``scope``
+is the one acted on; :func:`cleanup` returns the scope name. The older
+``admin=True`` still means ``scope="system"``.
 
 Font name fields (on FontFaceInfo / dict output):
 - postscript_name: stable programmatic ID used by apps (e.g. "HelveticaNeue-Bold")
 - full_name:       menu-friendly name (e.g. "Helvetica Neue Bold")
 - family_name:     groups all weights/styles (e.g. "Helvetica Neue")
 - style:           variant within the family (e.g. "Bold", "Italic")
+
+Errors are :class:`FontliftError` subclasses (``FontliftError`` itself is a
+``RuntimeError``): :class:`FontNotFoundError`, :class:`PermissionDeniedError`,
+:class:`AlreadyInstalledError` and :class:`ValidationError`. Errors raised
+from the native core carry structured attributes next to the message text:
+``code`` (e.g. ``"font_not_found"``, ``"permission_denied"``), ``message``
+(what went wrong) and ``suggestion`` (what to do about it).
+
+:func:`install`, :func:`uninstall` and :func:`cleanup` accept an optional
+``progress`` callable, called as ``progress(event, operation, done, total,
+path)``. ``event`` is ``"start"``, ``"item"`` or ``"done"``; ``operation`` is
+``"install"``, ``"uninstall"``, ``"prune"`` or ``"clear_cache"``; ``path`` is
+the font handled by an ``"item"`` event, or None. An exception raised by the
+callback does not stop the operation; it is re-raised when it has finished.
 """
 
 from __future__ import annotations
 
 from importlib import import_module
-from typing import Any, Dict, List, Mapping
+from typing import Any, Callable, Dict, List, Mapping, Optional
+
+ProgressCallback = Callable[[str, str, int, int, Optional[str]], None]
 
 try:
     _native = import_module("fontlift._native")
@@ -43,8 +66,30 @@ if _native:
     FontliftManager = _native.FontliftManager  # re-export
     FontSource = _native.FontSource
     FontFaceInfo = _native.FontFaceInfo  # exposed for structured metadata
+    FontSession = _native.FontSession
+    FontHandle = _native.FontHandle
+    FontliftError = _native.FontliftError
+    FontNotFoundError = _native.FontNotFoundError
+    PermissionDeniedError = _native.PermissionDeniedError
+    AlreadyInstalledError = _native.AlreadyInstalledError
+    ValidationError = _native.ValidationError
 else:  # pragma: no cover - importorskip handles runtime use without native module
-    FontliftManager = FontSource = FontFaceInfo = None
+    FontliftManager = FontSource = FontFaceInfo = FontSession = FontHandle = None
+
+    class FontliftError(RuntimeError):
+        """Base class of every fontlift error."""
+
+    class FontNotFoundError(FontliftError):
+        """The font file, or the font with that name, does not exist."""
+
+    class PermissionDeniedError(FontliftError):
+        """The operation needs admin rights, or touches a protected system font."""
+
+    class AlreadyInstalledError(FontliftError):
+        """A font with the same file name is already installed in that scope."""
+
+    class ValidationError(FontliftError):
+        """The file is not a usable font, or does not match what a catalog expects."""
 
 
 def _require_native() -> None:
@@ -54,18 +99,18 @@ def _require_native() -> None:
         ) from _native_import_error
 
 
-def _font_to_dict(font: Any) -> Dict[str, Any]:
+def _font_to_dict(font: Any, details: bool = False) -> Dict[str, Any]:
     """Normalise native FontFaceInfo objects into plain dictionaries."""
     if isinstance(font, Mapping):
         return dict(font)
 
     dict_fn = getattr(font, "dict", None)
     if callable(dict_fn):
-        return dict_fn()
+        return dict_fn(details=details) if details else dict_fn()
 
     source = getattr(font, "source", None)
     path = getattr(source, "path", getattr(font, "path", None))
-    return {
+    result = {
         "source": {
             "path": path,
             "format": getattr(source, "format", None),
@@ -80,12 +125,17 @@ def _font_to_dict(font: Any) -> Dict[str, Any]:
         "style": getattr(font, "style", None),
         "weight": getattr(font, "weight", None),
         "italic": getattr(font, "italic", None),
+        "width": getattr(font, "width", None),
+        "axes": list(getattr(font, "axes", None) or []),
         "format": getattr(source, "format", None),
         "scope": getattr(source, "scope", None),
     }
+    if details:
+        result["details"] = getattr(font, "details", None)
+    return result
 
 
-def list_fonts() -> List[Dict[str, Any]]:
+def list_fonts(details: bool = False) -> List[Dict[str, Any]]:
     """Return all fonts the OS currently knows about, one dict per face.
 
     A collection file (.ttc / .otc) produces multiple entries — one per face
@@ -99,18 +149,148 @@ def list_fonts() -> List[Dict[str, Any]]:
       style           – variant within the family (e.g. "Bold")
       weight          – numeric weight 100–900 (None if unknown)
       italic          – True/False (None if unknown)
+      width           – OS/2 width class 1–9, 5 is normal (None if unknown)
+      axes            – variation axis tags such as ["wght"]; empty if static
       format          – file format string (e.g. "TTF", "OTF") or None
       scope           – "user" or "system"
       source          – nested dict with the above source-level fields
+
+    With ``details=True`` each dict also has ``details``: a dict of
+    ``version``, ``vendor_id``, ``manufacturer``, ``designer`` and
+    ``copyright`` (None when the font names none of them).
     """
     _require_native()
-    return [_font_to_dict(font) for font in _native.list()]
+    return [_font_to_dict(font, details) for font in _native.list()]
 
 
 list = list_fonts  # alias for CLI parity
 
 
-def install(font_path: str, admin: bool = False, dry_run: bool = False) -> None:
+def temporary(font_path: str) -> "FontSession":
+    """Install a font only for the duration of a ``with`` block.
+
+    The font is registered for the current login session when the block
+    starts, without copying the file, and unregistered when it ends, even
+    if the block raises. Keep the file where it is until then. Should the
+    process die first, the registration still ends at logout::
+
+        with fontlift.temporary("Specimen.ttf") as session:
+            render_specimen()  # the font is available to every app here
+        # ...and gone again here
+
+    The session object can also be used without ``with``: call
+    ``install()`` and ``close()``. ``session.path`` is the font path and
+    ``session.active`` tells whether it is registered.
+
+    Raises:
+        FontNotFoundError: if the file does not exist (on entering).
+        ValidationError:   if it is not a valid font (on entering).
+        FontliftError:     if the platform has no session-scope installs.
+    """
+    _require_native()
+    return _native.temporary(font_path)
+
+
+def register_bytes(
+    data: bytes, label: Optional[str] = None, validate: bool = True
+) -> "FontHandle":
+    """Register a font from memory for this process, without a file on disk.
+
+    Useful for fonts that were downloaded, decrypted or generated and
+    should never be written out. Other processes do not see the font.
+    The returned handle unregisters it on ``close()``, at the end of a
+    ``with`` block, or when it is garbage-collected::
+
+        with fontlift.register_bytes(data, label="Brand.otf") as font:
+            print(font.postscript_names)
+
+    ``label`` names the data in errors and logs. With ``validate`` (the
+    default) the bytes are parsed first and ``font.postscript_names``
+    lists the faces; without it the list is empty. macOS registers one face
+    per call, so it refuses collections with several faces.
+
+    Raises:
+        ValidationError: if ``data`` is not a font.
+        FontliftError:   if the platform cannot register fonts from memory.
+    """
+    _require_native()
+    return _native.register_bytes(data, label, validate)
+
+
+def info(font_path: str) -> Dict[str, Any]:
+    """Describe a font file without installing it.
+
+    The returned dict has the :func:`list_fonts` keys (with ``details``) for
+    the file's first face, plus:
+      face_index      – index of the face inside the file (0 for single fonts)
+      variable        – True if the face has variation axes
+      axes            – list of {tag, name, min, default, max, hidden}
+      named_instances – list of {name, postscript_name, coordinates}
+      face_count      – number of faces in the file
+      faces           – one dict like this (without face_count/faces) per
+                        face, so collections (.ttc / .otc) list every face
+
+    Raises:
+        FontNotFoundError: if the file does not exist.
+        ValidationError:   if it cannot be parsed as a font.
+    """
+    _require_native()
+    return _native.info(font_path)
+
+
+def find(query: Optional[str] = None, supports: Optional[str] = None) -> List[Dict[str, Any]]:
+    """Search installed fonts by name and by the characters they support.
+
+    Matches faces whose PostScript name, full name or family name contains
+    ``query``, ignoring case, and that can display every character of
+    ``supports`` (whitespace ignored). Returns them in font-menu order
+    (family, then weight, then upright before italic) as :func:`list_fonts`
+    dicts.
+
+    Raises:
+        FontliftError: if ``query`` is empty, or neither argument is given.
+    """
+    _require_native()
+    return [_font_to_dict(font) for font in _native.find(query, supports)]
+
+
+def coverage(font_path: str, text: str) -> List[Dict[str, Any]]:
+    """Report which characters of ``text`` a font file can display.
+
+    Returns one dict per face (several for .ttc / .otc collections):
+      face_index      – index of the face inside the file
+      postscript_name – the face's PostScript name
+      supported       – characters the face maps, in order of appearance
+      missing         – characters it does not
+
+    Whitespace and repeated characters in ``text`` are ignored.
+
+    Raises:
+        FontNotFoundError: if the file does not exist.
+        ValidationError:   if it cannot be parsed as a font.
+    """
+    _require_native()
+    return _native.coverage(font_path, text)
+
+
+def is_elevated() -> bool:
+    """Return True when the process runs as root / Administrator.
+
+    System-scope operations need this; ``scope="auto"`` picks ``"system"``
+    exactly when it is True.
+    """
+    _require_native()
+    return _native.is_elevated()
+
+
+def install(
+    font_path: str,
+    admin: bool = False,
+    dry_run: bool = False,
+    *,
+    scope: str | None = None,
+    progress: ProgressCallback | None = None,
+) -> "FontSource | None":
     """Install a font file so applications can use it.
 
     Copies the file to the OS font directory for the chosen scope and
@@ -123,17 +303,64 @@ def install(font_path: str, admin: bool = False, dry_run: bool = False) -> None:
                    .woff, or .woff2 file.
         admin:     If True, install system-wide (all users). Requires sudo on
                    macOS or Administrator on Windows. Defaults to user scope.
-        dry_run:   If True, return immediately without changing anything.
+        dry_run:   If True, return None immediately without changing anything.
+        scope:     "user", "system" or "auto"; overrides ``admin``.
+        progress:  Optional progress callback (see the module docs).
+
+    Returns:
+        The installed :class:`FontSource`, with the scope it went to.
 
     Raises:
-        RuntimeError: if the file does not exist, is not a valid font, the
-                      process lacks the required privileges, or the OS
-                      registration call fails.
+        FontNotFoundError:     if the file does not exist.
+        ValidationError:       if it is not a valid font.
+        PermissionDeniedError: if the process lacks the required privileges.
+        AlreadyInstalledError: if a system-scope copy is already installed.
+        FontliftError:         if the OS registration call fails.
     """
     if dry_run:
-        return
+        return None
     _require_native()
-    _native.install(font_path, admin)
+    return _native.install(font_path, admin, progress=progress, scope=scope)
+
+
+def install_bytes(
+    data: bytes,
+    name: str,
+    admin: bool = False,
+    dry_run: bool = False,
+    *,
+    scope: str | None = None,
+    strict: bool = False,
+) -> "str | None":
+    """Install a font that exists only in memory, e.g. one a CI job generated.
+
+    ``name`` (``"MyFont.ttf"``) names the installed copy; its extension
+    says what format ``data`` is. The bytes go through a temporary file that
+    is deleted once the font is installed::
+
+        fontlift.install_bytes(build_font(), name="MyFont.ttf")
+
+    Args:
+        data:    The font file's bytes.
+        name:    File name for the installed copy; a bare name, not a path.
+        admin:   If True, install system-wide (all users).
+        dry_run: If True, return None immediately without changing anything.
+        scope:   "user", "system" or "auto"; overrides ``admin``.
+        strict:  Validate the font out of process before installing it.
+
+    Returns:
+        The scope the font was installed in, ``"user"`` or ``"system"``.
+
+    Raises:
+        ValidationError:       if ``name`` has no font extension or ``data``
+                               is not a valid font.
+        PermissionDeniedError: if the process lacks the required privileges.
+        FontliftError:         if the OS registration call fails.
+    """
+    if dry_run:
+        return None
+    _require_native()
+    return _native.install_bytes(data, name, admin, strict, scope=scope)
 
 
 def uninstall(
@@ -142,7 +369,9 @@ def uninstall(
     name: str | None = None,
     admin: bool = False,
     dry_run: bool = False,
-) -> None:
+    scope: str | None = None,
+    progress: ProgressCallback | None = None,
+) -> "FontSource":
     """Remove a font's OS registration while keeping the file on disk.
 
     Pass exactly one of ``font_path`` or ``name``. Name matching checks both
@@ -158,13 +387,61 @@ def uninstall(
         admin:     Prefer system scope first. Without this flag user scope
                    is tried first.
         dry_run:   If True, resolve the target without changing anything.
+        scope:     "user", "system" or "auto": the scope to try first;
+                   overrides ``admin``.
+        progress:  Optional progress callback (see the module docs).
+
+    Returns:
+        The uninstalled :class:`FontSource`, with the scope it was found in
+        (with ``dry_run``, the scope that would be tried first).
+
+    Raises:
+        FontNotFoundError: if the font is not found.
+        FontliftError:     if neither identifier is provided, both are
+                           provided, or the OS call fails.
+    """
+    _require_native()
+    return _native.uninstall(font_path, name, admin, dry_run, progress, scope)
+
+
+def uninstall_matching(
+    family: str | None = None,
+    *,
+    pattern: str | None = None,
+    admin: bool = False,
+    dry_run: bool = False,
+    scope: str | None = None,
+) -> "list[FontSource]":
+    """Uninstall every installed font that matches, keeping the files.
+
+    Pass exactly one of ``family``, a case-insensitive glob over family
+    names, or ``pattern``, a regular expression searched in the PostScript,
+    full and family names. Only fonts in the scope are considered; a
+    collection goes as a whole when any of its faces matches. The fonts are
+    uninstalled as one journaled batch::
+
+        fontlift.uninstall_matching("Test*")
+        fontlift.uninstall_matching(pattern=r"-Bold$", dry_run=True)
+
+    Args:
+        family:  Family name glob such as ``"Test*"``.
+        pattern: Regular expression over the font's names.
+        admin:   If True, match system-wide fonts instead of the user's.
+        dry_run: If True, return the matches without uninstalling them.
+        scope:   "user", "system" or "auto"; overrides ``admin``.
+
+    Returns:
+        The uninstalled :class:`FontSource` objects (with ``dry_run``, the
+        ones that would be uninstalled).
 
     Raises:
-        RuntimeError: if neither identifier is provided, both are provided,
-                      the font is not found, or the OS call fails.
+        ValidationError: if ``pattern`` is not a valid regular expression or
+                         neither or both of ``family`` and ``pattern`` are given.
+        FontliftError:   if a font could not be unregistered; the others in
+                         the batch are still uninstalled.
     """
     _require_native()
-    _native.uninstall(font_path, name, admin, dry_run)
+    return _native.uninstall_matching(family, pattern, admin, dry_run, scope)
 
 
 def remove(
@@ -173,7 +450,8 @@ def remove(
     name: str | None = None,
     admin: bool = False,
     dry_run: bool = False,
-) -> None:
+    scope: str | None = None,
+) -> "FontSource":
     """Unregister a font and delete its file from disk.
 
     This is the destructive counterpart to :func:`uninstall`. If
@@ -186,13 +464,17 @@ def remove(
         name:      PostScript name or full name of the font to remove.
         admin:     Prefer system scope first.
         dry_run:   If True, resolve the target without deleting anything.
+        scope:     "user", "system" or "auto"; overrides ``admin``.
+
+    Returns:
+        The removed :class:`FontSource`, with its scope.
 
     Raises:
-        RuntimeError: same conditions as :func:`uninstall`, plus IO errors
-                      when deleting the file.
+        FontliftError: same conditions as :func:`uninstall`, plus IO errors
+                       when deleting the file.
     """
     _require_native()
-    _native.remove(font_path, name, admin, dry_run)
+    return _native.remove(font_path, name, admin, dry_run, scope)
 
 
 def cleanup(
@@ -201,7 +483,9 @@ def cleanup(
     prune: bool = True,
     cache: bool = True,
     dry_run: bool = False,
-) -> None:
+    scope: str | None = None,
+    progress: ProgressCallback | None = None,
+) -> str:
     """Prune stale font registrations and/or clear OS font caches.
 
     Stale registrations point to files that no longer exist — they can
@@ -218,23 +502,44 @@ def cleanup(
         cache:   Clear OS font caches (Core Text on macOS, FontCache service
                  on Windows) and third-party app caches where supported.
         dry_run: If True, return immediately without changing anything.
+        scope:   "user", "system" or "auto"; overrides ``admin``.
+        progress: Optional progress callback (see the module docs). Pruning
+                 reports in chunks of up to 100 registrations.
+
+    Returns:
+        The scope cleaned, ``"user"`` or ``"system"``.
 
     Raises:
-        RuntimeError: if both ``prune`` and ``cache`` are False, or if an
-                      OS cache operation fails.
+        FontliftError: if both ``prune`` and ``cache`` are False, or if an
+                       OS cache operation fails.
     """
     _require_native()
-    _native.cleanup(admin, prune, cache, dry_run)
+    return _native.cleanup(admin, prune, cache, dry_run, progress, scope)
 
 
 __all__ = [
     "FontliftManager",
     "FontSource",
     "FontFaceInfo",
+    "FontSession",
+    "FontHandle",
+    "FontliftError",
+    "FontNotFoundError",
+    "PermissionDeniedError",
+    "AlreadyInstalledError",
+    "ValidationError",
     "list_fonts",
     "list",
+    "temporary",
+    "register_bytes",
+    "info",
+    "find",
+    "coverage",
+    "is_elevated",
     "install",
+    "install_bytes",
     "uninstall",
+    "uninstall_matching",
     "remove",
     "cleanup",
 ]
@@ -27,8 +27,9 @@ class FontliftCLI:
         name: bool = False,
         sorted: bool = False,  # noqa: A002
         json: bool = False,  # noqa: A002
+        details: bool = False,
     ) -> None:
-        fonts = list_fonts()
+        fonts = list_fonts(details=details)
         opts = ListRenderOptions(
             show_path=path,
             show_name=name,
@@ -15,11 +15,23 @@
 //! ├── FontSource           class  — where a font file lives and how it's scoped
 //! ├── FontFaceInfo         class  — metadata for one face inside a font file
 //! ├── FontliftManager      class  — reusable manager; create once, call many times
+//! ├── FontSession          class  — context manager from `temporary(path)`
+//! ├── FontHandle           class  — in-memory fonts from `register_bytes(data)`
+//! ├── FontliftError        exc    — base of every fontlift error (a RuntimeError)
+//! │   ├── FontNotFoundError, PermissionDeniedError,
+//! │   └── AlreadyInstalledError, ValidationError
 //! ├── install(...)         fn     — one-shot convenience: install a font file
+//! ├── install_bytes(...)   fn     — install font bytes under a file name
 //! ├── list()               fn     — one-shot convenience: list installed fonts
 //! ├── uninstall(...)       fn     — one-shot convenience: uninstall by path or name
+//! ├── uninstall_matching(...) fn  — uninstall every font matching a family glob or regex
 //! ├── remove(...)          fn     — one-shot convenience: uninstall + delete the file
-//! └── cleanup(...)         fn     — one-shot convenience: prune & clear caches
+//! ├── cleanup(...)         fn     — one-shot convenience: prune & clear caches
+//! ├── temporary(path)      fn     — install for a `with` block, then uninstall
+//! ├── register_bytes(data) fn     — register font bytes for this process only
+//! ├── info(path)           fn     — names, weight, axes and faces of a font file
+//! ├── find(query)          fn     — installed faces whose names contain `query`
+//! └── is_elevated()        fn     — whether system scope is available to this process
 //! ```
 //!
 //! Naming and scope match the Rust core:
@@ -28,36 +40,185 @@
 //! - Scope controls who sees the font:
 //! - `"user"` — only the current user sees it; no admin rights needed.
 //! - `"system"` — every user on the machine sees it; requires elevated privileges.
+//! - `"auto"` — `"system"` when the process is elevated, `"user"` otherwise,
+//!   for deployment scripts that run both ways. The older `admin=True` still
+//!   means `"system"`.
+//!
+//! `install`, `uninstall` and `remove` return a `FontSource` whose `scope` is
+//! the one acted on, `uninstall_matching` a list of them; `install_bytes` and
+//! `cleanup` return the scope name.
 
 #![allow(non_local_definitions)]
 
 use fontlift_core::{
-    validation_ext::ValidatorConfig, FontError, FontManager, FontScope, FontliftFontFaceInfo,
-    FontliftFontSource,
+    cache::{CacheClearStrategy, CacheTargets},
+    environment,
+    face_match::{self, FaceMatcher},
+    inventory::{InventoryFilter, InventoryPager},
+    memory::{FontHandle, MemoryFontOptions},
+    metadata, operations, policy,
+    progress::{self, ItemProgress, Operation, OperationObserver, OperationSummary, Tracker},
+    prune::PruneOptions,
+    session::TemporaryFont,
+    sorting,
+    validation_ext::ValidatorConfig,
+    variations, FontError, FontManager, FontScope, FontliftFontFaceInfo, FontliftFontSource,
 };
+use pyo3::create_exception;
 use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyModule};
+use pyo3::types::{PyDict, PyList, PyModule};
 use pyo3::{IntoPyObject, PyErr};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 #[cfg(test)]
-use fontlift_core::FontResult;
+use fontlift_core::{cache::CacheClearResult, FontResult};
 #[cfg(test)]
 use std::collections::VecDeque;
-#[cfg(test)]
-use std::sync::Mutex;
 
 pub const PYTHON_BINDINGS_ENABLED: bool = true;
 const VERSION: &str = env!("GIT_VERSION");
 
-/// Convert a Rust [`FontError`] into a Python `RuntimeError`.
+create_exception!(
+    fontlift,
+    FontliftError,
+    PyRuntimeError,
+    "Base class of every fontlift error. A RuntimeError, so older `except RuntimeError` handlers still catch it."
+);
+create_exception!(
+    fontlift,
+    FontNotFoundError,
+    FontliftError,
+    "The font file, or the font with that name, does not exist."
+);
+create_exception!(
+    fontlift,
+    PermissionDeniedError,
+    FontliftError,
+    "The operation needs admin rights, or touches a protected system font."
+);
+create_exception!(
+    fontlift,
+    AlreadyInstalledError,
+    FontliftError,
+    "A font with the same file name is already installed in that scope."
+);
+create_exception!(
+    fontlift,
+    ValidationError,
+    FontliftError,
+    "The file is not a usable font, or does not match what a catalog expects."
+);
+
+/// Convert a Rust [`FontError`] into the matching [`FontliftError`] subclass.
 ///
 /// Public Python entry points use this so errors read like
-/// `Failed to install font: ...`.
+/// `Failed to install font: ...`. The exception also carries the structured
+/// parts as attributes, so callers need not parse the text: `code`
+/// (`"font_not_found"`, ...), `message` and `suggestion`.
 fn py_error(action: &str, err: FontError) -> PyErr {
-    PyRuntimeError::new_err(format!("Failed to {action}: {err}"))
+    let text = format!("Failed to {action}: {err}");
+    let py_err = match err {
+        FontError::FontNotFound(_) => FontNotFoundError::new_err(text),
+        FontError::PermissionDenied(_) | FontError::SystemFontProtection(_) => {
+            PermissionDeniedError::new_err(text)
+        }
+        FontError::AlreadyInstalled(_) => AlreadyInstalledError::new_err(text),
+        FontError::InvalidFormat(_) | FontError::VerificationFailed(_) => {
+            ValidationError::new_err(text)
+        }
+        _ => FontliftError::new_err(text),
+    };
+    Python::with_gil(|py| {
+        let value = py_err.value(py);
+        let _ = value.setattr("code", err.code().as_str());
+        let _ = value.setattr("message", err.message());
+        let _ = value.setattr("suggestion", err.suggestion());
+    });
+    py_err
+}
+
+/// Forwards progress to the optional Python `progress` callback as
+/// `progress(event, operation, done, total, path)`, with `event` one of
+/// `"start"`, `"item"` and `"done"`.
+///
+/// The operation cannot be interrupted from an observer, so an exception
+/// raised by the callback is kept and re-raised by [`PyProgress::finish`]
+/// once the operation is over.
+struct PyProgress {
+    callback: Option<PyObject>,
+    error: Mutex<Option<PyErr>>,
+}
+
+impl PyProgress {
+    fn new(callback: Option<PyObject>) -> Self {
+        Self {
+            callback,
+            error: Mutex::new(None),
+        }
+    }
+
+    fn call(
+        &self,
+        event: &str,
+        operation: Operation,
+        done: usize,
+        total: usize,
+        path: Option<&Path>,
+    ) {
+        let Some(callback) = &self.callback else {
+            return;
+        };
+        let path = path.map(|p| p.to_string_lossy().into_owned());
+        Python::with_gil(|py| {
+            if let Err(err) = callback.call1(py, (event, operation.as_str(), done, total, path)) {
+                if let Ok(mut slot) = self.error.lock() {
+                    slot.get_or_insert(err);
+                }
+            }
+        });
+    }
+
+    /// The first exception the callback raised, if any.
+    fn finish(&self) -> PyResult<()> {
+        match self.error.lock().ok().and_then(|mut slot| slot.take()) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
+impl OperationObserver for PyProgress {
+    fn on_start(&self, operation: Operation, total: usize) {
+        self.call("start", operation, 0, total, None);
+    }
+
+    fn on_item(&self, item: &ItemProgress<'_>) {
+        self.call("item", item.operation, item.done, item.total, item.path);
+    }
+
+    fn on_done(&self, summary: &OperationSummary) {
+        self.call("done", summary.operation, summary.done, summary.total, None);
+    }
+}
+
+/// Run a single-font operation on `path`, reported to `progress`.
+fn observe_one<T>(
+    progress: &PyProgress,
+    operation: Operation,
+    path: &Path,
+    run: impl FnOnce() -> PyResult<T>,
+) -> PyResult<T> {
+    let result = {
+        let mut tracker = Tracker::start(progress, operation, 1);
+        let result = run();
+        tracker.item(Some(path), result.is_ok());
+        result
+    };
+    let value = result?;
+    progress.finish()?;
+    Ok(value)
 }
 
 /// Run cleanup against an existing manager.
@@ -67,52 +228,99 @@ fn py_error(action: &str, err: FontError) -> PyErr {
 /// enabled.
 fn cleanup_with_manager(
     manager: &Arc<dyn FontManager>,
-    admin: bool,
+    scope: FontScope,
+    prune: bool,
+    cache: bool,
+    dry_run: bool,
+) -> PyResult<()> {
+    cleanup_observed(
+        manager,
+        scope,
+        prune,
+        cache,
+        dry_run,
+        &PyProgress::new(None),
+    )
+}
+
+/// [`cleanup_with_manager`], reporting each step to `progress`.
+fn cleanup_observed(
+    manager: &Arc<dyn FontManager>,
+    scope: FontScope,
     prune: bool,
     cache: bool,
     dry_run: bool,
+    progress: &PyProgress,
 ) -> PyResult<()> {
     if !prune && !cache {
-        return Err(PyRuntimeError::new_err(
+        return Err(FontliftError::new_err(
             "cleanup requires at least one of prune or cache to be enabled",
         ));
     }
 
-    let scope = if admin {
-        FontScope::System
-    } else {
-        FontScope::User
-    };
-
+    policy::check_managed_scope(scope, "cleanup").map_err(|e| py_error("clean up", e))?;
     if dry_run {
         return Ok(());
     }
 
     if prune {
-        manager
-            .prune_missing_fonts(scope)
+        progress::prune_observed(manager.as_ref(), scope, &PruneOptions::default(), progress)
             .map_err(|e| py_error("prune stale font registrations", e))?;
     }
 
     if cache {
-        manager
-            .clear_font_caches(scope)
+        let strategy = CacheClearStrategy::for_scope(scope);
+        progress::clear_caches_observed(manager.as_ref(), strategy, &CacheTargets::all(), progress)
             .map_err(|e| py_error("clear font caches", e))?;
     }
 
-    Ok(())
+    progress.finish()
 }
 
-/// Return the two scopes in fallback order, preferred scope first.
+/// The scope named by the Python `scope` and `admin` arguments.
 ///
-/// Uninstall tries the expected scope first, then the other scope.
-fn scope_order(preferred: FontScope) -> [FontScope; 2] {
-    match preferred {
-        FontScope::User => [FontScope::User, FontScope::System],
-        FontScope::System => [FontScope::System, FontScope::User],
+/// `scope` is `"user"`, `"system"` or `"auto"`, which picks system only when
+/// the process is elevated. Without it, `admin` chooses, as it always has;
+/// `admin=True` together with `scope="user"` is a contradiction.
+fn resolve_scope(scope: Option<&str>, admin: bool) -> PyResult<FontScope> {
+    scope_from_args(scope, admin, environment::is_elevated())
+}
+
+fn scope_from_args(scope: Option<&str>, admin: bool, elevated: bool) -> PyResult<FontScope> {
+    let named = match scope.map(str::to_ascii_lowercase).as_deref() {
+        None => None,
+        Some("user") => Some(FontScope::User),
+        Some("system") => Some(FontScope::System),
+        Some("auto") if admin || elevated => Some(FontScope::System),
+        Some("auto") => Some(FontScope::User),
+        Some(other) => {
+            return Err(FontliftError::new_err(format!(
+                "scope must be \"user\", \"system\" or \"auto\", not {other:?}"
+            )))
+        }
+    };
+    match (named, admin) {
+        (Some(FontScope::User), true) => Err(FontliftError::new_err(
+            "admin=True asks for system scope, but scope=\"user\" was given",
+        )),
+        (Some(scope), _) => Ok(scope),
+        (None, true) => Ok(FontScope::System),
+        (None, false) => Ok(FontScope::User),
     }
 }
 
+fn scope_name(scope: FontScope) -> &'static str {
+    match scope {
+        FontScope::User => "user",
+        FontScope::System => "system",
+    }
+}
+
+/// The `FontSource` an install, uninstall or remove call returns.
+fn acted_on(path: PathBuf, scope: FontScope) -> PyFontSource {
+    PyFontSource::from(FontliftFontSource::new(path).with_scope(Some(scope)))
+}
+
 /// Resolve a Python font identifier to a filesystem path and scope.
 ///
 /// Callers must pass exactly one of:
@@ -128,10 +336,10 @@ fn resolve_font_target(
     default_scope: FontScope,
 ) -> PyResult<(PathBuf, FontScope)> {
     match (font_path, name) {
-        (Some(_), Some(_)) => Err(PyRuntimeError::new_err(
+        (Some(_), Some(_)) => Err(FontliftError::new_err(
             "Provide either font_path or name, not both",
         )),
-        (None, None) => Err(PyRuntimeError::new_err(
+        (None, None) => Err(FontliftError::new_err(
             "A font_path or name is required to select a font",
         )),
         (Some(path), None) => Ok((PathBuf::from(path), default_scope)),
@@ -148,13 +356,85 @@ fn resolve_font_target(
                 return Ok((font.source.path.clone(), starting_scope));
             }
 
-            Err(PyRuntimeError::new_err(format!(
+            Err(FontNotFoundError::new_err(format!(
                 "Font not found by name: {font_name}"
             )))
         }
     }
 }
 
+/// Installed faces whose PostScript, full or family name contains `query`,
+/// ignoring case, in font-menu order.
+fn matching_faces(
+    fonts: Vec<FontliftFontFaceInfo>,
+    query: &str,
+) -> PyResult<Vec<FontliftFontFaceInfo>> {
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Err(FontliftError::new_err(
+            "A name or family to search for is required",
+        ));
+    }
+    let mut found: Vec<FontliftFontFaceInfo> = fonts
+        .into_iter()
+        .filter(|font| {
+            [&font.postscript_name, &font.full_name, &font.family_name]
+                .iter()
+                .any(|name| name.to_lowercase().contains(&needle))
+        })
+        .collect();
+    found.sort_by(sorting::compare_faces);
+    Ok(found)
+}
+
+fn faces_to_py(py: Python<'_>, fonts: Vec<FontliftFontFaceInfo>) -> PyResult<Vec<PyObject>> {
+    fonts
+        .into_iter()
+        .map(|font| {
+            Ok(PyFontFaceInfo::from(font)
+                .into_pyobject(py)?
+                .unbind()
+                .into_any())
+        })
+        .collect()
+}
+
+/// One face of `info()`: the `FontFaceInfo.dict(details=True)` keys plus
+/// `face_index`, `variable`, `axes` and `named_instances`.
+fn face_details<'py>(py: Python<'py>, face: FontliftFontFaceInfo) -> PyResult<Bound<'py, PyDict>> {
+    let index = face.source.face_index.unwrap_or(0);
+    let variations = variations::read_variations(&face.source.path, index)
+        .map_err(|e| py_error("read font info", e))?;
+    let dict = PyFontFaceInfo::from(face).dict_py(py, true)?;
+    dict.set_item("face_index", index)?;
+    dict.set_item("variable", variations.is_some())?;
+
+    let axes = PyList::empty(py);
+    let instances = PyList::empty(py);
+    if let Some(variations) = variations {
+        for axis in variations.axes {
+            let item = PyDict::new(py);
+            item.set_item("tag", axis.tag)?;
+            item.set_item("name", axis.name)?;
+            item.set_item("min", axis.min)?;
+            item.set_item("default", axis.default)?;
+            item.set_item("max", axis.max)?;
+            item.set_item("hidden", axis.hidden)?;
+            axes.append(item)?;
+        }
+        for instance in variations.instances {
+            let item = PyDict::new(py);
+            item.set_item("name", instance.name)?;
+            item.set_item("postscript_name", instance.postscript_name)?;
+            item.set_item("coordinates", instance.coordinates)?;
+            instances.append(item)?;
+        }
+    }
+    dict.set_item("axes", axes)?;
+    dict.set_item("named_instances", instances)?;
+    Ok(dict)
+}
+
 /// Uninstall a known font path, trying scopes in fallback order.
 ///
 /// `uninstall` removes the OS registration and keeps the file on disk.
@@ -170,23 +450,12 @@ fn uninstall_resolved(
         return Ok(starting_scope);
     }
 
-    let mut last_error: Option<FontError> = None;
-
-    for scope in scope_order(starting_scope) {
-        let source = FontliftFontSource::new(path.to_path_buf()).with_scope(Some(scope));
-        match manager.uninstall_font(&source) {
-            Ok(()) => return Ok(scope),
-            Err(err) => last_error = Some(err),
-        }
-    }
-
-    Err(py_error(
-        "uninstall font",
-        last_error.unwrap_or(FontError::RegistrationFailed(format!(
-            "Failed to uninstall font {} in any scope",
-            path.display()
-        ))),
-    ))
+    operations::uninstall_across_scopes(
+        manager.as_ref(),
+        path,
+        &operations::scope_order(starting_scope),
+    )
+    .map_err(|e| py_error("uninstall font", e))
 }
 
 fn remove_resolved(
@@ -200,9 +469,7 @@ fn remove_resolved(
     }
 
     let source = FontliftFontSource::new(path.to_path_buf()).with_scope(Some(scope));
-    manager
-        .remove_font(&source)
-        .map_err(|e| py_error("remove font", e))
+    operations::remove(manager.as_ref(), &source).map_err(|e| py_error("remove font", e))
 }
 
 /// Python view of a `FontliftFontSource`.
@@ -226,10 +493,7 @@ struct PyFontSource {
 
 impl From<FontliftFontSource> for PyFontSource {
     fn from(source: FontliftFontSource) -> Self {
-        let scope = source.scope.map(|s| match s {
-            FontScope::User => "user".to_string(),
-            FontScope::System => "system".to_string(),
-        });
+        let scope = source.scope.map(|s| scope_name(s).to_string());
         Self {
             path: source.path.to_string_lossy().into_owned(),
             format: source.format,
@@ -250,6 +514,19 @@ fn source_dict<'py>(py: Python<'py>, source: &PyFontSource) -> PyResult<Bound<'p
     Ok(dict)
 }
 
+fn details_dict<'py>(
+    py: Python<'py>,
+    details: &metadata::FaceDetails,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("version", &details.version)?;
+    dict.set_item("vendor_id", &details.vendor_id)?;
+    dict.set_item("manufacturer", &details.manufacturer)?;
+    dict.set_item("designer", &details.designer)?;
+    dict.set_item("copyright", &details.copyright)?;
+    Ok(dict)
+}
+
 /// Python view of one installed font face.
 ///
 /// Name fields keep the same distinctions as the Rust core:
@@ -273,6 +550,11 @@ struct PyFontFaceInfo {
     weight: Option<u16>,
     #[pyo3(get)]
     italic: Option<bool>,
+    #[pyo3(get)]
+    width: Option<u16>,
+    #[pyo3(get)]
+    axes: Vec<String>,
+    details: Option<metadata::FaceDetails>,
 }
 
 impl From<FontliftFontFaceInfo> for PyFontFaceInfo {
@@ -287,19 +569,32 @@ impl From<FontliftFontFaceInfo> for PyFontFaceInfo {
             style: info.style,
             weight: info.weight,
             italic: info.italic,
+            width: info.width,
+            axes: info.axes,
+            details: info.details,
         }
     }
 }
 
 #[pymethods]
 impl PyFontFaceInfo {
+    /// Version, vendor ID, manufacturer, designer and copyright as a `dict`,
+    /// or `None` when the font names none of them.
+    #[getter]
+    fn details<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        self.details
+            .as_ref()
+            .map(|details| details_dict(py, details))
+            .transpose()
+    }
+
     /// Return a plain `dict` for JSON and interop.
     ///
     /// Keys mirror the object fields. For backward compatibility, `path`,
     /// `format`, and `scope` are duplicated at the top level as well as inside
-    /// the nested `source` dict.
-    #[pyo3(name = "dict")]
-    fn dict_py<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+    /// the nested `source` dict. `details=True` adds the `details` key.
+    #[pyo3(name = "dict", signature = (details = false))]
+    fn dict_py<'py>(&self, py: Python<'py>, details: bool) -> PyResult<Bound<'py, PyDict>> {
         let dict = PyDict::new(py);
         dict.set_item("source", source_dict(py, &self.source)?)?;
         dict.set_item("path", &self.source.path)?; // legacy top-level path
@@ -309,8 +604,13 @@ impl PyFontFaceInfo {
         dict.set_item("style", &self.style)?;
         dict.set_item("weight", self.weight)?;
         dict.set_item("italic", self.italic)?;
+        dict.set_item("width", self.width)?;
+        dict.set_item("axes", &self.axes)?;
         dict.set_item("format", &self.source.format)?;
         dict.set_item("scope", &self.source.scope)?;
+        if details {
+            dict.set_item("details", self.details(py)?)?;
+        }
         Ok(dict)
     }
 
@@ -339,9 +639,22 @@ impl PyFontFaceInfo {
 ///     print(face.postscript_name, face.source.path)
 /// mgr.cleanup(prune=True, cache=True)
 /// ```
+///
+/// For inventories too large to handle at once, `list_page()` returns them
+/// in pages that all come from the same snapshot:
+///
+/// ```python
+/// page = mgr.list_page(limit=500)
+/// while True:
+///     show(page["faces"])
+///     if page["next_cursor"] is None:
+///         break
+///     page = mgr.list_page(limit=500, cursor=page["next_cursor"])
+/// ```
 #[pyclass]
 struct FontliftManager {
     manager: Arc<dyn FontManager>,
+    pager: InventoryPager,
 }
 
 #[allow(non_local_definitions)]
@@ -351,7 +664,10 @@ impl FontliftManager {
     #[new]
     fn new() -> PyResult<Self> {
         let manager = create_platform_manager();
-        Ok(Self { manager })
+        Ok(Self {
+            manager,
+            pager: InventoryPager::default(),
+        })
     }
 
     /// Return one `FontFaceInfo` object per installed face.
@@ -362,7 +678,7 @@ impl FontliftManager {
         let fonts = self
             .manager
             .list_installed_fonts()
-            .map_err(|e| PyRuntimeError::new_err(format!("Failed to list fonts: {}", e)))?;
+            .map_err(|e| py_error("list fonts", e))?;
 
         let mut result = Vec::new();
         for font in fonts {
@@ -376,14 +692,19 @@ impl FontliftManager {
         Ok(result)
     }
 
-    #[pyo3(signature = (font_path, admin=false, strict=false))]
-    fn install_font(&self, font_path: &str, admin: bool, strict: bool) -> PyResult<()> {
+    /// `progress`, if given, is called as `progress(event, operation, done,
+    /// total, path)`; see the module-level `install()`.
+    #[pyo3(signature = (font_path, admin=false, strict=false, progress=None, scope=None))]
+    fn install_font(
+        &self,
+        font_path: &str,
+        admin: bool,
+        strict: bool,
+        progress: Option<PyObject>,
+        scope: Option<&str>,
+    ) -> PyResult<PyFontSource> {
         let path = PathBuf::from(font_path);
-        let scope = if admin {
-            FontScope::System
-        } else {
-            FontScope::User
-        };
+        let scope = resolve_scope(scope, admin)?;
         let source = FontliftFontSource::new(path).with_scope(Some(scope));
 
         // Use validating manager if strict mode requested
@@ -393,11 +714,11 @@ impl FontliftManager {
             self.manager.clone()
         };
 
-        manager
-            .install_font(&source)
-            .map_err(|e| PyRuntimeError::new_err(format!("Failed to install font: {}", e)))?;
-
-        Ok(())
+        let progress = PyProgress::new(progress);
+        observe_one(&progress, Operation::Install, &source.path, || {
+            operations::install(manager.as_ref(), &source).map_err(|e| py_error("install font", e))
+        })?;
+        Ok(source.into())
     }
 
     /// Return whether the OS currently has a registration for `font_path`.
@@ -408,7 +729,7 @@ impl FontliftManager {
         let installed = self
             .manager
             .is_font_installed(&source)
-            .map_err(|e| PyRuntimeError::new_err(format!("Failed to check font: {}", e)))?;
+            .map_err(|e| py_error("check font", e))?;
 
         Ok(installed)
     }
@@ -418,57 +739,116 @@ impl FontliftManager {
     /// `name` matches either a PostScript name or a full name. The file stays
     /// on disk. `dry_run=True` resolves the target and scope without changing
     /// the OS.
-    #[pyo3(signature = (font_path=None, name=None, admin=false, dry_run=false))]
+    #[pyo3(signature = (font_path=None, name=None, admin=false, dry_run=false, progress=None, scope=None))]
     fn uninstall_font(
         &self,
         font_path: Option<&str>,
         name: Option<&str>,
         admin: bool,
         dry_run: bool,
-    ) -> PyResult<()> {
-        let default_scope = if admin {
-            FontScope::System
-        } else {
-            FontScope::User
-        };
+        progress: Option<PyObject>,
+        scope: Option<&str>,
+    ) -> PyResult<PyFontSource> {
+        let default_scope = resolve_scope(scope, admin)?;
 
         let (path, starting_scope) =
             resolve_font_target(&self.manager, font_path, name, default_scope)?;
 
-        uninstall_resolved(&self.manager, &path, starting_scope, dry_run).map(|_| ())
+        let progress = PyProgress::new(progress);
+        let scope = observe_one(&progress, Operation::Uninstall, &path, || {
+            uninstall_resolved(&self.manager, &path, starting_scope, dry_run)
+        })?;
+        Ok(acted_on(path, scope))
     }
 
-    #[pyo3(signature = (font_path=None, name=None, admin=false, dry_run=false))]
+    #[pyo3(signature = (font_path=None, name=None, admin=false, dry_run=false, scope=None))]
     fn remove_font(
         &self,
         font_path: Option<&str>,
         name: Option<&str>,
         admin: bool,
         dry_run: bool,
-    ) -> PyResult<()> {
-        let default_scope = if admin {
-            FontScope::System
-        } else {
-            FontScope::User
-        };
+        scope: Option<&str>,
+    ) -> PyResult<PyFontSource> {
+        let default_scope = resolve_scope(scope, admin)?;
 
         let (path, scope) = resolve_font_target(&self.manager, font_path, name, default_scope)?;
 
-        remove_resolved(&self.manager, &path, scope, dry_run)
+        remove_resolved(&self.manager, &path, scope, dry_run)?;
+        Ok(acted_on(path, scope))
+    }
+
+    /// Prune stale registrations, clear caches, or both. Returns the scope
+    /// name cleaned.
+    #[pyo3(signature = (admin=false, prune=true, cache=true, dry_run=false, progress=None, scope=None))]
+    fn cleanup(
+        &self,
+        admin: bool,
+        prune: bool,
+        cache: bool,
+        dry_run: bool,
+        progress: Option<PyObject>,
+        scope: Option<&str>,
+    ) -> PyResult<&'static str> {
+        let scope = resolve_scope(scope, admin)?;
+        let progress = PyProgress::new(progress);
+        cleanup_observed(&self.manager, scope, prune, cache, dry_run, &progress)?;
+        Ok(scope_name(scope))
+    }
+
+    /// Installed faces whose PostScript, full or family name contains
+    /// `query`, ignoring case.
+    fn find_fonts(&self, py: Python, query: &str) -> PyResult<Vec<PyObject>> {
+        let fonts = self
+            .manager
+            .list_installed_fonts()
+            .map_err(|e| py_error("list fonts", e))?;
+        faces_to_py(py, matching_faces(fonts, query)?)
     }
 
-    /// Prune stale registrations, clear caches, or both.
-    #[pyo3(signature = (admin=false, prune=true, cache=true, dry_run=false))]
-    fn cleanup(&self, admin: bool, prune: bool, cache: bool, dry_run: bool) -> PyResult<()> {
-        cleanup_with_manager(&self.manager, admin, prune, cache, dry_run)
+    /// One page of the installed faces, as a dict with `snapshot`, `offset`,
+    /// `total` (matching faces overall), `faces` and `next_cursor`.
+    ///
+    /// Without `cursor`, takes a fresh snapshot of the inventory, sorted by
+    /// family. With the `next_cursor` of an earlier page, continues in that
+    /// snapshot, so installs in between do not shift pages; pass the same
+    /// `query`. `limit=0` returns everything. A cursor whose snapshot has
+    /// been dropped raises `FontliftError` (code `stale_cursor`).
+    #[pyo3(signature = (offset=0, limit=500, query=None, cursor=None))]
+    fn list_page<'py>(
+        &self,
+        py: Python<'py>,
+        offset: usize,
+        limit: usize,
+        query: Option<String>,
+        cursor: Option<&str>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let filter = InventoryFilter { query };
+        let page = match cursor {
+            Some(cursor) => self.pager.resume(cursor, limit, &filter),
+            None => self
+                .pager
+                .list(self.manager.as_ref(), offset, limit, &filter),
+        }
+        .map_err(|e| py_error("list fonts", e))?;
+
+        let dict = PyDict::new(py);
+        dict.set_item("snapshot", &page.snapshot)?;
+        dict.set_item("offset", page.offset)?;
+        dict.set_item("total", page.total)?;
+        dict.set_item("faces", faces_to_py(py, page.faces)?)?;
+        dict.set_item("next_cursor", page.next_cursor)?;
+        Ok(dict)
     }
 
     /// Clear caches only.
     ///
     /// Compatibility wrapper for `cleanup(prune=False, cache=True)`.
-    #[pyo3(signature = (admin=false))]
-    fn clear_caches(&self, admin: bool) -> PyResult<()> {
-        cleanup_with_manager(&self.manager, admin, false, true, false)
+    #[pyo3(signature = (admin=false, scope=None))]
+    fn clear_caches(&self, admin: bool, scope: Option<&str>) -> PyResult<&'static str> {
+        let scope = resolve_scope(scope, admin)?;
+        cleanup_with_manager(&self.manager, scope, false, true, false)?;
+        Ok(scope_name(scope))
     }
 }
 
@@ -508,8 +888,15 @@ fn create_platform_manager_with_validation(
 }
 
 #[pyfunction]
-#[pyo3(signature = (font_path, admin=false, strict=false))]
-fn install(font_path: &str, admin: bool, strict: bool) -> PyResult<()> {
+#[pyo3(signature = (font_path, admin=false, strict=false, progress=None, scope=None))]
+fn install(
+    font_path: &str,
+    admin: bool,
+    strict: bool,
+    progress: Option<PyObject>,
+    scope: Option<&str>,
+) -> PyResult<PyFontSource> {
+    let scope = resolve_scope(scope, admin)?;
     let validation_config = if strict {
         Some(ValidatorConfig::default())
     } else {
@@ -517,18 +904,35 @@ fn install(font_path: &str, admin: bool, strict: bool) -> PyResult<()> {
     };
     let manager = create_platform_manager_with_validation(validation_config);
     let path = PathBuf::from(font_path);
-    let scope = if admin {
-        FontScope::System
-    } else {
-        FontScope::User
-    };
     let source = FontliftFontSource::new(path).with_scope(Some(scope));
 
-    manager
-        .install_font(&source)
-        .map_err(|e| PyRuntimeError::new_err(format!("Failed to install font: {}", e)))?;
+    let progress = PyProgress::new(progress);
+    observe_one(&progress, Operation::Install, &source.path, || {
+        operations::install(manager.as_ref(), &source).map_err(|e| py_error("install font", e))
+    })?;
+    Ok(source.into())
+}
 
-    Ok(())
+/// Install font `data` as a file named `name` (`MyFont.ttf`).
+#[pyfunction]
+#[pyo3(signature = (data, name, admin=false, strict=false, scope=None))]
+fn install_bytes(
+    data: &[u8],
+    name: &str,
+    admin: bool,
+    strict: bool,
+    scope: Option<&str>,
+) -> PyResult<String> {
+    let scope = resolve_scope(scope, admin)?;
+    let validation_config = if strict {
+        Some(ValidatorConfig::default())
+    } else {
+        None
+    };
+    let manager = create_platform_manager_with_validation(validation_config);
+    operations::install_bytes(manager.as_ref(), data, name, scope)
+        .map_err(|e| py_error("install font", e))?;
+    Ok(scope_name(scope).to_string())
 }
 
 #[pyfunction]
@@ -536,7 +940,7 @@ fn list() -> PyResult<Vec<PyObject>> {
     let manager = create_platform_manager();
     let fonts = manager
         .list_installed_fonts()
-        .map_err(|e| PyRuntimeError::new_err(format!("Failed to list fonts: {}", e)))?;
+        .map_err(|e| py_error("list fonts", e))?;
 
     Python::with_gil(|py| {
         let mut result = Vec::with_capacity(fonts.len());
@@ -552,43 +956,371 @@ fn list() -> PyResult<Vec<PyObject>> {
 }
 
 #[pyfunction]
-#[pyo3(signature = (font_path=None, name=None, admin=false, dry_run=false))]
+#[pyo3(signature = (font_path=None, name=None, admin=false, dry_run=false, progress=None, scope=None))]
 fn uninstall(
     font_path: Option<&str>,
     name: Option<&str>,
     admin: bool,
     dry_run: bool,
-) -> PyResult<()> {
+    progress: Option<PyObject>,
+    scope: Option<&str>,
+) -> PyResult<PyFontSource> {
+    let default_scope = resolve_scope(scope, admin)?;
     let manager = create_platform_manager();
-    let default_scope = if admin {
-        FontScope::System
-    } else {
-        FontScope::User
-    };
 
     let (path, starting_scope) = resolve_font_target(&manager, font_path, name, default_scope)?;
-    uninstall_resolved(&manager, &path, starting_scope, dry_run).map(|_| ())
+    let progress = PyProgress::new(progress);
+    let scope = observe_one(&progress, Operation::Uninstall, &path, || {
+        uninstall_resolved(&manager, &path, starting_scope, dry_run)
+    })?;
+    Ok(acted_on(path, scope))
 }
 
+/// Uninstall every font in the scope whose family matches the glob `family`
+/// or whose names match the regex `pattern`, as one journaled batch.
 #[pyfunction]
-#[pyo3(signature = (font_path=None, name=None, admin=false, dry_run=false))]
-fn remove(font_path: Option<&str>, name: Option<&str>, admin: bool, dry_run: bool) -> PyResult<()> {
-    let manager = create_platform_manager();
-    let default_scope = if admin {
-        FontScope::System
-    } else {
-        FontScope::User
+#[pyo3(signature = (family=None, pattern=None, admin=false, dry_run=false, scope=None))]
+fn uninstall_matching(
+    family: Option<&str>,
+    pattern: Option<&str>,
+    admin: bool,
+    dry_run: bool,
+    scope: Option<&str>,
+) -> PyResult<Vec<PyFontSource>> {
+    let matcher = match (family, pattern) {
+        (Some(family), None) => FaceMatcher::family(family),
+        (None, Some(pattern)) => {
+            FaceMatcher::regex(pattern).map_err(|e| py_error("match fonts", e))?
+        }
+        _ => {
+            return Err(py_error(
+                "match fonts",
+                FontError::InvalidArgument("Pass exactly one of family or pattern".to_string()),
+            ))
+        }
     };
+    let scope = resolve_scope(scope, admin)?;
+    let manager = create_platform_manager();
+    let fonts = manager
+        .list_installed_fonts()
+        .map_err(|e| py_error("list fonts", e))?;
+    let matched = face_match::matching_fonts(&fonts, &matcher, scope);
+    if dry_run {
+        return Ok(matched
+            .into_iter()
+            .map(|font| acted_on(font.path, scope))
+            .collect());
+    }
+
+    let mut uninstalled = Vec::new();
+    let mut first_error = None;
+    face_match::uninstall_matching(
+        manager.as_ref(),
+        &matched,
+        scope,
+        |font, result| match result {
+            Ok(()) => uninstalled.push(acted_on(font.path.clone(), scope)),
+            Err(e) => {
+                first_error.get_or_insert_with(|| e.message());
+            }
+        },
+    )
+    .map_err(|e| py_error("uninstall fonts", e))?;
+    match first_error {
+        Some(message) => Err(py_error(
+            "uninstall fonts",
+            FontError::RegistrationFailed(message),
+        )),
+        None => Ok(uninstalled),
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (font_path=None, name=None, admin=false, dry_run=false, scope=None))]
+fn remove(
+    font_path: Option<&str>,
+    name: Option<&str>,
+    admin: bool,
+    dry_run: bool,
+    scope: Option<&str>,
+) -> PyResult<PyFontSource> {
+    let default_scope = resolve_scope(scope, admin)?;
+    let manager = create_platform_manager();
 
     let (path, scope) = resolve_font_target(&manager, font_path, name, default_scope)?;
-    remove_resolved(&manager, &path, scope, dry_run)
+    remove_resolved(&manager, &path, scope, dry_run)?;
+    Ok(acted_on(path, scope))
+}
+
+#[pyfunction]
+#[pyo3(signature = (admin=false, prune=true, cache=true, dry_run=false, progress=None, scope=None))]
+fn cleanup(
+    admin: bool,
+    prune: bool,
+    cache: bool,
+    dry_run: bool,
+    progress: Option<PyObject>,
+    scope: Option<&str>,
+) -> PyResult<&'static str> {
+    let scope = resolve_scope(scope, admin)?;
+    let manager = create_platform_manager();
+    let progress = PyProgress::new(progress);
+    cleanup_observed(&manager, scope, prune, cache, dry_run, &progress)?;
+    Ok(scope_name(scope))
+}
+
+/// Whether the process runs as root / Administrator, so `scope="system"`
+/// can succeed. `scope="auto"` picks system exactly when this is true.
+#[pyfunction]
+fn is_elevated() -> bool {
+    environment::is_elevated()
+}
+
+/// A font installed for the current session while a `with` block runs.
+///
+/// ```python
+/// with fontlift.temporary("Specimen.ttf") as session:
+///     render(session.path)
+/// ```
+///
+/// The font is registered on `__enter__` (for this login session only:
+/// nothing is copied) and unregistered on `__exit__`, also when the block
+/// raises. If the process dies first, the registration still ends at
+/// logout.
+#[pyclass(module = "fontlift._native", name = "FontSession")]
+struct PyFontSession {
+    manager: Arc<dyn FontManager>,
+    #[pyo3(get)]
+    path: String,
+    font: Option<TemporaryFont>,
+}
+
+#[pymethods]
+impl PyFontSession {
+    /// Whether the font is currently registered by this session.
+    #[getter]
+    fn active(&self) -> bool {
+        self.font.as_ref().is_some_and(TemporaryFont::is_active)
+    }
+
+    /// Register the font. Calling it again while active does nothing.
+    fn install(&mut self) -> PyResult<()> {
+        if self.active() {
+            return Ok(());
+        }
+        let font = TemporaryFont::install(self.manager.clone(), Path::new(&self.path))
+            .map_err(|e| py_error("install temporary font", e))?;
+        self.font = Some(font);
+        Ok(())
+    }
+
+    /// Unregister the font now. Safe to call more than once.
+    fn close(&mut self) -> PyResult<()> {
+        match self.font.take() {
+            Some(font) => font
+                .uninstall()
+                .map_err(|e| py_error("uninstall temporary font", e)),
+            None => Ok(()),
+        }
+    }
+
+    fn __enter__(mut slf: PyRefMut<'_, Self>) -> PyResult<PyRefMut<'_, Self>> {
+        slf.install()?;
+        Ok(slf)
+    }
+
+    /// Always unregisters; never suppresses the block's exception.
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<PyObject>,
+        _exc_value: Option<PyObject>,
+        _traceback: Option<PyObject>,
+    ) -> PyResult<bool> {
+        self.close()?;
+        Ok(false)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "FontSession(path='{}', active={})",
+            self.path,
+            if self.active() { "True" } else { "False" }
+        )
+    }
+}
+
+/// A [`PyFontSession`] for `font_path`; the font is installed when the
+/// `with` block starts (or on `install()`).
+#[pyfunction]
+fn temporary(font_path: &str) -> PyFontSession {
+    PyFontSession {
+        manager: create_platform_manager(),
+        path: font_path.to_string(),
+        font: None,
+    }
+}
+
+/// Fonts registered from bytes for this process, see
+/// [`fontlift_core::memory`].
+///
+/// ```python
+/// with fontlift.register_bytes(data, label="Brand.otf") as font:
+///     render(font.postscript_names)
+/// ```
+///
+/// Registered when created; unregistered by `close()`, on `__exit__` or
+/// when garbage-collected.
+#[pyclass(module = "fontlift._native", name = "FontHandle")]
+struct PyFontHandle {
+    #[pyo3(get)]
+    label: String,
+    #[pyo3(get)]
+    postscript_names: Vec<String>,
+    handle: Option<FontHandle>,
+}
+
+#[pymethods]
+impl PyFontHandle {
+    /// Whether the fonts are still registered by this handle.
+    #[getter]
+    fn registered(&self) -> bool {
+        self.handle.as_ref().is_some_and(FontHandle::is_registered)
+    }
+
+    /// Unregister the fonts now. Safe to call more than once.
+    fn close(&mut self) -> PyResult<()> {
+        match self.handle.take() {
+            Some(handle) => handle
+                .unregister()
+                .map_err(|e| py_error("unregister in-memory font", e)),
+            None => Ok(()),
+        }
+    }
+
+    fn __enter__(slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf
+    }
+
+    /// Always unregisters; never suppresses the block's exception.
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<PyObject>,
+        _exc_value: Option<PyObject>,
+        _traceback: Option<PyObject>,
+    ) -> PyResult<bool> {
+        self.close()?;
+        Ok(false)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "FontHandle(label='{}', postscript_names={:?}, registered={})",
+            self.label,
+            self.postscript_names,
+            if self.registered() { "True" } else { "False" }
+        )
+    }
+}
+
+/// Register font `data` for this process and return its [`PyFontHandle`].
+#[pyfunction]
+#[pyo3(signature = (data, label=None, validate=true))]
+fn register_bytes(data: &[u8], label: Option<String>, validate: bool) -> PyResult<PyFontHandle> {
+    let options = MemoryFontOptions { validate, label };
+    let handle = create_platform_manager()
+        .register_font_bytes(data, &options)
+        .map_err(|e| py_error("register in-memory font", e))?;
+    Ok(PyFontHandle {
+        label: handle.label().to_string(),
+        postscript_names: handle.postscript_names().to_vec(),
+        handle: Some(handle),
+    })
+}
+
+/// Describe a font file without installing it.
+///
+/// Returns face 0's fields at the top level and every face (one for a
+/// single font, several for a collection) under `faces`.
+#[pyfunction]
+fn info(py: Python<'_>, font_path: &str) -> PyResult<PyObject> {
+    let faces = metadata::read_all_face_info(Path::new(font_path))
+        .map_err(|e| py_error("read font info", e))?;
+    let details = faces
+        .into_iter()
+        .map(|face| face_details(py, face))
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let result = details[0].copy()?;
+    result.set_item("face_count", details.len())?;
+    result.set_item("faces", PyList::new(py, details)?)?;
+    Ok(result.into_any().unbind())
 }
 
+/// Installed faces whose PostScript, full or family name contains `query`,
+/// ignoring case, and that support every character of `supports`.
 #[pyfunction]
-#[pyo3(signature = (admin=false, prune=true, cache=true, dry_run=false))]
-fn cleanup(admin: bool, prune: bool, cache: bool, dry_run: bool) -> PyResult<()> {
+#[pyo3(signature = (query=None, supports=None))]
+fn find(py: Python<'_>, query: Option<&str>, supports: Option<&str>) -> PyResult<Vec<PyObject>> {
     let manager = create_platform_manager();
-    cleanup_with_manager(&manager, admin, prune, cache, dry_run)
+    let fonts = manager
+        .list_installed_fonts()
+        .map_err(|e| py_error("list fonts", e))?;
+    let mut found = match (query, supports) {
+        (Some(query), _) => matching_faces(fonts, query)?,
+        (None, Some(_)) => {
+            let mut fonts = fonts;
+            fonts.sort_by(sorting::compare_faces);
+            fonts
+        }
+        (None, None) => {
+            return Err(FontliftError::new_err(
+                "A name to search for or characters to support is required",
+            ))
+        }
+    };
+    if let Some(text) = supports {
+        found.retain(|font| {
+            metadata::read_coverage(&font.source.path, font.source.face_index.unwrap_or(0), text)
+                .is_ok_and(|coverage| coverage.is_complete())
+        });
+    }
+    faces_to_py(py, found)
+}
+
+/// Which characters of `text` each face of a font file supports.
+#[pyfunction]
+fn coverage(py: Python<'_>, font_path: &str, text: &str) -> PyResult<Vec<PyObject>> {
+    let path = Path::new(font_path);
+    let faces = metadata::read_all_face_info(path).map_err(|e| py_error("read font info", e))?;
+    let mut reports = Vec::with_capacity(faces.len());
+    for face in faces {
+        let index = face.source.face_index.unwrap_or(0);
+        let coverage = metadata::read_coverage(path, index, text)
+            .map_err(|e| py_error("read font coverage", e))?;
+        let item = PyDict::new(py);
+        item.set_item("face_index", index)?;
+        item.set_item("postscript_name", face.postscript_name)?;
+        item.set_item(
+            "supported",
+            coverage
+                .supported
+                .iter()
+                .map(char::to_string)
+                .collect::<Vec<_>>(),
+        )?;
+        item.set_item(
+            "missing",
+            coverage
+                .missing
+                .iter()
+                .map(char::to_string)
+                .collect::<Vec<_>>(),
+        )?;
+        reports.push(item.into_any().unbind());
+    }
+    Ok(reports)
 }
 
 #[pymodule]
@@ -596,11 +1328,32 @@ fn _native(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyFontSource>()?;
     m.add_class::<PyFontFaceInfo>()?;
     m.add_class::<FontliftManager>()?;
+    m.add_class::<PyFontSession>()?;
+    m.add_class::<PyFontHandle>()?;
     m.add_function(wrap_pyfunction!(install, m)?)?;
+    m.add_function(wrap_pyfunction!(install_bytes, m)?)?;
     m.add_function(wrap_pyfunction!(list, m)?)?;
     m.add_function(wrap_pyfunction!(uninstall, m)?)?;
+    m.add_function(wrap_pyfunction!(uninstall_matching, m)?)?;
     m.add_function(wrap_pyfunction!(remove, m)?)?;
     m.add_function(wrap_pyfunction!(cleanup, m)?)?;
+    m.add_function(wrap_pyfunction!(temporary, m)?)?;
+    m.add_function(wrap_pyfunction!(register_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(info, m)?)?;
+    m.add_function(wrap_pyfunction!(find, m)?)?;
+    m.add_function(wrap_pyfunction!(coverage, m)?)?;
+    m.add_function(wrap_pyfunction!(is_elevated, m)?)?;
+    m.add("FontliftError", py.get_type::<FontliftError>())?;
+    m.add("FontNotFoundError", py.get_type::<FontNotFoundError>())?;
+    m.add(
+        "PermissionDeniedError",
+        py.get_type::<PermissionDeniedError>(),
+    )?;
+    m.add(
+        "AlreadyInstalledError",
+        py.get_type::<AlreadyInstalledError>(),
+    )?;
+    m.add("ValidationError", py.get_type::<ValidationError>())?;
     m.add("__version__", VERSION)?;
 
     // Expose convenience alias matching CLI naming
@@ -612,15 +1365,56 @@ fn _native(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use fontlift_core::prune::{PruneProgress, PruneReport};
     #[cfg(feature = "python-bindings")]
     use pyo3::types::PyDict;
     use std::path::PathBuf;
     use std::sync::Arc;
 
+    #[test]
+    #[cfg(feature = "python-bindings")]
+    fn progress_callback_sees_each_step_and_errors_surface_later() {
+        Python::with_gil(|py| {
+            let manager = Arc::new(FakeManager::default());
+            let dyn_manager: Arc<dyn FontManager> = manager.clone();
+            let events = PyList::empty(py);
+            let record = py
+                .eval(
+                    c"lambda events: lambda *event: events.append(event)",
+                    None,
+                    None,
+                )
+                .unwrap()
+                .call1((events.clone(),))
+                .unwrap();
+
+            let progress = PyProgress::new(Some(record.unbind()));
+            cleanup_observed(&dyn_manager, FontScope::User, false, true, false, &progress).unwrap();
+            let events: Vec<(String, String, usize, usize, Option<String>)> =
+                events.extract().unwrap();
+            assert_eq!(
+                events,
+                vec![
+                    ("start".into(), "clear_cache".into(), 0, 1, None),
+                    ("item".into(), "clear_cache".into(), 1, 1, None),
+                    ("done".into(), "clear_cache".into(), 1, 1, None),
+                ]
+            );
+
+            let raise = py.eval(c"lambda *event: 1 / 0", None, None).unwrap();
+            let progress = PyProgress::new(Some(raise.unbind()));
+            let err =
+                cleanup_observed(&dyn_manager, FontScope::User, false, true, false, &progress)
+                    .expect_err("callback error is re-raised");
+            assert!(err.to_string().contains("ZeroDivisionError"));
+            assert_eq!(manager.cache_calls().len(), 2, "cache cleared despite it");
+        });
+    }
+
     #[derive(Default)]
     struct FakeManager {
         prune_calls: Mutex<VecDeque<FontScope>>,
-        cache_calls: Mutex<VecDeque<FontScope>>,
+        cache_calls: Mutex<VecDeque<CacheClearStrategy>>,
     }
 
     impl FakeManager {
@@ -633,7 +1427,7 @@ mod tests {
                 .collect()
         }
 
-        fn cache_calls(&self) -> Vec<FontScope> {
+        fn cache_calls(&self) -> Vec<CacheClearStrategy> {
             self.cache_calls
                 .lock()
                 .expect("cache lock")
@@ -670,20 +1464,25 @@ mod tests {
             Ok(Vec::new())
         }
 
-        fn clear_font_caches(&self, scope: FontScope) -> FontResult<()> {
+        fn clear_font_caches(&self, strategy: CacheClearStrategy) -> FontResult<CacheClearResult> {
             self.cache_calls
                 .lock()
                 .expect("cache lock")
-                .push_back(scope);
-            Ok(())
+                .push_back(strategy);
+            Ok(CacheClearResult::default())
         }
 
-        fn prune_missing_fonts(&self, scope: FontScope) -> FontResult<usize> {
+        fn prune_stale_registrations(
+            &self,
+            scope: FontScope,
+            _options: &PruneOptions,
+            _progress: &mut dyn FnMut(&PruneProgress),
+        ) -> FontResult<PruneReport> {
             self.prune_calls
                 .lock()
                 .expect("prune lock")
                 .push_back(scope);
-            Ok(1)
+            Ok(PruneReport::new(scope))
         }
     }
 
@@ -696,6 +1495,33 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "python-bindings")]
+    fn font_errors_map_to_typed_exceptions() {
+        Python::with_gil(|py| {
+            let err = py_error(
+                "install font",
+                FontError::FontNotFound(PathBuf::from("/tmp/Missing.ttf")),
+            );
+            assert!(err.is_instance_of::<FontNotFoundError>(py));
+            assert!(err.is_instance_of::<FontliftError>(py));
+            assert!(err.is_instance_of::<PyRuntimeError>(py));
+            let code: String = err.value(py).getattr("code").unwrap().extract().unwrap();
+            assert_eq!(code, "font_not_found");
+
+            let denied = py_error(
+                "remove font",
+                FontError::SystemFontProtection(PathBuf::from("/System/Library/Fonts/SFNS.ttf")),
+            );
+            assert!(denied.is_instance_of::<PermissionDeniedError>(py));
+            let invalid = py_error("install font", FontError::InvalidFormat("bad".into()));
+            assert!(invalid.is_instance_of::<ValidationError>(py));
+            let other = py_error("clear font caches", FontError::ScriptError("x".into()));
+            assert!(!other.is_instance_of::<ValidationError>(py));
+            assert!(other.is_instance_of::<FontliftError>(py));
+        });
+    }
+
     // This test requires linking to libpython and only runs when the
     // `python-bindings` feature is enabled (maturin builds).
     #[test]
@@ -756,10 +1582,10 @@ mod tests {
         let manager = Arc::new(FakeManager::default());
         let dyn_manager: Arc<dyn FontManager> = manager.clone();
 
-        cleanup_with_manager(&dyn_manager, false, true, true, false).expect("cleanup");
+        cleanup_with_manager(&dyn_manager, FontScope::User, true, true, false).expect("cleanup");
 
         assert_eq!(manager.prune_calls(), vec![FontScope::User]);
-        assert_eq!(manager.cache_calls(), vec![FontScope::User]);
+        assert_eq!(manager.cache_calls(), vec![CacheClearStrategy::UserOnly]);
     }
 
     #[test]
@@ -767,11 +1593,41 @@ mod tests {
         let manager = Arc::new(FakeManager::default());
         let dyn_manager: Arc<dyn FontManager> = manager.clone();
 
-        cleanup_with_manager(&dyn_manager, false, true, false, false).expect("prune only");
-        cleanup_with_manager(&dyn_manager, true, false, true, false).expect("cache only admin");
+        cleanup_with_manager(&dyn_manager, FontScope::User, true, false, false)
+            .expect("prune only");
+        cleanup_with_manager(&dyn_manager, FontScope::System, false, true, false)
+            .expect("cache only admin");
 
         assert_eq!(manager.prune_calls(), vec![FontScope::User]);
-        assert_eq!(manager.cache_calls(), vec![FontScope::System]);
+        assert_eq!(manager.cache_calls(), vec![CacheClearStrategy::SystemOnly]);
+    }
+
+    #[test]
+    fn scope_argument_overrides_admin_and_auto_follows_elevation() {
+        assert_eq!(scope_from_args(None, false, true).unwrap(), FontScope::User);
+        assert_eq!(
+            scope_from_args(None, true, false).unwrap(),
+            FontScope::System
+        );
+        assert_eq!(
+            scope_from_args(Some("System"), false, false).unwrap(),
+            FontScope::System
+        );
+        assert_eq!(
+            scope_from_args(Some("auto"), false, false).unwrap(),
+            FontScope::User
+        );
+        assert_eq!(
+            scope_from_args(Some("auto"), false, true).unwrap(),
+            FontScope::System
+        );
+        assert_eq!(
+            scope_from_args(Some("auto"), true, false).unwrap(),
+            FontScope::System
+        );
+        assert!(scope_from_args(Some("user"), true, true).is_err());
+        let err = scope_from_args(Some("machine"), false, false).unwrap_err();
+        assert!(err.to_string().contains("\"machine\""), "{err}");
     }
 
     #[test]
@@ -779,11 +1635,11 @@ mod tests {
         let manager = Arc::new(FakeManager::default());
         let dyn_manager: Arc<dyn FontManager> = manager.clone();
 
-        cleanup_with_manager(&dyn_manager, false, true, true, true).expect("dry run");
+        cleanup_with_manager(&dyn_manager, FontScope::User, true, true, true).expect("dry run");
         assert!(manager.prune_calls().is_empty());
         assert!(manager.cache_calls().is_empty());
 
-        let err = cleanup_with_manager(&dyn_manager, false, false, false, false)
+        let err = cleanup_with_manager(&dyn_manager, FontScope::User, false, false, false)
             .expect_err("at least one action required");
         assert!(
             err.to_string().contains("cleanup requires"),
@@ -871,13 +1727,25 @@ mod tests {
             Ok(self.installed_fonts.clone())
         }
 
-        fn clear_font_caches(&self, _scope: FontScope) -> FontResult<()> {
-            Ok(())
+        fn clear_font_caches(&self, _: CacheClearStrategy) -> FontResult<CacheClearResult> {
+            Ok(CacheClearResult::default())
         }
     }
 
+    /// Keep the install database and history that uninstalls and removes
+    /// write out of the user's own, for every test in the process.
+    fn keep_records_in_temp() {
+        static RECORDS: std::sync::Once = std::sync::Once::new();
+        RECORDS.call_once(|| {
+            let dir = std::env::temp_dir().join(format!("fontlift-py-{}", std::process::id()));
+            std::env::set_var("FONTLIFT_INSTALL_DB_PATH", dir.join("installed.json"));
+            std::env::set_var("FONTLIFT_HISTORY_PATH", dir.join("history.jsonl"));
+        });
+    }
+
     #[test]
     fn resolve_font_by_name_uses_scope_and_falls_back_on_error() {
+        keep_records_in_temp();
         let font = FontliftFontFaceInfo::new(
             FontliftFontSource::new(PathBuf::from("/fonts/Example.ttf"))
                 .with_scope(Some(FontScope::System)),
@@ -908,6 +1776,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn managed_policy_refuses_locked_scopes() {
+        keep_records_in_temp();
+        let policy = std::env::temp_dir().join(format!("fontlift-py-{}.json", std::process::id()));
+        std::fs::write(&policy, r#"{"allow_system_scope": false}"#).unwrap();
+        std::env::set_var("FONTLIFT_POLICY_PATH", &policy);
+
+        let manager = Arc::new(RecordingManager::default());
+        let dyn_manager: Arc<dyn FontManager> = manager.clone();
+        let path = PathBuf::from("/fonts/Example.ttf");
+        let used_scope = uninstall_resolved(&dyn_manager, &path, FontScope::System, false);
+        let removed = remove_resolved(&dyn_manager, &path, FontScope::System, false);
+        let cleaned = cleanup_observed(
+            &dyn_manager,
+            FontScope::System,
+            true,
+            true,
+            false,
+            &PyProgress::new(None),
+        );
+        std::env::remove_var("FONTLIFT_POLICY_PATH");
+        let _ = std::fs::remove_file(&policy);
+
+        assert_eq!(used_scope.expect("falls back to user"), FontScope::User);
+        assert_eq!(manager.uninstall_scopes(), vec![FontScope::User]);
+        assert!(removed.unwrap_err().to_string().contains("system"));
+        assert!(manager.remove_scopes().is_empty());
+        assert!(cleaned.is_err());
+    }
+
     #[test]
     fn resolve_font_target_requires_identifier() {
         let manager = Arc::new(RecordingManager::default());
@@ -919,8 +1817,46 @@ mod tests {
         assert!(err.to_string().contains("font_path or name is required"));
     }
 
+    #[test]
+    fn find_matches_names_case_insensitively_in_menu_order() {
+        let face = |ps: &str, family: &str, weight: u16| {
+            let mut font = FontliftFontFaceInfo::new(
+                FontliftFontSource::new(PathBuf::from(format!("/fonts/{ps}.ttf"))),
+                ps.to_string(),
+                ps.replace('-', " "),
+                family.to_string(),
+                "Regular".to_string(),
+            );
+            font.weight = Some(weight);
+            font
+        };
+        let fonts = vec![
+            face("Inter-Bold", "Inter", 700),
+            face("Arial-Regular", "Arial", 400),
+            face("InterDisplay-Light", "Inter Display", 300),
+            face("Inter-Regular", "Inter", 400),
+        ];
+
+        let names = |found: Vec<FontliftFontFaceInfo>| -> Vec<String> {
+            found.into_iter().map(|f| f.postscript_name).collect()
+        };
+        assert_eq!(
+            names(matching_faces(fonts.clone(), "inter").unwrap()),
+            vec!["Inter-Regular", "Inter-Bold", "InterDisplay-Light"]
+        );
+        assert_eq!(
+            names(matching_faces(fonts.clone(), "INTER DISPLAY").unwrap()),
+            vec!["InterDisplay-Light"]
+        );
+        assert!(matching_faces(fonts.clone(), "Helvetica")
+            .unwrap()
+            .is_empty());
+        assert!(matching_faces(fonts, "  ").is_err());
+    }
+
     #[test]
     fn remove_by_name_uses_font_scope_and_supports_dry_run() {
+        keep_records_in_temp();
         let font = FontliftFontFaceInfo::new(
             FontliftFontSource::new(PathBuf::from("/fonts/Remove.ttf"))
                 .with_scope(Some(FontScope::User)),
@@ -0,0 +1,778 @@
+//! Reproducible synthetic fonts for fontlift tests and benchmarks.
+//!
+//! Real fonts are the only honest input for code that parses, validates and
+//! installs fonts, but checked-in fixtures are large, carry licenses, and
+//! only cover the cases someone happened to download. This crate builds
+//! small, fully valid fonts on demand instead, with the names, weights,
+//! character coverage and outline format a test asks for:
+//!
+//! ```
+//! use fontlift_testfonts::{Defect, TestFont};
+//!
+//! let bold = TestFont::new("Fixture Sans").with_style("Bold").with_weight(700);
+//! let bytes = bold.build();
+//! assert_eq!(&bytes[..4], &[0, 1, 0, 0]);
+//!
+//! let broken = TestFont::new("Fixture Sans").with_defect(Defect::TruncatedTable(*b"name"));
+//! assert!(broken.build().len() < bytes.len());
+//! ```
+//!
+//! Output is deterministic: the same description always yields the same
+//! bytes, so digests can be asserted and benchmarks compare like with like.
+//! Glyphs are plain rectangles, one per mapped character plus `.notdef`.
+
+use kurbo::{BezPath, Rect, Shape};
+use std::io;
+use std::path::{Path, PathBuf};
+use write_fonts::read::tables::head::MacStyle;
+use write_fonts::read::tables::os2::SelectionFlags;
+use write_fonts::tables::cmap::Cmap;
+use write_fonts::tables::fvar::{AxisInstanceArrays, Fvar, InstanceRecord, VariationAxisRecord};
+use write_fonts::tables::glyf::{GlyfLocaBuilder, SimpleGlyph};
+use write_fonts::tables::head::Head;
+use write_fonts::tables::hhea::Hhea;
+use write_fonts::tables::hmtx::{Hmtx, LongMetric};
+use write_fonts::tables::maxp::Maxp;
+use write_fonts::tables::name::{Name, NameRecord};
+use write_fonts::tables::os2::Os2;
+use write_fonts::tables::post::Post;
+use write_fonts::types::{FWord, Fixed, GlyphId, NameId, Tag, UfWord, Version16Dot16};
+use write_fonts::FontBuilder;
+
+const UNITS_PER_EM: u16 = 1000;
+const ADVANCE: u16 = 600;
+const GLYPH_BOX: (i16, i16, i16, i16) = (50, 0, 550, 700);
+const VENDOR_ID: &[u8; 4] = b"FLFT";
+
+/// Outline technology of the generated font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outlines {
+    /// `glyf`/`loca` outlines, written as `.ttf`.
+    TrueType,
+    /// A `CFF ` table, written as `.otf` with the `OTTO` signature.
+    Cff,
+}
+
+/// Deliberate damage applied after the font is assembled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Defect {
+    /// The table directory records a wrong checksum for `name`. Every table
+    /// still parses; only checksum verification notices.
+    BadChecksum,
+    /// The file ends halfway through the given table, so it and every table
+    /// stored after it are cut off.
+    TruncatedTable([u8; 4]),
+}
+
+/// Description of one synthetic font face.
+#[derive(Debug, Clone)]
+pub struct TestFont {
+    family: String,
+    style: String,
+    postscript_name: Option<String>,
+    weight: u16,
+    width: u16,
+    italic: bool,
+    revision: f64,
+    chars: Vec<char>,
+    outlines: Outlines,
+    weight_axis: Option<(u16, u16)>,
+    family_class: i16,
+    x_height: i16,
+    defects: Vec<Defect>,
+}
+
+impl TestFont {
+    /// A Regular, weight 400 TrueType face covering `A`–`Z`.
+    pub fn new(family: impl Into<String>) -> Self {
+        Self {
+            family: family.into(),
+            style: "Regular".to_string(),
+            postscript_name: None,
+            weight: 400,
+            width: 5,
+            italic: false,
+            revision: 1.0,
+            chars: ('A'..='Z').collect(),
+            outlines: Outlines::TrueType,
+            weight_axis: None,
+            family_class: 0,
+            x_height: 500,
+            defects: Vec::new(),
+        }
+    }
+
+    pub fn with_style(mut self, style: impl Into<String>) -> Self {
+        self.style = style.into();
+        self
+    }
+
+    /// Override the PostScript name (default: family and style without spaces).
+    pub fn with_postscript_name(mut self, name: impl Into<String>) -> Self {
+        self.postscript_name = Some(name.into());
+        self
+    }
+
+    /// `OS/2.usWeightClass`; 700 and above also set the bold flags.
+    pub fn with_weight(mut self, weight: u16) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// `OS/2.usWidthClass` (1–9, 5 is normal).
+    pub fn with_width(mut self, width: u16) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn with_italic(mut self, italic: bool) -> Self {
+        self.italic = italic;
+        self
+    }
+
+    /// `head.fontRevision`, also used for the name table version string.
+    pub fn with_revision(mut self, revision: f64) -> Self {
+        self.revision = revision;
+        self
+    }
+
+    /// Characters mapped in `cmap`, one glyph each. Duplicates are ignored.
+    pub fn with_chars(mut self, chars: impl IntoIterator<Item = char>) -> Self {
+        let mut chars: Vec<char> = chars.into_iter().collect();
+        chars.sort_unstable();
+        chars.dedup();
+        self.chars = chars;
+        self
+    }
+
+    pub fn with_outlines(mut self, outlines: Outlines) -> Self {
+        self.outlines = outlines;
+        self
+    }
+
+    /// Make the font variable along `wght` from `min` to `max`, defaulting
+    /// to the face's weight, with a named instance per hundred in range.
+    pub fn with_weight_axis(mut self, min: u16, max: u16) -> Self {
+        self.weight_axis = Some((min, max));
+        self
+    }
+
+    /// `OS/2.sFamilyClass` as class and subclass (e.g. `(8, 1)` for a
+    /// grotesque sans). Default: no classification.
+    pub fn with_family_class(mut self, class: u8, subclass: u8) -> Self {
+        self.family_class = i16::from_be_bytes([class, subclass]);
+        self
+    }
+
+    /// `OS/2.sxHeight` in font units (the em is 1000). Default: 500.
+    pub fn with_x_height(mut self, x_height: i16) -> Self {
+        self.x_height = x_height;
+        self
+    }
+
+    pub fn with_defect(mut self, defect: Defect) -> Self {
+        self.defects.push(defect);
+        self
+    }
+
+    pub fn family(&self) -> &str {
+        &self.family
+    }
+
+    pub fn style(&self) -> &str {
+        &self.style
+    }
+
+    pub fn full_name(&self) -> String {
+        format!("{} {}", self.family, self.style)
+    }
+
+    pub fn postscript_name(&self) -> String {
+        self.postscript_name.clone().unwrap_or_else(|| {
+            let strip = |s: &str| s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+            format!("{}-{}", strip(&self.family), strip(&self.style))
+        })
+    }
+
+    /// `<PostScriptName>.ttf` or `.otf`, matching the outline format.
+    pub fn file_name(&self) -> String {
+        let ext = match self.outlines {
+            Outlines::TrueType => "ttf",
+            Outlines::Cff => "otf",
+        };
+        format!("{}.{}", self.postscript_name(), ext)
+    }
+
+    /// Assemble the font binary.
+    pub fn build(&self) -> Vec<u8> {
+        let num_glyphs = self.chars.len() as u16 + 1;
+        let mut builder = FontBuilder::new();
+
+        builder.add_table(&self.hhea(num_glyphs)).expect("hhea");
+        builder.add_table(&self.os2()).expect("OS/2");
+        builder.add_table(&self.name()).expect("name");
+        builder.add_table(&self.post()).expect("post");
+        builder
+            .add_table(&Hmtx::new(
+                vec![LongMetric::new(ADVANCE, GLYPH_BOX.0); num_glyphs as usize],
+                Vec::new(),
+            ))
+            .expect("hmtx");
+        builder
+            .add_table(
+                &Cmap::from_mappings(
+                    self.chars
+                        .iter()
+                        .enumerate()
+                        .map(|(i, c)| (*c, GlyphId::new(i as u32 + 1))),
+                )
+                .expect("cmap"),
+            )
+            .expect("cmap");
+
+        if let Some(fvar) = self.fvar() {
+            builder.add_table(&fvar).expect("fvar");
+        }
+
+        let mut head = self.head();
+        match self.outlines {
+            Outlines::TrueType => {
+                let mut glyphs = GlyfLocaBuilder::new();
+                let outline = glyph_outline();
+                for _ in 0..num_glyphs {
+                    glyphs.add_glyph(&outline).expect("glyph");
+                }
+                let (glyf, loca, loca_format) = glyphs.build();
+                head.index_to_loc_format = loca_format as i16;
+                builder.add_table(&glyf).expect("glyf");
+                builder.add_table(&loca).expect("loca");
+                builder.add_table(&truetype_maxp(num_glyphs)).expect("maxp");
+            }
+            Outlines::Cff => {
+                builder.add_raw(
+                    Tag::new(b"CFF "),
+                    cff_table(&self.postscript_name(), num_glyphs),
+                );
+                builder.add_table(&Maxp::new(num_glyphs)).expect("maxp");
+            }
+        }
+        builder.add_table(&head).expect("head");
+
+        let mut data = builder.build();
+        if self.outlines == Outlines::Cff {
+            data[..4].copy_from_slice(b"OTTO");
+            fix_checksum_adjustment(&mut data);
+        }
+        for defect in &self.defects {
+            apply_defect(&mut data, *defect);
+        }
+        data
+    }
+
+    /// Write the font into `dir` under [`TestFont::file_name`].
+    pub fn write_to(&self, dir: &Path) -> io::Result<PathBuf> {
+        let path = dir.join(self.file_name());
+        std::fs::write(&path, self.build())?;
+        Ok(path)
+    }
+
+    fn is_bold(&self) -> bool {
+        self.weight >= 700
+    }
+
+    fn head(&self) -> Head {
+        let mut mac_style = MacStyle::empty();
+        if self.is_bold() {
+            mac_style |= MacStyle::BOLD;
+        }
+        if self.italic {
+            mac_style |= MacStyle::ITALIC;
+        }
+        Head {
+            font_revision: Fixed::from_f64(self.revision),
+            units_per_em: UNITS_PER_EM,
+            x_min: GLYPH_BOX.0,
+            y_min: GLYPH_BOX.1,
+            x_max: GLYPH_BOX.2,
+            y_max: GLYPH_BOX.3,
+            mac_style,
+            lowest_rec_ppem: 8,
+            ..Default::default()
+        }
+    }
+
+    fn hhea(&self, num_glyphs: u16) -> Hhea {
+        Hhea::new(
+            FWord::new(800),
+            FWord::new(-200),
+            FWord::new(0),
+            UfWord::new(ADVANCE),
+            FWord::new(GLYPH_BOX.0),
+            FWord::new(ADVANCE as i16 - GLYPH_BOX.2),
+            FWord::new(GLYPH_BOX.2),
+            1,
+            0,
+            0,
+            num_glyphs,
+        )
+    }
+
+    fn os2(&self) -> Os2 {
+        let mut selection = SelectionFlags::empty();
+        if self.italic {
+            selection |= SelectionFlags::ITALIC;
+        }
+        if self.is_bold() {
+            selection |= SelectionFlags::BOLD;
+        }
+        if selection.is_empty() {
+            selection = SelectionFlags::REGULAR;
+        }
+        let first = self
+            .chars
+            .first()
+            .map_or(0, |c| (*c as u32).min(0xFFFF) as u16);
+        let last = self
+            .chars
+            .last()
+            .map_or(0, |c| (*c as u32).min(0xFFFF) as u16);
+
+        Os2 {
+            x_avg_char_width: ADVANCE as i16,
+            us_weight_class: self.weight,
+            us_width_class: self.width,
+            fs_type: 0,
+            s_family_class: self.family_class,
+            ul_unicode_range_1: 1,
+            ach_vend_id: Tag::new(VENDOR_ID),
+            fs_selection: selection,
+            us_first_char_index: first,
+            us_last_char_index: last,
+            s_typo_ascender: 800,
+            s_typo_descender: -200,
+            s_typo_line_gap: 0,
+            us_win_ascent: 800,
+            us_win_descent: 200,
+            ul_code_page_range_1: Some(1),
+            ul_code_page_range_2: Some(0),
+            sx_height: Some(self.x_height),
+            s_cap_height: Some(GLYPH_BOX.3),
+            us_default_char: Some(0),
+            us_break_char: Some(32),
+            us_max_context: Some(0),
+            ..Default::default()
+        }
+    }
+
+    fn name(&self) -> Name {
+        let postscript_name = self.postscript_name();
+        let mut names = vec![
+            (NameId::FAMILY_NAME, self.family.clone()),
+            (NameId::SUBFAMILY_NAME, self.style.clone()),
+            (
+                NameId::UNIQUE_ID,
+                format!("{:.3};FLFT;{}", self.revision, postscript_name),
+            ),
+            (NameId::FULL_NAME, self.full_name()),
+            (
+                NameId::VERSION_STRING,
+                format!("Version {:.3}", self.revision),
+            ),
+            (NameId::POSTSCRIPT_NAME, postscript_name),
+        ];
+        if self.weight_axis.is_some() {
+            names.push((NameId::new(256), "Weight".to_string()));
+            for (i, (_, label)) in self.instances().into_iter().enumerate() {
+                names.push((NameId::new(257 + i as u16), label.to_string()));
+            }
+        }
+
+        Name::new(
+            names
+                .into_iter()
+                .map(|(id, text)| NameRecord::new(3, 1, 0x409, id, text.into()))
+                .collect(),
+        )
+    }
+
+    fn post(&self) -> Post {
+        Post {
+            version: Version16Dot16::VERSION_3_0,
+            italic_angle: Fixed::from_f64(if self.italic { -12.0 } else { 0.0 }),
+            underline_position: FWord::new(-100),
+            underline_thickness: FWord::new(50),
+            ..Default::default()
+        }
+    }
+
+    fn instances(&self) -> Vec<(u16, &'static str)> {
+        let Some((min, max)) = self.weight_axis else {
+            return Vec::new();
+        };
+        (1..=9)
+            .map(|step| step * 100)
+            .filter(|weight| (min..=max).contains(weight))
+            .map(|weight| (weight, weight_name(weight)))
+            .collect()
+    }
+
+    fn fvar(&self) -> Option<Fvar> {
+        let (min, max) = self.weight_axis?;
+        let axis = VariationAxisRecord::new(
+            Tag::new(b"wght"),
+            Fixed::from_f64(min as f64),
+            Fixed::from_f64(self.weight.clamp(min, max) as f64),
+            Fixed::from_f64(max as f64),
+            0,
+            NameId::new(256),
+        );
+        let instances = self
+            .instances()
+            .into_iter()
+            .enumerate()
+            .map(|(i, (weight, _))| InstanceRecord {
+                subfamily_name_id: NameId::new(257 + i as u16),
+                flags: 0,
+                coordinates: vec![Fixed::from_f64(weight as f64)],
+                post_script_name_id: None,
+            })
+            .collect();
+        Some(Fvar::new(AxisInstanceArrays::new(vec![axis], instances)))
+    }
+}
+
+/// The four styles of a classic family: Regular, Italic, Bold, Bold Italic.
+pub fn ribbi_family(family: &str) -> Vec<TestFont> {
+    [
+        ("Regular", 400, false),
+        ("Italic", 400, true),
+        ("Bold", 700, false),
+        ("Bold Italic", 700, true),
+    ]
+    .into_iter()
+    .map(|(style, weight, italic)| {
+        TestFont::new(family)
+            .with_style(style)
+            .with_weight(weight)
+            .with_italic(italic)
+    })
+    .collect()
+}
+
+/// Pack `fonts` into a TrueType collection, one face per font, in order.
+///
+/// Tables are not shared between faces. Defects are applied to each face
+/// before packing; truncation is only meaningful for standalone files.
+pub fn build_collection(fonts: &[TestFont]) -> Vec<u8> {
+    let header_len = 12 + 4 * fonts.len();
+    let mut data = Vec::with_capacity(header_len);
+    data.extend_from_slice(b"ttcf");
+    data.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    data.extend_from_slice(&(fonts.len() as u32).to_be_bytes());
+    data.resize(header_len, 0);
+
+    for (index, font) in fonts.iter().enumerate() {
+        let base = data.len() as u32;
+        data[12 + 4 * index..16 + 4 * index].copy_from_slice(&base.to_be_bytes());
+
+        let mut face = font.build();
+        for record in 0..read_u16(&face, 4) as usize {
+            let at = 12 + 16 * record + 8;
+            let offset = read_u32(&face, at) + base;
+            face[at..at + 4].copy_from_slice(&offset.to_be_bytes());
+        }
+        data.extend_from_slice(&face);
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+    data
+}
+
+/// Write a collection of `fonts` to `dir/file_name`.
+pub fn write_collection(dir: &Path, file_name: &str, fonts: &[TestFont]) -> io::Result<PathBuf> {
+    let path = dir.join(file_name);
+    std::fs::write(&path, build_collection(fonts))?;
+    Ok(path)
+}
+
+fn weight_name(weight: u16) -> &'static str {
+    match weight {
+        100 => "Thin",
+        200 => "ExtraLight",
+        300 => "Light",
+        400 => "Regular",
+        500 => "Medium",
+        600 => "SemiBold",
+        700 => "Bold",
+        800 => "ExtraBold",
+        _ => "Black",
+    }
+}
+
+fn glyph_outline() -> SimpleGlyph {
+    let (x0, y0, x1, y1) = GLYPH_BOX;
+    let rect = Rect::new(x0.into(), y0.into(), x1.into(), y1.into());
+    let path: BezPath = rect.path_elements(0.1).collect();
+    SimpleGlyph::from_bezpath(&path).expect("rectangle is a valid contour")
+}
+
+fn truetype_maxp(num_glyphs: u16) -> Maxp {
+    Maxp {
+        num_glyphs,
+        max_points: Some(4),
+        max_contours: Some(1),
+        max_composite_points: Some(0),
+        max_composite_contours: Some(0),
+        max_zones: Some(2),
+        max_twilight_points: Some(0),
+        max_storage: Some(0),
+        max_function_defs: Some(0),
+        max_instruction_defs: Some(0),
+        max_stack_elements: Some(0),
+        max_size_of_instructions: Some(0),
+        max_component_elements: Some(0),
+        max_component_depth: Some(0),
+    }
+}
+
+/// A bare CFF table: one font named `postscript_name`, `num_glyphs` empty
+/// charstrings and the default ISOAdobe charset.
+fn cff_table(postscript_name: &str, num_glyphs: u16) -> Vec<u8> {
+    fn index(items: &[&[u8]], off_size: u8) -> Vec<u8> {
+        let mut out = (items.len() as u16).to_be_bytes().to_vec();
+        if items.is_empty() {
+            return out;
+        }
+        out.push(off_size);
+        let mut offset = 1u32;
+        let push_offset = |out: &mut Vec<u8>, offset: u32| {
+            out.extend_from_slice(&offset.to_be_bytes()[4 - off_size as usize..]);
+        };
+        push_offset(&mut out, offset);
+        for item in items {
+            offset += item.len() as u32;
+            push_offset(&mut out, offset);
+        }
+        for item in items {
+            out.extend_from_slice(item);
+        }
+        out
+    }
+    fn int5(value: u32) -> [u8; 5] {
+        let b = value.to_be_bytes();
+        [29, b[0], b[1], b[2], b[3]]
+    }
+
+    const HEADER: [u8; 4] = [1, 0, 4, 1];
+    const ENDCHAR: &[u8] = &[14];
+    // nominalWidthX 0
+    const PRIVATE: &[u8] = &[139, 21];
+    // CharStrings (17) and Private (18) operands, all five-byte integers.
+    const TOP_DICT_LEN: usize = 17;
+
+    let names = index(&[postscript_name.as_bytes()], 1);
+    let strings = index(&[], 1);
+    let global_subrs = index(&[], 1);
+    let charstrings = index(&vec![ENDCHAR; num_glyphs as usize], 2);
+
+    let top_dict_index_len = 2 + 1 + 2 + TOP_DICT_LEN;
+    let charstrings_at =
+        HEADER.len() + names.len() + top_dict_index_len + strings.len() + global_subrs.len();
+    let private_at = charstrings_at + charstrings.len();
+
+    let mut top_dict = Vec::with_capacity(TOP_DICT_LEN);
+    top_dict.extend_from_slice(&int5(charstrings_at as u32));
+    top_dict.push(17);
+    top_dict.extend_from_slice(&int5(PRIVATE.len() as u32));
+    top_dict.extend_from_slice(&int5(private_at as u32));
+    top_dict.push(18);
+    debug_assert_eq!(top_dict.len(), TOP_DICT_LEN);
+
+    let mut out = HEADER.to_vec();
+    out.extend(names);
+    out.extend(index(&[&top_dict], 1));
+    out.extend(strings);
+    out.extend(global_subrs);
+    out.extend(charstrings);
+    out.extend_from_slice(PRIVATE);
+    out
+}
+
+fn read_u16(data: &[u8], at: usize) -> u16 {
+    u16::from_be_bytes([data[at], data[at + 1]])
+}
+
+fn read_u32(data: &[u8], at: usize) -> u32 {
+    u32::from_be_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+}
+
+/// Offset, length and record position of `tag` in a single font's directory.
+fn find_table(data: &[u8], tag: [u8; 4]) -> Option<(usize, usize, usize)> {
+    (0..read_u16(data, 4) as usize)
+        .map(|i| 12 + 16 * i)
+        .find(|&record| data[record..record + 4] == tag)
+        .map(|record| {
+            (
+                read_u32(data, record + 8) as usize,
+                read_u32(data, record + 12) as usize,
+                record,
+            )
+        })
+}
+
+/// Recompute `head.checkSumAdjustment` after the file was edited.
+fn fix_checksum_adjustment(data: &mut [u8]) {
+    let (head, _, _) = find_table(data, *b"head").expect("head table");
+    data[head + 8..head + 12].fill(0);
+    let sum = data
+        .chunks(4)
+        .map(|chunk| {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_be_bytes(word)
+        })
+        .fold(0u32, u32::wrapping_add);
+    data[head + 8..head + 12].copy_from_slice(&0xB1B0_AFBAu32.wrapping_sub(sum).to_be_bytes());
+}
+
+fn apply_defect(data: &mut Vec<u8>, defect: Defect) {
+    match defect {
+        Defect::BadChecksum => {
+            let (_, _, record) = find_table(data, *b"name").expect("name table");
+            let checksum = read_u32(data, record + 4) ^ 0xDEAD_BEEF;
+            data[record + 4..record + 8].copy_from_slice(&checksum.to_be_bytes());
+        }
+        Defect::TruncatedTable(tag) => {
+            let (offset, length, _) = find_table(data, tag).unwrap_or_else(|| {
+                panic!("no '{}' table to truncate", String::from_utf8_lossy(&tag))
+            });
+            data.truncate(offset + length / 2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use write_fonts::read::{tables::name::NameId, FileRef, FontRef, TableProvider};
+
+    fn name(font: &FontRef, id: NameId) -> String {
+        let table = font.name().unwrap();
+        let record = table
+            .name_record()
+            .iter()
+            .find(|r| r.name_id() == id)
+            .unwrap();
+        record.string(table.string_data()).unwrap().to_string()
+    }
+
+    fn verify_checksums(data: &[u8]) -> bool {
+        let font = FontRef::new(data).unwrap();
+        font.table_directory.table_records().iter().all(|record| {
+            let start = record.offset() as usize;
+            let end = start + record.length() as usize;
+            let mut table = data[start..end].to_vec();
+            if record.tag() == Tag::new(b"head") {
+                table[8..12].fill(0);
+            }
+            write_fonts::read::tables::compute_checksum(&table) == record.checksum()
+        })
+    }
+
+    #[test]
+    fn builds_requested_names_and_style() {
+        let data = TestFont::new("Fixture Sans")
+            .with_style("Bold Italic")
+            .with_weight(700)
+            .with_italic(true)
+            .with_revision(2.5)
+            .build();
+        let font = FontRef::new(&data).unwrap();
+
+        assert_eq!(name(&font, NameId::FAMILY_NAME), "Fixture Sans");
+        assert_eq!(
+            name(&font, NameId::POSTSCRIPT_NAME),
+            "FixtureSans-BoldItalic"
+        );
+        assert_eq!(name(&font, NameId::VERSION_STRING), "Version 2.500");
+        let os2 = font.os2().unwrap();
+        assert_eq!(os2.us_weight_class(), 700);
+        assert!(os2.fs_selection().contains(SelectionFlags::ITALIC));
+        assert_eq!(font.head().unwrap().font_revision().to_f64(), 2.5);
+        assert_eq!(font.maxp().unwrap().num_glyphs(), 27);
+        assert!(verify_checksums(&data));
+        assert_eq!(
+            data,
+            TestFont::new("Fixture Sans")
+                .with_style("Bold Italic")
+                .with_weight(700)
+                .with_italic(true)
+                .with_revision(2.5)
+                .build()
+        );
+    }
+
+    #[test]
+    fn maps_requested_characters() {
+        let data = TestFont::new("Mapped")
+            .with_chars(['a', 'é', '€', '😀'])
+            .build();
+        let font = FontRef::new(&data).unwrap();
+        let cmap = font.cmap().unwrap();
+        assert_eq!(cmap.map_codepoint('€'), Some(GlyphId::new(3)));
+        assert_eq!(cmap.map_codepoint('😀'), Some(GlyphId::new(4)));
+        assert_eq!(cmap.map_codepoint('Z'), None);
+    }
+
+    #[test]
+    fn builds_cff_flavoured_opentype() {
+        let font = TestFont::new("Fixture Serif").with_outlines(Outlines::Cff);
+        assert_eq!(font.file_name(), "FixtureSerif-Regular.otf");
+        let data = font.build();
+        assert_eq!(&data[..4], b"OTTO");
+
+        let parsed = FontRef::new(&data).unwrap();
+        let cff = parsed.cff().unwrap();
+        assert_eq!(cff.name(0).unwrap().to_string(), "FixtureSerif-Regular");
+        assert!(verify_checksums(&data));
+    }
+
+    #[test]
+    fn builds_variable_weight_axis() {
+        let data = TestFont::new("Fixture Var")
+            .with_weight_axis(100, 900)
+            .build();
+        let font = FontRef::new(&data).unwrap();
+        let fvar = font.fvar().unwrap();
+        let axis = &fvar.axes().unwrap()[0];
+        assert_eq!(axis.axis_tag(), Tag::new(b"wght"));
+        assert_eq!(axis.default_value().to_f64(), 400.0);
+        assert_eq!(fvar.instances().unwrap().len(), 9);
+    }
+
+    #[test]
+    fn packs_collections() {
+        let data = build_collection(&ribbi_family("Fixture Sans"));
+        let FileRef::Collection(collection) = FileRef::new(&data).unwrap() else {
+            panic!("expected a collection");
+        };
+        assert_eq!(collection.len(), 4);
+        let bold = collection.get(2).unwrap();
+        assert_eq!(name(&bold, NameId::SUBFAMILY_NAME), "Bold");
+        assert_eq!(bold.os2().unwrap().us_weight_class(), 700);
+    }
+
+    #[test]
+    fn injects_defects() {
+        let bad = TestFont::new("Broken")
+            .with_defect(Defect::BadChecksum)
+            .build();
+        assert!(FontRef::new(&bad).unwrap().name().is_ok());
+        assert!(!verify_checksums(&bad));
+
+        let truncated = TestFont::new("Broken")
+            .with_defect(Defect::TruncatedTable(*b"name"))
+            .build();
+        let font = FontRef::new(&truncated).unwrap();
+        assert!(font.name().is_err());
+    }
+}
@@ -6,29 +6,17 @@
 
 use std::path::{Path, PathBuf};
 use tempfile::TempDir;
-use fontlift_core::{FontManager, FontScope};
+use fontlift_core::{cache::CacheClearStrategy, FontManager, FontScope};
+use fontlift_testfonts::TestFont;
 
-/// Forge a pretend font file from the ether of temporary directories
-/// 
-/// We're basically font counterfeiters, but in a good way - creating just enough
-/// TTF header bytes to convince the font managers we're worth talking to.
-/// Think of it as a student ID for a font that never went to font school.
+/// Bake a real (if tiny) font into a temporary directory.
+///
+/// `fontlift-testfonts` builds a complete TrueType face, so the platform
+/// font managers get something they can actually register.
 fn create_test_font_file() -> Result<(TempDir, PathBuf), Box<dyn std::error::Error>> {
     let temp_dir = TempDir::new()?;
-    let font_path = temp_dir.path().join("test-font.ttf");
-    
-    // We're baking a minimalist TTF cake with only the essential ingredients
-    // This fake font speaks the language but doesn't know how to draw letters
-    let ttf_header = vec![
-        0x00, 0x01, 0x00, 0x00, // Font file version - says "I'm a TrueType!"
-        0x00, 0x0C, // Number of tables - we lie convincingly
-        0x00, 0x20, // searchRange - mathematical magic for table lookup
-        0x00, 0x01, // entrySelector - more lookup table math
-        0x00, 0x00, // rangeShift - the final piece of the lookup puzzle
-    ];
-    
-    std::fs::write(&font_path, ttf_header)?;
-    
+    let font_path = TestFont::new("Fontlift Integration").write_to(temp_dir.path())?;
+
     Ok((temp_dir, font_path))
 }
 
@@ -205,8 +193,8 @@ async fn test_cache_clearing() {
     let manager = create_platform_manager();
     
     // First, let's clean up our own mess - user cache clearing
-    match manager.clear_font_caches(FontScope::User) {
-        Ok(()) => {
+    match manager.clear_font_caches(CacheClearStrategy::UserOnly) {
+        Ok(_) => {
             println!("✅ User cache clearing succeeded - digital dust bunnies vanquished");
         },
         Err(e) => {
@@ -216,8 +204,8 @@ async fn test_cache_clearing() {
     }
     
     // Now let's attempt the forbidden - system cache clearing without privilege
-    match manager.clear_font_caches(FontScope::System) {
-        Ok(()) => {
+    match manager.clear_font_caches(CacheClearStrategy::SystemOnly) {
+        Ok(_) => {
             println!("⚠️  System cache clearing should have failed - we shouldn't be admin here");
         },
         Err(e) => {
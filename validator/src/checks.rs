@@ -0,0 +1,501 @@
+//! Structural table checks run on every face after it opens.
+//!
+//! Opening a face only proves the table directory is sane. These checks
+//! look inside the tables an application reads first when it renders
+//! text — `cmap` to find glyphs, `hhea`/`hmtx` to advance the pen,
+//! `loca`/`glyf` or `CFF ` for the outlines — and report each problem as a
+//! [`ValidationIssue`] tagged with the area it concerns.
+//!
+//! How much runs, and how much it matters, depends on the strictness:
+//!
+//! | Strictness | Checks run                        | Problems are |
+//! |------------|-----------------------------------|--------------|
+//! | Lenient    | tables, cmap, metrics, loca       | warnings     |
+//! | Normal     | tables, cmap, metrics, loca       | as found     |
+//! | Paranoid   | the above, plus every outline     | as found     |
+
+use fontlift_core::validation_ext::ValidationStrictness;
+use read_fonts::tables::cmap::{CmapSubtable, PlatformId};
+use read_fonts::tables::glyf::Glyph;
+use read_fonts::types::{GlyphId, Tag};
+use read_fonts::{FontRef, TableProvider};
+use serde::Serialize;
+
+/// Which part of the font an issue concerns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueCategory {
+    /// A table is missing.
+    Tables,
+    /// The character-to-glyph map.
+    Cmap,
+    /// `maxp`, `hhea` and `hmtx` disagree.
+    Metrics,
+    /// Glyph offsets in `loca`.
+    Loca,
+    /// Glyph outlines in `glyf`, `CFF ` or `CFF2`.
+    Outlines,
+}
+
+impl IssueCategory {
+    /// The name used in JSON and error messages.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IssueCategory::Tables => "tables",
+            IssueCategory::Cmap => "cmap",
+            IssueCategory::Metrics => "metrics",
+            IssueCategory::Loca => "loca",
+            IssueCategory::Outlines => "outlines",
+        }
+    }
+}
+
+/// Whether an issue fails the face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    /// Worth knowing; the face is still accepted.
+    Warning,
+    /// The face is rejected.
+    Error,
+}
+
+/// One problem found in a face.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationIssue {
+    pub category: IssueCategory,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+/// Collects issues for one face.
+struct Report(Vec<ValidationIssue>);
+
+impl Report {
+    fn push(&mut self, category: IssueCategory, severity: IssueSeverity, message: String) {
+        self.0.push(ValidationIssue {
+            category,
+            severity,
+            message,
+        });
+    }
+
+    fn error(&mut self, category: IssueCategory, message: impl Into<String>) {
+        self.push(category, IssueSeverity::Error, message.into());
+    }
+
+    fn warning(&mut self, category: IssueCategory, message: impl Into<String>) {
+        self.push(category, IssueSeverity::Warning, message.into());
+    }
+}
+
+/// Tables every face needs; without them text cannot be laid out.
+const REQUIRED_TABLES: [&[u8; 4]; 6] = [b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name"];
+
+/// Tables the spec requires but most software copes without.
+const EXPECTED_TABLES: [&[u8; 4]; 2] = [b"OS/2", b"post"];
+
+/// Embedded bitmap tables; a face with only these has no outlines.
+const BITMAP_TABLES: [&[u8; 4]; 3] = [b"CBDT", b"EBDT", b"sbix"];
+
+/// Run the checks `strictness` calls for on one face.
+pub fn check_face(font: &FontRef, strictness: ValidationStrictness) -> Vec<ValidationIssue> {
+    let mut report = Report(Vec::new());
+    check_tables(font, &mut report);
+    check_cmap(font, &mut report);
+    check_metrics(font, &mut report);
+    check_loca(font, &mut report);
+    if strictness == ValidationStrictness::Paranoid {
+        check_outlines(font, &mut report);
+    }
+
+    let mut issues = report.0;
+    if strictness == ValidationStrictness::Lenient {
+        for issue in &mut issues {
+            issue.severity = IssueSeverity::Warning;
+        }
+    }
+    issues
+}
+
+fn has_table(font: &FontRef, tag: &[u8; 4]) -> bool {
+    font.table_data(Tag::new(tag)).is_some()
+}
+
+/// Glyph count from `maxp`, if it reads.
+fn num_glyphs(font: &FontRef) -> Option<u16> {
+    font.maxp().ok().map(|maxp| maxp.num_glyphs())
+}
+
+fn check_tables(font: &FontRef, report: &mut Report) {
+    for tag in REQUIRED_TABLES {
+        if !has_table(font, tag) {
+            report.error(
+                IssueCategory::Tables,
+                format!("missing required '{}' table", Tag::new(tag)),
+            );
+        }
+    }
+    for tag in EXPECTED_TABLES {
+        if !has_table(font, tag) {
+            report.warning(
+                IssueCategory::Tables,
+                format!("missing '{}' table", Tag::new(tag)),
+            );
+        }
+    }
+
+    let has_glyf = has_table(font, b"glyf");
+    if has_glyf != has_table(font, b"loca") {
+        report.error(
+            IssueCategory::Tables,
+            "'glyf' and 'loca' must be present together",
+        );
+    }
+    if !has_glyf && !has_table(font, b"CFF ") && !has_table(font, b"CFF2") {
+        if BITMAP_TABLES.iter().any(|tag| has_table(font, tag)) {
+            report.warning(IssueCategory::Tables, "bitmap-only face has no outlines");
+        } else {
+            report.error(
+                IssueCategory::Tables,
+                "no outline table ('glyf', 'CFF ' or 'CFF2')",
+            );
+        }
+    }
+}
+
+fn check_cmap(font: &FontRef, report: &mut Report) {
+    if !has_table(font, b"cmap") {
+        return;
+    }
+    let cmap = match font.cmap() {
+        Ok(cmap) => cmap,
+        Err(e) => return report.error(IssueCategory::Cmap, format!("unreadable: {e}")),
+    };
+    let records = cmap.encoding_records();
+    if records.is_empty() {
+        return report.error(IssueCategory::Cmap, "no encoding records");
+    }
+
+    let limit = num_glyphs(font).map(u32::from);
+    let mut unicode = false;
+    for record in records {
+        let subtable = match record.subtable(cmap.offset_data()) {
+            Ok(subtable) => subtable,
+            Err(e) => {
+                report.error(
+                    IssueCategory::Cmap,
+                    format!(
+                        "unreadable subtable for platform {} encoding {}: {e}",
+                        record.platform_id() as u16,
+                        record.encoding_id()
+                    ),
+                );
+                continue;
+            }
+        };
+        unicode |= matches!(
+            (record.platform_id(), record.encoding_id()),
+            (PlatformId::Unicode, _) | (PlatformId::Windows, 1 | 10)
+        );
+
+        let beyond = |gid: GlyphId| limit.is_some_and(|limit| gid.to_u32() >= limit);
+        let stray = match &subtable {
+            CmapSubtable::Format4(table) => table.iter().find(|(_, gid)| beyond(*gid)),
+            CmapSubtable::Format12(table) => table.iter().find(|(_, gid)| beyond(*gid)),
+            _ => None,
+        };
+        if let (Some((codepoint, gid)), Some(limit)) = (stray, limit) {
+            report.error(
+                IssueCategory::Cmap,
+                format!(
+                    "U+{codepoint:04X} maps to glyph {} but the font has {limit} glyphs",
+                    gid.to_u32()
+                ),
+            );
+        }
+    }
+    if !unicode {
+        report.warning(IssueCategory::Cmap, "no Unicode subtable");
+    }
+}
+
+fn check_metrics(font: &FontRef, report: &mut Report) {
+    let (Ok(maxp), Ok(hhea)) = (font.maxp(), font.hhea()) else {
+        return;
+    };
+    let glyphs = maxp.num_glyphs();
+    let metrics = hhea.number_of_h_metrics();
+    if glyphs == 0 {
+        report.error(IssueCategory::Metrics, "maxp reports no glyphs");
+    }
+    if metrics == 0 {
+        report.error(IssueCategory::Metrics, "hhea numberOfHMetrics is 0");
+    } else if metrics > glyphs {
+        report.error(
+            IssueCategory::Metrics,
+            format!("hhea numberOfHMetrics {metrics} exceeds maxp numGlyphs {glyphs}"),
+        );
+    }
+    if has_table(font, b"hmtx") {
+        if let Err(e) = font.hmtx() {
+            report.error(
+                IssueCategory::Metrics,
+                format!("hmtx too short for {glyphs} glyphs: {e}"),
+            );
+        }
+    }
+}
+
+fn check_loca(font: &FontRef, report: &mut Report) {
+    let (Some(glyf), true) = (font.table_data(Tag::new(b"glyf")), has_table(font, b"loca")) else {
+        return;
+    };
+    let Ok(head) = font.head() else {
+        return;
+    };
+    let format = head.index_to_loc_format();
+    if !matches!(format, 0 | 1) {
+        return report.error(
+            IssueCategory::Loca,
+            format!("head indexToLocFormat is {format}, expected 0 or 1"),
+        );
+    }
+    let loca = match font.loca(format == 1) {
+        Ok(loca) => loca,
+        Err(e) => return report.error(IssueCategory::Loca, format!("unreadable: {e}")),
+    };
+
+    if let Some(glyphs) = num_glyphs(font) {
+        let entries = loca.len();
+        if entries < glyphs as usize {
+            report.error(
+                IssueCategory::Loca,
+                format!("{entries} offsets for {glyphs} glyphs"),
+            );
+        } else if entries > glyphs as usize {
+            report.warning(
+                IssueCategory::Loca,
+                format!("{entries} offsets for {glyphs} glyphs"),
+            );
+        }
+    }
+    if !loca.all_offsets_are_ascending() {
+        report.error(IssueCategory::Loca, "offsets are not ascending");
+    }
+    if let Some(end) = loca.get_raw(loca.len()) {
+        if end as usize > glyf.len() {
+            report.error(
+                IssueCategory::Loca,
+                format!("offsets run to {end} but glyf is {} bytes", glyf.len()),
+            );
+        }
+    }
+}
+
+fn check_outlines(font: &FontRef, report: &mut Report) {
+    if has_table(font, b"CFF ") {
+        match font.cff() {
+            Ok(cff) if cff.top_dicts().count() != 1 => report.error(
+                IssueCategory::Outlines,
+                format!(
+                    "CFF has {} top DICTs, expected exactly 1",
+                    cff.top_dicts().count()
+                ),
+            ),
+            Ok(_) => {}
+            Err(e) => report.error(IssueCategory::Outlines, format!("unreadable CFF: {e}")),
+        }
+    }
+    if has_table(font, b"CFF2") {
+        if let Err(e) = font.cff2() {
+            report.error(IssueCategory::Outlines, format!("unreadable CFF2: {e}"));
+        }
+    }
+    check_glyf(font, report);
+}
+
+/// Parse every `glyf` outline. Each kind of problem is reported once, with
+/// the first glyph it was seen in and how many glyphs share it.
+fn check_glyf(font: &FontRef, report: &mut Report) {
+    let (Ok(glyf), Ok(loca), Some(glyphs), Ok(head)) =
+        (font.glyf(), font.loca(None), num_glyphs(font), font.head())
+    else {
+        return;
+    };
+    let count = (glyphs as usize).min(loca.len());
+
+    let mut unreadable = Problem::default();
+    let mut bad_points = Problem::default();
+    let mut bad_components = Problem::default();
+    let mut outside_head = Problem::default();
+    for index in 0..count {
+        let gid = GlyphId::new(index as u32);
+        let glyph = match loca.get_glyf(gid, &glyf) {
+            Ok(Some(glyph)) => glyph,
+            Ok(None) => continue,
+            Err(e) => {
+                unreadable.saw(index, e.to_string());
+                continue;
+            }
+        };
+
+        if glyph.x_min() < head.x_min()
+            || glyph.y_min() < head.y_min()
+            || glyph.x_max() > head.x_max()
+            || glyph.y_max() > head.y_max()
+        {
+            outside_head.saw(index, String::new());
+        }
+
+        match glyph {
+            Glyph::Simple(simple) => {
+                let ends = simple.end_pts_of_contours();
+                if ends.windows(2).any(|pair| pair[0].get() >= pair[1].get()) {
+                    bad_points.saw(index, "contour end points are not ascending".into());
+                } else if simple.points().count() != simple.num_points() {
+                    bad_points.saw(
+                        index,
+                        format!(
+                            "{} of {} points decode",
+                            simple.points().count(),
+                            simple.num_points()
+                        ),
+                    );
+                }
+            }
+            Glyph::Composite(composite) => {
+                if let Some(component) = composite
+                    .components()
+                    .find(|c| c.glyph.to_u16() >= glyphs || c.glyph.to_u16() as usize == index)
+                {
+                    bad_components.saw(
+                        index,
+                        format!("references glyph {}", component.glyph.to_u16()),
+                    );
+                }
+            }
+        }
+    }
+
+    unreadable.report(report, IssueSeverity::Error, "unreadable");
+    bad_points.report(report, IssueSeverity::Error, "malformed points");
+    bad_components.report(report, IssueSeverity::Error, "invalid component");
+    outside_head.report(report, IssueSeverity::Warning, "bounds outside head bbox");
+}
+
+/// Tally of one kind of per-glyph problem.
+#[derive(Default)]
+struct Problem {
+    first: Option<(usize, String)>,
+    count: usize,
+}
+
+impl Problem {
+    fn saw(&mut self, glyph: usize, detail: String) {
+        self.count += 1;
+        self.first.get_or_insert((glyph, detail));
+    }
+
+    fn report(self, report: &mut Report, severity: IssueSeverity, what: &str) {
+        let Some((glyph, detail)) = self.first else {
+            return;
+        };
+        let detail = if detail.is_empty() {
+            String::new()
+        } else {
+            format!(": {detail}")
+        };
+        let others = match self.count {
+            1 => String::new(),
+            n => format!(" (and {} more)", n - 1),
+        };
+        report.push(
+            IssueCategory::Outlines,
+            severity,
+            format!("glyph {glyph} {what}{detail}{others}"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontlift_testfonts::{Outlines, TestFont};
+
+    /// Byte offset of `tag` in a single font's table directory.
+    fn table_offset(data: &[u8], tag: &[u8; 4]) -> usize {
+        let font = FontRef::new(data).unwrap();
+        let record = font
+            .table_directory
+            .table_records()
+            .iter()
+            .find(|r| r.tag() == Tag::new(tag))
+            .unwrap();
+        record.offset() as usize
+    }
+
+    fn issues(data: &[u8], strictness: ValidationStrictness) -> Vec<ValidationIssue> {
+        check_face(&FontRef::new(data).unwrap(), strictness)
+    }
+
+    fn errors(issues: &[ValidationIssue]) -> Vec<IssueCategory> {
+        issues
+            .iter()
+            .filter(|i| i.severity == IssueSeverity::Error)
+            .map(|i| i.category)
+            .collect()
+    }
+
+    #[test]
+    fn generated_fonts_pass_paranoid_checks() {
+        for outlines in [Outlines::TrueType, Outlines::Cff] {
+            let data = TestFont::new("Fixture Sans")
+                .with_outlines(outlines)
+                .build();
+            let found = issues(&data, ValidationStrictness::Paranoid);
+            assert!(found.is_empty(), "{outlines:?}: {found:?}");
+        }
+    }
+
+    #[test]
+    fn glyph_count_mismatch_fails_cmap_metrics_and_loca() {
+        let mut data = TestFont::new("Fixture Sans").build();
+        // maxp.numGlyphs sits after the 4-byte version; claim 3 glyphs.
+        let maxp = table_offset(&data, b"maxp");
+        data[maxp + 4..maxp + 6].copy_from_slice(&3u16.to_be_bytes());
+
+        let normal = errors(&issues(&data, ValidationStrictness::Normal));
+        assert!(normal.contains(&IssueCategory::Cmap), "{normal:?}");
+        assert!(normal.contains(&IssueCategory::Metrics), "{normal:?}");
+
+        let lenient = issues(&data, ValidationStrictness::Lenient);
+        assert!(!lenient.is_empty());
+        assert!(errors(&lenient).is_empty());
+    }
+
+    #[test]
+    fn short_loca_is_an_error() {
+        let mut data = TestFont::new("Fixture Sans").build();
+        // Claim more glyphs than loca and hmtx describe.
+        let maxp = table_offset(&data, b"maxp");
+        data[maxp + 4..maxp + 6].copy_from_slice(&200u16.to_be_bytes());
+
+        let found = issues(&data, ValidationStrictness::Normal);
+        assert!(errors(&found).contains(&IssueCategory::Loca), "{found:?}");
+    }
+
+    #[test]
+    fn broken_outlines_only_fail_paranoid() {
+        let mut data = TestFont::new("Fixture Sans").build();
+        // Glyph 0 starts the glyf table; give it far more contours than
+        // its data holds.
+        let glyf = table_offset(&data, b"glyf");
+        data[glyf..glyf + 2].copy_from_slice(&0x7fffu16.to_be_bytes());
+
+        assert!(errors(&issues(&data, ValidationStrictness::Normal)).is_empty());
+        let paranoid = issues(&data, ValidationStrictness::Paranoid);
+        assert_eq!(errors(&paranoid), vec![IssueCategory::Outlines]);
+        assert!(paranoid[0].message.starts_with("glyph 0 "), "{paranoid:?}");
+    }
+}
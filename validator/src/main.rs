@@ -12,7 +12,7 @@
 //! ```json
 //! {
 //!   "paths": ["/path/to/font.ttf", "/path/to/another.otf"],
-//!   "config": { "max_file_size_bytes": 67108864, "timeout_ms": 5000 }
+//!   "config": { "max_file_size_bytes": 67108864, "timeout_ms": 5000, "strictness": "normal" }
 //! }
 //! ```
 //!
@@ -20,11 +20,19 @@
 //!
 //! ```json
 //! [
-//!   { "path": "/path/to/font.ttf", "ok": true, "info": { ... } },
+//!   { "path": "/path/to/font.ttf", "face_index": 0, "ok": true, "info": { ... } },
 //!   { "path": "/path/to/another.otf", "ok": false, "error": "Invalid font structure: ..." }
 //! ]
 //! ```
 //!
+//! Collections (`.ttc`/`.otc`) produce one result per face, with
+//! `face_index` counting from 0, so a collection with one corrupt face is
+//! reported as such. Faces that pass table checks with warnings, or fail
+//! them, also carry an `issues` array of `{ "category", "severity",
+//! "message" }` objects; see the `checks` module. A file that fails before its faces can be told apart
+//! (missing, too large, unparseable) produces a single result without
+//! `face_index`. Results for each path are contiguous and in input order.
+//!
 //! You can also pass paths as CLI arguments for quick manual checks:
 //! ```sh
 //! fontlift-validator /path/to/font.ttf
@@ -33,11 +41,18 @@
 //! # What it checks
 //!
 //! 1. File exists and is a regular file
-//! 2. Extension is a recognized font format (.ttf, .otf, .ttc, .otc, .woff, .woff2, .dfont)
+//! 2. Extension is a recognized font format (.ttf, .otf, .ttc, .otc, .woff, .woff2, .dfont);
+//!    PostScript Type 1 files are reported as such
 //! 3. File size is within limits (default: 64 MB — CJK fonts can be large)
 //! 4. The binary structure parses as a valid font (via `read-fonts`)
-//! 5. The `name` table contains required metadata (family, style, PostScript name)
-//! 6. The `OS/2` table provides weight and italic flags
+//! 5. Every face of a collection opens and has a readable `head` table, each
+//!    within its own timeout
+//! 6. Required tables are present, `cmap` parses and maps only to existing
+//!    glyphs, `maxp`/`hhea`/`hmtx` agree, and `loca` stays inside `glyf`
+//!    (warnings only when `strictness` is `lenient`)
+//! 7. At `paranoid` strictness, every glyph outline parses too
+//! 8. The `name` table contains required metadata (family, style, PostScript name)
+//! 9. The `OS/2` table provides weight and italic flags
 //!
 //! # The `read-fonts` crate
 //!
@@ -46,11 +61,17 @@
 //! (weight, width, selection flags), `head` (global metrics) — without
 //! needing any OS font APIs. Pure Rust, cross-platform.
 
+mod checks;
+
+use checks::{IssueSeverity, ValidationIssue};
+use fontlift_core::metadata::{self, Embedding, FaceDetails, LocalizedName};
+use fontlift_core::type1;
+use fontlift_core::validation_ext::ValidationStrictness;
 use fontlift_core::{FontliftFontFaceInfo, FontliftFontSource};
 use read_fonts::{FileRef, FontRef, TableProvider};
 use serde::{Deserialize, Serialize};
 use std::io::{self, BufRead};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 /// Reject files larger than this. 64 MB covers the largest legitimate
@@ -70,8 +91,8 @@ pub struct ValidatorConfig {
     #[serde(default = "default_max_size")]
     pub max_file_size_bytes: u64,
 
-    /// Per-font timeout in milliseconds. If parsing takes longer than
-    /// this, the font is rejected. Default: 5000 ms.
+    /// Per-face timeout in milliseconds. If parsing a face takes longer
+    /// than this, that face is rejected. Default: 5000 ms.
     #[serde(default = "default_timeout_ms")]
     pub timeout_ms: u64,
 
@@ -80,6 +101,11 @@ pub struct ValidatorConfig {
     /// single-face fonts.
     #[serde(default = "default_allow_collections")]
     pub allow_collections: bool,
+
+    /// Which table checks run on each face, and whether their findings
+    /// fail it. Default: normal.
+    #[serde(default)]
+    pub strictness: ValidationStrictness,
 }
 
 fn default_max_size() -> u64 {
@@ -98,6 +124,7 @@ impl Default for ValidatorConfig {
             max_file_size_bytes: DEFAULT_MAX_SIZE,
             timeout_ms: DEFAULT_TIMEOUT_MS,
             allow_collections: true,
+            strictness: ValidationStrictness::Normal,
         }
     }
 }
@@ -112,14 +139,19 @@ pub struct ValidatorInput {
     pub config: ValidatorConfig,
 }
 
-/// Outcome for a single font: either parsed metadata or an error string.
+/// Outcome for a single face: either parsed metadata or an error string.
 ///
-/// The parent process gets an array of these, one per input path, in the
-/// same order. It can check `ok` to decide whether to proceed with install.
+/// The parent process gets an array of these, one per face of each input
+/// path, in the same order. It can check `ok` to decide whether to proceed
+/// with install.
 #[derive(Debug, Serialize)]
 pub struct ValidationResult {
     /// Which file this result is for.
     pub path: PathBuf,
+    /// Which face of the file, when the file got as far as being split into
+    /// faces.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub face_index: Option<u32>,
     /// `true` if the font parsed successfully; `false` if validation failed.
     pub ok: bool,
     /// Extracted metadata (names, weight, italic, format). Present only when `ok` is true.
@@ -128,26 +160,44 @@ pub struct ValidationResult {
     /// What went wrong. Present only when `ok` is false.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Table check findings for this face, warnings and errors alike.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<ValidationIssue>,
 }
 
 impl ValidationResult {
     fn success(path: PathBuf, info: FontliftFontFaceInfo) -> Self {
         Self {
             path,
+            face_index: info.source.face_index,
             ok: true,
             info: Some(info),
             error: None,
+            issues: Vec::new(),
         }
     }
 
     fn failure(path: PathBuf, error: &str) -> Self {
         Self {
             path,
+            face_index: None,
             ok: false,
             info: None,
             error: Some(sanitize_error(error)),
+            issues: Vec::new(),
+        }
+    }
+
+    fn face_failure(path: PathBuf, face_index: u32, error: &str) -> Self {
+        Self {
+            face_index: Some(face_index),
+            ..Self::failure(path, error)
         }
     }
+
+    fn with_issues(self, issues: Vec<ValidationIssue>) -> Self {
+        Self { issues, ..self }
+    }
 }
 
 /// Clean up error messages before sending them back to the parent.
@@ -163,19 +213,24 @@ fn sanitize_error(error: &str) -> String {
 }
 
 /// Validate one font file: check existence, extension, size, then parse
-/// the binary structure and extract metadata from the `name` and `OS/2` tables.
-/// Returns success with full metadata, or failure with a human-readable reason.
-fn validate_font(path: &PathBuf, config: &ValidatorConfig) -> ValidationResult {
+/// the binary structure and extract metadata from the `name` and `OS/2`
+/// tables of every face. Returns one result per face (success with full
+/// metadata, or failure with a human-readable reason), or a single failure
+/// when the file itself is unusable.
+fn validate_font(path: &PathBuf, config: &ValidatorConfig) -> Vec<ValidationResult> {
     let start = Instant::now();
     let timeout = Duration::from_millis(config.timeout_ms);
 
     // Check file exists
     if !path.exists() {
-        return ValidationResult::failure(path.clone(), "File not found");
+        return vec![ValidationResult::failure(path.clone(), "File not found")];
     }
 
     if !path.is_file() {
-        return ValidationResult::failure(path.clone(), "Path is not a file");
+        return vec![ValidationResult::failure(
+            path.clone(),
+            "Path is not a file",
+        )];
     }
 
     // Check extension
@@ -189,83 +244,172 @@ fn validate_font(path: &PathBuf, config: &ValidatorConfig) -> ValidationResult {
         ext.as_str(),
         "ttf" | "otf" | "ttc" | "otc" | "woff" | "woff2" | "dfont"
     ) {
-        return ValidationResult::failure(path.clone(), "Invalid font extension");
+        if type1::detect(path).is_some() {
+            return vec![ValidationResult::failure(
+                path.clone(),
+                "PostScript Type 1 font: modern systems no longer load Type 1; \
+                 convert it to OpenType (fontlift install --convert)",
+            )];
+        }
+        return vec![ValidationResult::failure(
+            path.clone(),
+            "Invalid font extension",
+        )];
     }
 
     // Check file size
     let metadata = match std::fs::metadata(path) {
         Ok(m) => m,
-        Err(_) => return ValidationResult::failure(path.clone(), "Cannot read file metadata"),
+        Err(_) => {
+            return vec![ValidationResult::failure(
+                path.clone(),
+                "Cannot read file metadata",
+            )]
+        }
     };
 
     if metadata.len() > config.max_file_size_bytes {
-        return ValidationResult::failure(
+        return vec![ValidationResult::failure(
             path.clone(),
             &format!(
                 "File exceeds maximum size ({} bytes > {} bytes)",
                 metadata.len(),
                 config.max_file_size_bytes
             ),
-        );
+        )];
     }
 
     // Read file data
     let data = match std::fs::read(path) {
         Ok(d) => d,
-        Err(_) => return ValidationResult::failure(path.clone(), "Cannot read file"),
+        Err(_) => return vec![ValidationResult::failure(path.clone(), "Cannot read file")],
     };
 
     // Check timeout
     if start.elapsed() > timeout {
-        return ValidationResult::failure(path.clone(), "Validation timeout");
+        return vec![ValidationResult::failure(
+            path.clone(),
+            "Validation timeout",
+        )];
     }
 
     // Parse the binary font structure. FileRef distinguishes between
     // single fonts (FileRef::Font) and collections (FileRef::Collection).
     let file_ref = match FileRef::new(&data) {
         Ok(f) => f,
+        Err(_) if type1::sniff(&data).is_some() => {
+            return vec![ValidationResult::failure(
+                path.clone(),
+                "PostScript Type 1 font under a TrueType/OpenType extension; \
+                 convert it to OpenType (fontlift install --convert)",
+            )]
+        }
         Err(e) => {
-            return ValidationResult::failure(path.clone(), &format!("Invalid font structure: {e}"))
+            return vec![ValidationResult::failure(
+                path.clone(),
+                &format!("Invalid font structure: {e}"),
+            )]
         }
     };
 
     let is_collection = matches!(file_ref, FileRef::Collection(_));
 
     if is_collection && !config.allow_collections {
-        return ValidationResult::failure(path.clone(), "Font collections not allowed");
+        return vec![ValidationResult::failure(
+            path.clone(),
+            "Font collections not allowed",
+        )];
     }
 
-    // For collections, we validate face 0 (the first face in the file).
-    // A .ttc with 10 faces only needs one to pass structural validation.
-    let font = match file_ref {
-        FileRef::Font(f) => f,
-        FileRef::Collection(c) => match c.get(0) {
-            Ok(f) => f,
-            Err(e) => {
-                return ValidationResult::failure(
-                    path.clone(),
-                    &format!("Cannot read collection: {e}"),
-                )
-            }
-        },
+    // Every face of a collection is checked, each against its own timeout:
+    // applications may load any face, so one corrupt face makes the file
+    // unsafe to install.
+    let faces: Vec<Result<FontRef, String>> = match file_ref {
+        FileRef::Font(f) => vec![Ok(f)],
+        FileRef::Collection(c) => (0..c.len())
+            .map(|i| c.get(i).map_err(|e| format!("Cannot read face {i}: {e}")))
+            .collect(),
     };
+    if faces.is_empty() {
+        return vec![ValidationResult::failure(
+            path.clone(),
+            "Collection contains no faces",
+        )];
+    }
 
-    // Check timeout
-    if start.elapsed() > timeout {
-        return ValidationResult::failure(path.clone(), "Validation timeout");
+    faces
+        .into_iter()
+        .enumerate()
+        .map(|(i, face)| {
+            let face_index = i as u32;
+            match face {
+                Ok(font) => validate_face(
+                    path,
+                    &ext,
+                    face_index,
+                    is_collection,
+                    &font,
+                    timeout,
+                    config.strictness,
+                ),
+                Err(e) => ValidationResult::face_failure(path.clone(), face_index, &e),
+            }
+        })
+        .collect()
+}
+
+/// Check one face and extract its metadata, failing if it takes longer
+/// than `timeout` or if the table checks for `strictness` find an error.
+fn validate_face(
+    path: &Path,
+    ext: &str,
+    face_index: u32,
+    is_collection: bool,
+    font: &FontRef,
+    timeout: Duration,
+    strictness: ValidationStrictness,
+) -> ValidationResult {
+    let start = Instant::now();
+
+    if let Err(e) = font.head() {
+        return ValidationResult::face_failure(
+            path.to_path_buf(),
+            face_index,
+            &format!("Face {face_index}: unreadable head table: {e}"),
+        );
+    }
+
+    let issues = checks::check_face(font, strictness);
+    if let Some(first) = issues.iter().find(|i| i.severity == IssueSeverity::Error) {
+        let message = format!(
+            "Face {face_index}: {}: {}",
+            first.category.as_str(),
+            first.message
+        );
+        return ValidationResult::face_failure(path.to_path_buf(), face_index, &message)
+            .with_issues(issues);
     }
 
     // The `name` table holds human-readable strings: family, style,
     // PostScript name, full name. Every valid font has one.
-    let (postscript_name, full_name, family_name, style_name) = extract_names(&font);
+    let (postscript_name, full_name, family_name, style_name) = extract_names(font);
 
     // The `OS/2` table (yes, named after OS/2 Warp from 1994) holds
     // numeric metrics: weight class (100–900), width class, and
     // fsSelection flags (bit 0 = italic). Present in virtually all
-    // modern fonts.
-    let (weight, italic) = extract_os2_info(&font);
+    // modern fonts. `fvar` lists the axes of a variable font.
+    let (weight, width, italic) = extract_os2_info(font);
+    let axes = extract_axes(font);
 
-    let format = match ext.as_str() {
+    if start.elapsed() > timeout {
+        return ValidationResult::face_failure(
+            path.to_path_buf(),
+            face_index,
+            &format!("Face {face_index}: validation timeout"),
+        );
+    }
+
+    let format = match ext {
         "ttf" => "TrueType",
         "otf" => "OpenType",
         "ttc" | "otc" => "Collection",
@@ -275,11 +419,30 @@ fn validate_font(path: &PathBuf, config: &ValidatorConfig) -> ValidationResult {
         _ => "Unknown",
     };
 
-    let source = FontliftFontSource::new(path.clone())
+    let source = FontliftFontSource::new(path.to_path_buf())
         .with_format(Some(format.to_string()))
-        .with_face_index(Some(0))
+        .with_face_index(Some(face_index))
         .with_collection_flag(Some(is_collection));
 
+    // Name IDs 13/14 and OS/2 fsType: what the license allows.
+    let (license, license_url) = (find_name(font, 13), find_name(font, 14));
+    let embedding = font
+        .os2()
+        .ok()
+        .map(|os2| Embedding::from_fs_type(os2.fs_type()));
+    // Name IDs 5/8/9/0 and OS/2 achVendID: who made which version.
+    let details = FaceDetails {
+        version: find_name(font, 5),
+        vendor_id: font
+            .os2()
+            .ok()
+            .and_then(|os2| metadata::vendor_id(os2.ach_vend_id().to_be_bytes())),
+        manufacturer: find_name(font, 8),
+        designer: find_name(font, 9),
+        copyright: find_name(font, 0),
+    }
+    .non_empty();
+
     let info = FontliftFontFaceInfo {
         source,
         postscript_name,
@@ -288,9 +451,15 @@ fn validate_font(path: &PathBuf, config: &ValidatorConfig) -> ValidationResult {
         style: style_name,
         weight: Some(weight),
         italic: Some(italic),
+        width,
+        axes,
+        license,
+        license_url,
+        embedding,
+        details,
     };
 
-    ValidationResult::success(path.clone(), info)
+    ValidationResult::success(path.to_path_buf(), info).with_issues(issues)
 }
 
 /// Read the font's `name` table and extract the four key identifiers.
@@ -303,30 +472,20 @@ fn validate_font(path: &PathBuf, config: &ValidatorConfig) -> ValidationResult {
 ///
 /// If any are missing, we synthesize reasonable defaults from what we have.
 fn extract_names(font: &FontRef) -> (String, String, String, String) {
-    let name_table = match font.name() {
-        Ok(t) => t,
-        Err(_) => {
-            return (
-                "Unknown".to_string(),
-                "Unknown".to_string(),
-                "Unknown".to_string(),
-                "Regular".to_string(),
-            )
-        }
-    };
-
-    // Helper to find name by ID
-    let find_name = |id: u16| -> Option<String> {
-        name_table
-            .name_record()
-            .iter()
-            .find(|r| r.name_id() == read_fonts::tables::name::NameId::new(id))
-            .and_then(|r| r.string(name_table.string_data()).ok())
-            .map(|s| s.to_string())
-    };
+    if font.name().is_err() {
+        return (
+            "Unknown".to_string(),
+            "Unknown".to_string(),
+            "Unknown".to_string(),
+            "Regular".to_string(),
+        );
+    }
 
-    // Look up each name ID. The name table can have multiple entries per ID
-    // (different platforms, languages); we take the first match.
+    // The name table can have multiple entries per ID (different platforms,
+    // languages); pick them the way in-process metadata reads do.
+    let names = localized_names(font);
+    let languages = metadata::name_languages();
+    let find_name = |id: u16| metadata::select_name(&names, id, &languages).map(str::to_string);
     let family = find_name(1).unwrap_or_else(|| "Unknown".to_string());
     let style = find_name(2).unwrap_or_else(|| "Regular".to_string());
     let full_name = find_name(4).unwrap_or_else(|| format!("{} {}", family, style));
@@ -335,22 +494,85 @@ fn extract_names(font: &FontRef) -> (String, String, String, String) {
     (postscript, full_name, family, style)
 }
 
-/// Extract weight and italic from OS/2 table
-fn extract_os2_info(font: &FontRef) -> (u16, bool) {
-    let os2 = font.os2();
+/// Name ID `id` in the preferred language, if the font has it.
+fn find_name(font: &FontRef, id: u16) -> Option<String> {
+    metadata::select_name(&localized_names(font), id, &metadata::name_languages())
+        .map(str::to_string)
+}
 
-    let weight = os2.as_ref().map(|t| t.us_weight_class()).unwrap_or(400);
+/// The decodable `name` records of `font`, as [`metadata::localized_names`]
+/// reads them with the core crate's `read-fonts`.
+fn localized_names(font: &FontRef) -> Vec<LocalizedName> {
+    let Ok(name) = font.name() else {
+        return Vec::new();
+    };
+    let data = name.string_data();
+    let tags: Vec<String> = name
+        .lang_tag_record()
+        .unwrap_or_default()
+        .iter()
+        .map(|record| {
+            record
+                .lang_tag(data)
+                .map(|tag| tag.to_string())
+                .unwrap_or_default()
+        })
+        .collect();
 
-    let italic = os2
-        .as_ref()
-        .map(|t| {
-            let selection = t.fs_selection();
-            // Bit 0 = italic
-            selection.bits() & 1 != 0
+    name.name_record()
+        .iter()
+        .filter_map(|record| {
+            let value = record.string(data).ok()?.to_string();
+            let language = match record.language_id() {
+                id @ 0x8000.. => tags.get(usize::from(id - 0x8000)).cloned(),
+                id => metadata::language_tag(record.platform_id(), id).map(str::to_string),
+            };
+            Some(LocalizedName {
+                name_id: record.name_id().to_u16(),
+                platform_id: record.platform_id(),
+                language_id: record.language_id(),
+                language: language.filter(|tag| !tag.is_empty()),
+                value,
+                unicode: record.is_unicode(),
+            })
         })
-        .unwrap_or(false);
+        .collect()
+}
+
+/// Weight, width class and italic flag, normalized the way
+/// [`metadata::FaceStyle`] reads them in-process, so validated and listed
+/// faces agree.
+fn extract_os2_info(font: &FontRef) -> (u16, Option<u16>, bool) {
+    let os2 = font.os2();
+
+    let weight = os2
+        .as_ref()
+        .ok()
+        .and_then(|t| metadata::normalize_weight_class(t.us_weight_class()))
+        .unwrap_or(400);
+    let width = os2
+        .as_ref()
+        .ok()
+        .and_then(|t| metadata::normalize_width_class(t.us_width_class()));
+    let italic = metadata::is_italic(
+        os2.as_ref().ok().map(|t| t.fs_selection().bits()),
+        font.head().ok().map(|head| head.mac_style().bits()),
+    )
+    .unwrap_or(false);
+
+    (weight, width, italic)
+}
 
-    (weight, italic)
+/// Tags of the `fvar` axes; empty for static fonts.
+fn extract_axes(font: &FontRef) -> Vec<String> {
+    font.fvar()
+        .and_then(|fvar| fvar.axes())
+        .map(|axes| {
+            axes.iter()
+                .map(|axis| axis.axis_tag().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 fn main() {
@@ -387,11 +609,11 @@ fn main() {
         }
     };
 
-    // Validate each font
+    // Validate each font; collections contribute one result per face
     let results: Vec<ValidationResult> = input
         .paths
         .iter()
-        .map(|p| validate_font(p, &input.config))
+        .flat_map(|p| validate_font(p, &input.config))
         .collect();
 
     // Output JSON
@@ -410,12 +632,18 @@ mod tests {
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    /// The one result for a file that fails before it is split into faces.
+    fn single(mut results: Vec<ValidationResult>) -> ValidationResult {
+        assert_eq!(results.len(), 1, "{results:?}");
+        results.remove(0)
+    }
+
     #[test]
     fn rejects_nonexistent_file() {
-        let result = validate_font(
+        let result = single(validate_font(
             &PathBuf::from("/nonexistent/font.ttf"),
             &ValidatorConfig::default(),
-        );
+        ));
         assert!(!result.ok);
         assert!(result.error.as_ref().unwrap().contains("not found"));
     }
@@ -424,11 +652,26 @@ mod tests {
     fn rejects_invalid_extension() {
         let mut tmp = NamedTempFile::with_suffix(".txt").unwrap();
         tmp.write_all(b"not a font").unwrap();
-        let result = validate_font(&tmp.path().to_path_buf(), &ValidatorConfig::default());
+        let result = single(validate_font(
+            &tmp.path().to_path_buf(),
+            &ValidatorConfig::default(),
+        ));
         assert!(!result.ok);
         assert!(result.error.as_ref().unwrap().contains("extension"));
     }
 
+    #[test]
+    fn reports_type1_fonts_as_such() {
+        let mut tmp = NamedTempFile::with_suffix(".pfb").unwrap();
+        tmp.write_all(&[0x80, 0x01, 0x00, 0x00]).unwrap();
+        let result = single(validate_font(
+            &tmp.path().to_path_buf(),
+            &ValidatorConfig::default(),
+        ));
+        assert!(!result.ok);
+        assert!(result.error.as_ref().unwrap().contains("Type 1"));
+    }
+
     #[test]
     fn rejects_oversized_file() {
         let mut tmp = NamedTempFile::with_suffix(".ttf").unwrap();
@@ -437,7 +680,7 @@ mod tests {
             max_file_size_bytes: 5, // tiny limit
             ..Default::default()
         };
-        let result = validate_font(&tmp.path().to_path_buf(), &config);
+        let result = single(validate_font(&tmp.path().to_path_buf(), &config));
         assert!(!result.ok);
         assert!(result
             .error
@@ -450,7 +693,10 @@ mod tests {
     fn rejects_malformed_font() {
         let mut tmp = NamedTempFile::with_suffix(".ttf").unwrap();
         tmp.write_all(b"this is not a valid font file").unwrap();
-        let result = validate_font(&tmp.path().to_path_buf(), &ValidatorConfig::default());
+        let result = single(validate_font(
+            &tmp.path().to_path_buf(),
+            &ValidatorConfig::default(),
+        ));
         assert!(!result.ok);
         assert!(result
             .error
@@ -459,6 +705,114 @@ mod tests {
             .contains("Invalid font structure"));
     }
 
+    #[test]
+    fn accepts_generated_fonts_of_every_format() {
+        use fontlift_testfonts::{ribbi_family, write_collection, Outlines, TestFont};
+
+        let dir = tempfile::tempdir().unwrap();
+        let paths = [
+            TestFont::new("Fixture Sans").write_to(dir.path()).unwrap(),
+            TestFont::new("Fixture Serif")
+                .with_outlines(Outlines::Cff)
+                .write_to(dir.path())
+                .unwrap(),
+            TestFont::new("Fixture Var")
+                .with_weight_axis(100, 900)
+                .write_to(dir.path())
+                .unwrap(),
+            write_collection(dir.path(), "Family.ttc", &ribbi_family("Fixture Family")).unwrap(),
+        ];
+
+        for path in &paths {
+            for result in validate_font(path, &ValidatorConfig::default()) {
+                assert!(result.ok, "{}: {:?}", path.display(), result.error);
+            }
+        }
+
+        let collection = validate_font(&paths[3], &ValidatorConfig::default());
+        let faces: Vec<_> = collection.iter().map(|r| r.face_index).collect();
+        assert_eq!(faces, vec![Some(0), Some(1), Some(2), Some(3)]);
+        let info = collection[0].info.as_ref().unwrap();
+        assert_eq!(info.postscript_name, "FixtureFamily-Regular");
+        assert_eq!(info.source.is_collection, Some(true));
+        let bold_italic = collection[3].info.as_ref().unwrap();
+        assert_eq!(bold_italic.source.face_index, Some(3));
+    }
+
+    #[test]
+    fn flags_the_corrupt_face_of_a_collection() {
+        use fontlift_testfonts::{ribbi_family, write_collection};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_collection(dir.path(), "Broken.ttc", &ribbi_family("Broken")).unwrap();
+        // TTC header: tag, version, numFonts, then one u32 offset per face.
+        // Point face 2's table directory past the end of the file.
+        let mut data = std::fs::read(&path).unwrap();
+        data[20..24].copy_from_slice(&u32::MAX.to_be_bytes());
+        std::fs::write(&path, data).unwrap();
+
+        let results = validate_font(&path, &ValidatorConfig::default());
+        let ok: Vec<_> = results.iter().map(|r| (r.face_index, r.ok)).collect();
+        assert_eq!(
+            ok,
+            vec![
+                (Some(0), true),
+                (Some(1), true),
+                (Some(2), false),
+                (Some(3), true)
+            ]
+        );
+        assert!(results[2].error.as_ref().unwrap().contains("face 2"));
+    }
+
+    #[test]
+    fn table_check_errors_fail_the_face_unless_lenient() {
+        use fontlift_testfonts::TestFont;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = TestFont::new("Fixture Sans").write_to(dir.path()).unwrap();
+        // hhea.numberOfHMetrics is the last field of the 36-byte table;
+        // claim more metrics than the font has glyphs.
+        let mut data = std::fs::read(&path).unwrap();
+        let font = FontRef::new(&data).unwrap();
+        let hhea = font
+            .table_directory
+            .table_records()
+            .iter()
+            .find(|r| r.tag() == read_fonts::types::Tag::new(b"hhea"))
+            .unwrap()
+            .offset() as usize;
+        data[hhea + 34..hhea + 36].copy_from_slice(&500u16.to_be_bytes());
+        std::fs::write(&path, data).unwrap();
+
+        let normal = single(validate_font(&path, &ValidatorConfig::default()));
+        assert!(!normal.ok);
+        assert!(
+            normal
+                .error
+                .as_ref()
+                .unwrap()
+                .starts_with("Face 0: metrics: "),
+            "{:?}",
+            normal.error
+        );
+        let json = serde_json::to_value(&normal).unwrap();
+        assert_eq!(json["issues"][0]["category"], "metrics");
+        assert_eq!(json["issues"][0]["severity"], "error");
+
+        let lenient = ValidatorConfig {
+            strictness: ValidationStrictness::Lenient,
+            ..Default::default()
+        };
+        let accepted = single(validate_font(&path, &lenient));
+        assert!(accepted.ok, "{:?}", accepted.error);
+        assert!(accepted
+            .issues
+            .iter()
+            .all(|i| i.severity == IssueSeverity::Warning));
+        assert!(!accepted.issues.is_empty());
+    }
+
     #[test]
     fn sanitizes_long_errors() {
         let long_error = "x".repeat(300);